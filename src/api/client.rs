@@ -15,10 +15,20 @@ use super::types::{
 };
 use crate::models::{CalendarEvent, Document, PanelTemplate, Person, TranscriptUtterance};
 
-const API_V1_URL: &str = "https://api.granola.ai/v1";
-const API_V2_URL: &str = "https://api.granola.ai/v2";
 const DEFAULT_TIMEOUT_SECS: u64 = 30;
 
+/// Documents requested per get-documents page.
+const DOCUMENTS_PAGE_SIZE: usize = 100;
+
+/// The documents a get-documents fetch returned.
+#[derive(Debug)]
+pub struct DocumentList {
+    pub docs: Vec<Document>,
+    /// Whether the last page was confirmed as the last: a short page with
+    /// no cursor. Only then does a document's absence mean it is gone.
+    pub complete: bool,
+}
+
 /// Safely slice a string at UTF-8 character boundaries.
 /// Returns a substring from `start` to `end` byte positions, adjusted to valid char boundaries.
 fn safe_slice(s: &str, start: usize, end: usize) -> &str {
@@ -46,8 +56,9 @@ pub enum ApiError {
     #[error("Resource not found (404). The requested resource may not exist.")]
     NotFound,
 
+    /// `retry_after` is the server's `Retry-After`, when it sent one.
     #[error("Rate limited (429). Please wait before making more requests.")]
-    RateLimited,
+    RateLimited { retry_after: Option<Duration> },
 
     #[error("Server error ({0}): {1}")]
     ServerError(u16, String),
@@ -61,12 +72,16 @@ pub enum ApiError {
 
 pub struct ApiClient {
     token: String,
+    base_url: String,
     client_version: String,
     client: reqwest::blocking::Client,
 }
 
 impl ApiClient {
+    /// Create a client for the API base URL the environment or `config.toml`
+    /// selects (see [`super::endpoint`]).
     pub fn new(token: String) -> Result<Self> {
+        let base_url = super::endpoint::base_url()?;
         let client = reqwest::blocking::Client::builder()
             .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
             .build()
@@ -74,6 +89,7 @@ impl ApiClient {
 
         Ok(Self {
             token,
+            base_url,
             client_version: super::identity::client_version(),
             client,
         })
@@ -85,7 +101,7 @@ impl ApiClient {
         endpoint: &str,
         body: &impl serde::Serialize,
     ) -> Result<T, ApiError> {
-        let url = format!("{}/{}", API_V1_URL, endpoint);
+        let url = format!("{}/v1/{}", self.base_url, endpoint);
         self.post(&url, body)
     }
 
@@ -95,7 +111,7 @@ impl ApiClient {
         endpoint: &str,
         body: &impl serde::Serialize,
     ) -> Result<T, ApiError> {
-        let url = format!("{}/{}", API_V2_URL, endpoint);
+        let url = format!("{}/v2/{}", self.base_url, endpoint);
         self.post(&url, body)
     }
 
@@ -189,8 +205,13 @@ impl ApiClient {
                 Err(ApiError::NotFound)
             }
             429 => {
-                debug!("  rate limited (429)");
-                Err(ApiError::RateLimited)
+                let retry_after = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| parse_retry_after(v, chrono::Utc::now()));
+                debug!("  rate limited (429), retry after {:?}", retry_after);
+                Err(ApiError::RateLimited { retry_after })
             }
             _ => {
                 let body = response.text().unwrap_or_default();
@@ -210,7 +231,7 @@ impl ApiClient {
 
     /// Fetch a transcript for a document
     pub fn fetch_transcript(&self, document_id: &str) -> Result<TranscriptResponse, ApiError> {
-        let url = format!("{}/get-document-transcript", API_V1_URL);
+        let url = format!("{}/v1/get-document-transcript", self.base_url);
         debug!("POST {} (document_id={})", url, document_id);
 
         let request_body = GetTranscriptRequest {
@@ -255,8 +276,13 @@ impl ApiClient {
                 Err(ApiError::NotFound)
             }
             429 => {
-                debug!("  rate limited (429)");
-                Err(ApiError::RateLimited)
+                let retry_after = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| parse_retry_after(v, chrono::Utc::now()));
+                debug!("  rate limited (429), retry after {:?}", retry_after);
+                Err(ApiError::RateLimited { retry_after })
             }
             _ => {
                 let body = response.text().unwrap_or_default();
@@ -274,11 +300,48 @@ impl ApiClient {
     // Document Methods
     // ========================================================================
 
-    /// Fetch all documents from the API
-    pub fn get_documents(&self) -> Result<Vec<Document>, ApiError> {
-        let request = GetDocumentsRequest::default();
-        let response: GetDocumentsResponse = self.post_v2("get-documents", &request)?;
-        Ok(response.docs)
+    /// Fetch every document, or with `updated_after` only those changed
+    /// since then, following `next_cursor` until the last page.
+    pub fn get_documents(&self, updated_after: Option<&str>) -> Result<DocumentList, ApiError> {
+        let mut docs = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let request = GetDocumentsRequest {
+                limit: Some(DOCUMENTS_PAGE_SIZE),
+                cursor: cursor.clone(),
+                updated_after: updated_after.map(String::from),
+                ..Default::default()
+            };
+            let response: GetDocumentsResponse = self.post_v2("get-documents", &request)?;
+            debug!(
+                "get-documents page: {} docs, next_cursor={:?}",
+                response.docs.len(),
+                response.next_cursor
+            );
+            let page_len = response.docs.len();
+            docs.extend(response.docs);
+
+            match response.next_cursor {
+                // An empty page with a cursor would loop forever on a server
+                // that keeps handing one out; treat it as the end.
+                Some(next) if page_len > 0 => {
+                    if cursor.as_deref() == Some(next.as_str()) {
+                        return Err(ApiError::InvalidResponse(format!(
+                            "get-documents returned the same cursor twice ({})",
+                            next
+                        )));
+                    }
+                    cursor = Some(next);
+                }
+                next => {
+                    // A full page without a cursor may be a server that caps
+                    // the page and paginates some other way: more may follow.
+                    let complete = next.is_none() && page_len < DOCUMENTS_PAGE_SIZE;
+                    return Ok(DocumentList { docs, complete });
+                }
+            }
+        }
     }
 
     // ========================================================================
@@ -361,11 +424,19 @@ pub fn fetch_transcript(token: &str, document_id: &str) -> Result<TranscriptResp
     client.fetch_transcript(document_id)
 }
 
-/// Convenience function to fetch panels with a given token
-pub fn fetch_panels(token: &str, document_id: &str) -> Result<Vec<ApiPanel>, ApiError> {
-    let client =
-        ApiClient::new(token.to_string()).map_err(|e| ApiError::NetworkError(e.to_string()))?;
-    client.fetch_panels(document_id)
+/// Parse a `Retry-After` header: either delay-seconds or an HTTP-date,
+/// which is converted to the wait from `now` (zero once it has passed).
+fn parse_retry_after(value: &str, now: chrono::DateTime<chrono::Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (at.with_timezone(&chrono::Utc) - now)
+            .to_std()
+            .unwrap_or_default(),
+    )
 }
 
 /// Sleep for the specified duration plus random jitter (0-500ms)
@@ -387,10 +458,31 @@ mod tests {
         let err = ApiError::NotFound;
         assert!(err.to_string().contains("404"));
 
-        let err = ApiError::RateLimited;
+        let err = ApiError::RateLimited { retry_after: None };
         assert!(err.to_string().contains("429"));
     }
 
+    #[test]
+    fn retry_after_accepts_seconds_and_http_dates() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-03-02T09:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Mon, 02 Mar 2026 09:00:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Mon, 02 Mar 2026 08:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
     #[test]
     fn test_request_serialization() {
         let request = GetTranscriptRequest {
@@ -410,9 +502,23 @@ mod tests {
         // Request with ID
         let request = GetDocumentsRequest {
             id: Some("doc-123".to_string()),
+            ..Default::default()
         };
         let json = serde_json::to_string(&request).unwrap();
         assert_eq!(json, r#"{"id":"doc-123"}"#);
+
+        // Incremental page request
+        let request = GetDocumentsRequest {
+            limit: Some(100),
+            cursor: Some("c2".to_string()),
+            updated_after: Some("2026-01-01T00:00:00Z".to_string()),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            json,
+            r#"{"limit":100,"cursor":"c2","updated_after":"2026-01-01T00:00:00Z"}"#
+        );
     }
 
     #[test]
@@ -56,7 +56,7 @@ impl CredentialStore {
     /// A caller that needs to see a write another process made since must ask
     /// for it with [`Self::load`], which always reads.
     pub fn open() -> Result<(Self, Option<GranolaCredentials>)> {
-        let Some((entry, stored)) = reachable_keychain() else {
+        let Some((entry, stored)) = reachable_keychain(KEYCHAIN_ACCOUNT) else {
             let path = credentials_path()?;
             let stored = read_file(&path)?;
             return Ok((Self::File(path), stored));
@@ -106,7 +106,7 @@ impl CredentialStore {
             Self::Keychain(entry) => {
                 let json = serde_json::to_string(credentials)
                     .context("Failed to serialize credentials")?;
-                store_secret(entry, &json)
+                store_secret(entry, KEYCHAIN_ACCOUNT, &json)
             }
         }
     }
@@ -160,14 +160,75 @@ impl CredentialStore {
     }
 }
 
+/// Where grans keeps a secret other than the Granola session, such as the
+/// key that encrypts pushed databases.
+///
+/// The same keychain service holds it under its own account name, with the
+/// same `0600` file fallback, here holding the secret verbatim.
+pub enum SecretStore {
+    Keychain(Box<keyring::Entry>, String),
+    File(PathBuf),
+}
+
+impl SecretStore {
+    /// Open the best available store for `account`, falling back to
+    /// `data_dir()/{file_name}`.
+    ///
+    /// A secret a keychain-less run left in the file stays there: the
+    /// keychain is used only when it holds the secret or the file does not.
+    pub fn open(account: &str, file_name: &str) -> Result<Self> {
+        let path = data_dir()?.join(file_name);
+        Ok(match reachable_keychain(account) {
+            Some((_, None)) if path.exists() => Self::File(path),
+            Some((entry, _)) => Self::Keychain(Box::new(entry), account.to_string()),
+            None => Self::File(path),
+        })
+    }
+
+    pub fn load(&self) -> Result<Option<String>> {
+        match self {
+            Self::Keychain(entry, _) => read_keychain(entry),
+            Self::File(path) => match fs::read_to_string(path) {
+                Ok(secret) => Ok(Some(secret.trim().to_string())),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+            },
+        }
+    }
+
+    pub fn save(&self, secret: &str) -> Result<()> {
+        match self {
+            Self::Keychain(entry, account) => store_secret(entry, account, secret),
+            Self::File(path) => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)
+                        .with_context(|| format!("Failed to create {}", parent.display()))?;
+                }
+                let mut file = create_private_file(path)
+                    .with_context(|| format!("Failed to create {}", path.display()))?;
+                file.write_all(secret.as_bytes())
+                    .with_context(|| format!("Failed to write {}", path.display()))
+            }
+        }
+    }
+
+    /// Name this store in messages.
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Keychain(..) => format!("{} ({})", keychain_name(), KEYCHAIN_SERVICE),
+            Self::File(path) => path.display().to_string(),
+        }
+    }
+}
+
 /// The keychain, if one can actually be read from, and what that read found.
 ///
 /// Constructing an entry is not proof: a Linux box with no Secret Service, or
 /// a locked keychain, fails only when read. So this reads, and treats "no such
 /// entry" as a working keychain that is simply empty. What it read is handed
 /// back rather than discarded; see [`CredentialStore::open`].
-fn reachable_keychain() -> Option<(keyring::Entry, Option<String>)> {
-    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)
+fn reachable_keychain(account: &str) -> Option<(keyring::Entry, Option<String>)> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, account)
         .inspect_err(|e| debug!("No keychain available: {}", e))
         .ok()?;
 
@@ -228,13 +289,13 @@ fn open_up_pinned_item(_json: &str) {}
 /// [`super::keychain_acl`] exists to avoid. Reads and deletes still go through
 /// `keyring` on every platform.
 #[cfg(target_os = "macos")]
-fn store_secret(_entry: &keyring::Entry, json: &str) -> Result<()> {
-    keychain_acl::store_with_open_access(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT, json.as_bytes())
+fn store_secret(_entry: &keyring::Entry, account: &str, json: &str) -> Result<()> {
+    keychain_acl::store_with_open_access(KEYCHAIN_SERVICE, account, json.as_bytes())
         .context("Failed to store credentials in the keychain")
 }
 
 #[cfg(not(target_os = "macos"))]
-fn store_secret(entry: &keyring::Entry, json: &str) -> Result<()> {
+fn store_secret(entry: &keyring::Entry, _account: &str, json: &str) -> Result<()> {
     entry
         .set_password(json)
         .context("Failed to store credentials in the keychain")
@@ -491,4 +552,15 @@ mod tests {
 
         assert_eq!(file.metadata().unwrap().permissions().mode() & 0o777, 0o600);
     }
+
+    #[test]
+    fn test_secret_file_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let store = SecretStore::File(dir.path().join("nested").join("secret"));
+
+        assert_eq!(store.load().unwrap(), None);
+        store.save("first").unwrap();
+        store.save("second").unwrap();
+        assert_eq!(store.load().unwrap().as_deref(), Some("second"));
+    }
 }
@@ -0,0 +1,155 @@
+//! Where grans sends Granola API requests.
+//!
+//! Every request, data and auth alike, is built from one base URL so that the
+//! whole client can be pointed at a stand-in server: the integration tests run
+//! `grans sync --all` against a fixture-driven mock, and the same switch lets
+//! anyone reproduce a sync offline.
+
+use std::env;
+
+use anyhow::{Result, bail};
+use url::Url;
+
+use crate::config::Settings;
+
+/// Environment variable that overrides the API base URL.
+pub const BASE_URL_ENV_VAR: &str = "GRANS_API_BASE_URL";
+
+/// Base URL used when neither the environment nor `config.toml` sets one.
+pub const DEFAULT_BASE_URL: &str = "https://api.granola.ai";
+
+/// Granola API base URL to send requests to, without a trailing slash.
+///
+/// In order: `GRANS_API_BASE_URL`, then `api_base_url` in `config.toml`, then
+/// the real service. The settings file is only read when the environment does
+/// not decide, so an override still works next to a broken file.
+pub fn base_url() -> Result<String> {
+    let override_value = env::var(BASE_URL_ENV_VAR).ok();
+    if is_set(override_value.as_deref()) {
+        return resolve_base_url(override_value, None);
+    }
+
+    resolve_base_url(None, Settings::load()?.api_base_url)
+}
+
+/// Full URL of a v1 endpoint, e.g. `v1_url("get-people")`.
+pub fn v1_url(endpoint: &str) -> Result<String> {
+    Ok(format!("{}/v1/{}", base_url()?, endpoint))
+}
+
+/// Choose between the override, the configured value and the default.
+///
+/// Split from [`base_url`] so the precedence is testable without mutating the
+/// environment, which is `unsafe` in edition 2024.
+fn resolve_base_url(override_value: Option<String>, configured: Option<String>) -> Result<String> {
+    let chosen = [override_value, configured]
+        .into_iter()
+        .flatten()
+        .find(|value| is_set(Some(value)));
+
+    match chosen {
+        Some(value) => validate(value.trim()),
+        None => Ok(DEFAULT_BASE_URL.to_string()),
+    }
+}
+
+/// An exported-but-blank value counts as absent.
+fn is_set(value: Option<&str>) -> bool {
+    value.is_some_and(|v| !v.trim().is_empty())
+}
+
+/// Accept an http(s) origin, optionally with a path prefix, and normalize
+/// away the trailing slash so endpoint paths join cleanly.
+fn validate(value: &str) -> Result<String> {
+    let url = match Url::parse(value) {
+        Ok(url) => url,
+        Err(e) => bail!("Invalid Granola API base URL '{}': {}", value, e),
+    };
+
+    if !matches!(url.scheme(), "http" | "https") {
+        bail!(
+            "Invalid Granola API base URL '{}': expected an http:// or https:// URL",
+            value
+        );
+    }
+
+    if url.query().is_some() || url.fragment().is_some() {
+        bail!(
+            "Invalid Granola API base URL '{}': must not carry a query or fragment",
+            value
+        );
+    }
+
+    Ok(value.trim_end_matches('/').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_when_nothing_is_set() {
+        assert_eq!(resolve_base_url(None, None).unwrap(), DEFAULT_BASE_URL);
+    }
+
+    #[test]
+    fn configured_value_beats_default() {
+        let url = resolve_base_url(None, Some("http://127.0.0.1:8080".to_string())).unwrap();
+
+        assert_eq!(url, "http://127.0.0.1:8080");
+    }
+
+    #[test]
+    fn override_beats_configured_value() {
+        let url = resolve_base_url(
+            Some("http://localhost:9000".to_string()),
+            Some("http://127.0.0.1:8080".to_string()),
+        )
+        .unwrap();
+
+        assert_eq!(url, "http://localhost:9000");
+    }
+
+    #[test]
+    fn blank_override_falls_through() {
+        let url = resolve_base_url(
+            Some("  ".to_string()),
+            Some("http://127.0.0.1:8080".to_string()),
+        )
+        .unwrap();
+
+        assert_eq!(url, "http://127.0.0.1:8080");
+    }
+
+    #[test]
+    fn trailing_slash_is_dropped() {
+        let url = resolve_base_url(Some("http://127.0.0.1:8080/".to_string()), None).unwrap();
+
+        assert_eq!(url, "http://127.0.0.1:8080");
+    }
+
+    #[test]
+    fn path_prefix_is_kept() {
+        let url =
+            resolve_base_url(Some("https://proxy.example/granola/".to_string()), None).unwrap();
+
+        assert_eq!(url, "https://proxy.example/granola");
+    }
+
+    #[test]
+    fn rejects_non_http_scheme() {
+        let err = resolve_base_url(Some("ftp://127.0.0.1".to_string()), None).unwrap_err();
+
+        assert!(err.to_string().contains("http://"));
+    }
+
+    #[test]
+    fn rejects_unparseable_value() {
+        assert!(resolve_base_url(Some("127.0.0.1:8080".to_string()), None).is_err());
+    }
+
+    #[test]
+    fn rejects_query_string() {
+        assert!(resolve_base_url(Some("http://127.0.0.1:8080/?x=1".to_string()), None).is_err());
+    }
+}
@@ -18,11 +18,12 @@ use serde::Deserialize;
 use url::Url;
 
 use super::credentials::GranolaCredentials;
-use super::identity;
+use super::{endpoint, identity};
 
-const AUTH_URL: &str = "https://api.granola.ai/v1/auth";
-const AUTH_COMPLETE_URL: &str = "https://api.granola.ai/v1/workos-auth-complete";
-const REFRESH_URL: &str = "https://api.granola.ai/v1/refresh-access-token";
+/// v1 endpoints, joined onto the API base URL (see [`endpoint`]).
+const AUTH_ENDPOINT: &str = "auth";
+const AUTH_COMPLETE_ENDPOINT: &str = "workos-auth-complete";
+const REFRESH_ENDPOINT: &str = "refresh-access-token";
 
 /// Page the browser lands on when the login succeeds. Shown in error messages
 /// so the user knows which URL to copy.
@@ -83,10 +84,10 @@ impl TokenSet {
 
 /// Build the URL that starts the login, and the click id that must accompany
 /// the later code exchange.
-pub fn build_auth_url(challenge: &str, provider: Provider) -> (String, String) {
+pub fn build_auth_url(challenge: &str, provider: Provider) -> Result<(String, String)> {
     let sign_in_click_id = uuid::Uuid::new_v4().to_string();
 
-    let mut url = Url::parse(AUTH_URL).expect("auth URL is a valid constant");
+    let mut url = Url::parse(&endpoint::v1_url(AUTH_ENDPOINT)?)?;
     url.query_pairs_mut()
         .append_pair("dev", "false")
         .append_pair("code_challenge", challenge)
@@ -96,7 +97,7 @@ pub fn build_auth_url(challenge: &str, provider: Provider) -> (String, String) {
         .append_pair("intent", "download")
         .append_pair("provider", provider.as_str());
 
-    (url.into(), sign_in_click_id)
+    Ok((url.into(), sign_in_click_id))
 }
 
 /// What the login callback hands back.
@@ -215,7 +216,7 @@ pub fn exchange_code(callback: &Callback, verifier: &str, started_with: &str) ->
         "signInClickId": callback.sign_in_click_id.as_deref().unwrap_or(started_with),
     });
 
-    let response = post_json(AUTH_COMPLETE_URL, &body, None)
+    let response = post_json(&endpoint::v1_url(AUTH_COMPLETE_ENDPOINT)?, &body, None)
         .context("Failed to exchange the authorization code")?;
 
     extract_token_set(&response)
@@ -233,7 +234,7 @@ pub fn exchange_code(callback: &Callback, verifier: &str, started_with: &str) ->
 pub fn refresh_tokens(access_token: Option<&str>, refresh_token: &str) -> Result<TokenSet> {
     let body = serde_json::json!({ "refresh_token": refresh_token });
 
-    let response = post_json(REFRESH_URL, &body, access_token)
+    let response = post_json(&endpoint::v1_url(REFRESH_ENDPOINT)?, &body, access_token)
         .context("Failed to refresh the Granola access token")?;
 
     extract_token_set(&response)
@@ -362,9 +363,9 @@ mod tests {
 
     #[test]
     fn test_auth_url_carries_required_parameters() {
-        let (url, click_id) = build_auth_url("test-challenge", Provider::Google);
+        let (url, click_id) = build_auth_url("test-challenge", Provider::Google).unwrap();
 
-        assert!(url.starts_with(AUTH_URL));
+        assert!(url.starts_with(&endpoint::v1_url(AUTH_ENDPOINT).unwrap()));
         assert!(url.contains("code_challenge=test-challenge"));
         assert!(url.contains("provider=google"));
         assert!(url.contains("dev=false"));
@@ -377,15 +378,15 @@ mod tests {
 
     #[test]
     fn test_auth_url_click_id_is_per_call() {
-        let (_, first) = build_auth_url("c", Provider::Google);
-        let (_, second) = build_auth_url("c", Provider::Google);
+        let (_, first) = build_auth_url("c", Provider::Google).unwrap();
+        let (_, second) = build_auth_url("c", Provider::Google).unwrap();
 
         assert_ne!(first, second);
     }
 
     #[test]
     fn test_auth_url_provider_selects_microsoft() {
-        let (url, _) = build_auth_url("c", Provider::Microsoft);
+        let (url, _) = build_auth_url("c", Provider::Microsoft).unwrap();
 
         assert!(url.contains("provider=microsoft"));
     }
@@ -2,6 +2,7 @@ mod auth;
 pub mod client;
 pub mod credential_store;
 pub mod credentials;
+pub mod endpoint;
 pub mod granola_auth;
 pub mod identity;
 pub mod jwt;
@@ -18,5 +19,5 @@ mod token_store;
 pub mod types;
 
 pub use auth::{TOKEN_ENV_VAR, resolve_token, token_override};
-pub use client::{ApiClient, ApiError, fetch_panels, fetch_transcript};
+pub use client::{ApiClient, ApiError, fetch_transcript};
 pub use types::ApiPanel;
@@ -31,12 +31,24 @@ pub struct GetDocumentsRequest {
     /// Optional: fetch a specific document by ID
     #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
+    /// Page size; the server picks one when absent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Continue from the `next_cursor` of the previous page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// Only documents updated (or deleted) after this RFC 3339 timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_after: Option<String>,
 }
 
 /// Response from get-documents-v2 API
 #[derive(Debug, Deserialize)]
 pub struct GetDocumentsResponse {
     pub docs: Vec<Document>,
+    /// Cursor for the next page; absent on the last page
+    #[serde(default)]
+    pub next_cursor: Option<String>,
 }
 
 // ============================================================================
@@ -87,6 +87,26 @@ pub enum Commands {
         #[arg(long)]
         meeting: Option<String>,
 
+        /// Only meetings attended by this person (name or email fragment)
+        #[arg(long)]
+        with: Option<String>,
+
+        /// Only meetings with an attendee from this company (substring of the synced company name)
+        #[arg(long)]
+        company: Option<String>,
+
+        /// Only meetings synced from the Granola account with this email
+        #[arg(long)]
+        account: Option<String>,
+
+        /// Only meetings in this recurring series (title, compared trimmed and case-insensitively)
+        #[arg(long)]
+        series: Option<String>,
+
+        /// Only meetings with at least this many distinct attendees
+        #[arg(long)]
+        min_attendees: Option<usize>,
+
         /// Filter from date [e.g., 2024-01-15, 2024-01-15T10:30:00Z, or duration: 3d, 2w, 1m]
         #[arg(long)]
         from: Option<String>,
@@ -137,6 +157,26 @@ pub enum Commands {
         #[arg(long)]
         meeting: Option<String>,
 
+        /// Only meetings attended by this person (name or email fragment)
+        #[arg(long)]
+        with: Option<String>,
+
+        /// Only meetings with an attendee from this company (substring of the synced company name)
+        #[arg(long)]
+        company: Option<String>,
+
+        /// Only meetings synced from the Granola account with this email
+        #[arg(long)]
+        account: Option<String>,
+
+        /// Only meetings in this recurring series (title, compared trimmed and case-insensitively)
+        #[arg(long)]
+        series: Option<String>,
+
+        /// Only meetings with at least this many distinct attendees
+        #[arg(long)]
+        min_attendees: Option<usize>,
+
         /// Filter from date [e.g., 2024-01-15, 2024-01-15T10:30:00Z, or duration: 3d, 2w, 1m]
         #[arg(long)]
         from: Option<String>,
@@ -162,6 +202,73 @@ pub enum Commands {
         include_deleted: bool,
     },
 
+    /// Find meetings on the same topic as a given meeting
+    ///
+    /// Averages the meeting's embedded chunks into one vector and ranks the
+    /// other meetings by their closest chunk, so it needs `grans embed` but
+    /// never loads a model. --exclude-series drops the meeting's own
+    /// recurring series, leaving related meetings under other titles.
+    Similar {
+        /// Meeting ID or title substring
+        meeting: String,
+
+        /// Which embedded content to compare (comma-separated; titles are ignored)
+        #[arg(long, value_delimiter = ',', default_value = crate::query::filter::DEFAULT_SEARCH_TARGETS)]
+        r#in: Vec<SearchTarget>,
+
+        /// Leave out meetings with the same title as this one (its recurring series)
+        #[arg(long)]
+        exclude_series: bool,
+
+        /// Filter from date [e.g., 2024-01-15, 2024-01-15T10:30:00Z, or duration: 3d, 2w, 1m]
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Filter to date [e.g., 2024-01-15, 2024-01-15T10:30:00Z, or duration: 3d, 2w, 1m]
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Relative date filter, overrides --from/--to [today, yesterday, this-week, last-week, this-month, last-month]
+        #[arg(long)]
+        date: Option<String>,
+
+        /// Maximum number of meetings to show (0 = no limit)
+        #[arg(long, default_value = "10")]
+        limit: usize,
+
+        /// Include soft-deleted meetings in results
+        #[arg(long)]
+        include_deleted: bool,
+    },
+
+    /// Cluster meeting content into topics over a period
+    ///
+    /// Groups the embedded chunks of the meetings in range by meaning,
+    /// labels each topic with its most distinctive words, and shows how many
+    /// meetings touched it each week plus the meetings most about it. Reads
+    /// stored embeddings only; run `grans embed` after syncing.
+    Topics {
+        /// Number of topics to find (fewer when there is little content)
+        #[arg(long, default_value = "8")]
+        topics: usize,
+
+        /// Which embedded content to cluster (comma-separated; titles are ignored)
+        #[arg(long, value_delimiter = ',', default_value = crate::query::filter::DEFAULT_SEARCH_TARGETS)]
+        r#in: Vec<SearchTarget>,
+
+        /// Filter from date [e.g., 2024-01-15, 2024-01-15T10:30:00Z, or duration: 3d, 2w, 1m]
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Filter to date [e.g., 2024-01-15, 2024-01-15T10:30:00Z, or duration: 3d, 2w, 1m]
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Relative date filter, overrides --from/--to [today, yesterday, this-week, last-week, this-month, last-month]
+        #[arg(long)]
+        date: Option<String>,
+    },
+
     /// List meetings
     #[command(visible_alias = "ls")]
     List {
@@ -255,18 +362,106 @@ pub enum Commands {
         dry_run: bool,
     },
 
-    /// Dropbox sync (init, push, pull, status, logout)
+    /// Dropbox sync (init, push, pull, status, merge, logout)
     Dropbox {
         #[command(subcommand)]
         action: DropboxAction,
     },
 
+    /// Replicate the database to a folder, WebDAV server, S3 bucket or Dropbox
+    Remote {
+        #[command(subcommand)]
+        action: RemoteAction,
+    },
+
     /// Manage grans's Granola sign-in (login, status, logout)
     Auth {
         #[command(subcommand)]
         action: AuthAction,
     },
 
+    /// Export meetings to other tools (markdown)
+    Export {
+        #[command(subcommand)]
+        action: ExportAction,
+    },
+
+    /// Saved searches and new-match alerts (add, list, run, rm, check)
+    ///
+    /// A saved search is a named `grep` or `search` command with all its
+    /// flags. `check` reports only meetings that started matching since the
+    /// last check, which makes `grans sync && grans saved check --json` a
+    /// post-sync hook for notification scripts.
+    Saved {
+        #[command(subcommand)]
+        action: SavedAction,
+    },
+
+    /// Conversation statistics from transcripts (talk-time)
+    Stats {
+        #[command(subcommand)]
+        action: StatsAction,
+    },
+
+    /// Action items and decisions from AI panels (list, done, undone)
+    ///
+    /// Items are read out of panels' "Action Items", "Next Steps" and
+    /// "Decisions" sections whenever panels sync. Marking an item done is
+    /// local to this database and survives later syncs.
+    Actions {
+        #[command(subcommand)]
+        action: ActionsAction,
+    },
+
+    /// Link a person's emails, names and speaker names (merge, unmerge, suggest)
+    ///
+    /// One colleague can show up under several emails, name spellings and
+    /// detected speaker names. Merged identities count as one person in
+    /// `with`, `list --person`, `--with`, `--speaker`, `stats talk-time` and
+    /// `browse people profile`.
+    People {
+        #[command(subcommand)]
+        action: PeopleAliasAction,
+    },
+
+    /// Calendar events against recorded meetings (gaps)
+    ///
+    /// Events link to the meetings that recorded them after every documents
+    /// or calendar sync: by the event a meeting was started from, or else by
+    /// the event running when it was created.
+    Calendar {
+        #[command(subcommand)]
+        action: CalendarAction,
+    },
+
+    /// Serve search, grep, list, show and people over local HTTP
+    ///
+    /// Loads the embedding index, embedder and reranker once and keeps them
+    /// warm, so each query answers without the per-invocation model load.
+    /// Endpoints take the CLI's flags as query parameters and return the
+    /// same JSON as `--json`.
+    Serve {
+        /// Address to listen on (no authentication; keep it on loopback)
+        #[arg(long, default_value = crate::serve::DEFAULT_LISTEN)]
+        listen: String,
+
+        /// Don't load the reranker; every search keeps fusion order
+        #[arg(long)]
+        fast: bool,
+    },
+
+    /// Serve meeting queries to AI assistants over MCP (stdio)
+    ///
+    /// Speaks the Model Context Protocol on stdin/stdout, exposing search,
+    /// grep, show, meetings-with-person and transcript tools plus a resource
+    /// per meeting and transcript. Models load once at startup, as with
+    /// `grans serve`.
+    Mcp {
+        /// Don't load the reranker; every search keeps fusion order
+        #[arg(long)]
+        fast: bool,
+    },
+
     // === Grouped Commands ===
     /// Browse entities (people, calendars, templates, recipes)
     Browse {
@@ -327,6 +522,28 @@ pub enum Commands {
         /// Experiment knob: prepend meeting title/date/attendees to the embed input
         #[arg(long, hide = true, num_args = 0..=1, default_missing_value = "true")]
         contextual_headers: Option<bool>,
+
+        /// Store only the leading N dimensions of each vector (Matryoshka
+        /// truncation: 64, 128, 256, 512 or 768); existing vectors are
+        /// re-encoded in place
+        #[arg(long, value_name = "N", value_parser = parse_storage_dim)]
+        dim: Option<usize>,
+
+        /// Store vectors as f32, f16 or int8; existing vectors are
+        /// re-encoded in place
+        #[arg(long, value_parser = ["f32", "f16", "int8"])]
+        precision: Option<String>,
+
+        /// Embedding model to use; switching models re-embeds everything
+        #[arg(long, value_parser = model_parser())]
+        model: Option<String>,
+    },
+
+    /// Side-load embedding and reranker models for offline installs
+    /// (import, export)
+    Models {
+        #[command(subcommand)]
+        action: ModelsAction,
     },
 
     /// Benchmarking commands
@@ -346,6 +563,9 @@ pub enum QualityMode {
     Fts,
     /// Semantic search over embeddings
     Semantic,
+    /// Semantic search through an HNSW graph over the same embeddings,
+    /// with its recall against exact semantic search
+    SemanticAnn,
     /// RRF fusion of FTS and semantic rankings (what `search --fast` shows)
     Hybrid,
     /// Fusion + jina-reranker-v1-turbo-en cross-encoder blended with the
@@ -360,6 +580,7 @@ impl QualityMode {
         match self {
             QualityMode::Fts => "fts",
             QualityMode::Semantic => "semantic",
+            QualityMode::SemanticAnn => "semantic-ann",
             QualityMode::Hybrid => "hybrid",
             QualityMode::RerankJina => "rerank-jina",
             QualityMode::RerankBge => "rerank-bge",
@@ -439,9 +660,65 @@ pub enum BenchmarkAction {
         /// overriding the adopted default (0 disables the boost)
         #[arg(long, hide = true, value_name = "W")]
         title_boost_weight: Option<f32>,
+
+        /// Score vectors truncated to N dimensions, re-encoded in memory
+        /// (the database is untouched)
+        #[arg(long, value_name = "N", value_parser = parse_storage_dim)]
+        dim: Option<usize>,
+
+        /// Score vectors re-encoded in memory as f32, f16 or int8 (the
+        /// database is untouched)
+        #[arg(long, value_parser = ["f32", "f16", "int8"])]
+        precision: Option<String>,
+
+        /// Score this embedding model instead of the database's, embedding
+        /// into a cached copy of the database (the original is untouched)
+        #[arg(long, value_parser = model_parser())]
+        model: Option<String>,
     },
 }
 
+fn parse_storage_dim(s: &str) -> Result<usize, String> {
+    use crate::embed::quant::MATRYOSHKA_DIMS;
+    match s.parse::<usize>() {
+        Ok(n) if MATRYOSHKA_DIMS.contains(&n) => Ok(n),
+        _ => Err(format!(
+            "expected one of {}",
+            MATRYOSHKA_DIMS.map(|d| d.to_string()).join(", ")
+        )),
+    }
+}
+
+fn model_parser() -> clap::builder::PossibleValuesParser {
+    clap::builder::PossibleValuesParser::new(crate::embed::model::model_names())
+}
+
+// === Models Subcommands ===
+
+#[derive(Subcommand, Debug)]
+pub enum ModelsAction {
+    /// Verify exported models by checksum and copy them into the model
+    /// directory ($GRANS_MODEL_DIR, or models/ in the data directory)
+    Import {
+        /// Directory written by `grans models export` (or one model in it)
+        dir: std::path::PathBuf,
+    },
+    /// Download models and write them, with checksums, to a directory
+    /// that `grans models import` accepts
+    Export {
+        /// Destination directory
+        dir: std::path::PathBuf,
+        /// Model to export (repeatable); defaults to the default embedding
+        /// model and reranker
+        #[arg(long = "model", value_name = "NAME", value_parser = export_model_parser())]
+        models: Vec<String>,
+    },
+}
+
+fn export_model_parser() -> clap::builder::PossibleValuesParser {
+    clap::builder::PossibleValuesParser::new(crate::embed::offline::model_names())
+}
+
 // === Embed Subcommands ===
 
 #[derive(Subcommand, Debug)]
@@ -462,15 +739,40 @@ pub enum EmbedAction {
 /// and panel sync legs and the `sync --all` pipeline.
 pub const DEFAULT_SYNC_DELAY_MS: u64 = 1500;
 
+/// Default number of concurrent per-document API requests for the transcript
+/// and panel sync legs. Rate limiting lowers it on the fly.
+pub const DEFAULT_SYNC_CONCURRENCY: usize = 4;
+
+/// Upper bound for `--concurrency`, to stay a polite API client.
+pub const MAX_SYNC_CONCURRENCY: usize = 16;
+
+fn parse_concurrency(s: &str) -> Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(n) if (1..=MAX_SYNC_CONCURRENCY).contains(&n) => Ok(n),
+        _ => Err(format!(
+            "expected a number from 1 to {}",
+            MAX_SYNC_CONCURRENCY
+        )),
+    }
+}
+
 #[derive(Subcommand, Debug, Clone)]
 pub enum SyncAction {
     /// Sync documents (meetings) from Granola API
-    Documents,
+    ///
+    /// Fetches only documents changed since the last sync. Once a week, or
+    /// with --full, fetches every document and marks meetings deleted on
+    /// the server as deleted locally.
+    Documents {
+        /// Fetch every document and reconcile server-side deletions now
+        #[arg(long)]
+        full: bool,
+    },
 
     /// Sync transcripts for documents
     Transcripts {
         /// Fetch the transcript for a single document (full ID or unique prefix), replacing any existing transcript
-        #[arg(value_name = "DOCUMENT_ID", conflicts_with_all = ["limit", "since", "delay_ms", "retry"])]
+        #[arg(value_name = "DOCUMENT_ID", conflicts_with_all = ["limit", "since", "delay_ms", "concurrency", "retry"])]
         document_id: Option<String>,
 
         /// Maximum number of documents to fetch transcripts for
@@ -481,10 +783,14 @@ pub enum SyncAction {
         #[arg(long)]
         since: Option<String>,
 
-        /// Delay between API requests in milliseconds
+        /// Delay between each worker's API requests in milliseconds
         #[arg(long, default_value_t = DEFAULT_SYNC_DELAY_MS)]
         delay_ms: u64,
 
+        /// Maximum concurrent API requests (lowered automatically when rate limited)
+        #[arg(long, default_value_t = DEFAULT_SYNC_CONCURRENCY, value_parser = parse_concurrency)]
+        concurrency: usize,
+
         /// Retry documents that previously failed or had no transcript
         #[arg(long)]
         retry: bool,
@@ -516,10 +822,14 @@ pub enum SyncAction {
         #[arg(long)]
         since: Option<String>,
 
-        /// Delay between API requests in milliseconds
+        /// Delay between each worker's API requests in milliseconds
         #[arg(long, default_value_t = DEFAULT_SYNC_DELAY_MS)]
         delay_ms: u64,
 
+        /// Maximum concurrent API requests (lowered automatically when rate limited)
+        #[arg(long, default_value_t = DEFAULT_SYNC_CONCURRENCY, value_parser = parse_concurrency)]
+        concurrency: usize,
+
         /// Retry documents that previously failed or had no panels
         #[arg(long)]
         retry: bool,
@@ -565,6 +875,69 @@ pub enum PeopleAction {
         /// Person ID, name, or email fragment
         query: String,
     },
+    /// Show a person's profile: meetings, last contact, co-attendees,
+    /// recurring series and recent utterances
+    Profile {
+        /// Name or email fragment
+        query: String,
+
+        /// Number of recent utterances to show
+        #[arg(long, default_value = "5")]
+        utterances: usize,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PeopleAliasAction {
+    /// Merge two identities (emails or names) into one person
+    ///
+    /// Either may already be merged with others; merging two merged people
+    /// joins them.
+    Merge {
+        /// An email, or a name or unique part of one
+        first: String,
+        /// An email, or a name or unique part of one
+        second: String,
+    },
+    /// Split an identity back out of the person it was merged into
+    Unmerge {
+        /// The email or name to split out
+        identity: String,
+    },
+    /// Suggest identities that look like the same person
+    Suggest,
+    /// List merged people
+    #[command(visible_alias = "ls")]
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CalendarAction {
+    /// List past calendar events with no recorded meeting
+    ///
+    /// Declined events and events with nobody else invited are left out
+    /// unless --all.
+    Gaps {
+        /// Filter by calendar ID
+        #[arg(long)]
+        calendar: Option<String>,
+
+        /// Filter from date [e.g., 2024-01-15, 2024-01-15T10:30:00Z, or duration: 3d, 2w, 1m]
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Filter to date [e.g., 2024-01-15, 2024-01-15T10:30:00Z, or duration: 3d, 2w, 1m]
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Relative date filter, overrides --from/--to [today, yesterday, this-week, last-week, this-month, last-month]
+        #[arg(long)]
+        date: Option<String>,
+
+        /// Include declined events and events with no other attendees
+        #[arg(long)]
+        all: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -621,6 +994,163 @@ pub enum RecipesAction {
     },
 }
 
+// === Export Subcommands ===
+
+#[derive(Subcommand, Debug)]
+pub enum ExportAction {
+    /// Write one Markdown file per meeting into a notes vault (e.g. Obsidian)
+    ///
+    /// Each file has YAML front matter (id, title, date, attendees, calendar
+    /// event, source account), then the notes, AI panels and transcript.
+    /// Re-running only rewrites meetings whose updated_at changed since the
+    /// last export into the same directory.
+    Markdown {
+        /// Directory to write into (created if missing)
+        dir: std::path::PathBuf,
+
+        /// Filter from date [e.g., 2024-01-15, 2024-01-15T10:30:00Z, or duration: 3d, 2w, 1m]
+        #[arg(long)]
+        from: Option<String>,
+
+        /// Filter to date [e.g., 2024-01-15, 2024-01-15T10:30:00Z, or duration: 3d, 2w, 1m]
+        #[arg(long)]
+        to: Option<String>,
+
+        /// Relative date filter, overrides --from/--to [today, yesterday, this-week, last-week, this-month, last-month]
+        #[arg(long)]
+        date: Option<String>,
+
+        /// Include soft-deleted meetings
+        #[arg(long)]
+        include_deleted: bool,
+
+        /// Rewrite every file, even if the meeting is unchanged
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+// === Saved Subcommands ===
+
+#[derive(Subcommand, Debug)]
+pub enum SavedAction {
+    /// Save a grep or search command under a name
+    ///
+    /// Everything after the name is the command as you would type it, flags
+    /// included: `grans saved add incidents grep "security incident" --in
+    /// transcripts`. Relative dates stay relative. The meetings matching now
+    /// are the baseline: `check` reports only meetings that match later.
+    Add {
+        /// Name to save the search under
+        name: String,
+
+        /// A `grep` or `search` command line, without the leading `grans`
+        #[arg(
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            value_name = "COMMAND"
+        )]
+        command: Vec<String>,
+    },
+
+    /// List saved searches
+    List,
+
+    /// Run a saved search, printing results as its grep or search would
+    Run {
+        /// Saved search name
+        name: String,
+    },
+
+    /// Delete a saved search
+    Rm {
+        /// Saved search name
+        name: String,
+    },
+
+    /// Report meetings newly matching saved searches since the last check
+    ///
+    /// A saved search is only re-run when meetings, transcripts or panels
+    /// have synced since its last check. Grep searches count every match;
+    /// ranked searches report newcomers to their top results.
+    Check {
+        /// Saved searches to check (default: all)
+        names: Vec<String>,
+    },
+}
+
+// === Stats Subcommands ===
+
+#[derive(Subcommand, Debug)]
+pub enum StatsAction {
+    /// Talk share, longest monologue, interruptions and questions per speaker
+    ///
+    /// Utterances are merged into speaker turns first: a speaker's
+    /// utterances separated by short pauses, with nobody else taking the
+    /// floor in between, form one turn. Give one meeting, or a date range
+    /// to total every meeting in it.
+    TalkTime {
+        /// Meeting ID or title substring (omit to use a date range)
+        meeting: Option<String>,
+
+        /// Filter from date [e.g., 2024-01-15, 2024-01-15T10:30:00Z, or duration: 3d, 2w, 1m]
+        #[arg(long, conflicts_with = "meeting")]
+        from: Option<String>,
+
+        /// Filter to date [e.g., 2024-01-15, 2024-01-15T10:30:00Z, or duration: 3d, 2w, 1m]
+        #[arg(long, conflicts_with = "meeting")]
+        to: Option<String>,
+
+        /// Relative date filter, overrides --from/--to [today, yesterday, this-week, last-week, this-month, last-month]
+        #[arg(long, conflicts_with = "meeting")]
+        date: Option<String>,
+
+        /// Longest pause (seconds) within one speaker turn
+        #[arg(long, default_value_t = crate::query::turns::DEFAULT_TURN_GAP_SECS)]
+        gap: f64,
+    },
+}
+
+// === Actions Subcommands ===
+
+#[derive(Subcommand, Debug)]
+pub enum ActionsAction {
+    /// List action items, newest meeting first
+    #[command(visible_alias = "ls")]
+    List {
+        /// Only items owned by you (the meeting's creator), by name or email
+        #[arg(long)]
+        mine: bool,
+
+        /// Only items not marked done
+        #[arg(long)]
+        open: bool,
+
+        /// List decisions instead of action items
+        #[arg(long)]
+        decisions: bool,
+
+        /// Only meetings since this date [e.g., 2024-01-15, or duration: 3d, 2w, 1m]
+        #[arg(long)]
+        since: Option<String>,
+    },
+
+    /// Mark action items done
+    Done {
+        /// Item IDs as listed (a unique prefix is enough)
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+
+    /// Mark action items open again
+    Undone {
+        /// Item IDs as listed (a unique prefix is enough)
+        #[arg(required = true)]
+        ids: Vec<String>,
+    },
+}
+
 // === Admin Subcommands ===
 
 #[derive(Subcommand, Debug)]
@@ -655,6 +1185,14 @@ pub enum DbAction {
     /// The repair for the drift that 'grans admin db info' reports. Re-derives
     /// each index from its source, so nothing is lost and no re-sync is needed.
     RebuildFts,
+    /// Merge another grans database into this one, row by row
+    ///
+    /// Keeps meetings either copy has, the newer version of a meeting both
+    /// have, and the fuller transcript of each. The other file is only read.
+    Merge {
+        /// Database file to merge in
+        other: std::path::PathBuf,
+    },
 }
 
 // === Auth Subcommands ===
@@ -711,9 +1249,125 @@ pub enum DropboxAction {
     },
     /// Show sync status
     Status,
+    /// Merge the Dropbox copy into the local database row by row, then push
+    Merge,
+    /// Encrypt the database before it leaves this machine (enable, unlock, disable)
+    Encryption {
+        #[command(subcommand)]
+        action: EncryptionAction,
+    },
+    /// List the dated snapshots push keeps on Dropbox
+    Snapshots {
+        /// Keep this many snapshots from now on (0 stops taking them)
+        #[arg(long)]
+        keep: Option<usize>,
+    },
+    /// Download a snapshot in place of the local database
+    Restore {
+        /// Snapshot name, as listed by `grans dropbox snapshots`
+        snapshot: String,
+        /// Overwrite even if local has changes that were never pushed
+        #[arg(long)]
+        force: bool,
+    },
     /// Remove Dropbox authentication
     Logout,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum RemoteAction {
+    /// Choose where push and pull replicate the database
+    Init {
+        #[command(subcommand)]
+        kind: RemoteKind,
+    },
+    /// Upload database to the remote
+    Push {
+        /// Overwrite even if remote is newer
+        #[arg(long)]
+        force: bool,
+    },
+    /// Download database from the remote
+    Pull {
+        /// Overwrite even if local is newer
+        #[arg(long)]
+        force: bool,
+    },
+    /// Show sync status
+    Status,
+    /// Merge the remote copy into the local database row by row, then push
+    Merge,
+    /// Encrypt the database before it leaves this machine (enable, unlock, disable)
+    Encryption {
+        #[command(subcommand)]
+        action: EncryptionAction,
+    },
+    /// List the dated snapshots push keeps on the remote
+    Snapshots {
+        /// Keep this many snapshots from now on (0 stops taking them)
+        #[arg(long)]
+        keep: Option<usize>,
+    },
+    /// Download a snapshot in place of the local database
+    Restore {
+        /// Snapshot name, as listed by `grans remote snapshots`
+        snapshot: String,
+        /// Overwrite even if local has changes that were never pushed
+        #[arg(long)]
+        force: bool,
+    },
+    /// Forget the configured remote and its credentials (nothing remote is deleted)
+    Forget,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EncryptionAction {
+    /// Create a key, keep it in the keychain, and store a copy on the remote
+    /// sealed with a passphrase (read from GRANS_SYNC_PASSPHRASE or prompted for)
+    Enable,
+    /// Recover the key on another machine from the remote's passphrase-sealed copy
+    Unlock,
+    /// Push unencrypted again from this machine (the key stays in the keychain)
+    Disable,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RemoteKind {
+    /// Dropbox app folder (same as `grans dropbox init`)
+    Dropbox,
+    /// A directory: a mounted network share, or a folder Syncthing replicates
+    Folder {
+        /// Directory to hold the database (must already exist)
+        path: std::path::PathBuf,
+    },
+    /// A WebDAV collection (Nextcloud, ownCloud, a NAS)
+    Webdav {
+        /// Collection URL, e.g. https://cloud.example.com/remote.php/dav/files/me/grans
+        url: String,
+        /// User name for basic auth; the password is read from
+        /// GRANS_WEBDAV_PASSWORD or prompted for
+        #[arg(long)]
+        username: Option<String>,
+    },
+    /// An S3-compatible bucket (AWS, MinIO, Backblaze B2, Cloudflare R2)
+    S3 {
+        /// Bucket name
+        bucket: String,
+        /// Endpoint URL for non-AWS services (default: AWS for the region)
+        #[arg(long)]
+        endpoint: Option<String>,
+        /// Region used for request signing
+        #[arg(long, default_value = "us-east-1")]
+        region: String,
+        /// Key prefix within the bucket, e.g. "backups/grans"
+        #[arg(long)]
+        prefix: Option<String>,
+        /// Access key ID (default: AWS_ACCESS_KEY_ID or prompt); the secret is
+        /// read from AWS_SECRET_ACCESS_KEY or prompted for
+        #[arg(long)]
+        access_key_id: Option<String>,
+    },
+}
+
 #[cfg(test)]
 mod tests;
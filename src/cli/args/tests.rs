@@ -386,7 +386,7 @@ fn benchmark_quality_note_requires_record() {
     assert!(result.is_err());
 }
 
-fn transcripts_action(cli: &Cli) -> &SyncAction {
+fn sync_action(cli: &Cli) -> &SyncAction {
     match &cli.command {
         Commands::Sync {
             action: Some(action),
@@ -399,7 +399,7 @@ fn transcripts_action(cli: &Cli) -> &SyncAction {
 #[test]
 fn sync_transcripts_accepts_positional_document_id() {
     let cli = Cli::try_parse_from(["grans", "sync", "transcripts", "doc-1"]).unwrap();
-    match transcripts_action(&cli) {
+    match sync_action(&cli) {
         SyncAction::Transcripts { document_id, .. } => {
             assert_eq!(document_id.as_deref(), Some("doc-1"));
         }
@@ -407,6 +407,21 @@ fn sync_transcripts_accepts_positional_document_id() {
     }
 }
 
+#[test]
+fn sync_transcripts_concurrency_defaults_and_is_bounded() {
+    let cli = Cli::try_parse_from(["grans", "sync", "transcripts"]).unwrap();
+    match sync_action(&cli) {
+        SyncAction::Transcripts { concurrency, .. } => {
+            assert_eq!(*concurrency, DEFAULT_SYNC_CONCURRENCY)
+        }
+        _ => panic!("expected transcripts action"),
+    }
+
+    assert!(Cli::try_parse_from(["grans", "sync", "panels", "--concurrency", "8"]).is_ok());
+    assert!(Cli::try_parse_from(["grans", "sync", "panels", "--concurrency", "0"]).is_err());
+    assert!(Cli::try_parse_from(["grans", "sync", "transcripts", "--concurrency", "99"]).is_err());
+}
+
 #[test]
 fn sync_transcripts_positional_conflicts_with_limit() {
     let result = Cli::try_parse_from(["grans", "sync", "transcripts", "doc-1", "--limit", "5"]);
@@ -416,7 +431,7 @@ fn sync_transcripts_positional_conflicts_with_limit() {
 #[test]
 fn sync_transcripts_positional_allows_embed() {
     let cli = Cli::try_parse_from(["grans", "sync", "transcripts", "doc-1", "--embed"]).unwrap();
-    match transcripts_action(&cli) {
+    match sync_action(&cli) {
         SyncAction::Transcripts {
             document_id, embed, ..
         } => {
@@ -436,6 +451,21 @@ fn sync_transcripts_positional_allows_dry_run() {
     }
 }
 
+#[test]
+fn sync_documents_full_flag_parses() {
+    let cli = Cli::try_parse_from(["grans", "sync", "documents", "--full"]).unwrap();
+    assert!(matches!(
+        sync_action(&cli),
+        SyncAction::Documents { full: true }
+    ));
+
+    let cli = Cli::try_parse_from(["grans", "sync", "documents"]).unwrap();
+    assert!(matches!(
+        sync_action(&cli),
+        SyncAction::Documents { full: false }
+    ));
+}
+
 #[test]
 fn sync_all_flag_parses() {
     let cli = Cli::try_parse_from(["grans", "sync", "--all"]).unwrap();
@@ -489,3 +519,123 @@ fn sync_all_allows_dry_run() {
         _ => panic!("expected sync subcommand"),
     }
 }
+
+#[test]
+fn export_markdown_parses_dir_and_flags() {
+    let cli = Cli::try_parse_from([
+        "grans",
+        "export",
+        "markdown",
+        "vault",
+        "--date",
+        "this-week",
+        "--force",
+    ])
+    .unwrap();
+    match &cli.command {
+        Commands::Export {
+            action: ExportAction::Markdown {
+                dir, date, force, ..
+            },
+        } => {
+            assert_eq!(dir, &std::path::PathBuf::from("vault"));
+            assert_eq!(date.as_deref(), Some("this-week"));
+            assert!(*force);
+        }
+        _ => panic!("expected export markdown subcommand"),
+    }
+}
+
+#[test]
+fn serve_defaults_to_loopback() {
+    let cli = Cli::try_parse_from(["grans", "serve"]).unwrap();
+    match &cli.command {
+        Commands::Serve { listen, fast } => {
+            assert_eq!(listen, "127.0.0.1:7373");
+            assert!(!*fast);
+        }
+        _ => panic!("expected serve subcommand"),
+    }
+}
+
+#[test]
+fn mcp_parses_fast_flag() {
+    let cli = Cli::try_parse_from(["grans", "mcp", "--fast"]).unwrap();
+    assert!(matches!(cli.command, Commands::Mcp { fast: true }));
+}
+
+#[test]
+fn benchmark_quality_accepts_semantic_ann_mode() {
+    let cli = Cli::try_parse_from([
+        "grans",
+        "benchmark",
+        "quality",
+        "--file",
+        "golden.json",
+        "--compare",
+        "semantic,semantic-ann",
+    ])
+    .unwrap();
+    assert_eq!(
+        quality_compare(&cli),
+        &[QualityMode::Semantic, QualityMode::SemanticAnn]
+    );
+}
+
+#[test]
+fn embed_parses_storage_flags() {
+    let cli =
+        Cli::try_parse_from(["grans", "embed", "--dim", "256", "--precision", "int8"]).unwrap();
+    match &cli.command {
+        Commands::Embed { dim, precision, .. } => {
+            assert_eq!(*dim, Some(256));
+            assert_eq!(precision.as_deref(), Some("int8"));
+        }
+        _ => panic!("expected embed subcommand"),
+    }
+}
+
+#[test]
+fn embed_rejects_non_matryoshka_dims() {
+    assert!(Cli::try_parse_from(["grans", "embed", "--dim", "300"]).is_err());
+    assert!(Cli::try_parse_from(["grans", "embed", "--precision", "int4"]).is_err());
+}
+
+#[test]
+fn embed_accepts_known_models_only() {
+    let cli = Cli::try_parse_from(["grans", "embed", "--model", "multilingual-e5-base"]).unwrap();
+    match &cli.command {
+        Commands::Embed { model, .. } => {
+            assert_eq!(model.as_deref(), Some("multilingual-e5-base"));
+        }
+        _ => panic!("expected embed subcommand"),
+    }
+    assert!(Cli::try_parse_from(["grans", "embed", "--model", "word2vec"]).is_err());
+}
+
+#[test]
+fn models_export_accepts_embedding_models_and_rerankers() {
+    let cli = Cli::try_parse_from([
+        "grans",
+        "models",
+        "export",
+        "out",
+        "--model",
+        "bge-small-en-v1.5",
+        "--model",
+        "bge-reranker-base",
+    ])
+    .unwrap();
+    match &cli.command {
+        Commands::Models {
+            action: ModelsAction::Export { dir, models },
+        } => {
+            assert_eq!(dir, &std::path::PathBuf::from("out"));
+            assert_eq!(models, &["bge-small-en-v1.5", "bge-reranker-base"]);
+        }
+        _ => panic!("expected models export subcommand"),
+    }
+    assert!(
+        Cli::try_parse_from(["grans", "models", "export", "out", "--model", "word2vec"]).is_err()
+    );
+}
@@ -0,0 +1,109 @@
+//! `grans actions`: action items and decisions from AI panels.
+//!
+//! Items are extracted when panels sync (see [`crate::db::actions`]); the
+//! first run on a database that synced panels before extraction existed
+//! backfills them from the stored panels.
+
+use anyhow::Result;
+use rusqlite::Connection;
+
+use crate::cli::args::ActionsAction;
+use crate::cli::context::RunContext;
+use crate::db::actions::{ActionItemFilter, ActionItemRecord};
+use crate::output::format::OutputMode;
+use crate::output::json::to_json;
+use crate::query::actions::{ItemKind, owner_is};
+use crate::query::dates::build_date_range;
+
+pub fn run(conn: &Connection, action: &ActionsAction, ctx: &RunContext) -> Result<()> {
+    crate::db::actions::backfill_action_items(conn)?;
+    match action {
+        ActionsAction::List {
+            mine,
+            open,
+            decisions,
+            since,
+        } => {
+            let filter = ActionItemFilter {
+                open: *open,
+                kind: Some(if *decisions {
+                    ItemKind::Decision
+                } else {
+                    ItemKind::Action
+                }),
+                date_range: build_date_range(
+                    since.as_deref(),
+                    None,
+                    None,
+                    chrono::Utc::now(),
+                    &ctx.tz,
+                ),
+            };
+            let mut items = crate::db::actions::list_action_items(conn, &filter)?;
+            if *mine {
+                items.retain(is_mine);
+            }
+            print_items(&items, ctx);
+            Ok(())
+        }
+        ActionsAction::Done { ids } => mark(conn, ids, true, ctx),
+        ActionsAction::Undone { ids } => mark(conn, ids, false, ctx),
+    }
+}
+
+/// Whether the item's owner is the meeting's creator, the user who
+/// recorded it.
+fn is_mine(item: &ActionItemRecord) -> bool {
+    item.owner.as_deref().is_some_and(|owner| {
+        owner_is(
+            owner,
+            item.creator_name.as_deref(),
+            item.creator_email.as_deref(),
+        )
+    })
+}
+
+fn print_items(items: &[ActionItemRecord], ctx: &RunContext) {
+    match ctx.output_mode {
+        OutputMode::Json => println!("{}", to_json(&items)),
+        OutputMode::Tty => {
+            if items.is_empty() {
+                println!("No matching items.");
+                return;
+            }
+            let mut meeting: Option<&str> = None;
+            for item in items {
+                if meeting != Some(item.document_id.as_str()) {
+                    if meeting.is_some() {
+                        println!();
+                    }
+                    println!(
+                        "{}",
+                        crate::output::table::format_action_meeting_header(item, &ctx.tz)
+                    );
+                    meeting = Some(&item.document_id);
+                }
+                println!("{}", crate::output::table::format_action_item_row(item));
+            }
+        }
+    }
+}
+
+fn mark(conn: &Connection, ids: &[String], done: bool, ctx: &RunContext) -> Result<()> {
+    let changed = crate::db::actions::set_items_done(conn, ids, done)?;
+    match ctx.output_mode {
+        OutputMode::Json => println!(
+            "{}",
+            to_json(&serde_json::json!({"done": done, "changed": changed}))
+        ),
+        OutputMode::Tty => {
+            let state = if done { "done" } else { "open" };
+            if changed.is_empty() {
+                println!("Already {}.", state);
+            } else {
+                println!("Marked {} item(s) {}.", changed.len(), state);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,64 @@
+//! `grans people`: merging a person's emails, names and speaker names.
+//!
+//! Merges are local to this database (see [`crate::db::aliases`]) and are
+//! never suggested into effect: `suggest` only prints candidates with the
+//! `merge` command that would confirm each.
+
+use anyhow::Result;
+use rusqlite::Connection;
+
+use crate::cli::args::PeopleAliasAction;
+use crate::cli::context::RunContext;
+use crate::output::format::OutputMode;
+use crate::output::json::to_json;
+
+pub fn run(conn: &Connection, action: &PeopleAliasAction, ctx: &RunContext) -> Result<()> {
+    match action {
+        PeopleAliasAction::Merge { first, second } => {
+            let group = crate::db::aliases::merge(conn, first, second)?;
+            match ctx.output_mode {
+                OutputMode::Json => println!("{}", to_json(&group)),
+                OutputMode::Tty => println!(
+                    "Merged: {}",
+                    crate::output::table::format_alias_group(&group)
+                ),
+            }
+        }
+        PeopleAliasAction::Unmerge { identity } => {
+            let alias = crate::db::aliases::unmerge(conn, identity)?;
+            match ctx.output_mode {
+                OutputMode::Json => println!("{}", to_json(&alias)),
+                OutputMode::Tty => println!("Unmerged {}.", alias.display),
+            }
+        }
+        PeopleAliasAction::Suggest => {
+            let suggestions = crate::db::aliases::suggest_merges(conn)?;
+            match ctx.output_mode {
+                OutputMode::Json => println!("{}", to_json(&suggestions)),
+                OutputMode::Tty => {
+                    if suggestions.is_empty() {
+                        println!("No suggestions.");
+                    }
+                    for s in &suggestions {
+                        println!("{}", crate::output::table::format_merge_suggestion(s));
+                    }
+                }
+            }
+        }
+        PeopleAliasAction::List => {
+            let groups = crate::db::aliases::alias_groups(conn)?;
+            match ctx.output_mode {
+                OutputMode::Json => println!("{}", to_json(&groups)),
+                OutputMode::Tty => {
+                    if groups.is_empty() {
+                        println!("No merged people. See `grans people suggest`.");
+                    }
+                    for group in &groups {
+                        println!("{}", crate::output::table::format_alias_group(group));
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
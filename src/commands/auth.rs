@@ -113,7 +113,7 @@ fn credentials_from_stdin() -> Result<GranolaCredentials> {
 /// Run the browser login and exchange the pasted callback for tokens.
 fn credentials_from_browser_login(provider: Provider) -> Result<GranolaCredentials> {
     let pkce = PkceChallenge::generate(granola_auth::PKCE_ENTROPY_BYTES);
-    let (auth_url, sign_in_click_id) = granola_auth::build_auth_url(&pkce.challenge, provider);
+    let (auth_url, sign_in_click_id) = granola_auth::build_auth_url(&pkce.challenge, provider)?;
 
     granola_auth::check_client_version_accepted(&auth_url)?;
 
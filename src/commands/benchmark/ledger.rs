@@ -44,6 +44,12 @@ struct LedgerEntry<'a> {
     #[serde(skip_serializing_if = "BTreeMap::is_empty")]
     strata: &'a BTreeMap<String, AggregateMetrics>,
     latency_ms: &'a LatencyStats,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ann_recall: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    storage: Option<&'a str>,
     binary: &'a str,
     db: &'a str,
     /// Path of the full per-query output, relative to the benchmarks dir.
@@ -98,6 +104,9 @@ pub(super) fn record_run(ctx: &RecordContext, run: &ModeRun) -> Result<PathBuf>
         mrr: run.overall.mrr,
         strata: &run.strata,
         latency_ms: &run.latency,
+        ann_recall: run.ann_recall,
+        model: run.model,
+        storage: run.storage.as_deref(),
         binary: ctx.binary,
         db: ctx.db,
         per_query_results: rel_path,
@@ -174,6 +183,9 @@ mod tests {
                 avg_ms: 12.5,
                 p50_ms: 11.0,
             },
+            ann_recall: None,
+            model: None,
+            storage: None,
             query_results: Vec::new(),
         }
     }
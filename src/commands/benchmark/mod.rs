@@ -3,6 +3,7 @@
 mod dump;
 mod ledger;
 mod metrics;
+mod model_copy;
 mod perf;
 mod quality;
 mod report;
@@ -48,7 +49,24 @@ pub fn run(
             note,
             dump_candidates,
             title_boost_weight,
+            dim,
+            precision,
+            model,
         } => {
+            // Storage overrides start from the database's own layout.
+            let storage = (dim.is_some() || precision.is_some()).then(|| {
+                let mut storage = crate::embed::store::get_storage_spec(conn);
+                if dim.is_some() {
+                    storage.dim = *dim;
+                }
+                if let Some(p) = precision
+                    .as_deref()
+                    .and_then(crate::embed::quant::Precision::parse)
+                {
+                    storage.precision = p;
+                }
+                storage
+            });
             let args = quality::QualityArgs {
                 file,
                 k: *k,
@@ -61,6 +79,8 @@ pub fn run(
                 dump_candidates: dump_candidates.as_deref(),
                 ranking: crate::query::adjust::RankingConfig::default()
                     .with_overrides(*title_boost_weight),
+                storage,
+                model: model.as_deref().and_then(crate::embed::model::find_model),
             };
             quality::run_quality_benchmark(conn, &args, output_mode)
         }
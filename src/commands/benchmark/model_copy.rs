@@ -0,0 +1,144 @@
+//! Cached database copies for scoring an embedding model other than the
+//! one a database was embedded with.
+//!
+//! Benchmarking another model must not touch the real embeddings, so its
+//! vectors live in a copy of the database under `benchmarks/models/`. Each
+//! run re-copies the content and carries the previous run's vectors over,
+//! so only chunks that changed since are embedded again.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use rusqlite::Connection;
+
+use crate::embed::model::ModelInfo;
+
+/// `embedding_metadata` keys describing the vectors themselves, as opposed
+/// to how content is chunked. They travel with the cached vectors; the
+/// chunking keys come from the database being benchmarked.
+const VECTOR_KEYS: &str =
+    "('model_name', 'embedding_dim', 'max_length', 'storage_dim', 'storage_precision')";
+
+/// Copy `conn`'s database to `<dir>/<model>.db`, keeping the vectors a
+/// previous run embedded there with `model`, and open the copy.
+pub(super) fn open(conn: &Connection, dir: &Path, model: &ModelInfo) -> Result<Connection> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create model cache: {}", dir.display()))?;
+    let path = dir.join(format!("{}.db", model.name));
+    let staged = dir.join(format!("{}.db.new", model.name));
+    if staged.exists() {
+        fs::remove_file(&staged)?;
+    }
+
+    conn.execute("VACUUM INTO ?1", [staged.to_string_lossy()])?;
+    {
+        let copy = Connection::open(&staged)?;
+        copy.execute_batch(&format!(
+            "DELETE FROM ann_index;
+             DELETE FROM embeddings;
+             DELETE FROM chunks;
+             DELETE FROM embedding_metadata WHERE key IN {VECTOR_KEYS};"
+        ))?;
+        if path.exists()
+            && let Err(e) = carry_over(&copy, &path)
+        {
+            log::warn!(
+                "Ignoring unreadable cached vectors for {}: {}",
+                model.name,
+                e
+            );
+        }
+    }
+    fs::rename(&staged, &path)?;
+    Ok(Connection::open(&path)?)
+}
+
+/// Copy the chunks, vectors, and vector metadata of the previous copy.
+fn carry_over(copy: &Connection, previous: &Path) -> Result<()> {
+    copy.execute("ATTACH DATABASE ?1 AS prev", [previous.to_string_lossy()])?;
+    let result = copy.execute_batch(&format!(
+        "BEGIN;
+         INSERT INTO chunks SELECT * FROM prev.chunks;
+         INSERT INTO embeddings SELECT * FROM prev.embeddings;
+         INSERT INTO embedding_metadata
+             SELECT * FROM prev.embedding_metadata WHERE key IN {VECTOR_KEYS};
+         COMMIT;"
+    ));
+    if result.is_err() {
+        let _ = copy.execute_batch("ROLLBACK");
+    }
+    copy.execute("DETACH DATABASE prev", [])?;
+    Ok(result?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embed::chunk::{Chunk, ChunkSourceType, hash_content};
+    use crate::embed::model::find_model;
+    use crate::embed::store;
+
+    fn source_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::schema::create_tables(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO documents (id, title, created_at) VALUES ('doc1', 'Standup', '2025-01-01T00:00:00Z')",
+            [],
+        )
+        .unwrap();
+        store::set_model_metadata(&conn, "nomic-embed-text-v1.5", 2, 512).unwrap();
+        insert_chunk(&conn, "live vectors");
+        conn
+    }
+
+    fn insert_chunk(conn: &Connection, text: &str) {
+        let chunk = Chunk {
+            source_type: ChunkSourceType::TranscriptWindow,
+            source_id: format!("doc1:{}", text),
+            document_id: "doc1".to_string(),
+            text: text.to_string(),
+            content_hash: hash_content(text),
+            metadata: None,
+            header: None,
+        };
+        store::insert_chunk_with_embedding(conn, &chunk, &[1.0, 0.0]).unwrap();
+    }
+
+    #[test]
+    fn first_copy_keeps_content_but_no_vectors() {
+        let conn = source_db();
+        let dir = tempfile::tempdir().unwrap();
+        let model = find_model("multilingual-e5-small").unwrap();
+
+        let copy = open(&conn, dir.path(), model).unwrap();
+
+        let docs: i64 = copy
+            .query_row("SELECT COUNT(*) FROM documents", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(docs, 1);
+        assert!(store::load_all_vectors(&copy).unwrap().is_empty());
+        assert_eq!(store::get_model_name(&copy), None);
+        // The source database keeps its own vectors.
+        assert_eq!(store::load_all_vectors(&conn).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn later_copies_carry_the_cached_vectors_over() {
+        let conn = source_db();
+        let dir = tempfile::tempdir().unwrap();
+        let model = find_model("multilingual-e5-small").unwrap();
+        {
+            let copy = open(&conn, dir.path(), model).unwrap();
+            store::set_model_metadata(&copy, model.name, 2, 512).unwrap();
+            insert_chunk(&copy, "cached vectors");
+        }
+
+        let copy = open(&conn, dir.path(), model).unwrap();
+
+        let vectors = store::load_all_vectors(&copy).unwrap();
+        assert_eq!(vectors.len(), 1);
+        assert_eq!(vectors[0].text, "cached vectors");
+        assert_eq!(store::get_model_name(&copy).as_deref(), Some(model.name));
+    }
+}
@@ -7,7 +7,7 @@ use rand::prelude::*;
 use rusqlite::Connection;
 use serde::Serialize;
 
-use crate::embed::search::cosine_similarity;
+use crate::embed::quant::QuantizedVector;
 use crate::embed::store::load_all_vectors;
 use crate::output::format::OutputMode;
 
@@ -48,7 +48,10 @@ pub(super) fn run_semantic_search_benchmark(
 ) -> Result<()> {
     // Load or generate vectors
     let (vectors, mode) = if synthetic {
-        let vecs = generate_synthetic_vectors(vector_count);
+        let vecs = generate_synthetic_vectors(vector_count)
+            .into_iter()
+            .map(QuantizedVector::from)
+            .collect();
         (vecs, "Synthetic".to_string())
     } else {
         let vecs = load_real_vectors(conn)?;
@@ -99,7 +102,9 @@ pub(super) fn run_semantic_search_benchmark(
     Ok(())
 }
 
-fn load_real_vectors(conn: &Connection) -> Result<Vec<Vec<f32>>> {
+/// Stored vectors in their storage encoding, so the benchmark scores
+/// them exactly as search does.
+fn load_real_vectors(conn: &Connection) -> Result<Vec<QuantizedVector>> {
     let stored = load_all_vectors(conn)?;
     Ok(stored.into_iter().map(|sv| sv.vector).collect())
 }
@@ -130,12 +135,16 @@ fn normalize(vec: &mut [f32]) {
     }
 }
 
-fn run_single_search(query: &[f32], vectors: &[Vec<f32>], min_score: f32) -> Vec<(usize, f32)> {
+fn run_single_search(
+    query: &[f32],
+    vectors: &[QuantizedVector],
+    min_score: f32,
+) -> Vec<(usize, f32)> {
     vectors
         .iter()
         .enumerate()
         .filter_map(|(idx, v)| {
-            let score = cosine_similarity(query, v);
+            let score = v.cosine(query);
             if score >= min_score {
                 Some((idx, score))
             } else {
@@ -363,10 +372,10 @@ mod tests {
 
     #[test]
     fn test_run_single_search() {
-        let vectors = vec![
-            vec![1.0, 0.0, 0.0],
-            vec![0.0, 1.0, 0.0],
-            vec![0.7071, 0.7071, 0.0],
+        let vectors: Vec<QuantizedVector> = vec![
+            vec![1.0, 0.0, 0.0].into(),
+            vec![0.0, 1.0, 0.0].into(),
+            vec![0.7071, 0.7071, 0.0].into(),
         ];
         let query = vec![1.0, 0.0, 0.0];
 
@@ -18,6 +18,8 @@ use super::metrics::{self, AggregateMetrics, LabelMatcher, QueryScore, RankedDoc
 use super::perf::percentile;
 use super::retriever::Retriever;
 use crate::cli::args::QualityMode;
+use crate::embed::model::{self, ModelInfo};
+use crate::embed::quant::StorageSpec;
 use crate::output::format::OutputMode;
 use crate::query::adjust::RankingConfig;
 
@@ -37,6 +39,11 @@ pub struct QualityArgs<'a> {
     /// Ranking adjustment weights (defaults with any experiment-flag
     /// overrides applied).
     pub ranking: RankingConfig,
+    /// Vector storage layout to score instead of the stored one, applied
+    /// in memory, when given.
+    pub storage: Option<StorageSpec>,
+    /// Embedding model to score instead of the database's, when given.
+    pub model: Option<&'static ModelInfo>,
 }
 
 /// A single test query from the benchmark file.
@@ -129,6 +136,17 @@ pub struct ModeRun {
     /// Empty when no query carries a `query_type` label (v1 golden set).
     pub strata: BTreeMap<String, AggregateMetrics>,
     pub latency: LatencyStats,
+    /// semantic-ann only: mean share of exact search's top k documents the
+    /// ANN graph also returned in its top k.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ann_recall: Option<f64>,
+    /// The embedding model scored, for modes that embed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<&'static str>,
+    /// The vector storage layout scored, when overridden with --dim or
+    /// --precision (e.g. "256d int8").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<String>,
     pub(super) query_results: Vec<QueryOutcome>,
 }
 
@@ -169,10 +187,34 @@ pub(super) fn run_quality_benchmark(
         None => None,
     };
 
+    // Another model embeds into a cached copy, so the database's own
+    // vectors survive the comparison.
+    let stored = model::stored_or_default(conn);
+    let embed_model = args.model.unwrap_or(stored);
+    let copy = match args.model {
+        Some(m) if m != stored => {
+            let dir = crate::platform::data_dir()?
+                .join("benchmarks")
+                .join("models");
+            Some(super::model_copy::open(conn, &dir, m)?)
+        }
+        _ => None,
+    };
+    let embed_conn = copy.as_ref().unwrap_or(conn);
+
     let mut runs = Vec::with_capacity(modes.len());
     for mode in modes {
-        let retriever = Retriever::build(mode, conn, args.ranking)?;
-        runs.push(run_queries(
+        let retriever = match mode {
+            QualityMode::Fts => Retriever::build(mode, conn, args.ranking, None, embed_model)?,
+            _ => Retriever::build(
+                mode,
+                embed_conn,
+                args.ranking,
+                args.storage.as_ref(),
+                embed_model,
+            )?,
+        };
+        let mut run = run_queries(
             |q| match dump.as_mut() {
                 Some(writer) => {
                     let detailed = retriever
@@ -193,7 +235,13 @@ pub(super) fn run_quality_benchmark(
             &title_map,
             mode,
             args.k,
-        )?);
+        )?;
+        run.ann_recall = ann_recall(&retriever, &queries, args.k)?;
+        if mode != QualityMode::Fts {
+            run.model = Some(embed_model.name);
+            run.storage = args.storage.map(|s| s.to_string());
+        }
+        runs.push(run);
     }
 
     if let (Some(writer), Some(path)) = (dump.take(), args.dump_candidates) {
@@ -215,6 +263,21 @@ pub(super) fn run_quality_benchmark(
     Ok(())
 }
 
+/// Mean [`Retriever::ann_recall`] over the queries exact search answers;
+/// `None` for modes without an ANN graph.
+fn ann_recall(retriever: &Retriever, queries: &[BenchmarkQuery], k: usize) -> Result<Option<f64>> {
+    let mut recalls = Vec::new();
+    for bq in queries {
+        if let Some(recall) = retriever.ann_recall(&bq.query, k)? {
+            recalls.push(recall);
+        }
+    }
+    if recalls.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(recalls.iter().sum::<f64>() / recalls.len() as f64))
+}
+
 /// Persist every run to the results ledger in the benchmarks directory.
 /// Confirmation goes to stderr so --json stdout stays parseable.
 fn record_runs(runs: &[ModeRun], args: &QualityArgs) -> Result<()> {
@@ -333,6 +396,9 @@ where
         overall,
         strata,
         latency,
+        ann_recall: None,
+        model: None,
+        storage: None,
         query_results: outcomes,
     })
 }
@@ -37,6 +37,12 @@ fn print_run_tty(run: &ModeRun, detail: bool) {
     println!("{:24} {:>10}", "Queries:".bold(), run.overall.n);
     println!("{:24} {:>10}", "k:".bold(), run.k);
     println!("{:24} {:>10}", "Matching:".bold(), run.matching);
+    if let Some(model) = run.model {
+        println!("{:24} {:>10}", "Embedding model:".bold(), model);
+    }
+    if let Some(storage) = &run.storage {
+        println!("{:24} {:>10}", "Vector storage:".bold(), storage);
+    }
     println!(
         "{:24} {:>10}",
         "Matches in top k:".bold(),
@@ -63,6 +69,13 @@ fn print_run_tty(run: &ModeRun, detail: bool) {
         "Latency (avg / p50):".bold(),
         format!("{:.1} / {:.1} ms", run.latency.avg_ms, run.latency.p50_ms)
     );
+    if let Some(recall) = run.ann_recall {
+        println!(
+            "{:24} {:>10.1}%",
+            format!("ANN recall@{} vs exact:", run.k).bold(),
+            recall * 100.0
+        );
+    }
 
     if !run.strata.is_empty() {
         println!();
@@ -3,7 +3,8 @@
 //! Each mode returns the full document-level ranked list for a query;
 //! scoring (metrics.rs) applies k. Lists reflect current production
 //! behavior for the mode: FTS is ranked by bm25 with a recency tiebreak,
-//! semantic by best-chunk cosine score, hybrid by RRF over both, and the
+//! semantic by best-chunk cosine score (semantic-ann the same, through an
+//! HNSW graph instead of an exact scan), hybrid by RRF over both, and the
 //! rerank modes by cross-encoder score blended with the fusion prior over
 //! the top of the fused pool.
 
@@ -12,23 +13,43 @@ use rusqlite::Connection;
 
 use super::metrics::RankedDoc;
 use crate::cli::args::QualityMode;
+use crate::embed::ann::{AnnIndex, HnswParams};
 use crate::embed::config::EmbedSpec;
-use crate::embed::model::{Embedder, FastEmbedModel};
+use crate::embed::model::{Embedder, FastEmbedModel, ModelInfo};
+use crate::embed::quant::{Precision, StorageSpec};
 use crate::embed::rerank::{FastEmbedReranker, RerankModel, Reranker};
 use crate::embed::search::SemanticSearchResult;
-use crate::embed::{DEFAULT_BATCH_SIZE, EmbeddingIndex, ensure_embeddings};
+use crate::embed::{DEFAULT_BATCH_SIZE, DocumentScope, EmbeddingIndex, ensure_embeddings};
 use crate::query::adjust::{RankingConfig, RankingContext};
-use crate::query::filter::SearchTarget;
+use crate::query::filter::{MeetingFilters, SearchTarget};
+use crate::query::hybrid::CANDIDATE_POOL;
 use crate::query::rerank::RerankCandidate;
 
+/// Semantic modes rank every embedded document, deleted or not.
+const EVERY_DOCUMENT: DocumentScope<'static> = DocumentScope {
+    date_range: None,
+    include_deleted: true,
+    allowed: None,
+    exclude: None,
+};
+
 pub enum Retriever<'a> {
     Fts {
         conn: &'a Connection,
     },
     Semantic {
+        conn: &'a Connection,
         embedder: FastEmbedModel,
         index: EmbeddingIndex,
     },
+    /// Semantic search through an ANN graph, keeping the exact index so
+    /// [`Retriever::ann_recall`] can measure what the graph misses.
+    SemanticAnn {
+        conn: &'a Connection,
+        embedder: FastEmbedModel,
+        exact: EmbeddingIndex,
+        ann: EmbeddingIndex,
+    },
     Hybrid {
         conn: &'a Connection,
         embedder: FastEmbedModel,
@@ -51,19 +72,75 @@ impl<'a> Retriever<'a> {
     /// embedding spec resolves from the database's stored metadata, so a
     /// snapshot embedded with a variant scheme is benchmarked as-is
     /// instead of being silently re-embedded with this binary's defaults.
-    pub fn build(mode: QualityMode, conn: &'a Connection, cfg: RankingConfig) -> Result<Self> {
+    /// `model` embeds the queries and any chunks `conn` lacks vectors for.
+    /// With `storage`, the vectors are re-encoded to that layout in memory
+    /// first, so a storage setting can be scored before committing to it.
+    pub fn build(
+        mode: QualityMode,
+        conn: &'a Connection,
+        cfg: RankingConfig,
+        storage: Option<&StorageSpec>,
+        model: &'static ModelInfo,
+    ) -> Result<Self> {
         match mode {
             QualityMode::Fts => Ok(Retriever::Fts { conn }),
             QualityMode::Semantic => {
-                let embedder = FastEmbedModel::new()?;
+                let embedder = FastEmbedModel::new(model)?;
                 let spec = EmbedSpec::resolve_stored(conn, embedder.max_length());
-                let index = ensure_embeddings(conn, &embedder, DEFAULT_BATCH_SIZE, &spec)?;
-                Ok(Retriever::Semantic { embedder, index })
+                let index = reencode(
+                    ensure_embeddings(conn, &embedder, DEFAULT_BATCH_SIZE, &spec)?,
+                    storage,
+                )?;
+                Ok(Retriever::Semantic {
+                    conn,
+                    embedder,
+                    index,
+                })
+            }
+            QualityMode::SemanticAnn => {
+                let embedder = FastEmbedModel::new(model)?;
+                let spec = EmbedSpec::resolve_stored(conn, embedder.max_length());
+                let exact = reencode(
+                    ensure_embeddings(conn, &embedder, DEFAULT_BATCH_SIZE, &spec)?,
+                    storage,
+                )?;
+                // Built in memory with `grans embed`'s parameters, whatever
+                // the corpus size, so small benchmark databases still
+                // exercise the graph.
+                let vectors: Vec<Vec<f32>> =
+                    exact.vectors.iter().map(|v| v.vector.to_f32()).collect();
+                let graph = AnnIndex::build(
+                    vectors.first().map_or(0, Vec::len),
+                    exact
+                        .vectors
+                        .iter()
+                        .zip(&vectors)
+                        .map(|(s, v)| (s.chunk_id, s.source_type.as_str(), v.as_slice())),
+                    HnswParams::default(),
+                    exact
+                        .vectors
+                        .first()
+                        .map_or(Precision::F32, |v| v.vector.precision()),
+                );
+                let ann = EmbeddingIndex {
+                    vectors: Vec::new(),
+                    stats: None,
+                    ann: Some(graph),
+                };
+                Ok(Retriever::SemanticAnn {
+                    conn,
+                    embedder,
+                    exact,
+                    ann,
+                })
             }
             QualityMode::Hybrid => {
-                let embedder = FastEmbedModel::new()?;
+                let embedder = FastEmbedModel::new(model)?;
                 let spec = EmbedSpec::resolve_stored(conn, embedder.max_length());
-                let index = ensure_embeddings(conn, &embedder, DEFAULT_BATCH_SIZE, &spec)?;
+                let index = reencode(
+                    ensure_embeddings(conn, &embedder, DEFAULT_BATCH_SIZE, &spec)?,
+                    storage,
+                )?;
                 Ok(Retriever::Hybrid {
                     conn,
                     embedder,
@@ -71,14 +148,17 @@ impl<'a> Retriever<'a> {
                 })
             }
             QualityMode::RerankJina | QualityMode::RerankBge => {
-                let embedder = FastEmbedModel::new()?;
+                let embedder = FastEmbedModel::new(model)?;
                 let spec = EmbedSpec::resolve_stored(conn, embedder.max_length());
-                let index = ensure_embeddings(conn, &embedder, DEFAULT_BATCH_SIZE, &spec)?;
-                let model = match mode {
+                let index = reencode(
+                    ensure_embeddings(conn, &embedder, DEFAULT_BATCH_SIZE, &spec)?,
+                    storage,
+                )?;
+                let rerank_model = match mode {
                     QualityMode::RerankJina => RerankModel::JinaTurbo,
                     _ => RerankModel::BgeBase,
                 };
-                let reranker = Box::new(FastEmbedReranker::new(model)?);
+                let reranker = Box::new(FastEmbedReranker::new(rerank_model)?);
                 let ctx = RankingContext::load(conn)?;
                 Ok(Retriever::HybridRerank {
                     conn,
@@ -96,9 +176,38 @@ impl<'a> Retriever<'a> {
     pub fn retrieve(&self, query: &str) -> Result<Vec<RankedDoc>> {
         match self {
             Retriever::Fts { conn } => retrieve_fts(conn, query),
-            Retriever::Semantic { embedder, index } => {
+            Retriever::Semantic {
+                conn,
+                embedder,
+                index,
+            } => {
+                let query_vec = embedder.embed_query(query)?;
+                Ok(to_ranked(index.search(
+                    conn,
+                    &query_vec,
+                    0.0,
+                    None,
+                    &EVERY_DOCUMENT,
+                    0,
+                )?))
+            }
+            Retriever::SemanticAnn {
+                conn,
+                embedder,
+                ann,
+                ..
+            } => {
                 let query_vec = embedder.embed_query(query)?;
-                Ok(to_ranked(index.search(&query_vec, 0.0, None)))
+                // A bounded list, so the graph walk answers rather than the
+                // exact scan an unbounded search falls back to.
+                Ok(to_ranked(ann.search(
+                    conn,
+                    &query_vec,
+                    0.0,
+                    None,
+                    &EVERY_DOCUMENT,
+                    CANDIDATE_POOL,
+                )?))
             }
             Retriever::Hybrid {
                 conn,
@@ -139,6 +248,60 @@ impl<'a> Retriever<'a> {
             _ => Ok(None),
         }
     }
+
+    /// For semantic-ann, the share of exact search's top `k` documents the
+    /// graph also returned in its top `k` (recall against exact); `None`
+    /// for other modes, or when exact search finds nothing.
+    pub fn ann_recall(&self, query: &str, k: usize) -> Result<Option<f64>> {
+        let Retriever::SemanticAnn {
+            conn,
+            embedder,
+            exact,
+            ann,
+        } = self
+        else {
+            return Ok(None);
+        };
+        let query_vec = embedder.embed_query(query)?;
+        let exact_top: Vec<String> = exact
+            .search(conn, &query_vec, 0.0, None, &EVERY_DOCUMENT, 0)?
+            .into_iter()
+            .take(k)
+            .map(|r| r.document_id)
+            .collect();
+        if exact_top.is_empty() {
+            return Ok(None);
+        }
+        let ann_top: Vec<String> = ann
+            .search(conn, &query_vec, 0.0, None, &EVERY_DOCUMENT, k)?
+            .into_iter()
+            .take(k)
+            .map(|r| r.document_id)
+            .collect();
+        let found = exact_top.iter().filter(|id| ann_top.contains(id)).count();
+        Ok(Some(found as f64 / exact_top.len() as f64))
+    }
+}
+
+/// Re-encode an index's vectors to `storage` in memory. Truncation can only
+/// narrow what the database stores.
+fn reencode(mut index: EmbeddingIndex, storage: Option<&StorageSpec>) -> Result<EmbeddingIndex> {
+    let Some(storage) = storage else {
+        return Ok(index);
+    };
+    if let (Some(dim), Some(first)) = (storage.dim, index.vectors.first())
+        && dim > first.vector.len()
+    {
+        anyhow::bail!(
+            "--dim {} exceeds the {} dimensions stored in this database",
+            dim,
+            first.vector.len()
+        );
+    }
+    for v in &mut index.vectors {
+        v.vector = storage.decode(&storage.encode(&v.vector.to_f32()));
+    }
+    Ok(index)
 }
 
 /// FTS keyword search over the same targets `grans search` uses by default
@@ -172,7 +335,7 @@ fn retrieve_hybrid(
         index,
         query,
         &targets,
-        None,
+        &MeetingFilters::default(),
         None,
         false,
     )?
@@ -205,7 +368,7 @@ fn retrieve_hybrid_rerank_detailed(
         index,
         query,
         &targets,
-        None,
+        &MeetingFilters::default(),
         None,
         false,
     )?;
@@ -297,10 +460,11 @@ mod tests {
                 document_id: "doc-1".to_string(),
                 source_type: "transcript_window".to_string(),
                 text: String::new(),
-                vector: vec![1.0, 1.0],
+                vector: vec![1.0, 1.0].into(),
                 metadata_json: None,
             }],
             stats: None,
+            ann: None,
         };
         let embedder = MockEmbedder {
             dim: 2,
@@ -323,6 +487,7 @@ mod tests {
         let index = EmbeddingIndex {
             vectors: Vec::new(),
             stats: None,
+            ann: None,
         };
         let embedder = crate::embed::model::MockEmbedder {
             dim: 2,
@@ -352,7 +517,7 @@ mod tests {
             document_id: doc_id.to_string(),
             source_type: "transcript_window".to_string(),
             text: text.to_string(),
-            vector,
+            vector: vector.into(),
             metadata_json: None,
         };
         let index = EmbeddingIndex {
@@ -361,6 +526,7 @@ mod tests {
                 stored("doc-2", "planning", vec![0.0, 1.0]),
             ],
             stats: None,
+            ann: None,
         };
         let embedder = MockEmbedder {
             dim: 2,
@@ -399,7 +565,7 @@ mod tests {
             document_id: doc_id.to_string(),
             source_type: "transcript_window".to_string(),
             text: text.to_string(),
-            vector,
+            vector: vector.into(),
             metadata_json: None,
         };
         let index = EmbeddingIndex {
@@ -408,6 +574,7 @@ mod tests {
                 stored("doc-2", "planning", vec![0.0, 1.0]),
             ],
             stats: None,
+            ann: None,
         };
         let embedder = MockEmbedder {
             dim: 2,
@@ -454,6 +621,7 @@ mod tests {
         let index = EmbeddingIndex {
             vectors: Vec::new(),
             stats: None,
+            ann: None,
         };
         let embedder = MockEmbedder {
             dim: 2,
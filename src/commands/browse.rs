@@ -11,19 +11,22 @@ use crate::output::format::OutputMode;
 
 pub fn run(conn: &Connection, action: &BrowseAction, ctx: &RunContext) -> Result<()> {
     match action {
-        BrowseAction::People { action } => run_people(conn, action, ctx.output_mode),
+        BrowseAction::People { action } => run_people(conn, action, ctx),
         BrowseAction::Calendars { action } => run_calendars(conn, action, ctx),
         BrowseAction::Templates { action } => run_templates(conn, action, ctx.output_mode),
         BrowseAction::Recipes { action } => run_recipes(conn, action, ctx.output_mode),
     }
 }
 
-fn run_people(conn: &Connection, action: &PeopleAction, mode: OutputMode) -> Result<()> {
+fn run_people(conn: &Connection, action: &PeopleAction, ctx: &RunContext) -> Result<()> {
     match action {
         PeopleAction::List { company } => {
-            crate::commands::people::list(conn, company.as_deref(), mode)
+            crate::commands::people::list(conn, company.as_deref(), ctx.output_mode)
+        }
+        PeopleAction::Show { query } => crate::commands::people::show(conn, query, ctx.output_mode),
+        PeopleAction::Profile { query, utterances } => {
+            crate::commands::people::profile(conn, query, *utterances, ctx)
         }
-        PeopleAction::Show { query } => crate::commands::people::show(conn, query, mode),
     }
 }
 
@@ -2,6 +2,7 @@ use anyhow::Result;
 use chrono::Utc;
 use rusqlite::Connection;
 
+use crate::cli::args::CalendarAction;
 use crate::cli::context::RunContext;
 use crate::output::format::OutputMode;
 use crate::query::dates::build_date_range;
@@ -37,14 +38,17 @@ pub fn events(
     date: Option<&str>,
     ctx: &RunContext,
 ) -> Result<()> {
+    crate::db::event_links::ensure_linked(conn)?;
     let date_range = build_date_range(from, to, date, Utc::now(), &ctx.tz);
-    let events = crate::db::calendars::list_events(conn, calendar_filter, date_range.as_ref())?;
-
-    let refs: Vec<_> = events.iter().collect();
+    let events = crate::db::event_links::list_events_with_meetings(
+        conn,
+        calendar_filter,
+        date_range.as_ref(),
+    )?;
 
     match ctx.output_mode {
         OutputMode::Json => {
-            println!("{}", crate::output::json::format_events(&refs));
+            println!("{}", crate::output::json::format_events(&events));
         }
         OutputMode::Tty => {
             if events.is_empty() {
@@ -59,3 +63,62 @@ pub fn events(
 
     Ok(())
 }
+
+pub fn run(conn: &Connection, action: &CalendarAction, ctx: &RunContext) -> Result<()> {
+    match action {
+        CalendarAction::Gaps {
+            calendar,
+            from,
+            to,
+            date,
+            all,
+        } => gaps(
+            conn,
+            calendar.as_deref(),
+            from.as_deref(),
+            to.as_deref(),
+            date.as_deref(),
+            *all,
+            ctx,
+        ),
+    }
+}
+
+fn gaps(
+    conn: &Connection,
+    calendar_filter: Option<&str>,
+    from: Option<&str>,
+    to: Option<&str>,
+    date: Option<&str>,
+    all: bool,
+    ctx: &RunContext,
+) -> Result<()> {
+    crate::db::event_links::ensure_linked(conn)?;
+    let now = Utc::now();
+    let date_range = build_date_range(from, to, date, now, &ctx.tz);
+    let events = crate::db::event_links::unrecorded_events(
+        conn,
+        calendar_filter,
+        date_range.as_ref(),
+        now,
+        all,
+    )?;
+
+    match ctx.output_mode {
+        OutputMode::Json => {
+            let refs: Vec<_> = events.iter().collect();
+            println!("{}", crate::output::json::to_json(&refs));
+        }
+        OutputMode::Tty => {
+            if events.is_empty() {
+                println!("No unrecorded meetings.");
+                return Ok(());
+            }
+            for event in &events {
+                println!("{}", crate::output::table::format_gap_row(event, &ctx.tz));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -4,6 +4,7 @@ use std::path::Path;
 use crate::cli::args::DbAction;
 use crate::db::accounts::{self, account_label};
 use crate::db::integrity;
+use crate::db::merge::{self, MergeReport};
 
 pub fn run_with_path(action: &DbAction, db_path: &Path) -> Result<()> {
     match action {
@@ -23,6 +24,9 @@ pub fn run_with_path(action: &DbAction, db_path: &Path) -> Result<()> {
         DbAction::RebuildFts => {
             rebuild_search_indexes(db_path)?;
         }
+        DbAction::Merge { other } => {
+            merge_database(db_path, other)?;
+        }
     }
     Ok(())
 }
@@ -245,6 +249,47 @@ fn list_all_databases() -> Result<()> {
     Ok(())
 }
 
+fn merge_database(db_path: &Path, other: &Path) -> Result<()> {
+    if !other.exists() {
+        anyhow::bail!("No database found at {}", other.display());
+    }
+
+    println!("Merging {} into {}...", other.display(), db_path.display());
+    let report = merge::merge_files(db_path, other)?;
+    print_merge_report(&report);
+
+    Ok(())
+}
+
+/// Summarize what a merge took from the other copy.
+pub(super) fn print_merge_report(report: &MergeReport) {
+    if report.is_empty() {
+        println!("The other copy had nothing this one lacks.");
+    } else {
+        for (count, what) in [
+            (report.documents_added, "meeting(s) added"),
+            (
+                report.documents_updated,
+                "meeting(s) updated to a newer version",
+            ),
+            (report.transcripts_taken, "fuller transcript(s) taken"),
+            (report.panels_taken, "AI notes panel(s) added or updated"),
+            (report.other_rows, "people, events and other row(s) added"),
+            (report.embeddings_added, "embedding(s) copied"),
+        ] {
+            if count > 0 {
+                println!("  {:>6} {}", count, what);
+            }
+        }
+    }
+    if let Some(reason) = &report.embeddings_skipped {
+        println!(
+            "Embeddings were not copied: {}. Run 'grans embed' to embed the merged meetings.",
+            reason
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
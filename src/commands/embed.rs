@@ -34,17 +34,18 @@ pub fn run(
 
 /// Show embedding status without triggering embedding.
 fn show_status(conn: &Connection, mode: OutputMode, spec: &EmbedSpec) -> Result<()> {
-    let status = embed::get_embedding_status(conn, embed::model::MODEL_NAME, spec)?;
+    let status = embed::get_embedding_status(conn, spec.model.name, spec)?;
+    let model_dim = embed::store::get_embedding_dim(conn);
 
     match mode {
-        OutputMode::Json => print_status_json(&status, spec),
-        OutputMode::Tty => print_status_tty(&status, spec),
+        OutputMode::Json => print_status_json(&status, spec, model_dim),
+        OutputMode::Tty => print_status_tty(&status, spec, model_dim),
     }
 
     Ok(())
 }
 
-fn print_status_json(status: &EmbeddingStatus, spec: &EmbedSpec) {
+fn print_status_json(status: &EmbeddingStatus, spec: &EmbedSpec, model_dim: Option<usize>) {
     let mut json = serde_json::json!({
         "chunking": {
             "target_tokens": spec.chunking.target_tokens,
@@ -52,6 +53,11 @@ fn print_status_json(status: &EmbeddingStatus, spec: &EmbedSpec) {
             "overlap_mode": spec.chunking.overlap_mode.as_str(),
             "contextual_headers": spec.contextual_headers,
         },
+        "storage": {
+            "dim": spec.storage.dim,
+            "precision": spec.storage.precision.as_str(),
+            "bytes_per_vector": model_dim.map(|d| spec.storage.bytes_per_vector(d)),
+        },
         "chunking_changed_warning": status.chunking_changed_warning,
         "total_chunks": status.total_chunks,
         "embedded_chunks": status.embedded_chunks,
@@ -105,12 +111,17 @@ fn print_status_json(status: &EmbeddingStatus, spec: &EmbedSpec) {
     println!("{}", json);
 }
 
-fn print_status_tty(status: &EmbeddingStatus, spec: &EmbedSpec) {
+fn print_status_tty(status: &EmbeddingStatus, spec: &EmbedSpec, model_dim: Option<usize>) {
     println!("\x1b[1mEmbedding Status\x1b[0m");
     println!("\x1b[2m────────────────\x1b[0m");
 
     let model = status.model_name.as_deref().unwrap_or("(not set)");
-    println!("Model:      {}", model);
+    let multilingual = embed::model::find_model(model).is_some_and(|m| m.multilingual);
+    println!(
+        "Model:      {}{}",
+        model,
+        if multilingual { " (multilingual)" } else { "" }
+    );
     if let Some(max_len) = status.max_length {
         println!("Max length: {} tokens", format_number(max_len));
     }
@@ -121,6 +132,14 @@ fn print_status_tty(status: &EmbeddingStatus, spec: &EmbedSpec) {
         spec.chunking.overlap_mode.as_str(),
         if spec.contextual_headers { "on" } else { "off" },
     );
+    match model_dim {
+        Some(dim) => println!(
+            "Storage:    {} ({} bytes/vector)",
+            spec.storage,
+            format_number(spec.storage.bytes_per_vector(dim))
+        ),
+        None => println!("Storage:    {}", spec.storage),
+    }
     println!();
 
     println!("Total:     {} chunks", format_number(status.total_chunks));
@@ -271,7 +290,7 @@ fn clear_embeddings(
     mode: OutputMode,
     spec: &EmbedSpec,
 ) -> Result<()> {
-    let status = embed::get_embedding_status(conn, embed::model::MODEL_NAME, spec)?;
+    let status = embed::get_embedding_status(conn, spec.model.name, spec)?;
 
     if status.embedded_chunks == 0 && status.orphaned_chunks == 0 {
         match mode {
@@ -361,18 +380,27 @@ fn reconcile_without_model(
     conn: &Connection,
     spec: &EmbedSpec,
 ) -> Result<(embed::EmbeddingStatus, Option<ShortCircuit>)> {
+    // Re-encoding only rewrites blobs, so it runs even when nothing needs
+    // embedding. A model switch discards the vectors instead.
+    if embed::store::get_model_name(conn).is_none_or(|m| m == spec.model.name) {
+        embed::apply_storage_spec(conn, &spec.storage)?;
+    }
     let watermark = embed::freshness::current_sync_watermark(conn)?;
-    let status = embed::get_embedding_status(conn, embed::model::MODEL_NAME, spec)?;
+    let status = embed::get_embedding_status(conn, spec.model.name, spec)?;
 
     if status.total_chunks == 0 {
         let orphans_removed = status.orphan_ids.len();
         embed::store::delete_chunks(conn, &status.orphan_ids)?;
         embed::store::set_embedded_watermark(conn, watermark.as_deref())?;
+        embed::refresh_ann_index(conn)?;
         return Ok((status, Some(ShortCircuit::NoContent { orphans_removed })));
     }
 
     if status.pending_chunks == 0 && status.orphaned_chunks == 0 {
         embed::store::set_embedded_watermark(conn, watermark.as_deref())?;
+        // Nothing to embed, but a graph may still be missing (databases
+        // embedded before ANN indexes existed) or outdated.
+        embed::refresh_ann_index(conn)?;
         return Ok((status, Some(ShortCircuit::AlreadyEmbedded)));
     }
 
@@ -496,15 +524,17 @@ fn do_embed(
     mode: OutputMode,
     spec: &EmbedSpec,
 ) -> Result<()> {
-    let embedder = embed::model::FastEmbedModel::new()?;
+    let embedder = embed::model::FastEmbedModel::new(spec.model)?;
     let index = embed::ensure_embeddings(conn, &embedder, batch_size, spec)?;
+    let ann = embed::refresh_ann_index(conn)?;
 
     match mode {
         OutputMode::Json => {
             let mut json = serde_json::json!({
                 "action": "embed",
                 "success": true,
-                "total_vectors": index.vectors.len(),
+                "total_vectors": index.len(),
+                "ann_index": ann.as_str(),
             });
             if let Some(stats) = &index.stats {
                 json["stats"] = serde_json::json!({
@@ -518,7 +548,7 @@ fn do_embed(
         _ => {
             println!(
                 "Embedding complete. {} vectors ready for search.",
-                format_number(index.vectors.len())
+                format_number(index.len())
             );
         }
     }
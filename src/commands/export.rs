@@ -0,0 +1,362 @@
+//! `grans export`: write meetings out of the database into other tools.
+//!
+//! The Markdown export keeps a manifest in the target directory recording each
+//! exported document's file and a hash of the note written to it, so a re-run
+//! rewrites only the meetings whose note changed since the last one (a new
+//! transcript or panel changes the note without touching `updated_at`) and
+//! leaves the rest untouched for whatever tool is watching the vault.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::cli::args::ExportAction;
+use crate::cli::context::RunContext;
+use crate::db::export::ExportDocument;
+use crate::output::format::OutputMode;
+use crate::output::markdown::{MarkdownNote, note_file_stem, render_note};
+use crate::query::dates::build_date_range;
+
+/// Manifest file name inside the export directory. Dot-prefixed so vault
+/// tools ignore it.
+pub const MANIFEST_FILE: &str = ".grans-export.json";
+
+/// What a previous export wrote, keyed by document ID.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    documents: BTreeMap<String, ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+struct ManifestEntry {
+    /// File name relative to the export directory.
+    file: String,
+    /// SHA-256 of the note as written; absent in manifests from before
+    /// notes were hashed, whose documents are all rewritten once.
+    #[serde(default)]
+    content_hash: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct ExportSummary {
+    pub dir: PathBuf,
+    pub written: Vec<String>,
+    pub unchanged: usize,
+}
+
+pub fn run(conn: &Connection, action: &ExportAction, ctx: &RunContext) -> Result<()> {
+    match action {
+        ExportAction::Markdown {
+            dir,
+            from,
+            to,
+            date,
+            include_deleted,
+            force,
+        } => {
+            let date_range = build_date_range(
+                from.as_deref(),
+                to.as_deref(),
+                date.as_deref(),
+                Utc::now(),
+                &ctx.tz,
+            );
+            let docs = crate::db::export::list_export_documents(
+                conn,
+                date_range.as_ref(),
+                *include_deleted,
+            )?;
+            let summary = export_markdown(conn, &docs, dir, *force, &ctx.tz)?;
+
+            match ctx.output_mode {
+                OutputMode::Json => println!("{}", serde_json::to_string_pretty(&summary)?),
+                OutputMode::Tty => {
+                    println!(
+                        "Exported {} meeting(s) to {} ({} unchanged)",
+                        summary.written.len(),
+                        summary.dir.display(),
+                        summary.unchanged
+                    );
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Write one Markdown file per document into `dir`, skipping documents whose
+/// note matches the one the manifest recorded unless `force` is set.
+fn export_markdown(
+    conn: &Connection,
+    docs: &[ExportDocument],
+    dir: &Path,
+    force: bool,
+    tz: &chrono::FixedOffset,
+) -> Result<ExportSummary> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create export directory {}", dir.display()))?;
+
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let mut manifest = load_manifest(&manifest_path)?;
+    let mut summary = ExportSummary {
+        dir: dir.to_path_buf(),
+        ..Default::default()
+    };
+
+    // File name → owning document, so two meetings with the same date and
+    // title never overwrite each other.
+    let mut claimed: HashMap<String, String> = manifest
+        .documents
+        .iter()
+        .map(|(id, entry)| (entry.file.clone(), id.clone()))
+        .collect();
+
+    for doc in docs {
+        let previous = manifest.documents.get(&doc.id).cloned();
+        let file = choose_file_name(doc, previous.as_ref(), &claimed, tz);
+        let path = dir.join(&file);
+
+        let attendees = crate::db::export::document_attendees(conn, &doc.id)?;
+        let panels = crate::db::panels::load_panels(conn, &doc.id)?;
+        let transcript = crate::db::transcripts::load_transcript(conn, &doc.id)?;
+        let note = MarkdownNote {
+            document: doc,
+            attendees: &attendees,
+            panels: &panels,
+            transcript: &transcript,
+        };
+        let rendered = render_note(&note, tz);
+        let content_hash = format!("{:x}", Sha256::digest(rendered.as_bytes()));
+
+        let unchanged = previous
+            .as_ref()
+            .is_some_and(|p| p.file == file && p.content_hash.as_ref() == Some(&content_hash));
+        if unchanged && !force && path.exists() {
+            summary.unchanged += 1;
+            continue;
+        }
+
+        fs::write(&path, rendered)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+
+        // A retitled meeting gets a new file name; drop the old file rather
+        // than leave a stale duplicate in the vault.
+        if let Some(old) = previous.filter(|p| p.file != file) {
+            claimed.remove(&old.file);
+            let _ = fs::remove_file(dir.join(&old.file));
+        }
+
+        claimed.insert(file.clone(), doc.id.clone());
+        manifest.documents.insert(
+            doc.id.clone(),
+            ManifestEntry {
+                file: file.clone(),
+                content_hash: Some(content_hash),
+            },
+        );
+        summary.written.push(file);
+    }
+
+    save_manifest(&manifest_path, &manifest)?;
+    Ok(summary)
+}
+
+/// The file a document is written to: `<date> <title>.md`, or with the short
+/// document ID appended when another document already holds that name. A
+/// document keeps its previous name while that is still one of the two.
+fn choose_file_name(
+    doc: &ExportDocument,
+    previous: Option<&ManifestEntry>,
+    claimed: &HashMap<String, String>,
+    tz: &chrono::FixedOffset,
+) -> String {
+    let stem = note_file_stem(doc, tz);
+    let short_id: String = doc.id.chars().take(8).collect();
+    let plain = format!("{}.md", stem);
+    let disambiguated = format!("{} ({}).md", stem, short_id);
+
+    if let Some(previous) = previous.filter(|p| p.file == plain || p.file == disambiguated) {
+        return previous.file.clone();
+    }
+
+    match claimed.get(&plain) {
+        Some(owner) if owner != &doc.id => disambiguated,
+        _ => plain,
+    }
+}
+
+fn load_manifest(path: &Path) -> Result<Manifest> {
+    if !path.exists() {
+        return Ok(Manifest::default());
+    }
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| {
+        format!(
+            "Failed to parse {}; delete it to re-export everything",
+            path.display()
+        )
+    })
+}
+
+fn save_manifest(path: &Path, manifest: &Manifest) -> Result<()> {
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, serde_json::to_string_pretty(manifest)?)
+        .with_context(|| format!("Failed to write {}", temp_path.display()))?;
+    fs::rename(&temp_path, path).with_context(|| format!("Failed to replace {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_fixtures::{build_test_db, meetings_state};
+    use chrono::FixedOffset;
+    use tempfile::TempDir;
+
+    fn utc() -> FixedOffset {
+        FixedOffset::east_opt(0).unwrap()
+    }
+
+    fn export(conn: &Connection, dir: &Path, force: bool) -> ExportSummary {
+        let docs = crate::db::export::list_export_documents(conn, None, false).unwrap();
+        export_markdown(conn, &docs, dir, force, &utc()).unwrap()
+    }
+
+    #[test]
+    fn writes_one_file_per_document_and_a_manifest() {
+        let conn = build_test_db(&meetings_state());
+        let dir = TempDir::new().unwrap();
+
+        let summary = export(&conn, dir.path(), false);
+
+        assert_eq!(
+            summary.written,
+            vec![
+                "2026-01-20 AI Strategy Meeting.md",
+                "2026-01-22 Weekly Standup.md"
+            ]
+        );
+        let note =
+            fs::read_to_string(dir.path().join("2026-01-20 AI Strategy Meeting.md")).unwrap();
+        assert!(note.contains("**You:** Hello everyone"));
+        assert!(dir.path().join(MANIFEST_FILE).exists());
+    }
+
+    #[test]
+    fn rerun_skips_unchanged_documents() {
+        let conn = build_test_db(&meetings_state());
+        let dir = TempDir::new().unwrap();
+        export(&conn, dir.path(), false);
+
+        conn.execute(
+            "UPDATE documents SET updated_at = '2026-02-01T00:00:00Z' WHERE id = 'doc-2'",
+            [],
+        )
+        .unwrap();
+        let summary = export(&conn, dir.path(), false);
+
+        assert_eq!(summary.written, vec!["2026-01-22 Weekly Standup.md"]);
+        assert_eq!(summary.unchanged, 1);
+    }
+
+    #[test]
+    fn rerun_rewrites_documents_whose_transcript_arrived() {
+        let conn = build_test_db(&meetings_state());
+        let dir = TempDir::new().unwrap();
+        export(&conn, dir.path(), false);
+
+        conn.execute(
+            "INSERT INTO transcript_utterances (id, document_id, text, source, is_final)
+             VALUES ('u-late', 'doc-2', 'Late arrival', 'microphone', 1)",
+            [],
+        )
+        .unwrap();
+        let summary = export(&conn, dir.path(), false);
+
+        assert_eq!(summary.written, vec!["2026-01-22 Weekly Standup.md"]);
+        assert_eq!(summary.unchanged, 1);
+        let note = fs::read_to_string(dir.path().join("2026-01-22 Weekly Standup.md")).unwrap();
+        assert!(note.contains("Late arrival"));
+    }
+
+    #[test]
+    fn force_rewrites_everything() {
+        let conn = build_test_db(&meetings_state());
+        let dir = TempDir::new().unwrap();
+        export(&conn, dir.path(), false);
+
+        let summary = export(&conn, dir.path(), true);
+
+        assert_eq!(summary.written.len(), 2);
+        assert_eq!(summary.unchanged, 0);
+    }
+
+    #[test]
+    fn missing_file_is_rewritten() {
+        let conn = build_test_db(&meetings_state());
+        let dir = TempDir::new().unwrap();
+        export(&conn, dir.path(), false);
+        fs::remove_file(dir.path().join("2026-01-22 Weekly Standup.md")).unwrap();
+
+        let summary = export(&conn, dir.path(), false);
+
+        assert_eq!(summary.written, vec!["2026-01-22 Weekly Standup.md"]);
+    }
+
+    #[test]
+    fn retitled_document_replaces_its_old_file() {
+        let conn = build_test_db(&meetings_state());
+        let dir = TempDir::new().unwrap();
+        export(&conn, dir.path(), false);
+
+        conn.execute(
+            "UPDATE documents SET title = 'Daily Standup', updated_at = '2026-02-01T00:00:00Z' WHERE id = 'doc-2'",
+            [],
+        )
+        .unwrap();
+        export(&conn, dir.path(), false);
+
+        assert!(dir.path().join("2026-01-22 Daily Standup.md").exists());
+        assert!(!dir.path().join("2026-01-22 Weekly Standup.md").exists());
+    }
+
+    #[test]
+    fn same_title_same_day_gets_disambiguated() {
+        let conn = build_test_db(&meetings_state());
+        conn.execute(
+            "UPDATE documents SET title = 'Weekly Standup', created_at = '2026-01-22T15:00:00Z' WHERE id = 'doc-1'",
+            [],
+        )
+        .unwrap();
+        let dir = TempDir::new().unwrap();
+
+        let summary = export(&conn, dir.path(), false);
+
+        assert_eq!(
+            summary.written,
+            vec![
+                "2026-01-22 Weekly Standup.md",
+                "2026-01-22 Weekly Standup (doc-1).md"
+            ]
+        );
+    }
+
+    #[test]
+    fn corrupt_manifest_is_an_error() {
+        let conn = build_test_db(&meetings_state());
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join(MANIFEST_FILE), "not json").unwrap();
+        let docs = crate::db::export::list_export_documents(&conn, None, false).unwrap();
+
+        let err = export_markdown(&conn, &docs, dir.path(), false, &utc()).unwrap_err();
+
+        assert!(err.to_string().contains(MANIFEST_FILE));
+    }
+}
@@ -12,13 +12,15 @@ use crate::commands::search_common::{print_shaped_cards, shape_and_page};
 use crate::models::Document;
 use crate::output::format::OutputMode;
 use crate::query::dates::DateRange;
-use crate::query::filter::{SearchTarget, filter_by_meeting};
+use crate::query::filter::{MeetingFilters, SearchTarget, allowed_meeting_ids};
+use crate::query::shape::ShapedMeeting;
 use crate::query::speaker::SpeakerFilter;
 
 /// Options for a grep lookup.
 pub struct GrepOptions {
     pub targets: Vec<SearchTarget>,
-    pub meeting_filter: Option<String>,
+    /// Meeting-level filters, applied before the total is counted.
+    pub filters: MeetingFilters,
     pub limit: usize,
     /// Match snippets shown per meeting card.
     pub matches: usize,
@@ -40,19 +42,37 @@ pub fn grep(
     include_deleted: bool,
     ctx: &RunContext,
 ) -> Result<()> {
+    let limit = opts.limit;
+    let (shaped, total) = grep_meetings(conn, query, opts, date_range.as_ref(), include_deleted)?;
+
+    render_grep_meeting_list(&shaped, query, total, limit, ctx);
+    Ok(())
+}
+
+/// The shaped page of meetings containing `query`'s words, and the complete
+/// count of matching meetings.
+pub fn grep_meetings(
+    conn: &Connection,
+    query: &str,
+    opts: GrepOptions,
+    date_range: Option<&DateRange>,
+    include_deleted: bool,
+) -> Result<(Vec<ShapedMeeting>, usize)> {
     check_speaker_targets(opts.speaker.is_some(), &opts.targets)?;
 
-    let results = fts_meetings(
-        conn,
-        query,
-        &opts.targets,
-        date_range.as_ref(),
-        include_deleted,
-    )?;
-    let results = filter_by_meeting(results, opts.meeting_filter.as_deref());
-    let docs: Vec<(Document, Option<f32>)> = results.into_iter().map(|doc| (doc, None)).collect();
+    let results = fts_meetings(conn, query, &opts.targets, date_range, include_deleted)?;
+    let allowed = allowed_meeting_ids(conn, &opts.filters)?;
+    let docs: Vec<(Document, Option<f32>)> = results
+        .into_iter()
+        .filter(|doc| {
+            allowed
+                .as_ref()
+                .is_none_or(|set| doc.id.as_ref().is_some_and(|id| set.contains(id)))
+        })
+        .map(|doc| (doc, None))
+        .collect();
 
-    let tokens = crate::query::fts::parse_query(query);
+    let parsed = crate::query::fts::parse_query(query)?;
     let evidence_opts = crate::query::evidence::EvidenceOptions {
         max_matches: opts.matches,
         speaker: opts.speaker,
@@ -67,13 +87,12 @@ pub fn grep(
             best_chunk: None,
             score: None,
         },
-        &tokens,
+        &parsed,
         &evidence_opts,
         opts.limit,
     )?;
 
-    render_grep_meeting_list(&shaped, query, total, opts.limit, ctx);
-    Ok(())
+    Ok((shaped, total))
 }
 
 /// Run FTS retrieval for `query` over the selected targets.
@@ -142,7 +161,7 @@ fn render_grep_meeting_list(
 /// `--speaker` restricts match evidence to transcript utterances, so the
 /// target list must include transcripts for the filter to have anything to
 /// match against.
-fn check_speaker_targets(speaker: bool, targets: &[SearchTarget]) -> Result<()> {
+pub(crate) fn check_speaker_targets(speaker: bool, targets: &[SearchTarget]) -> Result<()> {
     if speaker && !targets.contains(&SearchTarget::Transcripts) {
         bail!(
             "--speaker matches transcript utterances, but --in excludes transcripts; \
@@ -121,12 +121,7 @@ pub fn show(
 
             match ctx.output_mode {
                 OutputMode::Json => {
-                    let mut detail: serde_json::Value =
-                        serde_json::from_str(&crate::output::json::format_meeting_detail(&doc))
-                            .unwrap_or_default();
-                    if !panels.is_empty() {
-                        detail["panels"] = serde_json::json!(panels);
-                    }
+                    let detail = crate::output::json::meeting_detail_value(&doc, &panels);
                     println!("{}", serde_json::to_string_pretty(&detail).unwrap());
                 }
                 OutputMode::Tty => {
@@ -177,7 +172,7 @@ pub fn show(
     }
 }
 
-fn filter_by_speaker(
+pub(crate) fn filter_by_speaker(
     utterances: Vec<crate::models::TranscriptUtterance>,
     speaker: Option<&SpeakerFilter>,
 ) -> Vec<crate::models::TranscriptUtterance> {
@@ -212,12 +207,42 @@ pub fn with_person(
     ctx: &RunContext,
 ) -> Result<()> {
     let date_range = build_date_range(from, to, date, Utc::now(), &ctx.tz);
+    let matching_docs = meetings_with_person(conn, person, date_range.as_ref(), include_deleted)?;
+
+    let refs: Vec<_> = matching_docs.iter().collect();
+
+    match ctx.output_mode {
+        OutputMode::Json => {
+            println!("{}", crate::output::json::format_meetings(&refs));
+        }
+        OutputMode::Tty => {
+            if matching_docs.is_empty() {
+                println!("No meetings found with \"{}\".", person);
+                return Ok(());
+            }
+            println!("Meetings with \"{}\":\n", person);
+            for doc in &matching_docs {
+                println!("{}", crate::output::table::format_meeting_row(doc, &ctx.tz));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Meetings a person attended (name or email fragment), newest first,
+/// within `date_range` when given.
+pub fn meetings_with_person(
+    conn: &Connection,
+    person: &str,
+    date_range: Option<&crate::query::dates::DateRange>,
+    include_deleted: bool,
+) -> Result<Vec<crate::models::Document>> {
     let matching_docs = crate::db::people::find_meetings_by_person(conn, person, include_deleted)?;
 
     // Apply date filter if specified
-    let matching_docs = if date_range.is_some() {
-        let range = date_range.as_ref().unwrap();
-        matching_docs
+    Ok(match date_range {
+        Some(range) => matching_docs
             .into_iter()
             .filter(|doc| {
                 if let Some(created_at) = &doc.created_at {
@@ -238,28 +263,7 @@ pub fn with_person(
                 }
                 false
             })
-            .collect()
-    } else {
-        matching_docs
-    };
-
-    let refs: Vec<_> = matching_docs.iter().collect();
-
-    match ctx.output_mode {
-        OutputMode::Json => {
-            println!("{}", crate::output::json::format_meetings(&refs));
-        }
-        OutputMode::Tty => {
-            if matching_docs.is_empty() {
-                println!("No meetings found with \"{}\".", person);
-                return Ok(());
-            }
-            println!("Meetings with \"{}\":\n", person);
-            for doc in &matching_docs {
-                println!("{}", crate::output::table::format_meeting_row(doc, &ctx.tz));
-            }
-        }
-    }
-
-    Ok(())
+            .collect(),
+        None => matching_docs,
+    })
 }
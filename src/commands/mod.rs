@@ -1,22 +1,34 @@
 mod account_record;
+pub mod actions;
+pub mod aliases;
 pub mod auth;
 pub mod benchmark;
 pub mod browse;
 pub mod calendars;
 pub mod db;
 pub mod embed;
+pub mod export;
 pub mod grep;
 pub mod info;
 pub mod meetings;
+pub mod models;
 pub mod people;
 pub mod recipes;
+pub mod saved;
 pub mod search;
 pub mod search_common;
+pub mod similar;
+pub mod stats;
 pub mod sync;
+mod sync_backfill;
+mod sync_documents;
+mod sync_encryption;
 pub mod sync_granola;
 mod sync_panels;
 pub mod sync_pipeline;
+mod sync_snapshots;
 mod sync_status;
 mod sync_transcripts;
 pub mod templates;
+pub mod topics;
 pub mod update;
@@ -0,0 +1,90 @@
+//! `grans models` — side-load models for offline installs.
+
+use anyhow::Result;
+
+use crate::cli::args::ModelsAction;
+use crate::embed::offline::{self, Manifest, ModelSource};
+use crate::embed::rerank::DEFAULT_RERANK_MODEL;
+use crate::output::format::OutputMode;
+
+/// Run `grans models` subcommands.
+pub fn run(action: &ModelsAction, mode: OutputMode) -> Result<()> {
+    match action {
+        ModelsAction::Import { dir } => {
+            let (root, _) = offline::model_root()?;
+            let imported = offline::import(dir, &root)?;
+            report("import", &imported, &root, mode);
+        }
+        ModelsAction::Export { dir, models } => {
+            let sources = if models.is_empty() {
+                vec![
+                    ModelSource::embedding(crate::embed::model::default_model())?,
+                    ModelSource::reranker(DEFAULT_RERANK_MODEL),
+                ]
+            } else {
+                models
+                    .iter()
+                    .map(|name| ModelSource::find(name))
+                    .collect::<Result<_>>()?
+            };
+            let mut exported = Vec::with_capacity(sources.len());
+            for source in &sources {
+                eprintln!("[grans] Exporting {} from {}...", source.name, source.repo);
+                exported.push(offline::export(source, dir)?);
+            }
+            report("export", &exported, dir, mode);
+        }
+    }
+    Ok(())
+}
+
+fn report(action: &str, manifests: &[Manifest], dir: &std::path::Path, mode: OutputMode) {
+    match mode {
+        OutputMode::Json => {
+            let models: Vec<_> = manifests
+                .iter()
+                .map(|m| {
+                    serde_json::json!({
+                        "model": m.model,
+                        "bytes": m.files.iter().map(|f| f.size).sum::<u64>(),
+                        "files": m.files,
+                    })
+                })
+                .collect();
+            println!(
+                "{}",
+                serde_json::json!({
+                    "action": action,
+                    "dir": dir.display().to_string(),
+                    "models": models,
+                })
+            );
+        }
+        OutputMode::Tty => {
+            let (verb, checked) = if action == "import" {
+                ("Imported", "verified")
+            } else {
+                ("Exported", "checksummed")
+            };
+            for m in manifests {
+                let bytes: u64 = m.files.iter().map(|f| f.size).sum();
+                println!(
+                    "{} {} ({:.1} MB, {} files {})",
+                    verb,
+                    m.model,
+                    bytes as f64 / 1_000_000.0,
+                    m.files.len(),
+                    checked
+                );
+            }
+            println!("Model directory: {}", dir.display());
+            if action == "export" {
+                println!(
+                    "Copy it to the offline machine and run `grans models import <dir>` there, \
+                     or point {} at it.",
+                    offline::MODEL_DIR_ENV
+                );
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::{Result, bail};
 use rusqlite::Connection;
 
+use crate::cli::context::RunContext;
 use crate::output::format::OutputMode;
 
 pub fn list(conn: &Connection, company: Option<&str>, mode: OutputMode) -> Result<()> {
@@ -55,3 +56,22 @@ pub fn show(conn: &Connection, query: &str, mode: OutputMode) -> Result<()> {
 
     Ok(())
 }
+
+pub fn profile(conn: &Connection, query: &str, utterances: usize, ctx: &RunContext) -> Result<()> {
+    let identity = crate::db::profile::resolve_identity(conn, query)?;
+    let profile = crate::db::profile::person_profile(conn, &identity, utterances, &ctx.tz)?;
+
+    match ctx.output_mode {
+        OutputMode::Json => {
+            println!("{}", crate::output::json::to_json(&profile));
+        }
+        OutputMode::Tty => {
+            print!(
+                "{}",
+                crate::output::table::format_person_profile(&profile, &ctx.tz)
+            );
+        }
+    }
+
+    Ok(())
+}
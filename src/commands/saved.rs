@@ -0,0 +1,672 @@
+//! `grans saved`: named grep/search queries and new-match alerts.
+//!
+//! A saved search stores the command's full option set as JSON next to its
+//! query. `check` re-runs each search only when a sync has landed since its
+//! last check (the same watermark `grans embed` uses to judge staleness) and
+//! reports the meetings it has not reported before, so a notification
+//! script sees each meeting once.
+
+use std::collections::HashSet;
+
+use anyhow::{Context, Result, bail};
+use clap::Parser;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::args::{Cli, Commands, SavedAction};
+use crate::cli::context::RunContext;
+use crate::commands::grep::GrepOptions;
+use crate::commands::search::{FilterEcho, SearchModels, SearchOptions};
+use crate::commands::search_common::print_shaped_cards;
+use crate::db::saved::SavedSearchRecord;
+use crate::embed::EmbeddingIndex;
+use crate::output::format::OutputMode;
+use crate::output::json::{SavedCheckJson, to_json};
+use crate::query::dates::{DateRange, build_date_range};
+use crate::query::filter::{MeetingFilters, SearchTarget};
+use crate::query::shape::ShapedMeeting;
+use crate::query::speaker::SpeakerSelector;
+
+/// Everything but the query that a saved command was given. Dates are kept
+/// as typed, so "last-week" means the week before each run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedOptions {
+    pub targets: Vec<SearchTarget>,
+    #[serde(default)]
+    pub filters: MeetingFilters,
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Option<String>,
+    #[serde(default)]
+    pub date: Option<String>,
+    #[serde(default)]
+    pub include_deleted: bool,
+    pub limit: usize,
+    pub matches: usize,
+    #[serde(default)]
+    pub context: usize,
+    /// Grep only.
+    #[serde(default)]
+    pub speaker: Option<SpeakerSelector>,
+    /// Search only: keep fusion order instead of reranking.
+    #[serde(default)]
+    pub fast: bool,
+    /// Search only.
+    #[serde(default)]
+    pub min_score: Option<f32>,
+}
+
+/// A parsed `saved add` command line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedCommand {
+    /// `grep` or `search`.
+    pub verb: &'static str,
+    pub query: String,
+    pub options: SavedOptions,
+}
+
+/// Parse the words after `grans saved add NAME` with the real CLI parser,
+/// so a saved command accepts exactly the flags the live one does.
+pub fn parse_saved_command(args: &[String]) -> Result<SavedCommand> {
+    let cli = Cli::try_parse_from(std::iter::once("grans".to_string()).chain(args.iter().cloned()))
+        .map_err(|e| anyhow::anyhow!("invalid saved command: {}", e.render().to_string().trim()))?;
+
+    match cli.command {
+        Commands::Grep {
+            query,
+            r#in,
+            matches,
+            context,
+            meeting,
+            with,
+            company,
+            account,
+            series,
+            min_attendees,
+            from,
+            to,
+            date,
+            speaker,
+            limit,
+            include_deleted,
+        } => Ok(SavedCommand {
+            verb: "grep",
+            query,
+            options: SavedOptions {
+                targets: r#in,
+                filters: MeetingFilters {
+                    meeting,
+                    with,
+                    company,
+                    account,
+                    series,
+                    min_attendees,
+                },
+                from,
+                to,
+                date,
+                include_deleted,
+                limit,
+                matches,
+                context,
+                speaker,
+                fast: false,
+                min_score: None,
+            },
+        }),
+        Commands::Search {
+            query,
+            r#in,
+            fast,
+            min_score,
+            matches,
+            context,
+            meeting,
+            with,
+            company,
+            account,
+            series,
+            min_attendees,
+            from,
+            to,
+            date,
+            limit,
+            include_deleted,
+        } => Ok(SavedCommand {
+            verb: "search",
+            query,
+            options: SavedOptions {
+                targets: r#in,
+                filters: MeetingFilters {
+                    meeting,
+                    with,
+                    company,
+                    account,
+                    series,
+                    min_attendees,
+                },
+                from,
+                to,
+                date,
+                include_deleted,
+                limit,
+                matches,
+                context,
+                speaker: None,
+                fast,
+                min_score,
+            },
+        }),
+        _ => bail!("only `grep` and `search` commands can be saved"),
+    }
+}
+
+impl SavedOptions {
+    fn date_range(&self, ctx: &RunContext) -> Option<DateRange> {
+        build_date_range(
+            self.from.as_deref(),
+            self.to.as_deref(),
+            self.date.as_deref(),
+            chrono::Utc::now(),
+            &ctx.tz,
+        )
+    }
+
+    fn grep_options(&self, conn: &Connection, limit: usize) -> Result<GrepOptions> {
+        Ok(GrepOptions {
+            targets: self.targets.clone(),
+            filters: self.filters.clone(),
+            limit,
+            matches: self.matches,
+            speaker: crate::query::speaker::resolve_opt(conn, self.speaker.as_ref())?,
+            context: self.context,
+        })
+    }
+
+    fn search_options(&self) -> SearchOptions {
+        let echo = FilterEcho {
+            in_targets: self.targets.clone(),
+            meeting: self.filters.meeting.clone(),
+            with: self.filters.with.clone(),
+            company: self.filters.company.clone(),
+            account: self.filters.account.clone(),
+            series: self.filters.series.clone(),
+            min_attendees: self.filters.min_attendees,
+            date: self.date.clone(),
+            from: self.from.clone(),
+            to: self.to.clone(),
+            include_deleted: self.include_deleted,
+        };
+        SearchOptions::from_cli_args(
+            self.fast,
+            self.min_score,
+            self.context,
+            self.limit,
+            self.matches,
+            echo,
+        )
+    }
+}
+
+fn load_options(record: &SavedSearchRecord) -> Result<SavedOptions> {
+    serde_json::from_str(&record.options_json)
+        .with_context(|| format!("saved search \"{}\" has unreadable options", record.name))
+}
+
+fn require(conn: &Connection, name: &str) -> Result<SavedSearchRecord> {
+    match crate::db::saved::get_saved_search(conn, name)? {
+        Some(record) => Ok(record),
+        None => bail!("No saved search named \"{}\"; see `grans saved list`", name),
+    }
+}
+
+pub fn run(conn: &Connection, action: &SavedAction, ctx: &RunContext) -> Result<()> {
+    match action {
+        SavedAction::Add { name, command } => add(conn, name, command, ctx),
+        SavedAction::List => list(conn, ctx),
+        SavedAction::Run { name } => {
+            let record = require(conn, name)?;
+            let options = load_options(&record)?;
+            let date_range = options.date_range(ctx);
+            if record.verb == "grep" {
+                let opts = options.grep_options(conn, options.limit)?;
+                crate::commands::grep::grep(
+                    conn,
+                    &record.query,
+                    opts,
+                    date_range,
+                    options.include_deleted,
+                    ctx,
+                )
+            } else {
+                crate::commands::search::search(
+                    conn,
+                    &record.query,
+                    options.search_options(),
+                    date_range,
+                    options.include_deleted,
+                    ctx,
+                )
+            }
+        }
+        SavedAction::Rm { name } => {
+            if !crate::db::saved::delete_saved_search(conn, name)? {
+                bail!("No saved search named \"{}\"; see `grans saved list`", name);
+            }
+            match ctx.output_mode {
+                OutputMode::Json => println!("{}", to_json(&serde_json::json!({"removed": name}))),
+                OutputMode::Tty => println!("Removed saved search \"{}\".", name),
+            }
+            Ok(())
+        }
+        SavedAction::Check { names } => check(conn, names, ctx),
+    }
+}
+
+/// Save the command and record what it matches now as the baseline, so the
+/// first check reports only meetings that match afterwards.
+fn add(conn: &Connection, name: &str, command: &[String], ctx: &RunContext) -> Result<()> {
+    let parsed = parse_saved_command(command)?;
+    // Parse the query now so a typo fails at save time, not at every check.
+    crate::query::fts::parse_query(&parsed.query)?;
+    let options_json = serde_json::to_string(&parsed.options)?;
+    crate::db::saved::insert_saved_search(conn, name, parsed.verb, &parsed.query, &options_json)?;
+
+    let record = require(conn, name)?;
+    let watermark = crate::embed::freshness::current_sync_watermark(conn)?;
+    let mut models = LazyModels::default();
+    let current = matching_meetings(conn, &record, &parsed.options, &mut models, ctx)?;
+    let ids: Vec<String> = current.iter().map(|m| m.document_id.clone()).collect();
+    crate::db::saved::record_check(conn, name, watermark.as_deref(), &ids)?;
+
+    match ctx.output_mode {
+        OutputMode::Json => println!(
+            "{}",
+            to_json(&serde_json::json!({
+                "name": name,
+                "verb": parsed.verb,
+                "query": parsed.query,
+                "baseline": ids.len(),
+            }))
+        ),
+        OutputMode::Tty => println!(
+            "Saved {} \"{}\" as \"{}\" ({} meeting(s) match now; `grans saved check` reports new ones).",
+            parsed.verb,
+            parsed.query,
+            name,
+            ids.len()
+        ),
+    }
+    Ok(())
+}
+
+fn list(conn: &Connection, ctx: &RunContext) -> Result<()> {
+    let records = crate::db::saved::list_saved_searches(conn)?;
+    match ctx.output_mode {
+        OutputMode::Json => {
+            let entries = records
+                .iter()
+                .map(|r| {
+                    Ok(serde_json::json!({
+                        "name": r.name,
+                        "verb": r.verb,
+                        "query": r.query,
+                        "options": serde_json::from_str::<serde_json::Value>(&r.options_json)?,
+                        "created_at": r.created_at,
+                        "checked_at": r.checked_at,
+                    }))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            println!("{}", to_json(&entries));
+        }
+        OutputMode::Tty => {
+            if records.is_empty() {
+                println!("No saved searches. Add one with `grans saved add NAME grep \"...\"`.");
+                return Ok(());
+            }
+            for record in &records {
+                println!(
+                    "{}",
+                    crate::output::table::format_saved_search_row(record, &ctx.tz)
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The embedding index, embedder and reranker, loaded on first use and
+/// shared by every ranked search in one `check`.
+#[derive(Default)]
+struct LazyModels {
+    loaded: Option<LoadedModels>,
+    reranker: Option<crate::embed::rerank::FastEmbedReranker>,
+}
+
+struct LoadedModels {
+    index: EmbeddingIndex,
+    embedder: Option<crate::embed::model::FastEmbedModel>,
+}
+
+impl LazyModels {
+    fn get(&mut self, conn: &Connection, rerank: bool) -> Result<SearchModels<'_>> {
+        if self.loaded.is_none() {
+            let model = crate::embed::model::stored_or_default(conn);
+            let (index, _) = crate::embed::freshness::load_search_index(conn, model.name)?;
+            let embedder = (!index.is_empty())
+                .then(|| crate::embed::model::FastEmbedModel::new(model))
+                .transpose()?;
+            self.loaded = Some(LoadedModels { index, embedder });
+        }
+        if rerank && self.reranker.is_none() {
+            self.reranker = Some(crate::embed::rerank::FastEmbedReranker::new(
+                crate::embed::rerank::DEFAULT_RERANK_MODEL,
+            )?);
+        }
+        let loaded = self.loaded.as_ref().expect("loaded above");
+        Ok(SearchModels {
+            embedder: loaded
+                .embedder
+                .as_ref()
+                .map(|e| e as &dyn crate::embed::model::Embedder),
+            index: &loaded.index,
+            reranker: if rerank {
+                self.reranker
+                    .as_ref()
+                    .map(|r| r as &dyn crate::embed::rerank::Reranker)
+            } else {
+                None
+            },
+        })
+    }
+}
+
+/// The meetings a saved search matches now. Grep takes every match, so a
+/// new match deep in the list still counts; search takes its top results.
+fn matching_meetings(
+    conn: &Connection,
+    record: &SavedSearchRecord,
+    options: &SavedOptions,
+    models: &mut LazyModels,
+    ctx: &RunContext,
+) -> Result<Vec<ShapedMeeting>> {
+    let date_range = options.date_range(ctx);
+    if record.verb == "grep" {
+        let (shaped, _) = crate::commands::grep::grep_meetings(
+            conn,
+            &record.query,
+            options.grep_options(conn, 0)?,
+            date_range.as_ref(),
+            options.include_deleted,
+        )?;
+        Ok(shaped)
+    } else {
+        let opts = options.search_options();
+        let models = models.get(conn, opts.rerank)?;
+        let (shaped, _) = crate::commands::search::ranked_meetings(
+            conn,
+            &models,
+            &record.query,
+            &opts,
+            date_range.as_ref(),
+            options.include_deleted,
+        )?;
+        Ok(shaped)
+    }
+}
+
+/// One saved search's check outcome.
+struct CheckOutcome {
+    record: SavedSearchRecord,
+    /// False when nothing synced since the last check.
+    rerun: bool,
+    new_meetings: Vec<ShapedMeeting>,
+}
+
+/// Check each named saved search (all when `names` is empty) for meetings
+/// it has not reported before.
+fn check_searches(
+    conn: &Connection,
+    names: &[String],
+    watermark: Option<&str>,
+    models: &mut LazyModels,
+    ctx: &RunContext,
+) -> Result<Vec<CheckOutcome>> {
+    let records = if names.is_empty() {
+        crate::db::saved::list_saved_searches(conn)?
+    } else {
+        names
+            .iter()
+            .map(|name| require(conn, name))
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let mut outcomes = Vec::new();
+    for record in records {
+        if record.checked_at.is_some() && record.checked_watermark.as_deref() == watermark {
+            outcomes.push(CheckOutcome {
+                record,
+                rerun: false,
+                new_meetings: Vec::new(),
+            });
+            continue;
+        }
+        let options = load_options(&record)?;
+        let seen: HashSet<String> = crate::db::saved::seen_meeting_ids(conn, &record.name)?;
+        let new_meetings: Vec<ShapedMeeting> =
+            matching_meetings(conn, &record, &options, models, ctx)?
+                .into_iter()
+                .filter(|m| !seen.contains(&m.document_id))
+                .collect();
+        let new_ids: Vec<String> = new_meetings.iter().map(|m| m.document_id.clone()).collect();
+        crate::db::saved::record_check(conn, &record.name, watermark, &new_ids)?;
+        outcomes.push(CheckOutcome {
+            record,
+            rerun: true,
+            new_meetings,
+        });
+    }
+    Ok(outcomes)
+}
+
+fn check(conn: &Connection, names: &[String], ctx: &RunContext) -> Result<()> {
+    let watermark = crate::embed::freshness::current_sync_watermark(conn)?;
+    let mut models = LazyModels::default();
+    let outcomes = check_searches(conn, names, watermark.as_deref(), &mut models, ctx)?;
+
+    match ctx.output_mode {
+        OutputMode::Json => {
+            let searches: Vec<SavedCheckJson> = outcomes
+                .iter()
+                .map(|o| {
+                    SavedCheckJson::new(
+                        &o.record.name,
+                        &o.record.verb,
+                        &o.record.query,
+                        o.rerun,
+                        &o.new_meetings,
+                    )
+                })
+                .collect();
+            println!(
+                "{}",
+                to_json(&serde_json::json!({
+                    "watermark": watermark,
+                    "searches": searches,
+                }))
+            );
+        }
+        OutputMode::Tty => {
+            if outcomes.is_empty() {
+                println!("No saved searches. Add one with `grans saved add NAME grep \"...\"`.");
+                return Ok(());
+            }
+            let mut any = false;
+            for outcome in outcomes.iter().filter(|o| !o.new_meetings.is_empty()) {
+                any = true;
+                println!(
+                    "{}: {} new meeting(s) matching \"{}\":\n",
+                    outcome.record.name,
+                    outcome.new_meetings.len(),
+                    outcome.record.query
+                );
+                print_shaped_cards(&outcome.new_meetings, ctx);
+            }
+            if !any {
+                println!("No new matches since the last check.");
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_fixtures::build_test_db;
+    use serde_json::json;
+
+    fn args(line: &[&str]) -> Vec<String> {
+        line.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn ctx() -> RunContext {
+        RunContext {
+            output_mode: OutputMode::Json,
+            tz: chrono::FixedOffset::east_opt(0).unwrap(),
+        }
+    }
+
+    fn set_watermark(conn: &Connection, stamp: &str) {
+        conn.execute(
+            "INSERT OR REPLACE INTO metadata (key, value) VALUES ('last_sync_documents', ?1)",
+            [stamp],
+        )
+        .unwrap();
+    }
+
+    fn add_doc(conn: &Connection, id: &str, title: &str, notes: &str) {
+        conn.execute(
+            "INSERT INTO documents (id, title, created_at, notes_plain) VALUES (?1, ?2, '2026-01-10T10:00:00Z', ?3)",
+            rusqlite::params![id, title, notes],
+        )
+        .unwrap();
+        conn.execute("INSERT INTO notes_fts(notes_fts) VALUES('rebuild')", [])
+            .unwrap();
+    }
+
+    #[test]
+    fn grep_command_keeps_every_flag() {
+        let parsed = parse_saved_command(&args(&[
+            "grep",
+            "security incident",
+            "--in",
+            "transcripts",
+            "--with",
+            "alice",
+            "--speaker",
+            "me",
+            "--date",
+            "last-week",
+        ]))
+        .unwrap();
+        assert_eq!(parsed.verb, "grep");
+        assert_eq!(parsed.query, "security incident");
+        assert_eq!(parsed.options.targets, vec![SearchTarget::Transcripts]);
+        assert_eq!(parsed.options.filters.with.as_deref(), Some("alice"));
+        assert_eq!(parsed.options.speaker, Some(SpeakerSelector::Me));
+        assert_eq!(parsed.options.date.as_deref(), Some("last-week"));
+
+        let json = serde_json::to_string(&parsed.options).unwrap();
+        let back: SavedOptions = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, parsed.options);
+    }
+
+    #[test]
+    fn search_command_keeps_ranking_flags() {
+        let parsed =
+            parse_saved_command(&args(&["search", "pricing", "--fast", "--limit", "3"])).unwrap();
+        assert_eq!(parsed.verb, "search");
+        assert!(parsed.options.fast);
+        assert_eq!(parsed.options.limit, 3);
+    }
+
+    #[test]
+    fn other_commands_are_rejected() {
+        let err = parse_saved_command(&args(&["list"])).unwrap_err();
+        assert!(err.to_string().contains("grep"));
+        assert!(parse_saved_command(&args(&["grep", "x", "--bogus"])).is_err());
+    }
+
+    #[test]
+    fn check_reports_only_meetings_new_since_the_last_sync() {
+        let conn = build_test_db(&json!({"documents": {}}));
+        add_doc(
+            &conn,
+            "d1",
+            "Incident review",
+            "security incident follow-up",
+        );
+        set_watermark(&conn, "2026-01-10T12:00:00Z");
+
+        let parsed = parse_saved_command(&args(&["grep", "incident"])).unwrap();
+        let options_json = serde_json::to_string(&parsed.options).unwrap();
+        crate::db::saved::insert_saved_search(&conn, "inc", "grep", "incident", &options_json)
+            .unwrap();
+        // Baseline: d1 matched when the search was saved.
+        crate::db::saved::record_check(&conn, "inc", Some("2026-01-10T12:00:00Z"), &["d1".into()])
+            .unwrap();
+
+        let mut models = LazyModels::default();
+        // Nothing synced since: the search is not re-run.
+        let outcomes = check_searches(
+            &conn,
+            &[],
+            Some("2026-01-10T12:00:00Z"),
+            &mut models,
+            &ctx(),
+        )
+        .unwrap();
+        assert!(!outcomes[0].rerun);
+
+        add_doc(&conn, "d2", "Postmortem", "another incident");
+        let outcomes = check_searches(
+            &conn,
+            &[],
+            Some("2026-01-11T12:00:00Z"),
+            &mut models,
+            &ctx(),
+        )
+        .unwrap();
+        assert!(outcomes[0].rerun);
+        let ids: Vec<&str> = outcomes[0]
+            .new_meetings
+            .iter()
+            .map(|m| m.document_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["d2"]);
+
+        // Reported once: a later sync with no new matches reports nothing.
+        let outcomes = check_searches(
+            &conn,
+            &[],
+            Some("2026-01-12T12:00:00Z"),
+            &mut models,
+            &ctx(),
+        )
+        .unwrap();
+        assert!(outcomes[0].rerun);
+        assert!(outcomes[0].new_meetings.is_empty());
+    }
+
+    #[test]
+    fn check_of_unknown_name_is_an_error() {
+        let conn = build_test_db(&json!({"documents": {}}));
+        let mut models = LazyModels::default();
+        let err = check_searches(&conn, &args(&["nope"]), None, &mut models, &ctx())
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("No saved search"));
+    }
+}
@@ -15,21 +15,31 @@ use rusqlite::Connection;
 
 use crate::cli::context::RunContext;
 use crate::commands::search_common::{print_shaped_cards, shape_and_page};
+use crate::embed::EmbeddingIndex;
 use crate::embed::freshness::IndexFreshness;
 use crate::models::Document;
 use crate::output::format::OutputMode;
 use crate::query::dates::DateRange;
-use crate::query::filter::{DEFAULT_SEARCH_TARGETS, SearchTarget, targets_to_flag_value};
+use crate::query::filter::{
+    DEFAULT_SEARCH_TARGETS, MeetingFilters, SearchTarget, targets_to_flag_value,
+};
+use crate::query::hybrid::HybridRanking;
+use crate::query::shape::ShapedMeeting;
 
 /// Filter values that affect the match count, kept so the grep cross-link
-/// can reproduce that count. Dates and the meeting filter are echoed as the
-/// user typed them; `in_targets` is the parsed target list, re-joined into
-/// the flag when the suggested command is built. Only filters that change
-/// the match count belong here.
+/// can reproduce that count. Dates and the meeting-level filters are echoed
+/// as the user typed them; `in_targets` is the parsed target list, re-joined
+/// into the flag when the suggested command is built. Only filters that
+/// change the match count belong here.
 pub struct FilterEcho {
     /// Parsed `--in` targets.
     pub in_targets: Vec<SearchTarget>,
     pub meeting: Option<String>,
+    pub with: Option<String>,
+    pub company: Option<String>,
+    pub account: Option<String>,
+    pub series: Option<String>,
+    pub min_attendees: Option<usize>,
     pub date: Option<String>,
     pub from: Option<String>,
     pub to: Option<String>,
@@ -41,6 +51,11 @@ impl Default for FilterEcho {
         FilterEcho {
             in_targets: SearchTarget::all(),
             meeting: None,
+            with: None,
+            company: None,
+            account: None,
+            series: None,
+            min_attendees: None,
             date: None,
             from: None,
             to: None,
@@ -49,10 +64,25 @@ impl Default for FilterEcho {
     }
 }
 
+impl FilterEcho {
+    /// The meeting-level filters these raw values select.
+    pub fn meeting_filters(&self) -> MeetingFilters {
+        MeetingFilters {
+            meeting: self.meeting.clone(),
+            with: self.with.clone(),
+            company: self.company.clone(),
+            account: self.account.clone(),
+            series: self.series.clone(),
+            min_attendees: self.min_attendees,
+        }
+    }
+}
+
 /// Options for a ranked search.
 pub struct SearchOptions {
     pub targets: Vec<SearchTarget>,
-    pub meeting_filter: Option<String>,
+    /// Meeting-level filters, applied before fusion.
+    pub filters: MeetingFilters,
     pub rerank: bool,
     pub min_score: Option<f32>,
     pub limit: usize,
@@ -66,8 +96,8 @@ pub struct SearchOptions {
 
 impl SearchOptions {
     /// Construct SearchOptions from CLI arguments. A bare search reranks;
-    /// --fast keeps fusion order. Targets and the meeting filter derive
-    /// from the raw values in `echo`.
+    /// --fast keeps fusion order. Targets and the meeting-level filters
+    /// derive from the raw values in `echo`.
     pub fn from_cli_args(
         fast: bool,
         min_score: Option<f32>,
@@ -78,7 +108,7 @@ impl SearchOptions {
     ) -> Self {
         SearchOptions {
             targets: echo.in_targets.clone(),
-            meeting_filter: echo.meeting.clone(),
+            filters: echo.meeting_filters(),
             rerank: !fast,
             min_score,
             limit,
@@ -100,8 +130,8 @@ pub fn search(
     include_deleted: bool,
     ctx: &RunContext,
 ) -> Result<()> {
-    let (index, freshness) =
-        crate::embed::freshness::load_search_index(conn, crate::embed::model::MODEL_NAME)?;
+    let model = crate::embed::model::stored_or_default(conn);
+    let (index, freshness) = crate::embed::freshness::load_search_index(conn, model.name)?;
     if let Some(warning) = freshness_warning(&freshness) {
         eprintln!("[grans] {}", warning);
     }
@@ -110,7 +140,7 @@ pub fn search(
     // empty or model-mismatched index yields keyword-only results without
     // paying embedder init (or the one-time model download).
     let embedder = (!index.is_empty())
-        .then(crate::embed::model::FastEmbedModel::new)
+        .then(|| crate::embed::model::FastEmbedModel::new(model))
         .transpose()?;
 
     // The reranker's model load needs nothing retrieval produces, so start
@@ -135,7 +165,7 @@ pub fn search(
         &index,
         query,
         &opts.targets,
-        opts.meeting_filter.as_deref(),
+        &opts.filters,
         date_range.as_ref(),
         include_deleted,
     )?;
@@ -146,9 +176,60 @@ pub fn search(
     let reranker = reranker
         .as_ref()
         .map(|r| r as &dyn crate::embed::rerank::Reranker);
+    let shaped = order_and_shape(conn, query, &ranking, reranker, &opts)?;
+
+    render_ranked_meeting_list(&shaped, query, ranking.keyword_total, &opts, ctx);
+    Ok(())
+}
+
+/// The loaded pieces a ranked search runs on. `grans search` loads them per
+/// invocation, overlapping the reranker load with retrieval; `grans serve`
+/// keeps them warm across requests.
+pub struct SearchModels<'a> {
+    /// Embeds the query; None searches keyword-only.
+    pub embedder: Option<&'a dyn crate::embed::model::Embedder>,
+    pub index: &'a EmbeddingIndex,
+    /// Reorders the fused candidates; None keeps fusion order.
+    pub reranker: Option<&'a dyn crate::embed::rerank::Reranker>,
+}
+
+/// The whole ranked pipeline with every model already loaded: the shaped
+/// meeting cards for `query` and the uncapped count of meetings containing
+/// its words.
+pub fn ranked_meetings(
+    conn: &Connection,
+    models: &SearchModels,
+    query: &str,
+    opts: &SearchOptions,
+    date_range: Option<&DateRange>,
+    include_deleted: bool,
+) -> Result<(Vec<ShapedMeeting>, usize)> {
+    let ranking = crate::query::hybrid::hybrid_ranked(
+        conn,
+        models.embedder,
+        models.index,
+        query,
+        &opts.targets,
+        &opts.filters,
+        date_range,
+        include_deleted,
+    )?;
+    let shaped = order_and_shape(conn, query, &ranking, models.reranker, opts)?;
+    Ok((shaped, ranking.keyword_total))
+}
+
+/// Rerank the fused candidates (when a reranker is given) and shape the
+/// display page into meeting cards with match evidence.
+fn order_and_shape(
+    conn: &Connection,
+    query: &str,
+    ranking: &HybridRanking,
+    reranker: Option<&dyn crate::embed::rerank::Reranker>,
+    opts: &SearchOptions,
+) -> Result<Vec<ShapedMeeting>> {
     // `ordered` is the pipeline's final order; nothing below re-sorts it.
     let ordered =
-        crate::query::rerank::order_candidates(conn, query, &ranking, reranker, opts.min_score)?;
+        crate::query::rerank::order_candidates(conn, query, ranking, reranker, opts.min_score)?;
 
     let ids: Vec<String> = ordered.iter().map(|(id, _)| id.clone()).collect();
     let docs = crate::db::meetings::get_meetings_by_ids(conn, &ids)?;
@@ -161,7 +242,7 @@ pub fn search(
         .filter_map(|(id, score)| doc_by_id.remove(&id).map(|doc| (doc, score)))
         .collect();
 
-    let tokens = crate::query::fts::parse_query(query);
+    let parsed = crate::query::fts::parse_query(query)?;
     let evidence_opts = crate::query::evidence::EvidenceOptions {
         max_matches: opts.matches,
         context: opts.context,
@@ -178,13 +259,12 @@ pub fn search(
                 score,
             }
         },
-        &tokens,
+        &parsed,
         &evidence_opts,
         opts.limit,
     )?;
 
-    render_ranked_meeting_list(&shaped, query, ranking.keyword_total, &opts, ctx);
-    Ok(())
+    Ok(shaped)
 }
 
 /// Header for ranked results: claims only what is shown, never a total.
@@ -206,6 +286,21 @@ fn grep_command_echo(query: &str, filters: &FilterEcho) -> String {
     if let Some(meeting) = &filters.meeting {
         cmd.push_str(&format!(" --meeting \"{}\"", meeting));
     }
+    if let Some(with) = &filters.with {
+        cmd.push_str(&format!(" --with \"{}\"", with));
+    }
+    if let Some(company) = &filters.company {
+        cmd.push_str(&format!(" --company \"{}\"", company));
+    }
+    if let Some(account) = &filters.account {
+        cmd.push_str(&format!(" --account {}", account));
+    }
+    if let Some(series) = &filters.series {
+        cmd.push_str(&format!(" --series \"{}\"", series));
+    }
+    if let Some(min) = filters.min_attendees {
+        cmd.push_str(&format!(" --min-attendees {}", min));
+    }
     if let Some(date) = &filters.date {
         cmd.push_str(&format!(" --date {}", date));
     }
@@ -274,7 +369,7 @@ fn render_ranked_meeting_list(
 /// The stderr warning for an index that cannot cover everything, or None
 /// when it is fresh. Printed in every output mode; stderr keeps JSON
 /// stdout clean.
-fn freshness_warning(freshness: &IndexFreshness) -> Option<String> {
+pub(crate) fn freshness_warning(freshness: &IndexFreshness) -> Option<String> {
     match freshness {
         IndexFreshness::Fresh => None,
         IndexFreshness::Stale => Some(
@@ -323,7 +418,7 @@ mod tests {
         assert_eq!(opts.targets.len(), 2);
         assert!(opts.targets.contains(&SearchTarget::Titles));
         assert!(opts.targets.contains(&SearchTarget::Notes));
-        assert!(opts.meeting_filter.is_none());
+        assert!(opts.filters.is_empty());
         assert!(opts.rerank);
         assert_eq!(opts.min_score, None);
         assert_eq!(opts.limit, 10);
@@ -353,7 +448,7 @@ mod tests {
             1,
             echo_with(|e| e.meeting = Some("daily".to_string())),
         );
-        assert_eq!(opts.meeting_filter.as_deref(), Some("daily"));
+        assert_eq!(opts.filters.meeting.as_deref(), Some("daily"));
     }
 
     #[test]
@@ -481,6 +576,11 @@ mod tests {
         let echo = FilterEcho {
             in_targets: vec![SearchTarget::Transcripts],
             meeting: Some("Weekly Standup".to_string()),
+            with: Some("alice".to_string()),
+            company: Some("Acme".to_string()),
+            account: Some("me@example.com".to_string()),
+            series: Some("weekly standup".to_string()),
+            min_attendees: Some(3),
             date: Some("last-week".to_string()),
             from: None,
             to: None,
@@ -490,8 +590,22 @@ mod tests {
             grep_cross_link(41, "budget", &echo).as_deref(),
             Some(
                 "41 meeting(s) contain these words; grans grep \"budget\" --in transcripts \
-                 --meeting \"Weekly Standup\" --date last-week --include-deleted lists them all."
+                 --meeting \"Weekly Standup\" --with \"alice\" --company \"Acme\" \
+                 --account me@example.com --series \"weekly standup\" --min-attendees 3 \
+                 --date last-week --include-deleted lists them all."
             )
         );
     }
+
+    #[test]
+    fn meeting_filters_mirror_the_echoed_values() {
+        let echo = echo_with(|e| {
+            e.with = Some("alice".to_string());
+            e.min_attendees = Some(4);
+        });
+        let filters = echo.meeting_filters();
+        assert_eq!(filters.with.as_deref(), Some("alice"));
+        assert_eq!(filters.min_attendees, Some(4));
+        assert!(filters.meeting.is_none());
+    }
 }
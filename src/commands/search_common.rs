@@ -25,7 +25,7 @@ pub fn shape_and_page<'a>(
     conn: &Connection,
     docs: Vec<(Document, Option<f32>)>,
     facts_for: impl Fn(&Document, Option<f32>) -> crate::query::evidence::RankingFacts<'a>,
-    tokens: &[crate::query::fts::FtsToken],
+    query: &crate::query::fts::Query,
     opts: &crate::query::evidence::EvidenceOptions,
     limit: usize,
 ) -> Result<(Vec<crate::query::shape::ShapedMeeting>, usize)> {
@@ -33,7 +33,7 @@ pub fn shape_and_page<'a>(
         docs.iter()
             .map(|(doc, score)| {
                 let facts = facts_for(doc, *score);
-                crate::query::evidence::shape_meeting(conn, doc, tokens, &facts, opts)
+                crate::query::evidence::shape_meeting(conn, doc, query, &facts, opts)
             })
             .collect::<Result<Vec<_>>>()
     };
@@ -120,7 +120,7 @@ mod tests {
             &conn,
             ranked_docs(&conn),
             plain_facts,
-            &crate::query::fts::parse_query("alpha"),
+            &crate::query::fts::parse_query("alpha").unwrap(),
             &crate::query::evidence::EvidenceOptions::default(),
             0,
         )
@@ -170,7 +170,7 @@ mod tests {
             &conn,
             ranked_docs(&conn),
             plain_facts,
-            &crate::query::fts::parse_query("kumquat"),
+            &crate::query::fts::parse_query("kumquat").unwrap(),
             &opts,
             0,
         )
@@ -224,7 +224,7 @@ mod tests {
             &conn,
             ranked_docs(&conn),
             plain_facts,
-            &crate::query::fts::parse_query("kumquat"),
+            &crate::query::fts::parse_query("kumquat").unwrap(),
             &opts,
             1,
         )
@@ -0,0 +1,227 @@
+//! `grans similar`: meetings on the same topic as a given one.
+//!
+//! Works entirely from stored vectors: the meeting's chunks are averaged
+//! into one document vector and scored against the embedding index, so no
+//! model is loaded. Results render as the same cards search and grep use,
+//! with the closest chunk of each meeting as its evidence.
+
+use std::collections::HashMap;
+
+use anyhow::{Result, bail};
+use rusqlite::Connection;
+
+use crate::cli::context::RunContext;
+use crate::commands::search_common::{print_shaped_cards, shape_and_page};
+use crate::embed::EmbeddingIndex;
+use crate::models::Document;
+use crate::output::format::OutputMode;
+use crate::query::adjust::normalize_title;
+use crate::query::dates::DateRange;
+use crate::query::filter::{SearchTarget, semantic_source_filter};
+use crate::query::hybrid::BestChunk;
+use crate::query::shape::ShapedMeeting;
+
+/// Options for a similarity lookup.
+pub struct SimilarOptions {
+    /// Which embedded sources to compare; titles have no vectors.
+    pub targets: Vec<SearchTarget>,
+    /// Drop meetings whose normalized title equals the source meeting's.
+    pub exclude_series: bool,
+    pub limit: usize,
+}
+
+/// Find and display the meetings most similar to `meeting` (an ID or title
+/// substring, resolved as `grans show` does).
+pub fn similar(
+    conn: &Connection,
+    meeting: &str,
+    opts: SimilarOptions,
+    date_range: Option<DateRange>,
+    include_deleted: bool,
+    ctx: &RunContext,
+) -> Result<()> {
+    let Some(source) = crate::db::meetings::show_meeting(conn, meeting)? else {
+        bail!("No meeting found matching \"{}\"", meeting);
+    };
+
+    let model = crate::embed::model::stored_or_default(conn);
+    let (index, _) = crate::embed::freshness::load_search_index(conn, model.name)?;
+    if index.is_empty() {
+        bail!("No usable embeddings; run `grans embed` first");
+    }
+
+    let shaped = similar_meetings(
+        conn,
+        &index,
+        &source,
+        &opts,
+        date_range.as_ref(),
+        include_deleted,
+    )?;
+
+    let source_id = source.id.as_deref().unwrap_or_default();
+    match ctx.output_mode {
+        OutputMode::Json => {
+            println!(
+                "{}",
+                crate::output::json::format_similar_meetings(
+                    &shaped,
+                    source_id,
+                    source.title.as_deref(),
+                    opts.limit
+                )
+            );
+        }
+        OutputMode::Tty => {
+            let title = source.title.as_deref().unwrap_or("(untitled)");
+            if shaped.is_empty() {
+                println!("No meetings found similar to \"{}\".", title);
+                return Ok(());
+            }
+            println!("Meetings similar to \"{}\":\n", title);
+            print_shaped_cards(&shaped, ctx);
+        }
+    }
+    Ok(())
+}
+
+/// The shaped page of meetings most similar to `source`, best first. Each
+/// card's score is the cosine similarity of the meeting's closest chunk.
+pub fn similar_meetings(
+    conn: &Connection,
+    index: &EmbeddingIndex,
+    source: &Document,
+    opts: &SimilarOptions,
+    date_range: Option<&DateRange>,
+    include_deleted: bool,
+) -> Result<Vec<ShapedMeeting>> {
+    let source_id = source.id.as_deref().unwrap_or_default();
+    let source_filter = semantic_source_filter(&opts.targets);
+    let Some(results) = crate::embed::similar_documents_with_index(
+        conn,
+        index,
+        source_id,
+        date_range,
+        source_filter.as_deref(),
+        include_deleted,
+    )?
+    else {
+        bail!(
+            "\"{}\" has no embedded content to compare; run `grans embed` if it synced recently",
+            source.title.as_deref().unwrap_or(source_id)
+        );
+    };
+
+    let ids: Vec<String> = results.iter().map(|r| r.document_id.clone()).collect();
+    let mut doc_by_id: HashMap<String, Document> =
+        crate::db::meetings::get_meetings_by_ids(conn, &ids)?
+            .into_iter()
+            .filter_map(|d| d.id.clone().map(|id| (id, d)))
+            .collect();
+
+    let series = opts
+        .exclude_series
+        .then(|| source.title.as_deref().map(normalize_title))
+        .flatten();
+    let mut best_chunks: HashMap<String, BestChunk> = HashMap::with_capacity(results.len());
+    let mut docs: Vec<(Document, Option<f32>)> = Vec::with_capacity(results.len());
+    for r in results {
+        let Some(doc) = doc_by_id.remove(&r.document_id) else {
+            continue;
+        };
+        if series.is_some() && doc.title.as_deref().map(normalize_title) == series {
+            continue;
+        }
+        best_chunks.insert(
+            r.document_id,
+            BestChunk {
+                text: r.matched_text,
+                source_type: r.source_type,
+                section_heading: r.section_heading,
+            },
+        );
+        docs.push((doc, Some(r.score)));
+    }
+
+    // An empty query has no lexical evidence, so every card falls back to
+    // its best chunk.
+    let query = crate::query::fts::parse_query("")?;
+    let (shaped, _) = shape_and_page(
+        conn,
+        docs,
+        |doc, score| crate::query::evidence::RankingFacts {
+            keyword: false,
+            best_chunk: doc.id.as_deref().and_then(|id| best_chunks.get(id)),
+            score,
+        },
+        &query,
+        &Default::default(),
+        opts.limit,
+    )?;
+    Ok(shaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embed::model::MockEmbedder;
+
+    const LONG: &str = "This is a longer utterance that contains enough characters to meet the minimum chunk size requirement for embedding.";
+
+    fn add_meeting(conn: &Connection, id: &str, title: &str) {
+        conn.execute(
+            "INSERT INTO documents (id, title, created_at) VALUES (?1, ?2, '2025-01-01T00:00:00Z')",
+            rusqlite::params![id, title],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO transcript_utterances (id, document_id, start_timestamp, end_timestamp, text)
+             VALUES (?1, ?2, '2025-01-01T10:00:00Z', '2025-01-01T10:00:30Z', ?3)",
+            rusqlite::params![format!("{}-u0", id), id, LONG],
+        )
+        .unwrap();
+    }
+
+    fn ids(shaped: &[ShapedMeeting]) -> Vec<&str> {
+        let mut ids: Vec<&str> = shaped.iter().map(|m| m.document_id.as_str()).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn exclude_series_drops_meetings_with_the_same_title() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::schema::create_tables(&conn).unwrap();
+        add_meeting(&conn, "w1", "Weekly sync");
+        add_meeting(&conn, "w2", " weekly SYNC");
+        add_meeting(&conn, "p1", "Pricing review");
+        let index = crate::embed::ensure_embeddings(
+            &conn,
+            &MockEmbedder::default(),
+            crate::embed::DEFAULT_BATCH_SIZE,
+            &crate::embed::config::EmbedSpec::default_for(512),
+        )
+        .unwrap();
+        let source = crate::db::meetings::show_meeting(&conn, "w1")
+            .unwrap()
+            .unwrap();
+
+        let mut opts = SimilarOptions {
+            targets: SearchTarget::all(),
+            exclude_series: false,
+            limit: 10,
+        };
+        let shaped = similar_meetings(&conn, &index, &source, &opts, None, false).unwrap();
+        assert_eq!(ids(&shaped), vec!["p1", "w2"]);
+        // The closest chunk stands in as evidence, scored by similarity.
+        assert!(
+            shaped
+                .iter()
+                .all(|m| m.score.is_some() && m.total_matches == 1)
+        );
+
+        opts.exclude_series = true;
+        let shaped = similar_meetings(&conn, &index, &source, &opts, None, false).unwrap();
+        assert_eq!(ids(&shaped), vec!["p1"]);
+    }
+}
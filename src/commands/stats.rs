@@ -0,0 +1,134 @@
+//! `grans stats`: conversation statistics from transcripts.
+//!
+//! `talk-time` rebuilds speaker turns from utterances (see
+//! [`crate::query::turns`]) and totals them per speaker, for one meeting or
+//! every meeting in a date range.
+
+use anyhow::{Result, bail};
+use chrono::Duration;
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::cli::args::StatsAction;
+use crate::cli::context::RunContext;
+use crate::db::aliases::canonical_name;
+use crate::models::Document;
+use crate::output::format::OutputMode;
+use crate::output::json::to_json;
+use crate::query::dates::build_date_range;
+use crate::query::turns::{SpeakerStats, build_turns, talk_time};
+
+/// A meeting whose transcript went into a report.
+#[derive(Debug, Serialize)]
+struct MeetingRef {
+    id: String,
+    title: Option<String>,
+    created_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct TalkTimeReport {
+    meetings: Vec<MeetingRef>,
+    /// Seconds of speech across all speakers.
+    talk_seconds: f64,
+    speakers: Vec<SpeakerStats>,
+}
+
+pub fn run(conn: &Connection, action: &StatsAction, ctx: &RunContext) -> Result<()> {
+    match action {
+        StatsAction::TalkTime {
+            meeting,
+            from,
+            to,
+            date,
+            gap,
+        } => {
+            if !gap.is_finite() || *gap < 0.0 {
+                bail!("--gap must be a non-negative number of seconds");
+            }
+            let docs = match meeting {
+                Some(q) => match crate::db::meetings::show_meeting(conn, q)? {
+                    Some(doc) => vec![doc],
+                    None => bail!("No meeting found matching \"{}\"", q),
+                },
+                None => {
+                    let Some(range) = build_date_range(
+                        from.as_deref(),
+                        to.as_deref(),
+                        date.as_deref(),
+                        chrono::Utc::now(),
+                        &ctx.tz,
+                    ) else {
+                        bail!("Give a meeting, or a date range with --from, --to or --date");
+                    };
+                    crate::db::meetings::list_meetings(conn, None, Some(&range), false)?
+                }
+            };
+            let gap = Duration::milliseconds((gap * 1000.0) as i64);
+            talk_time_report(conn, docs, gap, ctx)
+        }
+    }
+}
+
+fn talk_time_report(
+    conn: &Connection,
+    docs: Vec<Document>,
+    gap: Duration,
+    ctx: &RunContext,
+) -> Result<()> {
+    let mut meetings = Vec::new();
+    let groups = crate::db::aliases::alias_groups(conn)?;
+    let mut turns = Vec::new();
+    for doc in docs {
+        let Some(id) = doc.id else { continue };
+        let mut utterances = crate::db::transcripts::load_transcript(conn, &id)?;
+        // Merged speaker names are one speaker.
+        for u in &mut utterances {
+            if let Some(name) = u.detected_speaker_name.as_deref()
+                && let Some(canonical) = canonical_name(&groups, name)
+            {
+                u.detected_speaker_name = Some(canonical.to_string());
+            }
+        }
+        let meeting_turns = build_turns(&utterances, gap);
+        if meeting_turns.is_empty() {
+            continue;
+        }
+        turns.push(meeting_turns);
+        meetings.push(MeetingRef {
+            id,
+            title: doc.title,
+            created_at: doc.created_at,
+        });
+    }
+    let speakers = talk_time(&turns);
+    let report = TalkTimeReport {
+        meetings,
+        talk_seconds: speakers.iter().map(|s| s.talk_seconds).sum(),
+        speakers,
+    };
+
+    match ctx.output_mode {
+        OutputMode::Json => println!("{}", to_json(&report)),
+        OutputMode::Tty => {
+            if report.meetings.is_empty() {
+                println!("No timed transcripts found; run `grans sync` to fetch transcripts.");
+                return Ok(());
+            }
+            let scope = match report.meetings.as_slice() {
+                [one] => format!("\"{}\"", one.title.as_deref().unwrap_or("(untitled)")),
+                many => format!("{} meetings", many.len()),
+            };
+            println!(
+                "Talk time in {}, {} of speech:\n",
+                scope,
+                crate::output::table::format_duration_secs(report.talk_seconds)
+            );
+            println!(
+                "{}",
+                crate::output::table::format_talk_time_table(&report.speakers)
+            );
+        }
+    }
+    Ok(())
+}
@@ -1,4 +1,5 @@
-//! Sync command implementations.
+//! `grans remote` and `grans dropbox`: replicating the database to remote
+//! storage.
 
 use std::io::{self, BufWriter, IsTerminal, Write};
 use std::path::Path;
@@ -9,23 +10,33 @@ use indicatif::{ProgressBar, ProgressStyle};
 
 use chrono::FixedOffset;
 
-use crate::cli::args::DropboxAction;
+use crate::cli::args::{DropboxAction, RemoteAction, RemoteKind};
 use crate::db::integrity::check_pulled_database;
 use crate::output::format::{OutputMode, format_size};
 use crate::output::progress::create_spinner;
 use crate::pkce::PkceChallenge;
 use crate::sync::SyncError;
-use crate::sync::config::{SyncConfig, config_path};
+use crate::sync::config::{RemoteConfig, SyncConfig, config_path};
 use crate::sync::content_hash::{HashingWriter, hash_file};
+use crate::sync::crypto::{self, SyncKey};
+use crate::sync::delta::{
+    BlockMap, DeltaManifest, LAYOUT_MARKER, PatchEntry, REMOTE_BASE_PATH, REMOTE_MANIFEST_PATH,
+    RemoteDatabase, apply_patch, write_patch,
+};
 use crate::sync::dropbox::DropboxClient;
+use crate::sync::folder::FolderStore;
 use crate::sync::metadata::SyncMetadata;
 use crate::sync::oauth::{PKCE_ENTROPY_BYTES, build_auth_url, exchange_code, refresh_access_token};
 use crate::sync::reconcile::{TransferDecision, decide};
+use crate::sync::remote::{REMOTE_DB_PATH, REMOTE_METADATA_PATH, RemoteStore};
+use crate::sync::s3::{S3Settings, S3Store};
 use crate::sync::transfer::{ProgressFn, no_progress, verify_content_hash, verify_transfer_size};
+use crate::sync::webdav::WebDavStore;
 
-/// Remote paths on Dropbox (within app folder)
-pub(super) const REMOTE_DB_PATH: &str = "/grans.db";
-pub(super) const REMOTE_METADATA_PATH: &str = "/sync_metadata.json";
+/// Command prefixes named in conflict messages, so the advice matches what
+/// the user typed.
+const DROPBOX_CLI: &str = "grans dropbox";
+const REMOTE_CLI: &str = "grans remote";
 
 /// Run Dropbox commands (init, push, pull, status, logout)
 pub fn run_dropbox(
@@ -34,16 +45,195 @@ pub fn run_dropbox(
     tz: &FixedOffset,
 ) -> Result<()> {
     match action {
-        DropboxAction::Init => init()?,
-        DropboxAction::Push { force } => push(*force)?,
-        DropboxAction::Pull { force } => pull(*force)?,
-        DropboxAction::Status => super::sync_status::status(output_mode, tz)?,
+        DropboxAction::Init => init_dropbox()?,
+        DropboxAction::Push { force } => {
+            require_dropbox("push")?;
+            push(*force, DROPBOX_CLI)?
+        }
+        DropboxAction::Pull { force } => {
+            require_dropbox("pull")?;
+            pull(*force, DROPBOX_CLI)?
+        }
+        DropboxAction::Status => {
+            require_dropbox("status")?;
+            super::sync_status::status(output_mode, tz, DROPBOX_CLI)?
+        }
+        DropboxAction::Merge => {
+            require_dropbox("merge")?;
+            merge(DROPBOX_CLI)?
+        }
+        DropboxAction::Encryption { action } => {
+            require_dropbox("encryption")?;
+            super::sync_encryption::run(action, DROPBOX_CLI)?
+        }
+        DropboxAction::Snapshots { keep } => {
+            require_dropbox("snapshots")?;
+            super::sync_snapshots::list(*keep, output_mode, tz, DROPBOX_CLI)?
+        }
+        DropboxAction::Restore { snapshot, force } => {
+            require_dropbox("restore")?;
+            super::sync_snapshots::restore(snapshot, *force, DROPBOX_CLI)?
+        }
         DropboxAction::Logout => logout()?,
     }
     Ok(())
 }
 
-fn init() -> Result<()> {
+/// Run remote commands (init, push, pull, status, forget)
+pub fn run_remote(action: &RemoteAction, output_mode: OutputMode, tz: &FixedOffset) -> Result<()> {
+    match action {
+        RemoteAction::Init { kind } => init_remote(kind)?,
+        RemoteAction::Push { force } => push(*force, REMOTE_CLI)?,
+        RemoteAction::Pull { force } => pull(*force, REMOTE_CLI)?,
+        RemoteAction::Status => super::sync_status::status(output_mode, tz, REMOTE_CLI)?,
+        RemoteAction::Merge => merge(REMOTE_CLI)?,
+        RemoteAction::Encryption { action } => super::sync_encryption::run(action, REMOTE_CLI)?,
+        RemoteAction::Snapshots { keep } => {
+            super::sync_snapshots::list(*keep, output_mode, tz, REMOTE_CLI)?
+        }
+        RemoteAction::Restore { snapshot, force } => {
+            super::sync_snapshots::restore(snapshot, *force, REMOTE_CLI)?
+        }
+        RemoteAction::Forget => forget()?,
+    }
+    Ok(())
+}
+
+/// `grans dropbox` commands act on Dropbox only; with another remote
+/// configured they would quietly act on it instead.
+fn require_dropbox(action: &str) -> Result<()> {
+    let remote = SyncConfig::load()?.remote();
+    if remote != RemoteConfig::Dropbox {
+        anyhow::bail!(
+            "This machine syncs with a {} remote, not Dropbox. Use 'grans remote {}', \
+             or 'grans dropbox init' to switch back to Dropbox.",
+            remote.kind(),
+            action
+        );
+    }
+    Ok(())
+}
+
+/// Connect to the configured remote.
+pub(super) fn open_store(config: &SyncConfig) -> Result<Box<dyn RemoteStore>> {
+    Ok(match config.remote() {
+        RemoteConfig::Dropbox => {
+            if !config.is_authenticated() {
+                return Err(SyncError::NotAuthenticated.into());
+            }
+            Box::new(DropboxClient::new(get_access_token(config)?)?)
+        }
+        RemoteConfig::Folder { path } => {
+            // Writing into a missing mount point would fill the local disk
+            // and sync nothing.
+            if !path.is_dir() {
+                anyhow::bail!(
+                    "The remote folder {} does not exist (is the share mounted?)",
+                    path.display()
+                );
+            }
+            Box::new(FolderStore::new(path))
+        }
+        RemoteConfig::Webdav {
+            url,
+            username,
+            password,
+        } => Box::new(WebDavStore::new(&url, username, password)?),
+        RemoteConfig::S3 {
+            bucket,
+            region,
+            endpoint,
+            prefix,
+            access_key_id,
+            secret_access_key,
+        } => Box::new(S3Store::new(&S3Settings {
+            endpoint: endpoint.as_deref(),
+            region: &region,
+            bucket: &bucket,
+            prefix: prefix.as_deref(),
+            access_key_id: &access_key_id,
+            secret_access_key: &secret_access_key,
+        })?),
+    })
+}
+
+fn init_remote(kind: &RemoteKind) -> Result<()> {
+    let remote = match kind {
+        RemoteKind::Dropbox => return init_dropbox(),
+        RemoteKind::Folder { path } => {
+            if !path.is_dir() {
+                anyhow::bail!("{} is not a directory", path.display());
+            }
+            RemoteConfig::Folder {
+                path: std::path::absolute(path)?,
+            }
+        }
+        RemoteKind::Webdav { url, username } => RemoteConfig::Webdav {
+            url: url.clone(),
+            username: username.clone(),
+            password: username
+                .as_ref()
+                .map(|_| read_secret("WebDAV password", "GRANS_WEBDAV_PASSWORD"))
+                .transpose()?,
+        },
+        RemoteKind::S3 {
+            bucket,
+            region,
+            endpoint,
+            prefix,
+            access_key_id,
+        } => RemoteConfig::S3 {
+            bucket: bucket.clone(),
+            region: region.clone(),
+            endpoint: endpoint.clone(),
+            prefix: prefix.clone(),
+            access_key_id: match access_key_id {
+                Some(id) => id.clone(),
+                None => read_secret("Access key ID", "AWS_ACCESS_KEY_ID")?,
+            },
+            secret_access_key: read_secret("Secret access key", "AWS_SECRET_ACCESS_KEY")?,
+        },
+    };
+
+    let mut config = SyncConfig::load()?;
+    config.set_remote(remote);
+
+    // Fail now on a wrong URL or credentials, not on the first push.
+    let store = open_store(&config)?;
+    let existing = store
+        .stat(REMOTE_DB_PATH)
+        .with_context(|| format!("could not reach {}", store.describe()))?;
+    config.save()?;
+
+    println!("Push and pull now replicate to {}.", store.describe());
+    if existing.is_some() {
+        println!(
+            "It already holds a database: run '{} pull' to fetch it, or '{} status' to compare.",
+            REMOTE_CLI, REMOTE_CLI
+        );
+    }
+    Ok(())
+}
+
+/// A secret from `env_var` if set, or else typed at a prompt.
+pub(super) fn read_secret(prompt: &str, env_var: &str) -> Result<String> {
+    if let Ok(value) = std::env::var(env_var)
+        && !value.is_empty()
+    {
+        return Ok(value);
+    }
+    print!("{} (or set {}): ", prompt, env_var);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim();
+    if input.is_empty() {
+        anyhow::bail!("No {} provided", prompt.to_lowercase());
+    }
+    Ok(input.to_string())
+}
+
+fn init_dropbox() -> Result<()> {
     let mut config = SyncConfig::load()?;
 
     if config.is_authenticated() {
@@ -56,6 +246,11 @@ fn init() -> Result<()> {
 
         if !input.trim().eq_ignore_ascii_case("y") {
             println!("Keeping existing authentication.");
+            if config.remote() != RemoteConfig::Dropbox {
+                config.set_remote(RemoteConfig::Dropbox);
+                config.save()?;
+                println!("Push and pull now replicate to Dropbox.");
+            }
             return Ok(());
         }
     }
@@ -91,6 +286,7 @@ fn init() -> Result<()> {
 
     // Store refresh token
     config.refresh_token = tokens.refresh_token;
+    config.set_remote(RemoteConfig::Dropbox);
     config.save()?;
 
     println!("\nSuccessfully authenticated with Dropbox!");
@@ -99,17 +295,10 @@ fn init() -> Result<()> {
     Ok(())
 }
 
-/// Push database to Dropbox
-fn push(force: bool) -> Result<()> {
+/// Push the database to the configured remote
+fn push(force: bool, cli: &str) -> Result<()> {
     let mut config = SyncConfig::load()?;
-
-    if !config.is_authenticated() {
-        return Err(SyncError::NotAuthenticated.into());
-    }
-
-    // Get access token
-    let access_token = get_access_token(&config)?;
-    let client = DropboxClient::new(access_token)?;
+    let store = open_store(&config)?;
 
     // Get local database path
     let db_path = crate::db::connection::default_db_path()?;
@@ -119,116 +308,338 @@ fn push(force: bool) -> Result<()> {
         return Ok(());
     }
 
-    let synced_hash = push_file(
-        &client,
+    let key = push_key(&config, cli)?;
+    let synced_hash = push_database(
+        store.as_ref(),
         &db_path,
-        REMOTE_DB_PATH,
-        "database",
         force,
         config.last_synced_hash.as_deref(),
+        key.as_ref(),
+        config.snapshots_kept(),
+        cli,
     )?;
 
     // Generate and upload metadata
     let metadata = SyncMetadata::from_local_db(Some(&db_path))?;
-    upload_metadata(&client, &metadata)?;
+    upload_metadata(store.as_ref(), &metadata, key.as_ref())?;
 
     // Record what both sides now hold, so the next sync can tell which one moved.
     config.last_synced_hash = Some(synced_hash);
     config.last_push_time = Some(current_timestamp());
     config.save()?;
+
+    // The push itself has landed; a snapshot that fails only loses history.
+    if let Err(e) = super::sync_snapshots::record(
+        store.as_ref(),
+        Some(&metadata),
+        key.as_ref(),
+        config.snapshots_kept(),
+        cli,
+    ) {
+        eprintln!("Warning: could not keep a snapshot of this push: {:#}", e);
+    }
     println!("\nPush complete!");
 
     Ok(())
 }
 
-fn upload_metadata(client: &DropboxClient, metadata: &SyncMetadata) -> Result<()> {
-    let json = serde_json::to_string_pretty(metadata)?;
-    let temp_dir = std::env::temp_dir();
-    let temp_path = temp_dir.join("grans_sync_metadata.json");
-    std::fs::write(&temp_path, &json)?;
+/// The key pushes from this machine are encrypted with, if encryption is on.
+pub(super) fn push_key(config: &SyncConfig, cli: &str) -> Result<Option<SyncKey>> {
+    let Some(fingerprint) = &config.encryption_key else {
+        return Ok(None);
+    };
+    match SyncKey::load(fingerprint)? {
+        Some(key) => Ok(Some(key)),
+        None => anyhow::bail!(
+            "Encryption is on, but this machine no longer holds key {}. \
+             Run '{} encryption unlock' to recover it with the passphrase.",
+            fingerprint,
+            cli
+        ),
+    }
+}
 
-    println!("Uploading sync metadata...");
-    // A few hundred bytes: a progress bar would flash and vanish.
-    client.upload(&temp_path, REMOTE_METADATA_PATH, no_progress())?;
+/// The key that sealed something downloaded from `store`.
+pub(super) fn key_for(fingerprint: &str, store: &dyn RemoteStore, cli: &str) -> Result<SyncKey> {
+    SyncKey::load(fingerprint)?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "The copy on {} is encrypted with key {}, which this machine does not hold. \
+             Run '{} encryption unlock' to recover it with the passphrase.",
+            store.describe(),
+            fingerprint,
+            cli
+        )
+    })
+}
 
-    // Clean up temp file
+/// Upload a small file, such as metadata or a manifest, by way of a temp file.
+pub(super) fn upload_bytes(store: &dyn RemoteStore, bytes: &[u8], remote_path: &str) -> Result<()> {
+    let temp_path = std::env::temp_dir().join(format!(
+        "grans_sync_{}",
+        remote_path.trim_start_matches('/')
+    ));
+    std::fs::write(&temp_path, bytes)?;
+    // A few hundred bytes: a progress bar would flash and vanish.
+    let uploaded = store.upload(&temp_path, remote_path, no_progress());
     let _ = std::fs::remove_file(&temp_path);
-
+    uploaded?;
     Ok(())
 }
 
-/// Push a file, unless the remote copy holds changes this machine has not seen.
+fn upload_metadata(
+    store: &dyn RemoteStore,
+    metadata: &SyncMetadata,
+    key: Option<&SyncKey>,
+) -> Result<()> {
+    let json = serde_json::to_string_pretty(metadata)?;
+    let bytes = match key {
+        Some(key) => crypto::encrypt(key, json.as_bytes())?,
+        None => json.into_bytes(),
+    };
+
+    println!("Uploading sync metadata...");
+    upload_bytes(store, &bytes, REMOTE_METADATA_PATH)
+}
+
+/// Push the database, unless the remote copy holds changes this machine has not seen.
 ///
-/// Returns the content hash both sides hold afterwards, to record as the
-/// reference point for the next sync.
-fn push_file(
-    client: &DropboxClient,
+/// When the remote still holds the database this machine last synced, only
+/// the blocks changed since then are uploaded. Returns the content hash both
+/// sides hold afterwards, to record as the reference point for the next sync.
+///
+/// A remote database no snapshot holds yet, such as one pushed from a
+/// machine keeping none, is snapshotted before it is replaced.
+fn push_database(
+    store: &dyn RemoteStore,
     local_path: &Path,
-    remote_path: &str,
-    name: &str,
     force: bool,
     last_synced: Option<&str>,
+    key: Option<&SyncKey>,
+    keep: usize,
+    cli: &str,
 ) -> Result<String> {
-    let local_hash = hash_file(local_path)?;
-    let remote = client.get_metadata(remote_path)?;
-    let remote_hash = remote.as_ref().and_then(|m| m.content_hash.as_deref());
+    let local = BlockMap::of_file(local_path)?;
+    let remote = RemoteDatabase::load(store)?;
+    let remote_hash = remote.as_ref().and_then(|r| r.hash());
+    // A copy matching this one, but encrypted with another key or not at
+    // all, is still replaced: that is how turning encryption on or off
+    // reaches the remote.
+    let remote_key = remote
+        .as_ref()
+        .and_then(|r| r.chain())
+        .and_then(|m| m.key.clone());
+    let encryption_changed = remote_key != key.map(SyncKey::fingerprint);
 
-    match decide(&local_hash, remote_hash, last_synced) {
+    match decide(&local.content_hash, remote_hash, last_synced) {
+        TransferDecision::UpToDate if encryption_changed => {}
         TransferDecision::UpToDate => {
-            println!("Dropbox already has this {}; nothing to upload.", name);
-            return Ok(local_hash);
+            println!(
+                "{} already has this database; nothing to upload.",
+                store.describe()
+            );
+            return Ok(local.content_hash);
         }
         TransferDecision::Diverged if !force => {
             return Err(SyncError::ConflictRemoteChanged {
-                what: name.to_string(),
+                what: "database".to_string(),
+                remote: store.describe(),
+                cli: cli.to_string(),
             }
             .into());
         }
         TransferDecision::Unknown if !force => {
             return Err(SyncError::ConflictNoSyncRecord {
-                what: name.to_string(),
+                what: "database".to_string(),
+                remote: store.describe(),
+                cli: cli.to_string(),
             }
             .into());
         }
         _ => {}
     }
 
-    let size = std::fs::metadata(local_path)?.len();
-    println!("Uploading {} ({})...", name, format_size(size));
+    // Its metadata may describe another push, so the snapshot goes without.
+    super::sync_snapshots::record(store, None, key, keep, cli).with_context(|| {
+        format!(
+            "could not keep a snapshot of {} before replacing it \
+             (`{} snapshots --keep 0` pushes without snapshots)",
+            store.describe(),
+            cli
+        )
+    })?;
 
-    let progress = TransferProgress::new(size);
-    client.upload(local_path, remote_path, progress.reporter())?;
-    drop(progress);
+    let sent_delta = match (&remote, BlockMap::load()) {
+        (Some(remote), Some(synced)) if remote_hash == Some(synced.content_hash.as_str()) => {
+            push_delta(store, local_path, remote, &synced, key)?
+        }
+        _ => false,
+    };
+    if !sent_delta {
+        push_full(store, local_path, &local, remote.as_ref(), key)?;
+    }
+
+    local.save()?;
+    Ok(local.content_hash)
+}
+
+/// Upload the blocks changed since `synced` as a patch on the remote chain.
+///
+/// Returns false, having uploaded nothing, when the chain has no room left
+/// for the patch, or is encrypted differently from how this push would be,
+/// and the whole file should go instead.
+fn push_delta(
+    store: &dyn RemoteStore,
+    local_path: &Path,
+    remote: &RemoteDatabase,
+    synced: &BlockMap,
+    key: Option<&SyncKey>,
+) -> Result<bool> {
+    let Some(base_hash) = remote.base.content_hash.as_deref() else {
+        return Ok(false);
+    };
+    // Without a manifest the remote hash is the base's own, so the base is
+    // the unencrypted database this machine last synced.
+    let mut manifest = remote
+        .chain()
+        .cloned()
+        .unwrap_or_else(|| DeltaManifest::new(base_hash, remote.base.size));
+    if manifest.key != key.map(SyncKey::fingerprint) {
+        return Ok(false);
+    }
 
-    println!("  Uploaded to {}", remote_path);
+    let patch_path = local_path.with_extension("db.patch");
+    let sealed_path = local_path.with_extension("db.patch.enc");
+    let uploaded = write_patch(local_path, synced, &patch_path)
+        .map_err(anyhow::Error::from)
+        .and_then(|map| {
+            let patch_path = match key {
+                Some(key) => {
+                    crypto::encrypt_file(key, &patch_path, &sealed_path)?;
+                    &sealed_path
+                }
+                None => &patch_path,
+            };
+            let size = std::fs::metadata(patch_path)?.len();
+            if !manifest.has_room_for(size, remote.base.size) {
+                return Ok(false);
+            }
+
+            // The first patch moves the base aside and puts the marker in
+            // its place, so that a build unable to apply patches never takes
+            // the base for the whole database.
+            if remote.path() == REMOTE_DB_PATH {
+                store.copy(REMOTE_DB_PATH, REMOTE_BASE_PATH)?;
+                upload_bytes(store, LAYOUT_MARKER, REMOTE_DB_PATH)?;
+            }
 
-    Ok(local_hash)
+            let remote_path = DeltaManifest::patch_path(&synced.content_hash, &map.content_hash);
+            println!(
+                "Uploading changes to the database ({})...",
+                format_size(size)
+            );
+            let progress = TransferProgress::new(size);
+            store.upload(patch_path, &remote_path, progress.reporter())?;
+            drop(progress);
+
+            // The patch is in place before the manifest names it, so a
+            // machine pulling in between never looks for a missing file.
+            manifest.patches.push(PatchEntry {
+                path: remote_path.clone(),
+                from: synced.content_hash.clone(),
+                to: map.content_hash,
+                db_size: map.len,
+                size,
+            });
+            upload_bytes(
+                store,
+                &serde_json::to_vec_pretty(&manifest)?,
+                REMOTE_MANIFEST_PATH,
+            )?;
+            println!("  Uploaded to {}{}", store.describe(), remote_path);
+            Ok(true)
+        });
+    let _ = std::fs::remove_file(&patch_path);
+    let _ = std::fs::remove_file(&sealed_path);
+    uploaded
 }
 
-/// Pull database from Dropbox
-fn pull(force: bool) -> Result<()> {
-    let mut config = SyncConfig::load()?;
+/// Upload the whole database to [`REMOTE_DB_PATH`] as a new base, dropping
+/// the patches on the old one.
+fn push_full(
+    store: &dyn RemoteStore,
+    local_path: &Path,
+    local: &BlockMap,
+    previous: Option<&RemoteDatabase>,
+    key: Option<&SyncKey>,
+) -> Result<()> {
+    let sealed_path = local_path.with_extension("db.enc");
+    let upload_path = match key {
+        Some(key) => {
+            let spinner = create_spinner("Encrypting database...");
+            let sealed = crypto::encrypt_file(key, local_path, &sealed_path);
+            spinner.finish_and_clear();
+            if let Err(e) = sealed {
+                let _ = std::fs::remove_file(&sealed_path);
+                return Err(e);
+            }
+            sealed_path.as_path()
+        }
+        None => local_path,
+    };
 
-    if !config.is_authenticated() {
-        return Err(SyncError::NotAuthenticated.into());
+    let size = std::fs::metadata(upload_path)?.len();
+    println!("Uploading database ({})...", format_size(size));
+
+    let progress = TransferProgress::new(size);
+    let uploaded = store.upload(upload_path, REMOTE_DB_PATH, progress.reporter());
+    drop(progress);
+    let _ = std::fs::remove_file(&sealed_path);
+    let stored = uploaded?;
+
+    println!("  Uploaded to {}{}", store.describe(), REMOTE_DB_PATH);
+
+    // The manifest names the new base, so the patches on the old one, and
+    // the old base itself if it was moved aside for them, are no longer
+    // reachable; removing them just reclaims the space.
+    let mut manifest = DeltaManifest::new(&local.content_hash, local.len);
+    if let Some(key) = key {
+        manifest.stored_hash = stored.content_hash;
+        manifest.key = Some(key.fingerprint());
+    }
+    upload_bytes(
+        store,
+        &serde_json::to_vec_pretty(&manifest)?,
+        REMOTE_MANIFEST_PATH,
+    )?;
+    if let Some(previous) = previous {
+        let old_base = (previous.path() != REMOTE_DB_PATH).then(|| previous.path());
+        for path in previous.patch_paths().chain(old_base) {
+            if let Err(e) = store.delete(path) {
+                eprintln!("Warning: could not remove {}: {}", path, e);
+            }
+        }
     }
 
-    // Get access token
-    let access_token = get_access_token(&config)?;
-    let client = DropboxClient::new(access_token)?;
+    Ok(())
+}
+
+/// Pull the database from the configured remote
+fn pull(force: bool, cli: &str) -> Result<()> {
+    let mut config = SyncConfig::load()?;
+    let store = open_store(&config)?;
 
     // Get local database path
     let db_path = crate::db::connection::default_db_path()?;
 
     // Pull database
-    if client.get_metadata(REMOTE_DB_PATH)?.is_some() {
-        let synced_hash = pull_file(
-            &client,
+    if let Some(remote) = RemoteDatabase::load(store.as_ref())? {
+        let synced_hash = pull_database(
+            store.as_ref(),
+            &remote,
             &db_path,
-            REMOTE_DB_PATH,
-            "database",
             force,
             config.last_synced_hash.as_deref(),
+            cli,
         )?;
 
         // Record what both sides now hold, so the next sync can tell which one moved.
@@ -237,38 +648,29 @@ fn pull(force: bool) -> Result<()> {
         config.save()?;
         println!("\nPull complete!");
     } else {
-        println!("No database on Dropbox");
+        println!("No database on {}", store.describe());
     }
 
     Ok(())
 }
 
-/// Pull a file, unless the local copy holds changes that are not on Dropbox.
+/// Pull the database, unless the local copy holds changes that are not on the remote.
 ///
 /// Returns the content hash both sides hold afterwards, to record as the
 /// reference point for the next sync.
-fn pull_file(
-    client: &DropboxClient,
+fn pull_database(
+    store: &dyn RemoteStore,
+    remote: &RemoteDatabase,
     local_path: &Path,
-    remote_path: &str,
-    name: &str,
     force: bool,
     last_synced: Option<&str>,
+    cli: &str,
 ) -> Result<String> {
-    // Get remote metadata
-    let remote = client
-        .get_metadata(remote_path)?
-        .ok_or_else(|| SyncError::DropboxApi(format!("{} not found on Dropbox", remote_path)))?;
-
     // Verification needs the hash before the transfer starts; refusing early
     // beats discovering it after moving hundreds of megabytes.
-    let expected_hash =
-        remote
-            .content_hash
-            .as_deref()
-            .ok_or_else(|| SyncError::MissingContentHash {
-                path: remote_path.to_string(),
-            })?;
+    let expected_hash = remote.hash().ok_or_else(|| SyncError::MissingContentHash {
+        path: remote.path().to_string(),
+    })?;
 
     let local_hash = local_path
         .exists()
@@ -278,50 +680,42 @@ fn pull_file(
     match decide(expected_hash, local_hash.as_deref(), last_synced) {
         TransferDecision::UpToDate => {
             println!(
-                "The local {} already matches Dropbox; nothing to download.",
-                name
+                "The local database already matches {}; nothing to download.",
+                store.describe()
             );
             return Ok(expected_hash.to_string());
         }
         TransferDecision::Diverged if !force => {
             return Err(SyncError::ConflictLocalChanged {
-                what: name.to_string(),
+                what: "database".to_string(),
+                cli: cli.to_string(),
             }
             .into());
         }
         TransferDecision::Unknown if !force => {
             return Err(SyncError::ConflictNoSyncRecord {
-                what: name.to_string(),
+                what: "database".to_string(),
+                remote: store.describe(),
+                cli: cli.to_string(),
             }
             .into());
         }
         _ => {}
     }
 
-    println!("Downloading {} ({})...", name, format_size(remote.size));
-
     // Ensure parent directory exists
     if let Some(parent) = local_path.parent() {
         std::fs::create_dir_all(parent)?;
     }
 
-    // Download into a temp file so nothing replaces a good database until every
-    // check has passed.
+    // Assemble the download in a temp file so nothing replaces a good database
+    // until every check has passed.
     let temp_path = local_path.with_extension("db.tmp");
-    let downloaded = download_and_verify(
-        client,
-        remote_path,
-        &temp_path,
-        &Expected {
-            size: remote.size,
-            hash: expected_hash,
-            name,
-        },
-    );
-
-    match downloaded {
-        Ok(()) => {
+    let local = local_hash.as_deref().map(|hash| (local_path, hash));
+    match fetch_database(store, remote, local, &temp_path, cli) {
+        Ok(map) => {
             std::fs::rename(&temp_path, local_path)?;
+            map.save()?;
             println!("  Downloaded to {}", local_path.display());
             Ok(expected_hash.to_string())
         }
@@ -332,47 +726,196 @@ fn pull_file(
     }
 }
 
-/// What a download has to match before it is allowed to replace the database.
-struct Expected<'a> {
-    size: u64,
-    hash: &'a str,
-    name: &'a str,
+/// Merge the remote copy into the local database row by row, then push the
+/// result, so neither side's changes are lost.
+fn merge(cli: &str) -> Result<()> {
+    let mut config = SyncConfig::load()?;
+    let store = open_store(&config)?;
+    let db_path = crate::db::connection::default_db_path()?;
+
+    let Some(remote) = RemoteDatabase::load(store.as_ref())? else {
+        println!(
+            "No database on {}; run '{} push' to upload this one.",
+            store.describe(),
+            cli
+        );
+        return Ok(());
+    };
+    let remote_hash = remote
+        .hash()
+        .ok_or_else(|| SyncError::MissingContentHash {
+            path: remote.path().to_string(),
+        })?
+        .to_string();
+
+    if !db_path.exists() {
+        println!("No local database to merge; pulling instead.");
+        return pull(false, cli);
+    }
+    if hash_file(&db_path)? == remote_hash {
+        println!(
+            "The local database already matches {}; nothing to merge.",
+            store.describe()
+        );
+        return Ok(());
+    }
+
+    let temp_path = db_path.with_extension("db.remote");
+    let merged = fetch_database(store.as_ref(), &remote, None, &temp_path, cli).and_then(|map| {
+        println!("Merging...");
+        let report = crate::db::merge::merge_files(&db_path, &temp_path)?;
+        Ok((map, report))
+    });
+    let _ = std::fs::remove_file(&temp_path);
+    let (remote_map, report) = merged?;
+    super::db::print_merge_report(&report);
+
+    // The local copy now holds everything the remote one did, so the remote
+    // copy is the reference point: the push below proceeds unless another
+    // machine pushed in the meantime, and sends its changes as a patch on
+    // the remote copy when they are small enough.
+    remote_map.save()?;
+    config.last_synced_hash = Some(remote_hash);
+    config.save()?;
+
+    println!();
+    push(false, cli)
 }
 
-/// Stream a remote file to `temp_path` and prove it is safe to install.
+/// Assemble the remote database at `temp_path` and prove it is safe to install.
 ///
-/// Checks run cheapest first, so a failure costs as little as possible: the byte
-/// count and content hash both come free with the transfer, while the integrity
-/// check has to read the file back.
-fn download_and_verify(
-    client: &DropboxClient,
-    remote_path: &str,
+/// A local copy that sits on the remote's patch chain is brought up to date
+/// by applying the patches after it; otherwise the base is downloaded and
+/// every patch applied to it, decrypting whatever is encrypted. Either way
+/// the result has to match the size and content hash the remote describes
+/// before the integrity check, which has to read the file back, runs on the
+/// decrypted file. Returns the block map of the result.
+pub(super) fn fetch_database(
+    store: &dyn RemoteStore,
+    remote: &RemoteDatabase,
+    local: Option<(&Path, &str)>,
     temp_path: &Path,
-    expected: &Expected<'_>,
-) -> Result<()> {
-    let (written, actual_hash) = stream_to_file(client, remote_path, temp_path, expected.size)?;
+    cli: &str,
+) -> Result<BlockMap> {
+    let expected_hash = remote.hash().ok_or_else(|| SyncError::MissingContentHash {
+        path: remote.path().to_string(),
+    })?;
+
+    let patches_from_local = local
+        .zip(remote.chain())
+        .and_then(|((path, hash), chain)| chain.patches_from(hash).map(|patches| (path, patches)));
+    let patched = match patches_from_local {
+        Some((path, patches)) => match std::fs::copy(path, temp_path)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| apply_remote_patches(store, patches, temp_path, cli))
+        {
+            Ok(()) => true,
+            Err(e) => {
+                eprintln!(
+                    "Warning: could not apply the remote changes ({}); \
+                     downloading the whole database instead.",
+                    e
+                );
+                false
+            }
+        },
+        None => false,
+    };
+
+    if !patched {
+        let base_hash =
+            remote
+                .base
+                .content_hash
+                .as_deref()
+                .ok_or_else(|| SyncError::MissingContentHash {
+                    path: remote.path().to_string(),
+                })?;
+        println!(
+            "Downloading database ({})...",
+            format_size(remote.base.size)
+        );
+        let (written, actual_hash) =
+            stream_to_file(store, remote.path(), temp_path, remote.base.size)?;
+        verify_transfer_size(written, remote.base.size, "database")?;
+        verify_content_hash(&actual_hash, base_hash, "database")?;
+
+        if let Some(fingerprint) = crypto::file_sealed_with(temp_path)? {
+            let key = key_for(&fingerprint, store, cli)?;
+            let plain_path = temp_path.with_extension("plain");
+            let spinner = create_spinner("Decrypting database...");
+            let opened = crypto::decrypt_file(&key, temp_path, &plain_path)
+                .and_then(|()| Ok(std::fs::rename(&plain_path, temp_path)?));
+            spinner.finish_and_clear();
+            if let Err(e) = opened {
+                let _ = std::fs::remove_file(&plain_path);
+                return Err(e.context(format!(
+                    "the database downloaded from {} could not be decrypted; \
+                     the local file was left untouched",
+                    store.describe()
+                )));
+            }
+        }
+
+        if let Some(chain) = remote.chain() {
+            apply_remote_patches(store, &chain.patches, temp_path, cli)?;
+        }
+    }
 
-    verify_transfer_size(written, expected.size, expected.name)?;
-    verify_content_hash(&actual_hash, expected.hash, expected.name)?;
+    // Without a manifest there is nothing to check the decrypted file
+    // against; the checks on the download and the cipher's own tags stand in.
+    let map = BlockMap::of_file(temp_path)?;
+    if remote.chain().is_some() {
+        verify_transfer_size(map.len, remote.size(), "database")?;
+        verify_content_hash(&map.content_hash, expected_hash, "database")?;
+    }
 
-    let spinner = create_spinner(&format!("Checking {} integrity...", expected.name));
+    let spinner = create_spinner("Checking database integrity...");
     let result = check_pulled_database(temp_path);
     spinner.finish_and_clear();
 
     result.with_context(|| {
         format!(
-            "the {} downloaded from Dropbox failed its integrity check; \
+            "the database downloaded from {} failed its integrity check; \
              the local file was left untouched",
-            expected.name
+            store.describe()
         )
-    })
+    })?;
+    Ok(map)
+}
+
+/// Download each patch and apply it to the file at `target`, in order.
+fn apply_remote_patches(
+    store: &dyn RemoteStore,
+    patches: &[PatchEntry],
+    target: &Path,
+    cli: &str,
+) -> Result<()> {
+    if patches.is_empty() {
+        return Ok(());
+    }
+    let total: u64 = patches.iter().map(|p| p.size).sum();
+    println!(
+        "Downloading {} change(s) to the database ({})...",
+        patches.len(),
+        format_size(total)
+    );
+    for patch in patches {
+        let mut bytes = store.download(&patch.path)?;
+        verify_transfer_size(bytes.len() as u64, patch.size, "delta patch")?;
+        if let Some(fingerprint) = crypto::sealed_with(&bytes) {
+            bytes = crypto::decrypt(&key_for(&fingerprint, store, cli)?, &bytes)?;
+        }
+        apply_patch(target, &mut bytes.as_slice())?;
+    }
+    Ok(())
 }
 
 /// Write the download to disk, hashing as it streams, and flush it to the device.
 ///
 /// Returns the byte count and the Dropbox content hash of what was written.
 fn stream_to_file(
-    client: &DropboxClient,
+    store: &dyn RemoteStore,
     remote_path: &str,
     temp_path: &Path,
     expected_size: u64,
@@ -383,7 +926,7 @@ fn stream_to_file(
     let mut writer = HashingWriter::new(BufWriter::new(file));
 
     let written =
-        client.download_to_writer(remote_path, &mut writer, &mut |bytes| progress.set(bytes))?;
+        store.download_to_writer(remote_path, &mut writer, &mut |bytes| progress.set(bytes))?;
 
     let (buffered, actual_hash) = writer.finish();
     let file = buffered
@@ -463,8 +1006,10 @@ fn logout() -> Result<()> {
     config.clear_auth();
     config.save()?;
 
-    // Also try to remove the config file
-    if let Ok(path) = config_path() {
+    // Also try to remove the config file, unless it configures another remote
+    if config.remote() == RemoteConfig::Dropbox
+        && let Ok(path) = config_path()
+    {
         let _ = std::fs::remove_file(path);
     }
 
@@ -474,6 +1019,25 @@ fn logout() -> Result<()> {
     Ok(())
 }
 
+fn forget() -> Result<()> {
+    let config = SyncConfig::load()?;
+    let path = config_path()?;
+    if config.remote.is_none() && !config.is_authenticated() {
+        println!("No remote configured.");
+        return Ok(());
+    }
+
+    std::fs::remove_file(path)?;
+
+    println!(
+        "Forgot the {} remote and its credentials.",
+        config.remote().kind()
+    );
+    println!("Nothing stored on it has been deleted.");
+
+    Ok(())
+}
+
 /// Get a valid access token, refreshing if necessary.
 pub(super) fn get_access_token(config: &SyncConfig) -> Result<String> {
     let refresh_token = config
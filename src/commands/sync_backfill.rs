@@ -0,0 +1,323 @@
+//! Concurrent per-document backfill for the transcript and panel sync legs.
+//!
+//! Both legs make one API request per document, which is slow one at a time.
+//! [`Backfill::run`] keeps up to `concurrency` requests in flight on a pool of
+//! worker threads while the calling thread stays the only one touching the
+//! database: workers only fetch, and every result comes back over a channel
+//! to the caller's handler, in completion order.
+//!
+//! Concurrency adapts to the server. A 429 halves it and pauses dispatch for
+//! the `Retry-After` the server asked for (or an exponential default), and
+//! the document is queued again; each run of successes as long as the current
+//! limit raises it by one, back up to the configured maximum.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use log::debug;
+
+use crate::api::{ApiClient, ApiError};
+use crate::output::progress::SyncProgress;
+
+/// How many times one document may be rate limited before the leg gives up,
+/// as it did before backoff existed.
+const MAX_RATE_LIMIT_RETRIES: u32 = 5;
+
+/// First pause after a 429 without `Retry-After`; doubles per consecutive 429.
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Longest default pause between consecutive 429s.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// How a backfill run ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum BackfillEnd {
+    /// Every document was fetched (successfully or not).
+    Completed,
+    /// A document stayed rate limited through every retry, so the remaining
+    /// documents were left for the next sync.
+    RateLimited,
+}
+
+/// Settings for one backfill leg.
+pub(super) struct Backfill {
+    /// Most requests in flight at once.
+    pub concurrency: usize,
+    /// Base pause each worker takes between its own requests.
+    pub delay_ms: u64,
+}
+
+impl Backfill {
+    /// Fetch `ids` with `fetch` and hand each result to `handle` on the
+    /// calling thread, then tick `progress`. Rate limits are absorbed here
+    /// (backoff and requeue); an authentication failure stops the run with an
+    /// error, as nothing after it can succeed.
+    pub fn run<T, F, H>(
+        &self,
+        client: &ApiClient,
+        ids: &[&str],
+        progress: &mut SyncProgress,
+        fetch: F,
+        mut handle: H,
+    ) -> Result<BackfillEnd>
+    where
+        T: Send,
+        F: Fn(&ApiClient, &str) -> Result<T, ApiError> + Sync,
+        H: FnMut(&mut SyncProgress, usize, Result<T, ApiError>),
+    {
+        let workers = self.concurrency.clamp(1, ids.len().max(1));
+        let mut limiter = Limiter::new(workers);
+        let mut queue: VecDeque<usize> = (0..ids.len()).collect();
+        let mut attempts = vec![0u32; ids.len()];
+        let mut in_flight = 0;
+        let mut end = BackfillEnd::Completed;
+
+        let (job_tx, job_rx) = mpsc::channel::<usize>();
+        let job_rx = Mutex::new(job_rx);
+        thread::scope(|scope| {
+            // Owned by this closure, so every way out of it closes the job
+            // channel and lets the workers finish before the scope joins them.
+            let job_tx = job_tx;
+            let (result_tx, result_rx) = mpsc::channel();
+            for _ in 0..workers {
+                let job_rx = &job_rx;
+                let result_tx = result_tx.clone();
+                let fetch = &fetch;
+                let delay_ms = self.delay_ms;
+                scope.spawn(move || {
+                    let mut first = true;
+                    loop {
+                        // The guard drops at the end of this statement, so
+                        // only the wait for a job is serialized.
+                        let next = job_rx
+                            .lock()
+                            .map_err(|_| ())
+                            .and_then(|rx| rx.recv().map_err(|_| ()));
+                        let Ok(index) = next else { break };
+                        if !first {
+                            crate::api::client::sleep_with_jitter(delay_ms);
+                        }
+                        first = false;
+                        if result_tx.send((index, fetch(client, ids[index]))).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(result_tx);
+
+            loop {
+                let now = Instant::now();
+                if end == BackfillEnd::Completed && limiter.ready(now) {
+                    while in_flight < limiter.limit() {
+                        let Some(index) = queue.pop_front() else {
+                            break;
+                        };
+                        job_tx.send(index)?;
+                        in_flight += 1;
+                    }
+                }
+                if in_flight == 0 && (queue.is_empty() || end != BackfillEnd::Completed) {
+                    break;
+                }
+
+                let received = match limiter.resume_in(now) {
+                    Some(wait) if in_flight == 0 => {
+                        thread::sleep(wait);
+                        continue;
+                    }
+                    Some(wait) => result_rx.recv_timeout(wait),
+                    None => result_rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+                let (index, result) = match received {
+                    Ok(received) => received,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => {
+                        anyhow::bail!("Sync workers stopped unexpectedly")
+                    }
+                };
+                in_flight -= 1;
+
+                match result {
+                    Err(ApiError::RateLimited { retry_after }) => {
+                        attempts[index] += 1;
+                        if attempts[index] > MAX_RATE_LIMIT_RETRIES {
+                            progress.println("  -> Rate limited repeatedly, stopping sync");
+                            end = BackfillEnd::RateLimited;
+                            continue;
+                        }
+                        let wait = limiter.on_rate_limited(retry_after, Instant::now());
+                        debug!(
+                            "rate limited on {}; pausing {:?}, concurrency {}",
+                            ids[index],
+                            wait,
+                            limiter.limit()
+                        );
+                        progress.println(&format!(
+                            "  -> Rate limited, pausing {}s (concurrency {})",
+                            wait.as_secs_f32().ceil(),
+                            limiter.limit()
+                        ));
+                        queue.push_front(index);
+                    }
+                    Err(ApiError::Unauthorized) => {
+                        anyhow::bail!("Authentication failed. Please re-login to Granola.")
+                    }
+                    result => {
+                        limiter.on_success();
+                        handle(progress, index, result);
+                        progress.inc();
+                    }
+                }
+            }
+            Ok(end)
+        })
+    }
+}
+
+/// Additive-increase, multiplicative-decrease concurrency limit with a
+/// dispatch pause after each 429.
+struct Limiter {
+    max: usize,
+    limit: usize,
+    successes: usize,
+    /// 429s since the last success, for the default backoff.
+    strikes: u32,
+    paused_until: Option<Instant>,
+}
+
+impl Limiter {
+    fn new(max: usize) -> Self {
+        Limiter {
+            max: max.max(1),
+            limit: max.max(1),
+            successes: 0,
+            strikes: 0,
+            paused_until: None,
+        }
+    }
+
+    fn limit(&self) -> usize {
+        self.limit
+    }
+
+    fn ready(&self, now: Instant) -> bool {
+        self.paused_until.is_none_or(|until| now >= until)
+    }
+
+    /// Time left in the current pause, if any.
+    fn resume_in(&self, now: Instant) -> Option<Duration> {
+        self.paused_until
+            .map(|until| until.saturating_duration_since(now))
+            .filter(|wait| !wait.is_zero())
+    }
+
+    /// Any response that was not a 429: after `limit` of them in a row,
+    /// allow one more request in flight.
+    fn on_success(&mut self) {
+        self.strikes = 0;
+        self.successes += 1;
+        if self.successes >= self.limit && self.limit < self.max {
+            self.limit += 1;
+            self.successes = 0;
+        }
+    }
+
+    /// A 429: halve the limit and pause dispatch. Requests already in flight
+    /// when the pause began were sent at the old rate, so their 429s extend
+    /// the pause without halving again. Returns the pause length.
+    fn on_rate_limited(&mut self, retry_after: Option<Duration>, now: Instant) -> Duration {
+        self.strikes += 1;
+        self.successes = 0;
+        if self.ready(now) {
+            self.limit = (self.limit / 2).max(1);
+        }
+
+        let wait = retry_after.unwrap_or_else(|| {
+            let factor = 2u32.saturating_pow(self.strikes - 1);
+            DEFAULT_BACKOFF.saturating_mul(factor).min(MAX_BACKOFF)
+        });
+        let until = now + wait;
+        if self.paused_until.is_none_or(|current| until > current) {
+            self.paused_until = Some(until);
+        }
+        wait
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limit_halves_concurrency_down_to_one() {
+        let mut limiter = Limiter::new(8);
+        let now = Instant::now();
+
+        limiter.on_rate_limited(Some(Duration::ZERO), now);
+        assert_eq!(limiter.limit(), 4);
+        limiter.on_rate_limited(Some(Duration::ZERO), now);
+        limiter.on_rate_limited(Some(Duration::ZERO), now);
+        limiter.on_rate_limited(Some(Duration::ZERO), now);
+        assert_eq!(limiter.limit(), 1);
+    }
+
+    #[test]
+    fn rate_limits_during_a_pause_halve_only_once() {
+        let mut limiter = Limiter::new(8);
+        let now = Instant::now();
+
+        limiter.on_rate_limited(Some(Duration::from_secs(10)), now);
+        limiter.on_rate_limited(Some(Duration::from_secs(10)), now);
+
+        assert_eq!(limiter.limit(), 4);
+        assert!(!limiter.ready(now));
+        assert!(limiter.ready(now + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn retry_after_sets_the_pause() {
+        let mut limiter = Limiter::new(4);
+        let now = Instant::now();
+
+        let wait = limiter.on_rate_limited(Some(Duration::from_secs(7)), now);
+
+        assert_eq!(wait, Duration::from_secs(7));
+        assert_eq!(limiter.resume_in(now), Some(Duration::from_secs(7)));
+    }
+
+    #[test]
+    fn default_backoff_doubles_and_caps() {
+        let mut limiter = Limiter::new(1);
+        let mut now = Instant::now();
+        let mut waits = Vec::new();
+        for _ in 0..6 {
+            let wait = limiter.on_rate_limited(None, now);
+            now += wait;
+            waits.push(wait.as_secs());
+        }
+
+        assert_eq!(waits, vec![5, 10, 20, 40, 60, 60]);
+    }
+
+    #[test]
+    fn successes_ramp_concurrency_back_up() {
+        let mut limiter = Limiter::new(4);
+        let now = Instant::now();
+        limiter.on_rate_limited(Some(Duration::ZERO), now);
+        assert_eq!(limiter.limit(), 2);
+
+        limiter.on_success();
+        assert_eq!(limiter.limit(), 2);
+        limiter.on_success();
+        assert_eq!(limiter.limit(), 3);
+        for _ in 0..10 {
+            limiter.on_success();
+        }
+        assert_eq!(limiter.limit(), 4);
+    }
+}
@@ -0,0 +1,300 @@
+//! Document sync: incremental fetches with a periodic full reconciliation.
+//!
+//! A full document fetch grows with the whole meeting history, so ordinary
+//! syncs ask only for documents updated since the last successful one (the
+//! `last_sync_documents` watermark). An incremental fetch cannot see
+//! deletions, though: a deleted meeting simply stops being listed. So every
+//! [`RECONCILE_INTERVAL`], or on `grans sync documents --full`, the whole
+//! list is fetched and local documents the server no longer has are
+//! soft-deleted, the way Granola itself marks them.
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use log::debug;
+use rusqlite::Connection;
+
+use crate::api::ApiClient;
+use crate::db::sync::{
+    self, SyncStats, missing_documents, soft_delete_documents, upsert_documents,
+};
+use crate::output::format::OutputMode;
+use crate::output::progress::create_spinner;
+
+use super::sync_granola::warn_if_source_account_empty;
+
+/// How far before the watermark an incremental fetch starts, to absorb
+/// clock skew between this machine and the server.
+const WATERMARK_OVERLAP: Duration = Duration::minutes(10);
+
+/// How often a sync falls back to a full fetch to catch deletions.
+const RECONCILE_INTERVAL: Duration = Duration::days(7);
+
+/// `last_sync_*` entity recording the last full reconciliation.
+const RECONCILE_ENTITY: &str = "documents_reconcile";
+
+/// Which documents a sync asks the server for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum FetchPlan {
+    /// Only documents updated after the given RFC3339 time.
+    Incremental { updated_after: String },
+    /// Every document, followed by deletion reconciliation.
+    Full,
+}
+
+/// Decide between an incremental and a full fetch. Full when forced, when
+/// documents have never synced (or the watermark is unreadable), or when
+/// the last reconciliation is missing or older than [`RECONCILE_INTERVAL`].
+pub(super) fn plan_fetch(
+    conn: &Connection,
+    force_full: bool,
+    now: DateTime<Utc>,
+) -> Result<FetchPlan> {
+    if force_full {
+        return Ok(FetchPlan::Full);
+    }
+
+    let Some(watermark) = parse_stamp(sync::get_last_sync_time(conn, "documents")?) else {
+        return Ok(FetchPlan::Full);
+    };
+    match parse_stamp(sync::get_last_sync_time(conn, RECONCILE_ENTITY)?) {
+        Some(reconciled) if now - reconciled < RECONCILE_INTERVAL => {}
+        _ => return Ok(FetchPlan::Full),
+    }
+
+    Ok(FetchPlan::Incremental {
+        updated_after: (watermark - WATERMARK_OVERLAP).to_rfc3339_opts(SecondsFormat::Secs, true),
+    })
+}
+
+fn parse_stamp(raw: Option<String>) -> Option<DateTime<Utc>> {
+    raw.and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+        .map(|ts| ts.with_timezone(&Utc))
+}
+
+/// The outcome of one document sync.
+#[derive(Debug, Default, Clone, Copy)]
+pub(super) struct DocumentSync {
+    pub stats: SyncStats,
+    pub fetched: usize,
+    pub full: bool,
+    /// Documents soft-deleted (or, on a dry run, that would be).
+    pub deleted: usize,
+}
+
+impl DocumentSync {
+    pub fn mode(&self) -> &'static str {
+        if self.full { "full" } else { "incremental" }
+    }
+}
+
+/// `grans sync documents`.
+pub(super) fn sync_documents(
+    conn: &Connection,
+    full: bool,
+    dry_run: bool,
+    token: Option<&str>,
+    mode: OutputMode,
+) -> Result<()> {
+    debug!("sync_documents (full={}, dry_run={})", full, dry_run);
+    let token = crate::api::resolve_token(token)?;
+    let source_account = super::account_record::record_source_account(conn, &token, dry_run)?;
+    let client = ApiClient::new(token)?;
+
+    let spinner = create_spinner("Fetching documents from API...");
+    let result =
+        sync_documents_with_client(conn, &client, dry_run, source_account.as_deref(), full);
+    spinner.finish_and_clear();
+    let result = result?;
+
+    eprintln!(
+        "[grans] Fetched {} documents ({} sync)",
+        result.fetched,
+        result.mode()
+    );
+    print_document_sync(&result, dry_run, mode);
+    Ok(())
+}
+
+/// Fetch documents per [`plan_fetch`], upsert them and, on a full fetch,
+/// soft-delete local documents the server no longer lists. Records the
+/// `documents` watermark (and the reconciliation time) unless `dry_run`.
+pub(super) fn sync_documents_with_client(
+    conn: &Connection,
+    client: &ApiClient,
+    dry_run: bool,
+    source_account: Option<&str>,
+    force_full: bool,
+) -> Result<DocumentSync> {
+    let plan = plan_fetch(conn, force_full, Utc::now())?;
+    debug!("document fetch plan: {:?}", plan);
+
+    let fetched = match &plan {
+        FetchPlan::Incremental { updated_after } => client.get_documents(Some(updated_after))?,
+        FetchPlan::Full => client.get_documents(None)?,
+    };
+    let documents = fetched.docs;
+    debug!("Fetched {} documents from API", documents.len());
+
+    let full = plan == FetchPlan::Full;
+    let missing = if full {
+        // An incremental fetch that returns nothing is normal; a full one
+        // against a populated database is the empty-account signature, and
+        // must not be read as "every meeting was deleted".
+        warn_if_source_account_empty(conn, documents.len(), source_account);
+        if documents.is_empty() {
+            Vec::new()
+        } else if !fetched.complete {
+            // Documents past the last page received are not missing, just
+            // unseen; deleting them would remove real meetings.
+            eprintln!(
+                "[grans] Warning: could not confirm the document list was complete \
+                 (the last page was full but had no cursor); skipping deletion of \
+                 documents the server did not list."
+            );
+            Vec::new()
+        } else {
+            let remote: HashSet<&str> = documents.iter().filter_map(|d| d.id.as_deref()).collect();
+            missing_documents(conn, &remote, source_account)?
+        }
+    } else {
+        Vec::new()
+    };
+
+    if dry_run {
+        return Ok(DocumentSync {
+            stats: SyncStats {
+                inserted: documents.len(),
+                updated: 0,
+                unchanged: 0,
+                errors: 0,
+            },
+            fetched: documents.len(),
+            full,
+            deleted: missing.len(),
+        });
+    }
+
+    let stats = upsert_documents(conn, &documents, source_account)?;
+    let deleted = soft_delete_documents(conn, &missing)?;
+    if deleted > 0 {
+        debug!(
+            "Soft-deleted documents missing from the server: {:?}",
+            missing
+        );
+    }
+
+    sync::set_last_sync_time(conn, "documents")?;
+    if full {
+        sync::set_last_sync_time(conn, RECONCILE_ENTITY)?;
+    }
+    crate::db::event_links::link_events(conn)?;
+
+    Ok(DocumentSync {
+        stats,
+        fetched: documents.len(),
+        full,
+        deleted,
+    })
+}
+
+fn print_document_sync(result: &DocumentSync, dry_run: bool, mode: OutputMode) {
+    match mode {
+        OutputMode::Json => {
+            println!(
+                "{}",
+                serde_json::json!({
+                    "action": "sync_documents",
+                    "dry_run": dry_run,
+                    "mode": result.mode(),
+                    "inserted": result.stats.inserted,
+                    "updated": result.stats.updated,
+                    "unchanged": result.stats.unchanged,
+                    "deleted": result.deleted,
+                    "errors": result.stats.errors,
+                })
+            );
+        }
+        _ => {
+            let prefix = if dry_run { "[dry-run] " } else { "" };
+            println!(
+                "{}Sync documents ({}): {} inserted, {} updated, {} unchanged, {} deleted",
+                prefix,
+                result.mode(),
+                result.stats.inserted,
+                result.stats.updated,
+                result.stats.unchanged,
+                result.deleted
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_fixtures::build_test_db;
+    use serde_json::json;
+
+    fn now() -> DateTime<Utc> {
+        "2026-03-10T12:00:00Z".parse().unwrap()
+    }
+
+    fn stamp(conn: &Connection, entity: &str, ts: &str) {
+        conn.execute(
+            "INSERT OR REPLACE INTO metadata (key, value) VALUES (?1, ?2)",
+            rusqlite::params![format!("last_sync_{}", entity), ts],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn first_sync_is_full() {
+        let conn = build_test_db(&json!({}));
+
+        assert_eq!(plan_fetch(&conn, false, now()).unwrap(), FetchPlan::Full);
+    }
+
+    #[test]
+    fn recent_reconcile_fetches_since_the_watermark_with_overlap() {
+        let conn = build_test_db(&json!({}));
+        stamp(&conn, "documents", "2026-03-10T11:00:00+00:00");
+        stamp(&conn, RECONCILE_ENTITY, "2026-03-08T12:00:00+00:00");
+
+        assert_eq!(
+            plan_fetch(&conn, false, now()).unwrap(),
+            FetchPlan::Incremental {
+                updated_after: "2026-03-10T10:50:00Z".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn stale_or_missing_reconcile_is_full() {
+        let conn = build_test_db(&json!({}));
+        stamp(&conn, "documents", "2026-03-10T11:00:00+00:00");
+        assert_eq!(plan_fetch(&conn, false, now()).unwrap(), FetchPlan::Full);
+
+        stamp(&conn, RECONCILE_ENTITY, "2026-03-01T12:00:00+00:00");
+        assert_eq!(plan_fetch(&conn, false, now()).unwrap(), FetchPlan::Full);
+    }
+
+    #[test]
+    fn force_full_overrides_a_fresh_watermark() {
+        let conn = build_test_db(&json!({}));
+        stamp(&conn, "documents", "2026-03-10T11:00:00+00:00");
+        stamp(&conn, RECONCILE_ENTITY, "2026-03-10T11:00:00+00:00");
+
+        assert_eq!(plan_fetch(&conn, true, now()).unwrap(), FetchPlan::Full);
+    }
+
+    #[test]
+    fn unparseable_watermark_is_full() {
+        let conn = build_test_db(&json!({}));
+        stamp(&conn, "documents", "yesterday");
+        stamp(&conn, RECONCILE_ENTITY, "2026-03-10T11:00:00+00:00");
+
+        assert_eq!(plan_fetch(&conn, false, now()).unwrap(), FetchPlan::Full);
+    }
+}
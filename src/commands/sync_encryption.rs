@@ -0,0 +1,152 @@
+//! `grans remote encryption` and `grans dropbox encryption`: encrypting the
+//! database before it leaves this machine.
+
+use anyhow::{Context, Result, bail};
+
+use crate::cli::args::EncryptionAction;
+use crate::output::progress::create_spinner;
+use crate::sync::config::SyncConfig;
+use crate::sync::crypto::{REMOTE_KEY_PATH, SyncKey, WrappedKey};
+
+use super::sync::{open_store, read_secret, upload_bytes};
+
+/// Where a passphrase can be supplied without a prompt.
+const PASSPHRASE_ENV: &str = "GRANS_SYNC_PASSPHRASE";
+
+pub(super) fn run(action: &EncryptionAction, cli: &str) -> Result<()> {
+    match action {
+        EncryptionAction::Enable => enable(cli),
+        EncryptionAction::Unlock => unlock(cli),
+        EncryptionAction::Disable => disable(cli),
+    }
+}
+
+fn enable(cli: &str) -> Result<()> {
+    let mut config = SyncConfig::load()?;
+    let store = open_store(&config)?;
+
+    if let Some(fingerprint) = &config.encryption_key {
+        println!(
+            "Pushes to {} are already encrypted (key {}).",
+            store.describe(),
+            fingerprint
+        );
+        return Ok(());
+    }
+    // One key per remote, so every machine can read what any other pushed.
+    if store.stat(REMOTE_KEY_PATH)?.is_some() {
+        bail!(
+            "{} already holds an encryption key. Run '{} encryption unlock' to use it here.",
+            store.describe(),
+            cli
+        );
+    }
+
+    let passphrase = read_new_passphrase()?;
+    let spinner = create_spinner("Sealing the key...");
+    let key = SyncKey::generate();
+    let wrapped = WrappedKey::seal(&key, &passphrase);
+    spinner.finish_and_clear();
+    let wrapped = wrapped?;
+
+    // Kept here before the remote copy is written, so a failed upload
+    // leaves an unused key rather than a remote one nobody holds.
+    let kept_in = key.save()?;
+    upload_bytes(
+        store.as_ref(),
+        &serde_json::to_vec_pretty(&wrapped)?,
+        REMOTE_KEY_PATH,
+    )?;
+
+    config.encryption_key = Some(key.fingerprint());
+    config.save()?;
+
+    println!(
+        "Encryption is on (key {}), kept in {}.",
+        key.fingerprint(),
+        kept_in
+    );
+    println!(
+        "The next push replaces the copy on {} with an encrypted one.",
+        store.describe()
+    );
+    println!(
+        "On each other machine, run '{} encryption unlock' with the same passphrase. \
+         Without it, a machine that loses its keychain cannot read the remote copy.",
+        cli
+    );
+    Ok(())
+}
+
+fn unlock(cli: &str) -> Result<()> {
+    let mut config = SyncConfig::load()?;
+    let store = open_store(&config)?;
+
+    if store.stat(REMOTE_KEY_PATH)?.is_none() {
+        bail!(
+            "{} holds no encryption key; run '{} encryption enable' to set one up.",
+            store.describe(),
+            cli
+        );
+    }
+    let wrapped: WrappedKey = serde_json::from_slice(&store.download(REMOTE_KEY_PATH)?)
+        .context("the key file on the remote is malformed")?;
+
+    let fingerprint = match SyncKey::load(&wrapped.fingerprint)? {
+        Some(key) => key.fingerprint(),
+        None => {
+            let passphrase = read_secret("Passphrase", PASSPHRASE_ENV)?;
+            let spinner = create_spinner("Unsealing the key...");
+            let opened = wrapped.open(&passphrase);
+            spinner.finish_and_clear();
+            let key = opened
+                .with_context(|| format!("could not unlock the key on {}", store.describe()))?;
+            key.save()?;
+            key.fingerprint()
+        }
+    };
+
+    config.encryption_key = Some(fingerprint.clone());
+    config.save()?;
+
+    println!(
+        "Unlocked key {}. Pushes from this machine are encrypted, and pulls decrypt.",
+        fingerprint
+    );
+    Ok(())
+}
+
+fn disable(cli: &str) -> Result<()> {
+    let mut config = SyncConfig::load()?;
+    let Some(fingerprint) = config.encryption_key.take() else {
+        println!("Pushes from this machine are not encrypted.");
+        return Ok(());
+    };
+    config.save()?;
+
+    println!(
+        "Pushes from this machine are no longer encrypted; the next one replaces the encrypted copy."
+    );
+    println!(
+        "Key {} stays in the keychain, so encrypted copies can still be pulled. \
+         Run '{} encryption disable' on every machine, or the next push from one \
+         that still encrypts puts an encrypted copy back.",
+        fingerprint, cli
+    );
+    Ok(())
+}
+
+/// Read a new passphrase, asking twice when it is typed, since a mistyped one
+/// would seal the key away for good.
+fn read_new_passphrase() -> Result<String> {
+    if let Ok(value) = std::env::var(PASSPHRASE_ENV)
+        && !value.is_empty()
+    {
+        return Ok(value);
+    }
+    let passphrase = read_secret("Passphrase", PASSPHRASE_ENV)?;
+    if read_secret("Repeat passphrase", PASSPHRASE_ENV)? != passphrase {
+        bail!("The passphrases do not match");
+    }
+    Ok(passphrase)
+}
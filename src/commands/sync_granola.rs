@@ -11,12 +11,14 @@ use crate::api::ApiClient;
 use crate::cli::args::SyncAction;
 use crate::db::accounts;
 use crate::db::sync::{
-    self, SyncStats, upsert_calendar_events, upsert_calendars_from_selection, upsert_documents,
-    upsert_people, upsert_recipes, upsert_templates,
+    self, SyncStats, upsert_calendar_events, upsert_calendars_from_selection, upsert_people,
+    upsert_recipes, upsert_templates,
 };
 use crate::output::format::OutputMode;
 use crate::output::progress::create_spinner;
 
+use super::sync_backfill::Backfill;
+use super::sync_documents::{sync_documents, sync_documents_with_client};
 use super::sync_panels::sync_panels;
 use super::sync_transcripts::{sync_single_transcript, sync_transcripts};
 
@@ -33,12 +35,13 @@ pub fn run(
             // Full sync: all entity types
             sync_all(conn, dry_run, token, mode)
         }
-        Some(SyncAction::Documents) => sync_documents(conn, dry_run, token, mode),
+        Some(SyncAction::Documents { full }) => sync_documents(conn, *full, dry_run, token, mode),
         Some(SyncAction::Transcripts {
             document_id,
             limit,
             since,
             delay_ms,
+            concurrency,
             retry,
             embed,
         }) => {
@@ -48,7 +51,10 @@ pub fn run(
                     conn,
                     *limit,
                     since.as_deref(),
-                    *delay_ms,
+                    &Backfill {
+                        concurrency: *concurrency,
+                        delay_ms: *delay_ms,
+                    },
                     *retry,
                     dry_run,
                     token,
@@ -71,12 +77,16 @@ pub fn run(
             limit,
             since,
             delay_ms,
+            concurrency,
             retry,
         }) => sync_panels(
             conn,
             *limit,
             since.as_deref(),
-            *delay_ms,
+            &Backfill {
+                concurrency: *concurrency,
+                delay_ms: *delay_ms,
+            },
             *retry,
             dry_run,
             token,
@@ -104,12 +114,17 @@ pub(super) fn sync_all(
 
     // Documents
     eprintln!("[grans] Syncing documents...");
-    match sync_documents_with_client(conn, &client, dry_run, source_account.as_deref()) {
-        Ok(stats) => {
-            total_stats.documents = stats;
+    match sync_documents_with_client(conn, &client, dry_run, source_account.as_deref(), false) {
+        Ok(result) => {
+            total_stats.documents = result.stats;
+            total_stats.documents_deleted = result.deleted;
             eprintln!(
-                "[grans] Documents: {} inserted, {} updated, {} unchanged",
-                stats.inserted, stats.updated, stats.unchanged
+                "[grans] Documents ({}): {} inserted, {} updated, {} unchanged, {} deleted",
+                result.mode(),
+                result.stats.inserted,
+                result.stats.updated,
+                result.stats.unchanged,
+                result.deleted
             );
         }
         Err(e) => eprintln!("[grans] Documents sync failed: {}", e),
@@ -176,6 +191,7 @@ pub(super) fn sync_all(
 #[derive(Default)]
 struct FullSyncStats {
     documents: SyncStats,
+    documents_deleted: usize,
     people: SyncStats,
     events: SyncStats,
     templates: SyncStats,
@@ -194,6 +210,7 @@ fn print_full_sync_summary(stats: &FullSyncStats, dry_run: bool, mode: OutputMod
                         "inserted": stats.documents.inserted,
                         "updated": stats.documents.updated,
                         "unchanged": stats.documents.unchanged,
+                        "deleted": stats.documents_deleted,
                     },
                     "people": {
                         "inserted": stats.people.inserted,
@@ -221,8 +238,11 @@ fn print_full_sync_summary(stats: &FullSyncStats, dry_run: bool, mode: OutputMod
             println!();
             println!("{}Sync complete:", prefix);
             println!(
-                "  Documents:  {} inserted, {} updated, {} unchanged",
-                stats.documents.inserted, stats.documents.updated, stats.documents.unchanged
+                "  Documents:  {} inserted, {} updated, {} unchanged, {} deleted",
+                stats.documents.inserted,
+                stats.documents.updated,
+                stats.documents.unchanged,
+                stats.documents_deleted
             );
             println!(
                 "  People:     {} inserted, {} updated",
@@ -248,65 +268,6 @@ fn print_full_sync_summary(stats: &FullSyncStats, dry_run: bool, mode: OutputMod
 // Individual sync functions
 // ============================================================================
 
-fn sync_documents(
-    conn: &Connection,
-    dry_run: bool,
-    token: Option<&str>,
-    mode: OutputMode,
-) -> Result<()> {
-    debug!("sync_documents (dry_run={})", dry_run);
-    let token = crate::api::resolve_token(token)?;
-    let source_account = super::account_record::record_source_account(conn, &token, dry_run)?;
-    let client = ApiClient::new(token)?;
-
-    let spinner = create_spinner("Fetching documents from API...");
-    let documents = client.get_documents()?;
-    spinner.finish_and_clear();
-    debug!("Fetched {} documents from API", documents.len());
-    eprintln!("[grans] Fetched {} documents", documents.len());
-    warn_if_source_account_empty(conn, documents.len(), source_account.as_deref());
-
-    let stats = if dry_run {
-        SyncStats {
-            inserted: documents.len(),
-            updated: 0,
-            unchanged: 0,
-            errors: 0,
-        }
-    } else {
-        upsert_documents(conn, &documents, source_account.as_deref())?
-    };
-
-    print_sync_stats("documents", &stats, dry_run, mode);
-
-    if !dry_run {
-        sync::set_last_sync_time(conn, "documents")?;
-    }
-
-    Ok(())
-}
-
-fn sync_documents_with_client(
-    conn: &Connection,
-    client: &ApiClient,
-    dry_run: bool,
-    source_account: Option<&str>,
-) -> Result<SyncStats> {
-    let documents = client.get_documents()?;
-    warn_if_source_account_empty(conn, documents.len(), source_account);
-
-    if dry_run {
-        return Ok(SyncStats {
-            inserted: documents.len(),
-            updated: 0,
-            unchanged: 0,
-            errors: 0,
-        });
-    }
-
-    upsert_documents(conn, &documents, source_account)
-}
-
 fn sync_people(
     conn: &Connection,
     dry_run: bool,
@@ -404,7 +365,9 @@ fn sync_calendars(
             errors: 0,
         }
     } else {
-        upsert_calendar_events(conn, &events, source_account.as_deref())?
+        let stats = upsert_calendar_events(conn, &events, source_account.as_deref())?;
+        crate::db::event_links::link_events(conn)?;
+        stats
     };
 
     print_sync_stats("calendar events", &stats, dry_run, mode);
@@ -449,7 +412,9 @@ fn sync_calendars_with_client(
         });
     }
 
-    upsert_calendar_events(conn, &events, source_account)
+    let stats = upsert_calendar_events(conn, &events, source_account)?;
+    crate::db::event_links::link_events(conn)?;
+    Ok(stats)
 }
 
 fn sync_templates(
@@ -622,7 +587,11 @@ fn empty_account_warning(
 
 /// Print the empty-account warning when it applies. A diagnostic must not
 /// fail the sync, so evaluation errors are logged rather than propagated.
-fn warn_if_source_account_empty(conn: &Connection, fetched: usize, source_account: Option<&str>) {
+pub(super) fn warn_if_source_account_empty(
+    conn: &Connection,
+    fetched: usize,
+    source_account: Option<&str>,
+) {
     match empty_account_warning(conn, fetched, source_account) {
         Ok(Some(warning)) => eprintln!("[grans] {}", warning),
         Ok(None) => {}
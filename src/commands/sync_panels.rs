@@ -5,7 +5,7 @@ use chrono::Utc;
 use log::debug;
 use rusqlite::Connection;
 
-use crate::api::ApiError;
+use crate::api::{ApiClient, ApiError};
 use crate::db::panels::{
     clear_panel_sync_log_entry, count_panel_sync_failures, find_documents_without_panels,
     insert_panels_from_api, log_panel_sync_failure,
@@ -15,11 +15,13 @@ use crate::output::format::OutputMode;
 use crate::output::progress::SyncProgress;
 use crate::query::dates::build_date_range;
 
+use super::sync_backfill::{Backfill, BackfillEnd};
+
 pub(super) fn sync_panels(
     conn: &Connection,
     limit: Option<usize>,
     since: Option<&str>,
-    delay_ms: u64,
+    backfill: &Backfill,
     retry: bool,
     dry_run: bool,
     token: Option<&str>,
@@ -139,79 +141,72 @@ pub(super) fn sync_panels(
 
     let resolved_token = crate::api::resolve_token(token)?;
     let _ = super::account_record::record_source_account(conn, &resolved_token, dry_run)?;
+    let client = ApiClient::new(resolved_token)?;
 
     let mut fetched = 0;
     let mut errors = 0;
     let mut not_found = 0;
+    let mut done = 0;
     let total = documents.len();
+    let ids: Vec<&str> = documents.iter().map(|d| d.id.as_str()).collect();
     let mut progress = SyncProgress::new(total as u64);
 
-    for (i, doc) in documents.iter().enumerate() {
-        let title = doc.title.as_deref().unwrap_or("(untitled)");
-        let date = doc
-            .created_at
-            .as_deref()
-            .and_then(|s| s.get(..10))
-            .unwrap_or("unknown date");
-        progress.println(&format!(
-            "[{}/{}] Fetching panels: {} ({}) [{}]",
-            i + 1,
-            total,
-            title,
-            doc.id,
-            date
-        ));
-
-        match crate::api::fetch_panels(&resolved_token, &doc.id) {
-            Ok(panels) => {
-                if panels.is_empty() {
-                    progress.println("  -> No panels available");
-                    not_found += 1;
-                    log_panel_sync_failure(conn, &doc.id, "not_found").ok();
-                } else {
-                    match insert_panels_from_api(conn, &doc.id, &panels) {
-                        Ok(count) => {
-                            progress.println(&format!("  -> Stored {} panel(s)", count));
-                            fetched += 1;
-                            clear_panel_sync_log_entry(conn, &doc.id).ok();
-                        }
-                        Err(e) => {
-                            progress.println(&format!("  -> Error storing: {}", e));
-                            errors += 1;
-                            log_panel_sync_failure(conn, &doc.id, "error").ok();
+    let end = backfill.run(
+        &client,
+        &ids,
+        &mut progress,
+        |client, id| client.fetch_panels(id),
+        |progress, index, result| {
+            let doc = &documents[index];
+            done += 1;
+            let title = doc.title.as_deref().unwrap_or("(untitled)");
+            let date = doc
+                .created_at
+                .as_deref()
+                .and_then(|s| s.get(..10))
+                .unwrap_or("unknown date");
+            progress.println(&format!(
+                "[{}/{}] Fetching panels: {} ({}) [{}]",
+                done, total, title, doc.id, date
+            ));
+
+            match result {
+                Ok(panels) => {
+                    if panels.is_empty() {
+                        progress.println("  -> No panels available");
+                        not_found += 1;
+                        log_panel_sync_failure(conn, &doc.id, "not_found").ok();
+                    } else {
+                        match insert_panels_from_api(conn, &doc.id, &panels) {
+                            Ok(count) => {
+                                progress.println(&format!("  -> Stored {} panel(s)", count));
+                                fetched += 1;
+                                clear_panel_sync_log_entry(conn, &doc.id).ok();
+                            }
+                            Err(e) => {
+                                progress.println(&format!("  -> Error storing: {}", e));
+                                errors += 1;
+                                log_panel_sync_failure(conn, &doc.id, "error").ok();
+                            }
                         }
                     }
                 }
+                Err(ApiError::NotFound) => {
+                    progress.println("  -> Not found on server");
+                    not_found += 1;
+                    log_panel_sync_failure(conn, &doc.id, "not_found").ok();
+                }
+                Err(e) => {
+                    progress.println(&format!("  -> Error: {}", e));
+                    errors += 1;
+                    log_panel_sync_failure(conn, &doc.id, "error").ok();
+                }
             }
-            Err(ApiError::NotFound) => {
-                progress.println("  -> Not found on server");
-                not_found += 1;
-                log_panel_sync_failure(conn, &doc.id, "not_found").ok();
-            }
-            Err(ApiError::Unauthorized) => {
-                progress.finish();
-                anyhow::bail!("Authentication failed. Please re-login to Granola.");
-            }
-            Err(ApiError::RateLimited) => {
-                progress.println("  -> Rate limited, stopping sync");
-                break;
-            }
-            Err(e) => {
-                progress.println(&format!("  -> Error: {}", e));
-                errors += 1;
-                log_panel_sync_failure(conn, &doc.id, "error").ok();
-            }
-        }
-
-        progress.inc();
-
-        // Rate limiting
-        if i < total - 1 {
-            crate::api::client::sleep_with_jitter(delay_ms);
-        }
-    }
+        },
+    );
 
     progress.finish();
+    let end = end?;
 
     match mode {
         OutputMode::Json => {
@@ -224,6 +219,7 @@ pub(super) fn sync_panels(
                     "not_found": not_found,
                     "errors": errors,
                     "skipped": skipped,
+                    "rate_limited": end == BackfillEnd::RateLimited,
                 })
             );
         }
@@ -9,9 +9,10 @@
 use anyhow::Result;
 use rusqlite::Connection;
 
-use crate::cli::args::DEFAULT_SYNC_DELAY_MS;
+use crate::cli::args::{DEFAULT_SYNC_CONCURRENCY, DEFAULT_SYNC_DELAY_MS};
 use crate::output::format::OutputMode;
 
+use super::sync_backfill::Backfill;
 use super::sync_panels::sync_panels;
 use super::sync_transcripts::sync_transcripts;
 
@@ -31,7 +32,10 @@ pub fn run_complete_sync(
         conn,
         None,
         None,
-        DEFAULT_SYNC_DELAY_MS,
+        &Backfill {
+            concurrency: DEFAULT_SYNC_CONCURRENCY,
+            delay_ms: DEFAULT_SYNC_DELAY_MS,
+        },
         retry,
         dry_run,
         token,
@@ -43,7 +47,10 @@ pub fn run_complete_sync(
         conn,
         None,
         None,
-        DEFAULT_SYNC_DELAY_MS,
+        &Backfill {
+            concurrency: DEFAULT_SYNC_CONCURRENCY,
+            delay_ms: DEFAULT_SYNC_DELAY_MS,
+        },
         retry,
         dry_run,
         token,
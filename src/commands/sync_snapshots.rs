@@ -0,0 +1,305 @@
+//! `grans remote snapshots`/`restore` and their `grans dropbox` twins:
+//! the dated copies of the remote database push keeps.
+
+use anyhow::{Result, bail};
+use chrono::{FixedOffset, Utc};
+use serde::Serialize;
+
+use crate::output::format::{OutputMode, format_size};
+use crate::sync::config::SyncConfig;
+use crate::sync::content_hash::hash_file;
+use crate::sync::crypto::{self, SyncKey};
+use crate::sync::delta::RemoteDatabase;
+use crate::sync::metadata::SyncMetadata;
+use crate::sync::remote::RemoteStore;
+use crate::sync::snapshot::{REMOTE_SNAPSHOTS_PATH, Snapshot, SnapshotIndex};
+
+use super::sync::{fetch_database, key_for, open_store, push_key, upload_bytes};
+use super::sync_status::{format_number, format_short_time};
+
+/// A snapshot as `snapshots` reports it.
+#[derive(Debug, Serialize)]
+struct SnapshotSummary<'a> {
+    name: &'a str,
+    created: u64,
+    size_bytes: u64,
+    content_hash: &'a str,
+    /// Whether the remote database currently holds this snapshot.
+    current: bool,
+    metadata: Option<&'a SyncMetadata>,
+}
+
+#[derive(Debug, Serialize)]
+struct SnapshotList<'a> {
+    remote: String,
+    kept: usize,
+    /// Newest first.
+    snapshots: Vec<SnapshotSummary<'a>>,
+}
+
+/// Read the snapshot index, decrypting it if it is encrypted. A remote
+/// without one has no snapshots.
+fn load_index(store: &dyn RemoteStore, cli: &str) -> Result<SnapshotIndex> {
+    if store.stat(REMOTE_SNAPSHOTS_PATH)?.is_none() {
+        return Ok(SnapshotIndex::default());
+    }
+    let mut bytes = store.download(REMOTE_SNAPSHOTS_PATH)?;
+    if let Some(fingerprint) = crypto::sealed_with(&bytes) {
+        bytes = crypto::decrypt(&key_for(&fingerprint, store, cli)?, &bytes)?;
+    }
+    serde_json::from_slice(&bytes).map_err(|e| {
+        anyhow::anyhow!(
+            "the snapshot index on {} is malformed: {}",
+            store.describe(),
+            e
+        )
+    })
+}
+
+/// Write the snapshot index, encrypted when pushes are: it holds the same
+/// statistics as the sync metadata.
+fn save_index(store: &dyn RemoteStore, index: &SnapshotIndex, key: Option<&SyncKey>) -> Result<()> {
+    let json = serde_json::to_vec_pretty(index)?;
+    let bytes = match key {
+        Some(key) => crypto::encrypt(key, &json)?,
+        None => json,
+    };
+    upload_bytes(store, &bytes, REMOTE_SNAPSHOTS_PATH)
+}
+
+/// Save the pruned index, then delete the copies it no longer uses.
+fn save_pruned(
+    store: &dyn RemoteStore,
+    index: &SnapshotIndex,
+    unused: &[String],
+    key: Option<&SyncKey>,
+) -> Result<()> {
+    save_index(store, index, key)?;
+    for path in unused {
+        if let Err(e) = store.delete(path) {
+            eprintln!("Warning: could not remove {}: {}", path, e);
+        }
+    }
+    Ok(())
+}
+
+/// Keep a snapshot of what the remote now holds, unless one already holds
+/// it, and drop the oldest beyond `keep`.
+pub(super) fn record(
+    store: &dyn RemoteStore,
+    metadata: Option<&SyncMetadata>,
+    key: Option<&SyncKey>,
+    keep: usize,
+    cli: &str,
+) -> Result<()> {
+    if keep == 0 {
+        return Ok(());
+    }
+    let Some(remote) = RemoteDatabase::load(store)? else {
+        return Ok(());
+    };
+    let mut index = load_index(store, cli)?;
+    if remote.hash().is_some_and(|hash| index.holds(hash)) {
+        return Ok(());
+    }
+    let created = index.free_moment(Utc::now());
+    let Some((snapshot, copies)) = Snapshot::of(&remote, created, metadata.cloned()) else {
+        return Ok(());
+    };
+
+    for (from, to) in &copies {
+        if !index.uses(to) {
+            store.copy(from, to)?;
+        }
+    }
+    let name = snapshot.name.clone();
+    index.snapshots.push(snapshot);
+    let unused = index.prune(keep);
+    save_pruned(store, &index, &unused, key)?;
+
+    println!(
+        "Kept snapshot {} ({} of {} kept)",
+        name,
+        index.snapshots.len(),
+        keep
+    );
+    Ok(())
+}
+
+/// List the snapshots on the remote, after changing how many are kept if
+/// `keep` is given.
+pub(super) fn list(
+    keep: Option<usize>,
+    output_mode: OutputMode,
+    tz: &FixedOffset,
+    cli: &str,
+) -> Result<()> {
+    let mut config = SyncConfig::load()?;
+    let store = open_store(&config)?;
+    let mut index = load_index(store.as_ref(), cli)?;
+
+    if let Some(keep) = keep {
+        config.snapshots_kept = Some(keep);
+        config.save()?;
+        let before = index.snapshots.len();
+        let unused = index.prune(keep);
+        if index.snapshots.len() < before {
+            let key = push_key(&config, cli)?;
+            save_pruned(store.as_ref(), &index, &unused, key.as_ref())?;
+        }
+        if output_mode == OutputMode::Tty {
+            match keep {
+                0 => println!("Push no longer keeps snapshots.\n"),
+                n => println!("Push now keeps the last {} snapshot(s).\n", n),
+            }
+        }
+    }
+
+    let current = RemoteDatabase::load(store.as_ref())?;
+    let current_hash = current.as_ref().and_then(|r| r.hash());
+    let list = SnapshotList {
+        remote: store.describe(),
+        kept: config.snapshots_kept(),
+        snapshots: index
+            .snapshots
+            .iter()
+            .rev()
+            .map(|s| SnapshotSummary {
+                name: &s.name,
+                created: s.created,
+                size_bytes: s.size(),
+                content_hash: s.hash(),
+                current: current_hash == Some(s.hash()),
+                metadata: s.metadata.as_ref(),
+            })
+            .collect(),
+    };
+
+    match output_mode {
+        OutputMode::Json => println!("{}", serde_json::to_string_pretty(&list)?),
+        OutputMode::Tty => print_list_tty(&list, tz, cli),
+    }
+    Ok(())
+}
+
+fn print_list_tty(list: &SnapshotList, tz: &FixedOffset, cli: &str) {
+    use colored::Colorize;
+
+    if list.snapshots.is_empty() {
+        match list.kept {
+            0 => println!(
+                "No snapshots on {}. Run '{} snapshots --keep 5' to have push keep some.",
+                list.remote, cli
+            ),
+            _ => println!(
+                "No snapshots on {} yet; each push that changes it keeps one.",
+                list.remote
+            ),
+        }
+        return;
+    }
+
+    println!(
+        "{} on {} (keeping {})",
+        "Snapshots".bold(),
+        list.remote,
+        list.kept
+    );
+    println!(
+        "{:18} {:16} {:>10} {:>10} {:>12}",
+        "Name".bold(),
+        "Taken".bold(),
+        "Size".bold(),
+        "Documents".bold(),
+        "Transcripts".bold()
+    );
+    for snapshot in &list.snapshots {
+        let stats = snapshot.metadata.and_then(|m| m.index_db.as_ref());
+        let line = format!(
+            "{:18} {:16} {:>10} {:>10} {:>12}",
+            snapshot.name,
+            format_short_time(snapshot.created, tz),
+            format_size(snapshot.size_bytes),
+            stats.map_or("—".to_string(), |s| format_number(s.document_count)),
+            stats.map_or("—".to_string(), |s| format_number(
+                s.documents_with_transcripts
+            )),
+        );
+        if snapshot.current {
+            println!("{} {}", line, "(current)".green());
+        } else {
+            println!("{}", line);
+        }
+    }
+    println!(
+        "\nRun '{}' to bring one back.",
+        format!("{} restore <name>", cli).cyan()
+    );
+}
+
+/// Replace the local database with a snapshot, through the same checks a
+/// pull makes. The remote copy is left alone: pushing afterwards is what
+/// rolls it back too.
+pub(super) fn restore(name: &str, force: bool, cli: &str) -> Result<()> {
+    let config = SyncConfig::load()?;
+    let store = open_store(&config)?;
+    let index = load_index(store.as_ref(), cli)?;
+
+    let Some(snapshot) = index.find(name) else {
+        bail!(
+            "{} holds no snapshot named '{}'; run '{} snapshots' to list them.",
+            store.describe(),
+            name,
+            cli
+        );
+    };
+    let Some(base) = store.stat(&snapshot.base)? else {
+        bail!(
+            "The files of snapshot {} are missing from {}",
+            name,
+            store.describe()
+        );
+    };
+    let remote = RemoteDatabase::at(&snapshot.base, base, Some(snapshot.manifest.clone()));
+
+    let db_path = crate::db::connection::default_db_path()?;
+    let local_hash = db_path.exists().then(|| hash_file(&db_path)).transpose()?;
+    if local_hash.as_deref() == Some(snapshot.hash()) {
+        println!(
+            "The local database already matches snapshot {}; nothing to download.",
+            name
+        );
+        return Ok(());
+    }
+    // Only what was last synced is sure to survive elsewhere.
+    if let Some(hash) = &local_hash
+        && config.last_synced_hash.as_ref() != Some(hash)
+        && !force
+    {
+        bail!(
+            "The local database has changes that were never pushed, which restoring \
+             would discard. Push them first, or run '{} restore {} --force' to discard them.",
+            cli,
+            name
+        );
+    }
+
+    if let Some(parent) = db_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let temp_path = db_path.with_extension("db.tmp");
+    let local = local_hash.as_deref().map(|hash| (db_path.as_path(), hash));
+    if let Err(e) = fetch_database(store.as_ref(), &remote, local, &temp_path, cli) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e);
+    }
+    std::fs::rename(&temp_path, &db_path)?;
+
+    println!("  Restored snapshot {} to {}", name, db_path.display());
+    println!(
+        "\nThe copy on {} is unchanged; run '{} push' to replace it with this one.",
+        store.describe(),
+        cli
+    );
+    Ok(())
+}
@@ -1,6 +1,6 @@
-//! Rendering for `grans dropbox status`.
+//! Rendering for `grans remote status` and `grans dropbox status`.
 //!
-//! Compares the local database against the copy on Dropbox without downloading
+//! Compares the local database against the remote copy without downloading
 //! it, using the metadata file that `push` uploads alongside the database.
 
 use std::path::Path;
@@ -11,10 +11,13 @@ use serde::Serialize;
 
 use crate::output::format::{OutputMode, format_size};
 use crate::sync::config::SyncConfig;
-use crate::sync::dropbox::{DropboxClient, FileMetadata, format_timestamp, parse_dropbox_time};
+use crate::sync::crypto;
+use crate::sync::delta::RemoteDatabase;
+use crate::sync::dropbox::format_timestamp;
 use crate::sync::metadata::SyncMetadata;
+use crate::sync::remote::{REMOTE_METADATA_PATH, RemoteStore};
 
-use super::sync::{REMOTE_DB_PATH, REMOTE_METADATA_PATH, get_access_token, get_file_mtime};
+use super::sync::{get_file_mtime, key_for, open_store};
 
 /// File information for status display
 #[derive(Debug, Clone, Serialize)]
@@ -42,12 +45,12 @@ impl FileInfo {
         }
     }
 
-    fn from_remote(meta: Option<&FileMetadata>) -> Self {
-        match meta {
-            Some(m) => Self {
+    fn from_remote(remote: Option<&RemoteDatabase>) -> Self {
+        match remote {
+            Some(r) => Self {
                 exists: true,
-                size_bytes: Some(m.size),
-                modified_time: parse_dropbox_time(&m.server_modified),
+                size_bytes: Some(r.size()),
+                modified_time: r.base.modified,
             },
             None => Self {
                 exists: false,
@@ -62,6 +65,12 @@ impl FileInfo {
 #[derive(Debug, Serialize)]
 struct SyncStatusData {
     authenticated: bool,
+    /// Backend kind: "dropbox", "folder", "webdav" or "s3".
+    remote_kind: &'static str,
+    /// Where the remote copy lives, once connected.
+    remote_location: Option<String>,
+    /// Fingerprint of the key pushes are encrypted with, if they are.
+    encryption_key: Option<String>,
     last_push_time: Option<u64>,
     last_pull_time: Option<u64>,
     local: Option<SyncMetadata>,
@@ -70,7 +79,7 @@ struct SyncStatusData {
     remote_db: FileInfo,
 }
 
-pub(super) fn status(output_mode: OutputMode, tz: &FixedOffset) -> Result<()> {
+pub(super) fn status(output_mode: OutputMode, tz: &FixedOffset, cli: &str) -> Result<()> {
     let config = SyncConfig::load()?;
 
     // Get local database path
@@ -82,24 +91,26 @@ pub(super) fn status(output_mode: OutputMode, tz: &FixedOffset) -> Result<()> {
     // Local file info
     let local_db_info = FileInfo::from_local(&db_path);
 
-    // Remote info (only if authenticated)
-    let (remote_metadata, remote_db_info) = if config.is_authenticated() {
-        match get_access_token(&config) {
-            Ok(access_token) => {
-                let client = DropboxClient::new(access_token)?;
-                let remote_meta = fetch_remote_metadata(&client);
-                let db_meta = client.get_metadata(REMOTE_DB_PATH).ok().flatten();
-
-                (remote_meta, FileInfo::from_remote(db_meta.as_ref()))
-            }
-            Err(_) => (None, FileInfo::from_remote(None)),
-        }
+    // Remote info (only if connected)
+    let store = if config.is_connected() {
+        open_store(&config).ok()
     } else {
-        (None, FileInfo::from_remote(None))
+        None
+    };
+    let (remote_metadata, remote_db_info) = match &store {
+        Some(store) => {
+            let remote_meta = fetch_remote_metadata(store.as_ref(), cli);
+            let remote_db = RemoteDatabase::load(store.as_ref()).ok().flatten();
+            (remote_meta, FileInfo::from_remote(remote_db.as_ref()))
+        }
+        None => (None, FileInfo::from_remote(None)),
     };
 
     let status_data = SyncStatusData {
-        authenticated: config.is_authenticated(),
+        authenticated: config.is_connected(),
+        remote_kind: config.remote().kind(),
+        remote_location: store.as_ref().map(|s| s.describe()),
+        encryption_key: config.encryption_key.clone(),
         last_push_time: config.last_push_time,
         last_pull_time: config.last_pull_time,
         local: local_metadata.clone(),
@@ -113,16 +124,30 @@ pub(super) fn status(output_mode: OutputMode, tz: &FixedOffset) -> Result<()> {
             println!("{}", serde_json::to_string_pretty(&status_data)?);
         }
         OutputMode::Tty => {
-            print_status_tty(&status_data, tz);
+            print_status_tty(&status_data, tz, cli);
         }
     }
 
     Ok(())
 }
 
-fn fetch_remote_metadata(client: &DropboxClient) -> Option<SyncMetadata> {
-    match client.download(REMOTE_METADATA_PATH) {
+fn fetch_remote_metadata(store: &dyn RemoteStore, cli: &str) -> Option<SyncMetadata> {
+    match store.download(REMOTE_METADATA_PATH) {
         Ok(bytes) => {
+            let bytes = match crypto::sealed_with(&bytes) {
+                Some(fingerprint) => {
+                    match key_for(&fingerprint, store, cli)
+                        .and_then(|key| crypto::decrypt(&key, &bytes))
+                    {
+                        Ok(plain) => plain,
+                        Err(e) => {
+                            eprintln!("Warning: Could not read remote metadata: {:#}", e);
+                            return None;
+                        }
+                    }
+                }
+                None => bytes,
+            };
             let json = String::from_utf8(bytes).ok()?;
             match serde_json::from_str(&json) {
                 Ok(meta) => Some(meta),
@@ -136,7 +161,7 @@ fn fetch_remote_metadata(client: &DropboxClient) -> Option<SyncMetadata> {
     }
 }
 
-fn print_status_tty(data: &SyncStatusData, tz: &FixedOffset) {
+fn print_status_tty(data: &SyncStatusData, tz: &FixedOffset, cli: &str) {
     use colored::Colorize;
 
     println!("{}", "Sync Status".bold());
@@ -148,11 +173,19 @@ fn print_status_tty(data: &SyncStatusData, tz: &FixedOffset) {
     } else {
         println!("Authentication: {}", "Not connected".red());
         println!(
-            "\nRun '{}' to connect to Dropbox.",
-            "grans dropbox init".cyan()
+            "\nRun '{}' to connect to a remote.",
+            format!("{} init", cli).cyan()
         );
         return;
     }
+    match &data.remote_location {
+        Some(location) => println!("Remote: {} ({})", location, data.remote_kind),
+        None => println!("Remote: {} ({})", "unreachable".yellow(), data.remote_kind),
+    }
+    match &data.encryption_key {
+        Some(fingerprint) => println!("Encryption: {} (key {})", "On".green(), fingerprint),
+        None => println!("Encryption: {}", "Off".dimmed()),
+    }
 
     // Last sync times
     if let Some(ts) = data.last_push_time {
@@ -319,7 +352,7 @@ fn print_comparison_row_with_warning(
     }
 }
 
-fn format_number(n: i64) -> String {
+pub(super) fn format_number(n: i64) -> String {
     if n >= 1_000_000 {
         format!("{:.1}M", n as f64 / 1_000_000.0)
     } else if n >= 1_000 {
@@ -329,7 +362,7 @@ fn format_number(n: i64) -> String {
     }
 }
 
-fn format_short_time(ts: u64, tz: &FixedOffset) -> String {
+pub(super) fn format_short_time(ts: u64, tz: &FixedOffset) -> String {
     use chrono::DateTime;
     let dt = DateTime::from_timestamp(ts as i64, 0)
         .unwrap_or_else(|| DateTime::from_timestamp(0, 0).unwrap());
@@ -394,6 +427,9 @@ mod tests {
     fn test_sync_status_data_serialization() {
         let data = SyncStatusData {
             authenticated: true,
+            remote_kind: "folder",
+            remote_location: Some("/mnt/nas/grans".to_string()),
+            encryption_key: None,
             last_push_time: Some(1737973800),
             last_pull_time: None,
             local: None,
@@ -414,5 +450,6 @@ mod tests {
         assert!(json.contains("authenticated"));
         assert!(json.contains("local_db"));
         assert!(json.contains("remote_db"));
+        assert!(json.contains("\"remote_kind\": \"folder\""));
     }
 }
@@ -0,0 +1,51 @@
+//! `grans topics`: what meetings have been about over a period.
+//!
+//! Clusters the stored chunk embeddings of the meetings in range (see
+//! [`crate::query::topics`]). Reads only stored vectors and the full-text
+//! indexes, so it never loads a model.
+
+use anyhow::{Result, bail};
+use rusqlite::Connection;
+
+use crate::cli::context::RunContext;
+use crate::output::format::OutputMode;
+use crate::query::dates::DateRange;
+use crate::query::filter::SearchTarget;
+
+pub fn topics(
+    conn: &Connection,
+    targets: &[SearchTarget],
+    date_range: Option<DateRange>,
+    count: usize,
+    ctx: &RunContext,
+) -> Result<()> {
+    if count == 0 {
+        bail!("--topics must be at least 1");
+    }
+    let report =
+        crate::query::topics::topic_report(conn, targets, date_range.as_ref(), count, &ctx.tz)?;
+
+    match ctx.output_mode {
+        OutputMode::Json => println!("{}", crate::output::json::to_json(&report)),
+        OutputMode::Tty => {
+            if report.topics.is_empty() {
+                println!("No embedded meetings in that range; run `grans embed` after syncing.");
+                return Ok(());
+            }
+            println!(
+                "{} topic(s) across {} meeting(s), weeks of {} to {}:\n",
+                report.topics.len(),
+                report.meetings,
+                report.weeks.first().map(String::as_str).unwrap_or_default(),
+                report.weeks.last().map(String::as_str).unwrap_or_default()
+            );
+            for (i, topic) in report.topics.iter().enumerate() {
+                println!(
+                    "{}\n",
+                    crate::output::table::format_topic(topic, i + 1, &ctx.tz)
+                );
+            }
+        }
+    }
+    Ok(())
+}
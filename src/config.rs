@@ -0,0 +1,88 @@
+//! User settings: `config.toml` in the data directory.
+//!
+//! The file is optional and every key in it is too; a missing file means
+//! every setting takes its built-in default. Settings that also have an
+//! environment variable treat the variable as a per-invocation override and
+//! this file as the standing preference.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use crate::platform::data_dir;
+
+/// Everything `config.toml` can set.
+#[derive(Deserialize, Default, Debug, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// Origin of the Granola API, e.g. `http://127.0.0.1:8080` to sync
+    /// against a local stand-in. See [`crate::api::endpoint`].
+    pub api_base_url: Option<String>,
+}
+
+impl Settings {
+    /// Load the settings file, or the defaults when there is none.
+    pub fn load() -> Result<Self> {
+        Self::load_from(&config_path()?)
+    }
+
+    /// Load settings from a specific file, or the defaults when it is absent.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+}
+
+/// Path to the settings file.
+pub fn config_path() -> Result<PathBuf> {
+    Ok(data_dir()?.join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn missing_file_gives_defaults() {
+        let dir = TempDir::new().unwrap();
+
+        let settings = Settings::load_from(&dir.path().join("config.toml")).unwrap();
+
+        assert_eq!(settings, Settings::default());
+    }
+
+    #[test]
+    fn reads_api_base_url() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "api_base_url = \"http://127.0.0.1:8080\"\n").unwrap();
+
+        let settings = Settings::load_from(&path).unwrap();
+
+        assert_eq!(
+            settings.api_base_url.as_deref(),
+            Some("http://127.0.0.1:8080")
+        );
+    }
+
+    #[test]
+    fn unknown_key_is_an_error_naming_the_file() {
+        // A typo'd key silently doing nothing is worse than a refusal.
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "api_base = \"http://127.0.0.1:8080\"\n").unwrap();
+
+        let err = Settings::load_from(&path).unwrap_err();
+
+        assert!(format!("{:#}", err).contains("config.toml"));
+    }
+}
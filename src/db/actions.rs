@@ -0,0 +1,348 @@
+//! Action items and decisions derived from panels, and their done marks.
+//!
+//! Items are extracted by [`crate::query::actions`] and rebuilt per document
+//! whenever its panels are replaced. Done marks live in their own table
+//! keyed by item ID, which is a hash of what the item says, so marking an
+//! item done survives re-syncing its panels.
+
+use std::collections::HashSet;
+
+use anyhow::{Result, bail};
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::query::actions::{ItemKind, extract_items};
+use crate::query::dates::DateRange;
+
+/// Metadata key set once existing panels have been extracted.
+const BACKFILL_KEY: &str = "action_items_backfilled";
+
+/// Hex digits of the item hash used as its ID.
+const ID_LEN: usize = 10;
+
+/// One item with its meeting, as listed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ActionItemRecord {
+    pub id: String,
+    pub kind: ItemKind,
+    pub text: String,
+    pub owner: Option<String>,
+    pub section: String,
+    /// RFC 3339 timestamp of when the item was marked done, if it was.
+    pub done_at: Option<String>,
+    pub document_id: String,
+    pub title: Option<String>,
+    /// When the meeting took place.
+    pub created_at: Option<String>,
+    /// The meeting creator (the user who recorded it), for `--mine`.
+    #[serde(skip)]
+    pub creator_name: Option<String>,
+    #[serde(skip)]
+    pub creator_email: Option<String>,
+}
+
+/// Which items to list.
+#[derive(Debug, Clone, Default)]
+pub struct ActionItemFilter {
+    /// Only items not marked done.
+    pub open: bool,
+    pub kind: Option<ItemKind>,
+    /// Only items from meetings in this range.
+    pub date_range: Option<DateRange>,
+}
+
+/// Stable ID for an item: the same wording in the same meeting always
+/// hashes the same, whichever panel listed it.
+pub fn item_id(document_id: &str, kind: ItemKind, text: &str) -> String {
+    let normalized = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    let digest = Sha256::digest(format!(
+        "{}\0{}\0{}",
+        document_id,
+        kind.as_str(),
+        normalized
+    ));
+    digest
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>()[..ID_LEN]
+        .to_string()
+}
+
+/// Re-extract a document's items from its stored panels, replacing any it
+/// had. Call inside the transaction that replaced the panels. Returns the
+/// number of items stored; an item listed by two panels is stored once.
+pub fn refresh_document_items(conn: &Connection, document_id: &str) -> Result<usize> {
+    conn.execute(
+        "DELETE FROM action_items WHERE document_id = ?1",
+        [document_id],
+    )?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, content_markdown FROM panels
+         WHERE document_id = ?1 AND deleted_at IS NULL AND content_markdown IS NOT NULL
+         ORDER BY created_at",
+    )?;
+    let panels = stmt
+        .query_map([document_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut insert = conn.prepare(
+        "INSERT OR IGNORE INTO action_items
+             (id, document_id, panel_id, kind, section, text, owner, position)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    )?;
+    let mut stored = 0;
+    let mut position = 0;
+    for (panel_id, markdown) in panels {
+        for item in extract_items(&markdown) {
+            stored += insert.execute(rusqlite::params![
+                item_id(document_id, item.kind, &item.text),
+                document_id,
+                panel_id,
+                item.kind.as_str(),
+                item.section,
+                item.text,
+                item.owner,
+                position,
+            ])?;
+            position += 1;
+        }
+    }
+    Ok(stored)
+}
+
+/// Extract items from every document's stored panels, once per database:
+/// panels synced before the action_items table existed are never re-synced
+/// on their own. Later calls return immediately. Returns the number of items
+/// stored.
+pub fn backfill_action_items(conn: &Connection) -> Result<usize> {
+    let done: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM metadata WHERE key = ?1)",
+        [BACKFILL_KEY],
+        |row| row.get(0),
+    )?;
+    if done {
+        return Ok(0);
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    let document_ids = {
+        let mut stmt = tx.prepare("SELECT DISTINCT document_id FROM panels")?;
+        stmt.query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    let mut stored = 0;
+    for document_id in document_ids {
+        stored += refresh_document_items(&tx, &document_id)?;
+    }
+    tx.execute(
+        "INSERT OR REPLACE INTO metadata (key, value) VALUES (?1, ?2)",
+        rusqlite::params![BACKFILL_KEY, chrono::Utc::now().to_rfc3339()],
+    )?;
+    tx.commit()?;
+    Ok(stored)
+}
+
+/// Items matching `filter`, newest meeting first and in panel order within
+/// a meeting. Items from deleted meetings are left out.
+pub fn list_action_items(
+    conn: &Connection,
+    filter: &ActionItemFilter,
+) -> Result<Vec<ActionItemRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT a.id, a.kind, a.text, a.owner, a.section, s.done_at,
+                a.document_id, d.title, d.created_at,
+                (SELECT full_name FROM document_people p
+                 WHERE p.document_id = a.document_id AND p.role = 'creator' LIMIT 1),
+                (SELECT email FROM document_people p
+                 WHERE p.document_id = a.document_id AND p.role = 'creator' LIMIT 1)
+         FROM action_items a
+         JOIN documents d ON d.id = a.document_id
+         LEFT JOIN action_item_status s ON s.item_id = a.id
+         WHERE d.deleted_at IS NULL
+         ORDER BY d.created_at DESC, a.position",
+    )?;
+    let records = stmt
+        .query_map([], |row| {
+            let kind: String = row.get(1)?;
+            Ok(ActionItemRecord {
+                id: row.get(0)?,
+                kind: ItemKind::parse(&kind).unwrap_or(ItemKind::Action),
+                text: row.get(2)?,
+                owner: row.get(3)?,
+                section: row.get(4)?,
+                done_at: row.get(5)?,
+                document_id: row.get(6)?,
+                title: row.get(7)?,
+                created_at: row.get(8)?,
+                creator_name: row.get(9)?,
+                creator_email: row.get(10)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(records
+        .into_iter()
+        .filter(|r| !filter.open || r.done_at.is_none())
+        .filter(|r| filter.kind.is_none_or(|k| k == r.kind))
+        .filter(|r| {
+            crate::embed::created_in_range(r.created_at.as_deref(), filter.date_range.as_ref())
+        })
+        .collect())
+}
+
+/// Resolve an item ID or unique ID prefix to the full ID.
+fn resolve_item_id(conn: &Connection, id: &str) -> Result<String> {
+    let mut stmt =
+        conn.prepare("SELECT id FROM action_items WHERE id LIKE ?1 || '%' ESCAPE '\\' LIMIT 2")?;
+    let escaped = id
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    let matches = stmt
+        .query_map([escaped], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    match matches.as_slice() {
+        [one] => Ok(one.clone()),
+        [] => {
+            // A mark can outlive its item (the panel was re-worded); allow
+            // clearing it by full ID.
+            let marked: Option<String> = conn
+                .query_row(
+                    "SELECT item_id FROM action_item_status WHERE item_id = ?1",
+                    [id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            match marked {
+                Some(id) => Ok(id),
+                None => bail!(
+                    "No action item with ID \"{}\"; see `grans actions list`",
+                    id
+                ),
+            }
+        }
+        _ => bail!("Action item ID \"{}\" is ambiguous; give more of it", id),
+    }
+}
+
+/// Mark items done (or open again when `done` is false). Returns the full
+/// IDs whose state changed; items already in the requested state are left
+/// as they were.
+pub fn set_items_done(conn: &Connection, ids: &[String], done: bool) -> Result<Vec<String>> {
+    let resolved = ids
+        .iter()
+        .map(|id| resolve_item_id(conn, id))
+        .collect::<Result<Vec<_>>>()?;
+
+    let tx = conn.unchecked_transaction()?;
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut changed = Vec::new();
+    let mut seen = HashSet::new();
+    for id in resolved {
+        if !seen.insert(id.clone()) {
+            continue;
+        }
+        let n = if done {
+            tx.execute(
+                "INSERT INTO action_item_status (item_id, done_at) VALUES (?1, ?2)
+                 ON CONFLICT(item_id) DO NOTHING",
+                rusqlite::params![id, now],
+            )?
+        } else {
+            tx.execute("DELETE FROM action_item_status WHERE item_id = ?1", [&id])?
+        };
+        if n > 0 {
+            changed.push(id);
+        }
+    }
+    tx.commit()?;
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_fixtures::{build_test_db, panels_state};
+
+    fn texts(records: &[ActionItemRecord]) -> Vec<&str> {
+        records.iter().map(|r| r.text.as_str()).collect()
+    }
+
+    #[test]
+    fn backfill_extracts_existing_panels_once() {
+        let conn = build_test_db(&panels_state());
+        assert_eq!(backfill_action_items(&conn).unwrap(), 4);
+        assert_eq!(backfill_action_items(&conn).unwrap(), 0);
+
+        let items = list_action_items(&conn, &ActionItemFilter::default()).unwrap();
+        assert_eq!(
+            texts(&items),
+            vec![
+                "Discussed Q1 roadmap and priorities.",
+                "Review the deployment plan",
+                "Schedule follow-up meeting",
+                "Prepare quarterly report by Friday."
+            ]
+        );
+        assert!(items.iter().all(|i| i.document_id == "doc-1"));
+        assert_eq!(items[0].kind, ItemKind::Decision);
+        assert_eq!(items[1].section, "Action Items");
+        assert_eq!(items[1].title.as_deref(), Some("AI Meeting"));
+    }
+
+    #[test]
+    fn done_marks_survive_re_extraction() {
+        let conn = build_test_db(&panels_state());
+        backfill_action_items(&conn).unwrap();
+        let items = list_action_items(&conn, &ActionItemFilter::default()).unwrap();
+        let first = items[1].id.clone();
+
+        let changed = set_items_done(&conn, &[first[..6].to_string()], true).unwrap();
+        assert_eq!(changed, vec![first.clone()]);
+        // Marking again changes nothing.
+        assert!(
+            set_items_done(&conn, std::slice::from_ref(&first), true)
+                .unwrap()
+                .is_empty()
+        );
+
+        refresh_document_items(&conn, "doc-1").unwrap();
+        let open = ActionItemFilter {
+            open: true,
+            ..Default::default()
+        };
+        let items = list_action_items(&conn, &open).unwrap();
+        assert_eq!(items.len(), 3);
+        assert!(items.iter().all(|i| i.id != first));
+
+        set_items_done(&conn, &[first], false).unwrap();
+        assert_eq!(list_action_items(&conn, &open).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn unknown_id_is_an_error() {
+        let conn = build_test_db(&panels_state());
+        backfill_action_items(&conn).unwrap();
+        assert!(set_items_done(&conn, &["zzzz".to_string()], true).is_err());
+    }
+
+    #[test]
+    fn item_ids_ignore_case_and_spacing() {
+        assert_eq!(
+            item_id("d", ItemKind::Action, "Send  the deck"),
+            item_id("d", ItemKind::Action, "send the deck")
+        );
+        assert_ne!(
+            item_id("d", ItemKind::Action, "send the deck"),
+            item_id("d", ItemKind::Decision, "send the deck")
+        );
+    }
+}
@@ -0,0 +1,615 @@
+//! Person aliases: emails and names the user has merged into one person.
+//!
+//! An identity is an email address or a name (an attendee's full name, a
+//! people directory name, or a detected speaker name). Merged identities
+//! share a group; see `v021_person_aliases.sql`. Lookups that take a person
+//! query widen it to the whole group through [`attendee_condition`],
+//! [`crate::query::speaker::with_aliases`], [`canonical_name`] and the person
+//! profile.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use anyhow::{Result, bail};
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+
+use crate::query::aliases::{email_matches_name, local_part, name_key, org_stem, similar_names};
+
+/// How many candidates an ambiguous-identity error lists.
+const MAX_LISTED: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AliasKind {
+    Email,
+    Name,
+}
+
+impl AliasKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AliasKind::Email => "email",
+            AliasKind::Name => "name",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        if s == "email" {
+            AliasKind::Email
+        } else {
+            AliasKind::Name
+        }
+    }
+}
+
+/// One identity: an email or a name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Alias {
+    pub kind: AliasKind,
+    /// As first seen, for display.
+    pub display: String,
+    /// ASCII-lowercased, to compare with SQLite's `lower()`.
+    #[serde(skip)]
+    pub value: String,
+}
+
+impl Alias {
+    pub fn email(email: &str) -> Self {
+        Alias {
+            kind: AliasKind::Email,
+            display: email.trim().to_string(),
+            value: email.trim().to_ascii_lowercase(),
+        }
+    }
+
+    pub fn name(name: &str) -> Self {
+        Alias {
+            kind: AliasKind::Name,
+            display: name.trim().to_string(),
+            value: name.trim().to_ascii_lowercase(),
+        }
+    }
+}
+
+/// The identities merged into one person.
+#[derive(Debug, Clone, Serialize)]
+pub struct AliasGroup {
+    pub id: String,
+    pub aliases: Vec<Alias>,
+}
+
+impl AliasGroup {
+    /// The group's emails, lowercased.
+    pub fn emails(&self) -> impl Iterator<Item = &str> {
+        self.aliases
+            .iter()
+            .filter(|a| a.kind == AliasKind::Email)
+            .map(|a| a.value.as_str())
+    }
+
+    /// The group's names, as displayed.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.aliases
+            .iter()
+            .filter(|a| a.kind == AliasKind::Name)
+            .map(|a| a.display.as_str())
+    }
+
+    pub fn has_email(&self, email: &str) -> bool {
+        self.emails().any(|e| e.eq_ignore_ascii_case(email.trim()))
+    }
+
+    pub fn has_name(&self, name: &str) -> bool {
+        self.names().any(|n| n.eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Whether any identity in the group contains `needle`, ignoring ASCII
+    /// case like a SQL `LIKE`.
+    pub fn mentions(&self, needle: &str) -> bool {
+        let needle = needle.trim().to_ascii_lowercase();
+        self.aliases.iter().any(|a| a.value.contains(&needle))
+    }
+}
+
+/// The name a merged person is shown under (their first merged name), for
+/// a name merged into someone.
+pub fn canonical_name<'a>(groups: &'a [AliasGroup], name: &str) -> Option<&'a str> {
+    groups
+        .iter()
+        .find(|g| g.has_name(name))
+        .and_then(|g| g.names().next())
+}
+
+/// SQL condition matching a `document_people` row (aliased `dp`) against a
+/// person query bound at `p` as a `%query%` LIKE pattern: the row's email or
+/// name contains the query, or the row is an identity merged with one that
+/// does. `p` appears three times, so an anonymous `?` needs three binds.
+pub(crate) fn attendee_condition(dp: &str, p: &str) -> String {
+    format!(
+        "({dp}.email LIKE {p} OR {dp}.full_name LIKE {p}
+          OR EXISTS (SELECT 1 FROM person_aliases hit
+                     JOIN person_aliases pa ON pa.person_id = hit.person_id
+                     WHERE hit.value LIKE {p}
+                       AND ((pa.kind = 'email' AND pa.value = lower({dp}.email))
+                         OR (pa.kind = 'name' AND pa.value = lower({dp}.full_name)))))"
+    )
+}
+
+/// Every merged person, in merge order.
+pub fn alias_groups(conn: &Connection) -> Result<Vec<AliasGroup>> {
+    let mut stmt = conn.prepare(
+        "SELECT person_id, kind, value, display FROM person_aliases
+         ORDER BY created_at, rowid",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut groups: Vec<AliasGroup> = Vec::new();
+    for (id, kind, value, display) in rows {
+        let alias = Alias {
+            kind: AliasKind::parse(&kind),
+            display,
+            value,
+        };
+        match groups.iter_mut().find(|g| g.id == id) {
+            Some(group) => group.aliases.push(alias),
+            None => groups.push(AliasGroup {
+                id,
+                aliases: vec![alias],
+            }),
+        }
+    }
+    Ok(groups)
+}
+
+/// The group an identity belongs to, if it was merged.
+fn group_id_of(conn: &Connection, alias: &Alias) -> Result<Option<String>> {
+    Ok(conn
+        .query_row(
+            "SELECT person_id FROM person_aliases WHERE kind = ?1 AND value = ?2",
+            [alias.kind.as_str(), alias.value.as_str()],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// Every identity the database knows: emails (with the names each was
+/// listed under) and names.
+#[derive(Debug, Default)]
+struct KnownIdentities {
+    /// Lowercased email to its display form and listed names.
+    emails: BTreeMap<String, (String, BTreeSet<String>)>,
+    /// Lowercased name to its display form, for every name anywhere.
+    names: BTreeMap<String, String>,
+    /// Names that carry no email: detected speakers and email-less
+    /// attendees or directory entries.
+    bare_names: BTreeSet<String>,
+}
+
+fn known_identities(conn: &Connection) -> Result<KnownIdentities> {
+    let mut known = KnownIdentities::default();
+    let mut stmt = conn.prepare(
+        "SELECT email, full_name FROM document_people
+         UNION ALL SELECT email, name FROM people",
+    )?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (email, name) in rows {
+        let name = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+        let email = email
+            .map(|e| e.trim().to_string())
+            .filter(|e| e.contains('@'));
+        if let Some(name) = &name {
+            known
+                .names
+                .entry(name.to_ascii_lowercase())
+                .or_insert_with(|| name.clone());
+        }
+        match email {
+            Some(email) => {
+                let entry = known
+                    .emails
+                    .entry(email.to_ascii_lowercase())
+                    .or_insert_with(|| (email.clone(), BTreeSet::new()));
+                entry.1.extend(name);
+            }
+            None => known
+                .bare_names
+                .extend(name.map(|n| n.to_ascii_lowercase())),
+        }
+    }
+    for speaker in crate::db::transcripts::distinct_speaker_names(conn)? {
+        let key = speaker.trim().to_ascii_lowercase();
+        known.names.entry(key.clone()).or_insert(speaker);
+        known.bare_names.insert(key);
+    }
+    Ok(known)
+}
+
+/// Resolve what the user typed to one known identity. Anything with an `@`
+/// is an email and must match exactly; a name matches exactly or as the
+/// only name containing it.
+pub fn resolve_alias(conn: &Connection, query: &str) -> Result<Alias> {
+    let known = known_identities(conn)?;
+    let needle = query.trim().to_ascii_lowercase();
+    if needle.is_empty() {
+        bail!("Give an email or a name");
+    }
+
+    if needle.contains('@') {
+        return match known.emails.get(&needle) {
+            Some((display, _)) => Ok(Alias::email(display)),
+            None => bail!("No attendee or person has the email \"{}\"", query.trim()),
+        };
+    }
+
+    if let Some(display) = known.names.get(&needle) {
+        return Ok(Alias::name(display));
+    }
+    let hits: Vec<&String> = known
+        .names
+        .iter()
+        .filter(|(key, _)| key.contains(&needle))
+        .map(|(_, display)| display)
+        .collect();
+    match hits.as_slice() {
+        [] => bail!(
+            "No attendee, person or speaker is named \"{}\"",
+            query.trim()
+        ),
+        [one] => Ok(Alias::name(one)),
+        many => {
+            let shown: Vec<&str> = many.iter().take(MAX_LISTED).map(|s| s.as_str()).collect();
+            bail!(
+                "\"{}\" matches {} names: {}{}; give the full name",
+                query.trim(),
+                many.len(),
+                shown.join(", "),
+                if many.len() > shown.len() {
+                    ", ..."
+                } else {
+                    ""
+                }
+            )
+        }
+    }
+}
+
+/// Merge two identities into one person. Either may already belong to a
+/// merged person; merging two merged people joins them. Returns the
+/// resulting group.
+pub fn merge(conn: &Connection, a: &str, b: &str) -> Result<AliasGroup> {
+    let (a, b) = (resolve_alias(conn, a)?, resolve_alias(conn, b)?);
+    if a == b {
+        bail!(
+            "\"{}\" and \"{}\" are the same identity",
+            a.display,
+            b.display
+        );
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    let group_a = group_id_of(&tx, &a)?;
+    let group_b = group_id_of(&tx, &b)?;
+    let id = group_a
+        .clone()
+        .or_else(|| group_b.clone())
+        .unwrap_or_else(|| format!("{}:{}", a.kind.as_str(), a.value));
+    if let (Some(ga), Some(gb)) = (&group_a, &group_b)
+        && ga != gb
+    {
+        tx.execute(
+            "UPDATE person_aliases SET person_id = ?1 WHERE person_id = ?2",
+            [ga, gb],
+        )?;
+    }
+    let now = chrono::Utc::now().to_rfc3339();
+    for alias in [&a, &b] {
+        tx.execute(
+            "INSERT INTO person_aliases (kind, value, display, person_id, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(kind, value) DO NOTHING",
+            rusqlite::params![alias.kind.as_str(), alias.value, alias.display, id, now],
+        )?;
+    }
+    tx.commit()?;
+
+    alias_groups(conn)?
+        .into_iter()
+        .find(|g| g.id == id)
+        .ok_or_else(|| anyhow::anyhow!("merged person {} disappeared", id))
+}
+
+/// Split an identity back out of its merged person. A person left with a
+/// single identity is dissolved. Returns the identity that was removed.
+pub fn unmerge(conn: &Connection, query: &str) -> Result<Alias> {
+    // Look in the alias table first: an identity can outlive every meeting
+    // that mentioned it.
+    let trimmed = query.trim();
+    let kind = if trimmed.contains('@') {
+        AliasKind::Email
+    } else {
+        AliasKind::Name
+    };
+    let stored: Option<String> = conn
+        .query_row(
+            "SELECT display FROM person_aliases WHERE kind = ?1 AND value = ?2",
+            [kind.as_str(), trimmed.to_ascii_lowercase().as_str()],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let alias = match (stored, kind) {
+        (Some(display), AliasKind::Email) => Alias::email(&display),
+        (Some(display), AliasKind::Name) => Alias::name(&display),
+        (None, _) => resolve_alias(conn, trimmed)?,
+    };
+    let Some(id) = group_id_of(conn, &alias)? else {
+        bail!("\"{}\" is not merged with anyone", alias.display);
+    };
+
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "DELETE FROM person_aliases WHERE kind = ?1 AND value = ?2",
+        [alias.kind.as_str(), alias.value.as_str()],
+    )?;
+    let left: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM person_aliases WHERE person_id = ?1",
+        [&id],
+        |row| row.get(0),
+    )?;
+    if left < 2 {
+        tx.execute("DELETE FROM person_aliases WHERE person_id = ?1", [&id])?;
+    }
+    tx.commit()?;
+    Ok(alias)
+}
+
+/// Two identities that look like the same person, and why.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MergeSuggestion {
+    pub a: String,
+    pub b: String,
+    pub reason: String,
+}
+
+/// Identities that look like the same person and are not merged yet:
+/// one address at two domains of an organization, two addresses listed
+/// under one name, a name built into an address or listed for it, and
+/// names that are variants of each other.
+pub fn suggest_merges(conn: &Connection) -> Result<Vec<MergeSuggestion>> {
+    let known = known_identities(conn)?;
+    let groups = alias_groups(conn)?;
+    let merged = |a: &Alias, b: &Alias| {
+        groups
+            .iter()
+            .any(|g| g.aliases.iter().any(|x| x == a) && g.aliases.iter().any(|x| x == b))
+    };
+
+    let mut suggestions = Vec::new();
+    let mut seen: HashSet<(String, String)> = HashSet::new();
+    let mut push = |a: Alias, b: Alias, reason: String| {
+        if merged(&a, &b) {
+            return;
+        }
+        let key = if a.value <= b.value {
+            (a.value.clone(), b.value.clone())
+        } else {
+            (b.value.clone(), a.value.clone())
+        };
+        if seen.insert(key) {
+            suggestions.push(MergeSuggestion {
+                a: a.display,
+                b: b.display,
+                reason,
+            });
+        }
+    };
+
+    // Email pairs: bucket by local part within an organization, and by
+    // listed name.
+    let mut by_local: BTreeMap<(String, String), Vec<&str>> = BTreeMap::new();
+    let mut by_name: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for (email, (_, names)) in &known.emails {
+        if let Some(stem) = org_stem(email) {
+            by_local
+                .entry((local_part(email), stem))
+                .or_default()
+                .push(email);
+        }
+        for name in names.iter().map(|n| name_key(n)).collect::<BTreeSet<_>>() {
+            if name.contains(' ') {
+                by_name.entry(name).or_default().push(email);
+            }
+        }
+    }
+    let display_email = |e: &str| Alias::email(&known.emails[e].0);
+    for ((_, stem), emails) in &by_local {
+        for (i, a) in emails.iter().enumerate() {
+            for b in &emails[i + 1..] {
+                push(
+                    display_email(a),
+                    display_email(b),
+                    format!("same address at two {} domains", stem),
+                );
+            }
+        }
+    }
+    for emails in by_name.values() {
+        for (i, a) in emails.iter().enumerate() {
+            for b in &emails[i + 1..] {
+                let listed = known.emails[*a]
+                    .1
+                    .iter()
+                    .next()
+                    .cloned()
+                    .unwrap_or_default();
+                push(
+                    display_email(a),
+                    display_email(b),
+                    format!("both listed as {}", listed),
+                );
+            }
+        }
+    }
+
+    // Names without an email against emails.
+    for bare in &known.bare_names {
+        let display = &known.names[bare];
+        for (email, (_, listed)) in &known.emails {
+            let reason =
+                if let Some(same) = listed.iter().find(|l| name_key(l) == name_key(display)) {
+                    format!("listed as {}", same)
+                } else if let Some(similar) = listed.iter().find(|l| similar_names(l, display)) {
+                    format!("listed as {}", similar)
+                } else if listed.is_empty() && email_matches_name(email, display) {
+                    "address matches the name".to_string()
+                } else {
+                    continue;
+                };
+            push(Alias::name(display), display_email(email), reason);
+        }
+    }
+
+    // Name variants.
+    let names: Vec<&String> = known.names.values().collect();
+    for (i, a) in names.iter().enumerate() {
+        for b in &names[i + 1..] {
+            if similar_names(a, b) {
+                push(Alias::name(a), Alias::name(b), "similar names".to_string());
+            }
+        }
+    }
+
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_fixtures::build_test_db;
+    use serde_json::json;
+
+    fn attendee(email: &str, name: &str) -> serde_json::Value {
+        json!({"email": email, "details": {"person": {"name": {"fullName": name}}}})
+    }
+
+    fn state() -> serde_json::Value {
+        json!({
+            "documents": {
+                "d1": {"id": "d1", "title": "One", "created_at": "2026-01-05T10:00:00Z",
+                       "people": {"attendees": [attendee("dana@acme.com", "Dana Lee"), attendee("eli@acme.com", "Eli Park")]}},
+                "d2": {"id": "d2", "title": "Two", "created_at": "2026-02-05T10:00:00Z",
+                       "people": {"attendees": [attendee("dana@acme.io", "D. Lee"), attendee("eli.park@gmail.com", "Eli Park")]}}
+            },
+            "transcripts": {
+                "d2": [{"id": "u1", "document_id": "d2", "start_timestamp": "2026-02-05T10:01:00Z",
+                        "text": "Hello there everyone.", "source": "system", "speaker_name": "Dana L."}]
+            }
+        })
+    }
+
+    #[test]
+    fn merge_unmerge_and_groups() {
+        let conn = build_test_db(&state());
+        let group = merge(&conn, "dana@acme.com", "Dana L.").unwrap();
+        assert_eq!(group.aliases.len(), 2);
+
+        // Merging into an existing person extends it.
+        let group = merge(&conn, "DANA@acme.io", "dana l.").unwrap();
+        assert_eq!(group.aliases.len(), 3);
+        assert!(group.has_email("dana@acme.io"));
+        assert!(group.has_name("Dana L."));
+        assert_eq!(alias_groups(&conn).unwrap().len(), 1);
+
+        assert_eq!(
+            unmerge(&conn, "dana@acme.com").unwrap().display,
+            "dana@acme.com"
+        );
+        assert_eq!(alias_groups(&conn).unwrap()[0].aliases.len(), 2);
+        unmerge(&conn, "Dana L.").unwrap();
+        // A person left with one identity is dissolved.
+        assert!(alias_groups(&conn).unwrap().is_empty());
+        assert!(unmerge(&conn, "dana@acme.io").is_err());
+    }
+
+    #[test]
+    fn merging_two_people_joins_them() {
+        let conn = build_test_db(&state());
+        merge(&conn, "dana@acme.com", "Dana Lee").unwrap();
+        merge(&conn, "dana@acme.io", "D. Lee").unwrap();
+        let group = merge(&conn, "Dana Lee", "D. Lee").unwrap();
+        assert_eq!(group.aliases.len(), 4);
+        assert_eq!(alias_groups(&conn).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn resolve_alias_rejects_unknown_and_ambiguous() {
+        let conn = build_test_db(&state());
+        assert!(resolve_alias(&conn, "nobody@acme.com").is_err());
+        assert_eq!(resolve_alias(&conn, "eli").unwrap().display, "Eli Park");
+        let err = resolve_alias(&conn, "lee").unwrap_err().to_string();
+        assert!(
+            err.contains("Dana Lee") && err.contains("D. Lee"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn attendee_condition_widens_to_merged_identities() {
+        let conn = build_test_db(&state());
+        let count = |q: &str| -> i64 {
+            conn.query_row(
+                &format!(
+                    "SELECT COUNT(DISTINCT dp.document_id) FROM document_people dp WHERE {}",
+                    attendee_condition("dp", "?1")
+                ),
+                [format!("%{}%", q)],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        assert_eq!(count("dana@acme.com"), 1);
+        merge(&conn, "dana@acme.com", "dana@acme.io").unwrap();
+        assert_eq!(count("dana@acme.com"), 2);
+    }
+
+    #[test]
+    fn suggestions_skip_merged_pairs() {
+        let conn = build_test_db(&state());
+        let pairs = |conn: &Connection| -> Vec<(String, String)> {
+            suggest_merges(conn)
+                .unwrap()
+                .into_iter()
+                .map(|s| (s.a, s.b))
+                .collect()
+        };
+        let before = pairs(&conn);
+        let has = |list: &[(String, String)], a: &str, b: &str| {
+            list.iter()
+                .any(|(x, y)| (x == a && y == b) || (x == b && y == a))
+        };
+        assert!(has(&before, "dana@acme.com", "dana@acme.io"));
+        assert!(has(&before, "eli@acme.com", "eli.park@gmail.com"));
+        assert!(has(&before, "Dana L.", "dana@acme.com"));
+        assert!(has(&before, "Dana L.", "D. Lee") || has(&before, "Dana Lee", "Dana L."));
+        assert!(!has(&before, "eli@acme.com", "dana@acme.com"));
+
+        merge(&conn, "dana@acme.com", "dana@acme.io").unwrap();
+        assert!(!has(&pairs(&conn), "dana@acme.com", "dana@acme.io"));
+    }
+}
@@ -0,0 +1,442 @@
+//! Links between calendar events and the meetings that recorded them.
+//!
+//! A meeting started from the calendar names its event in
+//! `google_calendar_event_json`. One started by hand does not, and is
+//! linked to the event it was created during instead. The link table is
+//! derived data: [`link_events`] rebuilds it after documents or events
+//! sync.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::models::CalendarEvent;
+use crate::query::dates::DateRange;
+
+/// Metadata key holding when links were last rebuilt.
+const LINKED_KEY: &str = "event_documents_linked";
+
+/// How long before an event starts a recording still belongs to it:
+/// people start Granola while waiting for others to join.
+const EARLY_START_MINUTES: i64 = 15;
+
+/// A meeting linked to a calendar event.
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkedMeeting {
+    pub id: String,
+    pub title: Option<String>,
+    /// "event_id" when the meeting names the event, "time" when the link
+    /// was inferred from when the meeting was created.
+    pub method: String,
+    /// Whether the meeting has typed notes or AI panels.
+    pub has_notes: bool,
+    pub has_transcript: bool,
+}
+
+/// A calendar event with the meetings that recorded it.
+#[derive(Debug, Clone, Serialize)]
+pub struct EventWithMeetings {
+    #[serde(flatten)]
+    pub event: CalendarEvent,
+    pub meetings: Vec<LinkedMeeting>,
+}
+
+/// An event's time span, for matching meetings to it by time.
+#[derive(Debug, Clone)]
+struct TimedEvent {
+    id: String,
+    title_key: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+/// Rebuild the event/meeting links. Meetings that name a synced event link
+/// to it; meetings that name no event link to the event they were created
+/// during, if the time and title single one out. Deleted meetings are not
+/// linked. Returns the number of links.
+pub fn link_events(conn: &Connection) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM event_documents", [])?;
+    let mut linked = tx.execute(
+        "INSERT OR IGNORE INTO event_documents (event_id, document_id, method)
+         SELECT e.id, d.id, 'event_id'
+         FROM documents d
+         JOIN events e ON e.id = CASE WHEN json_valid(d.google_calendar_event_json)
+             THEN json_extract(d.google_calendar_event_json, '$.id') END
+         WHERE d.deleted_at IS NULL",
+        [],
+    )?;
+
+    let events = {
+        let mut stmt = tx.prepare(
+            "SELECT id, summary, start_time, end_time FROM events WHERE start_time IS NOT NULL",
+        )?;
+        stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter_map(|(id, summary, start, end)| {
+            let start = parse_ts(&start)?;
+            let end = end
+                .as_deref()
+                .and_then(parse_ts)
+                .unwrap_or(start)
+                .max(start);
+            Some(TimedEvent {
+                id,
+                title_key: title_key(summary.as_deref().unwrap_or("")),
+                start,
+                end,
+            })
+        })
+        .collect::<Vec<_>>()
+    };
+
+    // Meetings that name an event are left alone even when that event is
+    // not synced: the calendar said which event it was.
+    let documents = {
+        let mut stmt = tx.prepare(
+            "SELECT id, title, created_at FROM documents
+             WHERE deleted_at IS NULL AND created_at IS NOT NULL
+               AND CASE WHEN json_valid(google_calendar_event_json)
+                   THEN json_extract(google_calendar_event_json, '$.id') END IS NULL",
+        )?;
+        stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut insert = tx.prepare(
+        "INSERT OR IGNORE INTO event_documents (event_id, document_id, method)
+         VALUES (?1, ?2, 'time')",
+    )?;
+    for (document_id, title, created_at) in documents {
+        let Some(at) = parse_ts(&created_at) else {
+            continue;
+        };
+        if let Some(event) = event_at(&events, title.as_deref(), at) {
+            linked += insert.execute([&event.id, &document_id])?;
+        }
+    }
+    drop(insert);
+
+    tx.execute(
+        "INSERT OR REPLACE INTO metadata (key, value) VALUES (?1, ?2)",
+        rusqlite::params![LINKED_KEY, Utc::now().to_rfc3339()],
+    )?;
+    tx.commit()?;
+    Ok(linked)
+}
+
+/// Build the links if they never have been: databases synced before the
+/// link table existed have events and meetings but no links until the next
+/// sync.
+pub fn ensure_linked(conn: &Connection) -> Result<()> {
+    let linked: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM metadata WHERE key = ?1)",
+        [LINKED_KEY],
+        |row| row.get(0),
+    )?;
+    if !linked {
+        link_events(conn)?;
+    }
+    Ok(())
+}
+
+/// The event a meeting created at `at` recorded: the only event running
+/// then (or about to start), or the only one of several whose title matches
+/// the meeting's.
+fn event_at<'a>(
+    events: &'a [TimedEvent],
+    title: Option<&str>,
+    at: DateTime<Utc>,
+) -> Option<&'a TimedEvent> {
+    let lead = Duration::minutes(EARLY_START_MINUTES);
+    let running: Vec<&TimedEvent> = events
+        .iter()
+        .filter(|e| e.start - lead <= at && at <= e.end)
+        .collect();
+    if let [only] = running.as_slice() {
+        return Some(only);
+    }
+    let key = title_key(title.unwrap_or(""));
+    if key.is_empty() {
+        return None;
+    }
+    let mut titled = running.into_iter().filter(|e| e.title_key == key);
+    match (titled.next(), titled.next()) {
+        (Some(event), None) => Some(event),
+        _ => None,
+    }
+}
+
+/// Key under which titles that differ only in case and punctuation match.
+fn title_key(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_ts(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Linked meetings by event ID, oldest first within an event. Deleted
+/// meetings are left out.
+fn meetings_by_event(conn: &Connection) -> Result<HashMap<String, Vec<LinkedMeeting>>> {
+    let mut stmt = conn.prepare(
+        "SELECT l.event_id, d.id, d.title, l.method,
+                COALESCE(TRIM(d.notes_plain), '') != ''
+                    OR COALESCE(TRIM(d.notes_markdown), '') != ''
+                    OR EXISTS(SELECT 1 FROM panels p
+                              WHERE p.document_id = d.id AND p.deleted_at IS NULL),
+                EXISTS(SELECT 1 FROM transcript_utterances u WHERE u.document_id = d.id)
+         FROM event_documents l
+         JOIN documents d ON d.id = l.document_id
+         WHERE d.deleted_at IS NULL
+         ORDER BY d.created_at",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            LinkedMeeting {
+                id: row.get(1)?,
+                title: row.get(2)?,
+                method: row.get(3)?,
+                has_notes: row.get(4)?,
+                has_transcript: row.get(5)?,
+            },
+        ))
+    })?;
+    let mut by_event: HashMap<String, Vec<LinkedMeeting>> = HashMap::new();
+    for row in rows {
+        let (event_id, meeting) = row?;
+        by_event.entry(event_id).or_default().push(meeting);
+    }
+    Ok(by_event)
+}
+
+/// Events as listed by [`crate::db::calendars::list_events`], each with
+/// its linked meetings.
+pub fn list_events_with_meetings(
+    conn: &Connection,
+    calendar: Option<&str>,
+    date_range: Option<&DateRange>,
+) -> Result<Vec<EventWithMeetings>> {
+    let events = crate::db::calendars::list_events(conn, calendar, date_range)?;
+    let mut by_event = meetings_by_event(conn)?;
+    Ok(events
+        .into_iter()
+        .map(|event| {
+            let meetings = event
+                .id
+                .as_deref()
+                .and_then(|id| by_event.remove(id))
+                .unwrap_or_default();
+            EventWithMeetings { event, meetings }
+        })
+        .collect())
+}
+
+/// Events that ended by `now` with no linked meeting. Unless `all`, events
+/// the user declined and events with nobody else invited (focus time,
+/// reminders) are left out.
+pub fn unrecorded_events(
+    conn: &Connection,
+    calendar: Option<&str>,
+    date_range: Option<&DateRange>,
+    now: DateTime<Utc>,
+    all: bool,
+) -> Result<Vec<CalendarEvent>> {
+    let recorded: HashSet<String> = meetings_by_event(conn)?.into_keys().collect();
+    let events = crate::db::calendars::list_events(conn, calendar, date_range)?;
+    Ok(events
+        .into_iter()
+        .filter(|e| e.id.as_ref().is_some_and(|id| !recorded.contains(id)))
+        .filter(|e| ended_by(e, now))
+        .filter(|e| all || (!declined(e) && others_invited(e)))
+        .collect())
+}
+
+fn ended_by(event: &CalendarEvent, now: DateTime<Utc>) -> bool {
+    let time = |t: &Option<crate::models::EventDateTime>| {
+        t.as_ref()
+            .and_then(|t| t.date_time.as_deref())
+            .and_then(parse_ts)
+    };
+    time(&event.end)
+        .or_else(|| time(&event.start))
+        .is_some_and(|end| end <= now)
+}
+
+fn declined(event: &CalendarEvent) -> bool {
+    event
+        .attendees
+        .iter()
+        .flatten()
+        .any(|a| a.is_self == Some(true) && a.response_status.as_deref() == Some("declined"))
+}
+
+fn others_invited(event: &CalendarEvent) -> bool {
+    event.attendees.as_ref().is_some_and(|a| a.len() >= 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_fixtures::build_test_db;
+    use serde_json::json;
+
+    fn state() -> serde_json::Value {
+        json!({
+            "documents": {
+                "doc-named": {
+                    "id": "doc-named", "title": "Weekly Sync",
+                    "created_at": "2026-01-20T15:02:00Z",
+                    "google_calendar_event": {"id": "ev-sync"}
+                },
+                "doc-early": {
+                    "id": "doc-early", "title": "Untitled",
+                    "created_at": "2026-01-21T13:50:00Z"
+                },
+                "doc-overlap": {
+                    "id": "doc-overlap", "title": "design review!",
+                    "created_at": "2026-01-22T10:05:00Z",
+                    "notes_plain": "Agreed on the layout."
+                },
+                "doc-adhoc": {
+                    "id": "doc-adhoc", "title": "Hallway chat",
+                    "created_at": "2026-01-23T18:00:00Z"
+                }
+            },
+            "events": [
+                {"id": "ev-sync", "summary": "Weekly Sync",
+                 "start_time": "2026-01-20T09:00:00-06:00", "end_time": "2026-01-20T09:30:00-06:00",
+                 "attendees": [{"email": "a@x.com"}, {"email": "b@x.com"}]},
+                {"id": "ev-1on1", "summary": "1:1",
+                 "start_time": "2026-01-21T14:00:00Z", "end_time": "2026-01-21T14:30:00Z",
+                 "attendees": [{"email": "a@x.com"}, {"email": "b@x.com"}]},
+                {"id": "ev-review", "summary": "Design Review",
+                 "start_time": "2026-01-22T10:00:00Z", "end_time": "2026-01-22T11:00:00Z",
+                 "attendees": [{"email": "a@x.com"}, {"email": "b@x.com"}]},
+                {"id": "ev-focus", "summary": "Focus time",
+                 "start_time": "2026-01-22T10:00:00Z", "end_time": "2026-01-22T12:00:00Z",
+                 "attendees": [{"email": "a@x.com", "self": true}]},
+                {"id": "ev-skipped", "summary": "Planning",
+                 "start_time": "2026-01-23T09:00:00Z", "end_time": "2026-01-23T10:00:00Z",
+                 "attendees": [{"email": "a@x.com"}, {"email": "b@x.com"}]},
+                {"id": "ev-declined", "summary": "All hands",
+                 "start_time": "2026-01-23T11:00:00Z", "end_time": "2026-01-23T12:00:00Z",
+                 "attendees": [{"email": "a@x.com", "self": true, "responseStatus": "declined"},
+                               {"email": "b@x.com"}]},
+                {"id": "ev-later", "summary": "Retro",
+                 "start_time": "2026-02-01T09:00:00Z", "end_time": "2026-02-01T10:00:00Z",
+                 "attendees": [{"email": "a@x.com"}, {"email": "b@x.com"}]}
+            ]
+        })
+    }
+
+    fn links(conn: &Connection) -> Vec<(String, String, String)> {
+        let mut stmt = conn
+            .prepare("SELECT event_id, document_id, method FROM event_documents ORDER BY event_id")
+            .unwrap();
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn links_by_event_id_then_by_time_and_title() {
+        let conn = build_test_db(&state());
+        assert_eq!(link_events(&conn).unwrap(), 3);
+        let pair = |e: &str, d: &str, m: &str| (e.to_string(), d.to_string(), m.to_string());
+        assert_eq!(
+            links(&conn),
+            vec![
+                // Started ten minutes early, the only event around then.
+                pair("ev-1on1", "doc-early", "time"),
+                // Overlaps focus time too; the title decides.
+                pair("ev-review", "doc-overlap", "time"),
+                pair("ev-sync", "doc-named", "event_id"),
+            ]
+        );
+
+        // Rebuilding replaces rather than accumulates.
+        conn.execute(
+            "UPDATE documents SET deleted_at = '2026-01-25T00:00:00Z' WHERE id = 'doc-early'",
+            [],
+        )
+        .unwrap();
+        assert_eq!(link_events(&conn).unwrap(), 2);
+    }
+
+    #[test]
+    fn ambiguous_overlap_without_matching_title_is_not_linked() {
+        let t = |s: &str| parse_ts(s).unwrap();
+        let event = |id: &str, title: &str| TimedEvent {
+            id: id.to_string(),
+            title_key: title_key(title),
+            start: t("2026-01-22T10:00:00Z"),
+            end: t("2026-01-22T11:00:00Z"),
+        };
+        let events = [event("a", "Design Review"), event("b", "Focus")];
+        let at = t("2026-01-22T10:05:00Z");
+        assert!(event_at(&events, Some("Notes"), at).is_none());
+        assert_eq!(
+            event_at(&events, Some("Design review"), at).unwrap().id,
+            "a"
+        );
+        assert!(event_at(&events, Some("Notes"), t("2026-01-22T09:40:00Z")).is_none());
+    }
+
+    #[test]
+    fn unrecorded_events_skip_future_declined_and_solo_events() {
+        let conn = build_test_db(&state());
+        link_events(&conn).unwrap();
+        let now = parse_ts("2026-01-25T00:00:00Z").unwrap();
+        let ids = |all: bool| -> Vec<String> {
+            unrecorded_events(&conn, None, None, now, all)
+                .unwrap()
+                .into_iter()
+                .filter_map(|e| e.id)
+                .collect()
+        };
+        assert_eq!(ids(false), vec!["ev-skipped"]);
+        assert_eq!(ids(true), vec!["ev-declined", "ev-skipped", "ev-focus"]);
+    }
+
+    #[test]
+    fn events_carry_their_meetings() {
+        let conn = build_test_db(&state());
+        link_events(&conn).unwrap();
+        let events = list_events_with_meetings(&conn, None, None).unwrap();
+        let review = events
+            .iter()
+            .find(|e| e.event.id.as_deref() == Some("ev-review"))
+            .unwrap();
+        assert_eq!(review.meetings.len(), 1);
+        assert_eq!(review.meetings[0].id, "doc-overlap");
+        assert!(review.meetings[0].has_notes);
+        assert!(!review.meetings[0].has_transcript);
+    }
+}
@@ -0,0 +1,149 @@
+use anyhow::Result;
+use rusqlite::Connection;
+
+use crate::query::dates::DateRange;
+
+/// The document columns an export renders, with the source account's email
+/// joined in from the accounts log.
+#[derive(Debug, Clone, Default)]
+pub struct ExportDocument {
+    pub id: String,
+    pub title: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub deleted_at: Option<String>,
+    pub notes_plain: Option<String>,
+    pub notes_markdown: Option<String>,
+    pub google_calendar_event_json: Option<String>,
+    pub source_account_id: Option<String>,
+    pub source_account_email: Option<String>,
+}
+
+/// One `document_people` row.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExportAttendee {
+    pub full_name: Option<String>,
+    pub email: Option<String>,
+    pub role: String,
+}
+
+/// Documents to export, oldest first so a first export writes in meeting order.
+pub fn list_export_documents(
+    conn: &Connection,
+    date_range: Option<&DateRange>,
+    include_deleted: bool,
+) -> Result<Vec<ExportDocument>> {
+    let mut sql = String::from(
+        "SELECT d.id, d.title, d.created_at, d.updated_at, d.deleted_at, d.notes_plain, d.notes_markdown,
+                d.google_calendar_event_json, d.source_account_id, a.email
+         FROM documents d
+         LEFT JOIN accounts a ON a.account_id = d.source_account_id
+         WHERE 1=1",
+    );
+    if !include_deleted {
+        sql.push_str(" AND d.deleted_at IS NULL");
+    }
+    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+
+    if let Some(range) = date_range {
+        if let Some(start) = &range.start {
+            sql.push_str(" AND d.created_at >= ?");
+            params.push(Box::new(start.to_rfc3339()));
+        }
+        if let Some(end) = &range.end {
+            sql.push_str(" AND d.created_at < ?");
+            params.push(Box::new(end.to_rfc3339()));
+        }
+    }
+
+    sql.push_str(" ORDER BY d.created_at, d.id");
+
+    let mut stmt = conn.prepare(&sql)?;
+    let param_refs: Vec<&dyn rusqlite::types::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+
+    let rows = stmt.query_map(param_refs.as_slice(), |row| {
+        Ok(ExportDocument {
+            id: row.get(0)?,
+            title: row.get(1)?,
+            created_at: row.get(2)?,
+            updated_at: row.get(3)?,
+            deleted_at: row.get(4)?,
+            notes_plain: row.get(5)?,
+            notes_markdown: row.get(6)?,
+            google_calendar_event_json: row.get(7)?,
+            source_account_id: row.get(8)?,
+            source_account_email: row.get(9)?,
+        })
+    })?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Attendees of a document as `document_people` records them: the creator
+/// first, then attendees in sync order.
+pub fn document_attendees(conn: &Connection, document_id: &str) -> Result<Vec<ExportAttendee>> {
+    let mut stmt = conn.prepare(
+        "SELECT full_name, email, role
+         FROM document_people
+         WHERE document_id = ?1
+         ORDER BY role = 'creator' DESC, rowid",
+    )?;
+
+    let rows = stmt.query_map([document_id], |row| {
+        Ok(ExportAttendee {
+            full_name: row.get(0)?,
+            email: row.get(1)?,
+            role: row.get(2)?,
+        })
+    })?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_fixtures::{build_test_db, meetings_state};
+
+    #[test]
+    fn lists_documents_oldest_first() {
+        let conn = build_test_db(&meetings_state());
+        let docs = list_export_documents(&conn, None, false).unwrap();
+
+        let ids: Vec<&str> = docs.iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, vec!["doc-1", "doc-2"]);
+    }
+
+    #[test]
+    fn joins_source_account_email() {
+        let conn = build_test_db(&meetings_state());
+        conn.execute(
+            "INSERT INTO accounts (account_id, email, first_seen_at) VALUES ('user_01', 'me@example.com', '2026-01-01T00:00:00Z')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE documents SET source_account_id = 'user_01' WHERE id = 'doc-1'",
+            [],
+        )
+        .unwrap();
+
+        let docs = list_export_documents(&conn, None, false).unwrap();
+
+        assert_eq!(
+            docs[0].source_account_email.as_deref(),
+            Some("me@example.com")
+        );
+        assert_eq!(docs[1].source_account_email, None);
+    }
+
+    #[test]
+    fn attendees_put_the_creator_first() {
+        let conn = build_test_db(&meetings_state());
+        let attendees = document_attendees(&conn, "doc-1").unwrap();
+
+        assert_eq!(attendees.len(), 2);
+        assert_eq!(attendees[0].role, "creator");
+        assert_eq!(attendees[1].full_name.as_deref(), Some("Bob Jones"));
+    }
+}
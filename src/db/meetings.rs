@@ -3,11 +3,14 @@ use std::collections::HashMap;
 use anyhow::Result;
 use rusqlite::Connection;
 
+use super::aliases::attendee_condition;
 use super::common::{DocumentRow, row_to_document};
 
 use crate::models::{Document, TranscriptUtterance};
+use crate::query::adjust::normalize_title;
 use crate::query::dates::DateRange;
-use crate::query::fts::sanitize_fts_query;
+use crate::query::filter::{MeetingFilters, SearchTarget};
+use crate::query::fts::parse_query;
 
 pub fn list_meetings(
     conn: &Connection,
@@ -69,11 +72,12 @@ fn list_meetings_by_person(
     include_deleted: bool,
 ) -> Result<Vec<Document>> {
     let pattern = format!("%{}%", person_query);
-    let mut sql = String::from(
+    let mut sql = format!(
         "SELECT DISTINCT d.id, d.title, d.created_at, d.updated_at, d.deleted_at, d.doc_type, d.notes_plain, d.notes_markdown, d.summary, d.people_json, d.google_calendar_event_json
          FROM documents d
          JOIN document_people dp ON d.id = dp.document_id
-         WHERE (dp.email LIKE ?1 OR dp.full_name LIKE ?1)",
+         WHERE {}",
+        attendee_condition("dp", "?1")
     );
     if !include_deleted {
         sql.push_str(" AND d.deleted_at IS NULL");
@@ -220,44 +224,33 @@ pub fn search_meetings(
     date_range: Option<&DateRange>,
     include_deleted: bool,
 ) -> Result<Vec<Document>> {
-    // Each enabled source contributes (doc_id, score) rows, all bm25-scored
-    // through FTS5. A document's rank is its best (lowest) bm25 score across
-    // sources; titles compete on relevance like every other source.
-    let mut union_parts: Vec<&str> = Vec::new();
-    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
-
-    let fts_query = sanitize_fts_query(query);
-
-    if search_titles {
-        union_parts.push(
-            "SELECT d.id AS doc_id, bm25(titles_fts) AS score FROM titles_fts JOIN documents d ON titles_fts.rowid = d.rowid WHERE titles_fts MATCH ?",
-        );
-        params.push(Box::new(fts_query.clone()));
-    }
-
-    if search_notes {
-        union_parts.push(
-            "SELECT d.id AS doc_id, bm25(notes_fts) AS score FROM notes_fts JOIN documents d ON notes_fts.rowid = d.rowid WHERE notes_fts MATCH ?",
-        );
-        params.push(Box::new(fts_query.clone()));
-    }
-
-    if search_transcripts {
-        union_parts.push(
-            "SELECT tu.document_id AS doc_id, bm25(transcript_fts) AS score FROM transcript_fts JOIN transcript_utterances tu ON transcript_fts.rowid = tu.rowid WHERE transcript_fts MATCH ?",
-        );
-        params.push(Box::new(fts_query.clone()));
-    }
-
-    if search_panels {
-        union_parts.push(
-            "SELECT p.document_id AS doc_id, bm25(panels_fts) AS score FROM panels_fts JOIN panels p ON panels_fts.rowid = p.rowid WHERE p.deleted_at IS NULL AND panels_fts MATCH ?",
-        );
-        params.push(Box::new(fts_query));
+    // Each requirement of the query contributes (doc_id, score, req) rows
+    // from the sources it applies to, all bm25-scored through FTS5. A
+    // document matches when every requirement has a row; its rank is its
+    // best (lowest) bm25 score across sources, and titles compete on
+    // relevance like every other source.
+    let query = parse_query(query)?;
+    let targets: Vec<SearchTarget> = [
+        (search_titles, SearchTarget::Titles),
+        (search_transcripts, SearchTarget::Transcripts),
+        (search_notes, SearchTarget::Notes),
+        (search_panels, SearchTarget::Panels),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, target)| enabled.then_some(target))
+    .collect();
+    let requirements = query.requirements(&targets);
+    if requirements.is_empty() || requirements.iter().any(|alts| alts.is_empty()) {
+        return Ok(Vec::new());
     }
 
-    if union_parts.is_empty() {
-        return Ok(Vec::new());
+    let mut union_parts: Vec<String> = Vec::new();
+    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+    for (req, alternatives) in requirements.iter().enumerate() {
+        for (target, fts_query) in alternatives {
+            union_parts.push(target_hits_sql(*target, req));
+            params.push(Box::new(fts_query.to_string()));
+        }
     }
 
     // MATERIALIZED stops the query flattener from pulling bm25() into the
@@ -267,11 +260,28 @@ pub fn search_meetings(
          SELECT d.id, d.title, d.created_at, d.updated_at, d.deleted_at, d.doc_type, d.notes_plain, d.notes_markdown, d.summary, d.people_json, d.google_calendar_event_json
          FROM documents d
          JOIN (SELECT doc_id, MIN(score) AS best_score
-               FROM hits GROUP BY doc_id) m ON d.id = m.doc_id
+               FROM hits GROUP BY doc_id
+               HAVING COUNT(DISTINCT req) = {}) m ON d.id = m.doc_id
          WHERE 1=1",
-        union_parts.join(" UNION ALL ")
+        union_parts.join(" UNION ALL "),
+        requirements.len()
     );
 
+    let exclusions = query.exclusions(&targets);
+    if !exclusions.is_empty() {
+        let parts: Vec<String> = exclusions
+            .iter()
+            .map(|(target, fts_query)| {
+                params.push(Box::new(fts_query.to_string()));
+                target_hits_sql(*target, 0)
+            })
+            .collect();
+        sql.push_str(&format!(
+            " AND d.id NOT IN (SELECT doc_id FROM ({}))",
+            parts.join(" UNION ALL ")
+        ));
+    }
+
     if !include_deleted {
         sql.push_str(" AND d.deleted_at IS NULL");
     }
@@ -306,6 +316,25 @@ pub fn search_meetings(
     Ok(rows.into_iter().map(row_to_document).collect())
 }
 
+/// `(doc_id, score, req)` rows for one `MATCH ?` against a target's FTS
+/// table, tagged with the query requirement they satisfy.
+fn target_hits_sql(target: SearchTarget, req: usize) -> String {
+    match target {
+        SearchTarget::Titles => format!(
+            "SELECT d.id AS doc_id, bm25(titles_fts) AS score, {req} AS req FROM titles_fts JOIN documents d ON titles_fts.rowid = d.rowid WHERE titles_fts MATCH ?"
+        ),
+        SearchTarget::Notes => format!(
+            "SELECT d.id AS doc_id, bm25(notes_fts) AS score, {req} AS req FROM notes_fts JOIN documents d ON notes_fts.rowid = d.rowid WHERE notes_fts MATCH ?"
+        ),
+        SearchTarget::Transcripts => format!(
+            "SELECT tu.document_id AS doc_id, bm25(transcript_fts) AS score, {req} AS req FROM transcript_fts JOIN transcript_utterances tu ON transcript_fts.rowid = tu.rowid WHERE transcript_fts MATCH ?"
+        ),
+        SearchTarget::Panels => format!(
+            "SELECT p.document_id AS doc_id, bm25(panels_fts) AS score, {req} AS req FROM panels_fts JOIN panels p ON panels_fts.rowid = p.rowid WHERE p.deleted_at IS NULL AND panels_fts MATCH ?"
+        ),
+    }
+}
+
 /// Fetch documents by id, returned in the order the ids were given.
 /// Unknown ids are skipped. No deleted filter is applied: callers pass ids
 /// from a search that already honored include_deleted.
@@ -368,12 +397,61 @@ pub fn title_series_counts(conn: &Connection) -> Result<HashMap<String, u32>> {
     Ok(rows.collect::<rusqlite::Result<HashMap<_, _>>>()?)
 }
 
-/// Every document's (id, title), for building meeting-filter allow sets.
-/// Includes deleted documents: callers intersect with candidate lists that
-/// already honored include_deleted.
-pub fn all_meeting_refs(conn: &Connection) -> Result<Vec<(String, Option<String>)>> {
-    let mut stmt = conn.prepare("SELECT id, title FROM documents")?;
-    let rows = stmt.query_map([], |row| {
+/// The (id, title) of every document passing the SQL-expressible meeting
+/// filters: attendee, company, account, series and attendee count. The
+/// `--meeting` substring is left to the caller, which matches it with
+/// Unicode-aware lowering. Includes deleted documents: callers intersect
+/// with candidate lists that already honored include_deleted.
+pub fn filtered_meeting_refs(
+    conn: &Connection,
+    filters: &MeetingFilters,
+) -> Result<Vec<(String, Option<String>)>> {
+    let mut sql = String::from("SELECT d.id, d.title FROM documents d WHERE 1=1");
+    let mut params: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+
+    if let Some(person) = &filters.with {
+        sql.push_str(&format!(
+            " AND EXISTS (SELECT 1 FROM document_people dp WHERE dp.document_id = d.id
+               AND {})",
+            attendee_condition("dp", "?")
+        ));
+        let pattern = format!("%{}%", person);
+        for _ in 0..3 {
+            params.push(Box::new(pattern.clone()));
+        }
+    }
+    if let Some(company) = &filters.company {
+        sql.push_str(
+            " AND EXISTS (SELECT 1 FROM document_people dp
+               JOIN people p ON lower(p.email) = lower(dp.email)
+               WHERE dp.document_id = d.id AND p.company_name LIKE ?)",
+        );
+        params.push(Box::new(format!("%{}%", company)));
+    }
+    if let Some(email) = &filters.account {
+        sql.push_str(
+            " AND d.source_account_id IN
+               (SELECT account_id FROM accounts WHERE email = ? COLLATE NOCASE)",
+        );
+        params.push(Box::new(email.clone()));
+    }
+    if let Some(series) = &filters.series {
+        // Keyed like title_series_counts, so a series named by one meeting's
+        // title finds every meeting counted in it.
+        sql.push_str(" AND lower(trim(d.title)) = ?");
+        params.push(Box::new(normalize_title(series)));
+    }
+    if let Some(min) = filters.min_attendees {
+        sql.push_str(
+            " AND (SELECT COUNT(DISTINCT lower(COALESCE(dp.email, dp.full_name)))
+                   FROM document_people dp WHERE dp.document_id = d.id) >= ?",
+        );
+        params.push(Box::new(min as i64));
+    }
+
+    let mut stmt = conn.prepare(&sql)?;
+    let param_refs: Vec<&dyn rusqlite::types::ToSql> = params.iter().map(|p| p.as_ref()).collect();
+    let rows = stmt.query_map(param_refs.as_slice(), |row| {
         Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
     })?;
     Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
@@ -400,7 +478,9 @@ fn append_date_filter(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::db::test_fixtures::{build_test_db, meetings_state, transcripts_state};
+    use crate::db::test_fixtures::{
+        build_test_db, meetings_state, people_state, transcripts_state,
+    };
     use chrono::{TimeZone, Utc};
 
     #[test]
@@ -800,6 +880,85 @@ mod tests {
         assert!(results.is_empty());
     }
 
+    #[test]
+    fn test_search_meetings_or_matches_either_word() {
+        let conn = build_test_db(&transcripts_state());
+        let results = search_meetings(
+            &conn,
+            "architectures OR everyone",
+            false,
+            true,
+            false,
+            false,
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(results.len(), 2);
+    }
+
+    #[test]
+    fn test_search_meetings_excluded_word_drops_the_meeting() {
+        // doc-1 mentions neural in one utterance and "great" in another:
+        // a top-level exclusion drops the whole meeting, not the row.
+        let conn = build_test_db(&transcripts_state());
+        let results = search_meetings(
+            &conn,
+            "neural -great",
+            false,
+            true,
+            false,
+            false,
+            None,
+            false,
+        )
+        .unwrap();
+        let ids: Vec<_> = results.iter().filter_map(|d| d.id.as_deref()).collect();
+        assert_eq!(ids, vec!["doc-2"]);
+    }
+
+    #[test]
+    fn test_search_meetings_field_scope_ignores_in_targets() {
+        // title:other is checked against titles even though only
+        // transcripts are searched for the unscoped word.
+        let conn = build_test_db(&transcripts_state());
+        let results = search_meetings(
+            &conn,
+            "neural title:other",
+            false,
+            true,
+            false,
+            false,
+            None,
+            false,
+        )
+        .unwrap();
+        let ids: Vec<_> = results.iter().filter_map(|d| d.id.as_deref()).collect();
+        assert_eq!(ids, vec!["doc-2"]);
+    }
+
+    #[test]
+    fn test_search_meetings_prefix_and_near() {
+        let conn = build_test_db(&transcripts_state());
+        let prefix =
+            search_meetings(&conn, "architect*", false, true, false, false, None, false).unwrap();
+        assert_eq!(prefix.len(), 1);
+
+        let near = |q: &str| {
+            search_meetings(&conn, q, false, true, false, false, None, false)
+                .unwrap()
+                .len()
+        };
+        assert_eq!(near("NEAR(talk networks, 2)"), 1);
+        assert_eq!(near("NEAR(talk networks, 1)"), 0);
+    }
+
+    #[test]
+    fn test_search_meetings_rejects_an_exclusion_only_query() {
+        let conn = build_test_db(&transcripts_state());
+        assert!(search_meetings(&conn, "-neural", false, true, false, false, None, false).is_err());
+    }
+
     #[test]
     fn test_search_meetings_by_notes() {
         let conn = build_test_db(&meetings_state());
@@ -1001,4 +1160,83 @@ mod tests {
         let conn = build_test_db(&serde_json::json!({"documents": {}}));
         assert!(title_series_counts(&conn).unwrap().is_empty());
     }
+
+    fn filtered_ids(conn: &Connection, filters: MeetingFilters) -> Vec<String> {
+        let mut ids: Vec<String> = filtered_meeting_refs(conn, &filters)
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_filtered_meeting_refs_by_attendee_and_company() {
+        let conn = build_test_db(&people_state());
+
+        let with = |q: &str| MeetingFilters {
+            with: Some(q.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(filtered_ids(&conn, with("charlie")), vec!["doc-2"]);
+        assert_eq!(
+            filtered_ids(&conn, with("example.com")),
+            vec!["doc-1", "doc-2"]
+        );
+
+        // Bob, an attendee of doc-1, works at Widgets Inc.
+        let company = MeetingFilters {
+            company: Some("widgets".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(filtered_ids(&conn, company), vec!["doc-1"]);
+    }
+
+    #[test]
+    fn test_filtered_meeting_refs_by_account_email() {
+        let conn = build_test_db(&people_state());
+        conn.execute_batch(
+            "INSERT INTO accounts (account_id, email, first_seen_at)
+               VALUES ('user_01', 'me@example.com', '2026-01-01T00:00:00Z');
+             UPDATE documents SET source_account_id = 'user_01' WHERE id = 'doc-2';",
+        )
+        .unwrap();
+
+        let account = MeetingFilters {
+            account: Some("ME@example.com".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(filtered_ids(&conn, account), vec!["doc-2"]);
+    }
+
+    #[test]
+    fn test_filtered_meeting_refs_by_series_and_attendee_count() {
+        let state = serde_json::json!({
+            "documents": {
+                "doc-1": {"id": "doc-1", "title": "Weekly Standup", "created_at": "2026-01-05T10:00:00Z",
+                          "people": {"creator": {"name": "Alice", "email": "alice@example.com"},
+                                     "attendees": [{"email": "bob@example.com"}, {"email": "ALICE@example.com"}]}},
+                "doc-2": {"id": "doc-2", "title": "  weekly standup ", "created_at": "2026-01-12T10:00:00Z",
+                          "people": {"attendees": [{"email": "bob@example.com"}]}},
+                "doc-3": {"id": "doc-3", "title": "Weekly Standup Prep", "created_at": "2026-01-13T10:00:00Z"}
+            }
+        });
+        let conn = build_test_db(&state);
+
+        let series = MeetingFilters {
+            series: Some("WEEKLY STANDUP".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(filtered_ids(&conn, series), vec!["doc-1", "doc-2"]);
+
+        // Alice is both creator and attendee of doc-1 and counts once.
+        let min = |n: usize| MeetingFilters {
+            min_attendees: Some(n),
+            ..Default::default()
+        };
+        assert_eq!(filtered_ids(&conn, min(2)), vec!["doc-1"]);
+        assert!(filtered_ids(&conn, min(3)).is_empty());
+        assert_eq!(filtered_ids(&conn, min(0)), vec!["doc-1", "doc-2", "doc-3"]);
+    }
 }
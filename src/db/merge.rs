@@ -0,0 +1,624 @@
+//! Merging two diverged copies of the database row by row.
+//!
+//! Push and pull move whole files, so once two machines have both synced
+//! since they last agreed, one side's transcripts or embeddings would be
+//! thrown away. Everything worth keeping has a stable ID, so the copies can
+//! be unioned instead: rows only one side has are kept, and where both have
+//! a row the newer `updated_at` wins. Transcripts are taken a meeting at a
+//! time, from whichever side holds more of it, since two partial fetches of
+//! one transcript do not interleave. Derived state (search indexes, action
+//! items, event links, the ANN graph) is rebuilt from the merged rows rather
+//! than merged.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use rusqlite::{Connection, OptionalExtension};
+use serde::Serialize;
+
+use crate::db::{actions, event_links, integrity, migrations};
+use crate::embed::store;
+
+/// Tables whose rows carry `updated_at`, so the newer copy of a row wins.
+const VERSIONED_TABLES: [&str; 2] = ["templates", "recipes"];
+
+/// Tables where a row both sides hold is kept as the local copy has it:
+/// nothing says which is newer, and the rows rarely change once written.
+const UNVERSIONED_TABLES: [&str; 7] = [
+    "people",
+    "events",
+    "calendars",
+    "saved_searches",
+    "saved_search_hits",
+    "action_item_status",
+    "person_aliases",
+];
+
+/// What a merge took from the other copy.
+#[derive(Debug, Default, Serialize)]
+pub struct MergeReport {
+    /// Meetings only the other copy had.
+    pub documents_added: usize,
+    /// Meetings both had, where the other copy's was newer.
+    pub documents_updated: usize,
+    /// Meetings whose transcript came from the other copy.
+    pub transcripts_taken: usize,
+    /// Panels added or replaced from the other copy.
+    pub panels_taken: usize,
+    /// People, events, calendars, templates and the like added or replaced.
+    pub other_rows: usize,
+    /// Embedded chunks copied from the other copy.
+    pub embeddings_added: usize,
+    /// Why the other copy's embeddings were left out, if they were.
+    pub embeddings_skipped: Option<String>,
+}
+
+impl MergeReport {
+    /// Whether the other copy contributed nothing.
+    pub fn is_empty(&self) -> bool {
+        self.documents_added == 0
+            && self.documents_updated == 0
+            && self.transcripts_taken == 0
+            && self.panels_taken == 0
+            && self.other_rows == 0
+            && self.embeddings_added == 0
+    }
+}
+
+/// Merge the database at `other_path` into the one at `db_path`.
+///
+/// Works on copies beside `db_path`, both brought to the current schema, and
+/// renames the merged copy over `db_path` only once the merge has committed,
+/// so a failure leaves the local database as it was. `other_path` is only
+/// read.
+pub fn merge_files(db_path: &Path, other_path: &Path) -> Result<MergeReport> {
+    integrity::check_pulled_database(other_path)
+        .with_context(|| format!("{} is not a usable grans database", other_path.display()))?;
+
+    let work = scratch_path(db_path, "merging");
+    let source = scratch_path(db_path, "merge-source");
+    let result = merge_copies(db_path, other_path, &work, &source);
+    let _ = std::fs::remove_file(&source);
+    match result {
+        Ok(report) => {
+            std::fs::rename(&work, db_path)?;
+            Ok(report)
+        }
+        Err(e) => {
+            let _ = std::fs::remove_file(&work);
+            Err(e)
+        }
+    }
+}
+
+fn scratch_path(db_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = db_path.as_os_str().to_owned();
+    name.push(".");
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+fn merge_copies(
+    db_path: &Path,
+    other_path: &Path,
+    work: &Path,
+    source: &Path,
+) -> Result<MergeReport> {
+    if db_path.exists() {
+        std::fs::copy(db_path, work)?;
+    }
+    std::fs::copy(other_path, source)?;
+
+    let mut conn = Connection::open(work)?;
+    migrations::migrate(&mut conn)?;
+    let ours = migrations::get_schema_version(&conn)?;
+
+    let mut other = Connection::open(source)?;
+    let theirs = migrations::get_schema_version(&other)?;
+    if theirs > ours {
+        bail!(
+            "{} has schema version {}, newer than this grans understands ({}); update grans first",
+            other_path.display(),
+            theirs,
+            ours
+        );
+    }
+    migrations::migrate(&mut other)?;
+    drop(other);
+
+    conn.execute(
+        "ATTACH DATABASE ?1 AS other",
+        [source.to_string_lossy().as_ref()],
+    )?;
+    let report = merge_attached(&conn)?;
+    conn.execute("DETACH DATABASE other", [])?;
+
+    // Triggers kept the indexes in step row by row; rebuilding proves it
+    // rather than trusting every path above to have fired them.
+    integrity::rebuild_fts_indexes(&conn)?;
+    event_links::link_events(&conn)?;
+    Ok(report)
+}
+
+/// Merge the database attached as `other` into `conn`'s main database, in
+/// one transaction.
+fn merge_attached(conn: &Connection) -> Result<MergeReport> {
+    let tx = conn.unchecked_transaction()?;
+    let mut report = MergeReport::default();
+
+    (report.documents_added, report.documents_updated) = take_newer(&tx, "documents")?;
+    // Attendee rows are written from a document's people, so they follow it.
+    replace_for_documents(&tx, "document_people", "merge_taken_documents")?;
+
+    report.transcripts_taken = take_richer_transcripts(&tx)?;
+    replace_for_documents(&tx, "transcript_sync_log", "merge_transcripts")?;
+
+    let (added, updated) = take_newer(&tx, "panels")?;
+    report.panels_taken = added + updated;
+    tx.execute_batch(
+        "CREATE TEMP TABLE merge_panel_documents AS
+         SELECT DISTINCT document_id AS id FROM other.panels
+         WHERE id IN (SELECT id FROM temp.merge_taken_panels)",
+    )?;
+    replace_for_documents(&tx, "panel_sync_log", "merge_panel_documents")?;
+    let panel_documents = column_values(&tx, "SELECT id FROM temp.merge_panel_documents")?;
+    for document_id in &panel_documents {
+        actions::refresh_document_items(&tx, document_id)?;
+    }
+
+    for table in VERSIONED_TABLES {
+        let (added, updated) = take_newer(&tx, table)?;
+        report.other_rows += added + updated;
+    }
+    for table in UNVERSIONED_TABLES {
+        report.other_rows += add_missing(&tx, table, &[])?;
+    }
+    // The surrogate key differs between copies; account_id is the identity.
+    report.other_rows += add_missing(&tx, "accounts", &["id"])?;
+
+    merge_metadata(&tx)?;
+
+    tx.execute_batch(
+        "CREATE TEMP TABLE merge_changed_documents AS
+         SELECT id AS document_id FROM temp.merge_taken_documents
+         UNION SELECT id FROM temp.merge_transcripts
+         UNION SELECT id FROM temp.merge_panel_documents",
+    )?;
+    let changed: i64 = tx.query_row(
+        "SELECT COUNT(*) FROM temp.merge_changed_documents",
+        [],
+        |row| row.get(0),
+    )?;
+    match merge_embeddings(&tx)? {
+        Ok(added) => {
+            report.embeddings_added = added;
+            if changed > 0 {
+                // Coverage is certain only up to the older of the two.
+                let watermark = match (
+                    store::get_embedded_watermark(&tx)?,
+                    other_embedded_watermark(&tx)?,
+                ) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    _ => None,
+                };
+                store::set_embedded_watermark(&tx, watermark.as_deref())?;
+            }
+        }
+        Err(reason) => {
+            if changed > 0 {
+                store::set_embedded_watermark(&tx, None)?;
+            }
+            report.embeddings_skipped = Some(reason);
+        }
+    }
+
+    tx.commit()?;
+    Ok(report)
+}
+
+/// The table's columns, quoted for interpolation, less `skip`.
+fn columns(conn: &Connection, table: &str, skip: &[&str]) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA main.table_info({table})"))?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(names
+        .into_iter()
+        .filter(|name| !skip.contains(&name.as_str()))
+        .map(|name| format!("\"{}\"", name))
+        .collect())
+}
+
+fn column_values(conn: &Connection, sql: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(sql)?;
+    let values = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(values)
+}
+
+/// Copy rows the local copy lacks, by the table's key. Returns rows added.
+///
+/// `table` is interpolated, as SQLite does not bind identifiers; callers pass
+/// only the constants above.
+fn add_missing(conn: &Connection, table: &str, skip: &[&str]) -> Result<usize> {
+    let cols = columns(conn, table, skip)?.join(", ");
+    Ok(conn.execute(
+        &format!("INSERT OR IGNORE INTO main.{table} ({cols}) SELECT {cols} FROM other.{table}"),
+        [],
+    )?)
+}
+
+/// Take the other copy's version of each row keyed by `id` that the local
+/// copy lacks or holds an older `updated_at` for. The IDs taken are kept in
+/// `temp.merge_taken_{table}`. Returns (added, replaced).
+///
+/// Replacing goes through an upsert rather than delete-and-insert, so the
+/// row keeps its rowid and the update triggers keep its FTS entry current.
+fn take_newer(conn: &Connection, table: &str) -> Result<(usize, usize)> {
+    conn.execute_batch(&format!(
+        "CREATE TEMP TABLE merge_taken_{table} AS
+         SELECT o.id, m.id IS NULL AS added
+         FROM other.{table} o LEFT JOIN main.{table} m ON m.id = o.id
+         WHERE m.id IS NULL OR COALESCE(o.updated_at, '') > COALESCE(m.updated_at, '')"
+    ))?;
+
+    let cols = columns(conn, table, &[])?;
+    let updates = cols
+        .iter()
+        .filter(|c| *c != "\"id\"")
+        .map(|c| format!("{c} = excluded.{c}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let cols = cols.join(", ");
+    conn.execute(
+        &format!(
+            "INSERT INTO main.{table} ({cols})
+             SELECT {cols} FROM other.{table}
+             WHERE id IN (SELECT id FROM temp.merge_taken_{table})
+             ON CONFLICT(id) DO UPDATE SET {updates}"
+        ),
+        [],
+    )?;
+
+    let (added, total): (i64, i64) = conn.query_row(
+        &format!("SELECT COALESCE(SUM(added), 0), COUNT(*) FROM temp.merge_taken_{table}"),
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    Ok((added as usize, (total - added) as usize))
+}
+
+/// Replace the local rows of `table` for each document in `temp.{documents}`
+/// with the other copy's, then add the other copy's rows for any document
+/// the local copy has none for.
+fn replace_for_documents(conn: &Connection, table: &str, documents: &str) -> Result<()> {
+    let cols = columns(conn, table, &[])?.join(", ");
+    conn.execute_batch(&format!(
+        "DELETE FROM main.{table} WHERE document_id IN (SELECT id FROM temp.{documents});
+         INSERT INTO main.{table} ({cols})
+         SELECT {cols} FROM other.{table}
+         WHERE document_id IN (SELECT id FROM temp.{documents});"
+    ))?;
+    if table.ends_with("_sync_log") {
+        add_missing(conn, table, &[])?;
+    }
+    Ok(())
+}
+
+/// Take each meeting's transcript from the other copy where it holds more
+/// utterances. Returns the number of meetings taken.
+fn take_richer_transcripts(conn: &Connection) -> Result<usize> {
+    conn.execute_batch(
+        "CREATE TEMP TABLE merge_transcripts AS
+         SELECT o.document_id AS id
+         FROM (SELECT document_id, COUNT(*) AS n FROM other.transcript_utterances
+               GROUP BY document_id) o
+         LEFT JOIN (SELECT document_id, COUNT(*) AS n FROM main.transcript_utterances
+                    GROUP BY document_id) m ON m.document_id = o.document_id
+         WHERE m.n IS NULL OR o.n > m.n",
+    )?;
+    let cols = columns(conn, "transcript_utterances", &[])?.join(", ");
+    conn.execute_batch(&format!(
+        "DELETE FROM main.transcript_utterances
+         WHERE document_id IN (SELECT id FROM temp.merge_transcripts);
+         INSERT OR IGNORE INTO main.transcript_utterances ({cols})
+         SELECT {cols} FROM other.transcript_utterances
+         WHERE document_id IN (SELECT id FROM temp.merge_transcripts);"
+    ))?;
+    Ok(
+        conn.query_row("SELECT COUNT(*) FROM temp.merge_transcripts", [], |row| {
+            row.get::<_, i64>(0)
+        })? as usize,
+    )
+}
+
+/// Merge the metadata table. A `last_sync_*` watermark takes the later of the
+/// two: the merged rows hold everything either copy had synced, so the next
+/// sync only needs what is newer than both. Other keys keep the local value.
+fn merge_metadata(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "INSERT INTO main.metadata (key, value)
+         SELECT key, value FROM other.metadata WHERE key LIKE 'last\\_sync\\_%' ESCAPE '\\'
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value
+         WHERE excluded.value > metadata.value",
+        [],
+    )?;
+    add_missing(conn, "metadata", &[])?;
+    Ok(())
+}
+
+/// Embedding settings (model, chunking, storage layout), less the watermark.
+fn embedding_settings(conn: &Connection, schema: &str) -> Result<Vec<(String, String)>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT key, COALESCE(value, '') FROM {schema}.embedding_metadata
+         WHERE key <> 'embedded_sync_watermark' ORDER BY key"
+    ))?;
+    let settings = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(settings)
+}
+
+fn other_embedded_watermark(conn: &Connection) -> Result<Option<String>> {
+    Ok(conn
+        .query_row(
+            "SELECT value FROM other.embedding_metadata WHERE key = 'embedded_sync_watermark'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// Copy the other copy's embedded chunks the local copy lacks, and replace
+/// local chunks whose meeting was taken from the other copy. Vectors from a
+/// different model or layout cannot share an index, so those are left out:
+/// the outer `Err` is a failure, the inner one the reason they were skipped.
+/// Returns the embeddings added.
+fn merge_embeddings(conn: &Connection) -> Result<std::result::Result<usize, String>> {
+    let other_chunks: i64 = conn.query_row("SELECT COUNT(*) FROM other.embeddings", [], |row| {
+        row.get(0)
+    })?;
+    if other_chunks == 0 {
+        return Ok(Ok(0));
+    }
+
+    let local = embedding_settings(conn, "main")?;
+    let theirs = embedding_settings(conn, "other")?;
+    let local_chunks: i64 =
+        conn.query_row("SELECT COUNT(*) FROM main.chunks", [], |row| row.get(0))?;
+    if local.is_empty() && local_chunks == 0 {
+        // Nothing embedded here yet: adopt the other copy's settings.
+        conn.execute(
+            "INSERT INTO main.embedding_metadata (key, value)
+             SELECT key, value FROM other.embedding_metadata
+             WHERE key <> 'embedded_sync_watermark'",
+            [],
+        )?;
+    } else if local != theirs {
+        return Ok(Err(
+            "the other copy was embedded with a different model or settings".to_string(),
+        ));
+    }
+
+    conn.execute_batch(
+        "CREATE TEMP TABLE merge_stale_chunks AS
+         SELECT m.id FROM main.chunks m
+         JOIN other.chunks o ON o.source_type = m.source_type AND o.source_id = m.source_id
+         WHERE o.content_hash <> m.content_hash
+           AND m.document_id IN (SELECT document_id FROM temp.merge_changed_documents);
+         DELETE FROM main.embeddings WHERE chunk_id IN (SELECT id FROM temp.merge_stale_chunks);
+         DELETE FROM main.chunks WHERE id IN (SELECT id FROM temp.merge_stale_chunks);",
+    )?;
+
+    let cols = columns(conn, "chunks", &["id"])?;
+    let select = cols
+        .iter()
+        .map(|c| format!("o.{c}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    conn.execute(
+        &format!(
+            "INSERT INTO main.chunks ({})
+             SELECT {select} FROM other.chunks o
+             JOIN other.embeddings e ON e.chunk_id = o.id
+             WHERE NOT EXISTS (SELECT 1 FROM main.chunks m
+                               WHERE m.source_type = o.source_type AND m.source_id = o.source_id)",
+            cols.join(", ")
+        ),
+        [],
+    )?;
+    let added = conn.execute(
+        "INSERT INTO main.embeddings (chunk_id, vector)
+         SELECT m.id, e.vector FROM other.chunks o
+         JOIN other.embeddings e ON e.chunk_id = o.id
+         JOIN main.chunks m ON m.source_type = o.source_type AND m.source_id = o.source_id
+                           AND m.content_hash = o.content_hash
+         WHERE NOT EXISTS (SELECT 1 FROM main.embeddings x WHERE x.chunk_id = m.id)",
+        [],
+    )?;
+
+    if added > 0 {
+        // The graph covers the old vector set; search rebuilds it on demand.
+        store::clear_ann_index(conn)?;
+    }
+    Ok(Ok(added))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn database(dir: &TempDir, name: &str, sql: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        let conn = migrations::open_and_migrate(&path).unwrap();
+        conn.execute_batch(sql).unwrap();
+        path
+    }
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    const LOCAL: &str = "
+        INSERT INTO documents (id, title, created_at, updated_at)
+            VALUES ('shared', 'Old title', '2025-01-01', '2025-01-01'),
+                   ('local', 'Local only', '2025-01-02', '2025-01-02');
+        INSERT INTO transcript_utterances (id, document_id, text)
+            VALUES ('l1', 'shared', 'partial transcript');
+        INSERT INTO metadata (key, value)
+            VALUES ('last_sync_documents', '2025-01-02T00:00:00Z');";
+
+    const REMOTE: &str = "
+        INSERT INTO documents (id, title, created_at, updated_at)
+            VALUES ('shared', 'New title', '2025-01-01', '2025-01-05'),
+                   ('remote', 'Remote only', '2025-01-03', '2025-01-03');
+        INSERT INTO transcript_utterances (id, document_id, text)
+            VALUES ('r1', 'shared', 'complete transcript one'),
+                   ('r2', 'shared', 'complete transcript two');
+        INSERT INTO panels (id, document_id, title, content_markdown, updated_at)
+            VALUES ('p1', 'remote', 'Summary', '## Action Items\n- Send the deck', '2025-01-03');
+        INSERT INTO metadata (key, value)
+            VALUES ('last_sync_documents', '2025-01-05T00:00:00Z');";
+
+    #[test]
+    fn unions_documents_and_prefers_newer_and_richer_rows() {
+        let dir = TempDir::new().unwrap();
+        let local = database(&dir, "grans.db", LOCAL);
+        let remote = database(&dir, "remote.db", REMOTE);
+
+        let report = merge_files(&local, &remote).unwrap();
+        assert_eq!(report.documents_added, 1);
+        assert_eq!(report.documents_updated, 1);
+        assert_eq!(report.transcripts_taken, 1);
+        assert_eq!(report.panels_taken, 1);
+
+        let conn = Connection::open(&local).unwrap();
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM documents"), 3);
+        let title: String = conn
+            .query_row("SELECT title FROM documents WHERE id = 'shared'", [], |r| {
+                r.get(0)
+            })
+            .unwrap();
+        assert_eq!(title, "New title");
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM transcript_utterances WHERE document_id = 'shared'"
+            ),
+            2
+        );
+        // Derived rows follow the merged panels and titles.
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM action_items"), 1);
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM titles_fts WHERE titles_fts MATCH 'remote'"
+            ),
+            1
+        );
+        let watermark: String = conn
+            .query_row(
+                "SELECT value FROM metadata WHERE key = 'last_sync_documents'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(watermark, "2025-01-05T00:00:00Z");
+        assert!(!dir.path().join("grans.db.merging").exists());
+        assert!(!dir.path().join("grans.db.merge-source").exists());
+    }
+
+    #[test]
+    fn merging_the_same_copy_again_takes_nothing() {
+        let dir = TempDir::new().unwrap();
+        let local = database(&dir, "grans.db", LOCAL);
+        let remote = database(&dir, "remote.db", REMOTE);
+
+        merge_files(&local, &remote).unwrap();
+        let report = merge_files(&local, &remote).unwrap();
+        assert!(report.is_empty(), "{:?}", report);
+    }
+
+    #[test]
+    fn embeddings_from_another_model_are_skipped_and_uncertified() {
+        let dir = TempDir::new().unwrap();
+        let local = database(
+            &dir,
+            "grans.db",
+            &format!(
+                "{LOCAL}
+                INSERT INTO embedding_metadata (key, value)
+                    VALUES ('model_name', 'model-a'), ('embedded_sync_watermark', '2025-01-02');
+                INSERT INTO chunks (source_type, source_id, document_id, content_hash, text, created_at)
+                    VALUES ('transcript', 'l1', 'shared', 'h1', 'partial transcript', '2025-01-02');
+                INSERT INTO embeddings (chunk_id, vector) VALUES (1, x'00');"
+            ),
+        );
+        let remote = database(
+            &dir,
+            "remote.db",
+            &format!(
+                "{REMOTE}
+                INSERT INTO embedding_metadata (key, value) VALUES ('model_name', 'model-b');
+                INSERT INTO chunks (source_type, source_id, document_id, content_hash, text, created_at)
+                    VALUES ('transcript', 'r1', 'shared', 'h2', 'complete', '2025-01-05');
+                INSERT INTO embeddings (chunk_id, vector) VALUES (1, x'01');"
+            ),
+        );
+
+        let report = merge_files(&local, &remote).unwrap();
+        assert_eq!(report.embeddings_added, 0);
+        assert!(report.embeddings_skipped.is_some());
+
+        let conn = Connection::open(&local).unwrap();
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM embeddings"), 1);
+        assert_eq!(store::get_embedded_watermark(&conn).unwrap(), None);
+    }
+
+    #[test]
+    fn embeddings_from_the_same_model_are_copied() {
+        let dir = TempDir::new().unwrap();
+        let settings = "INSERT INTO embedding_metadata (key, value)
+            VALUES ('model_name', 'model-a'), ('embedded_sync_watermark', '2025-01-02');";
+        let local = database(&dir, "grans.db", &format!("{LOCAL}{settings}"));
+        let remote = database(
+            &dir,
+            "remote.db",
+            &format!(
+                "{REMOTE}{settings}
+                INSERT INTO chunks (source_type, source_id, document_id, content_hash, text, created_at)
+                    VALUES ('panel', 'p1', 'remote', 'h3', 'Send the deck', '2025-01-03');
+                INSERT INTO embeddings (chunk_id, vector) VALUES (1, x'02');"
+            ),
+        );
+
+        let report = merge_files(&local, &remote).unwrap();
+        assert_eq!(report.embeddings_added, 1);
+        assert!(report.embeddings_skipped.is_none());
+
+        let conn = Connection::open(&local).unwrap();
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM chunks c JOIN embeddings e ON e.chunk_id = c.id
+                 WHERE c.source_id = 'p1'"
+            ),
+            1
+        );
+    }
+
+    #[test]
+    fn a_newer_schema_is_refused_and_the_local_copy_kept() {
+        let dir = TempDir::new().unwrap();
+        let local = database(&dir, "grans.db", LOCAL);
+        let remote = database(&dir, "remote.db", "PRAGMA user_version = 999;");
+        let before = std::fs::read(&local).unwrap();
+
+        let err = merge_files(&local, &remote).unwrap_err();
+        assert!(err.to_string().contains("newer"), "{err}");
+        assert_eq!(std::fs::read(&local).unwrap(), before);
+        assert!(!dir.path().join("grans.db.merging").exists());
+    }
+}
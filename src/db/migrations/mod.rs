@@ -32,6 +32,11 @@ fn migrations() -> Migrations<'static> {
         M::up(include_str!("v015_fts_triggers.sql")),
         M::up(include_str!("v016_titles_fts.sql")),
         M::up(include_str!("v017_account_provenance.sql")),
+        M::up(include_str!("v018_ann_index.sql")),
+        M::up(include_str!("v019_saved_searches.sql")),
+        M::up(include_str!("v020_action_items.sql")),
+        M::up(include_str!("v021_person_aliases.sql")),
+        M::up(include_str!("v022_event_documents.sql")),
     ])
 }
 
@@ -56,7 +61,7 @@ pub fn open_and_migrate(db_path: &Path) -> Result<Connection> {
         rusqlite_migration::SchemaVersion::Inside(v) => {
             // Check if current version is less than the number of migrations
             let current = v.get();
-            let total = 17; // We have 17 migrations (v001-v017)
+            let total = 22; // We have 22 migrations (v001-v022)
             current < total
         }
         rusqlite_migration::SchemaVersion::Outside(_) => false,
@@ -78,6 +83,14 @@ pub fn open_and_migrate(db_path: &Path) -> Result<Connection> {
     Ok(conn)
 }
 
+/// Bring an open database to the latest schema, without the backup
+/// [`open_and_migrate`] takes. For scratch copies that are discarded anyway.
+pub fn migrate(conn: &mut Connection) -> Result<()> {
+    migrations()
+        .to_latest(conn)
+        .context("Failed to apply database migrations")
+}
+
 /// Get the current schema version from the database.
 pub fn get_schema_version(conn: &Connection) -> Result<usize> {
     let m = migrations();
@@ -172,8 +185,8 @@ mod tests {
         let conn = open_and_migrate(&db_path).unwrap();
         let version = get_schema_version(&conn).unwrap();
 
-        // Should be version 17 after all migrations
-        assert_eq!(version, 17);
+        // Should be version 22 after all migrations
+        assert_eq!(version, 22);
     }
 
     #[test]
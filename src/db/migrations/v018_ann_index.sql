@@ -0,0 +1,14 @@
+-- Persisted approximate-nearest-neighbor index over the embeddings table.
+--
+-- A single row holding a serialized HNSW graph (with its normalized vectors)
+-- so search can skip decoding every embedding row at startup. `signature`
+-- captures the embedding model, dimension, chunking scheme and the stored
+-- vector set the graph was built from; a row whose signature no longer
+-- matches is stale and ignored until `grans embed` rebuilds it.
+
+CREATE TABLE IF NOT EXISTS ann_index (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    signature TEXT NOT NULL,
+    built_at TEXT NOT NULL,
+    data BLOB NOT NULL
+);
@@ -0,0 +1,28 @@
+-- Saved searches and their watch state.
+--
+-- saved_searches holds named `grep` or `search` queries with their options
+-- as JSON, so `grans saved run` re-runs them exactly as saved. checked_at
+-- and checked_watermark record the last `grans saved check`: the watermark
+-- is the newest chunk-source sync stamp at that time, and a check with no
+-- newer stamp has nothing to report.
+--
+-- saved_search_hits records every meeting a saved search has already
+-- reported (or matched when it was saved), so a check reports only
+-- meetings that match for the first time.
+
+CREATE TABLE IF NOT EXISTS saved_searches (
+    name TEXT PRIMARY KEY,
+    verb TEXT NOT NULL CHECK (verb IN ('grep', 'search')),
+    query TEXT NOT NULL,
+    options_json TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    checked_at TEXT,
+    checked_watermark TEXT
+);
+
+CREATE TABLE IF NOT EXISTS saved_search_hits (
+    saved_name TEXT NOT NULL,
+    document_id TEXT NOT NULL,
+    first_seen_at TEXT NOT NULL,
+    PRIMARY KEY (saved_name, document_id)
+);
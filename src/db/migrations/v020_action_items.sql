@@ -0,0 +1,29 @@
+-- Action items and decisions derived from AI panels.
+--
+-- action_items is rebuilt from a document's panels whenever they sync (and
+-- backfilled once from panels already stored), so it never holds anything
+-- the panels don't. id is a short hash of the document, kind and item
+-- text: it survives re-extraction as long as the wording does.
+--
+-- action_item_status holds the one thing that is not derived: when an item
+-- was marked done locally. It is keyed by item id and deliberately not
+-- tied to action_items rows, so a panel re-sync keeps done marks.
+
+CREATE TABLE IF NOT EXISTS action_items (
+    id TEXT PRIMARY KEY,
+    document_id TEXT NOT NULL,
+    panel_id TEXT NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('action', 'decision')),
+    section TEXT NOT NULL,
+    text TEXT NOT NULL,
+    owner TEXT,
+    position INTEGER NOT NULL,
+    FOREIGN KEY (document_id) REFERENCES documents(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_action_items_document ON action_items(document_id);
+
+CREATE TABLE IF NOT EXISTS action_item_status (
+    item_id TEXT PRIMARY KEY,
+    done_at TEXT NOT NULL
+);
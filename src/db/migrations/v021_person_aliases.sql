@@ -0,0 +1,22 @@
+-- Person aliases: identities the user has confirmed are the same person.
+--
+-- One colleague shows up as an attendee email, a full_name variant, a
+-- people directory row and a detected speaker name, and nothing links
+-- them. Each row puts one identity, an email or a name, into a group;
+-- every identity in a group is the same person. Identities nobody merged
+-- have no row.
+--
+-- value is ASCII-lowercased to compare with SQLite's lower(). person_id
+-- is the first identity merged into the group, as "<kind>:<value>"; it is
+-- only a group key and does not move when that identity is unmerged.
+
+CREATE TABLE IF NOT EXISTS person_aliases (
+    kind TEXT NOT NULL CHECK (kind IN ('email', 'name')),
+    value TEXT NOT NULL,
+    display TEXT NOT NULL,
+    person_id TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    PRIMARY KEY (kind, value)
+);
+
+CREATE INDEX IF NOT EXISTS idx_person_aliases_person ON person_aliases(person_id);
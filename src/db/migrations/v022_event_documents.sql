@@ -0,0 +1,18 @@
+-- Event documents: which Granola meeting recorded which calendar event.
+--
+-- Events and documents sync separately, and a document's
+-- google_calendar_event_json is only set when the meeting was started from
+-- the calendar. Rows are derived (rebuilt after every documents or
+-- calendar sync), so nothing here is user data.
+--
+-- method is 'event_id' when the document names the event, 'time' when the
+-- document was created during the event and the link was inferred.
+
+CREATE TABLE IF NOT EXISTS event_documents (
+    event_id TEXT NOT NULL,
+    document_id TEXT NOT NULL,
+    method TEXT NOT NULL CHECK (method IN ('event_id', 'time')),
+    PRIMARY KEY (event_id, document_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_event_documents_document ON event_documents(document_id);
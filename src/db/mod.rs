@@ -1,17 +1,25 @@
 pub mod accounts;
+pub mod actions;
+pub mod aliases;
 pub mod calendars;
 mod common;
 pub mod connection;
+pub mod event_links;
+pub mod export;
 pub mod info;
 pub mod integrity;
 pub mod meetings;
+pub mod merge;
 pub mod migrations;
 pub mod panels;
 pub mod people;
+pub mod profile;
 pub mod recipes;
+pub mod saved;
 pub mod schema;
 pub mod sync;
 pub mod templates;
 #[cfg(test)]
 pub mod test_fixtures;
 pub mod transcripts;
+pub mod vocab;
@@ -288,6 +288,10 @@ pub fn insert_panels_from_api(
     }
 
     drop(stmt);
+    // Action items are derived from the panels, so they are replaced with
+    // them.
+    crate::db::actions::refresh_document_items(&tx, document_id)
+        .context("Failed to extract action items")?;
     tx.commit().context("Failed to commit panel replacement")?;
 
     Ok(inserted)
@@ -1019,6 +1023,56 @@ mod tests {
         assert_eq!(parsed["title"], "Summary");
     }
 
+    #[test]
+    fn test_insert_panels_from_api_replaces_action_items() {
+        let conn = build_test_db(&transcripts_state());
+        let panel = |items: &[&str]| crate::api::ApiPanel {
+            id: Some("panel-actions".to_string()),
+            document_id: Some("doc-1".to_string()),
+            title: Some("Summary".to_string()),
+            content: Some(json!({
+                "type": "doc",
+                "content": [
+                    {"type": "heading", "attrs": {"level": 3}, "content": [{"type": "text", "text": "Action Items"}]},
+                    {"type": "bulletList", "content": items.iter().map(|t| json!(
+                        {"type": "listItem", "content": [{"type": "paragraph", "content": [{"type": "text", "text": t}]}]}
+                    )).collect::<Vec<_>>()}
+                ]
+            })),
+            original_content: None,
+            template_slug: None,
+            created_at: Some("2026-01-20T11:00:00Z".to_string()),
+            updated_at: None,
+            deleted_at: None,
+            extra: Default::default(),
+        };
+        let items = |conn: &Connection| -> Vec<(String, Option<String>)> {
+            conn.prepare("SELECT text, owner FROM action_items ORDER BY position")
+                .unwrap()
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .unwrap()
+                .map(Result::unwrap)
+                .collect()
+        };
+
+        insert_panels_from_api(
+            &conn,
+            "doc-1",
+            &[panel(&["Alice: send the deck", "Book a room"])],
+        )
+        .unwrap();
+        assert_eq!(
+            items(&conn),
+            vec![
+                ("send the deck".to_string(), Some("Alice".to_string())),
+                ("Book a room".to_string(), None),
+            ]
+        );
+
+        insert_panels_from_api(&conn, "doc-1", &[panel(&["Book a room"])]).unwrap();
+        assert_eq!(items(&conn), vec![("Book a room".to_string(), None)]);
+    }
+
     #[test]
     fn test_insert_panels_from_api_re_insert_updates_snapshot() {
         let conn = build_test_db(&panels_state());
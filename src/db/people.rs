@@ -1,6 +1,7 @@
 use anyhow::Result;
 use rusqlite::Connection;
 
+use super::aliases::attendee_condition;
 use super::common::{DocumentRow, row_to_document};
 use crate::models::{Document, Person};
 
@@ -97,8 +98,9 @@ pub fn find_meetings_by_person(
         "SELECT DISTINCT d.id, d.title, d.created_at, d.updated_at, d.deleted_at, d.doc_type, d.notes_plain, d.notes_markdown, d.summary, d.people_json, d.google_calendar_event_json
          FROM documents d
          JOIN document_people dp ON d.id = dp.document_id
-         WHERE (dp.email LIKE ?1 OR dp.full_name LIKE ?1){}
+         WHERE {}{}
          ORDER BY d.created_at DESC",
+        attendee_condition("dp", "?1"),
         deleted_filter
     );
     let mut stmt = conn.prepare(&sql)?;
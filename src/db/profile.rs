@@ -0,0 +1,777 @@
+//! Person profiles: one person's meetings, gathered from everywhere a
+//! person shows up.
+//!
+//! A person is found in the `people` directory, in `document_people`
+//! (creator and attendees of each document), in a document's calendar
+//! event attendees, and as a detected transcript speaker. Email is the
+//! strongest key; the name links the speaker attribution, which has none.
+//! Identities merged with `grans people merge` count as the same person.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::{Result, bail};
+use chrono::{Datelike, FixedOffset};
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::db::aliases::AliasGroup;
+use crate::models::Person;
+use crate::query::adjust::normalize_title;
+
+/// Co-attendees and series listed in a profile.
+const TOP_ENTRIES: usize = 5;
+
+/// Recent meetings listed in a profile.
+const RECENT_MEETINGS: usize = 5;
+
+/// Utterances shorter than this many words ("Yeah.", "Sounds good.") say
+/// little about a person, so recent utterances skip them.
+const MIN_UTTERANCE_WORDS: usize = 4;
+
+/// Who a profile is about.
+#[derive(Debug, Clone, Default)]
+pub struct Identity {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    /// The `people` directory entry, when there is one.
+    pub person: Option<Person>,
+    /// Identities merged with this one.
+    pub aliases: Option<AliasGroup>,
+}
+
+impl Identity {
+    /// Every email of the person, ASCII-lowercased.
+    pub fn emails(&self) -> Vec<String> {
+        let mut emails: Vec<String> = self.email.iter().map(|e| e.to_ascii_lowercase()).collect();
+        for e in self.aliases.iter().flat_map(|g| g.emails()) {
+            if !emails.iter().any(|x| x == e) {
+                emails.push(e.to_string());
+            }
+        }
+        emails
+    }
+
+    /// Every name of the person, ASCII-lowercased.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.name.iter().map(|n| n.to_ascii_lowercase()).collect();
+        for n in self.aliases.iter().flat_map(|g| g.names()) {
+            let n = n.to_ascii_lowercase();
+            if !names.contains(&n) {
+                names.push(n);
+            }
+        }
+        names
+    }
+
+    fn is(&self, name: Option<&str>, email: Option<&str>) -> bool {
+        match email {
+            Some(e) if !self.emails().is_empty() => self.emails().contains(&e.to_ascii_lowercase()),
+            _ => name.is_some_and(|n| self.names().contains(&n.to_ascii_lowercase())),
+        }
+    }
+}
+
+/// A meeting the person was part of, and how that is known: `people`
+/// (creator or attendee), `calendar` (on the calendar invite) or
+/// `speaker` (detected speaking in the transcript).
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileMeeting {
+    pub id: String,
+    pub title: Option<String>,
+    pub created_at: Option<String>,
+    pub via: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MonthCount {
+    /// `YYYY-MM`, in the display timezone.
+    pub month: String,
+    pub meetings: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CoAttendee {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    pub meetings: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeriesCount {
+    /// The series' most recent title.
+    pub title: String,
+    pub meetings: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileUtterance {
+    pub document_id: String,
+    pub title: Option<String>,
+    pub start_timestamp: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PersonProfile {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    /// Other emails and names merged with this person.
+    pub aliases: Vec<String>,
+    pub company_name: Option<String>,
+    pub job_title: Option<String>,
+    pub meetings: usize,
+    /// Meetings they were detected speaking in.
+    pub spoke_in: usize,
+    pub first_meeting: Option<ProfileMeeting>,
+    /// The most recent meeting: last contact.
+    pub last_meeting: Option<ProfileMeeting>,
+    /// Meetings per month from the first meeting to the last, zero-filled.
+    pub monthly: Vec<MonthCount>,
+    /// The people they most often meet with, leaving out each meeting's
+    /// creator (the user who recorded it), who is usually an attendee too.
+    pub co_attendees: Vec<CoAttendee>,
+    /// Recurring meetings (the same title more than once) they attend.
+    pub series: Vec<SeriesCount>,
+    pub recent_meetings: Vec<ProfileMeeting>,
+    pub recent_utterances: Vec<ProfileUtterance>,
+}
+
+/// Find the one person `query` (a name, email or people-directory ID
+/// fragment) refers to. The people directory is tried first, then meeting
+/// attendees, then detected speaker names. A query naming several people
+/// is an error listing them, unless one matches exactly or they are all
+/// merged into one person.
+pub fn resolve_identity(conn: &Connection, query: &str) -> Result<Identity> {
+    let groups = crate::db::aliases::alias_groups(conn)?;
+    let mut identity = resolve_unmerged(conn, query, &groups)?;
+    let group = group_of(&groups, identity.name.as_deref(), identity.email.as_deref()).cloned();
+    // A bare name merged with an email is that email's person: profile it
+    // from the email, which carries the directory entry and listed name.
+    if identity.email.is_none()
+        && let Some(email) = group.as_ref().and_then(|g| g.emails().next())
+    {
+        identity = resolve_unmerged(conn, email, &groups)?;
+    }
+    identity.aliases = group;
+    Ok(identity)
+}
+
+fn resolve_unmerged(conn: &Connection, query: &str, groups: &[AliasGroup]) -> Result<Identity> {
+    let exact = |name: Option<&str>, email: Option<&str>| {
+        name.is_some_and(|n| n.eq_ignore_ascii_case(query.trim()))
+            || email.is_some_and(|e| e.eq_ignore_ascii_case(query.trim()))
+    };
+
+    let people = collapse(crate::db::people::find_person(conn, query)?, groups, |p| {
+        (p.name.as_deref(), p.email.as_deref())
+    });
+    let person = match people.len() {
+        0 => None,
+        1 => people.into_iter().next(),
+        _ => {
+            let mut exact_hits: Vec<Person> = people
+                .iter()
+                .filter(|p| exact(p.name.as_deref(), p.email.as_deref()))
+                .cloned()
+                .collect();
+            if exact_hits.len() != 1 {
+                bail!(
+                    "{}",
+                    ambiguous(
+                        query,
+                        people
+                            .iter()
+                            .map(|p| (p.name.as_deref(), p.email.as_deref()))
+                    )
+                );
+            }
+            exact_hits.pop()
+        }
+    };
+    if let Some(person) = person {
+        let mut identity = Identity {
+            name: person.name.clone(),
+            email: person.email.clone(),
+            person: Some(person),
+            aliases: None,
+        };
+        if identity.name.is_none()
+            && let Some(email) = identity.email.as_deref()
+        {
+            identity.name = attendee_name_for(conn, email)?;
+        }
+        return Ok(identity);
+    }
+
+    let attendees = collapse(matching_attendees(conn, query)?, groups, |i| {
+        (i.name.as_deref(), i.email.as_deref())
+    });
+    match attendees.len() {
+        0 => {}
+        1 => return Ok(attendees.into_iter().next().unwrap_or_default()),
+        _ => {
+            let mut exact_hits: Vec<Identity> = attendees
+                .iter()
+                .filter(|i| exact(i.name.as_deref(), i.email.as_deref()))
+                .cloned()
+                .collect();
+            if exact_hits.len() == 1 {
+                return Ok(exact_hits.pop().unwrap_or_default());
+            }
+            bail!(
+                "{}",
+                ambiguous(
+                    query,
+                    attendees
+                        .iter()
+                        .map(|i| (i.name.as_deref(), i.email.as_deref()))
+                )
+            );
+        }
+    }
+
+    let speakers = collapse(
+        crate::query::speaker::resolve_names(
+            query,
+            &crate::db::transcripts::distinct_speaker_names(conn)?,
+        ),
+        groups,
+        |n| (Some(n.as_str()), None),
+    );
+    match speakers.as_slice() {
+        [] => bail!("No person found matching \"{}\"", query),
+        [name] => Ok(Identity {
+            name: Some(name.clone()),
+            ..Default::default()
+        }),
+        many => bail!(
+            "{}",
+            ambiguous(query, many.iter().map(|n| (Some(n.as_str()), None)))
+        ),
+    }
+}
+
+/// The merged person an identity belongs to.
+fn group_of<'a>(
+    groups: &'a [AliasGroup],
+    name: Option<&str>,
+    email: Option<&str>,
+) -> Option<&'a AliasGroup> {
+    groups
+        .iter()
+        .find(|g| email.is_some_and(|e| g.has_email(e)) || name.is_some_and(|n| g.has_name(n)))
+}
+
+/// Keep only the first of several candidates merged into one person.
+fn collapse<T>(
+    candidates: Vec<T>,
+    groups: &[AliasGroup],
+    identity: impl Fn(&T) -> (Option<&str>, Option<&str>),
+) -> Vec<T> {
+    let mut seen: Vec<String> = Vec::new();
+    candidates
+        .into_iter()
+        .filter(|c| {
+            let (name, email) = identity(c);
+            match group_of(groups, name, email) {
+                Some(g) if seen.contains(&g.id) => false,
+                Some(g) => {
+                    seen.push(g.id.clone());
+                    true
+                }
+                None => true,
+            }
+        })
+        .collect()
+}
+
+fn ambiguous<'a>(
+    query: &str,
+    candidates: impl Iterator<Item = (Option<&'a str>, Option<&'a str>)>,
+) -> String {
+    let listed: Vec<String> = candidates
+        .map(|(name, email)| match (name, email) {
+            (Some(n), Some(e)) => format!("{} <{}>", n, e),
+            (Some(n), None) => n.to_string(),
+            (None, Some(e)) => e.to_string(),
+            (None, None) => "(unnamed)".to_string(),
+        })
+        .collect();
+    format!(
+        "\"{}\" matches {} people: {}; give more of the name or the email",
+        query,
+        listed.len(),
+        listed.join(", ")
+    )
+}
+
+/// Distinct meeting attendees whose name or email contains `query`, one per
+/// email (or per name, for attendees without one).
+fn matching_attendees(conn: &Connection, query: &str) -> Result<Vec<Identity>> {
+    let mut stmt = conn.prepare(
+        "SELECT lower(email), full_name FROM document_people
+         WHERE email LIKE ?1 OR full_name LIKE ?1
+         ORDER BY full_name IS NULL, full_name",
+    )?;
+    let rows = stmt
+        .query_map([format!("%{}%", query)], |row| {
+            Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut by_key: Vec<(String, Identity)> = Vec::new();
+    for (email, name) in rows {
+        let Some(key) = email
+            .clone()
+            .or_else(|| name.as_ref().map(|n| n.to_lowercase()))
+        else {
+            continue;
+        };
+        match by_key.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, identity)) => {
+                if identity.name.is_none() {
+                    identity.name = name;
+                }
+            }
+            None => by_key.push((
+                key,
+                Identity {
+                    name,
+                    email,
+                    ..Default::default()
+                },
+            )),
+        }
+    }
+    Ok(by_key.into_iter().map(|(_, identity)| identity).collect())
+}
+
+/// The name an email address is listed under as an attendee, if any.
+fn attendee_name_for(conn: &Connection, email: &str) -> Result<Option<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT full_name FROM document_people
+         WHERE lower(email) = lower(?1) AND full_name IS NOT NULL LIMIT 1",
+    )?;
+    let mut rows = stmt.query([email])?;
+    Ok(match rows.next()? {
+        Some(row) => row.get(0)?,
+        None => None,
+    })
+}
+
+/// Every non-deleted meeting the person was part of, newest first.
+pub fn person_meetings(conn: &Connection, identity: &Identity) -> Result<Vec<ProfileMeeting>> {
+    let mut stmt = conn.prepare(
+        "SELECT d.id, d.title, d.created_at, 'people'
+         FROM documents d JOIN document_people dp ON dp.document_id = d.id
+         WHERE d.deleted_at IS NULL
+           AND (lower(dp.email) IN (SELECT value FROM json_each(?1))
+             OR lower(dp.full_name) IN (SELECT value FROM json_each(?2)))
+         UNION
+         SELECT d.id, d.title, d.created_at, 'calendar'
+         FROM documents d,
+              json_each(CASE WHEN json_valid(d.google_calendar_event_json)
+                             THEN d.google_calendar_event_json ELSE '{}' END,
+                        '$.attendees') a
+         WHERE d.deleted_at IS NULL
+           AND lower(json_extract(a.value, '$.email')) IN (SELECT value FROM json_each(?1))
+         UNION
+         SELECT d.id, d.title, d.created_at, 'speaker'
+         FROM documents d
+         WHERE d.deleted_at IS NULL
+           AND EXISTS (SELECT 1 FROM transcript_utterances u
+                       WHERE u.document_id = d.id
+                         AND lower(u.speaker_name) IN (SELECT value FROM json_each(?2)))",
+    )?;
+    let rows = stmt
+        .query_map(
+            rusqlite::params![
+                serde_json::to_string(&identity.emails())?,
+                serde_json::to_string(&identity.names())?
+            ],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut meetings: Vec<ProfileMeeting> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (id, title, created_at, via) in rows {
+        match index.get(&id) {
+            Some(&i) => meetings[i].via.push(via),
+            None => {
+                index.insert(id.clone(), meetings.len());
+                meetings.push(ProfileMeeting {
+                    id,
+                    title,
+                    created_at,
+                    via: vec![via],
+                });
+            }
+        }
+    }
+    for m in &mut meetings {
+        m.via.sort_by_key(|v| {
+            ["people", "calendar", "speaker"]
+                .iter()
+                .position(|k| k == v)
+        });
+    }
+    meetings.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    Ok(meetings)
+}
+
+/// Build the full profile for `identity`. `utterances` caps the recent
+/// utterances listed; months are bucketed in `tz`.
+pub fn person_profile(
+    conn: &Connection,
+    identity: &Identity,
+    utterances: usize,
+    tz: &FixedOffset,
+) -> Result<PersonProfile> {
+    let meetings = person_meetings(conn, identity)?;
+
+    Ok(PersonProfile {
+        name: identity.name.clone(),
+        email: identity.email.clone(),
+        aliases: identity
+            .aliases
+            .iter()
+            .flat_map(|g| &g.aliases)
+            .filter(|a| {
+                ![&identity.email, &identity.name].iter().any(|own| {
+                    own.as_deref()
+                        .is_some_and(|o| o.eq_ignore_ascii_case(&a.display))
+                })
+            })
+            .map(|a| a.display.clone())
+            .collect(),
+        company_name: identity
+            .person
+            .as_ref()
+            .and_then(|p| p.company_name.clone()),
+        job_title: identity.person.as_ref().and_then(|p| p.job_title.clone()),
+        meetings: meetings.len(),
+        spoke_in: meetings
+            .iter()
+            .filter(|m| m.via.iter().any(|v| v == "speaker"))
+            .count(),
+        first_meeting: meetings.last().cloned(),
+        last_meeting: meetings.first().cloned(),
+        monthly: monthly_counts(&meetings, tz),
+        co_attendees: co_attendees(conn, identity, &meetings)?,
+        series: series_counts(&meetings),
+        recent_meetings: meetings.iter().take(RECENT_MEETINGS).cloned().collect(),
+        recent_utterances: recent_utterances(conn, identity, utterances)?,
+    })
+}
+
+fn monthly_counts(meetings: &[ProfileMeeting], tz: &FixedOffset) -> Vec<MonthCount> {
+    let mut counts: BTreeMap<(i32, u32), usize> = BTreeMap::new();
+    for m in meetings {
+        if let Some(dt) = m
+            .created_at
+            .as_deref()
+            .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        {
+            let local = dt.with_timezone(tz);
+            *counts.entry((local.year(), local.month())).or_insert(0) += 1;
+        }
+    }
+    let (Some(&first), Some(&last)) = (counts.keys().next(), counts.keys().next_back()) else {
+        return Vec::new();
+    };
+
+    let mut months = Vec::new();
+    let (mut year, mut month) = first;
+    while (year, month) <= last {
+        months.push(MonthCount {
+            month: format!("{:04}-{:02}", year, month),
+            meetings: counts.get(&(year, month)).copied().unwrap_or(0),
+        });
+        (year, month) = if month == 12 {
+            (year + 1, 1)
+        } else {
+            (year, month + 1)
+        };
+    }
+    months
+}
+
+fn co_attendees(
+    conn: &Connection,
+    identity: &Identity,
+    meetings: &[ProfileMeeting],
+) -> Result<Vec<CoAttendee>> {
+    let mut stmt = conn.prepare(
+        "SELECT p.email, p.full_name FROM document_people p
+         WHERE p.document_id = ?1 AND p.role != 'creator'
+           AND NOT EXISTS (SELECT 1 FROM document_people c
+                           WHERE c.document_id = p.document_id AND c.role = 'creator'
+                             AND lower(c.email) = lower(p.email))",
+    )?;
+    let mut tally: HashMap<String, (CoAttendee, HashSet<&str>)> = HashMap::new();
+    for m in meetings {
+        let rows = stmt
+            .query_map([&m.id], |row| {
+                Ok((
+                    row.get::<_, Option<String>>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        for (email, name) in rows {
+            if identity.is(name.as_deref(), email.as_deref()) {
+                continue;
+            }
+            let Some(key) = email.as_deref().or(name.as_deref()).map(str::to_lowercase) else {
+                continue;
+            };
+            let entry = tally.entry(key).or_insert_with(|| {
+                (
+                    CoAttendee {
+                        name: None,
+                        email: email.clone(),
+                        meetings: 0,
+                    },
+                    HashSet::new(),
+                )
+            });
+            if entry.0.name.is_none() {
+                entry.0.name = name;
+            }
+            if entry.1.insert(m.id.as_str()) {
+                entry.0.meetings += 1;
+            }
+        }
+    }
+
+    let mut list: Vec<CoAttendee> = tally.into_values().map(|(c, _)| c).collect();
+    list.sort_by(|a, b| {
+        b.meetings
+            .cmp(&a.meetings)
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.email.cmp(&b.email))
+    });
+    list.truncate(TOP_ENTRIES);
+    Ok(list)
+}
+
+fn series_counts(meetings: &[ProfileMeeting]) -> Vec<SeriesCount> {
+    // Meetings arrive newest first, so the first title seen is the latest.
+    let mut series: Vec<(String, SeriesCount)> = Vec::new();
+    for m in meetings {
+        let Some(title) = m.title.as_deref().filter(|t| !t.trim().is_empty()) else {
+            continue;
+        };
+        let key = normalize_title(title);
+        match series.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, s)) => s.meetings += 1,
+            None => series.push((
+                key,
+                SeriesCount {
+                    title: title.trim().to_string(),
+                    meetings: 1,
+                },
+            )),
+        }
+    }
+    let mut recurring: Vec<SeriesCount> = series
+        .into_iter()
+        .map(|(_, s)| s)
+        .filter(|s| s.meetings > 1)
+        .collect();
+    // Stable sort keeps the most recently held series first among equals.
+    recurring.sort_by_key(|s| std::cmp::Reverse(s.meetings));
+    recurring.truncate(TOP_ENTRIES);
+    recurring
+}
+
+fn recent_utterances(
+    conn: &Connection,
+    identity: &Identity,
+    limit: usize,
+) -> Result<Vec<ProfileUtterance>> {
+    let names = identity.names();
+    if names.is_empty() || limit == 0 {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare(
+        "SELECT u.document_id, d.title, u.start_timestamp, u.text
+         FROM transcript_utterances u JOIN documents d ON d.id = u.document_id
+         WHERE lower(u.speaker_name) IN (SELECT value FROM json_each(?1))
+           AND d.deleted_at IS NULL
+           AND u.text IS NOT NULL
+         ORDER BY u.start_timestamp DESC",
+    )?;
+    let mut rows = stmt.query([serde_json::to_string(&names)?])?;
+    let mut utterances = Vec::new();
+    while let Some(row) = rows.next()? {
+        let text: String = row.get(3)?;
+        if text.split_whitespace().count() < MIN_UTTERANCE_WORDS {
+            continue;
+        }
+        utterances.push(ProfileUtterance {
+            document_id: row.get(0)?,
+            title: row.get(1)?,
+            start_timestamp: row.get(2)?,
+            text,
+        });
+        if utterances.len() == limit {
+            break;
+        }
+    }
+    Ok(utterances)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_fixtures::build_test_db;
+    use serde_json::json;
+
+    fn attendee(email: &str, name: &str) -> serde_json::Value {
+        json!({"email": email, "details": {"person": {"name": {"fullName": name}}}})
+    }
+
+    fn state() -> serde_json::Value {
+        let me = json!({"name": "Me Myself", "email": "me@example.com"});
+        json!({
+            "documents": {
+                "d1": {"id": "d1", "title": "Weekly Sync", "created_at": "2026-01-05T10:00:00Z",
+                       "people": {"creator": me, "attendees": [attendee("dana@example.com", "Dana Lee"), attendee("eli@example.com", "Eli Park")]}},
+                "d2": {"id": "d2", "title": "weekly sync", "created_at": "2026-03-02T10:00:00Z",
+                       "people": {"creator": me, "attendees": [attendee("DANA@example.com", "Dana Lee"), attendee("eli@example.com", "Eli Park")]}},
+                "d3": {"id": "d3", "title": "Pricing", "created_at": "2026-03-20T10:00:00Z",
+                       "people": {"creator": me},
+                       "google_calendar_event": {"attendees": [{"email": "dana@example.com"}]}},
+                "d4": {"id": "d4", "title": "Vendor call", "created_at": "2026-04-01T10:00:00Z",
+                       "people": {"creator": me}},
+                "d5": {"id": "d5", "title": "Deleted", "created_at": "2026-04-02T10:00:00Z",
+                       "deleted_at": "2026-04-03T00:00:00Z",
+                       "people": {"attendees": [attendee("dana@example.com", "Dana Lee")]}},
+                "d6": {"id": "d6", "title": "Board prep", "created_at": "2026-04-10T10:00:00Z",
+                       "people": {"creator": me}}
+            },
+            "transcripts": {
+                "d4": [
+                    {"id": "u1", "document_id": "d4", "start_timestamp": "2026-04-01T10:01:00Z", "text": "We can ship the contract next week.", "source": "system", "speaker_name": "Dana Lee"},
+                    {"id": "u2", "document_id": "d4", "start_timestamp": "2026-04-01T10:02:00Z", "text": "Sounds good.", "source": "system", "speaker_name": "Dana Lee"}
+                ],
+                "d6": [
+                    {"id": "u3", "document_id": "d6", "start_timestamp": "2026-04-10T10:01:00Z", "text": "The numbers are final for the quarter.", "source": "system", "speaker_name": "D. Lee"}
+                ]
+            },
+            "people": [
+                {"id": "p-dana", "name": "Dana Lee", "email": "dana@example.com", "company_name": "Acme", "job_title": "CFO"},
+                {"id": "p-dan", "name": "Dan Brown", "email": "dan@other.example"}
+            ]
+        })
+    }
+
+    #[test]
+    fn profile_combines_attendees_calendar_and_speakers() {
+        let conn = build_test_db(&state());
+        let identity = resolve_identity(&conn, "dana").unwrap();
+        assert_eq!(identity.email.as_deref(), Some("dana@example.com"));
+
+        let p = person_profile(&conn, &identity, 5, &FixedOffset::east_opt(0).unwrap()).unwrap();
+        assert_eq!(p.company_name.as_deref(), Some("Acme"));
+        assert_eq!(p.meetings, 4);
+        assert_eq!(p.spoke_in, 1);
+        assert_eq!(p.first_meeting.unwrap().id, "d1");
+        let last = p.last_meeting.unwrap();
+        assert_eq!(
+            (last.id.as_str(), last.via.clone()),
+            ("d4", vec!["speaker".to_string()])
+        );
+        assert_eq!(p.recent_meetings[1].via, vec!["calendar"]);
+
+        let months: Vec<(String, usize)> = p
+            .monthly
+            .into_iter()
+            .map(|m| (m.month, m.meetings))
+            .collect();
+        assert_eq!(
+            months,
+            vec![
+                ("2026-01".to_string(), 1),
+                ("2026-02".to_string(), 0),
+                ("2026-03".to_string(), 2),
+                ("2026-04".to_string(), 1)
+            ]
+        );
+
+        // The creator (the user) is not a co-attendee.
+        assert_eq!(p.co_attendees.len(), 1);
+        assert_eq!(p.co_attendees[0].name.as_deref(), Some("Eli Park"));
+        assert_eq!(p.co_attendees[0].meetings, 2);
+
+        assert_eq!(p.series.len(), 1);
+        assert_eq!(p.series[0].title, "weekly sync");
+        assert_eq!(p.series[0].meetings, 2);
+
+        // Short acknowledgements are skipped.
+        assert_eq!(p.recent_utterances.len(), 1);
+        assert_eq!(
+            p.recent_utterances[0].text,
+            "We can ship the contract next week."
+        );
+    }
+
+    #[test]
+    fn merged_identities_count_as_one_person() {
+        let conn = build_test_db(&state());
+        crate::db::aliases::merge(&conn, "dana@example.com", "D. Lee").unwrap();
+        let utc = FixedOffset::east_opt(0).unwrap();
+
+        for query in ["dana", "d. lee"] {
+            let identity = resolve_identity(&conn, query).unwrap();
+            let p = person_profile(&conn, &identity, 5, &utc).unwrap();
+            assert_eq!(p.meetings, 5, "{}", query);
+            assert_eq!(p.last_meeting.unwrap().id, "d6");
+            assert_eq!(p.recent_utterances[0].document_id, "d6");
+        }
+
+        let identity = resolve_identity(&conn, "dana").unwrap();
+        let p = person_profile(&conn, &identity, 5, &utc).unwrap();
+        assert_eq!(p.aliases, vec!["D. Lee"]);
+    }
+
+    #[test]
+    fn resolves_attendees_and_speakers_outside_the_directory() {
+        let conn = build_test_db(&state());
+        let eli = resolve_identity(&conn, "eli").unwrap();
+        assert_eq!(eli.email.as_deref(), Some("eli@example.com"));
+        assert_eq!(eli.name.as_deref(), Some("Eli Park"));
+        assert!(eli.person.is_none());
+
+        assert!(resolve_identity(&conn, "nobody").is_err());
+        let err = resolve_identity(&conn, "dan").unwrap_err().to_string();
+        assert!(
+            err.contains("Dana Lee") && err.contains("Dan Brown"),
+            "{}",
+            err
+        );
+        assert_eq!(
+            resolve_identity(&conn, "dan brown")
+                .unwrap()
+                .email
+                .as_deref(),
+            Some("dan@other.example")
+        );
+    }
+}
@@ -0,0 +1,234 @@
+//! Saved searches: named `grep`/`search` queries and their watch state.
+//!
+//! Options are stored as opaque JSON owned by [`crate::commands::saved`];
+//! this module only persists rows. A saved search's hits are the meetings
+//! it has already reported, so a check can tell new matches from old ones.
+
+use std::collections::HashSet;
+
+use anyhow::{Result, bail};
+use rusqlite::{Connection, OptionalExtension, TransactionBehavior};
+
+/// One saved search as stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedSearchRecord {
+    pub name: String,
+    /// `grep` or `search`.
+    pub verb: String,
+    pub query: String,
+    pub options_json: String,
+    /// RFC 3339 timestamp of when the search was saved.
+    pub created_at: String,
+    /// RFC 3339 timestamp of the last check (or of the save, which
+    /// records the baseline).
+    pub checked_at: Option<String>,
+    /// The sync watermark the last check covered.
+    pub checked_watermark: Option<String>,
+}
+
+fn row_to_record(row: &rusqlite::Row) -> rusqlite::Result<SavedSearchRecord> {
+    Ok(SavedSearchRecord {
+        name: row.get(0)?,
+        verb: row.get(1)?,
+        query: row.get(2)?,
+        options_json: row.get(3)?,
+        created_at: row.get(4)?,
+        checked_at: row.get(5)?,
+        checked_watermark: row.get(6)?,
+    })
+}
+
+const SELECT_COLUMNS: &str =
+    "SELECT name, verb, query, options_json, created_at, checked_at, checked_watermark
+     FROM saved_searches";
+
+/// Store a new saved search. Fails when the name is taken.
+pub fn insert_saved_search(
+    conn: &Connection,
+    name: &str,
+    verb: &str,
+    query: &str,
+    options_json: &str,
+) -> Result<()> {
+    let inserted = conn.execute(
+        "INSERT INTO saved_searches (name, verb, query, options_json, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(name) DO NOTHING",
+        rusqlite::params![
+            name,
+            verb,
+            query,
+            options_json,
+            chrono::Utc::now().to_rfc3339()
+        ],
+    )?;
+    if inserted == 0 {
+        bail!(
+            "A saved search named \"{}\" already exists; remove it first with `grans saved rm {}`",
+            name,
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Every saved search, by name.
+pub fn list_saved_searches(conn: &Connection) -> Result<Vec<SavedSearchRecord>> {
+    let mut stmt = conn.prepare(&format!("{} ORDER BY name", SELECT_COLUMNS))?;
+    let records = stmt
+        .query_map([], row_to_record)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(records)
+}
+
+/// The saved search with this exact name, if any.
+pub fn get_saved_search(conn: &Connection, name: &str) -> Result<Option<SavedSearchRecord>> {
+    Ok(conn
+        .query_row(
+            &format!("{} WHERE name = ?1", SELECT_COLUMNS),
+            [name],
+            row_to_record,
+        )
+        .optional()?)
+}
+
+/// Delete a saved search and its hits. Returns false when no search has
+/// that name.
+pub fn delete_saved_search(conn: &Connection, name: &str) -> Result<bool> {
+    let tx = rusqlite::Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+    tx.execute(
+        "DELETE FROM saved_search_hits WHERE saved_name = ?1",
+        [name],
+    )?;
+    let deleted = tx.execute("DELETE FROM saved_searches WHERE name = ?1", [name])?;
+    tx.commit()?;
+    Ok(deleted > 0)
+}
+
+/// Ids of the meetings this saved search has already reported.
+pub fn seen_meeting_ids(conn: &Connection, name: &str) -> Result<HashSet<String>> {
+    let mut stmt =
+        conn.prepare("SELECT document_id FROM saved_search_hits WHERE saved_name = ?1")?;
+    let ids = stmt
+        .query_map([name], |row| row.get(0))?
+        .collect::<Result<HashSet<_>, _>>()?;
+    Ok(ids)
+}
+
+/// Record a check: mark `new_ids` as seen and stamp the check time and the
+/// sync watermark it covered, in one transaction.
+pub fn record_check(
+    conn: &Connection,
+    name: &str,
+    watermark: Option<&str>,
+    new_ids: &[String],
+) -> Result<()> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let tx = rusqlite::Transaction::new_unchecked(conn, TransactionBehavior::Immediate)?;
+    {
+        let mut stmt = tx.prepare(
+            "INSERT INTO saved_search_hits (saved_name, document_id, first_seen_at)
+             VALUES (?1, ?2, ?3)
+             ON CONFLICT(saved_name, document_id) DO NOTHING",
+        )?;
+        for id in new_ids {
+            stmt.execute(rusqlite::params![name, id, &now])?;
+        }
+    }
+    tx.execute(
+        "UPDATE saved_searches SET checked_at = ?2, checked_watermark = ?3 WHERE name = ?1",
+        rusqlite::params![name, &now, watermark],
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_fixtures::build_test_db;
+    use serde_json::json;
+
+    fn empty_db() -> Connection {
+        build_test_db(&json!({"documents": {}}))
+    }
+
+    #[test]
+    fn insert_then_get_roundtrips() {
+        let conn = empty_db();
+        insert_saved_search(&conn, "incidents", "grep", "security incident", "{}").unwrap();
+
+        let record = get_saved_search(&conn, "incidents").unwrap().unwrap();
+        assert_eq!(record.verb, "grep");
+        assert_eq!(record.query, "security incident");
+        assert_eq!(record.checked_at, None);
+        assert_eq!(get_saved_search(&conn, "other").unwrap(), None);
+    }
+
+    #[test]
+    fn duplicate_name_is_an_error() {
+        let conn = empty_db();
+        insert_saved_search(&conn, "incidents", "grep", "a", "{}").unwrap();
+        let err = insert_saved_search(&conn, "incidents", "search", "b", "{}").unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+        // The original is untouched.
+        let record = get_saved_search(&conn, "incidents").unwrap().unwrap();
+        assert_eq!(record.query, "a");
+    }
+
+    #[test]
+    fn list_is_ordered_by_name() {
+        let conn = empty_db();
+        insert_saved_search(&conn, "zeta", "grep", "z", "{}").unwrap();
+        insert_saved_search(&conn, "alpha", "search", "a", "{}").unwrap();
+
+        let names: Vec<String> = list_saved_searches(&conn)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(names, vec!["alpha", "zeta"]);
+    }
+
+    #[test]
+    fn record_check_accumulates_hits_and_stamps_the_watermark() {
+        let conn = empty_db();
+        insert_saved_search(&conn, "incidents", "grep", "a", "{}").unwrap();
+
+        record_check(
+            &conn,
+            "incidents",
+            Some("2026-01-01T00:00:00Z"),
+            &["d1".into()],
+        )
+        .unwrap();
+        record_check(
+            &conn,
+            "incidents",
+            Some("2026-01-02T00:00:00Z"),
+            &["d1".into(), "d2".into()],
+        )
+        .unwrap();
+
+        let seen = seen_meeting_ids(&conn, "incidents").unwrap();
+        assert_eq!(seen, HashSet::from(["d1".to_string(), "d2".to_string()]));
+        let record = get_saved_search(&conn, "incidents").unwrap().unwrap();
+        assert_eq!(
+            record.checked_watermark.as_deref(),
+            Some("2026-01-02T00:00:00Z")
+        );
+        assert!(record.checked_at.is_some());
+    }
+
+    #[test]
+    fn delete_removes_the_search_and_its_hits() {
+        let conn = empty_db();
+        insert_saved_search(&conn, "incidents", "grep", "a", "{}").unwrap();
+        record_check(&conn, "incidents", None, &["d1".into()]).unwrap();
+
+        assert!(delete_saved_search(&conn, "incidents").unwrap());
+        assert!(!delete_saved_search(&conn, "incidents").unwrap());
+        assert!(seen_meeting_ids(&conn, "incidents").unwrap().is_empty());
+        assert_eq!(get_saved_search(&conn, "incidents").unwrap(), None);
+    }
+}
@@ -43,5 +43,10 @@ pub fn create_tables(conn: &Connection) -> Result<()> {
     conn.execute_batch(include_str!("migrations/v015_fts_triggers.sql"))?;
     conn.execute_batch(include_str!("migrations/v016_titles_fts.sql"))?;
     conn.execute_batch(include_str!("migrations/v017_account_provenance.sql"))?;
+    conn.execute_batch(include_str!("migrations/v018_ann_index.sql"))?;
+    conn.execute_batch(include_str!("migrations/v019_saved_searches.sql"))?;
+    conn.execute_batch(include_str!("migrations/v020_action_items.sql"))?;
+    conn.execute_batch(include_str!("migrations/v021_person_aliases.sql"))?;
+    conn.execute_batch(include_str!("migrations/v022_event_documents.sql"))?;
     Ok(())
 }
@@ -3,6 +3,8 @@
 //! All sync operations are upserts: new records are inserted, existing records
 //! are updated based on their primary key (usually `id`).
 
+use std::collections::HashSet;
+
 use anyhow::Result;
 use rusqlite::{Connection, OptionalExtension};
 
@@ -164,7 +166,8 @@ pub fn upsert_documents(
             google_calendar_event_json = excluded.google_calendar_event_json,
             extra_json = excluded.extra_json,
             raw_json = excluded.raw_json
-         WHERE excluded.updated_at IS NOT documents.updated_at",
+         WHERE excluded.updated_at IS NOT documents.updated_at
+            OR excluded.deleted_at IS NOT documents.deleted_at",
     )?;
 
     for doc in documents {
@@ -620,6 +623,49 @@ pub fn upsert_recipes(
     Ok(stats)
 }
 
+/// Live local documents the server no longer lists.
+///
+/// `remote_ids` must be the complete document list from a full fetch. Only
+/// documents synced under `source_account_id` are candidates (rows with no
+/// recorded account when it is None), so another account's meetings in the
+/// same database are never reported missing.
+pub fn missing_documents(
+    conn: &Connection,
+    remote_ids: &HashSet<&str>,
+    source_account_id: Option<&str>,
+) -> Result<Vec<String>> {
+    let local: Vec<String> = conn
+        .prepare(
+            "SELECT id FROM documents
+             WHERE deleted_at IS NULL AND source_account_id IS ?1
+             ORDER BY id",
+        )?
+        .query_map([source_account_id], |row| row.get(0))?
+        .collect::<rusqlite::Result<_>>()?;
+
+    Ok(local
+        .into_iter()
+        .filter(|id| !remote_ids.contains(id.as_str()))
+        .collect())
+}
+
+/// Stamp `deleted_at` on the given documents, as the server does when a
+/// meeting is deleted. A later sync that sees the document again clears it.
+pub fn soft_delete_documents(conn: &Connection, ids: &[String]) -> Result<usize> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let tx = conn.unchecked_transaction()?;
+    let mut deleted = 0;
+    {
+        let mut stmt = tx
+            .prepare("UPDATE documents SET deleted_at = ?1 WHERE id = ?2 AND deleted_at IS NULL")?;
+        for id in ids {
+            deleted += stmt.execute(rusqlite::params![&now, id])?;
+        }
+    }
+    tx.commit()?;
+    Ok(deleted)
+}
+
 /// Read the last sync time recorded for a given entity type (RFC3339),
 /// or None if that entity has never synced. Database failures are errors,
 /// not None: freshness decisions ride on this value, and a locked or
@@ -851,6 +897,78 @@ mod tests {
         assert_eq!(source, Some("user_01AAA".to_string()));
     }
 
+    fn doc(id: &str, updated_at: &str) -> Document {
+        Document {
+            id: Some(id.to_string()),
+            title: Some(id.to_string()),
+            updated_at: Some(updated_at.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn deleted_at(conn: &Connection, id: &str) -> Option<String> {
+        conn.query_row(
+            "SELECT deleted_at FROM documents WHERE id = ?1",
+            [id],
+            |r| r.get(0),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn missing_documents_are_soft_deleted() {
+        let conn = build_test_db(&empty_state());
+        let docs = vec![
+            doc("doc-1", "2026-01-20T10:00:00Z"),
+            doc("doc-2", "2026-01-20T10:00:00Z"),
+        ];
+        upsert_documents(&conn, &docs, Some("user_01AAA")).unwrap();
+
+        let remote: HashSet<&str> = ["doc-1"].into_iter().collect();
+        let missing = missing_documents(&conn, &remote, Some("user_01AAA")).unwrap();
+        let deleted = soft_delete_documents(&conn, &missing).unwrap();
+
+        assert_eq!(missing, vec!["doc-2"]);
+        assert_eq!(deleted, 1);
+        assert_eq!(deleted_at(&conn, "doc-1"), None);
+        assert!(deleted_at(&conn, "doc-2").is_some());
+    }
+
+    #[test]
+    fn missing_documents_leaves_other_accounts_alone() {
+        let conn = build_test_db(&empty_state());
+        upsert_documents(
+            &conn,
+            &[doc("doc-a", "2026-01-20T10:00:00Z")],
+            Some("user_01AAA"),
+        )
+        .unwrap();
+        upsert_documents(
+            &conn,
+            &[doc("doc-b", "2026-01-20T10:00:00Z")],
+            Some("user_01BBB"),
+        )
+        .unwrap();
+
+        let missing = missing_documents(&conn, &HashSet::new(), Some("user_01AAA")).unwrap();
+
+        assert_eq!(missing, vec!["doc-a"]);
+    }
+
+    #[test]
+    fn test_upsert_documents_restores_a_reappearing_document() {
+        let conn = build_test_db(&empty_state());
+        let docs = vec![doc("doc-1", "2026-01-20T10:00:00Z")];
+        upsert_documents(&conn, &docs, None).unwrap();
+        soft_delete_documents(&conn, &["doc-1".to_string()]).unwrap();
+
+        // Same updated_at as before: only deleted_at differs.
+        let stats = upsert_documents(&conn, &docs, None).unwrap();
+
+        assert_eq!(stats.updated, 1);
+        assert_eq!(deleted_at(&conn, "doc-1"), None);
+    }
+
     #[test]
     fn test_upsert_documents_update_never_touches_source_account() {
         let conn = build_test_db(&empty_state());
@@ -0,0 +1,87 @@
+//! Term statistics from the full-text indexes, read through FTS5's
+//! `fts5vocab` tables.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use rusqlite::Connection;
+
+/// The content indexes whose vocabulary describes what meetings say.
+/// Titles are left out: they name meetings rather than discuss topics.
+const CONTENT_FTS_TABLES: &[(&str, &str)] = &[
+    ("transcript_fts", "transcript_utterances"),
+    ("notes_fts", "documents"),
+    ("panels_fts", "panels"),
+];
+
+/// How many indexed rows contain each term, across the content indexes.
+#[derive(Debug, Clone, Default)]
+pub struct TermFrequencies {
+    /// Indexed rows (utterances, notes bodies, panels) in total.
+    pub rows: u64,
+    /// Rows containing each term, keyed by the term as FTS5 tokenized it.
+    pub document_frequency: HashMap<String, u64>,
+}
+
+impl TermFrequencies {
+    /// Inverse document frequency of `term`, or None when the index has
+    /// never seen it.
+    pub fn idf(&self, term: &str) -> Option<f64> {
+        let df = *self.document_frequency.get(term)?;
+        Some(((self.rows.max(1) as f64 + 1.0) / (df as f64 + 1.0)).ln())
+    }
+}
+
+/// Document frequencies for every term in the transcript, notes and panel
+/// indexes. The vocab tables are temporary, so reading them leaves the
+/// database file untouched.
+pub fn content_term_frequencies(conn: &Connection) -> Result<TermFrequencies> {
+    let mut stats = TermFrequencies::default();
+    for (fts, content) in CONTENT_FTS_TABLES {
+        let vocab = format!("{}_vocab", fts);
+        conn.execute(
+            &format!(
+                "CREATE VIRTUAL TABLE IF NOT EXISTS temp.{} USING fts5vocab(main, {}, row)",
+                vocab, fts
+            ),
+            [],
+        )?;
+        let mut stmt = conn.prepare(&format!("SELECT term, doc FROM temp.{}", vocab))?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })?;
+        for row in rows {
+            let (term, doc) = row?;
+            *stats.document_frequency.entry(term).or_insert(0) += doc as u64;
+        }
+        let count: i64 =
+            conn.query_row(&format!("SELECT COUNT(*) FROM {}", content), [], |row| {
+                row.get(0)
+            })?;
+        stats.rows += count as u64;
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_fixtures::build_test_db;
+    use serde_json::json;
+
+    #[test]
+    fn counts_rows_containing_each_term() {
+        let conn = build_test_db(&json!({"documents": {
+            "a": {"id": "a", "title": "A", "notes_plain": "Pricing pricing renewal"},
+            "b": {"id": "b", "title": "B", "notes_plain": "Renewal plan"},
+        }}));
+        conn.execute("INSERT INTO notes_fts(notes_fts) VALUES('rebuild')", [])
+            .unwrap();
+
+        let stats = content_term_frequencies(&conn).unwrap();
+        assert_eq!(stats.document_frequency.get("pricing"), Some(&1));
+        assert_eq!(stats.document_frequency.get("renewal"), Some(&2));
+        assert!(stats.idf("pricing").unwrap() > stats.idf("renewal").unwrap());
+        assert_eq!(stats.idf("absent"), None);
+    }
+}
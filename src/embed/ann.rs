@@ -0,0 +1,831 @@
+//! Approximate nearest-neighbor index (HNSW) over the stored embeddings.
+//!
+//! Exact search decodes every embedding row and scores it against the query,
+//! so both startup and query cost grow with the corpus. An [`AnnIndex`] is a
+//! hierarchical navigable small-world graph (Malkov & Yashunin) over the same
+//! vectors, built by `grans embed` and persisted as one blob in the
+//! `ann_index` table. Search loads that blob, walks the graph to the closest
+//! chunks, and reads only those chunks' rows back from SQLite.
+//!
+//! The graph is approximate: a query can miss a true neighbor the exact scan
+//! would find. `grans benchmark quality --mode semantic-ann` reports its
+//! recall against exact search. Small corpora (under [`ANN_MIN_VECTORS`])
+//! are not worth the loss and always search exactly.
+//!
+//! Vectors are normalized at build and query time, so cosine similarity is
+//! a dot product and distance is `1 - similarity`. They are then quantized
+//! to the precision the embeddings are stored in, so the persisted graph is
+//! no larger per vector than an `embeddings` row, and the graph searches
+//! exactly the vectors it persists.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+
+use anyhow::{Result, bail};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use super::quant::{Precision, StorageSpec};
+
+/// Below this many vectors, `grans embed` builds no graph: the exact scan is
+/// already fast and loses no recall.
+pub const ANN_MIN_VECTORS: usize = 2_000;
+
+/// Graph construction parameters.
+#[derive(Debug, Clone, Copy)]
+pub struct HnswParams {
+    /// Links per node on the upper layers (twice this on layer 0).
+    pub m: usize,
+    /// Candidate list size while inserting; higher builds a better graph
+    /// more slowly.
+    pub ef_construction: usize,
+    /// Seed for layer assignment, so a rebuild of the same vectors is
+    /// reproducible.
+    pub seed: u64,
+}
+
+impl Default for HnswParams {
+    fn default() -> Self {
+        HnswParams {
+            m: 16,
+            ef_construction: 100,
+            seed: 0x0067_7261_6e73,
+        }
+    }
+}
+
+/// A scored node: distance plus node id, ordered by distance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Scored {
+    dist: f32,
+    node: u32,
+}
+
+impl Eq for Scored {}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.dist
+            .total_cmp(&other.dist)
+            .then(self.node.cmp(&other.node))
+    }
+}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The HNSW graph with the normalized vectors it links.
+#[derive(Debug, Clone)]
+pub struct Hnsw {
+    dim: usize,
+    m: usize,
+    /// How `encoded` holds each vector.
+    storage: StorageSpec,
+    /// Normalized vectors in their storage encoding, node-major
+    /// (`storage.bytes_per_vector(dim)` bytes per node): what is persisted.
+    encoded: Vec<u8>,
+    /// The same vectors decoded, node-major (`dim` floats per node): what
+    /// distances are computed on.
+    vectors: Vec<f32>,
+    /// `links[node][layer]`: the node's neighbors on that layer.
+    links: Vec<Vec<Vec<u32>>>,
+    entry: Option<u32>,
+}
+
+impl Hnsw {
+    /// Build a graph over `vectors` (all of length `dim`), held at `precision`.
+    pub fn build(dim: usize, vectors: &[&[f32]], params: HnswParams, precision: Precision) -> Self {
+        let storage = StorageSpec {
+            dim: None,
+            precision,
+        };
+        let mut graph = Hnsw {
+            dim,
+            m: params.m.max(2),
+            storage,
+            encoded: Vec::with_capacity(vectors.len() * storage.bytes_per_vector(dim)),
+            vectors: Vec::with_capacity(vectors.len() * dim),
+            links: Vec::with_capacity(vectors.len()),
+            entry: None,
+        };
+        let level_scale = 1.0 / (graph.m as f64).ln();
+        let mut rng = StdRng::seed_from_u64(params.seed);
+
+        for vector in vectors {
+            let level = (-rng.r#gen::<f64>().max(f64::MIN_POSITIVE).ln() * level_scale) as usize;
+            graph.insert(vector, level, params.ef_construction.max(graph.m));
+        }
+        graph
+    }
+
+    fn len(&self) -> usize {
+        self.links.len()
+    }
+
+    fn vector(&self, node: u32) -> &[f32] {
+        let start = node as usize * self.dim;
+        &self.vectors[start..start + self.dim]
+    }
+
+    fn distance(&self, query: &[f32], node: u32) -> f32 {
+        1.0 - dot(query, self.vector(node))
+    }
+
+    fn top_layer(&self) -> usize {
+        self.entry
+            .map(|e| self.links[e as usize].len() - 1)
+            .unwrap_or(0)
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 { self.m * 2 } else { self.m }
+    }
+
+    fn insert(&mut self, vector: &[f32], level: usize, ef_construction: usize) {
+        let node = self.links.len() as u32;
+        let encoded = self.storage.encode(&normalized(vector));
+        self.vectors.extend(self.storage.decode(&encoded).to_f32());
+        self.encoded.extend(encoded);
+        self.links.push(vec![Vec::new(); level + 1]);
+
+        let Some(entry) = self.entry else {
+            self.entry = Some(node);
+            return;
+        };
+        let query = self.vector(node).to_vec();
+        let top = self.top_layer();
+
+        let mut nearest = Scored {
+            dist: self.distance(&query, entry),
+            node: entry,
+        };
+        for layer in (level + 1..=top).rev() {
+            nearest = self.greedy_closest(&query, nearest, layer);
+        }
+
+        let mut entry_points = vec![nearest];
+        for layer in (0..=level.min(top)).rev() {
+            let candidates = self.search_layer(&query, &entry_points, ef_construction, layer, None);
+            let neighbors = self.select_neighbors(&candidates, self.m);
+            self.links[node as usize][layer] = neighbors.iter().map(|s| s.node).collect();
+
+            for neighbor in &neighbors {
+                self.link(neighbor.node, node, layer);
+            }
+            entry_points = candidates;
+        }
+
+        if level > top {
+            self.entry = Some(node);
+        }
+    }
+
+    /// Add `to` to `from`'s links on `layer`, pruning back to the layer's
+    /// limit with the same heuristic used at insertion.
+    fn link(&mut self, from: u32, to: u32, layer: usize) {
+        self.links[from as usize][layer].push(to);
+        if self.links[from as usize][layer].len() <= self.max_links(layer) {
+            return;
+        }
+        let base = self.vector(from).to_vec();
+        let mut candidates: Vec<Scored> = self.links[from as usize][layer]
+            .iter()
+            .map(|&n| Scored {
+                dist: self.distance(&base, n),
+                node: n,
+            })
+            .collect();
+        candidates.sort();
+        let kept = self.select_neighbors(&candidates, self.max_links(layer));
+        self.links[from as usize][layer] = kept.iter().map(|s| s.node).collect();
+    }
+
+    /// The neighbor-selection heuristic: take candidates nearest first, but
+    /// skip one that is closer to an already selected neighbor than to the
+    /// base, so links spread across clusters instead of piling into one.
+    /// Skipped candidates fill any remaining slots. `candidates` must be
+    /// sorted nearest first.
+    fn select_neighbors(&self, candidates: &[Scored], limit: usize) -> Vec<Scored> {
+        let mut selected: Vec<Scored> = Vec::with_capacity(limit);
+        let mut skipped = Vec::new();
+        for &candidate in candidates {
+            if selected.len() >= limit {
+                break;
+            }
+            let vector = self.vector(candidate.node);
+            let diverse = selected
+                .iter()
+                .all(|s| 1.0 - dot(vector, self.vector(s.node)) > candidate.dist);
+            if diverse {
+                selected.push(candidate);
+            } else {
+                skipped.push(candidate);
+            }
+        }
+        for candidate in skipped {
+            if selected.len() >= limit {
+                break;
+            }
+            selected.push(candidate);
+        }
+        selected
+    }
+
+    fn greedy_closest(&self, query: &[f32], start: Scored, layer: usize) -> Scored {
+        let mut best = start;
+        loop {
+            let mut improved = false;
+            for &n in &self.links[best.node as usize][layer] {
+                let dist = self.distance(query, n);
+                if dist < best.dist {
+                    best = Scored { dist, node: n };
+                    improved = true;
+                }
+            }
+            if !improved {
+                return best;
+            }
+        }
+    }
+
+    /// Best-first search of one layer. Returns up to `ef` nodes, nearest
+    /// first. With `accept`, rejected nodes are still traversed (they may
+    /// lead to accepted ones) but never returned.
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entry_points: &[Scored],
+        ef: usize,
+        layer: usize,
+        accept: Option<&dyn Fn(u32) -> bool>,
+    ) -> Vec<Scored> {
+        let accepts = |node: u32| accept.is_none_or(|f| f(node));
+        let mut visited: HashSet<u32> = entry_points.iter().map(|s| s.node).collect();
+        let mut candidates: BinaryHeap<Reverse<Scored>> =
+            entry_points.iter().copied().map(Reverse).collect();
+        let mut results: BinaryHeap<Scored> = entry_points
+            .iter()
+            .copied()
+            .filter(|s| accepts(s.node))
+            .collect();
+        while results.len() > ef {
+            results.pop();
+        }
+
+        while let Some(Reverse(current)) = candidates.pop() {
+            if results.len() >= ef
+                && results
+                    .peek()
+                    .is_some_and(|worst| current.dist > worst.dist)
+            {
+                break;
+            }
+            for &n in &self.links[current.node as usize][layer] {
+                if !visited.insert(n) {
+                    continue;
+                }
+                let scored = Scored {
+                    dist: self.distance(query, n),
+                    node: n,
+                };
+                let full = results.len() >= ef;
+                if full
+                    && results
+                        .peek()
+                        .is_some_and(|worst| scored.dist >= worst.dist)
+                {
+                    continue;
+                }
+                candidates.push(Reverse(scored));
+                if accepts(n) {
+                    results.push(scored);
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+        results.into_sorted_vec()
+    }
+
+    /// The `k` nodes most similar to `query` among those `accept` allows,
+    /// as `(node, cosine similarity)`, most similar first. `ef` (at least
+    /// `k`) trades speed for recall.
+    pub fn search(
+        &self,
+        query: &[f32],
+        k: usize,
+        ef: usize,
+        accept: Option<&dyn Fn(u32) -> bool>,
+    ) -> Vec<(u32, f32)> {
+        let Some(entry) = self.entry else {
+            return Vec::new();
+        };
+        if query.len() != self.dim || k == 0 {
+            return Vec::new();
+        }
+        let query = normalized(query);
+        let mut nearest = Scored {
+            dist: self.distance(&query, entry),
+            node: entry,
+        };
+        for layer in (1..=self.top_layer()).rev() {
+            nearest = self.greedy_closest(&query, nearest, layer);
+        }
+        self.search_layer(&query, &[nearest], ef.max(k), 0, accept)
+            .into_iter()
+            .take(k)
+            .map(|s| (s.node, 1.0 - s.dist))
+            .collect()
+    }
+
+    /// Every node `accept` allows, as `(node, cosine similarity)` in node
+    /// order: an exact scan of the graph's own vectors, for callers that
+    /// need every match rather than the nearest few.
+    pub fn scan(&self, query: &[f32], accept: Option<&dyn Fn(u32) -> bool>) -> Vec<(u32, f32)> {
+        if query.len() != self.dim {
+            return Vec::new();
+        }
+        let query = normalized(query);
+        (0..self.len() as u32)
+            .filter(|&n| accept.is_none_or(|f| f(n)))
+            .map(|n| (n, 1.0 - self.distance(&query, n)))
+            .collect()
+    }
+}
+
+/// An HNSW graph whose nodes map back to stored chunks.
+#[derive(Debug, Clone)]
+pub struct AnnIndex {
+    graph: Hnsw,
+    /// `chunk_ids[node]`: the chunk each node's vector belongs to.
+    chunk_ids: Vec<i64>,
+    /// Distinct chunk source types, indexed by `node_sources`.
+    source_types: Vec<String>,
+    node_sources: Vec<u8>,
+}
+
+/// Format tag and version at the start of a serialized [`AnnIndex`].
+const MAGIC: &[u8; 4] = b"GANN";
+const FORMAT_VERSION: u32 = 2;
+
+fn precision_code(precision: Precision) -> u32 {
+    match precision {
+        Precision::F32 => 0,
+        Precision::F16 => 1,
+        Precision::Int8 => 2,
+    }
+}
+
+fn precision_from_code(code: u32) -> Result<Precision> {
+    Ok(match code {
+        0 => Precision::F32,
+        1 => Precision::F16,
+        2 => Precision::Int8,
+        other => bail!("unknown ANN vector precision {}", other),
+    })
+}
+
+impl AnnIndex {
+    /// Build over `(chunk_id, source_type, vector)` entries, holding the
+    /// vectors at `precision`: the one the embeddings are stored in.
+    pub fn build<'a>(
+        dim: usize,
+        entries: impl IntoIterator<Item = (i64, &'a str, &'a [f32])>,
+        params: HnswParams,
+        precision: Precision,
+    ) -> Self {
+        let mut chunk_ids = Vec::new();
+        let mut source_types: Vec<String> = Vec::new();
+        let mut node_sources = Vec::new();
+        let mut vectors = Vec::new();
+        for (chunk_id, source_type, vector) in entries {
+            let code = match source_types.iter().position(|s| s == source_type) {
+                Some(i) => i,
+                None => {
+                    source_types.push(source_type.to_string());
+                    source_types.len() - 1
+                }
+            };
+            chunk_ids.push(chunk_id);
+            node_sources.push(code as u8);
+            vectors.push(vector);
+        }
+        AnnIndex {
+            graph: Hnsw::build(dim, &vectors, params, precision),
+            chunk_ids,
+            source_types,
+            node_sources,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.chunk_ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunk_ids.is_empty()
+    }
+
+    /// Dimensions of the indexed vectors.
+    pub fn dim(&self) -> usize {
+        self.graph.dim
+    }
+
+    /// The `k` chunks most similar to `query`, as `(chunk_id, similarity)`,
+    /// optionally restricted to the given source types and to the chunks
+    /// `chunk_filter` accepts. The restrictions apply during the search, so
+    /// up to `k` accepted chunks come back however few there are.
+    pub fn search(
+        &self,
+        query: &[f32],
+        k: usize,
+        ef: usize,
+        source_type_filter: Option<&[&str]>,
+        chunk_filter: Option<&dyn Fn(i64) -> bool>,
+    ) -> Vec<(i64, f32)> {
+        self.with_accept(source_type_filter, chunk_filter, |accept| {
+            self.graph.search(query, k, ef, accept)
+        })
+    }
+
+    /// Every chunk the filters accept, as `(chunk_id, similarity)`, scored
+    /// exactly against the graph's vectors.
+    pub fn scan(
+        &self,
+        query: &[f32],
+        source_type_filter: Option<&[&str]>,
+        chunk_filter: Option<&dyn Fn(i64) -> bool>,
+    ) -> Vec<(i64, f32)> {
+        self.with_accept(source_type_filter, chunk_filter, |accept| {
+            self.graph.scan(query, accept)
+        })
+    }
+
+    /// Run `find` with a node predicate built from the filters, mapping the
+    /// nodes it returns back to chunk ids.
+    fn with_accept(
+        &self,
+        source_type_filter: Option<&[&str]>,
+        chunk_filter: Option<&dyn Fn(i64) -> bool>,
+        find: impl FnOnce(Option<&dyn Fn(u32) -> bool>) -> Vec<(u32, f32)>,
+    ) -> Vec<(i64, f32)> {
+        let allowed: Option<Vec<bool>> = source_type_filter.map(|filter| {
+            self.source_types
+                .iter()
+                .map(|s| filter.contains(&s.as_str()))
+                .collect()
+        });
+        let accept = |node: u32| {
+            allowed
+                .as_ref()
+                .is_none_or(|a| a[self.node_sources[node as usize] as usize])
+                && chunk_filter.is_none_or(|f| f(self.chunk_ids[node as usize]))
+        };
+        let filtered = allowed.is_some() || chunk_filter.is_some();
+        let accept: Option<&dyn Fn(u32) -> bool> = filtered.then_some(&accept as _);
+
+        find(accept)
+            .into_iter()
+            .map(|(node, score)| (self.chunk_ids[node as usize], score))
+            .collect()
+    }
+
+    /// Serialize to the little-endian blob stored in `ann_index.data`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let g = &self.graph;
+        let mut out = Vec::with_capacity(g.encoded.len() + g.len() * (g.m * 12 + 16));
+        out.extend_from_slice(MAGIC);
+        put_u32(&mut out, FORMAT_VERSION);
+        put_u32(&mut out, g.dim as u32);
+        put_u32(&mut out, g.m as u32);
+        put_u32(&mut out, precision_code(g.storage.precision));
+        put_u32(&mut out, g.len() as u32);
+        put_u32(&mut out, g.entry.unwrap_or(u32::MAX));
+
+        put_u32(&mut out, self.source_types.len() as u32);
+        for source in &self.source_types {
+            put_u32(&mut out, source.len() as u32);
+            out.extend_from_slice(source.as_bytes());
+        }
+        for (chunk_id, source) in self.chunk_ids.iter().zip(&self.node_sources) {
+            out.extend_from_slice(&chunk_id.to_le_bytes());
+            out.push(*source);
+        }
+        out.extend_from_slice(&g.encoded);
+        for layers in &g.links {
+            put_u32(&mut out, layers.len() as u32);
+            for neighbors in layers {
+                put_u32(&mut out, neighbors.len() as u32);
+                for n in neighbors {
+                    put_u32(&mut out, *n);
+                }
+            }
+        }
+        out
+    }
+
+    /// Parse a blob written by [`AnnIndex::to_bytes`], validating every
+    /// length and node reference so a damaged blob is an error, not a panic.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut r = Reader { bytes, pos: 0 };
+        if r.take(4)? != MAGIC {
+            bail!("not an ANN index");
+        }
+        let version = r.u32()?;
+        if version != FORMAT_VERSION {
+            bail!("unsupported ANN index format version {}", version);
+        }
+        let dim = r.u32()? as usize;
+        let m = r.u32()? as usize;
+        let precision = precision_from_code(r.u32()?)?;
+        let storage = StorageSpec {
+            dim: None,
+            precision,
+        };
+        let count = r.u32()? as usize;
+        let entry = match r.u32()? {
+            u32::MAX => None,
+            e if (e as usize) < count => Some(e),
+            e => bail!("ANN entry point {} out of range", e),
+        };
+
+        let source_count = r.u32()? as usize;
+        let mut source_types = Vec::with_capacity(source_count.min(256));
+        for _ in 0..source_count {
+            let len = r.u32()? as usize;
+            source_types.push(String::from_utf8(r.take(len)?.to_vec())?);
+        }
+
+        let mut chunk_ids = Vec::with_capacity(count.min(bytes.len() / 9));
+        let mut node_sources = Vec::with_capacity(count.min(bytes.len() / 9));
+        for _ in 0..count {
+            chunk_ids.push(i64::from_le_bytes(r.take(8)?.try_into()?));
+            let source = r.take(1)?[0];
+            if source as usize >= source_types.len() {
+                bail!("ANN node source type {} out of range", source);
+            }
+            node_sources.push(source);
+        }
+
+        let vector_bytes = storage.bytes_per_vector(dim);
+        let block = count
+            .checked_mul(vector_bytes)
+            .filter(|&n| n <= bytes.len())
+            .ok_or_else(|| anyhow::anyhow!("ANN index vector block is truncated"))?;
+        let encoded = r.take(block)?.to_vec();
+        let mut vectors = Vec::with_capacity(count * dim);
+        if vector_bytes > 0 {
+            for blob in encoded.chunks_exact(vector_bytes) {
+                vectors.extend(storage.decode(blob).to_f32());
+            }
+        }
+
+        let mut links = Vec::with_capacity(count);
+        for _ in 0..count {
+            let layer_count = r.u32()? as usize;
+            if layer_count == 0 || layer_count > 64 {
+                bail!("ANN node has {} layers", layer_count);
+            }
+            let mut layers = Vec::with_capacity(layer_count);
+            for _ in 0..layer_count {
+                let len = r.u32()? as usize;
+                let mut neighbors = Vec::with_capacity(len.min(m * 2 + 1));
+                for _ in 0..len {
+                    let n = r.u32()?;
+                    if n as usize >= count {
+                        bail!("ANN link to node {} out of range", n);
+                    }
+                    neighbors.push(n);
+                }
+                layers.push(neighbors);
+            }
+            links.push(layers);
+        }
+        if r.pos != bytes.len() {
+            bail!("ANN index has {} trailing bytes", bytes.len() - r.pos);
+        }
+
+        // Every link must stay within the linked node's layers.
+        for layers in &links {
+            for (layer, neighbors) in layers.iter().enumerate() {
+                if neighbors.iter().any(|&n| links[n as usize].len() <= layer) {
+                    bail!("ANN link on layer {} to a node without that layer", layer);
+                }
+            }
+        }
+
+        Ok(AnnIndex {
+            graph: Hnsw {
+                dim,
+                m,
+                storage,
+                encoded,
+                vectors,
+                links,
+                entry,
+            },
+            chunk_ids,
+            source_types,
+            node_sources,
+        })
+    }
+}
+
+fn put_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| anyhow::anyhow!("ANN index is truncated"))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn normalized(v: &[f32]) -> Vec<f32> {
+    let norm = dot(v, v).sqrt();
+    if norm == 0.0 {
+        v.to_vec()
+    } else {
+        v.iter().map(|x| x / norm).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random unit-ish vectors.
+    fn random_vectors(count: usize, dim: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..count)
+            .map(|_| (0..dim).map(|_| rng.r#gen::<f32>() - 0.5).collect())
+            .collect()
+    }
+
+    fn exact_top(vectors: &[Vec<f32>], query: &[f32], k: usize) -> Vec<usize> {
+        let q = normalized(query);
+        let mut scored: Vec<(usize, f32)> = vectors
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (i, dot(&q, &normalized(v))))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.into_iter().take(k).map(|(i, _)| i).collect()
+    }
+
+    fn build_index(vectors: &[Vec<f32>]) -> AnnIndex {
+        build_index_at(vectors, Precision::F32)
+    }
+
+    fn build_index_at(vectors: &[Vec<f32>], precision: Precision) -> AnnIndex {
+        AnnIndex::build(
+            vectors[0].len(),
+            vectors.iter().enumerate().map(|(i, v)| {
+                let source = if i % 2 == 0 {
+                    "transcript_window"
+                } else {
+                    "panel_section"
+                };
+                (i as i64, source, v.as_slice())
+            }),
+            HnswParams::default(),
+            precision,
+        )
+    }
+
+    #[test]
+    fn empty_index_returns_nothing() {
+        let index = AnnIndex::build(4, std::iter::empty(), HnswParams::default(), Precision::F32);
+
+        assert!(index.is_empty());
+        assert!(
+            index
+                .search(&[1.0, 0.0, 0.0, 0.0], 5, 50, None, None)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn finds_an_exact_match_first() {
+        let vectors = random_vectors(300, 16, 1);
+        let index = build_index(&vectors);
+
+        let hits = index.search(&vectors[123], 3, 50, None, None);
+
+        assert_eq!(hits[0].0, 123);
+        assert!((hits[0].1 - 1.0).abs() < 1e-5);
+        assert!(hits.windows(2).all(|w| w[0].1 >= w[1].1));
+    }
+
+    #[test]
+    fn recall_against_exact_search_is_high() {
+        let vectors = random_vectors(1_000, 24, 2);
+        let queries = random_vectors(30, 24, 3);
+        let index = build_index(&vectors);
+
+        let mut found = 0;
+        for query in &queries {
+            let exact = exact_top(&vectors, query, 10);
+            let approx: Vec<usize> = index
+                .search(query, 10, 64, None, None)
+                .into_iter()
+                .map(|(id, _)| id as usize)
+                .collect();
+            found += exact.iter().filter(|id| approx.contains(id)).count();
+        }
+
+        let recall = found as f64 / (queries.len() * 10) as f64;
+        assert!(recall >= 0.9, "recall@10 was {}", recall);
+    }
+
+    #[test]
+    fn source_type_filter_only_returns_allowed_chunks() {
+        let vectors = random_vectors(200, 8, 4);
+        let index = build_index(&vectors);
+
+        let hits = index.search(&vectors[10], 20, 50, Some(&["panel_section"]), None);
+
+        assert_eq!(hits.len(), 20);
+        assert!(hits.iter().all(|(id, _)| id % 2 == 1));
+        assert!(
+            index
+                .search(&vectors[10], 5, 50, Some(&["notes_paragraph"]), None)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn serialization_round_trips() {
+        let vectors = random_vectors(150, 8, 5);
+        let index = build_index(&vectors);
+
+        let restored = AnnIndex::from_bytes(&index.to_bytes()).unwrap();
+
+        assert_eq!(restored.len(), 150);
+        assert_eq!(
+            restored.search(&vectors[7], 5, 40, None, None),
+            index.search(&vectors[7], 5, 40, None, None)
+        );
+    }
+
+    #[test]
+    fn quantized_graphs_persist_their_encoding() {
+        let vectors = random_vectors(150, 64, 8);
+        let full = build_index(&vectors).to_bytes();
+        let index = build_index_at(&vectors, Precision::Int8);
+        let bytes = index.to_bytes();
+
+        // The vectors shrink from 4 bytes per dimension to 1 plus a 4-byte
+        // scale. Quantizing can change a few neighbour choices, so the links
+        // may differ by a handful of entries.
+        let saved = 150 * (64 * 4 - (64 + 4));
+        assert!(full.len().abs_diff(bytes.len() + saved) < 150 * 4);
+
+        let restored = AnnIndex::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.to_bytes(), bytes);
+        assert_eq!(
+            restored.search(&vectors[7], 5, 40, None, None),
+            index.search(&vectors[7], 5, 40, None, None)
+        );
+        assert_eq!(index.search(&vectors[7], 1, 40, None, None)[0].0, 7);
+    }
+
+    #[test]
+    fn damaged_blobs_are_errors() {
+        let vectors = random_vectors(50, 4, 6);
+        let bytes = build_index(&vectors).to_bytes();
+
+        assert!(AnnIndex::from_bytes(&bytes[..bytes.len() - 3]).is_err());
+        assert!(AnnIndex::from_bytes(b"nope").is_err());
+        let mut wrong_version = bytes.clone();
+        wrong_version[4] = 99;
+        assert!(AnnIndex::from_bytes(&wrong_version).is_err());
+        let mut wrong_precision = bytes.clone();
+        wrong_precision[16] = 9;
+        assert!(AnnIndex::from_bytes(&wrong_precision).is_err());
+    }
+}
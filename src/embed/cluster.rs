@@ -0,0 +1,190 @@
+//! Spherical k-means over stored chunk embeddings, for `grans topics`.
+//!
+//! Vectors are normalized first, so similarity is a dot product and each
+//! centroid is the normalized mean of its members. Seeding is k-means++
+//! from a fixed seed: the same vectors always cluster the same way, so a
+//! topic report is reproducible until the embeddings change.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Seed for k-means++ initialization.
+const SEED: u64 = 0x0067_7261_6e73;
+
+/// Upper bound on assignment/update rounds; clustering usually settles
+/// well before it.
+const MAX_ITERATIONS: usize = 30;
+
+/// The outcome of a clustering run.
+#[derive(Debug, Clone)]
+pub struct Clustering {
+    /// Cluster index for each input vector.
+    pub assignments: Vec<usize>,
+    /// Unit-length centroid per cluster. A cluster that lost every member
+    /// keeps its last centroid and simply has no assignments.
+    pub centroids: Vec<Vec<f32>>,
+    /// Cosine similarity of each input vector to its centroid.
+    pub similarities: Vec<f32>,
+}
+
+/// Cluster `vectors` into at most `k` groups (fewer when there are fewer
+/// vectors). Vectors of different lengths are compared on their common
+/// prefix, as search does with Matryoshka-truncated storage.
+pub fn kmeans(vectors: &[Vec<f32>], k: usize) -> Clustering {
+    let dim = vectors.iter().map(Vec::len).min().unwrap_or(0);
+    let points: Vec<Vec<f32>> = vectors.iter().map(|v| normalized(&v[..dim])).collect();
+    let k = k.min(points.len());
+    if k == 0 {
+        return Clustering {
+            assignments: Vec::new(),
+            centroids: Vec::new(),
+            similarities: Vec::new(),
+        };
+    }
+
+    let mut centroids = seed_centroids(&points, k);
+    let mut assignments = vec![usize::MAX; points.len()];
+    let mut similarities = vec![0.0; points.len()];
+    for _ in 0..MAX_ITERATIONS {
+        let mut changed = false;
+        for (i, point) in points.iter().enumerate() {
+            let (best, sim) = nearest(point, &centroids);
+            similarities[i] = sim;
+            if assignments[i] != best {
+                assignments[i] = best;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        let mut sums = vec![vec![0.0_f32; dim]; k];
+        for (point, &cluster) in points.iter().zip(&assignments) {
+            for (s, x) in sums[cluster].iter_mut().zip(point) {
+                *s += x;
+            }
+        }
+        for (centroid, sum) in centroids.iter_mut().zip(sums) {
+            if sum.iter().any(|x| *x != 0.0) {
+                *centroid = normalized(&sum);
+            }
+        }
+    }
+
+    Clustering {
+        assignments,
+        centroids,
+        similarities,
+    }
+}
+
+/// k-means++: each further seed is drawn with probability proportional to
+/// its distance from the nearest seed so far, spreading seeds across the
+/// space.
+fn seed_centroids(points: &[Vec<f32>], k: usize) -> Vec<Vec<f32>> {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let mut centroids = vec![points[rng.gen_range(0..points.len())].clone()];
+    let mut distances: Vec<f32> = points.iter().map(|p| 1.0 - dot(p, &centroids[0])).collect();
+
+    while centroids.len() < k {
+        let total: f32 = distances.iter().map(|d| d.max(0.0)).sum();
+        let next = if total <= 0.0 {
+            // Every point coincides with a seed: any unused one will do.
+            match distances.iter().position(|d| *d > 0.0) {
+                Some(i) => i,
+                None => break,
+            }
+        } else {
+            let mut target = rng.r#gen::<f32>() * total;
+            let mut chosen = points.len() - 1;
+            for (i, d) in distances.iter().enumerate() {
+                target -= d.max(0.0);
+                if target <= 0.0 {
+                    chosen = i;
+                    break;
+                }
+            }
+            chosen
+        };
+        let seed = points[next].clone();
+        for (d, p) in distances.iter_mut().zip(points) {
+            *d = d.min(1.0 - dot(p, &seed));
+        }
+        centroids.push(seed);
+    }
+    centroids
+}
+
+/// The closest centroid to `point` and its similarity.
+fn nearest(point: &[f32], centroids: &[Vec<f32>]) -> (usize, f32) {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, dot(point, c)))
+        .fold((0, f32::NEG_INFINITY), |best, (i, sim)| {
+            if sim > best.1 { (i, sim) } else { best }
+        })
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn normalized(v: &[f32]) -> Vec<f32> {
+    let norm = dot(v, v).sqrt();
+    if norm == 0.0 {
+        v.to_vec()
+    } else {
+        v.iter().map(|x| x / norm).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separates_well_apart_groups() {
+        let vectors = vec![
+            vec![1.0, 0.1, 0.0],
+            vec![0.9, 0.0, 0.1],
+            vec![0.0, 1.0, 0.1],
+            vec![0.1, 0.9, 0.0],
+            vec![0.0, 0.1, 1.0],
+            vec![0.1, 0.0, 0.9],
+        ];
+        let c = kmeans(&vectors, 3);
+        assert_eq!(c.assignments[0], c.assignments[1]);
+        assert_eq!(c.assignments[2], c.assignments[3]);
+        assert_eq!(c.assignments[4], c.assignments[5]);
+        assert_ne!(c.assignments[0], c.assignments[2]);
+        assert_ne!(c.assignments[2], c.assignments[4]);
+        assert!(c.similarities.iter().all(|s| *s > 0.9));
+    }
+
+    #[test]
+    fn k_is_capped_by_the_number_of_vectors() {
+        let c = kmeans(&[vec![1.0, 0.0], vec![0.0, 1.0]], 8);
+        assert_eq!(c.centroids.len(), 2);
+        assert_ne!(c.assignments[0], c.assignments[1]);
+    }
+
+    #[test]
+    fn clustering_is_reproducible() {
+        let vectors: Vec<Vec<f32>> = (0..40)
+            .map(|i| vec![(i as f32).sin(), (i as f32).cos(), (i % 3) as f32])
+            .collect();
+        assert_eq!(
+            kmeans(&vectors, 4).assignments,
+            kmeans(&vectors, 4).assignments
+        );
+    }
+
+    #[test]
+    fn no_vectors_no_clusters() {
+        let c = kmeans(&[], 5);
+        assert!(c.assignments.is_empty());
+        assert!(c.centroids.is_empty());
+    }
+}
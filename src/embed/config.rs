@@ -1,6 +1,6 @@
-//! The embedding spec: chunking parameters plus the contextual-header
-//! toggle. The spec is persisted in `embedding_metadata` alongside the
-//! model name, so any binary can tell how a database's chunks were made
+//! The embedding spec: the model, chunking parameters, the contextual-header
+//! toggle, and the vector storage layout. The spec is persisted in
+//! `embedding_metadata`, so any binary can tell how a database's chunks were made
 //! and keep embedding with that scheme instead of silently re-chunking
 //! to its own compiled-in defaults.
 
@@ -8,32 +8,46 @@ use anyhow::{Result, bail};
 use rusqlite::Connection;
 
 use super::chunker::{ChunkingConfig, OverlapMode};
+use super::model::{self, ModelInfo};
+use super::quant::{Precision, StorageSpec};
 use super::store;
 
 /// How the embeddings in a database are (or should be) built.
 #[derive(Debug, Clone)]
 pub struct EmbedSpec {
+    /// The model that embeds (or embedded) the chunks. Changing it
+    /// re-embeds everything.
+    pub model: &'static ModelInfo,
     pub chunking: ChunkingConfig,
     /// Prepend meeting title/date/attendees to the embed input.
     pub contextual_headers: bool,
+    /// How vectors are truncated and encoded in `embeddings.vector`.
+    /// Unlike the chunking fields, changing it re-encodes the stored
+    /// vectors instead of re-embedding them.
+    pub storage: StorageSpec,
 }
 
 /// Explicit overrides from `grans embed` experiment flags. `None` fields
 /// leave the resolved value untouched.
 #[derive(Debug, Clone, Default)]
 pub struct EmbedOverrides {
+    pub model: Option<&'static ModelInfo>,
     pub target_tokens: Option<usize>,
     pub overlap_tokens: Option<usize>,
     pub overlap_mode: Option<OverlapMode>,
     pub contextual_headers: Option<bool>,
+    pub storage_dim: Option<usize>,
+    pub precision: Option<Precision>,
 }
 
 impl EmbedSpec {
     /// The binary's default spec for a model with this token limit.
     pub fn default_for(max_tokens: usize) -> Self {
         Self {
+            model: model::default_model(),
             chunking: ChunkingConfig::from_max_length(max_tokens),
             contextual_headers: false,
+            storage: StorageSpec::default(),
         }
     }
 
@@ -43,6 +57,7 @@ impl EmbedSpec {
     /// with a variant scheme is never silently migrated back.
     pub fn resolve_stored(conn: &Connection, max_tokens: usize) -> Self {
         let mut spec = Self::default_for(max_tokens);
+        spec.model = model::stored_or_default(conn);
         let stored = store::get_chunking_metadata(conn);
         if let Some(t) = stored.target_tokens {
             spec.chunking.target_tokens = t;
@@ -56,11 +71,20 @@ impl EmbedSpec {
         if let Some(h) = stored.contextual_headers {
             spec.contextual_headers = h;
         }
+        spec.storage = store::get_storage_spec(conn);
         spec
     }
 
     /// Apply explicit overrides (from `grans embed` flags) and validate.
     pub fn with_overrides(mut self, overrides: &EmbedOverrides) -> Result<Self> {
+        if let Some(m) = overrides.model
+            && m != self.model
+        {
+            // Switching models re-embeds everything, so a truncation picked
+            // for the old model doesn't carry over.
+            self.model = m;
+            self.storage.dim = None;
+        }
         if let Some(t) = overrides.target_tokens {
             self.chunking.target_tokens = t;
         }
@@ -73,6 +97,12 @@ impl EmbedSpec {
         if let Some(h) = overrides.contextual_headers {
             self.contextual_headers = h;
         }
+        if let Some(d) = overrides.storage_dim {
+            self.storage.dim = Some(d);
+        }
+        if let Some(p) = overrides.precision {
+            self.storage.precision = p;
+        }
         self.validate()?;
         Ok(self)
     }
@@ -96,7 +126,13 @@ impl EmbedSpec {
                 c.target_tokens
             );
         }
-        Ok(())
+        if self.storage.dim.is_some() && !self.model.matryoshka {
+            bail!(
+                "{} is not Matryoshka-trained, so its vectors cannot be truncated with --dim",
+                self.model.name
+            );
+        }
+        self.storage.validate(self.model.dim)
     }
 
     /// The persisted dimensions of the spec, for change detection against
@@ -170,6 +206,7 @@ mod tests {
     fn resolve_stored_prefers_stored_params() {
         let conn = test_db();
         let variant = EmbedSpec {
+            model: model::default_model(),
             chunking: ChunkingConfig {
                 target_tokens: 192,
                 overlap_tokens: 48,
@@ -177,6 +214,7 @@ mod tests {
                 ..ChunkingConfig::from_max_length(512)
             },
             contextual_headers: true,
+            storage: StorageSpec::default(),
         };
         store::set_chunking_metadata(&conn, &variant).unwrap();
 
@@ -194,10 +232,13 @@ mod tests {
     fn overrides_win_and_validate() {
         let spec = EmbedSpec::default_for(512)
             .with_overrides(&EmbedOverrides {
+                model: None,
                 target_tokens: Some(192),
                 overlap_tokens: Some(48),
                 overlap_mode: Some(OverlapMode::Utterances),
                 contextual_headers: Some(true),
+                storage_dim: Some(256),
+                precision: Some(Precision::Int8),
             })
             .unwrap();
 
@@ -205,6 +246,68 @@ mod tests {
             spec.persisted_fields(),
             (192, 48, OverlapMode::Utterances, true)
         );
+        assert_eq!(
+            spec.storage,
+            StorageSpec {
+                dim: Some(256),
+                precision: Precision::Int8
+            }
+        );
+    }
+
+    #[test]
+    fn resolve_stored_reads_the_storage_layout() {
+        let conn = test_db();
+        let storage = StorageSpec {
+            dim: Some(128),
+            precision: Precision::F16,
+        };
+        store::set_storage_metadata(&conn, &storage).unwrap();
+
+        assert_eq!(EmbedSpec::resolve_stored(&conn, 512).storage, storage);
+    }
+
+    #[test]
+    fn resolve_stored_reads_the_model() {
+        let conn = test_db();
+        store::set_model_metadata(&conn, "multilingual-e5-base", 768, 512).unwrap();
+
+        assert_eq!(
+            EmbedSpec::resolve_stored(&conn, 512).model.name,
+            "multilingual-e5-base"
+        );
+    }
+
+    #[test]
+    fn switching_models_drops_the_stored_truncation() {
+        let mut spec = EmbedSpec::default_for(512);
+        spec.storage = StorageSpec {
+            dim: Some(256),
+            precision: Precision::Int8,
+        };
+
+        let spec = spec
+            .with_overrides(&EmbedOverrides {
+                model: model::find_model("multilingual-e5-small"),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(spec.model.name, "multilingual-e5-small");
+        assert_eq!(spec.storage.dim, None);
+        assert_eq!(spec.storage.precision, Precision::Int8);
+    }
+
+    #[test]
+    fn overrides_reject_truncating_a_non_matryoshka_model() {
+        let err = EmbedSpec::default_for(512)
+            .with_overrides(&EmbedOverrides {
+                model: model::find_model("bge-base-en-v1.5"),
+                storage_dim: Some(256),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("Matryoshka"));
     }
 
     #[test]
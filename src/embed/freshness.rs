@@ -16,6 +16,7 @@ use anyhow::Result;
 use chrono::{DateTime, FixedOffset};
 use rusqlite::Connection;
 
+use super::ann::AnnIndex;
 use super::{EmbeddingIndex, store};
 use crate::db;
 
@@ -52,6 +53,7 @@ pub fn load_search_index(
     let empty = || EmbeddingIndex {
         vectors: Vec::new(),
         stats: None,
+        ann: None,
     };
 
     match store::get_model_name(conn) {
@@ -62,8 +64,23 @@ pub fn load_search_index(
         Some(_) => {}
     }
 
-    let vectors = store::load_all_vectors(conn)?;
-    if vectors.is_empty() {
+    // A persisted ANN graph over exactly these vectors spares loading them.
+    let ann = match store::load_ann_index(conn, &store::ann_signature(conn)?)? {
+        Some(bytes) => match AnnIndex::from_bytes(&bytes) {
+            Ok(ann) if !ann.is_empty() => Some(ann),
+            Ok(_) => None,
+            Err(e) => {
+                log::warn!("Ignoring unreadable ANN index: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+    let vectors = match ann {
+        Some(_) => Vec::new(),
+        None => store::load_all_vectors(conn)?,
+    };
+    if vectors.is_empty() && ann.is_none() {
         return Ok((empty(), IndexFreshness::Empty));
     }
 
@@ -78,6 +95,7 @@ pub fn load_search_index(
         EmbeddingIndex {
             vectors,
             stats: None,
+            ann,
         },
         freshness,
     ))
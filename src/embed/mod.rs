@@ -1,10 +1,14 @@
+pub mod ann;
 pub mod chunk;
 pub mod chunker;
+pub mod cluster;
 pub mod config;
 pub mod freshness;
 pub mod headers;
 pub mod model;
+pub mod offline;
 pub mod progress;
+pub mod quant;
 pub mod rerank;
 pub mod search;
 pub mod store;
@@ -335,11 +339,23 @@ fn chunk_metadata_differs(stored: Option<&str>, desired: Option<&serde_json::Val
 /// Used by `grans embed --force` to force re-embedding.
 pub fn wipe_all_embeddings(conn: &Connection) -> Result<()> {
     conn.execute_batch(
-        "DELETE FROM embeddings; DELETE FROM chunks; DELETE FROM embedding_metadata;",
+        "DELETE FROM embeddings; DELETE FROM chunks; DELETE FROM embedding_metadata; \
+         DELETE FROM ann_index;",
     )?;
     Ok(())
 }
 
+/// Bring the stored vectors to the `storage` layout, re-encoding them in
+/// place when it differs from the recorded one (no embedding model
+/// needed). Returns how many vectors were rewritten.
+pub fn apply_storage_spec(conn: &Connection, storage: &quant::StorageSpec) -> Result<usize> {
+    let count = store::reencode_vectors(conn, storage)?;
+    if count > 0 {
+        eprintln!("[grans] Re-encoded {} vectors as {}.", count, storage);
+    }
+    Ok(count)
+}
+
 /// Speed statistics from an embedding run.
 #[derive(Debug, Clone, Serialize)]
 pub struct EmbeddingStats {
@@ -351,26 +367,163 @@ pub struct EmbeddingStats {
     pub chunks_per_sec: f64,
 }
 
+/// How many chunks an ANN query first asks the graph for. Results collapse
+/// to the best chunk per document, so a search that gets back fewer
+/// documents than it was asked for doubles this and walks again.
+const ANN_CANDIDATES: usize = 500;
+
 /// In-memory index of all embedded vectors, ready for search.
 pub struct EmbeddingIndex {
+    /// Every stored vector, for an exact scan. Empty when `ann` is set.
     pub vectors: Vec<StoredVector>,
     /// Stats from the embedding run, if any chunks were embedded.
     pub stats: Option<EmbeddingStats>,
+    /// The persisted ANN graph, when one matches the stored vectors. Search
+    /// then reads only the candidate chunks' rows instead of every vector.
+    pub ann: Option<ann::AnnIndex>,
 }
 
 impl EmbeddingIndex {
+    /// The best chunk of each document in `scope` for `query_vec`, best
+    /// first. `limit` is how many documents the caller wants; 0 wants every
+    /// match.
     pub fn search(
         &self,
+        conn: &Connection,
         query_vec: &[f32],
         min_score: f32,
         source_type_filter: Option<&[&str]>,
-    ) -> Vec<SemanticSearchResult> {
-        search::rank_results(query_vec, &self.vectors, min_score, source_type_filter)
+        scope: &DocumentScope,
+        limit: usize,
+    ) -> Result<Vec<SemanticSearchResult>> {
+        let Some(ann) = &self.ann else {
+            let results =
+                search::rank_results(query_vec, &self.vectors, min_score, source_type_filter);
+            return scope.filter(conn, results);
+        };
+
+        // Stored vectors may be a Matryoshka prefix of the query's.
+        let query_vec = &query_vec[..ann.dim().min(query_vec.len())];
+        let documents = scope.document_ids(conn)?;
+        let chunk_documents: HashMap<i64, String> = store::chunk_documents(conn)?
+            .into_iter()
+            .filter(|(_, document_id)| documents.contains(document_id))
+            .collect();
+        let in_scope = |chunk_id: i64| chunk_documents.contains_key(&chunk_id);
+
+        let hits: Vec<(i64, f32)> = if limit == 0 {
+            // No bounded walk can promise every match, so score every
+            // vector in scope exactly: the graph already holds them all,
+            // and only each document's best chunk is read back.
+            let mut best: HashMap<&str, (i64, f32)> = HashMap::new();
+            for (chunk_id, score) in ann.scan(query_vec, source_type_filter, Some(&in_scope)) {
+                let entry = best
+                    .entry(chunk_documents[&chunk_id].as_str())
+                    .or_insert((chunk_id, score));
+                if score > entry.1 {
+                    *entry = (chunk_id, score);
+                }
+            }
+            best.into_values().collect()
+        } else {
+            // Widen the walk until it covers `limit` documents or has
+            // returned every chunk in scope it can reach.
+            let mut k = ANN_CANDIDATES.max(limit);
+            loop {
+                let hits = ann.search(query_vec, k, k, source_type_filter, Some(&in_scope));
+                let found: HashSet<&str> = hits
+                    .iter()
+                    .map(|(id, _)| chunk_documents[id].as_str())
+                    .collect();
+                if found.len() >= limit || hits.len() < k || k >= ann.len() {
+                    break hits;
+                }
+                k = (k * 2).min(ann.len());
+            }
+        };
+
+        let scores: HashMap<i64, f32> = hits.iter().copied().collect();
+        let ids: Vec<i64> = hits.iter().map(|(id, _)| *id).collect();
+        let chunks = store::load_chunks_by_ids(conn, &ids)?;
+        Ok(search::best_per_document(
+            chunks.iter().map(|c| (c, scores[&c.chunk_id])),
+            min_score,
+        ))
+    }
+
+    /// Number of vectors searchable through this index.
+    pub fn len(&self) -> usize {
+        self.ann.as_ref().map_or(self.vectors.len(), |a| a.len())
     }
 
     pub fn is_empty(&self) -> bool {
-        self.vectors.is_empty()
+        self.len() == 0
+    }
+}
+
+/// What [`refresh_ann_index`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnRefresh {
+    /// Built a new graph over this many vectors.
+    Built(usize),
+    /// The stored graph already matches the stored vectors.
+    Unchanged,
+    /// Too few vectors to be worth a graph; any stored one was dropped and
+    /// search scans exactly.
+    Skipped,
+}
+
+impl AnnRefresh {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnnRefresh::Built(_) => "built",
+            AnnRefresh::Unchanged => "unchanged",
+            AnnRefresh::Skipped => "skipped",
+        }
+    }
+}
+
+/// Bring the persisted ANN graph in line with the stored vectors: rebuild
+/// it when the vector set, model or chunking scheme changed since it was
+/// built (see [`store::ann_signature`]), and drop it below
+/// [`ann::ANN_MIN_VECTORS`]. Vectors are only loaded for a rebuild.
+pub fn refresh_ann_index(conn: &Connection) -> Result<AnnRefresh> {
+    refresh_ann_index_with_min(conn, ann::ANN_MIN_VECTORS)
+}
+
+fn refresh_ann_index_with_min(conn: &Connection, min_vectors: usize) -> Result<AnnRefresh> {
+    let count: i64 = conn.query_row("SELECT COUNT(*) FROM embeddings", [], |row| row.get(0))?;
+    if (count as usize) < min_vectors {
+        store::clear_ann_index(conn)?;
+        return Ok(AnnRefresh::Skipped);
+    }
+
+    let signature = store::ann_signature(conn)?;
+    if store::stored_ann_signature(conn)?.as_deref() == Some(signature.as_str()) {
+        return Ok(AnnRefresh::Unchanged);
     }
+
+    let start = Instant::now();
+    let stored = store::load_all_vectors(conn)?;
+    let vectors: Vec<Vec<f32>> = stored.iter().map(|v| v.vector.to_f32()).collect();
+    let dim = vectors.first().map_or(0, Vec::len);
+    let index = ann::AnnIndex::build(
+        dim,
+        stored
+            .iter()
+            .zip(&vectors)
+            .filter(|(_, v)| v.len() == dim)
+            .map(|(s, v)| (s.chunk_id, s.source_type.as_str(), v.as_slice())),
+        ann::HnswParams::default(),
+        store::get_storage_spec(conn).precision,
+    );
+    store::save_ann_index(conn, &signature, &index.to_bytes())?;
+    eprintln!(
+        "[grans] Built ANN index over {} vectors in {:.1}s.",
+        index.len(),
+        start.elapsed().as_secs_f64()
+    );
+    Ok(AnnRefresh::Built(index.len()))
 }
 
 /// Default batch size for embedding. Can be overridden via --batch-size flag.
@@ -397,6 +550,8 @@ pub fn ensure_embeddings(
 
     // Check model consistency — if model changed, all embeddings are wiped
     store::check_model_consistency(conn, embedder.model_name())?;
+    spec.storage.validate(embedder.dimension())?;
+    apply_storage_spec(conn, &spec.storage)?;
 
     let desired_chunks = desired_chunks_for_spec(conn, spec)?;
 
@@ -485,7 +640,8 @@ pub fn ensure_embeddings(
                         .map(|(chunk, vec)| (*chunk, vec.as_slice()))
                         .collect();
 
-                    let results = store::insert_chunks_with_embeddings_batch(conn, &items);
+                    let results =
+                        store::insert_chunks_with_embeddings_batch(conn, &items, &spec.storage);
 
                     for (i, result) in results.iter().enumerate() {
                         if let Err(e) = result {
@@ -552,58 +708,82 @@ pub fn ensure_embeddings(
     // Load all vectors for search
     let vectors = store::load_all_vectors(conn)?;
 
-    Ok(EmbeddingIndex { vectors, stats })
+    Ok(EmbeddingIndex {
+        vectors,
+        stats,
+        ann: None,
+    })
 }
 
-/// Restrict semantic hits to documents that pass the same document-level
-/// filters the FTS retriever applies in SQL: the document exists, is not
-/// deleted (unless deleted documents were requested), and falls inside
-/// the optional date range. Stored vectors outlive their documents
-/// between embed runs, so this boundary is what keeps orphaned vectors
-/// out of results. The allowed set comes from one scan of `documents`
-/// (thousands of rows) rather than an IN-list over per-chunk results,
-/// which could exceed SQLite's variable limit.
-fn filter_results_by_documents(
-    conn: &Connection,
-    results: Vec<SemanticSearchResult>,
-    date_range: Option<&crate::query::dates::DateRange>,
-    include_deleted: bool,
-) -> Result<Vec<SemanticSearchResult>> {
-    use std::collections::HashSet;
+/// The documents a semantic search may return. An ANN search applies it
+/// while walking the graph, so a narrow scope still fills its results
+/// instead of filtering the graph's nearest chunks after the fact.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DocumentScope<'a> {
+    pub date_range: Option<&'a crate::query::dates::DateRange>,
+    pub include_deleted: bool,
+    /// Only these documents, when set: the meetings the filters allow.
+    pub allowed: Option<&'a HashSet<String>>,
+    /// Never this document: the source of a similarity search, whose own
+    /// chunks would otherwise be the nearest to it.
+    pub exclude: Option<&'a str>,
+}
 
-    if results.is_empty() {
-        return Ok(results);
+impl DocumentScope<'_> {
+    /// Ids of the documents in scope, under the same document-level filters
+    /// the FTS retriever applies in SQL: the document exists, is not
+    /// deleted (unless deleted documents were requested), and falls inside
+    /// the optional date range. Stored vectors outlive their documents
+    /// between embed runs, so this boundary is what keeps orphaned vectors
+    /// out of results. The set comes from one scan of `documents`
+    /// (thousands of rows) rather than an IN-list over per-chunk results,
+    /// which could exceed SQLite's variable limit.
+    fn document_ids(&self, conn: &Connection) -> Result<HashSet<String>> {
+        let sql = if self.include_deleted {
+            "SELECT id, created_at FROM documents"
+        } else {
+            "SELECT id, created_at FROM documents WHERE deleted_at IS NULL"
+        };
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map([], |row| {
+            let id: String = row.get(0)?;
+            let created_at: Option<String> = row.get(1)?;
+            Ok((id, created_at))
+        })?;
+
+        let mut ids = HashSet::new();
+        for row in rows {
+            let (id, created_at) = row?;
+            if created_in_range(created_at.as_deref(), self.date_range)
+                && self.allowed.is_none_or(|allowed| allowed.contains(&id))
+                && self.exclude != Some(id.as_str())
+            {
+                ids.insert(id);
+            }
+        }
+        Ok(ids)
     }
 
-    let sql = if include_deleted {
-        "SELECT id, created_at FROM documents"
-    } else {
-        "SELECT id, created_at FROM documents WHERE deleted_at IS NULL"
-    };
-    let mut stmt = conn.prepare(sql)?;
-    let rows = stmt.query_map([], |row| {
-        let id: String = row.get(0)?;
-        let created_at: Option<String> = row.get(1)?;
-        Ok((id, created_at))
-    })?;
-
-    let mut allowed: HashSet<String> = HashSet::new();
-    for row in rows {
-        let (id, created_at) = row?;
-        if created_in_range(created_at.as_deref(), date_range) {
-            allowed.insert(id);
+    /// Keep only the results whose documents are in scope.
+    fn filter(
+        &self,
+        conn: &Connection,
+        results: Vec<SemanticSearchResult>,
+    ) -> Result<Vec<SemanticSearchResult>> {
+        if results.is_empty() {
+            return Ok(results);
         }
+        let ids = self.document_ids(conn)?;
+        Ok(results
+            .into_iter()
+            .filter(|r| ids.contains(&r.document_id))
+            .collect())
     }
-
-    Ok(results
-        .into_iter()
-        .filter(|r| allowed.contains(&r.document_id))
-        .collect())
 }
 
 /// Whether a document's creation date falls inside the range. No range
 /// admits everything; with a range, a missing or unparseable date is out.
-fn created_in_range(
+pub(crate) fn created_in_range(
     created_at: Option<&str>,
     date_range: Option<&crate::query::dates::DateRange>,
 ) -> bool {
@@ -630,29 +810,23 @@ fn created_in_range(
 
 /// Run a semantic search against an already-loaded embedder and index.
 /// Callers that issue many queries (hybrid search, benchmarks) load the
-/// model and index once and reuse them here.
-#[allow(clippy::too_many_arguments)]
+/// model and index once and reuse them here. Only documents in `scope`
+/// are returned; `limit` 0 returns every match.
 pub fn semantic_search_with_index(
     conn: &Connection,
     embedder: &dyn Embedder,
     index: &EmbeddingIndex,
     query: &str,
-    date_range: Option<&crate::query::dates::DateRange>,
+    scope: &DocumentScope,
     limit: usize,
     source_type_filter: Option<&[&str]>,
-    include_deleted: bool,
 ) -> Result<(Vec<SemanticSearchResult>, usize)> {
     if index.is_empty() {
         return Ok((Vec::new(), 0));
     }
 
     let query_vec = embedder.embed_query(query)?;
-    let mut results = index.search(&query_vec, 0.0, source_type_filter);
-
-    // Document-level constraints (existence, deletion, date) always apply:
-    // the read-only index may hold vectors for documents that were deleted
-    // or edited since the last embed (#126).
-    results = filter_results_by_documents(conn, results, date_range, include_deleted)?;
+    let mut results = index.search(conn, &query_vec, 0.0, source_type_filter, scope, limit)?;
 
     // Capture total count before applying limit
     let total_count = results.len();
@@ -665,6 +839,39 @@ pub fn semantic_search_with_index(
     Ok((results, total_count))
 }
 
+/// Rank other documents by similarity to `document_id`, whose stored chunk
+/// vectors are averaged into one document vector (see
+/// [`search::document_vector`]). Each other document scores by its chunk
+/// closest to that vector. None when the document has no embeddings.
+pub fn similar_documents_with_index(
+    conn: &Connection,
+    index: &EmbeddingIndex,
+    document_id: &str,
+    date_range: Option<&crate::query::dates::DateRange>,
+    source_type_filter: Option<&[&str]>,
+    include_deleted: bool,
+) -> Result<Option<Vec<SemanticSearchResult>>> {
+    let chunks = store::load_document_vectors(conn, document_id)?;
+    let Some(doc_vec) = search::document_vector(&chunks) else {
+        return Ok(None);
+    };
+
+    let scope = DocumentScope {
+        date_range,
+        include_deleted,
+        allowed: None,
+        exclude: Some(document_id),
+    };
+    Ok(Some(index.search(
+        conn,
+        &doc_vec,
+        0.0,
+        source_type_filter,
+        &scope,
+        0,
+    )?))
+}
+
 /// Run a semantic search with a provided embedder (for testing).
 /// Returns a tuple of (results, total_count). Uses limit=0 (no limit) by default.
 #[cfg(test)]
@@ -687,7 +894,15 @@ pub fn semantic_search_with_embedder_and_limit(
 ) -> Result<(Vec<SemanticSearchResult>, usize)> {
     let spec = config::EmbedSpec::resolve_stored(conn, embedder.max_length());
     let index = ensure_embeddings(conn, embedder, DEFAULT_BATCH_SIZE, &spec)?;
-    semantic_search_with_index(conn, embedder, &index, query, None, limit, None, false)
+    semantic_search_with_index(
+        conn,
+        embedder,
+        &index,
+        query,
+        &DocumentScope::default(),
+        limit,
+        None,
+    )
 }
 
 #[cfg(test)]
@@ -740,6 +955,54 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn similar_documents_exclude_the_source_meeting() {
+        let conn = setup_test_db();
+        let long = "This is a longer utterance that contains enough characters to meet the minimum chunk size requirement for embedding.";
+        insert_utterances(&conn, "doc1", &[long]);
+        insert_utterances(&conn, "doc2", &[long]);
+        insert_utterances(&conn, "doc3", &[long]);
+        conn.execute(
+            "INSERT INTO documents (id, title, created_at) VALUES ('empty', 'Empty', '2025-01-01T00:00:00Z')",
+            [],
+        )
+        .unwrap();
+
+        let embedder = MockEmbedder::default();
+        let index = ensure_embeddings(
+            &conn,
+            &embedder,
+            DEFAULT_BATCH_SIZE,
+            &config::EmbedSpec::default_for(512),
+        )
+        .unwrap();
+
+        let results = similar_documents_with_index(&conn, &index, "doc1", None, None, false)
+            .unwrap()
+            .unwrap();
+        let mut ids: Vec<&str> = results.iter().map(|r| r.document_id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["doc2", "doc3"]);
+
+        assert!(
+            similar_documents_with_index(&conn, &index, "empty", None, None, false)
+                .unwrap()
+                .is_none()
+        );
+
+        // Through an ANN graph the source is left out during the search,
+        // so its own chunks, nearest to its vector, crowd nothing out.
+        refresh_ann_index_with_min(&conn, 1).unwrap();
+        let (ann, _) = freshness::load_search_index(&conn, embedder.model_name()).unwrap();
+        assert!(ann.ann.is_some());
+        let results = similar_documents_with_index(&conn, &ann, "doc1", None, None, false)
+            .unwrap()
+            .unwrap();
+        let mut ids: Vec<&str> = results.iter().map(|r| r.document_id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["doc2", "doc3"]);
+    }
+
     #[test]
     fn ensure_embeddings_stores_the_pre_read_sync_watermark() {
         // Regression for #129 review: the stamp used to be the run's
@@ -772,6 +1035,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ensure_embeddings_writes_the_requested_storage_layout() {
+        let conn = setup_test_db();
+        insert_utterances(
+            &conn,
+            "doc1",
+            &[
+                "This is a longer utterance that contains enough characters to meet the minimum chunk size requirement for embedding.",
+            ],
+        );
+        let embedder = MockEmbedder::default();
+        let mut spec = config::EmbedSpec::default_for(512);
+        spec.storage = quant::StorageSpec {
+            dim: Some(256),
+            precision: quant::Precision::Int8,
+        };
+
+        let index = ensure_embeddings(&conn, &embedder, DEFAULT_BATCH_SIZE, &spec).unwrap();
+
+        assert_eq!(store::get_storage_spec(&conn), spec.storage);
+        assert!(!index.vectors.is_empty());
+        for v in &index.vectors {
+            assert!(matches!(v.vector, quant::QuantizedVector::Int8 { .. }));
+            assert_eq!(v.vector.len(), 256);
+        }
+        let query = embedder.embed_query("utterance").unwrap();
+        assert!(
+            !index
+                .search(&conn, &query, 0.0, None, &DocumentScope::default(), 0)
+                .unwrap()
+                .is_empty()
+        );
+
+        // The ANN graph persists its vectors in the same encoding, so the
+        // blob stays under what f32 copies alone would take.
+        refresh_ann_index_with_min(&conn, 1).unwrap();
+        let signature = store::ann_signature(&conn).unwrap();
+        let blob = store::load_ann_index(&conn, &signature).unwrap().unwrap();
+        assert!(blob.len() < index.vectors.len() * 256 * 4);
+
+        // Raising the precision again cannot recover what int8 dropped;
+        // narrowing further re-encodes in place.
+        spec.storage.precision = quant::Precision::F16;
+        assert!(ensure_embeddings(&conn, &embedder, DEFAULT_BATCH_SIZE, &spec).is_err());
+        spec.storage = quant::StorageSpec {
+            dim: Some(128),
+            precision: quant::Precision::Int8,
+        };
+        ensure_embeddings(&conn, &embedder, DEFAULT_BATCH_SIZE, &spec).unwrap();
+        let vectors = store::load_all_vectors(&conn).unwrap();
+        assert!(matches!(
+            vectors[0].vector,
+            quant::QuantizedVector::Int8 { .. }
+        ));
+        assert_eq!(vectors[0].vector.len(), 128);
+    }
+
     #[test]
     fn ensure_embeddings_certifies_when_nothing_to_embed() {
         // A no-op run still certifies the store covers the syncs recorded
@@ -925,9 +1245,12 @@ mod tests {
         let embedder = MockEmbedder::default();
         let spec = config::EmbedSpec::resolve_stored(&conn, embedder.max_length());
         let index = ensure_embeddings(&conn, &embedder, DEFAULT_BATCH_SIZE, &spec).unwrap();
+        let scope = DocumentScope {
+            include_deleted: true,
+            ..Default::default()
+        };
         let (results, _) =
-            semantic_search_with_index(&conn, &embedder, &index, "query", None, 0, None, true)
-                .unwrap();
+            semantic_search_with_index(&conn, &embedder, &index, "query", &scope, 0, None).unwrap();
 
         assert!(results.iter().any(|r| r.document_id == "doc-gone"));
     }
@@ -951,9 +1274,16 @@ mod tests {
         conn.execute("DELETE FROM documents WHERE id = 'doc-ghost'", [])
             .unwrap();
 
-        let (results, _) =
-            semantic_search_with_index(&conn, &embedder, &index, "query", None, 0, None, false)
-                .unwrap();
+        let (results, _) = semantic_search_with_index(
+            &conn,
+            &embedder,
+            &index,
+            "query",
+            &DocumentScope::default(),
+            0,
+            None,
+        )
+        .unwrap();
 
         assert!(results.iter().any(|r| r.document_id == "doc-live"));
         assert!(results.iter().all(|r| r.document_id != "doc-ghost"));
@@ -1344,6 +1674,7 @@ mod tests {
 
         let embedder = MockEmbedder::default();
         let variant = config::EmbedSpec {
+            model: model::default_model(),
             chunking: chunker::ChunkingConfig {
                 target_tokens: 100,
                 overlap_tokens: 20,
@@ -1351,6 +1682,7 @@ mod tests {
                 ..chunker::ChunkingConfig::from_max_length(512)
             },
             contextual_headers: true,
+            storage: Default::default(),
         };
 
         // Variant embed (what `grans embed --flags` will do).
@@ -1642,4 +1974,186 @@ mod tests {
         assert_eq!(status.embedded_chunks, 0);
         assert_eq!(status.pending_by_type.total(), status.total_chunks);
     }
+
+    fn embed_docs(conn: &Connection, docs: &[(&str, &str)]) {
+        for (doc_id, text) in docs {
+            insert_utterances(conn, doc_id, &[text]);
+        }
+        ensure_embeddings(
+            conn,
+            &MockEmbedder::default(),
+            DEFAULT_BATCH_SIZE,
+            &config::EmbedSpec::default_for(512),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn ann_index_is_rebuilt_only_when_the_vectors_change() {
+        let conn = setup_test_db();
+        embed_docs(
+            &conn,
+            &[(
+                "doc1",
+                "Quarterly budget review with the finance team covering hiring plans and spend.",
+            )],
+        );
+
+        assert_eq!(
+            refresh_ann_index_with_min(&conn, 1).unwrap(),
+            AnnRefresh::Built(1)
+        );
+        assert_eq!(
+            refresh_ann_index_with_min(&conn, 1).unwrap(),
+            AnnRefresh::Unchanged
+        );
+
+        embed_docs(
+            &conn,
+            &[(
+                "doc2",
+                "Design review of the onboarding flow with product and engineering leads.",
+            )],
+        );
+        assert_eq!(
+            refresh_ann_index_with_min(&conn, 1).unwrap(),
+            AnnRefresh::Built(2)
+        );
+
+        // Below the threshold the stored graph is dropped.
+        assert_eq!(
+            refresh_ann_index_with_min(&conn, 100).unwrap(),
+            AnnRefresh::Skipped
+        );
+        assert!(store::stored_ann_signature(&conn).unwrap().is_none());
+    }
+
+    #[test]
+    fn ann_search_matches_exact_search() {
+        let conn = setup_test_db();
+        embed_docs(
+            &conn,
+            &[
+                (
+                    "doc1",
+                    "Quarterly budget review with the finance team covering hiring plans and spend.",
+                ),
+                (
+                    "doc2",
+                    "Design review of the onboarding flow with product and engineering leads.",
+                ),
+                (
+                    "doc3",
+                    "Customer escalation about delayed shipments and the carrier contract renewal.",
+                ),
+            ],
+        );
+        let embedder = MockEmbedder::default();
+        let (exact, _) = freshness::load_search_index(&conn, embedder.model_name()).unwrap();
+        assert!(exact.ann.is_none());
+
+        refresh_ann_index_with_min(&conn, 1).unwrap();
+        let (index, _) = freshness::load_search_index(&conn, embedder.model_name()).unwrap();
+        assert!(index.ann.is_some());
+        assert!(index.vectors.is_empty());
+        assert_eq!(index.len(), exact.len());
+
+        let search = |index: &EmbeddingIndex| {
+            semantic_search_with_index(
+                &conn,
+                &embedder,
+                index,
+                "carrier contract for shipments",
+                &DocumentScope::default(),
+                0,
+                None,
+            )
+            .unwrap()
+            .0
+            .into_iter()
+            .map(|r| (r.document_id, r.matched_text))
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(search(&index), search(&exact));
+    }
+
+    #[test]
+    fn ann_search_fills_a_scope_that_excludes_the_nearest_chunks() {
+        // More chunks than one graph walk first asks for, with a scope
+        // holding only the documents exact search ranks last: filtering
+        // the walk's hits afterwards would find none of them.
+        let conn = setup_test_db();
+        let texts: Vec<(String, String)> = (0..ANN_CANDIDATES + 100)
+            .map(|i| {
+                (
+                    format!("doc{:04}", i),
+                    format!(
+                        "Meeting {} reviewed item {} of the plan, owner {}, and the follow-ups due by week {}.",
+                        i,
+                        i * 7 % 31,
+                        i % 13,
+                        i % 52
+                    ),
+                )
+            })
+            .collect();
+        let docs: Vec<(&str, &str)> = texts
+            .iter()
+            .map(|(id, text)| (id.as_str(), text.as_str()))
+            .collect();
+        embed_docs(&conn, &docs);
+        let embedder = MockEmbedder::default();
+        let query = embedder.embed_query("follow-ups for the plan").unwrap();
+
+        let (exact, _) = freshness::load_search_index(&conn, embedder.model_name()).unwrap();
+        let every = exact
+            .search(&conn, &query, 0.0, None, &DocumentScope::default(), 0)
+            .unwrap();
+        assert_eq!(every.len(), docs.len());
+        let last: HashSet<String> = every[every.len() - 20..]
+            .iter()
+            .map(|r| r.document_id.clone())
+            .collect();
+        let scope = DocumentScope {
+            allowed: Some(&last),
+            ..Default::default()
+        };
+        let ids = |results: Vec<SemanticSearchResult>| {
+            results
+                .into_iter()
+                .map(|r| r.document_id)
+                .collect::<Vec<_>>()
+        };
+        let expected = ids(exact.search(&conn, &query, 0.0, None, &scope, 0).unwrap());
+        assert_eq!(expected.len(), 20);
+
+        refresh_ann_index_with_min(&conn, 1).unwrap();
+        let (index, _) = freshness::load_search_index(&conn, embedder.model_name()).unwrap();
+        assert!(index.ann.is_some());
+
+        // Every match: the whole scope, ranked exactly.
+        let all = ids(index.search(&conn, &query, 0.0, None, &scope, 0).unwrap());
+        assert_eq!(all, expected);
+        // A limit: the walk widens until it reaches that many documents.
+        let top = ids(index.search(&conn, &query, 0.0, None, &scope, 5).unwrap());
+        assert!(top.len() >= 5, "{:?}", top);
+        assert!(top.iter().all(|id| last.contains(id)));
+    }
+
+    #[test]
+    fn wipe_all_embeddings_drops_the_ann_index() {
+        let conn = setup_test_db();
+        embed_docs(
+            &conn,
+            &[(
+                "doc1",
+                "Quarterly budget review with the finance team covering hiring plans and spend.",
+            )],
+        );
+        refresh_ann_index_with_min(&conn, 1).unwrap();
+
+        wipe_all_embeddings(&conn).unwrap();
+
+        assert!(store::stored_ann_signature(&conn).unwrap().is_none());
+    }
 }
@@ -2,7 +2,7 @@ use std::cell::RefCell;
 use std::env;
 use std::sync::OnceLock;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 
 use crate::platform;
 
@@ -16,14 +16,133 @@ pub trait Embedder {
     fn max_length(&self) -> usize;
 }
 
-/// Identity of the production embedder, exposed so status checks can compare
-/// stored metadata against it without loading the ONNX model.
+/// Name of the default embedding model, used when a database records none.
 pub const MODEL_NAME: &str = "nomic-embed-text-v1.5";
 
-/// Production embedder using fastembed (nomic-embed-text-v1.5).
+/// A fastembed model `grans embed --model` can run. Every entry runs at
+/// [`MODEL_MAX_TOKENS`](super::MODEL_MAX_TOKENS), so chunking is the same
+/// whichever model embeds it.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ModelInfo {
+    /// Name recorded in `embedding_metadata` and accepted by `--model`.
+    pub name: &'static str,
+    pub model: fastembed::EmbeddingModel,
+    pub dim: usize,
+    /// Handles non-English text.
+    pub multilingual: bool,
+    /// Trained so a prefix of each vector still ranks well, which is what
+    /// makes `--dim` truncation meaningful.
+    pub matryoshka: bool,
+    /// Prepended to queries and to documents; E5 models were trained to
+    /// expect these markers and rank noticeably worse without them.
+    pub query_prefix: &'static str,
+    pub document_prefix: &'static str,
+}
+
+/// The models grans supports, the default first.
+pub const MODELS: &[ModelInfo] = &[
+    ModelInfo {
+        name: MODEL_NAME,
+        model: fastembed::EmbeddingModel::NomicEmbedTextV15,
+        dim: 768,
+        multilingual: false,
+        matryoshka: true,
+        query_prefix: "",
+        document_prefix: "",
+    },
+    ModelInfo {
+        name: "bge-small-en-v1.5",
+        model: fastembed::EmbeddingModel::BGESmallENV15,
+        dim: 384,
+        multilingual: false,
+        matryoshka: false,
+        query_prefix: "",
+        document_prefix: "",
+    },
+    ModelInfo {
+        name: "bge-base-en-v1.5",
+        model: fastembed::EmbeddingModel::BGEBaseENV15,
+        dim: 768,
+        multilingual: false,
+        matryoshka: false,
+        query_prefix: "",
+        document_prefix: "",
+    },
+    ModelInfo {
+        name: "bge-large-en-v1.5",
+        model: fastembed::EmbeddingModel::BGELargeENV15,
+        dim: 1024,
+        multilingual: false,
+        matryoshka: false,
+        query_prefix: "",
+        document_prefix: "",
+    },
+    ModelInfo {
+        name: "multilingual-e5-small",
+        model: fastembed::EmbeddingModel::MultilingualE5Small,
+        dim: 384,
+        multilingual: true,
+        matryoshka: false,
+        query_prefix: "query: ",
+        document_prefix: "passage: ",
+    },
+    ModelInfo {
+        name: "multilingual-e5-base",
+        model: fastembed::EmbeddingModel::MultilingualE5Base,
+        dim: 768,
+        multilingual: true,
+        matryoshka: false,
+        query_prefix: "query: ",
+        document_prefix: "passage: ",
+    },
+    ModelInfo {
+        name: "multilingual-e5-large",
+        model: fastembed::EmbeddingModel::MultilingualE5Large,
+        dim: 1024,
+        multilingual: true,
+        matryoshka: false,
+        query_prefix: "query: ",
+        document_prefix: "passage: ",
+    },
+    ModelInfo {
+        name: "bge-m3",
+        model: fastembed::EmbeddingModel::BGEM3,
+        dim: 1024,
+        multilingual: true,
+        matryoshka: false,
+        query_prefix: "",
+        document_prefix: "",
+    },
+];
+
+/// The default model ([`MODEL_NAME`]).
+pub fn default_model() -> &'static ModelInfo {
+    &MODELS[0]
+}
+
+/// Look up a supported model by name.
+pub fn find_model(name: &str) -> Option<&'static ModelInfo> {
+    MODELS.iter().find(|m| m.name == name)
+}
+
+/// Names of all supported models, for `--model` validation.
+pub fn model_names() -> Vec<&'static str> {
+    MODELS.iter().map(|m| m.name).collect()
+}
+
+/// The model a database was embedded with, or the default when it records
+/// none (or one this build doesn't know, which search then reports as a
+/// model mismatch).
+pub fn stored_or_default(conn: &rusqlite::Connection) -> &'static ModelInfo {
+    super::store::get_model_name(conn)
+        .and_then(|name| find_model(&name))
+        .unwrap_or_else(default_model)
+}
+
+/// Production embedder using fastembed.
 pub struct FastEmbedModel {
     model: RefCell<fastembed::TextEmbedding>,
-    dim: usize,
+    info: &'static ModelInfo,
 }
 
 /// The model cache directory (in the platform data directory), created if
@@ -111,15 +230,18 @@ pub(crate) fn has_hardware_provider() -> bool {
 }
 
 impl FastEmbedModel {
-    pub fn new() -> Result<Self> {
+    pub fn new(info: &'static ModelInfo) -> Result<Self> {
         set_hf_cache_dir()?;
 
         let providers = execution_providers();
 
-        let mut opts =
-            fastembed::TextInitOptions::new(fastembed::EmbeddingModel::NomicEmbedTextV15)
-                .with_show_download_progress(true)
-                .with_max_length(512);
+        if let Some(dir) = super::offline::locate(info.name)? {
+            return Self::from_dir(info, &dir, providers);
+        }
+
+        let mut opts = fastembed::TextInitOptions::new(info.model.clone())
+            .with_show_download_progress(true)
+            .with_max_length(super::MODEL_MAX_TOKENS);
 
         if !providers.is_empty() {
             opts = opts.with_execution_providers(providers);
@@ -129,14 +251,47 @@ impl FastEmbedModel {
 
         Ok(Self {
             model: RefCell::new(model),
-            dim: 768,
+            info,
+        })
+    }
+
+    /// Load side-loaded files (see [`super::offline`]) with the pooling and
+    /// output settings fastembed would use for the downloaded model.
+    fn from_dir(
+        info: &'static ModelInfo,
+        dir: &std::path::Path,
+        providers: Vec<ort::execution_providers::ExecutionProviderDispatch>,
+    ) -> Result<Self> {
+        let onnx_path = dir.join(super::offline::ONNX_FILE);
+        let onnx = std::fs::read(&onnx_path)
+            .with_context(|| format!("Failed to read {}", onnx_path.display()))?;
+        let mut user_model =
+            fastembed::UserDefinedEmbeddingModel::new(onnx, super::offline::tokenizer_files(dir)?)
+                .with_quantization(fastembed::TextEmbedding::get_quantization_mode(&info.model));
+        if let Some(pooling) = fastembed::TextEmbedding::get_default_pooling_method(&info.model) {
+            user_model = user_model.with_pooling(pooling);
+        }
+        user_model.output_key = fastembed::TextEmbedding::get_model_info(&info.model)?
+            .output_key
+            .clone();
+
+        let opts = fastembed::InitOptionsUserDefined::new()
+            .with_execution_providers(providers)
+            .with_max_length(super::MODEL_MAX_TOKENS);
+        let model = fastembed::TextEmbedding::try_new_from_user_defined(user_model, opts)?;
+        Ok(Self {
+            model: RefCell::new(model),
+            info,
         })
     }
 }
 
 impl Embedder for FastEmbedModel {
     fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
-        let docs: Vec<String> = texts.iter().map(|t| t.to_string()).collect();
+        let docs: Vec<String> = texts
+            .iter()
+            .map(|t| format!("{}{}", self.info.document_prefix, t))
+            .collect();
         let embeddings = self.model.borrow_mut().embed(docs, None)?;
         Ok(embeddings)
     }
@@ -145,7 +300,7 @@ impl Embedder for FastEmbedModel {
         let results = self
             .model
             .borrow_mut()
-            .embed(vec![text.to_string()], None)?;
+            .embed(vec![format!("{}{}", self.info.query_prefix, text)], None)?;
         results
             .into_iter()
             .next()
@@ -153,15 +308,15 @@ impl Embedder for FastEmbedModel {
     }
 
     fn dimension(&self) -> usize {
-        self.dim
+        self.info.dim
     }
 
     fn model_name(&self) -> &str {
-        MODEL_NAME
+        self.info.name
     }
 
     fn max_length(&self) -> usize {
-        512
+        super::MODEL_MAX_TOKENS
     }
 }
 
@@ -297,4 +452,32 @@ mod tests {
         let v2 = embedder.embed_query("goodbye").unwrap();
         assert_ne!(v1, v2);
     }
+
+    #[test]
+    fn default_model_is_listed_first() {
+        assert_eq!(default_model().name, MODEL_NAME);
+        assert_eq!(find_model(MODEL_NAME), Some(default_model()));
+        assert!(find_model("no-such-model").is_none());
+    }
+
+    #[test]
+    fn model_names_are_unique_and_include_multilingual_models() {
+        let names = model_names();
+        let unique: std::collections::HashSet<_> = names.iter().collect();
+        assert_eq!(unique.len(), names.len());
+        assert!(MODELS.iter().any(|m| m.multilingual));
+    }
+
+    #[test]
+    fn stored_or_default_reads_the_recorded_model() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::schema::create_tables(&conn).unwrap();
+        assert_eq!(stored_or_default(&conn).name, MODEL_NAME);
+
+        crate::embed::store::set_model_metadata(&conn, "multilingual-e5-base", 768, 512).unwrap();
+        assert_eq!(stored_or_default(&conn).name, "multilingual-e5-base");
+
+        crate::embed::store::set_model_metadata(&conn, "retired-model", 768, 512).unwrap();
+        assert_eq!(stored_or_default(&conn).name, MODEL_NAME);
+    }
 }
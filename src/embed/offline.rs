@@ -0,0 +1,518 @@
+//! Side-loaded model files for offline installs.
+//!
+//! Models normally download from Hugging Face into the fastembed cache on
+//! first use. Air-gapped machines instead load them from a model directory
+//! holding one subdirectory per model (`<dir>/<name>/`): the ONNX graph,
+//! the four tokenizer files, and a `manifest.json` recording each file's
+//! size and SHA-256. `grans models export` writes that layout on a
+//! connected machine; `grans models import` verifies every checksum and
+//! copies it into place.
+//!
+//! The directory is `$GRANS_MODEL_DIR` when set, and models then never
+//! touch the network: a missing model or file is an error naming what to
+//! side-load. Otherwise it is `models/` in the data directory, used for
+//! the models imported there and bypassed (falling back to a download)
+//! for the rest.
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::model::{self, ModelInfo};
+use super::rerank::RerankModel;
+
+/// Environment variable naming the side-loaded model directory.
+pub const MODEL_DIR_ENV: &str = "GRANS_MODEL_DIR";
+
+/// The ONNX graph, whatever its path in the upstream repository.
+pub const ONNX_FILE: &str = "model.onnx";
+
+/// Tokenizer files, as fastembed reads them from the repository root.
+pub const TOKENIZER_FILES: [&str; 4] = [
+    "tokenizer.json",
+    "config.json",
+    "special_tokens_map.json",
+    "tokenizer_config.json",
+];
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Where a model's files come from upstream.
+#[derive(Debug, Clone)]
+pub struct ModelSource {
+    /// Directory name under the model directory.
+    pub name: &'static str,
+    /// Hugging Face repository.
+    pub repo: String,
+    /// Path of the ONNX graph in the repository.
+    pub onnx_path: String,
+    /// External weight files the graph references. Side-loading reads the
+    /// graph on its own, so models with any are not supported offline.
+    pub additional_files: Vec<String>,
+}
+
+impl ModelSource {
+    pub fn embedding(info: &'static ModelInfo) -> Result<Self> {
+        let upstream = fastembed::TextEmbedding::get_model_info(&info.model)?;
+        Ok(Self {
+            name: info.name,
+            repo: upstream.model_code.clone(),
+            onnx_path: upstream.model_file.clone(),
+            additional_files: upstream.additional_files.clone(),
+        })
+    }
+
+    pub fn reranker(choice: RerankModel) -> Self {
+        let upstream = fastembed::TextRerank::get_model_info(&choice.fastembed_model());
+        Self {
+            name: choice.name(),
+            repo: upstream.model_code,
+            onnx_path: upstream.model_file,
+            additional_files: upstream.additional_files,
+        }
+    }
+
+    /// Look up an embedding model or reranker by name.
+    pub fn find(name: &str) -> Result<Self> {
+        if let Some(info) = model::find_model(name) {
+            return Self::embedding(info);
+        }
+        if let Some(choice) = RerankModel::ALL.into_iter().find(|m| m.name() == name) {
+            return Ok(Self::reranker(choice));
+        }
+        bail!("Unknown model '{}'", name)
+    }
+
+    fn ensure_single_file(&self) -> Result<()> {
+        if !self.additional_files.is_empty() {
+            bail!(
+                "{} keeps its weights in separate data files ({}), which side-loading \
+                 does not support; pick another model for offline use",
+                self.name,
+                self.additional_files.join(", ")
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Names of every model that can be exported: the embedding models, then
+/// the rerankers.
+pub fn model_names() -> Vec<&'static str> {
+    model::model_names()
+        .into_iter()
+        .chain(RerankModel::ALL.into_iter().map(|m| m.name()))
+        .collect()
+}
+
+/// One file in a side-loaded model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestFile {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// `manifest.json`: what a side-loaded model directory must contain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub model: String,
+    pub files: Vec<ManifestFile>,
+}
+
+/// The side-loaded model directory, and whether it came from
+/// [`MODEL_DIR_ENV`] (which makes it the only source).
+pub fn model_root() -> Result<(PathBuf, bool)> {
+    match std::env::var_os(MODEL_DIR_ENV) {
+        Some(dir) if !dir.is_empty() => Ok((PathBuf::from(dir), true)),
+        _ => Ok((crate::platform::data_dir()?.join("models"), false)),
+    }
+}
+
+/// Find `name`'s side-loaded files. `None` means download as usual; under
+/// [`MODEL_DIR_ENV`] a missing model is an error instead.
+pub fn locate(name: &str) -> Result<Option<PathBuf>> {
+    let (root, exclusive) = model_root()?;
+    locate_in(&root, exclusive, name)
+}
+
+fn locate_in(root: &Path, exclusive: bool, name: &str) -> Result<Option<PathBuf>> {
+    let dir = root.join(name);
+    if !dir.is_dir() {
+        if exclusive {
+            bail!(
+                "Model {} is not in {} ({}). Export it on a connected machine with \
+                 `grans models export <dir> --model {}`, then run `grans models import <dir>` here.",
+                name,
+                MODEL_DIR_ENV,
+                root.display(),
+                name
+            );
+        }
+        return Ok(None);
+    }
+    check_present(&dir)?;
+    Ok(Some(dir))
+}
+
+fn read_manifest(dir: &Path) -> Result<Manifest> {
+    let path = dir.join(MANIFEST_FILE);
+    let content = fs::read_to_string(&path).with_context(|| {
+        format!(
+            "{} is missing; re-export the model with `grans models export`",
+            path.display()
+        )
+    })?;
+    let manifest: Manifest =
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))?;
+    check_names(&manifest, &path)?;
+    Ok(manifest)
+}
+
+/// Refuse a manifest naming anything but a known model and its expected
+/// files: import turns those names into paths under the model directory.
+fn check_names(manifest: &Manifest, path: &Path) -> Result<()> {
+    let names = model_names();
+    if !names.contains(&manifest.model.as_str()) {
+        bail!(
+            "{} names unknown model '{}' (expected one of: {})",
+            path.display(),
+            manifest.model,
+            names.join(", ")
+        );
+    }
+    for entry in &manifest.files {
+        if entry.path != ONNX_FILE && !TOKENIZER_FILES.contains(&entry.path.as_str()) {
+            bail!(
+                "{} lists unexpected file '{}' (a model holds only {} and {})",
+                path.display(),
+                entry.path,
+                ONNX_FILE,
+                TOKENIZER_FILES.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Cheap load-time check: every required file is present at its recorded
+/// size. Checksums are verified on import.
+fn check_present(dir: &Path) -> Result<Manifest> {
+    let manifest = read_manifest(dir)?;
+    for required in std::iter::once(ONNX_FILE).chain(TOKENIZER_FILES) {
+        let path = dir.join(required);
+        let Some(entry) = manifest.files.iter().find(|f| f.path == required) else {
+            bail!(
+                "{} does not list {}; re-export the model",
+                dir.join(MANIFEST_FILE).display(),
+                required
+            );
+        };
+        let size = fs::metadata(&path)
+            .with_context(|| {
+                format!(
+                    "Required model file {} is missing; re-import the model",
+                    path.display()
+                )
+            })?
+            .len();
+        if size != entry.size {
+            bail!(
+                "{} is {} bytes but the manifest expects {}; re-import the model",
+                path.display(),
+                size,
+                entry.size
+            );
+        }
+    }
+    Ok(manifest)
+}
+
+/// Full check: presence, sizes, and every checksum.
+pub fn verify(dir: &Path) -> Result<Manifest> {
+    let manifest = check_present(dir)?;
+    for entry in &manifest.files {
+        let path = dir.join(&entry.path);
+        let actual = sha256_file(&path)?;
+        if actual != entry.sha256 {
+            bail!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                path.display(),
+                entry.sha256,
+                actual
+            );
+        }
+    }
+    Ok(manifest)
+}
+
+fn sha256_file(path: &Path) -> Result<String> {
+    let mut file =
+        fs::File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1 << 20];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Read the tokenizer files from a side-loaded model directory.
+pub fn tokenizer_files(dir: &Path) -> Result<fastembed::TokenizerFiles> {
+    let read = |name: &str| {
+        let path = dir.join(name);
+        fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))
+    };
+    Ok(fastembed::TokenizerFiles {
+        tokenizer_file: read(TOKENIZER_FILES[0])?,
+        config_file: read(TOKENIZER_FILES[1])?,
+        special_tokens_map_file: read(TOKENIZER_FILES[2])?,
+        tokenizer_config_file: read(TOKENIZER_FILES[3])?,
+    })
+}
+
+/// Write `files` (destination name, source path) into `<root>/<name>/`
+/// with a manifest. The directory is staged and renamed into place, so an
+/// interrupted copy never leaves a model that looks complete.
+fn install(root: &Path, name: &str, files: &[(&str, PathBuf)]) -> Result<Manifest> {
+    fs::create_dir_all(root).with_context(|| format!("Failed to create {}", root.display()))?;
+    let dest = root.join(name);
+    let staged = root.join(format!(".{}.partial", name));
+    if staged.exists() {
+        fs::remove_dir_all(&staged)?;
+    }
+    fs::create_dir_all(&staged)?;
+
+    let mut manifest = Manifest {
+        model: name.to_string(),
+        files: Vec::with_capacity(files.len()),
+    };
+    for (file_name, source) in files {
+        let target = staged.join(file_name);
+        fs::copy(source, &target).with_context(|| {
+            format!(
+                "Failed to copy {} to {}",
+                source.display(),
+                target.display()
+            )
+        })?;
+        manifest.files.push(ManifestFile {
+            path: file_name.to_string(),
+            size: fs::metadata(&target)?.len(),
+            sha256: sha256_file(&target)?,
+        });
+    }
+    fs::write(
+        staged.join(MANIFEST_FILE),
+        serde_json::to_string_pretty(&manifest)?,
+    )?;
+
+    if dest.exists() {
+        fs::remove_dir_all(&dest)
+            .with_context(|| format!("Failed to replace {}", dest.display()))?;
+    }
+    fs::rename(&staged, &dest)?;
+    Ok(manifest)
+}
+
+/// Export a model into `<root>/<name>/`, downloading it into the fastembed
+/// cache first if needed.
+pub fn export(source: &ModelSource, root: &Path) -> Result<Manifest> {
+    source.ensure_single_file()?;
+    model::set_hf_cache_dir()?;
+    let endpoint =
+        std::env::var("HF_ENDPOINT").unwrap_or_else(|_| "https://huggingface.co".to_string());
+    let api = hf_hub::api::sync::ApiBuilder::new()
+        .with_cache_dir(model::hf_cache_dir()?)
+        .with_endpoint(endpoint)
+        .with_progress(true)
+        .build()?;
+    let repo = api.model(source.repo.clone());
+    let fetch = |file: &str| {
+        repo.get(file)
+            .with_context(|| format!("Failed to fetch {} from {}", file, source.repo))
+    };
+
+    let mut files = vec![(ONNX_FILE, fetch(&source.onnx_path)?)];
+    for name in TOKENIZER_FILES {
+        files.push((name, fetch(name)?));
+    }
+    install(root, source.name, &files)
+}
+
+/// Verify and copy every model under `from` (or `from` itself, when it is
+/// a single model directory) into `root`. Nothing is copied if any model
+/// fails verification.
+pub fn import(from: &Path, root: &Path) -> Result<Vec<Manifest>> {
+    let dirs: Vec<PathBuf> = if from.join(MANIFEST_FILE).is_file() {
+        vec![from.to_path_buf()]
+    } else {
+        let mut dirs: Vec<PathBuf> = fs::read_dir(from)
+            .with_context(|| format!("Failed to read {}", from.display()))?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.join(MANIFEST_FILE).is_file())
+            .collect();
+        dirs.sort();
+        dirs
+    };
+    if dirs.is_empty() {
+        bail!(
+            "No exported models in {} (expected <model>/{} entries from `grans models export`)",
+            from.display(),
+            MANIFEST_FILE
+        );
+    }
+
+    let verified: Vec<(PathBuf, Manifest)> = dirs
+        .into_iter()
+        .map(|dir| verify(&dir).map(|m| (dir, m)))
+        .collect::<Result<_>>()?;
+
+    let mut imported = Vec::with_capacity(verified.len());
+    for (dir, manifest) in verified {
+        if dir == root.join(&manifest.model) {
+            // Already in place; verification was the whole job.
+            imported.push(manifest);
+            continue;
+        }
+        let files: Vec<(&str, PathBuf)> = manifest
+            .files
+            .iter()
+            .map(|f| (f.path.as_str(), dir.join(&f.path)))
+            .collect();
+        let installed = install(root, &manifest.model, &files)?;
+        imported.push(installed);
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fake exported model: small stand-in files under `<root>/<name>/`.
+    fn fake_export(root: &Path, name: &str) -> PathBuf {
+        let src = root.join("src");
+        fs::create_dir_all(&src).unwrap();
+        let mut files = Vec::new();
+        for file in std::iter::once(ONNX_FILE).chain(TOKENIZER_FILES) {
+            let path = src.join(file);
+            fs::write(&path, format!("contents of {}", file)).unwrap();
+            files.push((file, path));
+        }
+        install(&root.join("export"), name, &files).unwrap();
+        root.join("export")
+    }
+
+    #[test]
+    fn import_verifies_and_copies_every_model() {
+        let tmp = tempfile::tempdir().unwrap();
+        let exported = fake_export(tmp.path(), "bge-small-en-v1.5");
+        let target = tmp.path().join("models");
+
+        let imported = import(&exported, &target).unwrap();
+
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].model, "bge-small-en-v1.5");
+        assert_eq!(imported[0].files.len(), 5);
+        let dir = locate_in(&target, true, "bge-small-en-v1.5")
+            .unwrap()
+            .unwrap();
+        assert!(verify(&dir).is_ok());
+    }
+
+    #[test]
+    fn import_rejects_a_corrupted_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let exported = fake_export(tmp.path(), "bge-small-en-v1.5");
+        // Same size, different bytes: only the checksum can tell.
+        let onnx = exported.join("bge-small-en-v1.5").join(ONNX_FILE);
+        let original = fs::read(&onnx).unwrap();
+        fs::write(&onnx, vec![b'x'; original.len()]).unwrap();
+        let target = tmp.path().join("models");
+
+        let err = import(&exported, &target).unwrap_err();
+
+        assert!(err.to_string().contains("Checksum mismatch"));
+        assert!(!target.join("bge-small-en-v1.5").exists());
+    }
+
+    #[test]
+    fn import_rejects_manifests_naming_paths_outside_the_model() {
+        let tmp = tempfile::tempdir().unwrap();
+        let target = tmp.path().join("models");
+        let bystander = tmp.path().join("bystander");
+        fs::create_dir_all(&bystander).unwrap();
+        fs::write(bystander.join("keep"), "keep").unwrap();
+
+        let hostile = |model: &str, file: &str| {
+            let exported = fake_export(tmp.path(), "bge-small-en-v1.5");
+            let dir = exported.join("bge-small-en-v1.5");
+            let mut manifest = read_manifest(&dir).unwrap();
+            manifest.model = model.to_string();
+            manifest.files[1].path = file.to_string();
+            fs::write(
+                dir.join(MANIFEST_FILE),
+                serde_json::to_string(&manifest).unwrap(),
+            )
+            .unwrap();
+            import(&dir, &target).unwrap_err().to_string()
+        };
+
+        let absolute = bystander.to_string_lossy().into_owned();
+        for model in ["../bystander", absolute.as_str(), ""] {
+            assert!(hostile(model, "tokenizer.json").contains("unknown model"));
+        }
+        for file in ["../../bystander/keep", "notes.txt"] {
+            assert!(hostile("bge-small-en-v1.5", file).contains("unexpected file"));
+        }
+        assert_eq!(fs::read_to_string(bystander.join("keep")).unwrap(), "keep");
+        assert!(!target.exists());
+    }
+
+    #[test]
+    fn locate_names_a_missing_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let exported = fake_export(tmp.path(), "bge-small-en-v1.5");
+        fs::remove_file(exported.join("bge-small-en-v1.5").join("tokenizer.json")).unwrap();
+
+        let err = locate_in(&exported, true, "bge-small-en-v1.5").unwrap_err();
+
+        assert!(err.to_string().contains("tokenizer.json"));
+    }
+
+    #[test]
+    fn locate_requires_the_model_only_under_the_override() {
+        let tmp = tempfile::tempdir().unwrap();
+
+        assert!(
+            locate_in(tmp.path(), false, "bge-small-en-v1.5")
+                .unwrap()
+                .is_none()
+        );
+        let err = locate_in(tmp.path(), true, "bge-small-en-v1.5").unwrap_err();
+        assert!(err.to_string().contains(MODEL_DIR_ENV));
+    }
+
+    #[test]
+    fn sources_resolve_embedding_models_and_rerankers() {
+        let nomic = ModelSource::find(model::MODEL_NAME).unwrap();
+        assert_eq!(nomic.repo, "nomic-ai/nomic-embed-text-v1.5");
+        assert!(nomic.ensure_single_file().is_ok());
+
+        let reranker = ModelSource::find(RerankModel::JinaTurbo.name()).unwrap();
+        assert_eq!(reranker.repo, "jinaai/jina-reranker-v1-turbo-en");
+
+        let large = ModelSource::find("multilingual-e5-large").unwrap();
+        assert!(large.ensure_single_file().is_err());
+        assert!(ModelSource::find("word2vec").is_err());
+    }
+}
@@ -0,0 +1,318 @@
+//! Vector storage specs: Matryoshka truncation and quantized encodings.
+//!
+//! nomic-embed-text-v1.5 is Matryoshka-trained, so the leading dimensions
+//! of a vector carry most of its meaning and a prefix (256 of 768, say)
+//! still ranks well on its own. A [`StorageSpec`] records how the
+//! `embeddings.vector` blobs in a database are laid out: how many leading
+//! dimensions are kept and how each is encoded. It is persisted in
+//! `embedding_metadata` beside the model and chunking scheme.
+//!
+//! Blob layouts (all little-endian):
+//! - `f32`: 4 bytes per dimension (the legacy layout).
+//! - `f16`: 2 bytes per dimension, IEEE half precision.
+//! - `int8`: a 4-byte f32 scale, then 1 signed byte per dimension;
+//!   dimension `i` is `byte[i] * scale`.
+//!
+//! Search scores [`QuantizedVector`]s directly: cosine similarity is
+//! scale-invariant, so int8 vectors never need decoding, and f16 values
+//! widen one at a time.
+
+use std::fmt;
+
+use anyhow::{Result, bail};
+use half::f16;
+
+/// Truncation sizes nomic-embed-text-v1.5 was trained to support.
+pub const MATRYOSHKA_DIMS: [usize; 5] = [64, 128, 256, 512, 768];
+
+/// How each stored dimension is encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Precision {
+    #[default]
+    F32,
+    F16,
+    Int8,
+}
+
+impl Precision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Precision::F32 => "f32",
+            Precision::F16 => "f16",
+            Precision::Int8 => "int8",
+        }
+    }
+
+    /// Bits per stored value (int8 also stores one f32 scale per vector).
+    pub fn bits(&self) -> u32 {
+        match self {
+            Precision::F32 => 32,
+            Precision::F16 => 16,
+            Precision::Int8 => 8,
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "f32" => Some(Precision::F32),
+            "f16" => Some(Precision::F16),
+            "int8" => Some(Precision::Int8),
+            _ => None,
+        }
+    }
+}
+
+/// How vectors are laid out in `embeddings.vector`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StorageSpec {
+    /// Leading dimensions kept; `None` keeps the model's full output.
+    pub dim: Option<usize>,
+    pub precision: Precision,
+}
+
+impl StorageSpec {
+    /// Check the spec against a model's output dimension.
+    pub fn validate(&self, model_dim: usize) -> Result<()> {
+        if let Some(dim) = self.dim
+            && (dim == 0 || dim > model_dim)
+        {
+            bail!(
+                "storage dimension {} must be between 1 and the model's {}",
+                dim,
+                model_dim
+            );
+        }
+        Ok(())
+    }
+
+    /// Dimensions stored per vector for a model with this output size.
+    pub fn stored_dim(&self, model_dim: usize) -> usize {
+        self.dim.map_or(model_dim, |d| d.min(model_dim))
+    }
+
+    /// Blob size of one vector for a model with this output size.
+    pub fn bytes_per_vector(&self, model_dim: usize) -> usize {
+        let dim = self.stored_dim(model_dim);
+        match self.precision {
+            Precision::F32 => dim * 4,
+            Precision::F16 => dim * 2,
+            Precision::Int8 => 4 + dim,
+        }
+    }
+
+    /// Truncate and encode a vector into its blob.
+    pub fn encode(&self, vector: &[f32]) -> Vec<u8> {
+        let vector = &vector[..self.stored_dim(vector.len())];
+        match self.precision {
+            Precision::F32 => vector.iter().flat_map(|v| v.to_le_bytes()).collect(),
+            Precision::F16 => vector
+                .iter()
+                .flat_map(|v| f16::from_f32(*v).to_le_bytes())
+                .collect(),
+            Precision::Int8 => {
+                let max = vector.iter().fold(0.0_f32, |m, v| m.max(v.abs()));
+                let scale = if max == 0.0 { 1.0 } else { max / 127.0 };
+                let mut blob = Vec::with_capacity(4 + vector.len());
+                blob.extend_from_slice(&scale.to_le_bytes());
+                blob.extend(
+                    vector
+                        .iter()
+                        .map(|v| (v / scale).round().clamp(-127.0, 127.0) as i8 as u8),
+                );
+                blob
+            }
+        }
+    }
+
+    /// Decode a blob written under this spec.
+    pub fn decode(&self, blob: &[u8]) -> QuantizedVector {
+        match self.precision {
+            Precision::F32 => QuantizedVector::F32(
+                blob.chunks_exact(4)
+                    .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                    .collect(),
+            ),
+            Precision::F16 => QuantizedVector::F16(
+                blob.chunks_exact(2)
+                    .map(|c| f16::from_le_bytes([c[0], c[1]]))
+                    .collect(),
+            ),
+            Precision::Int8 => {
+                let (scale, values) = match blob.split_first_chunk::<4>() {
+                    Some((scale, values)) => (f32::from_le_bytes(*scale), values),
+                    None => (1.0, &[][..]),
+                };
+                QuantizedVector::Int8 {
+                    scale,
+                    values: values.iter().map(|b| *b as i8).collect(),
+                }
+            }
+        }
+    }
+}
+
+/// Shown as e.g. `256d int8`, or `full f32` when nothing is truncated.
+impl fmt::Display for StorageSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.dim {
+            Some(dim) => write!(f, "{}d {}", dim, self.precision.as_str()),
+            None => write!(f, "full {}", self.precision.as_str()),
+        }
+    }
+}
+
+/// A stored vector in its storage encoding.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuantizedVector {
+    F32(Vec<f32>),
+    F16(Vec<f16>),
+    Int8 { scale: f32, values: Vec<i8> },
+}
+
+impl QuantizedVector {
+    pub fn len(&self) -> usize {
+        match self {
+            QuantizedVector::F32(v) => v.len(),
+            QuantizedVector::F16(v) => v.len(),
+            QuantizedVector::Int8 { values, .. } => values.len(),
+        }
+    }
+
+    /// The encoding the vector is held in.
+    pub fn precision(&self) -> Precision {
+        match self {
+            QuantizedVector::F32(_) => Precision::F32,
+            QuantizedVector::F16(_) => Precision::F16,
+            QuantizedVector::Int8 { .. } => Precision::Int8,
+        }
+    }
+
+    /// Cosine similarity against a full-precision query. A query longer
+    /// than the stored vector is truncated to the same Matryoshka prefix.
+    pub fn cosine(&self, query: &[f32]) -> f32 {
+        let query = &query[..self.len().min(query.len())];
+        let (dot, norm) = match self {
+            QuantizedVector::F32(v) => dot_and_norm(query, v.iter().copied()),
+            QuantizedVector::F16(v) => dot_and_norm(query, v.iter().map(|x| x.to_f32())),
+            // The scale cancels out of the cosine.
+            QuantizedVector::Int8 { values, .. } => {
+                dot_and_norm(query, values.iter().map(|x| *x as f32))
+            }
+        };
+        let query_norm = query.iter().map(|q| q * q).sum::<f32>().sqrt();
+        let denom = query_norm * norm;
+        if denom == 0.0 { 0.0 } else { dot / denom }
+    }
+
+    /// Widen back to f32 (int8 values rescaled).
+    pub fn to_f32(&self) -> Vec<f32> {
+        match self {
+            QuantizedVector::F32(v) => v.clone(),
+            QuantizedVector::F16(v) => v.iter().map(|x| x.to_f32()).collect(),
+            QuantizedVector::Int8 { scale, values } => {
+                values.iter().map(|x| *x as f32 * scale).collect()
+            }
+        }
+    }
+}
+
+impl From<Vec<f32>> for QuantizedVector {
+    fn from(v: Vec<f32>) -> Self {
+        QuantizedVector::F32(v)
+    }
+}
+
+fn dot_and_norm(query: &[f32], stored: impl Iterator<Item = f32>) -> (f32, f32) {
+    let mut dot = 0.0_f32;
+    let mut norm = 0.0_f32;
+    for (q, s) in query.iter().zip(stored) {
+        dot += q * s;
+        norm += s * s;
+    }
+    (dot, norm.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(dim: usize) -> Vec<f32> {
+        (0..dim)
+            .map(|i| ((i * 37 % 11) as f32 - 5.0) / 7.0)
+            .collect()
+    }
+
+    fn spec(dim: Option<usize>, precision: Precision) -> StorageSpec {
+        StorageSpec { dim, precision }
+    }
+
+    #[test]
+    fn f32_round_trips_exactly() {
+        let v = sample(8);
+        let s = spec(None, Precision::F32);
+
+        assert_eq!(s.decode(&s.encode(&v)), QuantizedVector::F32(v.clone()));
+        assert_eq!(s.encode(&v).len(), s.bytes_per_vector(8));
+    }
+
+    #[test]
+    fn truncation_keeps_the_leading_dimensions() {
+        let v = sample(16);
+        let s = spec(Some(4), Precision::F32);
+
+        assert_eq!(s.decode(&s.encode(&v)).to_f32(), v[..4].to_vec());
+        assert_eq!(s.bytes_per_vector(16), 16);
+    }
+
+    #[test]
+    fn quantized_scores_stay_close_to_exact() {
+        let stored = sample(64);
+        let query: Vec<f32> = sample(64).iter().rev().copied().collect();
+        let exact = QuantizedVector::F32(stored.clone()).cosine(&query);
+
+        for precision in [Precision::F16, Precision::Int8] {
+            let s = spec(None, precision);
+            let score = s.decode(&s.encode(&stored)).cosine(&query);
+            assert!(
+                (score - exact).abs() < 0.01,
+                "{:?}: {} vs {}",
+                precision,
+                score,
+                exact
+            );
+        }
+    }
+
+    #[test]
+    fn query_is_truncated_to_the_stored_prefix() {
+        let v = sample(32);
+        let s = spec(Some(8), Precision::Int8);
+        let stored = s.decode(&s.encode(&v));
+
+        let score = stored.cosine(&v);
+
+        assert!((score - 1.0).abs() < 0.01);
+        assert_eq!(s.bytes_per_vector(32), 12);
+    }
+
+    #[test]
+    fn zero_vectors_score_zero() {
+        let s = spec(None, Precision::Int8);
+        let stored = s.decode(&s.encode(&[0.0; 4]));
+
+        assert_eq!(stored.cosine(&[1.0, 0.0, 0.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn validate_bounds_the_dimension() {
+        assert!(spec(Some(256), Precision::Int8).validate(768).is_ok());
+        assert!(spec(Some(1024), Precision::F32).validate(768).is_err());
+        assert!(spec(Some(0), Precision::F32).validate(768).is_err());
+    }
+
+    #[test]
+    fn display_names_the_layout() {
+        assert_eq!(spec(Some(256), Precision::Int8).to_string(), "256d int8");
+        assert_eq!(StorageSpec::default().to_string(), "full f32");
+    }
+}
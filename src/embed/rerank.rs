@@ -32,7 +32,17 @@ pub enum RerankModel {
 pub const DEFAULT_RERANK_MODEL: RerankModel = RerankModel::JinaTurbo;
 
 impl RerankModel {
-    fn fastembed_model(&self) -> fastembed::RerankerModel {
+    pub const ALL: [RerankModel; 2] = [RerankModel::JinaTurbo, RerankModel::BgeBase];
+
+    /// Name for `grans models` and the side-loaded model directory.
+    pub fn name(&self) -> &'static str {
+        match self {
+            RerankModel::JinaTurbo => "jina-reranker-v1-turbo-en",
+            RerankModel::BgeBase => "bge-reranker-base",
+        }
+    }
+
+    pub(crate) fn fastembed_model(&self) -> fastembed::RerankerModel {
         match self {
             RerankModel::JinaTurbo => fastembed::RerankerModel::JINARerankerV1TurboEn,
             RerankModel::BgeBase => fastembed::RerankerModel::BGERerankerBase,
@@ -77,7 +87,17 @@ impl FastEmbedReranker {
     }
 
     fn load(choice: RerankModel, show_download_progress: bool) -> Result<Self> {
-        let model = fastembed::TextRerank::try_new(init_options(choice, show_download_progress)?)?;
+        let opts = init_options(choice, show_download_progress)?;
+        let model = match super::offline::locate(choice.name())? {
+            Some(dir) => fastembed::TextRerank::try_new_from_user_defined(
+                fastembed::UserDefinedRerankingModel::new(
+                    fastembed::OnnxSource::File(dir.join(super::offline::ONNX_FILE)),
+                    super::offline::tokenizer_files(&dir)?,
+                ),
+                opts.into(),
+            )?,
+            None => fastembed::TextRerank::try_new(opts)?,
+        };
         Ok(Self {
             model: RefCell::new(model),
         })
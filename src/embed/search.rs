@@ -19,24 +19,6 @@ pub struct SemanticSearchResult {
     pub section_heading: Option<String>,
 }
 
-/// Compute cosine similarity between two vectors.
-pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
-    debug_assert_eq!(a.len(), b.len());
-
-    let mut dot = 0.0_f32;
-    let mut norm_a = 0.0_f32;
-    let mut norm_b = 0.0_f32;
-
-    for i in 0..a.len() {
-        dot += a[i] * b[i];
-        norm_a += a[i] * a[i];
-        norm_b += b[i] * b[i];
-    }
-
-    let denom = norm_a.sqrt() * norm_b.sqrt();
-    if denom == 0.0 { 0.0 } else { dot / denom }
-}
-
 /// Parse window indices from metadata JSON.
 fn parse_window_indices(metadata_json: &Option<String>) -> (Option<usize>, Option<usize>) {
     if let Some(json_str) = metadata_json {
@@ -78,6 +60,28 @@ fn extract_match_context(source_type: &str, section_heading: Option<&str>) -> Op
     }
 }
 
+/// One vector standing for a whole document: the mean of its chunk
+/// vectors, each scaled to unit length first so long and short chunks
+/// weigh the same. None when the document has no vectors.
+pub fn document_vector(chunks: &[StoredVector]) -> Option<Vec<f32>> {
+    let dim = chunks.iter().map(|c| c.vector.len()).min()?;
+    if dim == 0 {
+        return None;
+    }
+    let mut sum = vec![0.0_f32; dim];
+    for chunk in chunks {
+        let v = chunk.vector.to_f32();
+        let norm = v[..dim].iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm == 0.0 {
+            continue;
+        }
+        for (s, x) in sum.iter_mut().zip(&v[..dim]) {
+            *s += x / norm;
+        }
+    }
+    Some(sum)
+}
+
 /// Rank all stored vectors against a query vector.
 /// Returns results deduplicated by document_id (highest score per doc), sorted by score descending.
 /// `source_type_filter`: if `Some`, only score vectors whose source_type is in the list.
@@ -88,17 +92,23 @@ pub fn rank_results(
     min_score: f32,
     source_type_filter: Option<&[&str]>,
 ) -> Vec<SemanticSearchResult> {
-    let mut doc_best: HashMap<&str, SemanticSearchResult> = HashMap::new();
+    let scored = stored
+        .iter()
+        .filter(|sv| source_type_filter.is_none_or(|f| f.contains(&sv.source_type.as_str())))
+        .map(|sv| (sv, sv.vector.cosine(query_vec)));
+    best_per_document(scored, min_score)
+}
 
-    for sv in stored {
-        // Apply source type filter
-        if let Some(filter) = source_type_filter {
-            if !filter.contains(&sv.source_type.as_str()) {
-                continue;
-            }
-        }
+/// Collapse scored chunks to the best-scoring chunk per document, dropping
+/// scores under `min_score`, sorted by score descending. Shared by the
+/// exact scan and the ANN path, which scores chunks without their vectors.
+pub fn best_per_document<'a>(
+    scored: impl IntoIterator<Item = (&'a StoredVector, f32)>,
+    min_score: f32,
+) -> Vec<SemanticSearchResult> {
+    let mut doc_best: HashMap<&str, SemanticSearchResult> = HashMap::new();
 
-        let score = cosine_similarity(query_vec, &sv.vector);
+    for (sv, score) in scored {
         if score < min_score {
             continue;
         }
@@ -144,11 +154,36 @@ pub fn rank_results(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::embed::quant::QuantizedVector;
+
+    fn stored(document_id: &str, vector: Vec<f32>) -> StoredVector {
+        StoredVector {
+            chunk_id: 0,
+            document_id: document_id.to_string(),
+            source_type: "transcript_window".to_string(),
+            text: String::new(),
+            vector: QuantizedVector::from(vector),
+            metadata_json: None,
+        }
+    }
+
+    #[test]
+    fn document_vector_weighs_chunks_equally() {
+        // A long chunk vector does not outvote a short one.
+        let v =
+            document_vector(&[stored("d", vec![10.0, 0.0]), stored("d", vec![0.0, 1.0])]).unwrap();
+        assert!((v[0] - v[1]).abs() < 1e-6);
+    }
+
+    #[test]
+    fn document_vector_of_nothing_is_none() {
+        assert_eq!(document_vector(&[]), None);
+    }
 
     #[test]
     fn test_cosine_similarity_identical() {
         let v = vec![1.0, 2.0, 3.0];
-        let sim = cosine_similarity(&v, &v);
+        let sim = QuantizedVector::from(v.clone()).cosine(&v);
         assert!((sim - 1.0).abs() < 1e-6);
     }
 
@@ -156,7 +191,7 @@ mod tests {
     fn test_cosine_similarity_orthogonal() {
         let a = vec![1.0, 0.0, 0.0];
         let b = vec![0.0, 1.0, 0.0];
-        let sim = cosine_similarity(&a, &b);
+        let sim = QuantizedVector::from(b.clone()).cosine(&a);
         assert!(sim.abs() < 1e-6);
     }
 
@@ -164,7 +199,7 @@ mod tests {
     fn test_cosine_similarity_opposite() {
         let a = vec![1.0, 2.0, 3.0];
         let b = vec![-1.0, -2.0, -3.0];
-        let sim = cosine_similarity(&a, &b);
+        let sim = QuantizedVector::from(b.clone()).cosine(&a);
         assert!((sim + 1.0).abs() < 1e-6);
     }
 
@@ -172,7 +207,7 @@ mod tests {
     fn test_cosine_similarity_zero_vector() {
         let a = vec![1.0, 2.0, 3.0];
         let b = vec![0.0, 0.0, 0.0];
-        let sim = cosine_similarity(&a, &b);
+        let sim = QuantizedVector::from(b.clone()).cosine(&a);
         assert_eq!(sim, 0.0);
     }
 
@@ -184,7 +219,7 @@ mod tests {
                 document_id: "doc1".to_string(),
                 source_type: "transcript_window".to_string(),
                 text: "chunk 1".to_string(),
-                vector: vec![1.0, 0.0, 0.0],
+                vector: vec![1.0, 0.0, 0.0].into(),
                 metadata_json: None,
             },
             StoredVector {
@@ -192,7 +227,7 @@ mod tests {
                 document_id: "doc1".to_string(),
                 source_type: "transcript_window".to_string(),
                 text: "chunk 2".to_string(),
-                vector: vec![0.9, 0.1, 0.0],
+                vector: vec![0.9, 0.1, 0.0].into(),
                 metadata_json: None,
             },
             StoredVector {
@@ -200,7 +235,7 @@ mod tests {
                 document_id: "doc2".to_string(),
                 source_type: "transcript_window".to_string(),
                 text: "chunk 3".to_string(),
-                vector: vec![0.5, 0.5, 0.0],
+                vector: vec![0.5, 0.5, 0.0].into(),
                 metadata_json: None,
             },
         ];
@@ -221,7 +256,7 @@ mod tests {
                 document_id: "doc1".to_string(),
                 source_type: "transcript_window".to_string(),
                 text: "relevant".to_string(),
-                vector: vec![1.0, 0.0, 0.0],
+                vector: vec![1.0, 0.0, 0.0].into(),
                 metadata_json: None,
             },
             StoredVector {
@@ -229,7 +264,7 @@ mod tests {
                 document_id: "doc2".to_string(),
                 source_type: "transcript_window".to_string(),
                 text: "irrelevant".to_string(),
-                vector: vec![0.0, 1.0, 0.0],
+                vector: vec![0.0, 1.0, 0.0].into(),
                 metadata_json: None,
             },
         ];
@@ -249,7 +284,7 @@ mod tests {
                 document_id: "doc1".to_string(),
                 source_type: "transcript_window".to_string(),
                 text: "low".to_string(),
-                vector: vec![0.1, 0.9, 0.0],
+                vector: vec![0.1, 0.9, 0.0].into(),
                 metadata_json: None,
             },
             StoredVector {
@@ -257,7 +292,7 @@ mod tests {
                 document_id: "doc2".to_string(),
                 source_type: "transcript_window".to_string(),
                 text: "high".to_string(),
-                vector: vec![0.95, 0.05, 0.0],
+                vector: vec![0.95, 0.05, 0.0].into(),
                 metadata_json: None,
             },
             StoredVector {
@@ -265,7 +300,7 @@ mod tests {
                 document_id: "doc3".to_string(),
                 source_type: "transcript_window".to_string(),
                 text: "mid".to_string(),
-                vector: vec![0.7, 0.3, 0.0],
+                vector: vec![0.7, 0.3, 0.0].into(),
                 metadata_json: None,
             },
         ];
@@ -292,7 +327,7 @@ mod tests {
                 document_id: "doc1".to_string(),
                 source_type: "transcript_window".to_string(),
                 text: "chunk with metadata".to_string(),
-                vector: vec![1.0, 0.0, 0.0],
+                vector: vec![1.0, 0.0, 0.0].into(),
                 metadata_json: Some(metadata_with_indices),
             },
             StoredVector {
@@ -300,7 +335,7 @@ mod tests {
                 document_id: "doc2".to_string(),
                 source_type: "transcript_window".to_string(),
                 text: "chunk without metadata".to_string(),
-                vector: vec![0.8, 0.2, 0.0],
+                vector: vec![0.8, 0.2, 0.0].into(),
                 metadata_json: None,
             },
         ];
@@ -349,7 +384,7 @@ mod tests {
                 document_id: "doc1".to_string(),
                 source_type: "transcript_window".to_string(),
                 text: "transcript text".to_string(),
-                vector: vec![1.0, 0.0, 0.0],
+                vector: vec![1.0, 0.0, 0.0].into(),
                 metadata_json: None,
             },
             StoredVector {
@@ -357,7 +392,7 @@ mod tests {
                 document_id: "doc2".to_string(),
                 source_type: "panel_section".to_string(),
                 text: "panel text".to_string(),
-                vector: vec![0.9, 0.1, 0.0],
+                vector: vec![0.9, 0.1, 0.0].into(),
                 metadata_json: Some(serde_json::json!({"section_heading": "Budget"}).to_string()),
             },
             StoredVector {
@@ -365,7 +400,7 @@ mod tests {
                 document_id: "doc3".to_string(),
                 source_type: "notes_paragraph".to_string(),
                 text: "notes text".to_string(),
-                vector: vec![0.8, 0.2, 0.0],
+                vector: vec![0.8, 0.2, 0.0].into(),
                 metadata_json: None,
             },
         ];
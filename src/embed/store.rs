@@ -2,6 +2,7 @@ use anyhow::Result;
 use rusqlite::{Connection, OptionalExtension};
 
 use super::chunk::{Chunk, ChunkSourceType};
+use super::quant::{Precision, QuantizedVector, StorageSpec};
 
 /// A stored chunk with its database ID.
 #[derive(Debug)]
@@ -42,7 +43,8 @@ pub fn get_stored_chunks(conn: &Connection) -> Result<Vec<StoredChunk>> {
     Ok(chunks)
 }
 
-/// Insert a chunk and its embedding vector.
+/// Insert a chunk and its embedding vector, encoded with the database's
+/// stored [`StorageSpec`].
 /// Note: For production use, prefer `insert_chunks_with_embeddings_batch` for better performance.
 #[allow(dead_code)]
 pub fn insert_chunk_with_embedding(
@@ -72,7 +74,7 @@ pub fn insert_chunk_with_embedding(
 
     let chunk_id = conn.last_insert_rowid();
 
-    let blob = vector_to_blob(vector);
+    let blob = get_storage_spec(conn).encode(vector);
     conn.execute(
         "INSERT OR REPLACE INTO embeddings (chunk_id, vector) VALUES (?1, ?2)",
         rusqlite::params![chunk_id, blob],
@@ -81,11 +83,13 @@ pub fn insert_chunk_with_embedding(
     Ok(chunk_id)
 }
 
-/// Insert multiple chunks and their embeddings in a single transaction.
+/// Insert multiple chunks and their embeddings in a single transaction,
+/// encoding each vector with `storage`.
 /// Returns a vector of results, one per chunk (chunk_id on success, error on failure).
 pub fn insert_chunks_with_embeddings_batch(
     conn: &Connection,
     items: &[(&Chunk, &[f32])],
+    storage: &StorageSpec,
 ) -> Vec<Result<i64>> {
     if items.is_empty() {
         return Vec::new();
@@ -146,7 +150,7 @@ pub fn insert_chunks_with_embeddings_batch(
             ])?;
 
             let chunk_id = tx.last_insert_rowid();
-            let blob = vector_to_blob(vector);
+            let blob = storage.encode(vector);
             embedding_stmt.execute(rusqlite::params![chunk_id, blob])?;
 
             Ok(chunk_id)
@@ -230,32 +234,48 @@ pub fn delete_recent_chunks(conn: &Connection, count: usize) -> Result<usize> {
 /// A loaded vector with its chunk metadata.
 #[derive(Debug, Clone)]
 pub struct StoredVector {
-    #[allow(dead_code)]
     pub chunk_id: i64,
     pub document_id: String,
     pub source_type: String,
     pub text: String,
-    pub vector: Vec<f32>,
+    /// The vector in its storage encoding; search scores it as-is.
+    pub vector: QuantizedVector,
     /// JSON metadata from chunk (contains window_start_idx, window_end_idx, etc.)
     pub metadata_json: Option<String>,
 }
 
-/// Load all vectors into memory for search.
+/// Load all vectors into memory for search, in their storage encoding.
 pub fn load_all_vectors(conn: &Connection) -> Result<Vec<StoredVector>> {
-    let mut stmt = conn.prepare(
+    load_vectors(conn, "", [])
+}
+
+/// Load one document's vectors, in their storage encoding.
+pub fn load_document_vectors(conn: &Connection, document_id: &str) -> Result<Vec<StoredVector>> {
+    load_vectors(conn, "WHERE c.document_id = ?1", [document_id])
+}
+
+fn load_vectors(
+    conn: &Connection,
+    where_sql: &str,
+    params: impl rusqlite::Params,
+) -> Result<Vec<StoredVector>> {
+    let storage = get_storage_spec(conn);
+    let mut stmt = conn.prepare(&format!(
         "SELECT c.id, c.document_id, c.source_type, c.text, e.vector, c.metadata_json
          FROM chunks c
-         JOIN embeddings e ON e.chunk_id = c.id",
-    )?;
+         JOIN embeddings e ON e.chunk_id = c.id
+         {}",
+        where_sql
+    ))?;
 
-    let rows = stmt.query_map([], |row| {
+    let rows = stmt.query_map(params, |row| {
         let blob: Vec<u8> = row.get(4)?;
         Ok(StoredVector {
             chunk_id: row.get(0)?,
             document_id: row.get(1)?,
             source_type: row.get(2)?,
             text: row.get(3)?,
-            vector: blob_to_vector(&blob),
+            vector: storage.decode(&blob),
             metadata_json: row.get(5)?,
         })
     })?;
@@ -338,6 +358,11 @@ fn get_metadata_value(conn: &Connection, key: &str) -> Option<String> {
     .ok()
 }
 
+/// Get the model's output dimension recorded at embed time.
+pub fn get_embedding_dim(conn: &Connection) -> Option<usize> {
+    get_metadata_value(conn, "embedding_dim").and_then(|s| s.parse().ok())
+}
+
 /// Chunking parameters read back from `embedding_metadata`. Absent keys
 /// (legacy databases) are `None`.
 #[derive(Debug, Clone, Default)]
@@ -391,6 +416,99 @@ pub fn get_chunking_metadata(conn: &Connection) -> StoredChunkingParams {
     }
 }
 
+/// Persist the layout of the stored vector blobs.
+pub fn set_storage_metadata(conn: &Connection, storage: &StorageSpec) -> Result<()> {
+    match storage.dim {
+        Some(dim) => {
+            conn.execute(
+                "INSERT OR REPLACE INTO embedding_metadata (key, value) VALUES ('storage_dim', ?1)",
+                [dim.to_string()],
+            )?;
+        }
+        None => {
+            conn.execute(
+                "DELETE FROM embedding_metadata WHERE key = 'storage_dim'",
+                [],
+            )?;
+        }
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO embedding_metadata (key, value) VALUES ('storage_precision', ?1)",
+        [storage.precision.as_str()],
+    )?;
+    Ok(())
+}
+
+/// Read back the layout of the stored vector blobs. Absent keys (legacy
+/// databases) mean full-size f32.
+pub fn get_storage_spec(conn: &Connection) -> StorageSpec {
+    StorageSpec {
+        dim: get_metadata_value(conn, "storage_dim").and_then(|s| s.parse().ok()),
+        precision: get_metadata_value(conn, "storage_precision")
+            .and_then(|s| Precision::parse(&s))
+            .unwrap_or_default(),
+    }
+}
+
+/// Re-encode every stored vector from the stored layout to `to`, in one
+/// transaction that also records `to`. Narrowing (fewer dimensions, lower
+/// precision) works on the stored vectors; widening past the dimensions or
+/// precision actually stored cannot recover what was dropped, and is an
+/// error. Returns the vectors rewritten.
+pub fn reencode_vectors(conn: &Connection, to: &StorageSpec) -> Result<usize> {
+    let from = get_storage_spec(conn);
+    if from == *to {
+        return Ok(0);
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    let rows: Vec<(i64, Vec<u8>)> = {
+        let mut stmt = tx.prepare("SELECT chunk_id, vector FROM embeddings")?;
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<std::result::Result<_, _>>()?
+    };
+    if let Some((_, blob)) = rows.first() {
+        let stored = from.decode(blob).len();
+        let model_dim = get_embedding_dim(conn).unwrap_or(stored);
+        let reembed = format!(
+            "Re-embed instead: `grans embed clear --yes && grans embed{}{}`",
+            to.dim.map(|d| format!(" --dim {}", d)).unwrap_or_default(),
+            match to.precision {
+                Precision::F32 => String::new(),
+                precision => format!(" --precision {}", precision.as_str()),
+            }
+        );
+        if to.stored_dim(model_dim) > stored {
+            anyhow::bail!(
+                "Vectors are stored with {} dimensions, so they cannot be widened to {}. {}",
+                stored,
+                to.dim
+                    .map_or("the full size".to_string(), |d| d.to_string()),
+                reembed
+            );
+        }
+        if to.precision.bits() > from.precision.bits() {
+            anyhow::bail!(
+                "Vectors are stored as {}, so they cannot be widened to {}. {}",
+                from.precision.as_str(),
+                to.precision.as_str(),
+                reembed
+            );
+        }
+    }
+
+    {
+        let mut stmt = tx.prepare("UPDATE embeddings SET vector = ?2 WHERE chunk_id = ?1")?;
+        for (chunk_id, blob) in &rows {
+            let vector = from.decode(blob).to_f32();
+            stmt.execute(rusqlite::params![chunk_id, to.encode(&vector)])?;
+        }
+    }
+    set_storage_metadata(&tx, to)?;
+    tx.commit()?;
+    Ok(rows.len())
+}
+
 /// Get stored max_length (None for legacy embeddings that don't have it).
 pub fn get_max_length(conn: &Connection) -> Option<usize> {
     conn.query_row(
@@ -402,6 +520,134 @@ pub fn get_max_length(conn: &Connection) -> Option<usize> {
     .and_then(|s| s.parse().ok())
 }
 
+/// A cheap signature of the stored vectors: the model, the vector count,
+/// the newest chunk id and the certified sync watermark. Any embed run that
+/// adds, replaces or removes vectors changes it, so a long-lived reader can
+/// tell when its in-memory index is out of date without reloading it.
+pub fn vectors_fingerprint(conn: &Connection) -> Result<String> {
+    let (count, newest): (i64, i64) = conn.query_row(
+        "SELECT COUNT(*), COALESCE(MAX(chunk_id), 0) FROM embeddings",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    Ok(format!(
+        "{}:{}:{}:{}",
+        get_model_name(conn).unwrap_or_default(),
+        count,
+        newest,
+        get_embedded_watermark(conn)?.unwrap_or_default()
+    ))
+}
+
+/// The identity of the vector set an ANN graph was built over: the model,
+/// its dimension, the storage layout, the chunking scheme, and the count, newest id and id sum
+/// of the stored embeddings. Chunks are never re-embedded in place (a
+/// changed chunk gets a new row), so any embed run that changes the vectors
+/// changes this. Unlike [`vectors_fingerprint`] it leaves out the sync
+/// watermark, which moves without touching a single vector.
+pub fn ann_signature(conn: &Connection) -> Result<String> {
+    let (count, newest, sum): (i64, i64, i64) = conn.query_row(
+        "SELECT COUNT(*), COALESCE(MAX(chunk_id), 0), COALESCE(SUM(chunk_id), 0) FROM embeddings",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    let chunking = get_chunking_metadata(conn);
+    Ok(format!(
+        "{}:{}:{}:{}/{}/{}/{}:{}:{}:{}",
+        get_model_name(conn).unwrap_or_default(),
+        get_metadata_value(conn, "embedding_dim").unwrap_or_default(),
+        get_storage_spec(conn),
+        chunking.target_tokens.unwrap_or_default(),
+        chunking.overlap_tokens.unwrap_or_default(),
+        chunking
+            .overlap_mode
+            .map(|m| m.as_str())
+            .unwrap_or_default(),
+        chunking.contextual_headers.unwrap_or_default(),
+        count,
+        newest,
+        sum
+    ))
+}
+
+/// Persist a serialized ANN graph built over the vectors `signature`
+/// describes, replacing any previous one.
+pub fn save_ann_index(conn: &Connection, signature: &str, data: &[u8]) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO ann_index (id, signature, built_at, data)
+         VALUES (1, ?1, ?2, ?3)",
+        rusqlite::params![signature, chrono::Utc::now().to_rfc3339(), data],
+    )?;
+    Ok(())
+}
+
+/// The stored ANN graph, if one exists and was built over the vectors
+/// `signature` describes. A graph for any other vector set is stale and
+/// is ignored.
+pub fn load_ann_index(conn: &Connection, signature: &str) -> Result<Option<Vec<u8>>> {
+    Ok(conn
+        .query_row(
+            "SELECT data FROM ann_index WHERE id = 1 AND signature = ?1",
+            [signature],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+/// The signature of the stored ANN graph, if any.
+pub fn stored_ann_signature(conn: &Connection) -> Result<Option<String>> {
+    Ok(conn
+        .query_row("SELECT signature FROM ann_index WHERE id = 1", [], |row| {
+            row.get(0)
+        })
+        .optional()?)
+}
+
+/// Drop the stored ANN graph.
+pub fn clear_ann_index(conn: &Connection) -> Result<()> {
+    conn.execute("DELETE FROM ann_index", [])?;
+    Ok(())
+}
+
+/// Chunk rows for the given ids, without their vectors (left empty). Ids
+/// with no chunk (deleted since the ANN graph was built) are skipped.
+pub fn load_chunks_by_ids(conn: &Connection, ids: &[i64]) -> Result<Vec<StoredVector>> {
+    let mut chunks = Vec::with_capacity(ids.len());
+    // Batched to stay under SQLite's bound-variable limit.
+    for batch in ids.chunks(500) {
+        let placeholders = vec!["?"; batch.len()].join(",");
+        let sql = format!(
+            "SELECT id, document_id, source_type, text, metadata_json
+             FROM chunks WHERE id IN ({})",
+            placeholders
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(rusqlite::params_from_iter(batch), |row| {
+            Ok(StoredVector {
+                chunk_id: row.get(0)?,
+                document_id: row.get(1)?,
+                source_type: row.get(2)?,
+                text: row.get(3)?,
+                vector: QuantizedVector::F32(Vec::new()),
+                metadata_json: row.get(4)?,
+            })
+        })?;
+        for row in rows {
+            chunks.push(row?);
+        }
+    }
+    Ok(chunks)
+}
+
+/// Every chunk's document, as `(chunk_id, document_id)`.
+pub fn chunk_documents(conn: &Connection) -> Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare("SELECT id, document_id FROM chunks")?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<std::result::Result<_, _>>()?;
+    Ok(rows)
+}
+
 /// Get stored model name (to detect model changes).
 pub fn get_model_name(conn: &Connection) -> Option<String> {
     conn.query_row(
@@ -418,27 +664,15 @@ pub fn check_model_consistency(conn: &Connection, current_model: &str) -> Result
         Some(stored) if stored == current_model => Ok(true),
         Some(_) => {
             // Model changed — wipe embeddings
-            conn.execute_batch("DELETE FROM embeddings; DELETE FROM chunks;")?;
+            conn.execute_batch(
+                "DELETE FROM embeddings; DELETE FROM chunks; DELETE FROM ann_index;",
+            )?;
             Ok(false)
         }
         None => Ok(false),
     }
 }
 
-fn vector_to_blob(vector: &[f32]) -> Vec<u8> {
-    let mut blob = Vec::with_capacity(vector.len() * 4);
-    for &val in vector {
-        blob.extend_from_slice(&val.to_le_bytes());
-    }
-    blob
-}
-
-fn blob_to_vector(blob: &[u8]) -> Vec<f32> {
-    blob.chunks_exact(4)
-        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
-        .collect()
-}
-
 /// Get the source type filter for stored chunks.
 #[allow(dead_code)]
 pub fn get_stored_chunks_by_source(
@@ -500,7 +734,30 @@ mod tests {
         let vectors = load_all_vectors(&conn).unwrap();
         assert_eq!(vectors.len(), 1);
         assert_eq!(vectors[0].document_id, "doc1");
-        assert_eq!(vectors[0].vector, vec![1.0, 2.0, 3.0]);
+        assert_eq!(vectors[0].vector, vec![1.0, 2.0, 3.0].into());
+    }
+
+    #[test]
+    fn vectors_fingerprint_changes_when_vectors_change() {
+        let conn = test_db();
+        let empty = vectors_fingerprint(&conn).unwrap();
+        let chunk = Chunk {
+            source_type: ChunkSourceType::TranscriptWindow,
+            source_id: "doc1:w0".to_string(),
+            document_id: "doc1".to_string(),
+            text: "test".to_string(),
+            content_hash: hash_content("test"),
+            metadata: None,
+            header: None,
+        };
+
+        let id = insert_chunk_with_embedding(&conn, &chunk, &[1.0]).unwrap();
+        let one = vectors_fingerprint(&conn).unwrap();
+        assert_ne!(empty, one);
+        assert_eq!(one, vectors_fingerprint(&conn).unwrap());
+
+        delete_chunks(&conn, &[id]).unwrap();
+        assert_ne!(one, vectors_fingerprint(&conn).unwrap());
     }
 
     #[test]
@@ -586,7 +843,7 @@ mod tests {
         assert_eq!(meta["speakers"], serde_json::json!(["Jane Doe"]));
         // The embedding vector is untouched.
         let vectors = load_all_vectors(&conn).unwrap();
-        assert_eq!(vectors[0].vector, vec![1.0, 2.0]);
+        assert_eq!(vectors[0].vector, vec![1.0, 2.0].into());
     }
 
     #[test]
@@ -595,14 +852,6 @@ mod tests {
         update_chunk_metadata_batch(&conn, &[]).unwrap();
     }
 
-    #[test]
-    fn test_vector_blob_roundtrip() {
-        let v = vec![1.0_f32, -2.5, 0.0, 3.14159];
-        let blob = vector_to_blob(&v);
-        let recovered = blob_to_vector(&blob);
-        assert_eq!(v, recovered);
-    }
-
     #[test]
     fn test_model_consistency_check() {
         let conn = test_db();
@@ -801,7 +1050,7 @@ mod tests {
             .map(|(c, v)| (c, v.as_slice()))
             .collect();
 
-        let results = insert_chunks_with_embeddings_batch(&conn, &items);
+        let results = insert_chunks_with_embeddings_batch(&conn, &items, &StorageSpec::default());
 
         assert_eq!(results.len(), 5);
         for result in &results {
@@ -819,7 +1068,7 @@ mod tests {
     #[test]
     fn test_insert_chunks_batch_empty() {
         let conn = test_db();
-        let results = insert_chunks_with_embeddings_batch(&conn, &[]);
+        let results = insert_chunks_with_embeddings_batch(&conn, &[], &StorageSpec::default());
         assert!(results.is_empty());
     }
 
@@ -839,6 +1088,7 @@ mod tests {
         assert!(get_chunking_metadata(&conn).is_empty());
 
         let spec = EmbedSpec {
+            model: crate::embed::model::default_model(),
             chunking: ChunkingConfig {
                 target_tokens: 192,
                 overlap_tokens: 48,
@@ -846,6 +1096,7 @@ mod tests {
                 ..ChunkingConfig::from_max_length(512)
             },
             contextual_headers: true,
+            storage: Default::default(),
         };
         set_chunking_metadata(&conn, &spec).unwrap();
 
@@ -883,4 +1134,95 @@ mod tests {
         set_model_metadata(&conn, "model-a", 768, 512).unwrap();
         assert_eq!(get_max_length(&conn), Some(512));
     }
+
+    #[test]
+    fn reencode_vectors_narrows_and_records_the_layout() {
+        let conn = test_db();
+        set_model_metadata(&conn, "model-a", 8, 256).unwrap();
+        let chunk = Chunk {
+            source_type: ChunkSourceType::TranscriptWindow,
+            source_id: "doc1:w0".to_string(),
+            document_id: "doc1".to_string(),
+            text: "hello".to_string(),
+            content_hash: hash_content("hello"),
+            metadata: None,
+            header: None,
+        };
+        insert_chunk_with_embedding(&conn, &chunk, &[1.0, -2.0, 3.0, 0.5, 0.0, 1.0, 2.0, 3.0])
+            .unwrap();
+        let int8 = StorageSpec {
+            dim: Some(4),
+            precision: Precision::Int8,
+        };
+
+        assert_eq!(reencode_vectors(&conn, &int8).unwrap(), 1);
+        assert_eq!(get_storage_spec(&conn), int8);
+        let vectors = load_all_vectors(&conn).unwrap();
+        assert!(matches!(vectors[0].vector, QuantizedVector::Int8 { .. }));
+        assert_eq!(vectors[0].vector.len(), 4);
+
+        // Same layout again is a no-op.
+        assert_eq!(reencode_vectors(&conn, &int8).unwrap(), 0);
+    }
+
+    #[test]
+    fn reencode_vectors_refuses_to_widen() {
+        let conn = test_db();
+        set_model_metadata(&conn, "model-a", 8, 256).unwrap();
+        set_storage_metadata(
+            &conn,
+            &StorageSpec {
+                dim: Some(4),
+                precision: Precision::F32,
+            },
+        )
+        .unwrap();
+        let chunk = Chunk {
+            source_type: ChunkSourceType::TranscriptWindow,
+            source_id: "doc1:w0".to_string(),
+            document_id: "doc1".to_string(),
+            text: "hello".to_string(),
+            content_hash: hash_content("hello"),
+            metadata: None,
+            header: None,
+        };
+        insert_chunk_with_embedding(&conn, &chunk, &[1.0, 2.0, 3.0, 4.0]).unwrap();
+
+        let err = reencode_vectors(&conn, &StorageSpec::default()).unwrap_err();
+
+        assert!(err.to_string().contains("cannot be widened"));
+        assert_eq!(get_storage_spec(&conn).dim, Some(4));
+    }
+
+    #[test]
+    fn reencode_vectors_refuses_to_raise_precision() {
+        let conn = test_db();
+        set_model_metadata(&conn, "model-a", 4, 256).unwrap();
+        let spec = |precision| StorageSpec {
+            dim: None,
+            precision,
+        };
+        let chunk = Chunk {
+            source_type: ChunkSourceType::TranscriptWindow,
+            source_id: "doc1:w0".to_string(),
+            document_id: "doc1".to_string(),
+            text: "hello".to_string(),
+            content_hash: hash_content("hello"),
+            metadata: None,
+            header: None,
+        };
+        insert_chunk_with_embedding(&conn, &chunk, &[1.0, 2.0, 3.0, 4.0]).unwrap();
+        assert_eq!(reencode_vectors(&conn, &spec(Precision::F16)).unwrap(), 1);
+
+        let err = reencode_vectors(&conn, &spec(Precision::F32)).unwrap_err();
+        assert!(err.to_string().contains("stored as f16"));
+        assert_eq!(get_storage_spec(&conn).precision, Precision::F16);
+
+        assert_eq!(reencode_vectors(&conn, &spec(Precision::Int8)).unwrap(), 1);
+        for precision in [Precision::F16, Precision::F32] {
+            let err = reencode_vectors(&conn, &spec(precision)).unwrap_err();
+            assert!(err.to_string().contains("stored as int8"));
+        }
+        assert_eq!(get_storage_spec(&conn).precision, Precision::Int8);
+    }
 }
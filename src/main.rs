@@ -1,6 +1,7 @@
 mod api;
 mod cli;
 mod commands;
+mod config;
 mod db;
 mod embed;
 mod models;
@@ -8,6 +9,7 @@ mod output;
 mod pkce;
 mod platform;
 mod query;
+mod serve;
 mod sync;
 mod tiptap;
 mod update;
@@ -66,6 +68,13 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Remote commands replicate the database file, so like Dropbox they run
+    // without opening it.
+    if let Commands::Remote { action } = &cli.command {
+        commands::sync::run_remote(action, ctx.output_mode, &ctx.tz)?;
+        return Ok(());
+    }
+
     // Auth commands (login, status, logout). Status reads the accounts log
     // for the account email, so it takes the db path; none of them need a
     // live connection.
@@ -112,16 +121,24 @@ fn main() -> Result<()> {
         chunk_overlap_tokens,
         overlap_mode,
         contextual_headers,
+        dim,
+        precision,
+        model,
     } = &cli.command
     {
         let conn = get_connection(cli.db.as_deref())?;
         let overrides = embed::config::EmbedOverrides {
+            model: model.as_deref().and_then(embed::model::find_model),
             target_tokens: *chunk_target_tokens,
             overlap_tokens: *chunk_overlap_tokens,
             overlap_mode: overlap_mode
                 .as_deref()
                 .and_then(embed::chunker::OverlapMode::parse),
             contextual_headers: *contextual_headers,
+            storage_dim: *dim,
+            precision: precision
+                .as_deref()
+                .and_then(embed::quant::Precision::parse),
         };
         commands::embed::run(
             &conn,
@@ -134,6 +151,12 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
+    // Models command (import, export); no database needed
+    if let Commands::Models { action } = &cli.command {
+        commands::models::run(action, ctx.output_mode)?;
+        return Ok(());
+    }
+
     // Benchmark command
     if let Commands::Benchmark { action } = &cli.command {
         let conn = get_connection(cli.db.as_deref())?;
@@ -154,6 +177,11 @@ fn main() -> Result<()> {
             matches,
             context,
             meeting,
+            with,
+            company,
+            account,
+            series,
+            min_attendees,
             from,
             to,
             date,
@@ -163,6 +191,11 @@ fn main() -> Result<()> {
             let echo = commands::search::FilterEcho {
                 in_targets: r#in.clone(),
                 meeting: meeting.clone(),
+                with: with.clone(),
+                company: company.clone(),
+                account: account.clone(),
+                series: series.clone(),
+                min_attendees: *min_attendees,
                 date: date.clone(),
                 from: from.clone(),
                 to: to.clone(),
@@ -186,6 +219,11 @@ fn main() -> Result<()> {
             matches,
             context,
             meeting,
+            with,
+            company,
+            account,
+            series,
+            min_attendees,
             from,
             to,
             date,
@@ -195,7 +233,14 @@ fn main() -> Result<()> {
         } => {
             let opts = commands::grep::GrepOptions {
                 targets: r#in.clone(),
-                meeting_filter: meeting.clone(),
+                filters: query::filter::MeetingFilters {
+                    meeting: meeting.clone(),
+                    with: with.clone(),
+                    company: company.clone(),
+                    account: account.clone(),
+                    series: series.clone(),
+                    min_attendees: *min_attendees,
+                },
                 limit: *limit,
                 matches: *matches,
                 speaker: query::speaker::resolve_opt(&conn, speaker.as_ref())?,
@@ -211,6 +256,48 @@ fn main() -> Result<()> {
             commands::grep::grep(&conn, query, opts, date_range, *include_deleted, &ctx)?;
         }
 
+        Commands::Similar {
+            meeting,
+            r#in,
+            exclude_series,
+            from,
+            to,
+            date,
+            limit,
+            include_deleted,
+        } => {
+            let opts = commands::similar::SimilarOptions {
+                targets: r#in.clone(),
+                exclude_series: *exclude_series,
+                limit: *limit,
+            };
+            let date_range = query::dates::build_date_range(
+                from.as_deref(),
+                to.as_deref(),
+                date.as_deref(),
+                chrono::Utc::now(),
+                &ctx.tz,
+            );
+            commands::similar::similar(&conn, meeting, opts, date_range, *include_deleted, &ctx)?;
+        }
+
+        Commands::Topics {
+            topics,
+            r#in,
+            from,
+            to,
+            date,
+        } => {
+            let date_range = query::dates::build_date_range(
+                from.as_deref(),
+                to.as_deref(),
+                date.as_deref(),
+                chrono::Utc::now(),
+                &ctx.tz,
+            );
+            commands::topics::topics(&conn, r#in, date_range, *topics, &ctx)?;
+        }
+
         Commands::List {
             person,
             from,
@@ -278,10 +365,44 @@ fn main() -> Result<()> {
 
         Commands::Benchmark { .. } => unreachable!(), // Handled above
         Commands::Dropbox { .. } => unreachable!(),   // Handled above
+        Commands::Remote { .. } => unreachable!(),    // Handled above
         Commands::Auth { .. } => unreachable!(),      // Handled above
         Commands::Update { .. } => unreachable!(),    // Handled above
         Commands::Sync { .. } => unreachable!(),      // Handled above
         Commands::Embed { .. } => unreachable!(),     // Handled above
+        Commands::Models { .. } => unreachable!(),    // Handled above
+
+        Commands::Export { action } => {
+            commands::export::run(&conn, action, &ctx)?;
+        }
+
+        Commands::Saved { action } => {
+            commands::saved::run(&conn, action, &ctx)?;
+        }
+
+        Commands::Stats { action } => {
+            commands::stats::run(&conn, action, &ctx)?;
+        }
+
+        Commands::Actions { action } => {
+            commands::actions::run(&conn, action, &ctx)?;
+        }
+
+        Commands::People { action } => {
+            commands::aliases::run(&conn, action, &ctx)?;
+        }
+
+        Commands::Calendar { action } => {
+            commands::calendars::run(&conn, action, &ctx)?;
+        }
+
+        Commands::Serve { listen, fast } => {
+            serve::run(&conn, listen, *fast, ctx.tz)?;
+        }
+
+        Commands::Mcp { fast } => {
+            serve::mcp::run(&conn, *fast, ctx.tz)?;
+        }
 
         // === Browse Commands ===
         Commands::Browse { action } => {
@@ -1,6 +1,7 @@
 use serde::Serialize;
 
-use crate::models::{Calendar, CalendarEvent, Document, PanelTemplate, Person, Recipe};
+use crate::db::event_links::EventWithMeetings;
+use crate::models::{Calendar, Document, Panel, PanelTemplate, Person, Recipe};
 
 /// Serialize any serializable value to pretty JSON string.
 pub fn to_json<T: Serialize>(value: &T) -> String {
@@ -12,9 +13,14 @@ pub fn format_meetings(docs: &[&Document]) -> String {
     to_json(&docs)
 }
 
-/// Format a single document as JSON.
-pub fn format_meeting_detail(doc: &Document) -> String {
-    to_json(doc)
+/// A document with its AI panels attached, as `grans show --json` prints it.
+/// The `panels` key is present only when the meeting has any.
+pub fn meeting_detail_value(doc: &Document, panels: &[Panel]) -> serde_json::Value {
+    let mut detail = serde_json::to_value(doc).unwrap_or_default();
+    if !panels.is_empty() {
+        detail["panels"] = serde_json::json!(panels);
+    }
+    detail
 }
 
 /// One match's evidence in shaped search JSON.
@@ -98,6 +104,49 @@ pub struct SearchResponse {
     pub meetings: Vec<ShapedMeetingJson>,
 }
 
+/// Response envelope for `grans similar`: the meeting compared against and
+/// its nearest neighbors, most similar first.
+#[derive(Debug, Serialize)]
+pub struct SimilarResponse {
+    pub meeting_id: String,
+    pub meeting_title: Option<String>,
+    pub limit: usize,
+    pub returned: usize,
+    pub meetings: Vec<ShapedMeetingJson>,
+}
+
+/// One saved search in `grans saved check --json`. `rerun` is false when
+/// nothing had synced since the previous check, so the search was skipped.
+#[derive(Debug, Serialize)]
+pub struct SavedCheckJson {
+    pub name: String,
+    pub verb: String,
+    pub query: String,
+    pub rerun: bool,
+    pub new_meetings: Vec<ShapedMeetingJson>,
+}
+
+impl SavedCheckJson {
+    pub fn new(
+        name: &str,
+        verb: &str,
+        query: &str,
+        rerun: bool,
+        new_meetings: &[crate::query::shape::ShapedMeeting],
+    ) -> Self {
+        SavedCheckJson {
+            name: name.to_string(),
+            verb: verb.to_string(),
+            query: query.to_string(),
+            rerun,
+            new_meetings: new_meetings
+                .iter()
+                .map(ShapedMeetingJson::from_shaped)
+                .collect(),
+        }
+    }
+}
+
 fn shaped_source_label(source: crate::query::shape::EvidenceSource) -> &'static str {
     use crate::query::shape::EvidenceSource;
     match source {
@@ -174,42 +223,78 @@ impl ShapedMeetingJson {
     }
 }
 
+/// Build the grep response envelope with the complete match count.
+pub fn grep_response(
+    results: &[crate::query::shape::ShapedMeeting],
+    query: &str,
+    total_meetings: usize,
+    limit: usize,
+) -> GrepResponse {
+    let meetings: Vec<ShapedMeetingJson> =
+        results.iter().map(ShapedMeetingJson::from_shaped).collect();
+    GrepResponse {
+        query: query.to_string(),
+        total_meetings,
+        limit,
+        returned: meetings.len(),
+        meetings,
+    }
+}
+
 /// Format grep results as JSON with the complete match count.
 pub fn format_grep_meetings(
     results: &[crate::query::shape::ShapedMeeting],
     query: &str,
     total_meetings: usize,
     limit: usize,
+) -> String {
+    to_json(&grep_response(results, query, total_meetings, limit))
+}
+
+/// Format `grans similar` results as JSON.
+pub fn format_similar_meetings(
+    results: &[crate::query::shape::ShapedMeeting],
+    meeting_id: &str,
+    meeting_title: Option<&str>,
+    limit: usize,
 ) -> String {
     let meetings: Vec<ShapedMeetingJson> =
         results.iter().map(ShapedMeetingJson::from_shaped).collect();
-    let response = GrepResponse {
-        query: query.to_string(),
-        total_meetings,
+    to_json(&SimilarResponse {
+        meeting_id: meeting_id.to_string(),
+        meeting_title: meeting_title.map(str::to_string),
         limit,
         returned: meetings.len(),
         meetings,
-    };
-    to_json(&response)
+    })
 }
 
-/// Format ranked search results as JSON with the uncapped FTS count.
-pub fn format_search_meetings(
+/// Build the ranked search response envelope with the uncapped FTS count.
+pub fn search_response(
     results: &[crate::query::shape::ShapedMeeting],
     query: &str,
     keyword_total: usize,
     limit: usize,
-) -> String {
+) -> SearchResponse {
     let meetings: Vec<ShapedMeetingJson> =
         results.iter().map(ShapedMeetingJson::from_shaped).collect();
-    let response = SearchResponse {
+    SearchResponse {
         query: query.to_string(),
         keyword_total,
         limit,
         returned: meetings.len(),
         meetings,
-    };
-    to_json(&response)
+    }
+}
+
+/// Format ranked search results as JSON with the uncapped FTS count.
+pub fn format_search_meetings(
+    results: &[crate::query::shape::ShapedMeeting],
+    query: &str,
+    keyword_total: usize,
+    limit: usize,
+) -> String {
+    to_json(&search_response(results, query, keyword_total, limit))
 }
 
 /// Format a list of people as JSON.
@@ -222,8 +307,8 @@ pub fn format_calendars(calendars: &[&Calendar]) -> String {
     to_json(&calendars)
 }
 
-/// Format a list of events as JSON.
-pub fn format_events(events: &[&CalendarEvent]) -> String {
+/// Format a list of events, with their recorded meetings, as JSON.
+pub fn format_events(events: &[EventWithMeetings]) -> String {
     to_json(&events)
 }
 
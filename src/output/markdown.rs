@@ -0,0 +1,481 @@
+//! Markdown rendering of a meeting for notes vaults such as Obsidian.
+//!
+//! One meeting becomes one file: YAML front matter describing the meeting,
+//! then the user's notes, the AI panels and the transcript. Rendering is pure;
+//! `commands::export` decides which meetings to write and where.
+
+use chrono::FixedOffset;
+
+use crate::db::accounts::account_label;
+use crate::db::export::{ExportAttendee, ExportDocument};
+use crate::models::{Panel, TranscriptUtterance};
+use crate::query::speaker::label as speaker_label;
+use crate::tiptap::{extract_chat_url, tiptap_to_markdown};
+
+/// Longest file stem we produce, in characters. Keeps paths comfortably under
+/// filesystem limits once the vault path and extension are added.
+const MAX_STEM_CHARS: usize = 120;
+
+/// Everything one note is rendered from.
+pub struct MarkdownNote<'a> {
+    pub document: &'a ExportDocument,
+    pub attendees: &'a [ExportAttendee],
+    pub panels: &'a [Panel],
+    pub transcript: &'a [TranscriptUtterance],
+}
+
+/// Render a meeting as a Markdown note with YAML front matter.
+pub fn render_note(note: &MarkdownNote, tz: &FixedOffset) -> String {
+    let doc = note.document;
+    let title = display_title(doc);
+    let mut out = front_matter(note);
+
+    out.push_str(&format!("\n# {}\n", title));
+
+    let notes = doc
+        .notes_markdown
+        .as_deref()
+        .filter(|n| !n.trim().is_empty())
+        .or(doc.notes_plain.as_deref().filter(|n| !n.trim().is_empty()));
+    if let Some(notes) = notes {
+        out.push_str("\n## Notes\n\n");
+        out.push_str(notes.trim_end());
+        out.push('\n');
+    }
+
+    if !note.panels.is_empty() {
+        out.push_str("\n## AI Notes\n");
+        for panel in note.panels {
+            out.push_str(&format!(
+                "\n### {}\n",
+                panel.title.as_deref().unwrap_or("Untitled panel")
+            ));
+            let (markdown, chat_url) = panel_markdown(panel);
+            if !markdown.is_empty() {
+                out.push('\n');
+                out.push_str(&markdown);
+                out.push('\n');
+            }
+            if let Some(url) = chat_url {
+                out.push_str(&format!("\n[Chat with this meeting]({})\n", url));
+            }
+        }
+    }
+
+    if !note.transcript.is_empty() {
+        out.push_str("\n## Transcript\n\n");
+        for utt in note.transcript {
+            out.push_str(&transcript_line(utt, tz));
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+/// File name (without extension) for a meeting: its local date and title,
+/// stripped of characters that filesystems or Obsidian links reject.
+pub fn note_file_stem(doc: &ExportDocument, tz: &FixedOffset) -> String {
+    let date = doc
+        .created_at
+        .as_deref()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        .map(|dt| dt.with_timezone(tz).format("%Y-%m-%d").to_string());
+
+    let title = sanitize_file_component(display_title(doc));
+    let stem = match date {
+        Some(date) => format!("{} {}", date, title),
+        None => title,
+    };
+    stem.chars()
+        .take(MAX_STEM_CHARS)
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+fn display_title(doc: &ExportDocument) -> &str {
+    doc.title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or("Untitled meeting")
+}
+
+fn front_matter(note: &MarkdownNote) -> String {
+    let doc = note.document;
+    let mut out = String::from("---\n");
+
+    out.push_str(&format!("id: {}\n", yaml_string(&doc.id)));
+    out.push_str(&format!("title: {}\n", yaml_string(display_title(doc))));
+    if let Some(created) = &doc.created_at {
+        out.push_str(&format!("date: {}\n", yaml_string(created)));
+    }
+    if let Some(updated) = &doc.updated_at {
+        out.push_str(&format!("updated: {}\n", yaml_string(updated)));
+    }
+    if let Some(deleted) = &doc.deleted_at {
+        out.push_str(&format!("deleted: {}\n", yaml_string(deleted)));
+    }
+
+    let attendees = attendee_names(note.attendees);
+    if !attendees.is_empty() {
+        out.push_str("attendees:\n");
+        for attendee in attendees {
+            out.push_str(&format!("  - {}\n", yaml_string(&attendee)));
+        }
+    }
+
+    if let Some(event) = doc
+        .google_calendar_event_json
+        .as_deref()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok())
+    {
+        let fields = [
+            ("id", event.get("id")),
+            ("summary", event.get("summary")),
+            ("start", event.pointer("/start/dateTime")),
+            ("end", event.pointer("/end/dateTime")),
+            ("calendar", event.get("calendarId")),
+        ];
+        let present: Vec<(&str, &str)> = fields
+            .iter()
+            .filter_map(|(key, value)| Some((*key, value.and_then(|v| v.as_str())?)))
+            .collect();
+        if !present.is_empty() {
+            out.push_str("calendar_event:\n");
+            for (key, value) in present {
+                out.push_str(&format!("  {}: {}\n", key, yaml_string(value)));
+            }
+        }
+    }
+
+    if let Some(account_id) = &doc.source_account_id {
+        let account = match &doc.source_account_email {
+            Some(email) => account_label(email, account_id),
+            None => account_id.clone(),
+        };
+        out.push_str(&format!("source_account: {}\n", yaml_string(&account)));
+    }
+
+    out.push_str("---\n");
+    out
+}
+
+/// "Name <email>" for each distinct person. The creator usually appears again
+/// as an attendee, so people are deduplicated by email, then by name.
+fn attendee_names(attendees: &[ExportAttendee]) -> Vec<String> {
+    let mut seen: Vec<String> = Vec::new();
+    let mut names = Vec::new();
+
+    for attendee in attendees {
+        let name = attendee
+            .full_name
+            .as_deref()
+            .filter(|n| !n.trim().is_empty());
+        let email = attendee.email.as_deref().filter(|e| !e.trim().is_empty());
+        let Some(key) = email.or(name).map(str::to_lowercase) else {
+            continue;
+        };
+        if seen.contains(&key) {
+            continue;
+        }
+        seen.push(key);
+
+        names.push(match (name, email) {
+            (Some(name), Some(email)) => format!("{} <{}>", name, email),
+            (Some(name), None) => name.to_string(),
+            (None, Some(email)) => email.to_string(),
+            (None, None) => unreachable!(),
+        });
+    }
+
+    names
+}
+
+/// Render a panel from its TipTap source, falling back to the Markdown stored
+/// at sync time when the source is missing or empty.
+fn panel_markdown(panel: &Panel) -> (String, Option<String>) {
+    let rendered = panel
+        .content_json
+        .as_deref()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok())
+        .map(|doc| tiptap_to_markdown(&doc))
+        .filter(|md| !md.is_empty());
+
+    match rendered {
+        Some(md) => {
+            let (cleaned, url) = extract_chat_url(&md);
+            (
+                cleaned.trim().to_string(),
+                url.or_else(|| panel.chat_url.clone()),
+            )
+        }
+        None => (
+            panel
+                .content_markdown
+                .as_deref()
+                .unwrap_or("")
+                .trim()
+                .to_string(),
+            panel.chat_url.clone(),
+        ),
+    }
+}
+
+fn transcript_line(utt: &TranscriptUtterance, tz: &FixedOffset) -> String {
+    let text = utt.text.as_deref().unwrap_or("").trim();
+    let timestamp = utt
+        .start_timestamp
+        .as_deref()
+        .and_then(|s| chrono::DateTime::parse_from_rfc3339(s).ok())
+        .map(|dt| format!("`{}` ", dt.with_timezone(tz).format("%H:%M:%S")))
+        .unwrap_or_default();
+
+    match speaker_label(utt.source.as_deref(), utt.detected_speaker_name.as_deref()) {
+        Some(label) => format!("- {}**{}:** {}", timestamp, label.as_str(), text),
+        None => format!("- {}{}", timestamp, text),
+    }
+}
+
+/// Double-quoted YAML scalar, which round-trips any string.
+fn yaml_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Replace characters that are invalid in file names on some platform, or
+/// that break Obsidian wiki links (`#`, `^`, `[`, `]`, `|`).
+fn sanitize_file_component(s: &str) -> String {
+    let replaced: String = s
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | '#' | '^' | '[' | ']' => '-',
+            c if c.is_control() => ' ',
+            c => c,
+        })
+        .collect();
+
+    let collapsed = replaced.split_whitespace().collect::<Vec<_>>().join(" ");
+    let trimmed = collapsed.trim_matches(|c: char| c == '.' || c == ' ');
+    if trimmed.is_empty() {
+        "Untitled meeting".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc() -> FixedOffset {
+        FixedOffset::east_opt(0).unwrap()
+    }
+
+    fn doc() -> ExportDocument {
+        ExportDocument {
+            id: "doc-1".to_string(),
+            title: Some("Weekly Sync".to_string()),
+            created_at: Some("2026-01-20T10:00:00Z".to_string()),
+            updated_at: Some("2026-01-20T11:00:00Z".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn attendee(name: Option<&str>, email: Option<&str>, role: &str) -> ExportAttendee {
+        ExportAttendee {
+            full_name: name.map(String::from),
+            email: email.map(String::from),
+            role: role.to_string(),
+        }
+    }
+
+    fn utterance(source: &str, speaker: Option<&str>, text: &str) -> TranscriptUtterance {
+        TranscriptUtterance {
+            start_timestamp: Some("2026-01-20T10:00:05Z".to_string()),
+            text: Some(text.to_string()),
+            source: Some(source.to_string()),
+            detected_speaker_name: speaker.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    fn render(note: &MarkdownNote) -> String {
+        render_note(note, &utc())
+    }
+
+    #[test]
+    fn front_matter_carries_meeting_metadata() {
+        let mut document = doc();
+        document.google_calendar_event_json = Some(
+            r#"{"id":"evt-1","summary":"Weekly Sync","start":{"dateTime":"2026-01-20T10:00:00Z"}}"#
+                .to_string(),
+        );
+        document.source_account_id = Some("user_01".to_string());
+        document.source_account_email = Some("me@example.com".to_string());
+        let attendees = [attendee(
+            Some("Bob Jones"),
+            Some("bob@example.com"),
+            "attendee",
+        )];
+
+        let out = render(&MarkdownNote {
+            document: &document,
+            attendees: &attendees,
+            panels: &[],
+            transcript: &[],
+        });
+
+        assert!(out.starts_with("---\nid: \"doc-1\"\ntitle: \"Weekly Sync\"\n"));
+        assert!(out.contains("date: \"2026-01-20T10:00:00Z\"\n"));
+        assert!(out.contains("attendees:\n  - \"Bob Jones <bob@example.com>\"\n"));
+        assert!(out.contains("calendar_event:\n  id: \"evt-1\"\n  summary: \"Weekly Sync\"\n"));
+        assert!(out.contains("source_account: \"me@example.com (user_01)\"\n"));
+    }
+
+    #[test]
+    fn attendees_are_deduplicated_by_email() {
+        let attendees = [
+            attendee(Some("Alice"), Some("alice@example.com"), "creator"),
+            attendee(None, Some("ALICE@example.com"), "attendee"),
+            attendee(Some("Carol"), None, "attendee"),
+        ];
+
+        assert_eq!(
+            attendee_names(&attendees),
+            vec!["Alice <alice@example.com>", "Carol"]
+        );
+    }
+
+    #[test]
+    fn notes_prefer_markdown_over_plain() {
+        let mut document = doc();
+        document.notes_plain = Some("plain".to_string());
+        document.notes_markdown = Some("**rich**".to_string());
+
+        let out = render(&MarkdownNote {
+            document: &document,
+            attendees: &[],
+            panels: &[],
+            transcript: &[],
+        });
+
+        assert!(out.contains("## Notes\n\n**rich**\n"));
+        assert!(!out.contains("plain"));
+    }
+
+    #[test]
+    fn panels_render_from_tiptap_source() {
+        let panel = Panel {
+            title: Some("Summary".to_string()),
+            content_json: Some(
+                r#"{"type":"doc","content":[{"type":"paragraph","content":[{"type":"text","text":"Shipped it"}]}]}"#
+                    .to_string(),
+            ),
+            content_markdown: Some("stale".to_string()),
+            ..Default::default()
+        };
+
+        let out = render(&MarkdownNote {
+            document: &doc(),
+            attendees: &[],
+            panels: &[panel],
+            transcript: &[],
+        });
+
+        assert!(out.contains("## AI Notes\n\n### Summary\n\nShipped it\n"));
+        assert!(!out.contains("stale"));
+    }
+
+    #[test]
+    fn panels_fall_back_to_stored_markdown() {
+        let panel = Panel {
+            title: Some("Summary".to_string()),
+            content_json: Some("{}".to_string()),
+            content_markdown: Some("- Review the plan".to_string()),
+            chat_url: Some("https://notes.granola.ai/t/abc".to_string()),
+            ..Default::default()
+        };
+
+        let out = render(&MarkdownNote {
+            document: &doc(),
+            attendees: &[],
+            panels: &[panel],
+            transcript: &[],
+        });
+
+        assert!(out.contains("- Review the plan\n"));
+        assert!(out.contains("[Chat with this meeting](https://notes.granola.ai/t/abc)"));
+    }
+
+    #[test]
+    fn transcript_uses_speaker_labels() {
+        let transcript = [
+            utterance("microphone", None, "Morning."),
+            utterance("system", Some("Dana"), "Hi there."),
+            utterance("system", None, "Hello."),
+        ];
+
+        let out = render(&MarkdownNote {
+            document: &doc(),
+            attendees: &[],
+            panels: &[],
+            transcript: &transcript,
+        });
+
+        assert!(out.contains("## Transcript\n\n- `10:00:05` **You:** Morning.\n"));
+        assert!(out.contains("- `10:00:05` **Dana:** Hi there.\n"));
+        assert!(out.contains("- `10:00:05` **Other:** Hello.\n"));
+    }
+
+    #[test]
+    fn yaml_string_escapes_quotes_and_newlines() {
+        assert_eq!(yaml_string("a \"b\"\nc\\"), r#""a \"b\"\nc\\""#);
+    }
+
+    #[test]
+    fn file_stem_is_date_and_sanitized_title() {
+        let mut document = doc();
+        document.title = Some("Q1: Plan / Review #2?".to_string());
+
+        assert_eq!(
+            note_file_stem(&document, &utc()),
+            "2026-01-20 Q1- Plan - Review -2-"
+        );
+    }
+
+    #[test]
+    fn file_stem_uses_display_timezone() {
+        let utc_minus_5 = FixedOffset::west_opt(5 * 3600).unwrap();
+        let mut document = doc();
+        document.created_at = Some("2026-01-21T02:00:00Z".to_string());
+
+        assert_eq!(
+            note_file_stem(&document, &utc_minus_5),
+            "2026-01-20 Weekly Sync"
+        );
+    }
+
+    #[test]
+    fn file_stem_for_untitled_meeting() {
+        let mut document = doc();
+        document.title = Some("  ".to_string());
+        document.created_at = None;
+
+        assert_eq!(note_file_stem(&document, &utc()), "Untitled meeting");
+    }
+}
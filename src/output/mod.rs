@@ -1,5 +1,6 @@
 pub mod card;
 pub mod format;
 pub mod json;
+pub mod markdown;
 pub mod progress;
 pub mod table;
@@ -1,6 +1,7 @@
 use chrono::FixedOffset;
 use colored::Colorize;
 
+use crate::db::event_links::EventWithMeetings;
 use crate::models::{
     Calendar, CalendarEvent, Document, PanelTemplate, Person, Recipe, TranscriptUtterance,
 };
@@ -176,8 +177,38 @@ pub fn format_calendar_row(cal: &Calendar) -> String {
     format!("{} {}{}", id, summary, primary)
 }
 
-/// Format an event for TTY display.
-pub fn format_event_row(event: &CalendarEvent, tz: &FixedOffset) -> String {
+/// Format an event for TTY display, marking what its recorded meetings
+/// captured.
+pub fn format_event_row(event: &EventWithMeetings, tz: &FixedOffset) -> String {
+    let line = event_line(&event.event, tz);
+    if event.meetings.is_empty() {
+        return line;
+    }
+    let mut captured = Vec::new();
+    if event.meetings.iter().any(|m| m.has_notes) {
+        captured.push("notes");
+    }
+    if event.meetings.iter().any(|m| m.has_transcript) {
+        captured.push("transcript");
+    }
+    if captured.is_empty() {
+        captured.push("recorded");
+    }
+    format!("{} {}", line, format!("[{}]", captured.join(", ")).green())
+}
+
+/// Format an unrecorded event for TTY display.
+pub fn format_gap_row(event: &CalendarEvent, tz: &FixedOffset) -> String {
+    let attendees = event.attendees.as_ref().map_or(0, |a| a.len());
+    let count = match attendees {
+        0 => String::new(),
+        1 => " (1 attendee)".to_string(),
+        n => format!(" ({} attendees)", n),
+    };
+    format!("{}{}", event_line(event, tz), count.dimmed())
+}
+
+fn event_line(event: &CalendarEvent, tz: &FixedOffset) -> String {
     let summary = event
         .summary
         .as_deref()
@@ -255,6 +286,292 @@ pub fn format_recipe_row(recipe: &Recipe) -> String {
     format!("{} {}{}", id, name, visibility)
 }
 
+/// Format a saved search list entry for TTY display.
+pub fn format_saved_search_row(
+    record: &crate::db::saved::SavedSearchRecord,
+    tz: &FixedOffset,
+) -> String {
+    let checked = record
+        .checked_at
+        .as_deref()
+        .map(|d| format!("checked {}", format_date_short(d, tz)))
+        .unwrap_or_else(|| "never checked".to_string())
+        .dimmed()
+        .to_string();
+    format!(
+        "{} {} \"{}\" {}",
+        record.name.bold(),
+        record.verb,
+        record.query,
+        checked
+    )
+}
+
+/// Format the meeting line that heads a group of action items.
+pub fn format_action_meeting_header(
+    item: &crate::db::actions::ActionItemRecord,
+    tz: &FixedOffset,
+) -> String {
+    let date = item
+        .created_at
+        .as_deref()
+        .map(|d| format_date_short(d, tz))
+        .unwrap_or_default()
+        .dimmed()
+        .to_string();
+    let title = item
+        .title
+        .as_deref()
+        .unwrap_or("(untitled)")
+        .bold()
+        .to_string();
+    format!("{} {}", date, title)
+}
+
+/// Format one action item or decision for TTY display: its ID, a done
+/// checkbox for actions, the text and the owner when known.
+pub fn format_action_item_row(item: &crate::db::actions::ActionItemRecord) -> String {
+    let mark = match (item.kind, &item.done_at) {
+        (crate::query::actions::ItemKind::Decision, _) => "•".to_string(),
+        (_, Some(_)) => "[x]".green().to_string(),
+        (_, None) => "[ ]".to_string(),
+    };
+    let owner = item
+        .owner
+        .as_deref()
+        .map(|o| format!(" — {}", o.cyan()))
+        .unwrap_or_default();
+    format!("  {} {} {}{}", item.id.dimmed(), mark, item.text, owner)
+}
+
+/// Format per-speaker talk-time statistics as an aligned table.
+pub fn format_talk_time_table(speakers: &[crate::query::turns::SpeakerStats]) -> String {
+    let width = speakers
+        .iter()
+        .map(|s| s.speaker.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Speaker".len());
+    let mut lines = vec![
+        format!(
+            "{:<width$}  {:>5}  {:>8}  {:>5}  {:>8}  {:>10}  {:>9}",
+            "Speaker", "Share", "Talk", "Turns", "Longest", "Interrupts", "Questions"
+        )
+        .bold()
+        .to_string(),
+    ];
+    for s in speakers {
+        lines.push(format!(
+            "{:<width$}  {:>4.0}%  {:>8}  {:>5}  {:>8}  {:>10}  {:>9}",
+            s.speaker,
+            s.share * 100.0,
+            format_duration_secs(s.talk_seconds),
+            s.turns,
+            format_duration_secs(s.longest_monologue_seconds),
+            s.interruptions,
+            s.questions
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Format a merged person as its identities, names first.
+pub fn format_alias_group(group: &crate::db::aliases::AliasGroup) -> String {
+    let mut parts: Vec<String> = group.names().map(|n| n.bold().to_string()).collect();
+    parts.extend(group.emails().map(|e| format!("<{}>", e)));
+    parts.join(", ")
+}
+
+/// Format a merge suggestion with the command that confirms it.
+pub fn format_merge_suggestion(s: &crate::db::aliases::MergeSuggestion) -> String {
+    format!(
+        "{} = {} {}\n  {}",
+        s.a.bold(),
+        s.b.bold(),
+        format!("({})", s.reason).dimmed(),
+        format!("grans people merge \"{}\" \"{}\"", s.a, s.b).dimmed()
+    )
+}
+
+/// Seconds as `m:ss`, or `h:mm:ss` from an hour up.
+pub fn format_duration_secs(secs: f64) -> String {
+    let total = secs.round() as u64;
+    let (h, m, s) = (total / 3600, total / 60 % 60, total % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
+
+/// Format one topic for TTY display: its label terms and size, a weekly
+/// sparkline of meetings touching it, and its representative meetings.
+pub fn format_topic(topic: &crate::query::topics::Topic, rank: usize, tz: &FixedOffset) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let label = if topic.terms.is_empty() {
+        "(no distinctive terms)".to_string()
+    } else {
+        topic.terms.join(", ")
+    };
+    let mut lines = vec![format!(
+        "{}. {} {}",
+        rank,
+        label.bold(),
+        format!("({} meetings)", topic.meetings).dimmed()
+    )];
+
+    let peak = topic.weekly.iter().map(|w| w.meetings).max().unwrap_or(0);
+    if peak > 0 {
+        let spark: String = topic
+            .weekly
+            .iter()
+            .map(|w| match w.meetings {
+                0 => ' ',
+                n => BARS[((n * BARS.len()).div_ceil(peak) - 1).min(BARS.len() - 1)],
+            })
+            .collect();
+        lines.push(format!(
+            "   weekly {} {}",
+            spark,
+            format!("(peak {} in a week)", peak).dimmed()
+        ));
+    }
+
+    for meeting in &topic.representative {
+        lines.push(format!(
+            "   {} {}",
+            format_date_short(&meeting.created_at, tz).dimmed(),
+            meeting.title.as_deref().unwrap_or("(untitled)")
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Format a person profile as a card: who they are, meeting counts and
+/// last contact, a monthly sparkline, co-attendees, series, recent
+/// meetings and recent utterances.
+pub fn format_person_profile(
+    profile: &crate::db::profile::PersonProfile,
+    tz: &FixedOffset,
+) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let heading = match (&profile.name, &profile.email) {
+        (Some(name), Some(email)) => format!("{} {}", name.bold(), format!("<{}>", email).dimmed()),
+        (Some(name), None) => name.bold().to_string(),
+        (None, Some(email)) => email.bold().to_string(),
+        (None, None) => "(unnamed)".bold().to_string(),
+    };
+    let mut lines = vec![heading];
+    let role: Vec<&str> = [
+        profile.job_title.as_deref(),
+        profile.company_name.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if !role.is_empty() {
+        lines.push(format!("  {}", role.join(", ")));
+    }
+    if !profile.aliases.is_empty() {
+        lines.push(format!(
+            "  {} {}",
+            "Also:".dimmed(),
+            profile.aliases.join(", ")
+        ));
+    }
+    lines.push(String::new());
+
+    let meeting_line = |m: &crate::db::profile::ProfileMeeting| {
+        format!(
+            "{} {}",
+            format_date_short(m.created_at.as_deref().unwrap_or(""), tz).dimmed(),
+            m.title.as_deref().unwrap_or("(untitled)")
+        )
+    };
+    lines.push(format!(
+        "  Meetings:   {} (spoke in {})",
+        profile.meetings, profile.spoke_in
+    ));
+    if let Some(first) = &profile.first_meeting {
+        lines.push(format!("  First:      {}", meeting_line(first)));
+    }
+    if let Some(last) = &profile.last_meeting {
+        lines.push(format!("  Last:       {}", meeting_line(last)));
+    }
+
+    let peak = profile
+        .monthly
+        .iter()
+        .map(|m| m.meetings)
+        .max()
+        .unwrap_or(0);
+    if peak > 0
+        && let (Some(first), Some(last)) = (profile.monthly.first(), profile.monthly.last())
+    {
+        let spark: String = profile
+            .monthly
+            .iter()
+            .map(|m| match m.meetings {
+                0 => ' ',
+                n => BARS[((n * BARS.len()).div_ceil(peak) - 1).min(BARS.len() - 1)],
+            })
+            .collect();
+        lines.push(format!(
+            "  Monthly:    {} {}",
+            spark,
+            format!("({} to {}, peak {})", first.month, last.month, peak).dimmed()
+        ));
+    }
+
+    if !profile.co_attendees.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("  {}", "Meets most with".bold()));
+        for c in &profile.co_attendees {
+            let who = c
+                .name
+                .as_deref()
+                .or(c.email.as_deref())
+                .unwrap_or("(unnamed)");
+            lines.push(format!("    {:>3}  {}", c.meetings, who));
+        }
+    }
+
+    if !profile.series.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("  {}", "Recurring".bold()));
+        for s in &profile.series {
+            lines.push(format!("    {:>3}  {}", s.meetings, s.title));
+        }
+    }
+
+    if !profile.recent_meetings.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("  {}", "Recent meetings".bold()));
+        for m in &profile.recent_meetings {
+            lines.push(format!("    {}", meeting_line(m)));
+        }
+    }
+
+    if !profile.recent_utterances.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("  {}", "Recently said".bold()));
+        for u in &profile.recent_utterances {
+            lines.push(format!(
+                "    {} \"{}\"",
+                format!(
+                    "{} {}:",
+                    format_date_short(u.start_timestamp.as_deref().unwrap_or(""), tz),
+                    u.title.as_deref().unwrap_or("(untitled)")
+                )
+                .dimmed(),
+                u.text
+            ));
+        }
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
 pub(super) fn format_date_short(s: &str, tz: &FixedOffset) -> String {
     // Try to parse and format nicely, fallback to raw string
     if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
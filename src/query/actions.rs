@@ -0,0 +1,395 @@
+//! Action items and decisions pulled out of AI panel markdown.
+//!
+//! Granola's panels usually end with "Action Items", "Next Steps" or
+//! "Decisions" sections. Each list item or line under such a heading, up to
+//! the next heading at the same level or above, becomes one item. Headings
+//! are tracked by level rather than with
+//! [`super::text::split_markdown_sections`], which splits on one level only
+//! and would lose a person's sub-heading inside "Action Items". An owner is
+//! recognized when the item names one up front ("Alice: send the deck",
+//! "**Alice** – send the deck", "@alice", "Alice to send the deck"), at the
+//! end ("send the deck (Alice)"), or as a heading or parent bullet the
+//! items sit under.
+
+use serde::Serialize;
+
+use super::text::strip_panel_footer;
+
+/// Whether an item is something to do or something settled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    Action,
+    Decision,
+}
+
+impl ItemKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ItemKind::Action => "action",
+            ItemKind::Decision => "decision",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "action" => Some(ItemKind::Action),
+            "decision" => Some(ItemKind::Decision),
+            _ => None,
+        }
+    }
+}
+
+/// One item found in a panel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedItem {
+    pub kind: ItemKind,
+    /// The heading the item was listed under, as written.
+    pub section: String,
+    /// The item with list markers, checkboxes, emphasis and any owner
+    /// prefix or suffix removed.
+    pub text: String,
+    pub owner: Option<String>,
+}
+
+/// Words that start a capitalized phrase without naming anyone, so "We
+/// will ship" or "Next: ..." never yields an owner.
+const NOT_OWNERS: &[&str] = &[
+    "all", "both", "everyone", "i", "it", "next", "note", "team", "that", "the", "these", "they",
+    "this", "we", "you",
+];
+
+/// Classify a heading as an action or decision section.
+pub fn section_kind(heading: &str) -> Option<ItemKind> {
+    let h = heading
+        .trim_matches(|c: char| c == '*' || c == '_' || c == ':' || c.is_whitespace())
+        .to_lowercase();
+    if h.contains("decision") {
+        return Some(ItemKind::Decision);
+    }
+    const ACTION_WORDS: &[&str] = &[
+        "action",
+        "next step",
+        "to-do",
+        "todo",
+        "to do",
+        "follow-up",
+        "follow up",
+        "followup",
+        "task",
+    ];
+    ACTION_WORDS
+        .iter()
+        .any(|w| h.contains(w))
+        .then_some(ItemKind::Action)
+}
+
+/// Every action item and decision in a panel's markdown, in document order.
+pub fn extract_items(markdown: &str) -> Vec<ExtractedItem> {
+    let mut items = Vec::new();
+    // The list section being read: its kind, heading and heading level.
+    let mut section: Option<(ItemKind, String, usize)> = None;
+    // Owner named by a heading or a bare parent bullet, and the indent of
+    // that bullet (headings use None: they cover the rest).
+    let mut group_owner: Option<(String, Option<usize>)> = None;
+
+    for line in strip_panel_footer(markdown).lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some((level, heading)) = heading_text(line) {
+            let heading = clean_inline(heading);
+            match (section_kind(&heading), &section) {
+                (Some(kind), _) => {
+                    section = Some((kind, heading, level));
+                    group_owner = None;
+                }
+                // A deeper heading inside a list groups the items after
+                // it, under its owner when it names one.
+                (None, Some((_, _, section_level))) if level > *section_level => {
+                    group_owner = looks_like_name(&heading).then_some((heading, None));
+                }
+                (None, _) => {
+                    section = None;
+                    group_owner = None;
+                }
+            }
+            continue;
+        }
+        let Some((kind, section_name, _)) = &section else {
+            continue;
+        };
+
+        let indent = line.len() - line.trim_start().len();
+        let (is_list, content) = strip_list_marker(line.trim());
+        let content = clean_inline(content);
+        if content.is_empty() {
+            continue;
+        }
+
+        if let Some((_, Some(owner_indent))) = &group_owner
+            && indent <= *owner_indent
+        {
+            group_owner = None;
+        }
+        // A nested bullet under an ordinary item is detail, not an item.
+        if is_list && indent > 0 && group_owner.is_none() {
+            continue;
+        }
+
+        let bare_name = content.strip_suffix(':').unwrap_or(&content).trim();
+        if *kind == ItemKind::Action && looks_like_name(bare_name) {
+            group_owner = Some((bare_name.to_string(), is_list.then_some(indent)));
+            continue;
+        }
+        if !is_list && content.ends_with(':') {
+            continue;
+        }
+
+        let (text, owner) = if *kind == ItemKind::Action {
+            split_owner(&content)
+        } else {
+            (content.clone(), None)
+        };
+        let owner = owner.or_else(|| group_owner.as_ref().map(|(name, _)| name.clone()));
+        items.push(ExtractedItem {
+            kind: *kind,
+            section: section_name.clone(),
+            text,
+            owner,
+        });
+    }
+    items
+}
+
+/// Whether an item's owner names the given person: their full name, first
+/// name, or email local part (or its first dotted part), ignoring case. A
+/// joint owner ("Alice and Bob") names each of them.
+pub fn owner_is(owner: &str, name: Option<&str>, email: Option<&str>) -> bool {
+    let mut forms: Vec<String> = Vec::new();
+    if let Some(name) = name.map(str::trim).filter(|n| !n.is_empty()) {
+        forms.push(name.to_lowercase());
+        if let Some(first) = name.split_whitespace().next() {
+            forms.push(first.to_lowercase());
+        }
+    }
+    if let Some(local) = email
+        .and_then(|e| e.split('@').next())
+        .filter(|l| !l.is_empty())
+    {
+        forms.push(local.to_lowercase());
+        if let Some(first) = local.split(['.', '_']).next() {
+            forms.push(first.to_lowercase());
+        }
+    }
+    owner
+        .split([',', '&', '/'])
+        .flat_map(|part| part.split(" and "))
+        .map(|part| part.trim().trim_start_matches('@').to_lowercase())
+        .any(|part| forms.contains(&part))
+}
+
+/// The level and text of a markdown heading line, or None for any other
+/// line.
+fn heading_text(line: &str) -> Option<(usize, &str)> {
+    let hashes = line.bytes().take_while(|&b| b == b'#').count();
+    if (1..=6).contains(&hashes) && line.as_bytes().get(hashes) == Some(&b' ') {
+        Some((hashes, line[hashes..].trim()))
+    } else {
+        None
+    }
+}
+
+/// Remove a bullet, number or checkbox from the front of a line, reporting
+/// whether there was a list marker.
+fn strip_list_marker(line: &str) -> (bool, &str) {
+    let mut is_list = false;
+    let mut rest = line;
+    if let Some(r) = ["- ", "* ", "+ "].iter().find_map(|m| rest.strip_prefix(m)) {
+        is_list = true;
+        rest = r;
+    } else {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        if digits > 0
+            && let Some(r) = rest[digits..]
+                .strip_prefix(". ")
+                .or_else(|| rest[digits..].strip_prefix(") "))
+        {
+            is_list = true;
+            rest = r;
+        }
+    }
+    for checkbox in ["[ ] ", "[x] ", "[X] "] {
+        if let Some(r) = rest.strip_prefix(checkbox) {
+            rest = r;
+        }
+    }
+    (is_list, rest.trim())
+}
+
+/// Drop bold/italic markers and collapse whitespace.
+fn clean_inline(s: &str) -> String {
+    s.replace("**", "")
+        .replace("__", "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Whether `s` reads as one or more people's names: up to three
+/// capitalized words each, joined by "and", "&", "/" or commas.
+fn looks_like_name(s: &str) -> bool {
+    let s = s.trim().trim_start_matches('@');
+    if s.is_empty() || s.len() > 60 {
+        return false;
+    }
+    s.split([',', '&', '/'])
+        .flat_map(|part| part.split(" and "))
+        .all(|name| {
+            let words: Vec<&str> = name.split_whitespace().collect();
+            !words.is_empty()
+                && words.len() <= 3
+                && !NOT_OWNERS.contains(&words[0].to_lowercase().as_str())
+                && words.iter().all(|w| {
+                    w.chars().next().is_some_and(char::is_uppercase)
+                        && w.chars()
+                            .all(|c| c.is_alphabetic() || matches!(c, '.' | '-' | '\''))
+                })
+        })
+}
+
+/// Separate an owner from an action item's text, when one is named.
+fn split_owner(item: &str) -> (String, Option<String>) {
+    // "@alice send the deck"
+    if let Some(rest) = item.strip_prefix('@') {
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == ':')
+            .unwrap_or(rest.len());
+        let text = rest[end..].trim_start_matches(':').trim();
+        if end > 0 && !text.is_empty() {
+            return (text.to_string(), Some(rest[..end].to_string()));
+        }
+    }
+    // "Alice: send the deck", "Alice - send the deck", "Alice – send the deck"
+    for sep in [": ", " - ", " – ", " — "] {
+        if let Some((head, tail)) = item.split_once(sep)
+            && looks_like_name(head)
+            && !tail.trim().is_empty()
+        {
+            return (tail.trim().to_string(), Some(head.trim().to_string()));
+        }
+    }
+    // "Send the deck (Alice)", "Send the deck (owner: Alice)"
+    if let Some(open) = item.rfind(" (")
+        && item.ends_with(')')
+    {
+        let inner = &item[open + 2..item.len() - 1];
+        let name = inner
+            .strip_prefix("owner:")
+            .or_else(|| inner.strip_prefix("Owner:"))
+            .unwrap_or(inner)
+            .trim();
+        if looks_like_name(name) {
+            return (item[..open].trim().to_string(), Some(name.to_string()));
+        }
+    }
+    // "Alice to send the deck", "Alice will send the deck"
+    for verb in [" to ", " will "] {
+        if let Some((head, _)) = item.split_once(verb)
+            && head.split_whitespace().count() <= 2
+            && looks_like_name(head)
+        {
+            return (item.to_string(), Some(head.to_string()));
+        }
+    }
+    (item.to_string(), None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owners_and_texts(md: &str) -> Vec<(Option<String>, String)> {
+        extract_items(md)
+            .into_iter()
+            .map(|i| (i.owner, i.text))
+            .collect()
+    }
+
+    #[test]
+    fn takes_items_from_action_and_decision_sections_only() {
+        let md = "### Overview\n\n- Talked about pricing\n\n### Decisions\n\n- Keep the annual plan\n\n### Action Items\n\n- Review the deployment plan\n- [ ] Schedule follow-up meeting\n\n---\n\nChat with meeting transcript: https://example.com";
+        let items = extract_items(md);
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].kind, ItemKind::Decision);
+        assert_eq!(items[0].text, "Keep the annual plan");
+        assert_eq!(items[1].kind, ItemKind::Action);
+        assert_eq!(items[1].section, "Action Items");
+        assert_eq!(items[2].text, "Schedule follow-up meeting");
+    }
+
+    #[test]
+    fn recognizes_owner_forms() {
+        let md = "### Next Steps\n\n- Alice: send the revised deck\n- **Bob Lee** – book the venue\n- @carol confirm budget\n- Update the roadmap (Dana)\n- Erin to draft the memo\n- We will circle back\n- Ship it by Friday";
+        assert_eq!(
+            owners_and_texts(md),
+            vec![
+                (Some("Alice".into()), "send the revised deck".into()),
+                (Some("Bob Lee".into()), "book the venue".into()),
+                (Some("carol".into()), "confirm budget".into()),
+                (Some("Dana".into()), "Update the roadmap".into()),
+                (Some("Erin".into()), "Erin to draft the memo".into()),
+                (None, "We will circle back".into()),
+                (None, "Ship it by Friday".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn items_grouped_under_a_name_take_it_as_owner() {
+        let md = "### Action Items\n\n- Alice\n  - Send the deck\n  - Book travel\n- Follow up with legal\n\n#### Bob\n\n- Draft the memo";
+        assert_eq!(
+            owners_and_texts(md),
+            vec![
+                (Some("Alice".into()), "Send the deck".into()),
+                (Some("Alice".into()), "Book travel".into()),
+                (None, "Follow up with legal".into()),
+                (Some("Bob".into()), "Draft the memo".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_detail_under_an_item_is_not_its_own_item() {
+        let md = "### Action Items\n\n1. Prepare the launch plan\n   - include the risks\n2. Book the room";
+        let texts: Vec<String> = extract_items(md).into_iter().map(|i| i.text).collect();
+        assert_eq!(texts, vec!["Prepare the launch plan", "Book the room"]);
+    }
+
+    #[test]
+    fn paragraph_lines_count_and_other_sections_end_the_list() {
+        let md = "### Next Steps\n\nPrepare quarterly report by Friday.\n\n### Background\n\n- Not an item";
+        let texts: Vec<String> = extract_items(md).into_iter().map(|i| i.text).collect();
+        assert_eq!(texts, vec!["Prepare quarterly report by Friday."]);
+    }
+
+    #[test]
+    fn owner_is_matches_name_first_name_or_email() {
+        let name = Some("Alice Smith");
+        let email = Some("alice.smith@example.com");
+        assert!(owner_is("Alice", name, email));
+        assert!(owner_is("alice smith", name, email));
+        assert!(owner_is("alice.smith", None, email));
+        assert!(owner_is("Bob and Alice", name, email));
+        assert!(!owner_is("Alicia", name, email));
+        assert!(!owner_is("Alice", None, None));
+    }
+
+    #[test]
+    fn section_kinds() {
+        assert_eq!(section_kind("Key Decisions"), Some(ItemKind::Decision));
+        assert_eq!(section_kind("**Next Steps:**"), Some(ItemKind::Action));
+        assert_eq!(section_kind("To-Dos"), Some(ItemKind::Action));
+        assert_eq!(section_kind("Summary"), None);
+    }
+}
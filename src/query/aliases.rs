@@ -0,0 +1,169 @@
+//! Heuristics for spotting two identities of the same person.
+//!
+//! Only used to suggest merges: nothing is linked until the user confirms
+//! with `grans people merge`. The rules are deliberately narrow (a shared
+//! first name alone is never enough) because a wrong suggestion costs a
+//! read, but a list full of them stops being read.
+
+/// Webmail domains, whose address stems say nothing about an organization.
+const WEBMAIL: &[&str] = &[
+    "gmail",
+    "googlemail",
+    "outlook",
+    "hotmail",
+    "live",
+    "yahoo",
+    "icloud",
+    "me",
+    "proton",
+    "protonmail",
+    "aol",
+];
+
+/// Shortest first-name prefix taken as a short form ("Dan" for "Daniel").
+const MIN_SHORT_FORM: usize = 3;
+
+/// Lowercased alphanumeric tokens of a name, in order.
+pub fn name_tokens(name: &str) -> Vec<String> {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Key under which names that differ only in case, punctuation and
+/// spacing compare equal.
+pub fn name_key(name: &str) -> String {
+    name_tokens(name).join(" ")
+}
+
+/// Whether two different-looking names plausibly belong to one person:
+/// the same words reordered ("Lee, Dana"), a last initial ("Dana L."), a
+/// short first name ("Dan Lee" and "Daniel Lee"), or a dropped middle name
+/// ("Dana M. Lee"). Names equal under [`name_key`] are not "similar"; a
+/// single word is never enough.
+pub fn similar_names(a: &str, b: &str) -> bool {
+    let (ta, tb) = (name_tokens(a), name_tokens(b));
+    if ta.len() < 2 || tb.len() < 2 || ta == tb {
+        return false;
+    }
+
+    let mut sa = ta.clone();
+    let mut sb = tb.clone();
+    sa.sort();
+    sb.sort();
+    if sa == sb {
+        return true;
+    }
+
+    let first_ok = ta[0] == tb[0] || short_form(&ta[0], &tb[0]);
+    let (la, lb) = (&ta[ta.len() - 1], &tb[tb.len() - 1]);
+    let last_ok = la == lb || initial_of(la, lb) || initial_of(lb, la);
+    first_ok && last_ok
+}
+
+/// Whether `short` is a short form of `long`, or the other way round.
+fn short_form(a: &str, b: &str) -> bool {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    short.len() >= MIN_SHORT_FORM && long.starts_with(short)
+}
+
+/// Whether `initial` is a single letter starting `word`.
+fn initial_of(initial: &str, word: &str) -> bool {
+    initial.chars().count() == 1 && word.len() > 1 && word.starts_with(initial)
+}
+
+/// Whether an email's local part is built from a name: "dana.lee",
+/// "dana_lee", "danalee", "dlee", "danal", "lee.dana", or "dana" for a
+/// one-word name.
+pub fn email_matches_name(email: &str, name: &str) -> bool {
+    let Some(local) = email.split('@').next() else {
+        return false;
+    };
+    let local: String = local
+        .to_lowercase()
+        .split('+')
+        .next()
+        .unwrap_or("")
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .collect();
+    let tokens = name_tokens(name);
+    match tokens.as_slice() {
+        [] => false,
+        [only] => local == *only,
+        [first, .., last] => {
+            let initial = |s: &str| s.chars().next().map(String::from).unwrap_or_default();
+            [
+                format!("{first}{last}"),
+                format!("{last}{first}"),
+                format!("{}{last}", initial(first)),
+                format!("{first}{}", initial(last)),
+            ]
+            .contains(&local)
+        }
+    }
+}
+
+/// The part of an email domain that names the organization: "acme" for
+/// both acme.com and mail.acme.io. `None` for webmail.
+pub fn org_stem(email: &str) -> Option<String> {
+    let domain = email.rsplit_once('@')?.1.to_lowercase();
+    let labels: Vec<&str> = domain.split('.').filter(|l| !l.is_empty()).collect();
+    // Skip a second-level suffix like the "co" of acme.co.uk.
+    let stem = match labels.as_slice() {
+        [.., stem, sld, _] if ["co", "com", "org", "net", "ac"].contains(sld) => *stem,
+        [.., stem, _] => *stem,
+        _ => return None,
+    };
+    (!WEBMAIL.contains(&stem)).then(|| stem.to_string())
+}
+
+/// The local part of an email, lowercased.
+pub fn local_part(email: &str) -> String {
+    email
+        .rsplit_once('@')
+        .map_or(email, |(local, _)| local)
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn similar_names_accepts_variants_of_one_name() {
+        assert!(similar_names("Dana Lee", "Lee, Dana"));
+        assert!(similar_names("Dana Lee", "Dana L."));
+        assert!(similar_names("Dan Lee", "Daniel Lee"));
+        assert!(similar_names("Dana M. Lee", "Dana Lee"));
+    }
+
+    #[test]
+    fn similar_names_rejects_different_people_and_equal_names() {
+        assert!(!similar_names("Priya Raman", "Priya Nair"));
+        assert!(!similar_names("Dana", "Dana Lee"));
+        assert!(!similar_names("Dana Lee", "dana lee"));
+        assert!(!similar_names("Al Lee", "Alan Lee"));
+    }
+
+    #[test]
+    fn email_matches_common_address_forms() {
+        assert!(email_matches_name("dana.lee@acme.com", "Dana Lee"));
+        assert!(email_matches_name("DLee@acme.com", "Dana Lee"));
+        assert!(email_matches_name("danal+work@acme.com", "Dana Lee"));
+        assert!(email_matches_name("dana@acme.com", "Dana"));
+        assert!(!email_matches_name("dana@acme.com", "Dana Lee"));
+        assert!(!email_matches_name("eli@acme.com", "Dana"));
+    }
+
+    #[test]
+    fn org_stem_ignores_tld_subdomains_and_webmail() {
+        assert_eq!(org_stem("a@acme.com").as_deref(), Some("acme"));
+        assert_eq!(org_stem("a@mail.acme.io").as_deref(), Some("acme"));
+        assert_eq!(org_stem("a@acme.co.uk").as_deref(), Some("acme"));
+        assert_eq!(org_stem("a@gmail.com"), None);
+        assert_eq!(org_stem("not-an-email"), None);
+    }
+}
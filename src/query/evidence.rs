@@ -1,7 +1,8 @@
 //! Lexical match evidence for one document: where in its panels, notes, and
 //! transcript the query terms actually occur.
 //!
-//! A site matches when it contains every query token (`matches_all_tokens`);
+//! A site matches when it satisfies the query the way an FTS row would
+//! (`Query::matches`);
 //! panels split into sections on the most frequent heading level, notes
 //! split into paragraphs, transcripts into utterances. Sites are collected
 //! in display priority order (AI-notes panels first as the most distilled
@@ -12,7 +13,8 @@ use anyhow::Result;
 use rusqlite::Connection;
 
 use crate::models::Document;
-use crate::query::fts::{FtsToken, matches_all_tokens};
+use crate::query::filter::SearchTarget;
+use crate::query::fts::Query;
 use crate::query::hybrid::BestChunk;
 use crate::query::shape::{
     ContextUnit, EvidenceSource, MatchEvidence, ShapedMeeting, Signals, excerpt_around_match,
@@ -78,13 +80,12 @@ struct Site {
 pub fn collect_document_evidence(
     conn: &Connection,
     doc: &Document,
-    tokens: &[FtsToken],
+    query: &Query,
     opts: &EvidenceOptions,
 ) -> Result<DocumentEvidence> {
-    // An empty query matches nothing, mirroring the FTS empty-phrase
-    // behavior; without this, matches_all_tokens is vacuously true and
-    // every site in the document would count as a match.
-    if tokens.is_empty() {
+    // An empty query matches nothing, mirroring the FTS side, which
+    // returns no meetings for it.
+    if query.is_empty() {
         return Ok(DocumentEvidence {
             matches: Vec::new(),
             total: 0,
@@ -97,12 +98,12 @@ pub fn collect_document_evidence(
     // A speaker filter restricts evidence to attributable transcript sites.
     if opts.speaker.is_none() {
         if let Some(doc_id) = doc.id.as_deref() {
-            collect_panel_sites(conn, doc_id, tokens, opts.context, &mut sites)?;
+            collect_panel_sites(conn, doc_id, query, opts.context, &mut sites)?;
         }
-        collect_notes_sites(doc.notes_plain.as_deref(), tokens, opts.context, &mut sites);
+        collect_notes_sites(doc.notes_plain.as_deref(), query, opts.context, &mut sites);
     }
     if let Some(doc_id) = doc.id.as_deref() {
-        collect_transcript_sites(conn, doc_id, tokens, opts, &mut sites)?;
+        collect_transcript_sites(conn, doc_id, query, opts, &mut sites)?;
     }
 
     // Context expansion shows the matched unit whole; the window cap only
@@ -113,6 +114,7 @@ pub fn collect_document_evidence(
         opts.max_chars
     };
 
+    let terms = query.highlight_terms();
     let total = sites.len();
     let mut matches = Vec::new();
     let mut remaining_sources = Vec::new();
@@ -120,7 +122,7 @@ pub fn collect_document_evidence(
         if i < opts.max_matches {
             matches.push(MatchEvidence {
                 source: site.source,
-                excerpt: excerpt_around_match(&site.text, tokens, width),
+                excerpt: excerpt_around_match(&site.text, &terms, width),
                 speaker: site.speaker,
                 speaker_name: site.speaker_name,
                 timestamp: site.timestamp,
@@ -159,18 +161,18 @@ pub struct RankingFacts<'a> {
 pub fn shape_meeting(
     conn: &Connection,
     doc: &Document,
-    tokens: &[FtsToken],
+    query: &Query,
     facts: &RankingFacts,
     opts: &EvidenceOptions,
 ) -> Result<ShapedMeeting> {
-    let evidence = collect_document_evidence(conn, doc, tokens, opts)?;
+    let evidence = collect_document_evidence(conn, doc, query, opts)?;
     let signals = Signals {
         keyword: facts.keyword,
         semantic: facts.best_chunk.is_some(),
         title: doc
             .title
             .as_deref()
-            .map(|t| title_matches(t, tokens))
+            .map(|t| title_matches(t, query))
             .unwrap_or(false),
     };
 
@@ -183,7 +185,7 @@ pub fn shape_meeting(
     } else if let Some(m) = facts
         .best_chunk
         .filter(|_| opts.speaker.is_none())
-        .and_then(|c| chunk_evidence(c, tokens, opts))
+        .and_then(|c| chunk_evidence(c, query, opts))
     {
         (vec![m], 1, Vec::new())
     } else {
@@ -205,7 +207,7 @@ pub fn shape_meeting(
 /// Tier-2 evidence: the semantic best chunk, excerpted like any other site.
 fn chunk_evidence(
     chunk: &BestChunk,
-    tokens: &[FtsToken],
+    query: &Query,
     opts: &EvidenceOptions,
 ) -> Option<MatchEvidence> {
     let source = match chunk.source_type.as_str() {
@@ -216,7 +218,7 @@ fn chunk_evidence(
     };
     Some(MatchEvidence {
         source,
-        excerpt: excerpt_around_match(&chunk.text, tokens, opts.max_chars),
+        excerpt: excerpt_around_match(&chunk.text, &query.highlight_terms(), opts.max_chars),
         speaker: None,
         speaker_name: None,
         timestamp: None,
@@ -244,12 +246,12 @@ fn neighbors_of<T>(
     (before, after)
 }
 
-/// Panel sections whose body contains every token, in panel order.
+/// Panel sections whose body matches the query, in panel order.
 /// Neighboring sections stay within the same panel.
 fn collect_panel_sites(
     conn: &Connection,
     doc_id: &str,
-    tokens: &[FtsToken],
+    query: &Query,
     context_size: usize,
     sites: &mut Vec<Site>,
 ) -> Result<()> {
@@ -260,7 +262,7 @@ fn collect_panel_sites(
         let cleaned = strip_panel_footer(markdown);
         let sections: Vec<(Option<&str>, &str)> = split_markdown_sections(cleaned);
         for (i, (heading, body)) in sections.iter().enumerate() {
-            if matches_all_tokens(body, tokens) {
+            if query.matches(body, SearchTarget::Panels) {
                 let (context_before, context_after) =
                     neighbors_of(&sections, i, context_size, |(h, b)| ContextUnit {
                         text: crate::query::shape::normalize_whitespace(b),
@@ -285,10 +287,10 @@ fn collect_panel_sites(
     Ok(())
 }
 
-/// Notes paragraphs that contain every token, in document order.
+/// Notes paragraphs that match the query, in document order.
 fn collect_notes_sites(
     notes_plain: Option<&str>,
-    tokens: &[FtsToken],
+    query: &Query,
     context_size: usize,
     sites: &mut Vec<Site>,
 ) {
@@ -297,7 +299,7 @@ fn collect_notes_sites(
     };
     let paras: Vec<&str> = split_into_paragraphs(notes);
     for (i, para) in paras.iter().enumerate() {
-        if matches_all_tokens(para, tokens) {
+        if query.matches(para, SearchTarget::Notes) {
             let (context_before, context_after) =
                 neighbors_of(&paras, i, context_size, |p| ContextUnit {
                     text: crate::query::shape::normalize_whitespace(p),
@@ -320,14 +322,14 @@ fn collect_notes_sites(
     }
 }
 
-/// Transcript utterances that contain every token, in time order. With a
+/// Transcript utterances that match the query, in time order. With a
 /// speaker filter, only that speaker's utterances count as matches, but
 /// context neighbors are unfiltered: the point of context is to read the
 /// conversation around the match.
 fn collect_transcript_sites(
     conn: &Connection,
     doc_id: &str,
-    tokens: &[FtsToken],
+    query: &Query,
     opts: &EvidenceOptions,
     sites: &mut Vec<Site>,
 ) -> Result<()> {
@@ -344,7 +346,7 @@ fn collect_transcript_sites(
             }
         }
         let text = utt.text.as_deref().unwrap_or_default();
-        if matches_all_tokens(text, tokens) {
+        if query.matches(text, SearchTarget::Transcripts) {
             let (context_before, context_after) =
                 neighbors_of(&utterances, i, opts.context, |u| ContextUnit {
                     text: crate::query::shape::normalize_whitespace(
@@ -426,7 +428,7 @@ mod tests {
             max_matches,
             ..Default::default()
         };
-        collect_document_evidence(conn, doc, &parse_query(query), &limits).unwrap()
+        collect_document_evidence(conn, doc, &parse_query(query).unwrap(), &limits).unwrap()
     }
 
     #[test]
@@ -554,7 +556,7 @@ mod tests {
         shape_meeting(
             conn,
             doc,
-            &parse_query(query),
+            &parse_query(query).unwrap(),
             facts,
             &EvidenceOptions::default(),
         )
@@ -652,7 +654,14 @@ mod tests {
             ..Default::default()
         };
 
-        let shaped = shape_meeting(&conn, &doc, &parse_query("kumquat"), &facts, &limits).unwrap();
+        let shaped = shape_meeting(
+            &conn,
+            &doc,
+            &parse_query("kumquat").unwrap(),
+            &facts,
+            &limits,
+        )
+        .unwrap();
 
         // Headers-only: no snippets, but the real lexical count and its
         // sources survive for the collapse line, and the semantic chunk
@@ -694,7 +703,7 @@ mod tests {
             context,
             ..Default::default()
         };
-        collect_document_evidence(conn, doc, &parse_query(query), &opts).unwrap()
+        collect_document_evidence(conn, doc, &parse_query(query).unwrap(), &opts).unwrap()
     }
 
     #[test]
@@ -794,7 +803,8 @@ mod tests {
             speaker: Some(crate::query::speaker::SpeakerFilter::Me),
             ..Default::default()
         };
-        let ev = collect_document_evidence(&conn, &doc, &parse_query("kumquat"), &opts).unwrap();
+        let ev = collect_document_evidence(&conn, &doc, &parse_query("kumquat").unwrap(), &opts)
+            .unwrap();
         // Only u1 (microphone) matches; its neighbor u2 is by the other
         // speaker and still appears as context.
         assert_eq!(ev.total, 1);
@@ -817,7 +827,8 @@ mod tests {
             speaker: Some(crate::query::speaker::SpeakerFilter::Me),
             ..Default::default()
         };
-        let ev = collect_document_evidence(&conn, &doc, &parse_query("kumquat"), &opts).unwrap();
+        let ev = collect_document_evidence(&conn, &doc, &parse_query("kumquat").unwrap(), &opts)
+            .unwrap();
         assert_eq!(ev.total, 1);
         assert_eq!(ev.matches[0].source, EvidenceSource::Transcript);
         assert_eq!(ev.matches[0].speaker.as_deref(), Some("microphone"));
@@ -832,7 +843,8 @@ mod tests {
             speaker: Some(SpeakerFilter::Names(vec!["Jane Doe".to_string()])),
             ..Default::default()
         };
-        let ev = collect_document_evidence(&conn, &doc, &parse_query("kumquat"), &opts).unwrap();
+        let ev = collect_document_evidence(&conn, &doc, &parse_query("kumquat").unwrap(), &opts)
+            .unwrap();
         assert_eq!(ev.total, 1);
         assert_eq!(ev.matches[0].speaker_name.as_deref(), Some("Jane Doe"));
         assert!(ev.matches[0].excerpt.text.contains("rollout"));
@@ -849,7 +861,8 @@ mod tests {
             speaker: Some(SpeakerFilter::Names(vec!["Marcus Webb".to_string()])),
             ..Default::default()
         };
-        let ev = collect_document_evidence(&conn, &doc, &parse_query("kumquat"), &opts).unwrap();
+        let ev = collect_document_evidence(&conn, &doc, &parse_query("kumquat").unwrap(), &opts)
+            .unwrap();
         assert_eq!(ev.total, 1);
         assert_eq!(ev.matches[0].speaker_name.as_deref(), Some("Marcus Webb"));
     }
@@ -901,7 +914,8 @@ mod tests {
             speaker: Some(crate::query::speaker::SpeakerFilter::Other),
             ..Default::default()
         };
-        let ev = collect_document_evidence(&conn, &doc, &parse_query("kumquat"), &opts).unwrap();
+        let ev = collect_document_evidence(&conn, &doc, &parse_query("kumquat").unwrap(), &opts)
+            .unwrap();
         // Only u3 ("Back to the kumquat question.", system); the panel and
         // notes sites have no speaker to attribute and do not count.
         assert_eq!(ev.total, 1);
@@ -927,7 +941,8 @@ mod tests {
             ..Default::default()
         };
 
-        let shaped = shape_meeting(&conn, &doc, &parse_query("rollout"), &facts, &opts).unwrap();
+        let shaped =
+            shape_meeting(&conn, &doc, &parse_query("rollout").unwrap(), &facts, &opts).unwrap();
 
         assert_eq!(shaped.total_matches, 0);
         assert!(shaped.matches.is_empty());
@@ -1,6 +1,9 @@
-use clap::ValueEnum;
+use std::collections::HashSet;
 
-use crate::models::Document;
+use anyhow::Result;
+use clap::ValueEnum;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
 
 /// The default `--in` target list for search and grep: every source.
 pub const DEFAULT_SEARCH_TARGETS: &str = "titles,transcripts,notes,panels";
@@ -10,8 +13,9 @@ pub const DEFAULT_SEARCH_TARGETS: &str = "titles,transcripts,notes,panels";
 /// Parsed directly by clap as a `ValueEnum`, so `--in` rejects unknown
 /// targets at parse time and names the valid ones, rather than silently
 /// dropping typos.
-#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[value(rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
 pub enum SearchTarget {
     Titles,
     Transcripts,
@@ -93,19 +97,50 @@ pub fn meeting_filter_matches(filter_lower: &str, title: Option<&str>, id: Optio
             .unwrap_or(false)
 }
 
-/// Keep only documents whose title or id contains `filter` (case-insensitive).
-/// No filter keeps everything.
-pub fn filter_by_meeting(results: Vec<Document>, meeting_filter: Option<&str>) -> Vec<Document> {
-    let Some(filter) = meeting_filter else {
-        return results;
-    };
-    let filter_lower = filter.to_lowercase();
-    results
-        .into_iter()
-        .filter(|doc| {
-            meeting_filter_matches(&filter_lower, doc.title.as_deref(), doc.id.as_deref())
-        })
-        .collect()
+/// Meeting-level filters shared by search and grep. Each set field narrows
+/// the candidate meetings; all of them must hold.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeetingFilters {
+    /// Title or id substring (`--meeting`).
+    pub meeting: Option<String>,
+    /// Attendee name or email fragment (`--with`).
+    pub with: Option<String>,
+    /// Company of an attendee, from the synced people directory (`--company`).
+    pub company: Option<String>,
+    /// Email of the account the meeting was synced from (`--account`).
+    pub account: Option<String>,
+    /// Recurring series, by normalized title (`--series`).
+    pub series: Option<String>,
+    /// Minimum number of distinct attendees (`--min-attendees`).
+    pub min_attendees: Option<usize>,
+}
+
+impl MeetingFilters {
+    /// True when no filter is set, so every meeting is allowed.
+    pub fn is_empty(&self) -> bool {
+        *self == MeetingFilters::default()
+    }
+}
+
+/// Ids of the meetings `filters` allows, or None when no filter is set.
+/// Deleted meetings are included: callers intersect with candidate lists
+/// that already honored include_deleted.
+pub fn allowed_meeting_ids(
+    conn: &Connection,
+    filters: &MeetingFilters,
+) -> Result<Option<HashSet<String>>> {
+    if filters.is_empty() {
+        return Ok(None);
+    }
+    let mut allowed = crate::db::meetings::filtered_meeting_refs(conn, filters)?;
+    if let Some(filter) = &filters.meeting {
+        let filter_lower = filter.to_lowercase();
+        allowed.retain(|(id, title)| {
+            meeting_filter_matches(&filter_lower, title.as_deref(), Some(id))
+        });
+    }
+    Ok(Some(allowed.into_iter().map(|(id, _)| id).collect()))
 }
 
 #[cfg(test)]
@@ -1,16 +1,45 @@
-//! Shared FTS5 query construction and term matching.
+//! The lexical query language: parsing, FTS5 MATCH construction, and
+//! Rust-side matching that mirrors it.
 //!
-//! A user query is parsed into tokens: bare words become individual terms
-//! (implicit AND), and double-quoted spans become phrases that must match
-//! contiguously. Every token is emitted double-quoted in the MATCH string,
-//! which keeps FTS5 operators (AND, OR, NOT, NEAR, `-`, `*`, `:`) literal.
+//! A query is a list of clauses, implicitly ANDed:
+//!
+//! - `budget` matches the word; `"status update"` the contiguous phrase;
+//!   `migrat*` (or `"status upd"*`) any word with that prefix.
+//! - `a OR b`, `a AND b` (the same as `a b`), and `( … )` grouping. AND
+//!   binds tighter than OR, as in FTS5: `a b OR c` is `(a b) OR c`.
+//! - `-term` or `NOT term` excludes. At the top level it drops every
+//!   meeting whose searched text mentions the term anywhere; inside a group,
+//!   `(budget -draft)`, it filters rows the way FTS5's `NOT` does.
+//! - `title:`, `transcript:`, `notes:`, `panel:` (or the plural `--in`
+//!   spellings) scope a top-level clause to one target, whatever `--in`
+//!   says: `title:roadmap`, `-title:draft`, `notes:(budget OR forecast)`.
+//! - `NEAR(a b "c d", N)` matches the terms within N words of each other
+//!   (10 when omitted).
+//!
+//! Operators are uppercase; lowercase `or`, `and`, `not`, `near` and
+//! unknown `word:` prefixes stay literal words. Everything else is emitted
+//! double-quoted in the MATCH string, so FTS5 syntax characters inside
+//! words stay literal.
+//!
+//! Unscoped positive clauses must all match one row (an utterance, a notes
+//! body, a panel, a title), as before; each scoped clause must match some
+//! row of its own target. [`Query::matches`] applies the same test to a
+//! single piece of display text, which is how match evidence is located.
+
+use anyhow::{Result, bail};
 
-/// A parsed unit of a user search query.
+use crate::query::filter::SearchTarget;
+use crate::query::text::contains_ignore_case;
+
+/// NEAR distance when none is given, FTS5's own default.
+pub const DEFAULT_NEAR_DISTANCE: usize = 10;
+
+/// A highlightable unit of a query: the text excerpts emphasize.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FtsToken {
-    /// A single bare word; matches anywhere in the row.
+    /// A single word, or the stem of a prefix term.
     Term(String),
-    /// A user-quoted span; must match contiguously.
+    /// A user-quoted span.
     Phrase(String),
 }
 
@@ -23,252 +52,852 @@ impl FtsToken {
     }
 }
 
-/// Parse a raw user query into terms and quoted phrases.
+/// A boolean expression over words, evaluated against one row of text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Term(String),
+    /// A word or phrase whose last word may continue (`migrat*`).
+    Prefix(String),
+    Phrase(String),
+    /// Every item within `distance` words of the others.
+    Near {
+        items: Vec<Expr>,
+        distance: usize,
+    },
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    /// Only valid beside a positive sibling in an `And`.
+    Not(Box<Expr>),
+}
+
+impl Expr {
+    /// True when `text` satisfies the expression, case-insensitively.
+    /// Terms and phrases match as substrings; NEAR counts words.
+    pub fn matches(&self, text: &str) -> bool {
+        match self {
+            Expr::Term(s) | Expr::Prefix(s) | Expr::Phrase(s) => contains_ignore_case(text, s),
+            Expr::Near { items, distance } => near_matches(text, items, *distance),
+            Expr::And(items) => items.iter().all(|e| e.matches(text)),
+            Expr::Or(items) => items.iter().any(|e| e.matches(text)),
+            Expr::Not(inner) => !inner.matches(text),
+        }
+    }
+
+    /// The FTS5 MATCH string for this expression. Fails for negations
+    /// FTS5 cannot express: a `NOT` needs a positive left-hand side.
+    fn to_fts(&self) -> Result<String> {
+        Ok(match self {
+            Expr::Term(s) | Expr::Phrase(s) => quote(s),
+            Expr::Prefix(s) => format!("{}*", quote(s)),
+            Expr::Near { items, distance } => {
+                let items = items.iter().map(Expr::to_fts).collect::<Result<Vec<_>>>()?;
+                format!("NEAR({}, {})", items.join(" "), distance)
+            }
+            Expr::Or(items) => {
+                if items.iter().any(|e| matches!(e, Expr::Not(_))) {
+                    bail!(
+                        "An excluded term needs something to exclude it from: \
+                         write `a -b`, not `a OR -b`"
+                    );
+                }
+                let items = items.iter().map(Expr::to_fts).collect::<Result<Vec<_>>>()?;
+                format!("({})", items.join(" OR "))
+            }
+            Expr::And(items) => {
+                let (negated, positive): (Vec<&Expr>, Vec<&Expr>) =
+                    items.iter().partition(|e| matches!(e, Expr::Not(_)));
+                if positive.is_empty() {
+                    bail!("A group of only excluded terms matches nothing; add a term to match");
+                }
+                let positive = positive
+                    .into_iter()
+                    .map(Expr::to_fts)
+                    .collect::<Result<Vec<_>>>()?;
+                let mut fts = if positive.len() == 1 {
+                    positive.into_iter().next().unwrap_or_default()
+                } else {
+                    format!("({})", positive.join(" AND "))
+                };
+                for not in negated {
+                    if let Expr::Not(inner) = not {
+                        fts = format!("({} NOT {})", fts, inner.to_fts()?);
+                    }
+                }
+                fts
+            }
+            Expr::Not(_) => {
+                bail!("An excluded term needs something to exclude it from, like `budget -draft`")
+            }
+        })
+    }
+
+    /// Positive leaves, for highlighting.
+    fn collect_terms(&self, out: &mut Vec<FtsToken>) {
+        match self {
+            Expr::Term(s) | Expr::Prefix(s) => out.push(FtsToken::Term(s.clone())),
+            Expr::Phrase(s) => out.push(FtsToken::Phrase(s.clone())),
+            Expr::Near { items, .. } | Expr::And(items) | Expr::Or(items) => {
+                items.iter().for_each(|e| e.collect_terms(out))
+            }
+            Expr::Not(_) => {}
+        }
+    }
+}
+
+/// One expression with its compiled MATCH string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Clause {
+    expr: Expr,
+    fts: String,
+}
+
+impl Clause {
+    fn new(expr: Expr) -> Result<Self> {
+        let fts = expr.to_fts()?;
+        Ok(Self { expr, fts })
+    }
+}
+
+/// A parsed lexical query. See the module docs for the language.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Query {
+    /// What one row of a searched target must match, when any unscoped
+    /// positive clause was given.
+    content: Option<Clause>,
+    /// What one row of a given target must match, per `field:` clause.
+    scoped: Vec<(SearchTarget, Clause)>,
+    /// Meetings with a row matching one of these are dropped. `None` means
+    /// every searched target.
+    excluded: Vec<(Option<SearchTarget>, Clause)>,
+}
+
+impl Query {
+    /// True when nothing is required, so nothing can match.
+    pub fn is_empty(&self) -> bool {
+        self.content.is_none() && self.scoped.is_empty()
+    }
+
+    /// What a meeting must match, one entry per requirement: the
+    /// `(target, MATCH string)` alternatives any of which satisfies it. A
+    /// requirement with no alternatives (unscoped clauses with no targets
+    /// searched) cannot be met.
+    pub fn requirements(&self, targets: &[SearchTarget]) -> Vec<Vec<(SearchTarget, &str)>> {
+        let mut reqs = Vec::new();
+        if let Some(content) = &self.content {
+            reqs.push(targets.iter().map(|t| (*t, content.fts.as_str())).collect());
+        }
+        for (target, clause) in &self.scoped {
+            reqs.push(vec![(*target, clause.fts.as_str())]);
+        }
+        reqs
+    }
+
+    /// `(target, MATCH string)` pairs a meeting must not match.
+    pub fn exclusions(&self, targets: &[SearchTarget]) -> Vec<(SearchTarget, &str)> {
+        let mut out = Vec::new();
+        for (scope, clause) in &self.excluded {
+            match scope {
+                Some(target) => out.push((*target, clause.fts.as_str())),
+                None => out.extend(targets.iter().map(|t| (*t, clause.fts.as_str()))),
+            }
+        }
+        out
+    }
+
+    /// True when `text`, a row of `target`, satisfies one of the query's
+    /// requirements: the unscoped clauses, or a clause scoped to `target`.
+    /// Exclusions are meeting-level and not consulted.
+    pub fn matches(&self, text: &str, target: SearchTarget) -> bool {
+        self.content.as_ref().is_some_and(|c| c.expr.matches(text))
+            || self
+                .scoped
+                .iter()
+                .any(|(t, c)| *t == target && c.expr.matches(text))
+    }
+
+    /// Every positive term and phrase, for highlighting.
+    pub fn highlight_terms(&self) -> Vec<FtsToken> {
+        let mut out = Vec::new();
+        if let Some(content) = &self.content {
+            content.expr.collect_terms(&mut out);
+        }
+        for (_, clause) in &self.scoped {
+            clause.expr.collect_terms(&mut out);
+        }
+        out
+    }
+}
+
+/// Parse a raw user query.
 ///
-/// A `"` starts a phrase (ending any bare word in progress); the phrase runs
-/// to the next `"` or the end of the string, so unbalanced quotes are treated
-/// as if closed at the end. Empty or whitespace-only phrases are dropped.
-pub fn parse_query(query: &str) -> Vec<FtsToken> {
-    let mut tokens = Vec::new();
-    let mut current = String::new();
-    let mut in_phrase = false;
-
-    let flush = |buf: &mut String, in_phrase: bool, tokens: &mut Vec<FtsToken>| {
-        if !buf.trim().is_empty() {
-            let text = std::mem::take(buf);
-            tokens.push(if in_phrase {
-                FtsToken::Phrase(text)
-            } else {
-                FtsToken::Term(text)
-            });
+/// Quotes and parentheses are forgiving: an unclosed `"` or `(` runs to
+/// the end of the query and a stray `)` is ignored. Errors are reserved for
+/// queries that cannot be searched as written, such as one that only
+/// excludes or a `title:` scope inside `OR`.
+pub fn parse_query(query: &str) -> Result<Query> {
+    let mut parser = Parser {
+        chars: query.chars().collect(),
+        pos: 0,
+        depth: 0,
+    };
+    let node = parser.parse_or()?;
+    lower_query(node)
+}
+
+/// Parse tree, before scopes are lifted to the top level.
+#[derive(Debug)]
+enum Node {
+    Leaf(Expr),
+    And(Vec<Node>),
+    Or(Vec<Node>),
+    Not(Box<Node>),
+    Scoped(SearchTarget, Box<Node>),
+    /// Parenthesized, so never split into top-level clauses.
+    Group(Box<Node>),
+}
+
+fn lower_query(node: Option<Node>) -> Result<Query> {
+    let clauses = match node {
+        None => return Ok(Query::default()),
+        Some(Node::And(items)) => items,
+        Some(other) => vec![other],
+    };
+
+    let mut content = Vec::new();
+    let mut query = Query::default();
+    for clause in clauses {
+        match clause {
+            Node::Not(inner) => match *inner {
+                Node::Scoped(target, inner) => {
+                    query
+                        .excluded
+                        .push((Some(target), Clause::new(lower(*inner)?)?));
+                }
+                other => query.excluded.push((None, Clause::new(lower(other)?)?)),
+            },
+            Node::Scoped(target, inner) => match *inner {
+                Node::Not(inner) => {
+                    query
+                        .excluded
+                        .push((Some(target), Clause::new(lower(*inner)?)?));
+                }
+                inner => query.scoped.push((target, Clause::new(lower(inner)?)?)),
+            },
+            other => content.push(lower(other)?),
+        }
+    }
+    if !content.is_empty() {
+        let expr = if content.len() == 1 {
+            content.remove(0)
         } else {
-            buf.clear();
+            Expr::And(content)
+        };
+        query.content = Some(Clause::new(expr)?);
+    }
+    if query.is_empty() && !query.excluded.is_empty() {
+        bail!("A query needs at least one term to match; `-term` only excludes");
+    }
+    Ok(query)
+}
+
+fn lower(node: Node) -> Result<Expr> {
+    Ok(match node {
+        Node::Leaf(expr) => expr,
+        Node::And(items) => Expr::And(items.into_iter().map(lower).collect::<Result<_>>()?),
+        Node::Or(items) => Expr::Or(items.into_iter().map(lower).collect::<Result<_>>()?),
+        Node::Not(inner) => Expr::Not(Box::new(lower(*inner)?)),
+        Node::Group(inner) => lower(*inner)?,
+        Node::Scoped(target, _) => bail!(
+            "`{}:` scopes a whole top-level clause; it cannot sit inside OR, NOT \
+             or parentheses (write `{}:(a OR b)` instead)",
+            target.as_str(),
+            target.as_str()
+        ),
+    })
+}
+
+/// Recursive-descent parser over the raw query characters.
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    /// Open parentheses; a `)` at depth 0 is stray and skipped.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
         }
-    };
+    }
 
-    for ch in query.chars() {
-        match ch {
-            '"' => {
-                flush(&mut current, in_phrase, &mut tokens);
-                in_phrase = !in_phrase;
+    /// The bare word at the cursor, without consuming it.
+    fn peek_word(&self) -> String {
+        self.chars[self.pos..]
+            .iter()
+            .take_while(|&&c| !ends_word(c, false))
+            .collect()
+    }
+
+    /// True when the cursor is at the standalone operator `op`.
+    fn at_operator(&self, op: &str) -> bool {
+        self.peek_word() == op
+    }
+
+    fn parse_or(&mut self) -> Result<Option<Node>> {
+        let mut alternatives = Vec::new();
+        loop {
+            if let Some(node) = self.parse_and()? {
+                alternatives.push(node);
+            }
+            self.skip_whitespace();
+            if self.at_operator("OR") {
+                self.pos += 2;
+                continue;
+            }
+            break;
+        }
+        Ok(match alternatives.len() {
+            0 => None,
+            1 => alternatives.pop(),
+            _ => Some(Node::Or(alternatives)),
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Option<Node>> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => break,
+                Some(')') if self.depth > 0 => break,
+                Some(')') => {
+                    self.pos += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            if self.at_operator("OR") {
+                break;
+            }
+            if self.at_operator("AND") {
+                self.pos += 3;
+                continue;
+            }
+            if let Some(node) = self.parse_unary()? {
+                items.push(node);
+            }
+        }
+        Ok(match items.len() {
+            0 => None,
+            1 => items.pop(),
+            _ => Some(Node::And(items)),
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Option<Node>> {
+        let negated = if self.at_operator("NOT") {
+            self.pos += 3;
+            self.skip_whitespace();
+            true
+        } else if self.peek() == Some('-')
+            && self
+                .chars
+                .get(self.pos + 1)
+                .is_some_and(|c| !c.is_whitespace() && *c != ')' && *c != '-')
+        {
+            self.pos += 1;
+            true
+        } else {
+            false
+        };
+        if !negated {
+            return self.parse_primary();
+        }
+        Ok(self.parse_unary()?.map(|node| Node::Not(Box::new(node))))
+    }
+
+    fn parse_primary(&mut self) -> Result<Option<Node>> {
+        match self.peek() {
+            None | Some(')') => Ok(None),
+            Some('(') => {
+                self.pos += 1;
+                self.depth += 1;
+                let node = self.parse_or()?;
+                if self.peek() == Some(')') {
+                    self.pos += 1;
+                }
+                self.depth -= 1;
+                Ok(node.map(|n| Node::Group(Box::new(n))))
             }
-            c if c.is_whitespace() && !in_phrase => {
-                flush(&mut current, false, &mut tokens);
+            Some('"') => Ok(self.parse_phrase().map(Node::Leaf)),
+            _ => {
+                if self.chars[self.pos..].starts_with(&['N', 'E', 'A', 'R', '(']) {
+                    return self.parse_near().map(|e| Some(Node::Leaf(e)));
+                }
+                if let Some(target) = self.parse_scope() {
+                    return Ok(self
+                        .parse_primary()?
+                        .map(|node| Node::Scoped(target, Box::new(node))));
+                }
+                Ok(self.parse_word(false).map(Node::Leaf))
             }
-            c => current.push(c),
         }
     }
-    flush(&mut current, in_phrase, &mut tokens);
 
-    tokens
+    /// A `field:` prefix naming a search target, consumed when followed by
+    /// something to scope. Other `word:` prefixes are left as text.
+    fn parse_scope(&mut self) -> Option<SearchTarget> {
+        let name: String = self.chars[self.pos..]
+            .iter()
+            .take_while(|c| c.is_ascii_alphabetic())
+            .collect();
+        let colon = self.pos + name.chars().count();
+        if self.chars.get(colon) != Some(&':') {
+            return None;
+        }
+        let scoped = self
+            .chars
+            .get(colon + 1)
+            .is_some_and(|c| !c.is_whitespace() && *c != ')');
+        let target = scope_target(&name).filter(|_| scoped)?;
+        self.pos = colon + 1;
+        Some(target)
+    }
+
+    /// A quoted phrase, optionally followed by `*`. The phrase runs to the
+    /// next `"` or the end of the query; an empty one yields nothing.
+    fn parse_phrase(&mut self) -> Option<Expr> {
+        self.pos += 1;
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c != '"') {
+            self.pos += 1;
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        if self.peek() == Some('"') {
+            self.pos += 1;
+        }
+        let prefix = self.peek() == Some('*');
+        if prefix {
+            self.pos += 1;
+        }
+        if text.trim().is_empty() {
+            return None;
+        }
+        Some(if prefix {
+            Expr::Prefix(text)
+        } else {
+            Expr::Phrase(text)
+        })
+    }
+
+    /// A bare word; a trailing `*` makes it a prefix.
+    fn parse_word(&mut self, in_near: bool) -> Option<Expr> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| !ends_word(c, in_near)) {
+            self.pos += 1;
+        }
+        let word: String = self.chars[start..self.pos].iter().collect();
+        if word.is_empty() {
+            // A lone delimiter the grammar has no use for; step over it.
+            self.pos = (self.pos + 1).min(self.chars.len());
+            return None;
+        }
+        match word.strip_suffix('*') {
+            Some(stem) if stem.chars().any(char::is_alphanumeric) => {
+                Some(Expr::Prefix(stem.to_string()))
+            }
+            _ => Some(Expr::Term(word)),
+        }
+    }
+
+    /// `NEAR(item item …[, distance])`, with the cursor on `NEAR(`.
+    fn parse_near(&mut self) -> Result<Expr> {
+        self.pos += 5;
+        let mut items = Vec::new();
+        let mut distance = DEFAULT_NEAR_DISTANCE;
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => break,
+                Some(')') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(',') => {
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    let digits: String = self.chars[self.pos..]
+                        .iter()
+                        .take_while(|c| c.is_ascii_digit())
+                        .collect();
+                    self.pos += digits.len();
+                    self.skip_whitespace();
+                    distance = match digits.parse() {
+                        Ok(n) if matches!(self.peek(), Some(')') | None) => n,
+                        _ => bail!("NEAR takes a word distance after the comma, like NEAR(a b, 5)"),
+                    };
+                }
+                Some('"') => items.extend(self.parse_phrase()),
+                Some('(') => bail!("NEAR groups words and phrases only, like NEAR(a \"b c\", 5)"),
+                _ => items.extend(self.parse_word(true)),
+            }
+        }
+        if items.len() < 2 {
+            bail!("NEAR needs at least two words or phrases, like NEAR(budget forecast, 5)");
+        }
+        Ok(Expr::Near { items, distance })
+    }
 }
 
-/// Build an FTS5 MATCH expression from a user query.
-///
-/// Each token is double-quoted so FTS5 treats it literally; whitespace-joined
-/// quoted tokens give implicit-AND semantics. An empty query yields `""`
-/// (an empty phrase), which matches nothing.
-pub fn sanitize_fts_query(query: &str) -> String {
-    let tokens = parse_query(query);
-    if tokens.is_empty() {
-        return "\"\"".to_string();
-    }
-    tokens
-        .iter()
-        .map(|t| format!("\"{}\"", t.text()))
-        .collect::<Vec<_>>()
-        .join(" ")
+/// Characters that end a bare word. Commas only delimit inside NEAR, so
+/// `1,000` stays one word elsewhere.
+fn ends_word(c: char, in_near: bool) -> bool {
+    c.is_whitespace() || matches!(c, '(' | ')' | '"') || (in_near && c == ',')
 }
 
-/// True when `text` contains every token, case-insensitively.
-///
-/// Mirrors the per-row semantics of the MATCH expression built by
-/// `sanitize_fts_query`, for filtering display text (e.g. context windows).
-/// An empty token list is vacuously true.
-pub fn matches_all_tokens(text: &str, tokens: &[FtsToken]) -> bool {
-    tokens
-        .iter()
-        .all(|t| crate::query::text::contains_ignore_case(text, t.text()))
+/// The search target a `field:` prefix names.
+fn scope_target(name: &str) -> Option<SearchTarget> {
+    match name.to_ascii_lowercase().as_str() {
+        "title" | "titles" => Some(SearchTarget::Titles),
+        "transcript" | "transcripts" => Some(SearchTarget::Transcripts),
+        "note" | "notes" => Some(SearchTarget::Notes),
+        "panel" | "panels" => Some(SearchTarget::Panels),
+        _ => None,
+    }
+}
+
+/// Double-quote `s` as an FTS5 string, so it is matched literally.
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('"', "\"\""))
+}
+
+/// Lowercased words, split the way FTS5's default tokenizer splits them.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// True when every NEAR item occurs in `text` with at most `distance`
+/// words between the first and the last, as FTS5 counts them.
+fn near_matches(text: &str, items: &[Expr], distance: usize) -> bool {
+    let text_words = words(text);
+    let mut occurrences: Vec<Vec<(usize, usize)>> = Vec::with_capacity(items.len());
+    for item in items {
+        let (pattern, prefix) = match item {
+            Expr::Term(s) | Expr::Phrase(s) => (words(s), false),
+            Expr::Prefix(s) => (words(s), true),
+            _ => return false,
+        };
+        if pattern.is_empty() {
+            return false;
+        }
+        let found: Vec<(usize, usize)> = (0..text_words.len())
+            .filter(|&i| words_match_at(&text_words, i, &pattern, prefix))
+            .map(|i| (i, pattern.len()))
+            .collect();
+        if found.is_empty() {
+            return false;
+        }
+        occurrences.push(found);
+    }
+
+    // The tightest span starting at each occurrence takes every other
+    // item's first occurrence at or after it.
+    let item_words: usize = occurrences.iter().map(|o| o[0].1).sum();
+    occurrences.iter().flatten().any(|&(start, _)| {
+        let mut end = start;
+        for found in &occurrences {
+            match found.iter().find(|(s, _)| *s >= start) {
+                Some(&(s, len)) => end = end.max(s + len),
+                None => return false,
+            }
+        }
+        end - start <= item_words + distance
+    })
+}
+
+fn words_match_at(text: &[String], i: usize, pattern: &[String], prefix: bool) -> bool {
+    i + pattern.len() <= text.len()
+        && pattern.iter().enumerate().all(|(k, p)| {
+            if prefix && k == pattern.len() - 1 {
+                text[i + k].starts_with(p.as_str())
+            } else {
+                text[i + k] == *p
+            }
+        })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    // --- parse_query ---
+    fn content_fts(query: &str) -> String {
+        parse_query(query)
+            .unwrap()
+            .content
+            .map(|c| c.fts)
+            .unwrap_or_default()
+    }
+
+    fn matches(query: &str, text: &str) -> bool {
+        parse_query(query)
+            .unwrap()
+            .matches(text, SearchTarget::Transcripts)
+    }
+
+    // --- parsing and MATCH construction ---
 
     #[test]
-    fn parse_single_word() {
-        assert_eq!(parse_query("hello"), vec![FtsToken::Term("hello".into())]);
+    fn single_word_is_quoted() {
+        assert_eq!(content_fts("hello"), "\"hello\"");
     }
 
     #[test]
-    fn parse_multiple_words() {
+    fn words_are_implicitly_anded() {
         assert_eq!(
-            parse_query("resource allocation"),
-            vec![
-                FtsToken::Term("resource".into()),
-                FtsToken::Term("allocation".into())
-            ]
+            content_fts("resource allocation"),
+            "(\"resource\" AND \"allocation\")"
+        );
+        assert_eq!(
+            content_fts("resource AND allocation"),
+            content_fts("resource allocation")
         );
     }
 
     #[test]
-    fn parse_quoted_phrase() {
+    fn quoted_spans_stay_phrases() {
         assert_eq!(
-            parse_query("\"resource allocation\""),
-            vec![FtsToken::Phrase("resource allocation".into())]
+            content_fts("budget \"status update\""),
+            "(\"budget\" AND \"status update\")"
         );
     }
 
     #[test]
-    fn parse_phrase_and_terms_mixed() {
-        assert_eq!(
-            parse_query("budget \"status update\" quarterly"),
-            vec![
-                FtsToken::Term("budget".into()),
-                FtsToken::Phrase("status update".into()),
-                FtsToken::Term("quarterly".into())
-            ]
-        );
+    fn quote_attached_to_word_splits_it() {
+        assert_eq!(content_fts("foo\"bar baz\""), "(\"foo\" AND \"bar baz\")");
     }
 
     #[test]
-    fn parse_unbalanced_quote_runs_to_end() {
-        assert_eq!(
-            parse_query("foo \"bar baz"),
-            vec![
-                FtsToken::Term("foo".into()),
-                FtsToken::Phrase("bar baz".into())
-            ]
-        );
+    fn unbalanced_quote_runs_to_end() {
+        assert_eq!(content_fts("foo \"bar baz"), "(\"foo\" AND \"bar baz\")");
     }
 
     #[test]
-    fn parse_quote_attached_to_word() {
-        assert_eq!(
-            parse_query("foo\"bar baz\""),
-            vec![
-                FtsToken::Term("foo".into()),
-                FtsToken::Phrase("bar baz".into())
-            ]
+    fn empty_phrases_are_dropped() {
+        assert_eq!(content_fts("foo \"\" bar"), "(\"foo\" AND \"bar\")");
+        assert!(parse_query("\"   \"").unwrap().is_empty());
+    }
+
+    #[test]
+    fn empty_query_requires_nothing() {
+        assert!(parse_query("").unwrap().is_empty());
+        assert!(parse_query("   ").unwrap().is_empty());
+        assert!(
+            parse_query("")
+                .unwrap()
+                .requirements(&SearchTarget::all())
+                .is_empty()
         );
     }
 
     #[test]
-    fn parse_empty_phrase_dropped() {
+    fn or_binds_looser_than_and() {
+        assert_eq!(
+            content_fts("budget OR forecast"),
+            "(\"budget\" OR \"forecast\")"
+        );
         assert_eq!(
-            parse_query("foo \"\" bar"),
-            vec![FtsToken::Term("foo".into()), FtsToken::Term("bar".into())]
+            content_fts("q3 budget OR forecast"),
+            "((\"q3\" AND \"budget\") OR \"forecast\")"
+        );
+        assert_eq!(
+            content_fts("q3 (budget OR forecast)"),
+            "(\"q3\" AND (\"budget\" OR \"forecast\"))"
         );
     }
 
     #[test]
-    fn parse_whitespace_only_phrase_dropped() {
-        assert_eq!(parse_query("\"   \""), Vec::<FtsToken>::new());
+    fn lowercase_operators_and_syntax_inside_words_stay_literal() {
+        assert_eq!(
+            content_fts("cats or dogs"),
+            "(\"cats\" AND \"or\" AND \"dogs\")"
+        );
+        assert_eq!(content_fts("col:value"), "\"col:value\"");
+        assert_eq!(content_fts("covid-19 v2.0"), "(\"covid-19\" AND \"v2.0\")");
+        assert_eq!(content_fts("10:30"), "\"10:30\"");
     }
 
     #[test]
-    fn parse_empty_query() {
-        assert_eq!(parse_query(""), Vec::<FtsToken>::new());
-        assert_eq!(parse_query("   "), Vec::<FtsToken>::new());
+    fn trailing_star_is_a_prefix() {
+        assert_eq!(content_fts("migrat*"), "\"migrat\"*");
+        assert_eq!(content_fts("\"status upd\"*"), "\"status upd\"*");
+        assert_eq!(content_fts("*"), "\"*\"");
     }
 
     #[test]
-    fn parse_keeps_punctuation_in_terms() {
+    fn near_takes_an_optional_distance() {
         assert_eq!(
-            parse_query("covid-19 v2.0"),
-            vec![
-                FtsToken::Term("covid-19".into()),
-                FtsToken::Term("v2.0".into())
-            ]
+            content_fts("NEAR(budget \"q3 forecast\", 5)"),
+            "NEAR(\"budget\" \"q3 forecast\", 5)"
         );
+        assert_eq!(
+            content_fts("NEAR(budget forecast)"),
+            "NEAR(\"budget\" \"forecast\", 10)"
+        );
+        assert!(parse_query("NEAR(budget)").is_err());
+        assert!(parse_query("NEAR(budget forecast, soon)").is_err());
     }
 
-    // --- sanitize_fts_query ---
-
     #[test]
-    fn sanitize_single_word_is_quoted() {
-        assert_eq!(sanitize_fts_query("hello"), "\"hello\"");
+    fn grouped_negation_filters_rows() {
+        assert_eq!(content_fts("(budget -draft)"), "(\"budget\" NOT \"draft\")");
+        assert!(parse_query("(budget OR -draft)").is_err());
     }
 
     #[test]
-    fn sanitize_multi_word_quotes_each_term() {
-        // Implicit AND: each word quoted separately, not one phrase.
+    fn top_level_negation_excludes_meetings() {
+        let query = parse_query("budget -draft NOT \"old plan\"").unwrap();
+        assert_eq!(query.content.as_ref().unwrap().fts, "\"budget\"");
+        let targets = [SearchTarget::Notes];
         assert_eq!(
-            sanitize_fts_query("resource allocation"),
-            "\"resource\" \"allocation\""
+            query.exclusions(&targets),
+            vec![
+                (SearchTarget::Notes, "\"draft\""),
+                (SearchTarget::Notes, "\"old plan\"")
+            ]
         );
+        assert!(parse_query("-draft").is_err());
     }
 
     #[test]
-    fn sanitize_preserves_user_phrases() {
+    fn field_scopes_become_their_own_requirements() {
+        let query = parse_query("budget title:roadmap -title:draft notes:(a OR b)").unwrap();
+        let reqs = query.requirements(&[SearchTarget::Transcripts]);
+        assert_eq!(
+            reqs,
+            vec![
+                vec![(SearchTarget::Transcripts, "\"budget\"")],
+                vec![(SearchTarget::Titles, "\"roadmap\"")],
+                vec![(SearchTarget::Notes, "(\"a\" OR \"b\")")],
+            ]
+        );
         assert_eq!(
-            sanitize_fts_query("budget \"status update\""),
-            "\"budget\" \"status update\""
+            query.exclusions(&[SearchTarget::Transcripts]),
+            vec![(SearchTarget::Titles, "\"draft\"")]
         );
     }
 
     #[test]
-    fn sanitize_neutralizes_fts_operators() {
+    fn scopes_accept_phrases_prefixes_and_plurals() {
+        let query = parse_query("Titles:\"road map\" transcript:migrat*").unwrap();
+        assert!(query.content.is_none());
         assert_eq!(
-            sanitize_fts_query("cats OR dogs"),
-            "\"cats\" \"OR\" \"dogs\""
+            query.requirements(&[]),
+            vec![
+                vec![(SearchTarget::Titles, "\"road map\"")],
+                vec![(SearchTarget::Transcripts, "\"migrat\"*")],
+            ]
         );
-        assert_eq!(sanitize_fts_query("foo NOT bar"), "\"foo\" \"NOT\" \"bar\"");
-        assert_eq!(sanitize_fts_query("col:value"), "\"col:value\"");
-        assert_eq!(sanitize_fts_query("wild*"), "\"wild*\"");
     }
 
     #[test]
-    fn sanitize_empty_query_matches_nothing() {
-        // Preserves the pre-existing behavior for empty input: an empty
-        // phrase, which no row matches.
-        assert_eq!(sanitize_fts_query(""), "\"\"");
+    fn scopes_must_be_top_level() {
+        assert!(parse_query("title:roadmap OR budget").is_err());
+        assert!(parse_query("(title:roadmap budget)").is_err());
+    }
+
+    #[test]
+    fn stray_parentheses_are_forgiven() {
+        assert_eq!(content_fts("budget)"), "\"budget\"");
+        assert_eq!(
+            content_fts("(budget OR forecast"),
+            "(\"budget\" OR \"forecast\")"
+        );
     }
 
-    // --- matches_all_tokens ---
+    // --- Rust-side matching ---
 
     #[test]
     fn matches_all_terms_any_order() {
-        let tokens = parse_query("allocation resource");
-        assert!(matches_all_tokens(
-            "We discussed resource allocation today",
-            &tokens
+        assert!(matches(
+            "allocation resource",
+            "We discussed resource allocation today"
         ));
     }
 
     #[test]
     fn matches_is_case_insensitive() {
-        let tokens = parse_query("RESOURCE");
-        assert!(matches_all_tokens("resource allocation", &tokens));
+        assert!(matches("RESOURCE", "resource allocation"));
     }
 
     #[test]
     fn missing_term_fails_match() {
-        let tokens = parse_query("resource headcount");
-        assert!(!matches_all_tokens(
-            "We discussed resource allocation today",
-            &tokens
+        assert!(!matches(
+            "resource headcount",
+            "We discussed resource allocation today"
         ));
     }
 
     #[test]
     fn phrase_must_be_contiguous() {
-        let tokens = parse_query("\"allocation resource\"");
-        assert!(!matches_all_tokens(
-            "We discussed resource allocation today",
-            &tokens
+        assert!(!matches(
+            "\"allocation resource\"",
+            "We discussed resource allocation today"
         ));
-        let tokens = parse_query("\"resource allocation\"");
-        assert!(matches_all_tokens(
-            "We discussed resource allocation today",
-            &tokens
+        assert!(matches(
+            "\"resource allocation\"",
+            "We discussed resource allocation today"
         ));
     }
 
     #[test]
-    fn empty_tokens_match_vacuously() {
-        assert!(matches_all_tokens("anything", &[]));
+    fn boolean_operators_match_like_fts() {
+        assert!(matches("budget OR forecast", "the forecast slipped"));
+        assert!(matches("migrat*", "the migration plan"));
+        assert!(matches("(budget -draft)", "final budget"));
+        assert!(!matches("(budget -draft)", "draft budget"));
+    }
+
+    #[test]
+    fn near_counts_words_between() {
+        let text = "the budget for next quarter depends on the forecast";
+        assert!(matches("NEAR(budget forecast, 6)", text));
+        assert!(!matches("NEAR(budget forecast, 5)", text));
+        assert!(matches("NEAR(forecast budget, 6)", text));
+        assert!(matches("NEAR(\"next quarter\" forec*, 3)", text));
+        assert!(!matches("NEAR(budget headcount)", text));
+    }
+
+    #[test]
+    fn scoped_clauses_match_only_their_target() {
+        let query = parse_query("title:roadmap").unwrap();
+        assert!(query.matches("Roadmap review", SearchTarget::Titles));
+        assert!(!query.matches("Roadmap review", SearchTarget::Transcripts));
+    }
+
+    #[test]
+    fn empty_query_matches_nothing() {
+        assert!(!matches("", "anything"));
+    }
+
+    #[test]
+    fn highlight_terms_skip_exclusions() {
+        let query = parse_query("budget \"q3 plan\" migrat* -draft title:roadmap").unwrap();
+        assert_eq!(
+            query.highlight_terms(),
+            vec![
+                FtsToken::Term("budget".into()),
+                FtsToken::Phrase("q3 plan".into()),
+                FtsToken::Term("migrat".into()),
+                FtsToken::Term("roadmap".into()),
+            ]
+        );
     }
 }
@@ -10,10 +10,12 @@ use std::collections::{HashMap, HashSet};
 use anyhow::Result;
 use rusqlite::Connection;
 
-use crate::embed::EmbeddingIndex;
 use crate::embed::model::Embedder;
+use crate::embed::{DocumentScope, EmbeddingIndex};
 use crate::query::dates::DateRange;
-use crate::query::filter::{SearchTarget, meeting_filter_matches, semantic_source_filter};
+use crate::query::filter::{
+    MeetingFilters, SearchTarget, allowed_meeting_ids, semantic_source_filter,
+};
 use crate::query::fusion::{FusedDoc, RRF_K, reciprocal_rank_fusion};
 
 /// How many top documents each retriever contributes to fusion.
@@ -52,10 +54,10 @@ pub struct HybridRanking {
 /// ranking); callers with an empty or unusable index pass None to avoid
 /// paying embedder initialization for nothing.
 ///
-/// `meeting_filter` (case-insensitive substring of document title or id)
-/// restricts both candidate lists before fusion truncates them to the
-/// pool, so ranking happens within the requested meeting rather than
-/// intersecting it with the global top candidates.
+/// `filters` (meeting, attendee, company, account, series, attendee count)
+/// restrict both candidate lists before fusion truncates them to the pool,
+/// so ranking happens within the filtered set rather than intersecting it
+/// with the global top candidates.
 #[allow(clippy::too_many_arguments)]
 pub fn hybrid_ranked(
     conn: &Connection,
@@ -63,13 +65,11 @@ pub fn hybrid_ranked(
     index: &EmbeddingIndex,
     query: &str,
     targets: &[SearchTarget],
-    meeting_filter: Option<&str>,
+    filters: &MeetingFilters,
     date_range: Option<&DateRange>,
     include_deleted: bool,
 ) -> Result<HybridRanking> {
-    let allowed = meeting_filter
-        .map(|f| allowed_meeting_ids(conn, f))
-        .transpose()?;
+    let allowed = allowed_meeting_ids(conn, filters)?;
     let is_allowed = |id: &str| allowed.as_ref().is_none_or(|set| set.contains(id));
 
     let fts_docs = crate::db::meetings::search_meetings(
@@ -92,16 +92,21 @@ pub fn hybrid_ranked(
     // including FTS-only documents outside the semantic top of the pool.
     // Fusion still truncates each id list to the pool.
     let source_filter = semantic_source_filter(targets);
+    let scope = DocumentScope {
+        date_range,
+        include_deleted,
+        allowed: allowed.as_ref(),
+        exclude: None,
+    };
     let (semantic_results, _) = match embedder {
         Some(embedder) => crate::embed::semantic_search_with_index(
             conn,
             embedder,
             index,
             query,
-            date_range,
+            &scope,
             0,
             source_filter.as_deref(),
-            include_deleted,
         )?,
         None => (Vec::new(), 0),
     };
@@ -109,9 +114,6 @@ pub fn hybrid_ranked(
     let mut semantic_ids = Vec::with_capacity(semantic_results.len());
     let mut best_chunks = HashMap::with_capacity(semantic_results.len());
     for r in semantic_results {
-        if !is_allowed(&r.document_id) {
-            continue;
-        }
         semantic_ids.push(r.document_id.clone());
         best_chunks.insert(
             r.document_id,
@@ -133,16 +135,6 @@ pub fn hybrid_ranked(
     })
 }
 
-/// Ids of documents whose title or id contains `filter`, case-insensitive.
-fn allowed_meeting_ids(conn: &Connection, filter: &str) -> Result<HashSet<String>> {
-    let filter_lower = filter.to_lowercase();
-    Ok(crate::db::meetings::all_meeting_refs(conn)?
-        .into_iter()
-        .filter(|(id, title)| meeting_filter_matches(&filter_lower, title.as_deref(), Some(id)))
-        .map(|(id, _)| id)
-        .collect())
-}
-
 /// Truncate each ranked id list to the candidate pool and fuse with RRF.
 fn fuse_candidates(mut fts_ids: Vec<String>, mut semantic_ids: Vec<String>) -> Vec<FusedDoc> {
     fts_ids.truncate(CANDIDATE_POOL);
@@ -189,7 +181,7 @@ mod tests {
             document_id: doc_id.to_string(),
             source_type: "transcript_window".to_string(),
             text: text.to_string(),
-            vector,
+            vector: vector.into(),
             metadata_json: None,
         }
     }
@@ -215,6 +207,7 @@ mod tests {
                 stored("doc-sem", "sem chunk", vec![1.0, 1.0]),
             ],
             stats: None,
+            ann: None,
         }
     }
 
@@ -222,6 +215,13 @@ mod tests {
         SearchTarget::all()
     }
 
+    fn meeting(filter: &str) -> MeetingFilters {
+        MeetingFilters {
+            meeting: Some(filter.to_string()),
+            ..Default::default()
+        }
+    }
+
     #[test]
     fn doc_found_by_both_retrievers_fuses_to_top() {
         let conn = build_test_db(&hybrid_state());
@@ -233,7 +233,7 @@ mod tests {
             &index,
             "kumquat",
             &all_targets(),
-            None,
+            &MeetingFilters::default(),
             None,
             false,
         )
@@ -266,7 +266,7 @@ mod tests {
             &index,
             "kumquat",
             &targets,
-            None,
+            &MeetingFilters::default(),
             None,
             false,
         )
@@ -290,6 +290,7 @@ mod tests {
                 stored("doc-sem", "strong chunk", vec![1.0, 0.5]),
             ],
             stats: None,
+            ann: None,
         };
 
         let ranking = hybrid_ranked(
@@ -298,7 +299,7 @@ mod tests {
             &index,
             "kumquat",
             &all_targets(),
-            None,
+            &MeetingFilters::default(),
             None,
             false,
         )
@@ -334,7 +335,7 @@ mod tests {
             &index,
             "kumquat",
             &all_targets(),
-            None,
+            &MeetingFilters::default(),
             None,
             false,
         )
@@ -368,6 +369,7 @@ mod tests {
         let index = EmbeddingIndex {
             vectors: Vec::new(),
             stats: None,
+            ann: None,
         };
 
         let ranking = hybrid_ranked(
@@ -376,7 +378,7 @@ mod tests {
             &index,
             "kumquat",
             &all_targets(),
-            None,
+            &MeetingFilters::default(),
             None,
             false,
         )
@@ -422,6 +424,7 @@ mod tests {
         let index = EmbeddingIndex {
             vectors: Vec::new(),
             stats: None,
+            ann: None,
         };
 
         let ranking = hybrid_ranked(
@@ -430,7 +433,7 @@ mod tests {
             &index,
             "kumquat",
             &all_targets(),
-            Some("beta"),
+            &meeting("beta"),
             None,
             false,
         )
@@ -451,7 +454,7 @@ mod tests {
             &index,
             "kumquat",
             &all_targets(),
-            None,
+            &MeetingFilters::default(),
             None,
             false,
         )
@@ -477,7 +480,7 @@ mod tests {
             &index,
             "kumquat",
             &all_targets(),
-            Some("unrelated"),
+            &meeting("unrelated"),
             None,
             false,
         )
@@ -507,7 +510,7 @@ mod tests {
             &index,
             "kumquat",
             &all_targets(),
-            Some("SYNC A"),
+            &meeting("SYNC A"),
             None,
             false,
         )
@@ -526,7 +529,7 @@ mod tests {
             &index,
             "kumquat",
             &all_targets(),
-            Some("doc-fts"),
+            &meeting("doc-fts"),
             None,
             false,
         )
@@ -545,28 +548,71 @@ mod tests {
         let index = hybrid_index();
 
         for filter in [None, Some("sync"), Some("sync a"), Some("nowhere")] {
+            let filters = MeetingFilters {
+                meeting: filter.map(str::to_string),
+                ..Default::default()
+            };
             let ranking = hybrid_ranked(
                 &conn,
                 Some(&FixedEmbedder),
                 &index,
                 "kumquat",
                 &all_targets(),
-                filter,
+                &filters,
                 None,
                 false,
             )
             .unwrap();
 
-            let grep_docs = crate::db::meetings::search_meetings(
-                &conn, "kumquat", true, true, true, true, None, false,
-            )
-            .unwrap();
-            let grep_count = crate::query::filter::filter_by_meeting(grep_docs, filter).len();
+            let opts = crate::commands::grep::GrepOptions {
+                targets: all_targets(),
+                filters,
+                limit: 0,
+                matches: 0,
+                speaker: None,
+                context: 0,
+            };
+            let (_, grep_count) =
+                crate::commands::grep::grep_meetings(&conn, "kumquat", opts, None, false).unwrap();
 
             assert_eq!(ranking.keyword_total, grep_count, "filter {filter:?}");
         }
     }
 
+    #[test]
+    fn attendee_filter_restricts_both_retrievers_before_fusion() {
+        let mut state = hybrid_state();
+        state["documents"]["doc-sem"]["people"] =
+            json!({"attendees": [{"email": "carol@widgets.io"}]});
+        let conn = build_test_db(&state);
+        let index = hybrid_index();
+        let filters = MeetingFilters {
+            with: Some("carol".to_string()),
+            ..Default::default()
+        };
+
+        let ranking = hybrid_ranked(
+            &conn,
+            Some(&FixedEmbedder),
+            &index,
+            "kumquat",
+            &all_targets(),
+            &filters,
+            None,
+            false,
+        )
+        .unwrap();
+
+        let ids: Vec<&str> = ranking
+            .fused
+            .iter()
+            .map(|d| d.document_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["doc-sem"]);
+        assert_eq!(ranking.keyword_total, 0);
+        assert!(!ranking.best_chunks.contains_key("doc-both"));
+    }
+
     #[test]
     fn no_embedder_yields_keyword_only_ranking() {
         // The caller skips embedder init when the index is unusable; the
@@ -575,6 +621,7 @@ mod tests {
         let index = EmbeddingIndex {
             vectors: Vec::new(),
             stats: None,
+            ann: None,
         };
 
         let ranking = hybrid_ranked(
@@ -583,7 +630,7 @@ mod tests {
             &index,
             "kumquat",
             &all_targets(),
-            None,
+            &MeetingFilters::default(),
             None,
             false,
         )
@@ -604,6 +651,7 @@ mod tests {
         let index = EmbeddingIndex {
             vectors: Vec::new(),
             stats: None,
+            ann: None,
         };
 
         let ranking = hybrid_ranked(
@@ -612,7 +660,7 @@ mod tests {
             &index,
             "zyzzyva",
             &all_targets(),
-            None,
+            &MeetingFilters::default(),
             None,
             false,
         )
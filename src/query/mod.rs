@@ -1,4 +1,6 @@
+pub mod actions;
 pub mod adjust;
+pub mod aliases;
 pub mod dates;
 pub mod evidence;
 pub mod filter;
@@ -9,3 +11,5 @@ pub mod rerank;
 pub mod shape;
 pub mod speaker;
 pub mod text;
+pub mod topics;
+pub mod turns;
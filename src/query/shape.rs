@@ -7,7 +7,8 @@
 //! `output::card`; evidence collection against the database lives in
 //! `query::evidence`.
 
-use crate::query::fts::FtsToken;
+use crate::query::filter::SearchTarget;
+use crate::query::fts::{FtsToken, Query};
 
 /// Where a piece of match evidence came from.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -138,10 +139,10 @@ pub fn normalize_whitespace(text: &str) -> String {
     text.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
-/// True when every query token appears in the title (the FTS title-tier
-/// match criterion, mirrored for display).
-pub fn title_matches(title: &str, tokens: &[FtsToken]) -> bool {
-    !tokens.is_empty() && crate::query::fts::matches_all_tokens(title, tokens)
+/// True when the title matches the query as a title row would (the FTS
+/// title-tier match criterion, mirrored for display).
+pub fn title_matches(title: &str, query: &Query) -> bool {
+    query.matches(title, SearchTarget::Titles)
 }
 
 /// Choose window bounds around `matched` (a char span) in `chars`, capped at
@@ -227,7 +228,7 @@ mod tests {
     use crate::query::fts::parse_query;
 
     fn excerpt(text: &str, query: &str, max: usize) -> Excerpt {
-        excerpt_around_match(text, &parse_query(query), max)
+        excerpt_around_match(text, &parse_query(query).unwrap().highlight_terms(), max)
     }
 
     fn highlighted<'a>(e: &'a Excerpt) -> Vec<&'a str> {
@@ -365,19 +366,19 @@ mod tests {
 
     #[test]
     fn title_matches_when_all_tokens_present() {
-        let tokens = parse_query("infra sync");
-        assert!(title_matches("Weekly Infra Sync", &tokens));
+        let query = parse_query("infra sync").unwrap();
+        assert!(title_matches("Weekly Infra Sync", &query));
     }
 
     #[test]
     fn title_does_not_match_on_partial_tokens() {
-        let tokens = parse_query("infra budget");
-        assert!(!title_matches("Weekly Infra Sync", &tokens));
+        let query = parse_query("infra budget").unwrap();
+        assert!(!title_matches("Weekly Infra Sync", &query));
     }
 
     #[test]
     fn empty_query_never_title_matches() {
-        assert!(!title_matches("Weekly Infra Sync", &[]));
+        assert!(!title_matches("Weekly Infra Sync", &Query::default()));
     }
 
     // --- find_ignore_case (via excerpt highlights) ---
@@ -14,13 +14,17 @@
 
 use anyhow::{Result, bail};
 use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::db::aliases::AliasGroup;
 
 /// How many known speakers an error message lists before it truncates.
 const MAX_LISTED_SPEAKERS: usize = 10;
 
 /// What the user asked for on `--speaker`, before it is resolved against the
 /// names present in the database.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SpeakerSelector {
     /// The local user: the microphone channel.
     Me,
@@ -95,6 +99,50 @@ pub fn resolve_names(pattern: &str, available: &[String]) -> Vec<String> {
         .collect()
 }
 
+/// Widen resolved names to every speaker name merged with them (see
+/// [`crate::db::aliases`]). A merged person is also hit through any of their
+/// identities containing the pattern, so `--speaker dana@acme.com` finds the
+/// name Dana speaks under. After an exact hit only that name's person is
+/// added, so the exact-wins rule of [`resolve_names`] still holds.
+pub fn with_aliases(
+    pattern: &str,
+    matched: Vec<String>,
+    available: &[String],
+    groups: &[AliasGroup],
+) -> Vec<String> {
+    let exact = matched
+        .iter()
+        .any(|n| n.eq_ignore_ascii_case(pattern.trim()));
+    let mut names = matched;
+    for group in groups {
+        let hit = names.iter().any(|n| group.has_name(n)) || (!exact && group.mentions(pattern));
+        if !hit {
+            continue;
+        }
+        for name in available {
+            if group.has_name(name) && !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+    names
+}
+
+/// How many people the names belong to, counting merged names once.
+fn distinct_people(names: &[String], groups: &[AliasGroup]) -> usize {
+    let mut seen: Vec<&str> = Vec::new();
+    for name in names {
+        let key = groups
+            .iter()
+            .find(|g| g.has_name(name))
+            .map_or(name.as_str(), |g| g.id.as_str());
+        if !seen.contains(&key) {
+            seen.push(key);
+        }
+    }
+    seen.len()
+}
+
 /// How to name a speaker in output.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SpeakerLabel<'a> {
@@ -137,7 +185,8 @@ pub fn label<'a>(source: Option<&str>, speaker_name: Option<&'a str>) -> Option<
 /// Resolve a selector against the database, reporting what the pattern hit.
 ///
 /// `Me` and `Other` need no lookup. A name pattern that matches nothing is an
-/// error rather than an empty result; one that matches several speakers takes
+/// error rather than an empty result. Merged names count as one speaker. A
+/// pattern that matches several speakers takes
 /// all of them and says so on stderr, since the union is usually what was
 /// wanted and the note names the string needed to narrow.
 pub fn resolve(conn: &Connection, selector: &SpeakerSelector) -> Result<SpeakerFilter> {
@@ -148,9 +197,15 @@ pub fn resolve(conn: &Connection, selector: &SpeakerSelector) -> Result<SpeakerF
     };
 
     let available = crate::db::transcripts::distinct_speaker_names(conn)?;
-    let matched = resolve_names(pattern, &available);
+    let groups = crate::db::aliases::alias_groups(conn)?;
+    let matched = with_aliases(
+        pattern,
+        resolve_names(pattern, &available),
+        &available,
+        &groups,
+    );
 
-    match matched.len() {
+    match distinct_people(&matched, &groups) {
         0 => bail!("{}", no_match_message(pattern, &available)),
         1 => Ok(SpeakerFilter::Names(matched)),
         n => {
@@ -278,6 +333,49 @@ mod tests {
         assert!(resolve_names("jayne", &available).is_empty());
     }
 
+    // --- with_aliases ---
+
+    fn group(names_in: &[&str], emails: &[&str]) -> AliasGroup {
+        use crate::db::aliases::Alias;
+        AliasGroup {
+            id: "g".to_string(),
+            aliases: names_in
+                .iter()
+                .map(|n| Alias::name(n))
+                .chain(emails.iter().map(|e| Alias::email(e)))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn with_aliases_adds_merged_names() {
+        let available = names(&["Dana Lee", "Dana L.", "Marcus Webb"]);
+        let groups = [group(&["Dana Lee", "Dana L."], &["dana@acme.com"])];
+        assert_eq!(
+            with_aliases("dana lee", names(&["Dana Lee"]), &available, &groups),
+            names(&["Dana Lee", "Dana L."])
+        );
+        // Through an email, which no speaker name contains.
+        assert_eq!(
+            with_aliases("dana@acme", Vec::new(), &available, &groups),
+            names(&["Dana Lee", "Dana L."])
+        );
+        assert_eq!(
+            distinct_people(&names(&["Dana Lee", "Dana L."]), &groups),
+            1
+        );
+    }
+
+    #[test]
+    fn with_aliases_keeps_exact_hits_exact() {
+        let available = names(&["Jane Doe", "Jane Doe Jr", "J. Doe"]);
+        let groups = [group(&["Jane Doe Jr", "J. Doe"], &[])];
+        assert_eq!(
+            with_aliases("jane doe", names(&["Jane Doe"]), &available, &groups),
+            names(&["Jane Doe"])
+        );
+    }
+
     // --- SpeakerFilter::matches ---
 
     #[test]
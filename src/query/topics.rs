@@ -0,0 +1,413 @@
+//! Topic clustering over stored chunk embeddings, for `grans topics`.
+//!
+//! Chunks from the meetings in range are clustered with spherical k-means
+//! ([`crate::embed::cluster`]). Each cluster is labeled with the terms that
+//! are common among its chunks but rare across the full-text indexes, and
+//! summarized as distinct meetings per week plus the meetings that
+//! contribute most to it.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::Result;
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate};
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::db::vocab::TermFrequencies;
+use crate::embed::store::StoredVector;
+use crate::query::dates::DateRange;
+use crate::query::filter::{SearchTarget, semantic_source_filter};
+
+/// Label terms reported per topic.
+pub const LABEL_TERMS: usize = 5;
+
+/// Representative meetings reported per topic.
+pub const REPRESENTATIVE_MEETINGS: usize = 3;
+
+/// Tokens shorter than this never label a topic.
+const MIN_TERM_CHARS: usize = 3;
+
+/// Every topic found in a date range.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicReport {
+    /// Meetings with embedded content in range.
+    pub meetings: usize,
+    /// Chunks clustered.
+    pub chunks: usize,
+    /// Monday of each week covered, `YYYY-MM-DD` in the display timezone.
+    /// Every topic's `weekly` list follows this order.
+    pub weeks: Vec<String>,
+    /// Largest topic (by meetings) first.
+    pub topics: Vec<Topic>,
+}
+
+/// One cluster of chunks.
+#[derive(Debug, Clone, Serialize)]
+pub struct Topic {
+    /// Most distinctive terms, best first.
+    pub terms: Vec<String>,
+    /// Distinct meetings with at least one chunk in the topic.
+    pub meetings: usize,
+    pub chunks: usize,
+    pub weekly: Vec<WeekCount>,
+    pub representative: Vec<TopicMeeting>,
+}
+
+/// Distinct meetings touching a topic in one week.
+#[derive(Debug, Clone, Serialize)]
+pub struct WeekCount {
+    pub week: String,
+    pub meetings: usize,
+}
+
+/// A meeting standing for a topic.
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicMeeting {
+    pub id: String,
+    pub title: Option<String>,
+    pub created_at: String,
+    /// The meeting's chunks in this topic.
+    pub chunks: usize,
+}
+
+/// A meeting eligible for the report.
+struct MeetingInfo {
+    title: Option<String>,
+    created_at: String,
+    week: NaiveDate,
+}
+
+/// Cluster the embedded chunks of non-deleted meetings in `date_range`
+/// into at most `k` topics.
+pub fn topic_report(
+    conn: &Connection,
+    targets: &[SearchTarget],
+    date_range: Option<&DateRange>,
+    k: usize,
+    tz: &FixedOffset,
+) -> Result<TopicReport> {
+    let meetings = meetings_in_range(conn, date_range, tz)?;
+    let source_filter = semantic_source_filter(targets);
+    let chunks: Vec<StoredVector> = crate::embed::store::load_all_vectors(conn)?
+        .into_iter()
+        .filter(|c| meetings.contains_key(&c.document_id))
+        .filter(|c| {
+            source_filter
+                .as_ref()
+                .is_none_or(|f| f.contains(&c.source_type.as_str()))
+        })
+        .collect();
+
+    let vectors: Vec<Vec<f32>> = chunks.iter().map(|c| c.vector.to_f32()).collect();
+    let clustering = crate::embed::cluster::kmeans(&vectors, k);
+    let vocab = crate::db::vocab::content_term_frequencies(conn)?;
+
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); clustering.centroids.len()];
+    for (i, &cluster) in clustering.assignments.iter().enumerate() {
+        members[cluster].push(i);
+    }
+
+    let mut week_set: HashSet<NaiveDate> = HashSet::new();
+    for chunk in &chunks {
+        week_set.insert(meetings[&chunk.document_id].week);
+    }
+    let weeks = week_span(&week_set);
+
+    let mut topics: Vec<Topic> = members
+        .iter()
+        .filter(|m| !m.is_empty())
+        .map(|m| {
+            summarize(
+                m,
+                &chunks,
+                &clustering.similarities,
+                &meetings,
+                &weeks,
+                &vocab,
+            )
+        })
+        .collect();
+    topics.sort_by(|a, b| {
+        b.meetings
+            .cmp(&a.meetings)
+            .then(b.chunks.cmp(&a.chunks))
+            .then(a.terms.cmp(&b.terms))
+    });
+
+    let meeting_count = chunks
+        .iter()
+        .map(|c| c.document_id.as_str())
+        .collect::<HashSet<_>>()
+        .len();
+    Ok(TopicReport {
+        meetings: meeting_count,
+        chunks: chunks.len(),
+        weeks: weeks.iter().map(|w| w.to_string()).collect(),
+        topics,
+    })
+}
+
+/// Non-deleted meetings created in range, with the week each falls in.
+/// Meetings without a parseable creation time cannot be placed on the
+/// timeline and are left out.
+fn meetings_in_range(
+    conn: &Connection,
+    date_range: Option<&DateRange>,
+    tz: &FixedOffset,
+) -> Result<HashMap<String, MeetingInfo>> {
+    let mut stmt =
+        conn.prepare("SELECT id, title, created_at FROM documents WHERE deleted_at IS NULL")?;
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, Option<String>>(1)?,
+            row.get::<_, Option<String>>(2)?,
+        ))
+    })?;
+
+    let mut meetings = HashMap::new();
+    for row in rows {
+        let (id, title, created_at) = row?;
+        let Some(created_at) = created_at else {
+            continue;
+        };
+        let Ok(created) = DateTime::parse_from_rfc3339(&created_at) else {
+            continue;
+        };
+        if !crate::embed::created_in_range(Some(&created_at), date_range) {
+            continue;
+        }
+        let week = week_start(created.with_timezone(tz).date_naive());
+        meetings.insert(
+            id,
+            MeetingInfo {
+                title,
+                created_at,
+                week,
+            },
+        );
+    }
+    Ok(meetings)
+}
+
+/// The Monday starting `day`'s week.
+fn week_start(day: NaiveDate) -> NaiveDate {
+    day - Duration::days(day.weekday().num_days_from_monday() as i64)
+}
+
+/// Every week from the earliest to the latest in `weeks`, so quiet weeks
+/// show as zeros rather than gaps.
+fn week_span(weeks: &HashSet<NaiveDate>) -> Vec<NaiveDate> {
+    let (Some(first), Some(last)) = (weeks.iter().min(), weeks.iter().max()) else {
+        return Vec::new();
+    };
+    let mut span = Vec::new();
+    let mut week = *first;
+    while week <= *last {
+        span.push(week);
+        week += Duration::weeks(1);
+    }
+    span
+}
+
+fn summarize(
+    members: &[usize],
+    chunks: &[StoredVector],
+    similarities: &[f32],
+    meetings: &HashMap<String, MeetingInfo>,
+    weeks: &[NaiveDate],
+    vocab: &TermFrequencies,
+) -> Topic {
+    // Per meeting: chunks in the topic and their summed similarity to the
+    // centroid.
+    let mut per_meeting: HashMap<&str, (usize, f32)> = HashMap::new();
+    for &i in members {
+        let entry = per_meeting
+            .entry(chunks[i].document_id.as_str())
+            .or_insert((0, 0.0));
+        entry.0 += 1;
+        entry.1 += similarities[i];
+    }
+
+    let mut by_week: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for id in per_meeting.keys() {
+        *by_week.entry(meetings[*id].week).or_insert(0) += 1;
+    }
+    let weekly = weeks
+        .iter()
+        .map(|w| WeekCount {
+            week: w.to_string(),
+            meetings: by_week.get(w).copied().unwrap_or(0),
+        })
+        .collect();
+
+    let mut ranked: Vec<(&str, usize, f32)> = per_meeting
+        .iter()
+        .map(|(id, (count, sim))| (*id, *count, *sim / *count as f32))
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.1.cmp(&a.1)
+            .then(b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal))
+            .then(a.0.cmp(b.0))
+    });
+    let representative = ranked
+        .iter()
+        .take(REPRESENTATIVE_MEETINGS)
+        .map(|(id, count, _)| {
+            let info = &meetings[*id];
+            TopicMeeting {
+                id: id.to_string(),
+                title: info.title.clone(),
+                created_at: info.created_at.clone(),
+                chunks: *count,
+            }
+        })
+        .collect();
+
+    let texts: Vec<&str> = members.iter().map(|&i| chunks[i].text.as_str()).collect();
+    Topic {
+        terms: label_terms(&texts, vocab, LABEL_TERMS),
+        meetings: per_meeting.len(),
+        chunks: members.len(),
+        weekly,
+        representative,
+    }
+}
+
+/// The `limit` terms that best distinguish these chunks: the share of
+/// chunks containing the term, weighted by its inverse document frequency
+/// in the full-text indexes. Only terms the indexes know are candidates,
+/// and in a topic of several chunks a term must appear in at least two.
+pub fn label_terms(texts: &[&str], vocab: &TermFrequencies, limit: usize) -> Vec<String> {
+    let mut chunk_counts: HashMap<String, usize> = HashMap::new();
+    for text in texts {
+        for term in tokens(text) {
+            *chunk_counts.entry(term).or_insert(0) += 1;
+        }
+    }
+
+    let min_chunks = if texts.len() > 1 { 2 } else { 1 };
+    let mut scored: Vec<(String, f64)> = chunk_counts
+        .into_iter()
+        .filter(|(_, count)| *count >= min_chunks)
+        .filter_map(|(term, count)| {
+            let idf = vocab.idf(&term)?;
+            Some((term, count as f64 / texts.len() as f64 * idf))
+        })
+        .filter(|(_, score)| *score > 0.0)
+        .collect();
+    scored.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.0.cmp(&b.0))
+    });
+    scored.into_iter().take(limit).map(|(t, _)| t).collect()
+}
+
+/// The distinct label candidates in `text`: lowercased alphanumeric runs,
+/// split as FTS5's default tokenizer splits them, minus short and numeric
+/// tokens.
+fn tokens(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.chars().count() >= MIN_TERM_CHARS)
+        .filter(|t| !t.chars().all(|c| c.is_numeric()))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vocab(terms: &[(&str, u64)], rows: u64) -> TermFrequencies {
+        TermFrequencies {
+            rows,
+            document_frequency: terms.iter().map(|(t, d)| (t.to_string(), *d)).collect(),
+        }
+    }
+
+    #[test]
+    fn labels_prefer_terms_rare_in_the_corpus() {
+        let v = vocab(&[("pricing", 3), ("meeting", 90), ("renewal", 5)], 100);
+        let texts = [
+            "Pricing for the renewal meeting",
+            "pricing meeting recap",
+            "renewal pricing",
+        ];
+        assert_eq!(label_terms(&texts, &v, 2), vec!["pricing", "renewal"]);
+    }
+
+    #[test]
+    fn labels_skip_terms_outside_the_vocabulary_and_one_offs() {
+        let v = vocab(&[("pricing", 3), ("zebra", 1)], 100);
+        let texts = ["pricing zebra", "pricing unknownword unknownword"];
+        assert_eq!(label_terms(&texts, &v, 5), vec!["pricing"]);
+    }
+
+    #[test]
+    fn report_counts_meetings_per_week_within_the_range() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::schema::create_tables(&conn).unwrap();
+        let long =
+            "Quarterly pricing discussion about the enterprise renewal and its discount structure.";
+        for (id, created) in [
+            ("a", "2026-07-07T10:00:00Z"),
+            ("b", "2026-07-21T10:00:00Z"),
+            ("old", "2026-01-05T10:00:00Z"),
+        ] {
+            conn.execute(
+                "INSERT INTO documents (id, title, created_at) VALUES (?1, ?1, ?2)",
+                rusqlite::params![id, created],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO transcript_utterances (id, document_id, start_timestamp, end_timestamp, text)
+                 VALUES (?1, ?1, ?2, ?2, ?3)",
+                rusqlite::params![id, created, long],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO transcript_fts(transcript_fts) VALUES('rebuild')",
+            [],
+        )
+        .unwrap();
+        crate::embed::ensure_embeddings(
+            &conn,
+            &crate::embed::model::MockEmbedder::default(),
+            crate::embed::DEFAULT_BATCH_SIZE,
+            &crate::embed::config::EmbedSpec::default_for(512),
+        )
+        .unwrap();
+
+        let range = DateRange {
+            start: Some("2026-07-01T00:00:00Z".parse().unwrap()),
+            end: None,
+        };
+        let utc = FixedOffset::east_opt(0).unwrap();
+        let report = topic_report(&conn, &SearchTarget::all(), Some(&range), 4, &utc).unwrap();
+
+        assert_eq!(report.meetings, 2);
+        assert_eq!(report.weeks, vec!["2026-07-06", "2026-07-13", "2026-07-20"]);
+        assert_eq!(report.topics.len(), 1);
+        let topic = &report.topics[0];
+        let weekly: Vec<usize> = topic.weekly.iter().map(|w| w.meetings).collect();
+        assert_eq!(weekly, vec![1, 0, 1]);
+        assert_eq!(topic.representative.len(), 2);
+        assert_eq!(topic.terms.len(), LABEL_TERMS);
+    }
+
+    #[test]
+    fn weeks_start_on_monday_and_fill_gaps() {
+        let wed = NaiveDate::from_ymd_opt(2026, 7, 8).unwrap();
+        assert_eq!(week_start(wed).to_string(), "2026-07-06");
+
+        let weeks: HashSet<NaiveDate> = [
+            NaiveDate::from_ymd_opt(2026, 7, 6).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 7, 20).unwrap(),
+        ]
+        .into();
+        let span: Vec<String> = week_span(&weeks).iter().map(|w| w.to_string()).collect();
+        assert_eq!(span, vec!["2026-07-06", "2026-07-13", "2026-07-20"]);
+    }
+}
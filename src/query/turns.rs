@@ -0,0 +1,324 @@
+//! Speaker turns rebuilt from transcript utterances, and talk-time stats.
+//!
+//! Utterances are silence-delimited segments per audio channel, and the two
+//! channels overlap (see `docs/transcript-segmentation.md`). A turn is a
+//! speaker's run of utterances with only short silences between them and
+//! nobody else taking the floor in those silences. Another speaker talking
+//! over the turn (a "yeah" on the other channel) does not end it.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::models::TranscriptUtterance;
+use crate::query::speaker::label;
+
+/// Longest silence inside one turn. Same-channel gaps are real detected
+/// silences; within a remote run the median is 0.6s and under a fifth
+/// exceed 2s, so longer pauses are treated as turn boundaries.
+pub const DEFAULT_TURN_GAP_SECS: f64 = 2.0;
+
+/// An overlapping turn shorter than this many words is an acknowledgement
+/// ("Yeah.", "Right, okay."), not an interruption.
+const BACKCHANNEL_WORDS: usize = 3;
+
+/// Speaker name for utterances whose audio channel was never recorded.
+const UNKNOWN_SPEAKER: &str = "Unknown";
+
+/// One speaker holding the floor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Turn {
+    /// "You", the detected name, "Other", or "Unknown" for rows synced
+    /// before channels were recorded.
+    pub speaker: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Time spent actually speaking: the turn's utterances, without the
+    /// pauses between them.
+    pub speaking: Duration,
+    pub text: String,
+    pub utterances: usize,
+}
+
+/// Build turns from a meeting's utterances, in start order. Utterances
+/// without timestamps are left out.
+pub fn build_turns(utterances: &[TranscriptUtterance], max_gap: Duration) -> Vec<Turn> {
+    let mut timed: Vec<(DateTime<Utc>, DateTime<Utc>, String, &str)> = utterances
+        .iter()
+        .filter_map(|u| {
+            let start = parse_ts(u.start_timestamp.as_deref()?)?;
+            let end = parse_ts(u.end_timestamp.as_deref()?)?.max(start);
+            let speaker = label(u.source.as_deref(), u.detected_speaker_name.as_deref())
+                .map(|l| l.as_str().to_string())
+                .unwrap_or_else(|| UNKNOWN_SPEAKER.to_string());
+            Some((start, end, speaker, u.text.as_deref().unwrap_or("").trim()))
+        })
+        .collect();
+    timed.sort_by_key(|(start, ..)| *start);
+
+    let mut turns: Vec<Turn> = Vec::new();
+    // Index of each speaker's latest turn.
+    let mut latest: HashMap<String, usize> = HashMap::new();
+    for (start, end, speaker, text) in timed {
+        let continues = latest.get(&speaker).copied().filter(|&i| {
+            let turn = &turns[i];
+            start - turn.end <= max_gap
+                // Turns begun since are only talk-over, not a new floor.
+                && turns[i + 1..].iter().all(|other| other.start < turn.end)
+        });
+        match continues {
+            Some(i) => {
+                let turn = &mut turns[i];
+                turn.end = turn.end.max(end);
+                turn.speaking += end - start;
+                if !text.is_empty() {
+                    if !turn.text.is_empty() {
+                        turn.text.push(' ');
+                    }
+                    turn.text.push_str(text);
+                }
+                turn.utterances += 1;
+            }
+            None => {
+                latest.insert(speaker.clone(), turns.len());
+                turns.push(Turn {
+                    speaker,
+                    start,
+                    end,
+                    speaking: end - start,
+                    text: text.to_string(),
+                    utterances: 1,
+                });
+            }
+        }
+    }
+    turns
+}
+
+fn parse_ts(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// One speaker's share of the conversation.
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeakerStats {
+    pub speaker: String,
+    /// Seconds spent speaking.
+    pub talk_seconds: f64,
+    /// Fraction of all speaking time, 0 to 1.
+    pub share: f64,
+    pub turns: usize,
+    /// Duration of the speaker's longest turn, pauses included.
+    pub longest_monologue_seconds: f64,
+    /// Turns started while another speaker's turn was still running,
+    /// excluding brief acknowledgements.
+    pub interruptions: usize,
+    /// Sentences ending in a question mark.
+    pub questions: usize,
+}
+
+/// Talk-time statistics over one or more meetings' turns, most talkative
+/// speaker first. Interruptions are only counted within a meeting.
+pub fn talk_time(meetings: &[Vec<Turn>]) -> Vec<SpeakerStats> {
+    let mut by_speaker: HashMap<&str, SpeakerStats> = HashMap::new();
+    for turns in meetings {
+        for (i, turn) in turns.iter().enumerate() {
+            let stats = by_speaker
+                .entry(turn.speaker.as_str())
+                .or_insert_with(|| SpeakerStats {
+                    speaker: turn.speaker.clone(),
+                    talk_seconds: 0.0,
+                    share: 0.0,
+                    turns: 0,
+                    longest_monologue_seconds: 0.0,
+                    interruptions: 0,
+                    questions: 0,
+                });
+            stats.talk_seconds += seconds(turn.speaking);
+            stats.turns += 1;
+            stats.longest_monologue_seconds = stats
+                .longest_monologue_seconds
+                .max(seconds(turn.end - turn.start));
+            stats.questions += count_questions(&turn.text);
+            let talks_over = turns[..i]
+                .iter()
+                .any(|earlier| earlier.speaker != turn.speaker && earlier.end > turn.start);
+            if talks_over && turn.text.split_whitespace().count() > BACKCHANNEL_WORDS {
+                stats.interruptions += 1;
+            }
+        }
+    }
+
+    let total: f64 = by_speaker.values().map(|s| s.talk_seconds).sum();
+    let mut speakers: Vec<SpeakerStats> = by_speaker.into_values().collect();
+    for s in &mut speakers {
+        s.share = if total > 0.0 {
+            s.talk_seconds / total
+        } else {
+            0.0
+        };
+    }
+    speakers.sort_by(|a, b| {
+        b.talk_seconds
+            .total_cmp(&a.talk_seconds)
+            .then_with(|| a.speaker.cmp(&b.speaker))
+    });
+    speakers
+}
+
+fn seconds(d: Duration) -> f64 {
+    d.num_milliseconds() as f64 / 1000.0
+}
+
+/// Sentences in `text` ending with a question mark ("?!" and "??" count
+/// once).
+fn count_questions(text: &str) -> usize {
+    let mut count = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '?' {
+            count += 1;
+            while chars.next_if(|c| matches!(c, '?' | '!')).is_some() {}
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utt(
+        start: f64,
+        end: f64,
+        source: &str,
+        name: Option<&str>,
+        text: &str,
+    ) -> TranscriptUtterance {
+        let at = |secs: f64| {
+            (DateTime::parse_from_rfc3339("2026-01-20T10:00:00Z").unwrap()
+                + Duration::milliseconds((secs * 1000.0) as i64))
+            .to_rfc3339()
+        };
+        TranscriptUtterance {
+            start_timestamp: Some(at(start)),
+            end_timestamp: Some(at(end)),
+            source: Some(source.to_string()),
+            detected_speaker_name: name.map(str::to_string),
+            text: Some(text.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn gap() -> Duration {
+        Duration::milliseconds((DEFAULT_TURN_GAP_SECS * 1000.0) as i64)
+    }
+
+    fn speakers(turns: &[Turn]) -> Vec<(&str, usize)> {
+        turns
+            .iter()
+            .map(|t| (t.speaker.as_str(), t.utterances))
+            .collect()
+    }
+
+    #[test]
+    fn short_gaps_merge_and_long_gaps_split() {
+        let turns = build_turns(
+            &[
+                utt(0.0, 3.0, "microphone", None, "First point."),
+                utt(3.5, 6.0, "microphone", None, "Second point."),
+                utt(10.0, 12.0, "microphone", None, "After a pause."),
+            ],
+            gap(),
+        );
+        assert_eq!(speakers(&turns), vec![("You", 2), ("You", 1)]);
+        assert_eq!(turns[0].text, "First point. Second point.");
+        assert_eq!(turns[0].speaking, Duration::milliseconds(5500));
+    }
+
+    #[test]
+    fn talk_over_does_not_end_a_turn_but_taking_the_floor_does() {
+        let turns = build_turns(
+            &[
+                utt(0.0, 5.0, "system", Some("Dana"), "Let me walk through it."),
+                utt(2.0, 2.5, "microphone", None, "Yeah."),
+                utt(5.5, 8.0, "system", Some("Dana"), "Then the numbers."),
+                utt(8.5, 10.0, "microphone", None, "Can I ask something?"),
+                utt(10.5, 12.0, "system", Some("Dana"), "Sure."),
+            ],
+            gap(),
+        );
+        assert_eq!(
+            speakers(&turns),
+            vec![("Dana", 2), ("You", 1), ("You", 1), ("Dana", 1)]
+        );
+    }
+
+    #[test]
+    fn named_speakers_on_one_channel_are_separate_turns() {
+        let turns = build_turns(
+            &[
+                utt(0.0, 2.0, "system", Some("Dana"), "Over to you."),
+                utt(2.5, 4.0, "system", Some("Eli"), "Thanks."),
+                utt(4.5, 5.0, "system", None, "Hm."),
+            ],
+            gap(),
+        );
+        assert_eq!(
+            speakers(&turns),
+            vec![("Dana", 1), ("Eli", 1), ("Other", 1)]
+        );
+    }
+
+    #[test]
+    fn talk_time_reports_share_monologue_interruptions_and_questions() {
+        let turns = build_turns(
+            &[
+                utt(
+                    0.0,
+                    6.0,
+                    "system",
+                    Some("Dana"),
+                    "Here is the plan. Any questions?",
+                ),
+                utt(2.0, 2.5, "microphone", None, "Mm-hm."),
+                utt(
+                    5.0,
+                    8.0,
+                    "microphone",
+                    None,
+                    "Wait, what about the budget? And timing??",
+                ),
+                utt(9.0, 11.0, "system", Some("Dana"), "Good question."),
+            ],
+            gap(),
+        );
+        let stats = talk_time(&[turns]);
+        assert_eq!(stats[0].speaker, "Dana");
+        assert_eq!(stats[0].talk_seconds, 8.0);
+        assert_eq!(stats[0].questions, 1);
+        assert_eq!(stats[0].interruptions, 0);
+        assert_eq!(stats[1].speaker, "You");
+        assert_eq!(stats[1].talk_seconds, 3.5);
+        assert_eq!(stats[1].turns, 2);
+        // The acknowledgement talks over Dana too, but only the real
+        // question counts as an interruption.
+        assert_eq!(stats[1].interruptions, 1);
+        assert_eq!(stats[1].questions, 2);
+        assert_eq!(stats[1].longest_monologue_seconds, 3.0);
+        assert!((stats[0].share + stats[1].share - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn untimed_and_unknown_channel_utterances() {
+        let mut untimed = utt(0.0, 1.0, "microphone", None, "Lost.");
+        untimed.start_timestamp = None;
+        let mut legacy = utt(1.0, 2.0, "microphone", None, "Old row.");
+        legacy.source = None;
+        let turns = build_turns(&[untimed, legacy], gap());
+        assert_eq!(speakers(&turns), vec![("Unknown", 1)]);
+    }
+}
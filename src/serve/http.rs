@@ -0,0 +1,173 @@
+//! Just enough HTTP/1.1 for a local JSON query server.
+//!
+//! Requests are read up to the end of their headers; bodies are ignored
+//! because every endpoint takes its parameters from the query string. Each
+//! response closes the connection, so there is no keep-alive or chunked
+//! encoding to get wrong.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+
+use anyhow::{Result, bail};
+
+/// Longest request line or header line accepted, in bytes.
+const MAX_LINE_BYTES: usize = 8 * 1024;
+
+/// Most header lines accepted before the request is rejected.
+const MAX_HEADERS: usize = 100;
+
+/// The parts of a request the router looks at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    /// Path without the query string. Every route is plain ASCII, so the
+    /// path is matched as sent rather than percent-decoded.
+    pub path: String,
+    /// Raw query string, without the leading `?`.
+    pub query: String,
+    /// The `Host` header, if sent.
+    pub host: Option<String>,
+    /// The `Origin` header, which browsers send on cross-site requests.
+    pub origin: Option<String>,
+}
+
+impl Request {
+    /// Parse a request line such as `GET /search?q=budget HTTP/1.1`.
+    pub fn parse_line(line: &str) -> Result<Self> {
+        let mut parts = line.split_whitespace();
+        let (Some(method), Some(target), Some(version)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            bail!("Malformed request line");
+        };
+        if !version.starts_with("HTTP/1.") {
+            bail!("Unsupported protocol {}", version);
+        }
+
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+        Ok(Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: query.to_string(),
+            host: None,
+            origin: None,
+        })
+    }
+
+    /// Record `line` if it is one of the headers the router looks at.
+    fn add_header(&mut self, line: &str) {
+        let Some((name, value)) = line.split_once(':') else {
+            return;
+        };
+        let value = Some(value.trim().to_string());
+        if name.trim().eq_ignore_ascii_case("host") {
+            self.host = value;
+        } else if name.trim().eq_ignore_ascii_case("origin") {
+            self.origin = value;
+        }
+    }
+}
+
+/// Read one request from the stream, keeping the headers [`Request`] holds.
+pub fn read_request(stream: &TcpStream) -> Result<Request> {
+    read_from(&mut BufReader::new(stream))
+}
+
+fn read_from(reader: &mut impl BufRead) -> Result<Request> {
+    let request_line = read_line(reader)?;
+    let mut request = Request::parse_line(&request_line)?;
+
+    for _ in 0..MAX_HEADERS {
+        let line = read_line(reader)?;
+        if line.is_empty() {
+            return Ok(request);
+        }
+        request.add_header(&line);
+    }
+    bail!("Too many request headers")
+}
+
+fn read_line(reader: &mut impl BufRead) -> Result<String> {
+    let mut buf = Vec::new();
+    let read = reader
+        .by_ref()
+        .take(MAX_LINE_BYTES as u64 + 1)
+        .read_until(b'\n', &mut buf)?;
+    if read == 0 {
+        bail!("Connection closed before the request was complete");
+    }
+    if buf.len() > MAX_LINE_BYTES {
+        bail!("Request line too long");
+    }
+    Ok(String::from_utf8_lossy(&buf).trim_end().to_string())
+}
+
+/// Write a complete JSON response and let the connection close.
+pub fn write_json(stream: &mut TcpStream, status: u16, body: &str) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason_phrase(status),
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_path_and_query() {
+        let req = Request::parse_line("GET /search?q=budget&limit=5 HTTP/1.1").unwrap();
+
+        assert_eq!(req.method, "GET");
+        assert_eq!(req.path, "/search");
+        assert_eq!(req.query, "q=budget&limit=5");
+    }
+
+    #[test]
+    fn query_is_empty_without_a_question_mark() {
+        let req = Request::parse_line("GET /health HTTP/1.1").unwrap();
+
+        assert_eq!(req.path, "/health");
+        assert_eq!(req.query, "");
+    }
+
+    #[test]
+    fn rejects_malformed_request_line() {
+        assert!(Request::parse_line("GET /search").is_err());
+        assert!(Request::parse_line("GET /search SPDY/3").is_err());
+    }
+
+    #[test]
+    fn keeps_host_and_origin_headers() {
+        let raw = "GET /health HTTP/1.1\r\nhost: localhost:7373\r\nAccept: */*\r\nOrigin:  http://evil.example\r\n\r\n";
+        let req = read_from(&mut std::io::Cursor::new(raw.as_bytes())).unwrap();
+
+        assert_eq!(req.host.as_deref(), Some("localhost:7373"));
+        assert_eq!(req.origin.as_deref(), Some("http://evil.example"));
+    }
+
+    #[test]
+    fn reads_a_line_up_to_the_limit() {
+        let long = format!("{}\r\n", "a".repeat(MAX_LINE_BYTES + 10));
+        let mut reader = std::io::Cursor::new(long.into_bytes());
+
+        assert!(read_line(&mut reader).is_err());
+    }
+}
@@ -0,0 +1,641 @@
+//! `grans mcp`: the query service over the Model Context Protocol.
+//!
+//! MCP is JSON-RPC 2.0, one message per line on stdin and stdout. Tools
+//! wrap the same queries as the HTTP endpoints; resources expose each
+//! meeting and its transcript by URI. Search and grep results keep the
+//! shaped-card evidence, so an assistant gets cited snippets and fetches a
+//! whole transcript only when it asks for one.
+//!
+//! Stdout carries protocol messages only; notes go to stderr as usual.
+
+use std::io::{BufRead, Write};
+
+use anyhow::Result;
+use chrono::FixedOffset;
+use rusqlite::Connection;
+use serde_json::{Value, json};
+
+use super::ServeError;
+use super::params::Params;
+use super::service::QueryService;
+
+/// Protocol revisions this server speaks, newest first.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// First revision with `structuredContent` and `resource_link` content.
+const STRUCTURED_CONTENT_VERSION: &str = "2025-06-18";
+
+/// Meetings per `resources/list` page.
+const RESOURCE_PAGE_SIZE: usize = 100;
+
+const URI_PREFIX: &str = "grans://meetings/";
+
+// JSON-RPC error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+/// MCP's code for a resource URI that names nothing.
+const RESOURCE_NOT_FOUND: i64 = -32002;
+
+/// Load the models, then answer messages from stdin until it closes.
+pub fn run(conn: &Connection, fast: bool, tz: FixedOffset) -> Result<()> {
+    let mut session = Session::new(QueryService::start(conn, tz, fast)?);
+    eprintln!("[grans] MCP server ready on stdio");
+
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout().lock();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = session.handle_line(&line) {
+            writeln!(stdout, "{}", response)?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// One client connection: the service plus the negotiated protocol revision.
+struct Session<'c> {
+    service: QueryService<'c>,
+    protocol_version: &'static str,
+}
+
+impl<'c> Session<'c> {
+    fn new(service: QueryService<'c>) -> Self {
+        Session {
+            service,
+            protocol_version: PROTOCOL_VERSIONS[0],
+        }
+    }
+
+    /// Handle one line of input. Notifications get no response.
+    fn handle_line(&mut self, line: &str) -> Option<String> {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(e) => {
+                let err = RpcError::new(PARSE_ERROR, format!("Parse error: {}", e));
+                return Some(error_response(Value::Null, err));
+            }
+        };
+
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // Replies to requests we never send, or not a message at all.
+            let id = message.get("id")?.clone();
+            let err = RpcError::new(INVALID_REQUEST, "Expected a request with a method");
+            return Some(error_response(id, err));
+        };
+        let id = message.get("id")?.clone();
+        let params = message.get("params").cloned().unwrap_or(json!({}));
+
+        log::debug!("mcp {} {}", method, params);
+        Some(match self.dispatch(method, &params) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string(),
+            Err(err) => error_response(id, err),
+        })
+    }
+
+    fn dispatch(&mut self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => Ok(self.initialize(params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_definitions() })),
+            "tools/call" => self.call_tool(params),
+            "resources/list" => self.list_resources(params),
+            "resources/templates/list" => Ok(json!({ "resourceTemplates": resource_templates() })),
+            "resources/read" => self.read_resource(params),
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Method not found: {}", method),
+            )),
+        }
+    }
+
+    /// Agree on the client's revision when we speak it, else offer our newest.
+    fn initialize(&mut self, params: &Value) -> Value {
+        let requested = params.get("protocolVersion").and_then(Value::as_str);
+        self.protocol_version = PROTOCOL_VERSIONS
+            .iter()
+            .find(|v| Some(**v) == requested)
+            .copied()
+            .unwrap_or(PROTOCOL_VERSIONS[0]);
+
+        json!({
+            "protocolVersion": self.protocol_version,
+            "capabilities": { "tools": {}, "resources": {} },
+            "serverInfo": { "name": "grans", "version": env!("CARGO_PKG_VERSION") },
+            "instructions": "Meeting history synced from Granola. Use search_meetings to find \
+                meetings by meaning and grep_meetings for every meeting containing exact words; \
+                both return cited snippets. Read a meeting's full transcript with get_transcript \
+                or the grans://meetings/{id}/transcript resource only when the snippets are not enough.",
+        })
+    }
+
+    fn call_tool(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "tools/call needs a tool name"))?;
+        let args = Params::from_json(params.get("arguments").unwrap_or(&Value::Null));
+
+        self.service.refresh().map_err(internal)?;
+        let outcome = match name {
+            "search_meetings" => args
+                .required("query")
+                .and_then(|q| self.service.search(q, &args)),
+            "grep_meetings" => args
+                .required("query")
+                .and_then(|q| self.service.grep(q, &args)),
+            "show_meeting" => args
+                .required("meeting")
+                .and_then(|m| self.service.show(m, &args)),
+            "meetings_with_person" => args
+                .required("person")
+                .and_then(|p| self.service.with_person(p, &args)),
+            "get_transcript" => args
+                .required("meeting")
+                .and_then(|m| self.service.transcript(m, &args)),
+            _ => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    format!("Unknown tool: {}", name),
+                ));
+            }
+        };
+
+        // A failed call is reported in the result, where the model can see
+        // it and correct its arguments.
+        Ok(match outcome {
+            Ok(value) => self.tool_result(name, value),
+            Err(e) => json!({
+                "content": [{ "type": "text", "text": format!("{:#}", e) }],
+                "isError": true,
+            }),
+        })
+    }
+
+    /// The tool's JSON as text, with a link to each cited meeting and the
+    /// same value as structured content where the revision supports them.
+    fn tool_result(&self, name: &str, value: Value) -> Value {
+        let mut content = vec![json!({
+            "type": "text",
+            "text": crate::output::json::to_json(&value),
+        })];
+        let mut result = json!({ "isError": false });
+
+        if self.protocol_version >= STRUCTURED_CONTENT_VERSION {
+            if matches!(name, "search_meetings" | "grep_meetings") {
+                let meetings = value["meetings"].as_array().into_iter().flatten();
+                content.extend(meetings.filter_map(meeting_link));
+            }
+            if value.is_object() {
+                result["structuredContent"] = value;
+            }
+        }
+        result["content"] = Value::Array(content);
+        result
+    }
+
+    fn list_resources(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let offset = match params.get("cursor").and_then(Value::as_str) {
+            None => 0,
+            Some(cursor) => cursor
+                .parse::<usize>()
+                .map_err(|_| RpcError::new(INVALID_PARAMS, "Invalid cursor"))?,
+        };
+
+        self.service.refresh().map_err(internal)?;
+        let meetings = self
+            .service
+            .list(&Params::default())
+            .map_err(|e| RpcError::new(INTERNAL_ERROR, format!("{:#}", e)))?;
+        let meetings = meetings.as_array().cloned().unwrap_or_default();
+
+        let resources: Vec<Value> = meetings
+            .iter()
+            .skip(offset)
+            .take(RESOURCE_PAGE_SIZE)
+            .filter_map(|m| {
+                let id = m["id"].as_str()?;
+                Some(json!({
+                    "uri": format!("{}{}", URI_PREFIX, id),
+                    "name": m["title"].as_str().unwrap_or("Untitled meeting"),
+                    "description": m["created_at"],
+                    "mimeType": "application/json",
+                }))
+            })
+            .collect();
+
+        let mut result = json!({ "resources": resources });
+        if offset + RESOURCE_PAGE_SIZE < meetings.len() {
+            result["nextCursor"] = json!((offset + RESOURCE_PAGE_SIZE).to_string());
+        }
+        Ok(result)
+    }
+
+    fn read_resource(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let uri = params
+            .get("uri")
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "resources/read needs a uri"))?;
+        let not_found =
+            || RpcError::new(RESOURCE_NOT_FOUND, format!("Resource not found: {}", uri));
+
+        let path = uri.strip_prefix(URI_PREFIX).ok_or_else(not_found)?;
+        let (id, transcript) = match path.strip_suffix("/transcript") {
+            Some(id) => (id, true),
+            None => (path, false),
+        };
+        if id.is_empty() || id.contains('/') {
+            return Err(not_found());
+        }
+
+        self.service.refresh().map_err(internal)?;
+        let outcome = if transcript {
+            self.service.transcript(id, &Params::default())
+        } else {
+            self.service.show(id, &Params::default())
+        };
+        let value = outcome.map_err(|e| match e {
+            ServeError::NotFound(_) => not_found(),
+            ServeError::BadRequest(msg) | ServeError::Forbidden(msg) => {
+                RpcError::new(INVALID_PARAMS, msg)
+            }
+            ServeError::Internal(e) => internal(e),
+        })?;
+
+        Ok(json!({
+            "contents": [{
+                "uri": uri,
+                "mimeType": "application/json",
+                "text": crate::output::json::to_json(&value),
+            }]
+        }))
+    }
+}
+
+fn error_response(id: Value, err: RpcError) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": err.code, "message": err.message },
+    })
+    .to_string()
+}
+
+fn internal(err: anyhow::Error) -> RpcError {
+    RpcError::new(INTERNAL_ERROR, format!("{:#}", err))
+}
+
+/// A `resource_link` citing one shaped meeting card.
+fn meeting_link(meeting: &Value) -> Option<Value> {
+    let id = meeting["id"].as_str()?;
+    Some(json!({
+        "type": "resource_link",
+        "uri": format!("{}{}", URI_PREFIX, id),
+        "name": meeting["title"].as_str().unwrap_or("Untitled meeting"),
+        "mimeType": "application/json",
+    }))
+}
+
+fn resource_templates() -> Value {
+    json!([
+        {
+            "uriTemplate": "grans://meetings/{id}",
+            "name": "Meeting",
+            "description": "A meeting's details, notes and AI panels",
+            "mimeType": "application/json",
+        },
+        {
+            "uriTemplate": "grans://meetings/{id}/transcript",
+            "name": "Meeting transcript",
+            "description": "Every utterance of a meeting, with timestamps and speakers",
+            "mimeType": "application/json",
+        },
+    ])
+}
+
+fn tool_definitions() -> Value {
+    let targets = json!({
+        "type": "array",
+        "items": { "type": "string", "enum": ["titles", "transcripts", "notes", "panels"] },
+        "description": "Where to look (default: everywhere)",
+    });
+    let meeting_filters = json!({
+        "meeting": { "type": "string", "description": "Limit to one meeting (ID or title substring)" },
+        "with": { "type": "string", "description": "Only meetings attended by this person (name or email fragment)" },
+        "company": { "type": "string", "description": "Only meetings with an attendee from this company" },
+        "account": { "type": "string", "description": "Only meetings synced from the Granola account with this email" },
+        "series": { "type": "string", "description": "Only meetings in this recurring series (title, case-insensitive)" },
+        "min_attendees": { "type": "integer", "minimum": 0, "description": "Only meetings with at least this many attendees" },
+    });
+    let date_filters = json!({
+        "from": { "type": "string", "description": "Start date, e.g. 2026-01-15 or a duration such as 2w" },
+        "to": { "type": "string", "description": "End date, e.g. 2026-01-31 or a duration such as 3d" },
+        "date": { "type": "string", "description": "today, yesterday, this-week, last-week, this-month or last-month" },
+        "include_deleted": { "type": "boolean", "description": "Include deleted meetings" },
+    });
+    let card_options = json!({
+        "limit": { "type": "integer", "minimum": 0, "description": "Meetings to return (default 10, 0 = no limit)" },
+        "matches": { "type": "integer", "minimum": 0, "description": "Cited snippets per meeting (default 1)" },
+        "context": { "type": "integer", "minimum": 0, "description": "Neighbouring utterances, sections or paragraphs around each snippet (default 0)" },
+    });
+
+    let mut search = json!({
+        "query": { "type": "string", "description": "What to look for; \"quoted phrases\" must match exactly" },
+        "in": targets,
+        "fast": { "type": "boolean", "description": "Skip the rerank stage" },
+        "min_score": { "type": "number", "description": "Minimum rerank relevance (0-1); not with fast" },
+    });
+    merge(&mut search, &meeting_filters);
+    merge(&mut search, &date_filters);
+    merge(&mut search, &card_options);
+
+    let mut grep = json!({
+        "query": { "type": "string", "description": "Words that must all appear; \"quoted phrases\" must match exactly. Supports OR, -excluded, prefix*, (grouping), NEAR(a b, 5) and title:/transcript:/notes:/panel: scopes" },
+        "in": targets,
+        "speaker": { "type": "string", "description": "Only utterances by this speaker: me, other, or a name" },
+    });
+    merge(&mut grep, &meeting_filters);
+    merge(&mut grep, &date_filters);
+    merge(&mut grep, &card_options);
+
+    let mut with_person = json!({
+        "person": { "type": "string", "description": "Name or email fragment" },
+    });
+    merge(&mut with_person, &date_filters);
+
+    json!([
+        {
+            "name": "search_meetings",
+            "description": "Ranked search by meaning across meeting titles, notes, AI notes and transcripts. Returns the best few meetings, each with cited snippets (source, speaker, timestamp) showing why it matched.",
+            "inputSchema": { "type": "object", "properties": search, "required": ["query"] },
+        },
+        {
+            "name": "grep_meetings",
+            "description": "Every meeting containing all the given words, with the complete match count and cited snippets. Use for exhaustive lookups or to find what a specific speaker said.",
+            "inputSchema": { "type": "object", "properties": grep, "required": ["query"] },
+        },
+        {
+            "name": "show_meeting",
+            "description": "A meeting's details, attendees, notes and AI-generated panels. Does not include the transcript.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "meeting": { "type": "string", "description": "Meeting ID, ID prefix or title substring" } },
+                "required": ["meeting"],
+            },
+        },
+        {
+            "name": "meetings_with_person",
+            "description": "Meetings a person attended, newest first.",
+            "inputSchema": { "type": "object", "properties": with_person, "required": ["person"] },
+        },
+        {
+            "name": "get_transcript",
+            "description": "A meeting's full transcript with timestamps and speakers. Long; prefer the snippets from search_meetings or grep_meetings when they suffice.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "meeting": { "type": "string", "description": "Meeting ID, ID prefix or title substring" },
+                    "speaker": { "type": "string", "description": "Only utterances by this speaker: me, other, or a name" },
+                },
+                "required": ["meeting"],
+            },
+        },
+    ])
+}
+
+fn merge(target: &mut Value, extra: &Value) {
+    if let (Some(target), Some(extra)) = (target.as_object_mut(), extra.as_object()) {
+        target.extend(extra.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_fixtures::{build_test_db, meetings_state};
+
+    fn session(conn: &Connection) -> Session<'_> {
+        let utc = FixedOffset::east_opt(0).unwrap();
+        Session::new(QueryService::with_models(conn, utc, None, None).unwrap())
+    }
+
+    fn request(session: &mut Session, method: &str, params: Value) -> Value {
+        let line = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response = session.handle_line(&line.to_string()).unwrap();
+        serde_json::from_str(&response).unwrap()
+    }
+
+    #[test]
+    fn initialize_negotiates_a_known_revision() {
+        let conn = build_test_db(&meetings_state());
+        let mut s = session(&conn);
+
+        let known = request(
+            &mut s,
+            "initialize",
+            json!({ "protocolVersion": "2025-03-26" }),
+        );
+        let unknown = request(
+            &mut s,
+            "initialize",
+            json!({ "protocolVersion": "1999-01-01" }),
+        );
+
+        assert_eq!(known["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(unknown["result"]["protocolVersion"], PROTOCOL_VERSIONS[0]);
+        assert_eq!(known["result"]["serverInfo"]["name"], "grans");
+    }
+
+    #[test]
+    fn notifications_get_no_response() {
+        let conn = build_test_db(&meetings_state());
+        let mut s = session(&conn);
+
+        let line = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+
+        assert!(s.handle_line(line).is_none());
+    }
+
+    #[test]
+    fn malformed_json_is_a_parse_error() {
+        let conn = build_test_db(&meetings_state());
+        let mut s = session(&conn);
+
+        let response: Value = serde_json::from_str(&s.handle_line("{not json").unwrap()).unwrap();
+
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        assert_eq!(response["id"], Value::Null);
+    }
+
+    #[test]
+    fn unknown_method_is_method_not_found() {
+        let conn = build_test_db(&meetings_state());
+        let mut s = session(&conn);
+
+        let response = request(&mut s, "prompts/list", json!({}));
+
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn tools_list_names_every_tool() {
+        let conn = build_test_db(&meetings_state());
+        let mut s = session(&conn);
+
+        let response = request(&mut s, "tools/list", json!({}));
+
+        let names: Vec<&str> = response["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "search_meetings",
+                "grep_meetings",
+                "show_meeting",
+                "meetings_with_person",
+                "get_transcript"
+            ]
+        );
+    }
+
+    #[test]
+    fn grep_tool_returns_cited_snippets_and_links() {
+        let conn = build_test_db(&meetings_state());
+        let mut s = session(&conn);
+
+        let response = request(
+            &mut s,
+            "tools/call",
+            json!({ "name": "grep_meetings", "arguments": { "query": "Hello", "in": ["transcripts"] } }),
+        );
+
+        let result = &response["result"];
+        assert_eq!(result["isError"], false);
+        let structured = &result["structuredContent"];
+        assert_eq!(structured["total_meetings"], 1);
+        assert!(
+            structured["meetings"][0]["matches"][0]["snippet"]
+                .as_str()
+                .unwrap()
+                .contains("Hello")
+        );
+        assert_eq!(result["content"][1]["type"], "resource_link");
+        assert_eq!(result["content"][1]["uri"], "grans://meetings/doc-1");
+    }
+
+    #[test]
+    fn older_revision_gets_text_content_only() {
+        let conn = build_test_db(&meetings_state());
+        let mut s = session(&conn);
+        request(
+            &mut s,
+            "initialize",
+            json!({ "protocolVersion": "2024-11-05" }),
+        );
+
+        let response = request(
+            &mut s,
+            "tools/call",
+            json!({ "name": "grep_meetings", "arguments": { "query": "Hello" } }),
+        );
+
+        let result = &response["result"];
+        assert!(result.get("structuredContent").is_none());
+        assert_eq!(result["content"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn tool_failure_is_reported_in_the_result() {
+        let conn = build_test_db(&meetings_state());
+        let mut s = session(&conn);
+
+        let response = request(
+            &mut s,
+            "tools/call",
+            json!({ "name": "show_meeting", "arguments": { "meeting": "no-such-meeting" } }),
+        );
+
+        assert_eq!(response["result"]["isError"], true);
+        assert!(
+            response["result"]["content"][0]["text"]
+                .as_str()
+                .unwrap()
+                .contains("no-such-meeting")
+        );
+    }
+
+    #[test]
+    fn unknown_tool_is_invalid_params() {
+        let conn = build_test_db(&meetings_state());
+        let mut s = session(&conn);
+
+        let response = request(&mut s, "tools/call", json!({ "name": "delete_everything" }));
+
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn resources_list_and_read() {
+        let conn = build_test_db(&meetings_state());
+        let mut s = session(&conn);
+
+        let list = request(&mut s, "resources/list", json!({}));
+        let meeting = request(
+            &mut s,
+            "resources/read",
+            json!({ "uri": "grans://meetings/doc-1" }),
+        );
+        let transcript = request(
+            &mut s,
+            "resources/read",
+            json!({ "uri": "grans://meetings/doc-1/transcript" }),
+        );
+
+        assert_eq!(list["result"]["resources"].as_array().unwrap().len(), 2);
+        assert!(list["result"].get("nextCursor").is_none());
+        let text = meeting["result"]["contents"][0]["text"].as_str().unwrap();
+        assert!(text.contains("AI Strategy Meeting"));
+        let text = transcript["result"]["contents"][0]["text"]
+            .as_str()
+            .unwrap();
+        assert!(text.contains("Hello everyone"));
+    }
+
+    #[test]
+    fn unknown_resource_is_not_found() {
+        let conn = build_test_db(&meetings_state());
+        let mut s = session(&conn);
+
+        let response = request(
+            &mut s,
+            "resources/read",
+            json!({ "uri": "grans://meetings/nope/transcript" }),
+        );
+
+        assert_eq!(response["error"]["code"], RESOURCE_NOT_FOUND);
+    }
+}
@@ -0,0 +1,317 @@
+//! `grans serve`: a local HTTP server that keeps the search models warm.
+//!
+//! Every `grans search` pays for loading the embedding index, the embedder
+//! and the reranker before it can answer. The server pays once at startup
+//! and then answers each request from memory, returning the same JSON the
+//! CLI prints with `--json`. `grans mcp` ([`mcp`]) answers the same
+//! queries for AI assistants over the Model Context Protocol.
+//!
+//! Each connection is read and answered on its own thread, so a client
+//! that stalls holds up only itself. The queries themselves run one at a
+//! time on the main thread: the ONNX models are not `Sync`, and a single
+//! user's editor or script rarely has more than one query in flight, so a
+//! queue of one costs nothing in practice.
+//!
+//! Requests must name the server by the address they arrived on (or
+//! `localhost`), and browsers may not send them from another origin, so a
+//! web page cannot rebind its own host name to loopback and read meetings.
+
+mod http;
+pub mod mcp;
+pub mod params;
+pub mod service;
+
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use chrono::FixedOffset;
+use rusqlite::Connection;
+use thiserror::Error;
+
+use http::Request;
+use params::Params;
+use service::QueryService;
+
+/// Default `--listen` address: loopback only, since there is no auth.
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:7373";
+
+/// How long a client may take to send its request line and headers.
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a client may take to accept the response.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why a request could not be answered. Each variant maps to one status.
+#[derive(Error, Debug)]
+pub enum ServeError {
+    #[error("{0}")]
+    BadRequest(String),
+
+    #[error("{0}")]
+    Forbidden(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl ServeError {
+    pub fn status(&self) -> u16 {
+        match self {
+            ServeError::BadRequest(_) => 400,
+            ServeError::Forbidden(_) => 403,
+            ServeError::NotFound(_) => 404,
+            ServeError::Internal(_) => 500,
+        }
+    }
+}
+
+/// Bind `listen`, load the models, and serve until the process is killed.
+pub fn run(conn: &Connection, listen: &str, fast: bool, tz: FixedOffset) -> Result<()> {
+    let listener =
+        TcpListener::bind(listen).with_context(|| format!("Failed to listen on {}", listen))?;
+    let addr = listener.local_addr()?;
+    if !addr.ip().is_loopback() {
+        eprintln!(
+            "[grans] Warning: listening on {} exposes your meetings to the network without authentication",
+            addr
+        );
+    }
+
+    let mut service = QueryService::start(conn, tz, fast)?;
+    if !service.reranks() {
+        eprintln!("[grans] Reranker not loaded (--fast); searches keep fusion order");
+    }
+    eprintln!("[grans] Listening on http://{}", addr);
+
+    let (queue, jobs) = mpsc::channel();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let queue = queue.clone();
+                    thread::spawn(move || handle_connection(stream, addr, &queue));
+                }
+                Err(e) => log::debug!("Failed to accept connection: {}", e),
+            }
+        }
+    });
+
+    for Job { request, reply } in jobs {
+        let _ = reply.send(route(&mut service, &request));
+    }
+    Ok(())
+}
+
+/// A request waiting for the main thread, and where to send its answer.
+struct Job {
+    request: Request,
+    reply: Sender<(u16, String)>,
+}
+
+fn handle_connection(mut stream: TcpStream, addr: SocketAddr, queue: &Sender<Job>) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(WRITE_TIMEOUT));
+    // Bound to every interface, the address is the one this client reached.
+    let local = stream.local_addr().unwrap_or(addr);
+
+    let (status, body) = match http::read_request(&stream) {
+        Ok(request) => {
+            log::debug!(
+                "{} {} {}?{}",
+                local,
+                request.method,
+                request.path,
+                request.query
+            );
+            match check_host(&request, local) {
+                Ok(()) => {
+                    let (reply, answer) = mpsc::channel();
+                    if queue.send(Job { request, reply }).is_err() {
+                        return;
+                    }
+                    let Ok(answer) = answer.recv() else {
+                        return;
+                    };
+                    answer
+                }
+                Err(e) => error_body(&e),
+            }
+        }
+        Err(e) => error_body(&ServeError::BadRequest(e.to_string())),
+    };
+
+    if let Err(e) = http::write_json(&mut stream, status, &body) {
+        log::debug!("Failed to write response: {}", e);
+    }
+}
+
+/// Refuse a request whose `Host` is not the address it arrived on (or
+/// `localhost`, on loopback), or that a browser sent from another origin.
+fn check_host(request: &Request, local: SocketAddr) -> Result<(), ServeError> {
+    let is_local = |host: &str| {
+        let host = host.to_ascii_lowercase();
+        host == local.to_string()
+            || (local.ip().is_loopback() && host == format!("localhost:{}", local.port()))
+    };
+
+    let Some(host) = request.host.as_deref() else {
+        return Err(ServeError::Forbidden(
+            "Requests must send a Host header".to_string(),
+        ));
+    };
+    if !is_local(host) {
+        return Err(ServeError::Forbidden(format!(
+            "Host {} is not this server; use {} or localhost",
+            host, local
+        )));
+    }
+    if let Some(origin) = request.origin.as_deref()
+        && !origin.strip_prefix("http://").is_some_and(is_local)
+    {
+        return Err(ServeError::Forbidden(format!(
+            "Requests from {} are not allowed",
+            origin
+        )));
+    }
+    Ok(())
+}
+
+/// Dispatch a request to its endpoint and render the status and JSON body.
+fn route(service: &mut QueryService, request: &Request) -> (u16, String) {
+    if request.method != "GET" {
+        return (
+            405,
+            error_json(&format!("{} is not supported; use GET", request.method)),
+        );
+    }
+
+    let params = Params::from_query(&request.query);
+    let result = match request.path.as_str() {
+        "/health" => Ok(serde_json::json!({ "status": "ok" })),
+        path => service
+            .refresh()
+            .map_err(ServeError::from)
+            .and_then(|()| match path {
+                "/search" => service.search(params.required("q")?, &params),
+                "/grep" => service.grep(params.required("q")?, &params),
+                "/list" => service.list(&params),
+                "/show" => service.show(params.required("meeting")?, &params),
+                "/people" => service.people(&params),
+                _ => Err(ServeError::NotFound(format!("No endpoint at {}", path))),
+            }),
+    };
+
+    match result {
+        Ok(value) => (200, crate::output::json::to_json(&value)),
+        Err(e) => error_body(&e),
+    }
+}
+
+fn error_body(err: &ServeError) -> (u16, String) {
+    if let ServeError::Internal(e) = err {
+        log::debug!("Request failed: {:#}", e);
+    }
+    (err.status(), error_json(&format!("{:#}", err)))
+}
+
+fn error_json(message: &str) -> String {
+    crate::output::json::to_json(&serde_json::json!({ "error": message }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_fixtures::{build_test_db, meetings_state};
+
+    fn get(service: &mut QueryService, target: &str) -> (u16, serde_json::Value) {
+        let request = Request::parse_line(&format!("GET {} HTTP/1.1", target)).unwrap();
+        let (status, body) = route(service, &request);
+        (status, serde_json::from_str(&body).unwrap())
+    }
+
+    fn utc() -> FixedOffset {
+        FixedOffset::east_opt(0).unwrap()
+    }
+
+    #[test]
+    fn routes_known_paths() {
+        let conn = build_test_db(&meetings_state());
+        let mut service = QueryService::with_models(&conn, utc(), None, None).unwrap();
+
+        assert_eq!(get(&mut service, "/health").0, 200);
+        let (status, body) = get(&mut service, "/grep?q=Hello");
+        assert_eq!(status, 200);
+        assert_eq!(body["total_meetings"], 1);
+    }
+
+    #[test]
+    fn unknown_path_is_not_found() {
+        let conn = build_test_db(&meetings_state());
+        let mut service = QueryService::with_models(&conn, utc(), None, None).unwrap();
+
+        let (status, body) = get(&mut service, "/nope");
+
+        assert_eq!(status, 404);
+        assert!(body["error"].as_str().unwrap().contains("/nope"));
+    }
+
+    #[test]
+    fn bad_parameters_are_400_with_a_message() {
+        let conn = build_test_db(&meetings_state());
+        let mut service = QueryService::with_models(&conn, utc(), None, None).unwrap();
+
+        let (status, body) = get(&mut service, "/search?q=x&limit=many");
+
+        assert_eq!(status, 400);
+        assert!(body["error"].as_str().unwrap().contains("'limit'"));
+    }
+
+    #[test]
+    fn only_requests_naming_this_server_are_allowed() {
+        let local: SocketAddr = "127.0.0.1:7373".parse().unwrap();
+        let request = |host: Option<&str>, origin: Option<&str>| Request {
+            host: host.map(str::to_string),
+            origin: origin.map(str::to_string),
+            ..Request::parse_line("GET /health HTTP/1.1").unwrap()
+        };
+        let allowed = |host, origin| check_host(&request(host, origin), local).is_ok();
+
+        assert!(allowed(Some("127.0.0.1:7373"), None));
+        assert!(allowed(Some("LocalHost:7373"), None));
+        assert!(allowed(
+            Some("localhost:7373"),
+            Some("http://localhost:7373")
+        ));
+
+        assert!(!allowed(None, None));
+        assert!(!allowed(Some("rebound.example:7373"), None));
+        assert!(!allowed(Some("localhost:8080"), None));
+        assert!(!allowed(
+            Some("127.0.0.1:7373"),
+            Some("http://rebound.example:7373")
+        ));
+        assert!(!allowed(Some("127.0.0.1:7373"), Some("null")));
+
+        let status = check_host(&request(Some("evil.example"), None), local)
+            .unwrap_err()
+            .status();
+        assert_eq!(status, 403);
+    }
+
+    #[test]
+    fn non_get_is_rejected() {
+        let conn = build_test_db(&meetings_state());
+        let mut service = QueryService::with_models(&conn, utc(), None, None).unwrap();
+        let request = Request::parse_line("POST /search HTTP/1.1").unwrap();
+
+        let (status, _) = route(&mut service, &request);
+
+        assert_eq!(status, 405);
+    }
+}
@@ -0,0 +1,218 @@
+//! Request parameters, parsed the way the matching CLI flags are.
+//!
+//! Values arrive as strings (a query string) or loosely typed JSON, so both
+//! are flattened into one string map and converted on access. A value that
+//! does not parse is a [`ServeError::BadRequest`] naming the parameter, the
+//! same way clap names the flag.
+
+use std::collections::HashMap;
+
+use clap::ValueEnum;
+
+use super::ServeError;
+use crate::query::filter::SearchTarget;
+use crate::query::speaker::SpeakerSelector;
+
+#[derive(Debug, Default, Clone)]
+pub struct Params {
+    values: HashMap<String, String>,
+}
+
+impl Params {
+    /// Parameters from a URL query string. A repeated key keeps its last value.
+    pub fn from_query(query: &str) -> Self {
+        Params {
+            values: url::form_urlencoded::parse(query.as_bytes())
+                .into_owned()
+                .collect(),
+        }
+    }
+
+    /// Parameters from a JSON object. Arrays are joined with commas, so
+    /// `"in": ["notes", "panels"]` reads like `in=notes,panels`.
+    pub fn from_json(value: &serde_json::Value) -> Self {
+        let values = value
+            .as_object()
+            .map(|obj| {
+                obj.iter()
+                    .filter_map(|(key, v)| Some((key.clone(), json_to_string(v)?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Params { values }
+    }
+
+    /// A parameter's value; blank counts as absent.
+    pub fn str(&self, key: &str) -> Option<&str> {
+        self.values
+            .get(key)
+            .map(|v| v.trim())
+            .filter(|v| !v.is_empty())
+    }
+
+    pub fn string(&self, key: &str) -> Option<String> {
+        self.str(key).map(String::from)
+    }
+
+    pub fn required(&self, key: &str) -> Result<&str, ServeError> {
+        self.str(key)
+            .ok_or_else(|| ServeError::BadRequest(format!("missing required parameter '{}'", key)))
+    }
+
+    pub fn usize_or(&self, key: &str, default: usize) -> Result<usize, ServeError> {
+        match self.str(key) {
+            None => Ok(default),
+            Some(v) => v
+                .parse()
+                .map_err(|_| invalid(key, v, "a non-negative integer")),
+        }
+    }
+
+    pub fn usize(&self, key: &str) -> Result<Option<usize>, ServeError> {
+        self.str(key)
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| invalid(key, v, "a non-negative integer"))
+            })
+            .transpose()
+    }
+
+    pub fn f32(&self, key: &str) -> Result<Option<f32>, ServeError> {
+        self.str(key)
+            .map(|v| v.parse().map_err(|_| invalid(key, v, "a number")))
+            .transpose()
+    }
+
+    /// A boolean switch: absent is false; `true`/`1`/`yes` and
+    /// `false`/`0`/`no` are accepted.
+    pub fn flag(&self, key: &str) -> Result<bool, ServeError> {
+        match self.str(key).map(str::to_lowercase).as_deref() {
+            None | Some("false") | Some("0") | Some("no") => Ok(false),
+            Some("true") | Some("1") | Some("yes") => Ok(true),
+            Some(v) => Err(invalid(key, v, "true or false")),
+        }
+    }
+
+    /// The `--in` target list; every target when absent.
+    pub fn targets(&self, key: &str) -> Result<Vec<SearchTarget>, ServeError> {
+        let Some(raw) = self.str(key) else {
+            return Ok(SearchTarget::all());
+        };
+        raw.split(',')
+            .map(|t| {
+                SearchTarget::from_str(t.trim(), true).map_err(|_| {
+                    invalid(
+                        key,
+                        t,
+                        &format!(
+                            "one of {}",
+                            crate::query::filter::DEFAULT_SEARCH_TARGETS.replace(',', ", ")
+                        ),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    pub fn speaker(&self, key: &str) -> Option<SpeakerSelector> {
+        self.str(key).and_then(SpeakerSelector::parse)
+    }
+}
+
+fn json_to_string(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Array(items) => Some(
+            items
+                .iter()
+                .filter_map(json_to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        other => Some(other.to_string()),
+    }
+}
+
+fn invalid(key: &str, value: &str, expected: &str) -> ServeError {
+    ServeError::BadRequest(format!(
+        "invalid value '{}' for '{}': expected {}",
+        value, key, expected
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_string_values_are_decoded() {
+        let params = Params::from_query("q=budget+review&meeting=Q1%20Plan");
+
+        assert_eq!(params.str("q"), Some("budget review"));
+        assert_eq!(params.str("meeting"), Some("Q1 Plan"));
+    }
+
+    #[test]
+    fn blank_value_counts_as_absent() {
+        let params = Params::from_query("q=&limit=");
+
+        assert_eq!(params.str("q"), None);
+        assert_eq!(params.usize_or("limit", 10).unwrap(), 10);
+        assert!(params.required("q").is_err());
+    }
+
+    #[test]
+    fn numbers_parse_or_name_the_parameter() {
+        let params = Params::from_query("limit=5&min_score=0.4&matches=lots");
+
+        assert_eq!(params.usize_or("limit", 10).unwrap(), 5);
+        assert_eq!(params.f32("min_score").unwrap(), Some(0.4));
+        let err = params.usize_or("matches", 1).unwrap_err();
+        assert!(err.to_string().contains("'matches'"));
+    }
+
+    #[test]
+    fn flags_accept_common_spellings() {
+        let params = Params::from_query("a=true&b=1&c=no&d=maybe");
+
+        assert!(params.flag("a").unwrap());
+        assert!(params.flag("b").unwrap());
+        assert!(!params.flag("c").unwrap());
+        assert!(!params.flag("missing").unwrap());
+        assert!(params.flag("d").is_err());
+    }
+
+    #[test]
+    fn targets_default_to_all_and_reject_unknown() {
+        assert_eq!(
+            Params::default().targets("in").unwrap(),
+            SearchTarget::all()
+        );
+        assert_eq!(
+            Params::from_query("in=notes,panels").targets("in").unwrap(),
+            vec![SearchTarget::Notes, SearchTarget::Panels]
+        );
+        assert!(Params::from_query("in=slides").targets("in").is_err());
+    }
+
+    #[test]
+    fn json_arguments_flatten_to_strings() {
+        let params = Params::from_json(&serde_json::json!({
+            "query": "budget",
+            "limit": 3,
+            "fast": true,
+            "in": ["notes", "panels"],
+            "meeting": null
+        }));
+
+        assert_eq!(params.str("query"), Some("budget"));
+        assert_eq!(params.usize_or("limit", 10).unwrap(), 3);
+        assert!(params.flag("fast").unwrap());
+        assert_eq!(
+            params.targets("in").unwrap(),
+            vec![SearchTarget::Notes, SearchTarget::Panels]
+        );
+        assert_eq!(params.str("meeting"), None);
+    }
+}
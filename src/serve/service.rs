@@ -0,0 +1,463 @@
+//! The query side of `grans serve`: models loaded once, answers per request.
+//!
+//! Each method takes loosely typed [`Params`] and returns the JSON value the
+//! matching CLI command prints with `--json`, so a client can switch between
+//! the two without reshaping anything.
+
+use chrono::{FixedOffset, Utc};
+use rusqlite::Connection;
+use serde_json::Value;
+
+use super::ServeError;
+use super::params::Params;
+use crate::commands::grep::GrepOptions;
+use crate::commands::search::{FilterEcho, SearchModels, SearchOptions};
+use crate::embed::EmbeddingIndex;
+use crate::embed::model::{Embedder, FastEmbedModel};
+use crate::embed::rerank::{DEFAULT_RERANK_MODEL, FastEmbedReranker, Reranker};
+use crate::query::dates::{DateRange, build_date_range};
+use crate::query::filter::MeetingFilters;
+
+/// Results per page when the request does not say.
+const DEFAULT_LIMIT: usize = 10;
+
+/// Warm search state over one database connection.
+pub struct QueryService<'c> {
+    conn: &'c Connection,
+    tz: FixedOffset,
+    index: EmbeddingIndex,
+    /// [`crate::embed::store::vectors_fingerprint`] when `index` was loaded.
+    fingerprint: String,
+    embedder: Option<Box<dyn Embedder>>,
+    reranker: Option<Box<dyn Reranker>>,
+    /// Whether to load the production embedder once the index has vectors.
+    /// Off in tests, which bring their own models.
+    load_embedder: bool,
+}
+
+impl<'c> QueryService<'c> {
+    /// Load the index and the production models. `fast` skips the reranker,
+    /// so every search keeps fusion order.
+    pub fn start(conn: &'c Connection, tz: FixedOffset, fast: bool) -> anyhow::Result<Self> {
+        let reranker = if fast {
+            None
+        } else {
+            Some(Box::new(FastEmbedReranker::new(DEFAULT_RERANK_MODEL)?) as Box<dyn Reranker>)
+        };
+        let mut service = QueryService {
+            conn,
+            tz,
+            index: EmbeddingIndex {
+                vectors: Vec::new(),
+                stats: None,
+                ann: None,
+            },
+            fingerprint: String::new(),
+            embedder: None,
+            reranker,
+            load_embedder: true,
+        };
+        service.reload_index()?;
+        Ok(service)
+    }
+
+    /// A service over caller-supplied models; the index is loaded as usual.
+    #[cfg(test)]
+    pub fn with_models(
+        conn: &'c Connection,
+        tz: FixedOffset,
+        embedder: Option<Box<dyn Embedder>>,
+        reranker: Option<Box<dyn Reranker>>,
+    ) -> anyhow::Result<Self> {
+        let mut service = QueryService {
+            conn,
+            tz,
+            index: EmbeddingIndex {
+                vectors: Vec::new(),
+                stats: None,
+                ann: None,
+            },
+            fingerprint: String::new(),
+            embedder,
+            reranker,
+            load_embedder: false,
+        };
+        service.reload_index()?;
+        Ok(service)
+    }
+
+    /// Reload the in-memory index if `grans embed` has changed the stored
+    /// vectors since it was loaded. Cheap when nothing changed: one
+    /// aggregate query.
+    pub fn refresh(&mut self) -> anyhow::Result<()> {
+        if crate::embed::store::vectors_fingerprint(self.conn)? != self.fingerprint {
+            self.reload_index()?;
+        }
+        Ok(())
+    }
+
+    fn reload_index(&mut self) -> anyhow::Result<()> {
+        self.fingerprint = crate::embed::store::vectors_fingerprint(self.conn)?;
+        let model = crate::embed::model::stored_or_default(self.conn);
+        let (index, freshness) = crate::embed::freshness::load_search_index(self.conn, model.name)?;
+        if let Some(warning) = crate::commands::search::freshness_warning(&freshness) {
+            eprintln!("[grans] {}", warning);
+        }
+        log::debug!("Loaded embedding index: {} vectors", index.len());
+
+        // As in `grans search`, an empty index never pays for the embedder.
+        // A re-embed with another model swaps it.
+        let stale = self
+            .embedder
+            .as_ref()
+            .is_none_or(|e| e.model_name() != model.name);
+        if stale && self.load_embedder && !index.is_empty() {
+            self.embedder = Some(Box::new(FastEmbedModel::new(model)?));
+        }
+        self.index = index;
+        Ok(())
+    }
+
+    /// Whether searches rerank by default.
+    pub fn reranks(&self) -> bool {
+        self.reranker.is_some()
+    }
+
+    /// Ranked search, shaped like `grans search --json`.
+    pub fn search(&self, query: &str, params: &Params) -> Result<Value, ServeError> {
+        let fast = params.flag("fast")?;
+        let min_score = params.f32("min_score")?;
+        if fast && min_score.is_some() {
+            return Err(ServeError::BadRequest(
+                "'min_score' filters on rerank scores and cannot be combined with 'fast'".into(),
+            ));
+        }
+        let rerank = !fast && self.reranker.is_some();
+
+        let echo = FilterEcho {
+            in_targets: params.targets("in")?,
+            meeting: params.string("meeting"),
+            with: params.string("with"),
+            company: params.string("company"),
+            account: params.string("account"),
+            series: params.string("series"),
+            min_attendees: params.usize("min_attendees")?,
+            date: params.string("date"),
+            from: params.string("from"),
+            to: params.string("to"),
+            include_deleted: params.flag("include_deleted")?,
+        };
+        let opts = SearchOptions::from_cli_args(
+            !rerank,
+            min_score,
+            params.usize_or("context", 0)?,
+            params.usize_or("limit", DEFAULT_LIMIT)?,
+            params.usize_or("matches", 1)?,
+            echo,
+        );
+        let models = SearchModels {
+            embedder: self.embedder.as_deref(),
+            index: &self.index,
+            reranker: if rerank {
+                self.reranker.as_deref()
+            } else {
+                None
+            },
+        };
+
+        let (shaped, keyword_total) = crate::commands::search::ranked_meetings(
+            self.conn,
+            &models,
+            query,
+            &opts,
+            self.date_range(params).as_ref(),
+            opts.echo.include_deleted,
+        )?;
+        to_value(crate::output::json::search_response(
+            &shaped,
+            query,
+            keyword_total,
+            opts.limit,
+        ))
+    }
+
+    /// Complete lexical lookup, shaped like `grans grep --json`.
+    pub fn grep(&self, query: &str, params: &Params) -> Result<Value, ServeError> {
+        let targets = params.targets("in")?;
+        let speaker = params.speaker("speaker");
+        crate::commands::grep::check_speaker_targets(speaker.is_some(), &targets)
+            .map_err(bad_request)?;
+        let speaker =
+            crate::query::speaker::resolve_opt(self.conn, speaker.as_ref()).map_err(bad_request)?;
+
+        let opts = GrepOptions {
+            targets,
+            filters: MeetingFilters {
+                meeting: params.string("meeting"),
+                with: params.string("with"),
+                company: params.string("company"),
+                account: params.string("account"),
+                series: params.string("series"),
+                min_attendees: params.usize("min_attendees")?,
+            },
+            limit: params.usize_or("limit", DEFAULT_LIMIT)?,
+            matches: params.usize_or("matches", 1)?,
+            speaker,
+            context: params.usize_or("context", 0)?,
+        };
+        let limit = opts.limit;
+
+        let (shaped, total) = crate::commands::grep::grep_meetings(
+            self.conn,
+            query,
+            opts,
+            self.date_range(params).as_ref(),
+            params.flag("include_deleted")?,
+        )?;
+        to_value(crate::output::json::grep_response(
+            &shaped, query, total, limit,
+        ))
+    }
+
+    /// Meetings in a date range, shaped like `grans list --json`.
+    pub fn list(&self, params: &Params) -> Result<Value, ServeError> {
+        let docs = crate::db::meetings::list_meetings(
+            self.conn,
+            params.str("person"),
+            self.date_range(params).as_ref(),
+            params.flag("include_deleted")?,
+        )?;
+        to_value(docs)
+    }
+
+    /// One meeting with its panels, shaped like `grans show --json`. With
+    /// `transcript` set, the utterances are attached under `transcript`.
+    pub fn show(&self, meeting: &str, params: &Params) -> Result<Value, ServeError> {
+        let doc = self.find_meeting(meeting)?;
+        let doc_id = doc.id.as_deref().unwrap_or("");
+
+        let panels = crate::db::panels::load_panels(self.conn, doc_id)?;
+        let mut detail = crate::output::json::meeting_detail_value(&doc, &panels);
+        if params.flag("transcript")? {
+            let transcript = crate::db::meetings::get_transcript(self.conn, doc_id)?;
+            detail["transcript"] = serde_json::json!(transcript);
+        }
+        Ok(detail)
+    }
+
+    /// Meetings with a person, shaped like `grans with --json`.
+    pub fn with_person(&self, person: &str, params: &Params) -> Result<Value, ServeError> {
+        let docs = crate::commands::meetings::meetings_with_person(
+            self.conn,
+            person,
+            self.date_range(params).as_ref(),
+            params.flag("include_deleted")?,
+        )?;
+        to_value(docs)
+    }
+
+    /// A meeting's full transcript, optionally narrowed to one speaker.
+    pub fn transcript(&self, meeting: &str, params: &Params) -> Result<Value, ServeError> {
+        let doc = self.find_meeting(meeting)?;
+        let doc_id = doc.id.as_deref().unwrap_or("");
+        let speaker =
+            crate::query::speaker::resolve_opt(self.conn, params.speaker("speaker").as_ref())
+                .map_err(bad_request)?;
+
+        let transcript = crate::commands::meetings::filter_by_speaker(
+            crate::db::meetings::get_transcript(self.conn, doc_id)?,
+            speaker.as_ref(),
+        );
+        Ok(serde_json::json!({
+            "id": doc.id,
+            "title": doc.title,
+            "created_at": doc.created_at,
+            "transcript": transcript,
+        }))
+    }
+
+    /// People matching `q`, or everyone (optionally at one company), shaped
+    /// like `grans browse people --json`.
+    pub fn people(&self, params: &Params) -> Result<Value, ServeError> {
+        let people = match params.str("q") {
+            Some(q) => crate::db::people::find_person(self.conn, q)?,
+            None => crate::db::people::list_people(self.conn, params.str("company"))?,
+        };
+        to_value(people)
+    }
+
+    fn find_meeting(&self, query: &str) -> Result<crate::models::Document, ServeError> {
+        crate::db::meetings::show_meeting(self.conn, query)?
+            .ok_or_else(|| ServeError::NotFound(format!("No meeting found matching \"{}\"", query)))
+    }
+
+    fn date_range(&self, params: &Params) -> Option<DateRange> {
+        build_date_range(
+            params.str("from"),
+            params.str("to"),
+            params.str("date"),
+            Utc::now(),
+            &self.tz,
+        )
+    }
+}
+
+fn to_value(value: impl serde::Serialize) -> Result<Value, ServeError> {
+    Ok(serde_json::to_value(value).map_err(anyhow::Error::from)?)
+}
+
+/// Errors in what the client asked for (an unknown speaker, a speaker filter
+/// without transcripts) rather than in the server.
+fn bad_request(err: anyhow::Error) -> ServeError {
+    ServeError::BadRequest(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_fixtures::{build_test_db, meetings_state};
+    use crate::embed::rerank::MockReranker;
+
+    fn utc() -> FixedOffset {
+        FixedOffset::east_opt(0).unwrap()
+    }
+
+    fn service(conn: &Connection) -> QueryService<'_> {
+        QueryService::with_models(conn, utc(), None, Some(Box::new(MockReranker))).unwrap()
+    }
+
+    #[test]
+    fn grep_returns_the_cli_envelope() {
+        let conn = build_test_db(&meetings_state());
+        let svc = service(&conn);
+
+        let value = svc.grep("Hello", &Params::default()).unwrap();
+
+        assert_eq!(value["query"], "Hello");
+        assert_eq!(value["total_meetings"], 1);
+        assert_eq!(value["limit"], DEFAULT_LIMIT);
+        assert_eq!(value["meetings"][0]["id"], "doc-1");
+    }
+
+    #[test]
+    fn grep_speaker_without_transcripts_is_a_bad_request() {
+        let conn = build_test_db(&meetings_state());
+        let svc = service(&conn);
+
+        let err = svc
+            .grep("Hello", &Params::from_query("speaker=me&in=notes"))
+            .unwrap_err();
+
+        assert!(matches!(err, ServeError::BadRequest(_)));
+    }
+
+    #[test]
+    fn search_without_vectors_is_keyword_only() {
+        let conn = build_test_db(&meetings_state());
+        let svc = service(&conn);
+
+        let value = svc
+            .search("Hello", &Params::from_query("fast=true"))
+            .unwrap();
+
+        assert_eq!(value["query"], "Hello");
+        assert_eq!(value["keyword_total"], 1);
+        assert_eq!(value["meetings"][0]["id"], "doc-1");
+    }
+
+    #[test]
+    fn search_rejects_an_unknown_target() {
+        let conn = build_test_db(&meetings_state());
+        let svc = service(&conn);
+
+        let err = svc
+            .search("Hello", &Params::from_query("in=slides"))
+            .unwrap_err();
+
+        assert!(matches!(err, ServeError::BadRequest(_)));
+    }
+
+    #[test]
+    fn min_score_with_fast_is_rejected() {
+        let conn = build_test_db(&meetings_state());
+        let svc = service(&conn);
+
+        let err = svc
+            .search("Hello", &Params::from_query("fast=1&min_score=0.5"))
+            .unwrap_err();
+
+        assert!(err.to_string().contains("min_score"));
+    }
+
+    #[test]
+    fn list_and_people_match_cli_json() {
+        let conn = build_test_db(&meetings_state());
+        let svc = service(&conn);
+
+        let meetings = svc.list(&Params::default()).unwrap();
+        let people = svc.people(&Params::from_query("q=Bob")).unwrap();
+
+        assert_eq!(meetings.as_array().unwrap().len(), 2);
+        assert!(people.is_array());
+    }
+
+    #[test]
+    fn show_attaches_transcript_on_request() {
+        let conn = build_test_db(&meetings_state());
+        let svc = service(&conn);
+
+        let plain = svc.show("doc-1", &Params::default()).unwrap();
+        let with_transcript = svc
+            .show("doc-1", &Params::from_query("transcript=true"))
+            .unwrap();
+
+        assert_eq!(plain["title"], "AI Strategy Meeting");
+        assert!(plain.get("transcript").is_none());
+        assert_eq!(with_transcript["transcript"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn transcript_filters_by_speaker() {
+        let conn = build_test_db(&meetings_state());
+        let svc = service(&conn);
+
+        let value = svc
+            .transcript("doc-1", &Params::from_query("speaker=me"))
+            .unwrap();
+
+        let utterances = value["transcript"].as_array().unwrap();
+        assert_eq!(utterances.len(), 1);
+        assert_eq!(utterances[0]["text"], "Hello everyone");
+    }
+
+    #[test]
+    fn with_person_lists_their_meetings() {
+        let conn = build_test_db(&meetings_state());
+        let svc = service(&conn);
+
+        let value = svc.with_person("Bob", &Params::default()).unwrap();
+
+        assert_eq!(value[0]["id"], "doc-1");
+    }
+
+    #[test]
+    fn show_unknown_meeting_is_not_found() {
+        let conn = build_test_db(&meetings_state());
+        let svc = service(&conn);
+
+        let err = svc.show("nope", &Params::default()).unwrap_err();
+
+        assert!(matches!(err, ServeError::NotFound(_)));
+    }
+
+    #[test]
+    fn refresh_is_a_no_op_when_vectors_are_unchanged() {
+        let conn = build_test_db(&meetings_state());
+        let mut svc = service(&conn);
+        let before = svc.fingerprint.clone();
+
+        svc.refresh().unwrap();
+
+        assert_eq!(svc.fingerprint, before);
+    }
+}
@@ -1,18 +1,20 @@
 //! Sync configuration management.
 //!
-//! Stores OAuth tokens and sync state in `~/.local/share/grans/sync.toml`.
+//! Stores the chosen remote, its credentials and sync state in
+//! `~/.local/share/grans/sync.toml`.
 
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
+use super::snapshot::DEFAULT_SNAPSHOTS_KEPT;
 use super::{SyncError, SyncResult};
 use crate::platform::data_dir;
 
-/// Configuration for Dropbox sync.
+/// Configuration for remote sync.
 #[derive(Serialize, Deserialize, Default, Debug)]
 pub struct SyncConfig {
-    /// OAuth refresh token (long-lived)
+    /// Dropbox OAuth refresh token (long-lived)
     pub refresh_token: Option<String>,
 
     /// Unix timestamp of last successful push
@@ -26,6 +28,59 @@ pub struct SyncConfig {
     /// The reference point for deciding which side has changed. Absent until
     /// the first push or pull completes under a version that records it.
     pub last_synced_hash: Option<String>,
+
+    /// Where push and pull replicate the database. Absent means Dropbox,
+    /// the only backend before others existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<RemoteConfig>,
+
+    /// Fingerprint of the key pushes are encrypted with; absent when they
+    /// are not. The key itself is in the keychain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_key: Option<String>,
+
+    /// How many snapshots of the remote database push keeps; absent means
+    /// [`DEFAULT_SNAPSHOTS_KEPT`], and 0 takes none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshots_kept: Option<usize>,
+}
+
+/// A configured remote, as chosen with `grans remote init`.
+///
+/// Secrets are kept here, like the Dropbox refresh token, in a file only
+/// the owner can read.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum RemoteConfig {
+    Dropbox,
+    Folder {
+        path: PathBuf,
+    },
+    Webdav {
+        url: String,
+        username: Option<String>,
+        password: Option<String>,
+    },
+    S3 {
+        bucket: String,
+        region: String,
+        endpoint: Option<String>,
+        prefix: Option<String>,
+        access_key_id: String,
+        secret_access_key: String,
+    },
+}
+
+impl RemoteConfig {
+    /// The backend's name for messages.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Dropbox => "Dropbox",
+            Self::Folder { .. } => "folder",
+            Self::Webdav { .. } => "WebDAV",
+            Self::S3 { .. } => "S3",
+        }
+    }
 }
 
 impl SyncConfig {
@@ -73,11 +128,43 @@ impl SyncConfig {
         Ok(())
     }
 
-    /// Check if we have authentication credentials.
+    /// Check if we have Dropbox authentication credentials.
     pub fn is_authenticated(&self) -> bool {
         self.refresh_token.is_some()
     }
 
+    /// The configured remote, Dropbox unless another was chosen.
+    pub fn remote(&self) -> RemoteConfig {
+        self.remote.clone().unwrap_or(RemoteConfig::Dropbox)
+    }
+
+    /// How many snapshots of the remote database push keeps.
+    pub fn snapshots_kept(&self) -> usize {
+        self.snapshots_kept.unwrap_or(DEFAULT_SNAPSHOTS_KEPT)
+    }
+
+    /// Whether the configured remote can be reached without further setup:
+    /// Dropbox needs a sign-in, other backends only their configuration.
+    pub fn is_connected(&self) -> bool {
+        match self.remote() {
+            RemoteConfig::Dropbox => self.is_authenticated(),
+            _ => true,
+        }
+    }
+
+    /// Switch to another remote. Sync state describes the old one, so it is
+    /// reset unless the remote is unchanged. So is encryption, since the
+    /// passphrase-sealed key a new machine recovers it from is on the old one.
+    pub fn set_remote(&mut self, remote: RemoteConfig) {
+        if self.remote() != remote {
+            self.last_synced_hash = None;
+            self.last_push_time = None;
+            self.last_pull_time = None;
+            self.encryption_key = None;
+        }
+        self.remote = Some(remote);
+    }
+
     /// Clear all authentication data.
     pub fn clear_auth(&mut self) {
         self.refresh_token = None;
@@ -119,6 +206,9 @@ mod tests {
             last_push_time: Some(1234567890),
             last_pull_time: Some(1234567891),
             last_synced_hash: Some("abc123".to_string()),
+            remote: None,
+            encryption_key: None,
+            snapshots_kept: None,
         };
 
         let serialized = toml::to_string_pretty(&config).unwrap();
@@ -142,6 +232,29 @@ mod tests {
         assert!(!config.is_authenticated());
     }
 
+    #[test]
+    fn remote_round_trips_and_defaults_to_dropbox() {
+        let mut config = SyncConfig::default();
+        assert_eq!(config.remote(), RemoteConfig::Dropbox);
+
+        config.last_synced_hash = Some("abc".to_string());
+        config.set_remote(RemoteConfig::Folder {
+            path: PathBuf::from("/mnt/nas/grans"),
+        });
+        // The hash described Dropbox's copy, not the folder's.
+        assert!(config.last_synced_hash.is_none());
+        assert!(config.is_connected());
+
+        let serialized = toml::to_string_pretty(&config).unwrap();
+        assert!(serialized.contains("kind = \"folder\""), "{}", serialized);
+        let loaded: SyncConfig = toml::from_str(&serialized).unwrap();
+        assert_eq!(loaded.remote(), config.remote());
+
+        // Files written before remotes existed still load.
+        let legacy: SyncConfig = toml::from_str("refresh_token = \"t\"").unwrap();
+        assert_eq!(legacy.remote(), RemoteConfig::Dropbox);
+    }
+
     #[test]
     fn test_roundtrip_file() {
         with_test_data_dir(|dir| {
@@ -152,6 +265,9 @@ mod tests {
                 last_push_time: Some(100),
                 last_pull_time: None,
                 last_synced_hash: None,
+                remote: None,
+                encryption_key: None,
+                snapshots_kept: None,
             };
 
             let content = toml::to_string_pretty(&config).unwrap();
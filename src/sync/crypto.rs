@@ -0,0 +1,428 @@
+//! Encrypting what a push sends, so the remote only ever holds ciphertext.
+//!
+//! One random 256-bit key (the sync key) encrypts the database, its patches
+//! and the sync metadata with AES-256-GCM. Files are sealed in 1 MiB chunks,
+//! each with its own tag, so a 400 MB database never has to sit in memory;
+//! every chunk's nonce carries its position and whether it is the last, so
+//! chunks cannot be reordered, dropped or cut off the end unnoticed. The
+//! header names the key by fingerprint, so a machine holding a different key
+//! says so instead of reporting a corrupt download.
+//!
+//! The key lives in the platform keychain. For a machine that has never had
+//! it, the remote also keeps a copy at [`REMOTE_KEY_PATH`] sealed with a key
+//! derived from a passphrase (Argon2id), which only someone who knows the
+//! passphrase can open.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, Key, KeyInit, Nonce};
+use anyhow::{Context, Result, anyhow, bail};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::api::credential_store::SecretStore;
+
+/// Remote path of the passphrase-sealed copy of the sync key.
+pub const REMOTE_KEY_PATH: &str = "/grans.key.json";
+
+/// First bytes of every encrypted file.
+const MAGIC: &[u8] = b"grans-encrypted 1\n";
+
+const KEY_LEN: usize = 32;
+const FINGERPRINT_LEN: usize = 8;
+const NONCE_PREFIX_LEN: usize = 7;
+const HEADER_LEN: usize = MAGIC.len() + FINGERPRINT_LEN + NONCE_PREFIX_LEN;
+const TAG_LEN: usize = 16;
+
+/// Plaintext bytes sealed per chunk.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// The key that encrypts everything a push sends.
+pub struct SyncKey([u8; KEY_LEN]);
+
+impl SyncKey {
+    pub fn generate() -> Self {
+        let mut key = [0u8; KEY_LEN];
+        rand::rngs::OsRng.fill_bytes(&mut key);
+        Self(key)
+    }
+
+    /// A short public name for the key, safe to show and to store beside
+    /// ciphertext.
+    pub fn fingerprint(&self) -> String {
+        hex(&self.fingerprint_bytes())
+    }
+
+    fn fingerprint_bytes(&self) -> [u8; FINGERPRINT_LEN] {
+        let digest = Sha256::new()
+            .chain_update(b"grans sync key")
+            .chain_update(self.0)
+            .finalize();
+        digest[..FINGERPRINT_LEN].try_into().unwrap()
+    }
+
+    /// Where the key with this fingerprint is kept. Each key has its own
+    /// entry, so setting up a new one never overwrites an old one that a
+    /// remote may still be encrypted with.
+    fn store(fingerprint: &str) -> Result<SecretStore> {
+        SecretStore::open(
+            &format!("sync-key-{}", fingerprint),
+            &format!("sync_key_{}", fingerprint),
+        )
+    }
+
+    /// The key with this fingerprint, if this machine holds it.
+    pub fn load(fingerprint: &str) -> Result<Option<Self>> {
+        let Some(encoded) = Self::store(fingerprint)?.load()? else {
+            return Ok(None);
+        };
+        let key = Self::decode(&encoded).context("the stored sync key is malformed")?;
+        if key.fingerprint() != fingerprint {
+            bail!("the stored sync key does not match its fingerprint");
+        }
+        Ok(Some(key))
+    }
+
+    /// Keep the key on this machine; returns where it went.
+    pub fn save(&self) -> Result<String> {
+        let store = Self::store(&self.fingerprint())?;
+        store.save(&STANDARD.encode(self.0))?;
+        Ok(store.describe())
+    }
+
+    fn decode(encoded: &str) -> Option<Self> {
+        let bytes = STANDARD.decode(encoded.trim()).ok()?;
+        Some(Self(bytes.try_into().ok()?))
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&self.0))
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// The fingerprint of the key that sealed `data`, or `None` if it is not
+/// encrypted. Only the header is needed.
+pub fn sealed_with(data: &[u8]) -> Option<String> {
+    let rest = data.strip_prefix(MAGIC)?;
+    rest.get(..FINGERPRINT_LEN).map(hex)
+}
+
+/// Whether the file at `path` is encrypted, and if so the key's fingerprint.
+pub fn file_sealed_with(path: &Path) -> Result<Option<String>> {
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    File::open(path)?
+        .take(HEADER_LEN as u64)
+        .read_to_end(&mut header)?;
+    Ok(sealed_with(&header))
+}
+
+/// Nonce for chunk `index`: the file's random prefix, the chunk's position,
+/// and whether it is the last one.
+fn chunk_nonce(prefix: &[u8], index: u32, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..11].copy_from_slice(&index.to_be_bytes());
+    nonce[11] = last as u8;
+    nonce
+}
+
+/// Fill `buffer` from `reader`, short only at end of input.
+fn read_full(reader: &mut impl Read, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+fn seal_stream(key: &SyncKey, reader: &mut impl Read, writer: &mut impl Write) -> Result<()> {
+    let mut prefix = [0u8; NONCE_PREFIX_LEN];
+    rand::rngs::OsRng.fill_bytes(&mut prefix);
+    let header = [MAGIC, &key.fingerprint_bytes(), &prefix].concat();
+    writer.write_all(&header)?;
+
+    let cipher = key.cipher();
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut index = 0u32;
+    loop {
+        let n = read_full(reader, &mut buffer)?;
+        // A full chunk may be followed by more; the one that comes up short,
+        // even empty, is the last.
+        let last = n < CHUNK_SIZE;
+        let sealed = cipher
+            .encrypt(
+                Nonce::from_slice(&chunk_nonce(&prefix, index, last)),
+                Payload {
+                    msg: &buffer[..n],
+                    aad: &header,
+                },
+            )
+            .map_err(|_| anyhow!("encryption failed"))?;
+        writer.write_all(&sealed)?;
+        if last {
+            return Ok(());
+        }
+        index = index.checked_add(1).context("file too large to encrypt")?;
+    }
+}
+
+fn open_stream(key: &SyncKey, reader: &mut impl Read, writer: &mut impl Write) -> Result<()> {
+    let mut header = [0u8; HEADER_LEN];
+    if read_full(reader, &mut header)? < HEADER_LEN || !header.starts_with(MAGIC) {
+        bail!("not a file encrypted by grans");
+    }
+    let fingerprint = hex(&header[MAGIC.len()..MAGIC.len() + FINGERPRINT_LEN]);
+    if fingerprint != key.fingerprint() {
+        bail!(
+            "encrypted with key {}, but this machine's key is {}",
+            fingerprint,
+            key.fingerprint()
+        );
+    }
+    let prefix = &header[MAGIC.len() + FINGERPRINT_LEN..];
+
+    let cipher = key.cipher();
+    let mut buffer = vec![0u8; CHUNK_SIZE + TAG_LEN];
+    let mut index = 0u32;
+    loop {
+        let n = read_full(reader, &mut buffer)?;
+        let last = n < buffer.len();
+        let opened = cipher
+            .decrypt(
+                Nonce::from_slice(&chunk_nonce(prefix, index, last)),
+                Payload {
+                    msg: &buffer[..n],
+                    aad: &header,
+                },
+            )
+            .map_err(|_| anyhow!("the encrypted file is corrupt or incomplete"))?;
+        writer.write_all(&opened)?;
+        if last {
+            return Ok(());
+        }
+        index = index
+            .checked_add(1)
+            .context("the encrypted file is corrupt")?;
+    }
+}
+
+/// Encrypt the file at `source` into `target`.
+pub fn encrypt_file(key: &SyncKey, source: &Path, target: &Path) -> Result<()> {
+    let mut reader = BufReader::new(File::open(source)?);
+    let mut writer = BufWriter::new(File::create(target)?);
+    seal_stream(key, &mut reader, &mut writer)?;
+    writer
+        .into_inner()
+        .map_err(|e| e.into_error())?
+        .sync_all()?;
+    Ok(())
+}
+
+/// Decrypt the file at `source` into `target`.
+pub fn decrypt_file(key: &SyncKey, source: &Path, target: &Path) -> Result<()> {
+    let mut reader = BufReader::new(File::open(source)?);
+    let mut writer = BufWriter::new(File::create(target)?);
+    open_stream(key, &mut reader, &mut writer)?;
+    writer
+        .into_inner()
+        .map_err(|e| e.into_error())?
+        .sync_all()?;
+    Ok(())
+}
+
+pub fn encrypt(key: &SyncKey, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(plaintext.len() + HEADER_LEN + TAG_LEN);
+    seal_stream(key, &mut &plaintext[..], &mut out)?;
+    Ok(out)
+}
+
+pub fn decrypt(key: &SyncKey, sealed: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(sealed.len());
+    open_stream(key, &mut &sealed[..], &mut out)?;
+    Ok(out)
+}
+
+/// The sync key sealed with a passphrase, as kept on the remote.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WrappedKey {
+    pub fingerprint: String,
+    /// Argon2id cost parameters, kept so they can be raised later without
+    /// stranding keys sealed under the old ones.
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    salt: String,
+    nonce: String,
+    sealed_key: String,
+}
+
+impl WrappedKey {
+    pub fn seal(key: &SyncKey, passphrase: &str) -> Result<Self> {
+        Self::seal_with(key, passphrase, Params::default())
+    }
+
+    fn seal_with(key: &SyncKey, passphrase: &str, params: Params) -> Result<Self> {
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 12];
+        rand::rngs::OsRng.fill_bytes(&mut salt);
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+        let mut wrapped = Self {
+            fingerprint: key.fingerprint(),
+            memory_kib: params.m_cost(),
+            iterations: params.t_cost(),
+            parallelism: params.p_cost(),
+            salt: STANDARD.encode(salt),
+            nonce: STANDARD.encode(nonce),
+            sealed_key: String::new(),
+        };
+        let sealed = wrapped
+            .passphrase_key(passphrase)?
+            .cipher()
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &key.0,
+                    aad: wrapped.fingerprint.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow!("encryption failed"))?;
+        wrapped.sealed_key = STANDARD.encode(sealed);
+        Ok(wrapped)
+    }
+
+    /// Recover the sync key. Fails on the wrong passphrase.
+    pub fn open(&self, passphrase: &str) -> Result<SyncKey> {
+        let nonce = STANDARD.decode(&self.nonce).context("malformed key file")?;
+        let sealed = STANDARD
+            .decode(&self.sealed_key)
+            .context("malformed key file")?;
+        if nonce.len() != 12 {
+            bail!("malformed key file");
+        }
+        let opened = self
+            .passphrase_key(passphrase)?
+            .cipher()
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &sealed,
+                    aad: self.fingerprint.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow!("wrong passphrase"))?;
+        let key = SyncKey(
+            opened
+                .try_into()
+                .map_err(|_| anyhow!("malformed key file"))?,
+        );
+        if key.fingerprint() != self.fingerprint {
+            bail!("the key file does not match its fingerprint");
+        }
+        Ok(key)
+    }
+
+    fn passphrase_key(&self, passphrase: &str) -> Result<SyncKey> {
+        let salt = STANDARD.decode(&self.salt).context("malformed key file")?;
+        let params = Params::new(
+            self.memory_kib,
+            self.iterations,
+            self.parallelism,
+            Some(KEY_LEN),
+        )
+        .map_err(|e| anyhow!("invalid key derivation parameters: {}", e))?;
+        let mut derived = [0u8; KEY_LEN];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut derived)
+            .map_err(|e| anyhow!("key derivation failed: {}", e))?;
+        Ok(SyncKey(derived))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn cheap_params() -> Params {
+        Params::new(64, 1, 1, Some(KEY_LEN)).unwrap()
+    }
+
+    #[test]
+    fn round_trips_across_chunk_boundaries() {
+        let key = SyncKey::generate();
+        for len in [0, 1, CHUNK_SIZE - 1, CHUNK_SIZE, CHUNK_SIZE * 2 + 5] {
+            let plaintext: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+            let sealed = encrypt(&key, &plaintext).unwrap();
+            assert_eq!(sealed_with(&sealed), Some(key.fingerprint()));
+            assert_eq!(decrypt(&key, &sealed).unwrap(), plaintext, "len {}", len);
+        }
+    }
+
+    #[test]
+    fn tampering_truncation_and_the_wrong_key_are_refused() {
+        let key = SyncKey::generate();
+        let plaintext = vec![7u8; CHUNK_SIZE + 100];
+        let sealed = encrypt(&key, &plaintext).unwrap();
+
+        let mut flipped = sealed.clone();
+        flipped[HEADER_LEN + 10] ^= 1;
+        assert!(decrypt(&key, &flipped).is_err());
+
+        // Cut at the chunk boundary, so what is left is a complete but
+        // non-final chunk.
+        let cut = &sealed[..HEADER_LEN + CHUNK_SIZE + TAG_LEN];
+        assert!(decrypt(&key, cut).is_err());
+
+        let other = SyncKey::generate();
+        let err = decrypt(&other, &sealed).unwrap_err().to_string();
+        assert!(err.contains(&key.fingerprint()), "{}", err);
+    }
+
+    #[test]
+    fn files_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let key = SyncKey::generate();
+        let plain = dir.path().join("plain");
+        let sealed = dir.path().join("sealed");
+        let opened = dir.path().join("opened");
+        std::fs::write(&plain, b"SQLite format 3\0 and the rest").unwrap();
+
+        encrypt_file(&key, &plain, &sealed).unwrap();
+        assert_eq!(file_sealed_with(&sealed).unwrap(), Some(key.fingerprint()));
+        assert_eq!(file_sealed_with(&plain).unwrap(), None);
+
+        decrypt_file(&key, &sealed, &opened).unwrap();
+        assert_eq!(
+            std::fs::read(&opened).unwrap(),
+            std::fs::read(&plain).unwrap()
+        );
+    }
+
+    #[test]
+    fn wrapped_key_opens_only_with_its_passphrase() {
+        let key = SyncKey::generate();
+        let wrapped = WrappedKey::seal_with(&key, "correct horse", cheap_params()).unwrap();
+
+        let json = serde_json::to_string(&wrapped).unwrap();
+        let wrapped: WrappedKey = serde_json::from_str(&json).unwrap();
+        assert_eq!(wrapped.fingerprint, key.fingerprint());
+
+        assert_eq!(wrapped.open("correct horse").unwrap().0, key.0);
+        assert!(wrapped.open("battery staple").is_err());
+    }
+}
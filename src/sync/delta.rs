@@ -0,0 +1,580 @@
+//! Block-level deltas, so a push uploads only what changed.
+//!
+//! SQLite rewrites pages in place, so between two syncs most of a database
+//! file is byte-for-byte unchanged. After every sync grans records a SHA-256
+//! of each fixed-size block of the file both sides then held (the block
+//! map). The next push compares the database against that map and uploads
+//! only the blocks that differ, as a patch; a machine still holding the file
+//! the patch was made from downloads the patch instead of the database.
+//!
+//! The remote keeps the last full upload at [`REMOTE_DB_PATH`] as the base,
+//! and a manifest listing the patches on top of it in order. The manifest
+//! names the content hash of the base it applies to, so one left describing
+//! a base that has since been replaced (a full push by an older grans) is
+//! ignored rather than applied. Once the patches add up to a sizeable part
+//! of the base, the next push uploads the whole file and starts over.
+//!
+//! Builds from before patches read [`REMOTE_DB_PATH`] as the whole
+//! database, so while patches sit on top of the base it is kept at
+//! [`REMOTE_BASE_PATH`] instead, and [`REMOTE_DB_PATH`] holds
+//! [`LAYOUT_MARKER`]. To such a build the marker is a copy someone else
+//! changed: its push stops as a conflict and its pull fails the integrity
+//! check, instead of taking the stale base for the database and replacing
+//! the patches with it on the next push. A full push puts a whole database
+//! back at [`REMOTE_DB_PATH`].
+//!
+//! [`REMOTE_DB_PATH`]: super::remote::REMOTE_DB_PATH
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::config::config_path;
+use super::content_hash::ContentHasher;
+use super::remote::{REMOTE_DB_PATH, RemoteFile, RemoteStore};
+use super::{SyncError, SyncResult};
+
+/// Size of a diffed block. Small enough that a sync touching a few hundred
+/// scattered pages uploads megabytes rather than the file; large enough that
+/// the block map of a 400 MB database stays under a megabyte.
+pub const BLOCK_SIZE: usize = 16 * 1024;
+
+/// Remote path of the manifest listing the patches on top of the base.
+pub const REMOTE_MANIFEST_PATH: &str = "/grans.db.deltas.json";
+
+/// Remote path of the base while patches are stacked on it.
+pub const REMOTE_BASE_PATH: &str = "/grans.db.base";
+
+/// What [`REMOTE_DB_PATH`] holds while the base is at [`REMOTE_BASE_PATH`].
+pub const LAYOUT_MARKER: &[u8] = b"This grans remote holds the database as grans.db.base \
+plus the patches listed in grans.db.deltas.json.\n\
+Upgrade grans to pull it; this build cannot.\n";
+
+/// A push starts a fresh chain once the patches would exceed this fraction of
+/// the base, so a machine pulling from scratch never downloads much more than
+/// the database itself.
+const MAX_CHAIN_FRACTION: u64 = 4;
+
+/// A push starts a fresh chain once this many patches are stacked.
+const MAX_CHAIN_LEN: usize = 50;
+
+/// First bytes of a patch file.
+const PATCH_MAGIC: &[u8] = b"grans-patch 1\n";
+
+/// First line of the block map file.
+const MAP_MAGIC: &str = "grans-blocks 1";
+
+/// One patch on the remote chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchEntry {
+    /// Remote path of the patch file.
+    pub path: String,
+    /// Content hash of the database the patch applies to.
+    pub from: String,
+    /// Content hash of the database it produces.
+    pub to: String,
+    /// Size of the database it produces.
+    pub db_size: u64,
+    /// Size of the patch file itself.
+    pub size: u64,
+}
+
+/// The patches stacked on the remote base, oldest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeltaManifest {
+    /// Content hash of the base the first patch applies to.
+    pub base_hash: String,
+    /// Size of that base.
+    pub base_size: u64,
+    /// Content hash of the base as stored, where that differs because it is
+    /// encrypted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stored_hash: Option<String>,
+    /// Fingerprint of the key the base and patches are encrypted with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub patches: Vec<PatchEntry>,
+}
+
+impl DeltaManifest {
+    pub fn new(base_hash: &str, base_size: u64) -> Self {
+        Self {
+            base_hash: base_hash.to_string(),
+            base_size,
+            stored_hash: None,
+            key: None,
+            patches: Vec::new(),
+        }
+    }
+
+    /// Content hash of the database after every patch.
+    pub fn tip(&self) -> &str {
+        self.patches
+            .last()
+            .map_or(self.base_hash.as_str(), |p| p.to.as_str())
+    }
+
+    /// Size of the database after every patch.
+    pub fn tip_size(&self) -> u64 {
+        self.patches.last().map_or(self.base_size, |p| p.db_size)
+    }
+
+    /// Total size of the patch files.
+    pub fn chain_size(&self) -> u64 {
+        self.patches.iter().map(|p| p.size).sum()
+    }
+
+    /// The patches that bring a database with content hash `hash` to the
+    /// tip, or `None` if no point on the chain holds that content.
+    pub fn patches_from(&self, hash: &str) -> Option<&[PatchEntry]> {
+        if hash == self.base_hash {
+            return Some(&self.patches);
+        }
+        self.patches
+            .iter()
+            .position(|p| p.to == hash)
+            .map(|i| &self.patches[i + 1..])
+    }
+
+    /// Whether a patch of `size` bytes still fits on a chain over a base of
+    /// `base_size` bytes.
+    pub fn has_room_for(&self, size: u64, base_size: u64) -> bool {
+        self.patches.len() < MAX_CHAIN_LEN
+            && self.chain_size() + size <= base_size / MAX_CHAIN_FRACTION
+    }
+
+    /// Remote path for the patch from `from` to `to`. Only the same pair of
+    /// databases can produce the same name, so a patch is never overwritten
+    /// with different contents.
+    pub fn patch_path(from: &str, to: &str) -> String {
+        format!(
+            "/grans.db.delta-{}-{}",
+            &from[..from.len().min(12)],
+            &to[..to.len().min(12)]
+        )
+    }
+}
+
+/// The database as the remote holds it: the stored base, plus any patches.
+#[derive(Debug, Clone)]
+pub struct RemoteDatabase {
+    /// Remote path of the base: [`REMOTE_DB_PATH`], or a snapshot's copy.
+    path: String,
+    pub base: RemoteFile,
+    /// The manifest as stored, which may describe some other base.
+    manifest: Option<DeltaManifest>,
+}
+
+impl RemoteDatabase {
+    /// The database whose base is stored at `path`, with the patches
+    /// `manifest` lists on top of it.
+    pub fn at(path: &str, base: RemoteFile, manifest: Option<DeltaManifest>) -> Self {
+        Self {
+            path: path.to_string(),
+            base,
+            manifest,
+        }
+    }
+
+    /// Remote path of the base.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Look up the remote database; `None` if nothing has been pushed.
+    pub fn load(store: &dyn RemoteStore) -> SyncResult<Option<Self>> {
+        let Some(top) = store.stat(REMOTE_DB_PATH)? else {
+            return Ok(None);
+        };
+        // A marker whose base has gone missing is left to stand for the
+        // database: nothing matches it, so only a forced push replaces it.
+        let split = if top.content_hash == Some(layout_marker_hash()) {
+            store.stat(REMOTE_BASE_PATH)?
+        } else {
+            None
+        };
+        let (path, base) = match split {
+            Some(base) => (REMOTE_BASE_PATH, base),
+            None => (REMOTE_DB_PATH, top),
+        };
+        let manifest =
+            match store.stat(REMOTE_MANIFEST_PATH)? {
+                Some(_) => {
+                    let bytes = store.download(REMOTE_MANIFEST_PATH)?;
+                    Some(serde_json::from_slice(&bytes).map_err(|e| {
+                        SyncError::RemoteApi(format!("parse delta manifest: {}", e))
+                    })?)
+                }
+                None => None,
+            };
+        Ok(Some(Self::at(path, base, manifest)))
+    }
+
+    /// The patches on top of the stored base, if the manifest describes it.
+    pub fn chain(&self) -> Option<&DeltaManifest> {
+        self.manifest.as_ref().filter(|m| {
+            let stored = m.stored_hash.as_deref().unwrap_or(&m.base_hash);
+            self.base.content_hash.as_deref() == Some(stored)
+        })
+    }
+
+    /// Every patch file the remote holds, whichever base they belong to.
+    pub fn patch_paths(&self) -> impl Iterator<Item = &str> {
+        self.manifest
+            .iter()
+            .flat_map(|m| m.patches.iter().map(|p| p.path.as_str()))
+    }
+
+    /// Content hash of the database once every patch is applied.
+    ///
+    /// Without a manifest describing it, an encrypted base is known only by
+    /// the hash of its ciphertext, which no local database matches; sync
+    /// then treats the two as different, which is the safe answer.
+    pub fn hash(&self) -> Option<&str> {
+        match self.chain() {
+            Some(m) => Some(m.tip()),
+            None => self.base.content_hash.as_deref(),
+        }
+    }
+
+    /// Size of the database once every patch is applied.
+    pub fn size(&self) -> u64 {
+        self.chain().map_or(self.base.size, DeltaManifest::tip_size)
+    }
+}
+
+/// Content hash of [`LAYOUT_MARKER`], which tells it apart from a database
+/// at [`REMOTE_DB_PATH`] without downloading it.
+pub fn layout_marker_hash() -> String {
+    let mut hasher = ContentHasher::new();
+    hasher.update(LAYOUT_MARKER);
+    hasher.finish()
+}
+
+/// Hashes of each block of a database file, recorded when both sides held it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockMap {
+    /// Content hash of the whole file.
+    pub content_hash: String,
+    pub len: u64,
+    blocks: Vec<[u8; 32]>,
+}
+
+impl BlockMap {
+    /// Compute the map of a file, in one pass that also yields its content hash.
+    pub fn of_file(path: &Path) -> io::Result<Self> {
+        let mut file = BufReader::new(File::open(path)?);
+        let mut content = ContentHasher::new();
+        let mut blocks = Vec::new();
+        let mut len = 0;
+        let mut buffer = vec![0u8; BLOCK_SIZE];
+        loop {
+            let n = read_block(&mut file, &mut buffer)?;
+            if n == 0 {
+                break;
+            }
+            content.update(&buffer[..n]);
+            blocks.push(Sha256::digest(&buffer[..n]).into());
+            len += n as u64;
+        }
+        Ok(Self {
+            content_hash: content.finish(),
+            len,
+            blocks,
+        })
+    }
+
+    /// Where the map of the last synced database is kept, beside the sync
+    /// config.
+    fn path() -> SyncResult<PathBuf> {
+        Ok(config_path()?.with_file_name("sync_blocks"))
+    }
+
+    /// The map recorded at the last sync, if there is a readable one.
+    pub fn load() -> Option<Self> {
+        let bytes = fs::read(Self::path().ok()?).ok()?;
+        Self::decode(&bytes)
+    }
+
+    /// Record this map as the last synced database's.
+    pub fn save(&self) -> SyncResult<()> {
+        let path = Self::path()?;
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, self.encode())?;
+        fs::rename(&temp, &path)?;
+        Ok(())
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = format!(
+            "{} {} {} {}\n",
+            MAP_MAGIC, BLOCK_SIZE, self.len, self.content_hash
+        )
+        .into_bytes();
+        for digest in &self.blocks {
+            out.extend_from_slice(digest);
+        }
+        out
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let newline = bytes.iter().position(|&b| b == b'\n')?;
+        let header = std::str::from_utf8(&bytes[..newline]).ok()?;
+        let mut fields = header.strip_prefix(MAP_MAGIC)?.split_whitespace();
+        let block_size: usize = fields.next()?.parse().ok()?;
+        let len: u64 = fields.next()?.parse().ok()?;
+        let content_hash = fields.next()?.to_string();
+        let digests = &bytes[newline + 1..];
+        if block_size != BLOCK_SIZE
+            || !digests.len().is_multiple_of(32)
+            || digests.len() / 32 != len.div_ceil(BLOCK_SIZE as u64) as usize
+        {
+            return None;
+        }
+        Some(Self {
+            content_hash,
+            len,
+            blocks: digests
+                .chunks_exact(32)
+                .map(|d| d.try_into().unwrap())
+                .collect(),
+        })
+    }
+}
+
+/// Fill `buffer` from `reader`, short only at end of file.
+fn read_block(reader: &mut impl Read, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// Write to `out` a patch turning the file `base` describes into the one at
+/// `path`. Returns the map of the file at `path`, for the next push.
+///
+/// A patch is [`PATCH_MAGIC`], the block size and the new length as
+/// little-endian u64s, then each changed block as its index (u64) followed
+/// by its bytes.
+pub fn write_patch(path: &Path, base: &BlockMap, out: &Path) -> SyncResult<BlockMap> {
+    let mut source = BufReader::new(File::open(path)?);
+    let len = fs::metadata(path)?.len();
+    let mut patch = BufWriter::new(File::create(out)?);
+    patch.write_all(PATCH_MAGIC)?;
+    patch.write_all(&(BLOCK_SIZE as u64).to_le_bytes())?;
+    patch.write_all(&len.to_le_bytes())?;
+
+    let mut content = ContentHasher::new();
+    let mut blocks = Vec::new();
+    let mut buffer = vec![0u8; BLOCK_SIZE];
+    loop {
+        let n = read_block(&mut source, &mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        let block = &buffer[..n];
+        content.update(block);
+        let digest: [u8; 32] = Sha256::digest(block).into();
+        let index = blocks.len();
+        // A short final block in the base differs from a full one here even
+        // when the bytes it has match, so its digest differs too.
+        if base.blocks.get(index) != Some(&digest) {
+            patch.write_all(&(index as u64).to_le_bytes())?;
+            patch.write_all(block)?;
+        }
+        blocks.push(digest);
+    }
+    patch
+        .into_inner()
+        .map_err(|e| SyncError::Io(e.into_error()))?
+        .sync_all()?;
+
+    Ok(BlockMap {
+        content_hash: content.finish(),
+        len,
+        blocks,
+    })
+}
+
+/// Apply a patch to the file at `target`, in place.
+pub fn apply_patch(target: &Path, patch: &mut impl Read) -> SyncResult<()> {
+    let corrupt = |what: &str| SyncError::RemoteApi(format!("corrupt delta patch: {}", what));
+
+    let mut magic = vec![0u8; PATCH_MAGIC.len()];
+    patch.read_exact(&mut magic)?;
+    if magic != PATCH_MAGIC {
+        return Err(corrupt("not a grans patch"));
+    }
+    let mut word = [0u8; 8];
+    patch.read_exact(&mut word)?;
+    if u64::from_le_bytes(word) != BLOCK_SIZE as u64 {
+        return Err(corrupt("unexpected block size"));
+    }
+    patch.read_exact(&mut word)?;
+    let len = u64::from_le_bytes(word);
+
+    let mut file = OpenOptions::new().write(true).open(target)?;
+    file.set_len(len)?;
+    let mut buffer = vec![0u8; BLOCK_SIZE];
+    loop {
+        if read_block(patch, &mut word)? == 0 {
+            break;
+        }
+        let offset = u64::from_le_bytes(word)
+            .checked_mul(BLOCK_SIZE as u64)
+            .filter(|&offset| offset < len)
+            .ok_or_else(|| corrupt("block beyond the end of the file"))?;
+        let block = &mut buffer[..(len - offset).min(BLOCK_SIZE as u64) as usize];
+        patch.read_exact(block)?;
+        file.seek(SeekFrom::Start(offset))?;
+        file.write_all(block)?;
+    }
+    file.sync_all()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::content_hash::hash_file;
+    use tempfile::TempDir;
+
+    fn bytes(len: usize, seed: u8) -> Vec<u8> {
+        (0..len)
+            .map(|i| (i as u8).wrapping_mul(31) ^ seed)
+            .collect()
+    }
+
+    /// Patch `old` into `new` and return the patch size and the result.
+    fn round_trip(dir: &TempDir, old: &[u8], new: &[u8]) -> (u64, Vec<u8>) {
+        let old_path = dir.path().join("old.db");
+        let new_path = dir.path().join("new.db");
+        let patch_path = dir.path().join("patch");
+        fs::write(&old_path, old).unwrap();
+        fs::write(&new_path, new).unwrap();
+
+        let base = BlockMap::of_file(&old_path).unwrap();
+        let map = write_patch(&new_path, &base, &patch_path).unwrap();
+        assert_eq!(map, BlockMap::of_file(&new_path).unwrap());
+        assert_eq!(map.content_hash, hash_file(&new_path).unwrap());
+
+        let mut patch = File::open(&patch_path).unwrap();
+        apply_patch(&old_path, &mut patch).unwrap();
+        (
+            fs::metadata(&patch_path).unwrap().len(),
+            fs::read(&old_path).unwrap(),
+        )
+    }
+
+    #[test]
+    fn a_patch_carries_only_changed_blocks() {
+        let dir = TempDir::new().unwrap();
+        let old = bytes(BLOCK_SIZE * 10, 1);
+        let mut new = old.clone();
+        new[BLOCK_SIZE * 3 + 5] ^= 0xff;
+        new[BLOCK_SIZE * 7] ^= 0xff;
+
+        let (size, result) = round_trip(&dir, &old, &new);
+        assert_eq!(result, new);
+        let header = PATCH_MAGIC.len() as u64 + 16;
+        assert_eq!(size, header + 2 * (8 + BLOCK_SIZE as u64));
+    }
+
+    #[test]
+    fn patches_grow_and_shrink_the_file() {
+        let dir = TempDir::new().unwrap();
+        let old = bytes(BLOCK_SIZE * 3 + 100, 2);
+
+        let mut grown = old.clone();
+        grown.extend(bytes(BLOCK_SIZE * 2 + 7, 3));
+        assert_eq!(round_trip(&dir, &old, &grown).1, grown);
+
+        let shrunk = old[..BLOCK_SIZE + 9].to_vec();
+        assert_eq!(round_trip(&dir, &old, &shrunk).1, shrunk);
+    }
+
+    #[test]
+    fn block_map_survives_encoding_and_rejects_damage() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("db");
+        fs::write(&path, bytes(BLOCK_SIZE * 2 + 1, 4)).unwrap();
+        let map = BlockMap::of_file(&path).unwrap();
+
+        let encoded = map.encode();
+        assert_eq!(BlockMap::decode(&encoded), Some(map));
+        assert_eq!(BlockMap::decode(&encoded[..encoded.len() - 1]), None);
+        assert_eq!(BlockMap::decode(b"something else\n"), None);
+    }
+
+    #[test]
+    fn manifest_finds_the_patches_from_any_point_on_the_chain() {
+        let entry = |from: &str, to: &str| PatchEntry {
+            path: DeltaManifest::patch_path(from, to),
+            from: from.to_string(),
+            to: to.to_string(),
+            db_size: 100,
+            size: 10,
+        };
+        let mut manifest = DeltaManifest::new("a", 100);
+        manifest.patches = vec![entry("a", "b"), entry("b", "c")];
+
+        assert_eq!(manifest.tip(), "c");
+        assert_eq!(manifest.patches_from("a").unwrap().len(), 2);
+        assert_eq!(manifest.patches_from("b").unwrap()[0].to, "c");
+        assert!(manifest.patches_from("c").unwrap().is_empty());
+        assert!(manifest.patches_from("z").is_none());
+
+        assert!(manifest.has_room_for(5, 100));
+        assert!(!manifest.has_room_for(6, 100));
+    }
+
+    #[test]
+    fn an_encrypted_base_is_matched_by_its_stored_hash() {
+        let mut manifest = DeltaManifest::new("plain", 100);
+        manifest.stored_hash = Some("sealed".to_string());
+        let remote = |stored: &str| {
+            RemoteDatabase::at(
+                REMOTE_DB_PATH,
+                RemoteFile {
+                    size: 140,
+                    modified: None,
+                    content_hash: Some(stored.to_string()),
+                },
+                Some(manifest.clone()),
+            )
+        };
+
+        let current = remote("sealed");
+        assert_eq!(current.hash(), Some("plain"));
+        assert_eq!(current.size(), 100);
+
+        // The base was replaced without the manifest.
+        let replaced = remote("other");
+        assert!(replaced.chain().is_none());
+        assert_eq!(replaced.hash(), Some("other"));
+        assert_eq!(replaced.size(), 140);
+    }
+
+    #[test]
+    fn corrupt_patches_are_refused() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("db");
+        fs::write(&target, b"data").unwrap();
+
+        let mut not_a_patch: &[u8] = b"definitely not a patch file";
+        assert!(apply_patch(&target, &mut not_a_patch).is_err());
+
+        let mut out_of_range = PATCH_MAGIC.to_vec();
+        out_of_range.extend((BLOCK_SIZE as u64).to_le_bytes());
+        out_of_range.extend(4u64.to_le_bytes());
+        out_of_range.extend(9u64.to_le_bytes());
+        assert!(apply_patch(&target, &mut out_of_range.as_slice()).is_err());
+    }
+}
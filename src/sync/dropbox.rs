@@ -10,6 +10,7 @@ use std::path::Path;
 use std::time::Instant;
 
 use super::content_hash;
+use super::remote::{RemoteFile, RemoteStore};
 use super::transfer::{self, ProgressFn, ProgressReader, UPLOAD_TIMEOUT};
 use super::{SyncError, SyncResult};
 
@@ -22,6 +23,8 @@ const UPLOAD_SESSION_FINISH_URL: &str =
     "https://content.dropboxapi.com/2/files/upload_session/finish";
 const DOWNLOAD_URL: &str = "https://content.dropboxapi.com/2/files/download";
 const METADATA_URL: &str = "https://api.dropboxapi.com/2/files/get_metadata";
+const DELETE_URL: &str = "https://api.dropboxapi.com/2/files/delete_v2";
+const COPY_URL: &str = "https://api.dropboxapi.com/2/files/copy_v2";
 
 /// Dropbox's single-request upload limit is 150 MB.
 const UPLOAD_SINGLE_LIMIT: u64 = 150 * 1024 * 1024;
@@ -172,7 +175,9 @@ impl DropboxClient {
             .timeout(UPLOAD_TIMEOUT)
             .body(body)
             .send()
-            .map_err(|e| transfer::transport_error(format!("upload of {}", dropbox_path), e))?;
+            .map_err(|e| {
+                transfer::transport_error(format!("Dropbox upload of {}", dropbox_path), e)
+            })?;
 
         self.handle_response(response)
     }
@@ -242,7 +247,7 @@ impl DropboxClient {
             .timeout(UPLOAD_TIMEOUT)
             .body(data.to_vec())
             .send()
-            .map_err(|e| transfer::transport_error("upload session start", e))?;
+            .map_err(|e| transfer::transport_error("Dropbox upload session start", e))?;
 
         #[derive(Deserialize)]
         struct StartResult {
@@ -281,7 +286,10 @@ impl DropboxClient {
             .body(data.to_vec())
             .send()
             .map_err(|e| {
-                transfer::transport_error(format!("upload of chunk at offset {}", offset), e)
+                transfer::transport_error(
+                    format!("Dropbox upload of chunk at offset {}", offset),
+                    e,
+                )
             })?;
 
         let status = response.status();
@@ -332,22 +340,12 @@ impl DropboxClient {
             .body(data.to_vec())
             .send()
             .map_err(|e| {
-                transfer::transport_error(format!("upload commit for {}", dropbox_path), e)
+                transfer::transport_error(format!("Dropbox upload commit for {}", dropbox_path), e)
             })?;
 
         self.handle_response(response)
     }
 
-    /// Download a small file from Dropbox into memory.
-    ///
-    /// Only for files whose size is known to be modest, such as sync metadata.
-    /// Use [`DropboxClient::download_to_writer`] for databases.
-    pub fn download(&self, dropbox_path: &str) -> SyncResult<Vec<u8>> {
-        let mut buffer = Vec::new();
-        self.download_to_writer(dropbox_path, &mut buffer, &mut |_| {})?;
-        Ok(buffer)
-    }
-
     /// Stream a file from Dropbox into `writer`, reporting the running byte count.
     ///
     /// Returns the number of bytes written.
@@ -381,7 +379,7 @@ impl DropboxClient {
             .send()
             .map_err(|e| {
                 debug!("  network error after {:?}: {}", start.elapsed(), e);
-                transfer::transport_error(format!("download of {}", dropbox_path), e)
+                transfer::transport_error(format!("Dropbox download of {}", dropbox_path), e)
             })?;
 
         let status = response.status();
@@ -420,7 +418,7 @@ impl DropboxClient {
                     transfer::describe_throughput(transferred, elapsed)
                 );
                 Err(transfer::stream_error(
-                    format!("download of {}", dropbox_path),
+                    format!("Dropbox download of {}", dropbox_path),
                     e,
                 ))
             }
@@ -452,7 +450,10 @@ impl DropboxClient {
             .send()
             .map_err(|e| {
                 debug!("  network error after {:?}: {}", start.elapsed(), e);
-                transfer::transport_error(format!("metadata lookup for {}", dropbox_path), e)
+                transfer::transport_error(
+                    format!("Dropbox metadata lookup for {}", dropbox_path),
+                    e,
+                )
             })?;
 
         let status = response.status();
@@ -477,6 +478,100 @@ impl DropboxClient {
         Ok(Some(metadata))
     }
 
+    /// Delete a file on Dropbox.
+    ///
+    /// A file that does not exist is not an error.
+    pub fn delete(&self, dropbox_path: &str) -> SyncResult<()> {
+        #[derive(Serialize)]
+        struct DeleteArg {
+            path: String,
+        }
+
+        let arg = DeleteArg {
+            path: dropbox_path.to_string(),
+        };
+
+        debug!("POST {} (delete {})", DELETE_URL, dropbox_path);
+        let start = Instant::now();
+
+        let response = self
+            .http
+            .post(DELETE_URL)
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .header("Content-Type", "application/json")
+            .json(&arg)
+            .send()
+            .map_err(|e| {
+                debug!("  network error after {:?}: {}", start.elapsed(), e);
+                transfer::transport_error(format!("Dropbox delete of {}", dropbox_path), e)
+            })?;
+
+        let status = response.status();
+        debug!("  response: {} in {:?}", status, start.elapsed());
+        let body = response.text().unwrap_or_default();
+
+        if status.as_u16() == 409 && body.contains("not_found") {
+            debug!("  {} was already gone", dropbox_path);
+            return Ok(());
+        }
+
+        if !status.is_success() {
+            return Err(self.parse_error(status, &body));
+        }
+
+        Ok(())
+    }
+
+    /// Copy a file within Dropbox, replacing any file at `to_path`.
+    ///
+    /// Dropbox refuses to copy over an existing file, so one in the way is
+    /// deleted first.
+    pub fn copy(&self, from_path: &str, to_path: &str) -> SyncResult<()> {
+        #[derive(Serialize)]
+        struct CopyArg<'a> {
+            from_path: &'a str,
+            to_path: &'a str,
+        }
+
+        let arg = CopyArg { from_path, to_path };
+
+        for attempt in 0..2 {
+            debug!("POST {} (copy {} to {})", COPY_URL, from_path, to_path);
+            let start = Instant::now();
+
+            let response = self
+                .http
+                .post(COPY_URL)
+                .header("Authorization", format!("Bearer {}", self.access_token))
+                .header("Content-Type", "application/json")
+                .json(&arg)
+                .send()
+                .map_err(|e| {
+                    debug!("  network error after {:?}: {}", start.elapsed(), e);
+                    transfer::transport_error(format!("Dropbox copy of {}", from_path), e)
+                })?;
+
+            let status = response.status();
+            debug!("  response: {} in {:?}", status, start.elapsed());
+            let body = response.text().unwrap_or_default();
+
+            if status.as_u16() == 409 && body.contains("to/conflict") && attempt == 0 {
+                debug!("  {} exists; replacing it", to_path);
+                self.delete(to_path)?;
+                continue;
+            }
+
+            if !status.is_success() {
+                return Err(self.parse_error(status, &body));
+            }
+            return Ok(());
+        }
+        Err(SyncError::DropboxApi(format!(
+            "could not replace {} with a copy of {}",
+            to_path, from_path
+        )))
+    }
+
     fn handle_response<T: for<'de> Deserialize<'de>>(
         &self,
         response: reqwest::blocking::Response,
@@ -501,6 +596,52 @@ impl DropboxClient {
     }
 }
 
+impl From<FileMetadata> for RemoteFile {
+    fn from(meta: FileMetadata) -> Self {
+        Self {
+            size: meta.size,
+            modified: parse_dropbox_time(&meta.server_modified),
+            content_hash: meta.content_hash,
+        }
+    }
+}
+
+impl RemoteStore for DropboxClient {
+    fn describe(&self) -> String {
+        "Dropbox".to_string()
+    }
+
+    fn stat(&self, path: &str) -> SyncResult<Option<RemoteFile>> {
+        Ok(self.get_metadata(path)?.map(RemoteFile::from))
+    }
+
+    fn upload(
+        &self,
+        local_path: &Path,
+        path: &str,
+        on_progress: ProgressFn,
+    ) -> SyncResult<RemoteFile> {
+        DropboxClient::upload(self, local_path, path, on_progress).map(RemoteFile::from)
+    }
+
+    fn copy(&self, from: &str, to: &str) -> SyncResult<()> {
+        DropboxClient::copy(self, from, to)
+    }
+
+    fn delete(&self, path: &str) -> SyncResult<()> {
+        DropboxClient::delete(self, path)
+    }
+
+    fn download_to_writer(
+        &self,
+        path: &str,
+        mut writer: &mut dyn Write,
+        on_progress: &mut dyn FnMut(u64),
+    ) -> SyncResult<u64> {
+        DropboxClient::download_to_writer(self, path, &mut writer, on_progress)
+    }
+}
+
 /// Parse a Dropbox server_modified timestamp to Unix timestamp.
 pub fn parse_dropbox_time(server_modified: &str) -> Option<u64> {
     // Format: "2025-01-27T10:30:00Z"
@@ -0,0 +1,237 @@
+//! A plain directory as remote storage: a network share, or a folder that
+//! Syncthing or a similar tool replicates between machines.
+//!
+//! Files are written to a temporary name and renamed into place, so a
+//! reader on another machine never sees half a database. The content hash
+//! goes in a sidecar written after the file; a sidecar missing or older
+//! than its file (the file was replaced by hand, or the sync tool has
+//! delivered only one of the two so far) is not trusted, and the hash is
+//! computed from the file instead.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use log::debug;
+
+use super::content_hash::{HashingWriter, hash_file};
+use super::remote::{RemoteFile, RemoteStore, hash_sidecar};
+use super::transfer::{self, ProgressFn};
+use super::{SyncError, SyncResult};
+
+/// A directory holding the pushed files.
+pub struct FolderStore {
+    root: PathBuf,
+}
+
+impl FolderStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn resolve(&self, path: &str) -> PathBuf {
+        self.root.join(path.trim_start_matches('/'))
+    }
+
+    /// The hash in `file`'s sidecar, if the sidecar was written after it.
+    fn sidecar_hash(&self, path: &str, file: &fs::Metadata) -> Option<String> {
+        let sidecar = self.resolve(&hash_sidecar(path));
+        let written = fs::metadata(&sidecar).ok()?.modified().ok()?;
+        if written < file.modified().ok()? {
+            debug!("ignoring stale {}", sidecar.display());
+            return None;
+        }
+        let hash = fs::read_to_string(&sidecar).ok()?;
+        Some(hash.trim().to_string()).filter(|h| !h.is_empty())
+    }
+}
+
+/// Write `path` by way of a temporary file in the same directory, flushed
+/// to the device before it is renamed over the target.
+fn write_atomically(
+    path: &Path,
+    write: impl FnOnce(&mut File) -> SyncResult<()>,
+) -> SyncResult<()> {
+    let temp = path.with_extension(format!(
+        "{}.tmp",
+        path.extension().and_then(|e| e.to_str()).unwrap_or("")
+    ));
+    let result = File::create(&temp)
+        .map_err(SyncError::from)
+        .and_then(|mut file| {
+            write(&mut file)?;
+            file.sync_all()?;
+            Ok(())
+        })
+        .and_then(|()| fs::rename(&temp, path).map_err(SyncError::from));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+impl RemoteStore for FolderStore {
+    fn describe(&self) -> String {
+        self.root.display().to_string()
+    }
+
+    fn stat(&self, path: &str) -> SyncResult<Option<RemoteFile>> {
+        let target = self.resolve(path);
+        let meta = match fs::metadata(&target) {
+            Ok(meta) => meta,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let content_hash = match self.sidecar_hash(path, &meta) {
+            Some(hash) => hash,
+            None => hash_file(&target)?,
+        };
+        Ok(Some(RemoteFile {
+            size: meta.len(),
+            modified: meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs()),
+            content_hash: Some(content_hash),
+        }))
+    }
+
+    fn upload(
+        &self,
+        local_path: &Path,
+        path: &str,
+        mut on_progress: ProgressFn,
+    ) -> SyncResult<RemoteFile> {
+        let local_hash = hash_file(local_path)?;
+        let target = self.resolve(path);
+
+        // Hash what was written rather than trusting the copy: a share that
+        // drops writes fails here instead of on another machine's pull.
+        write_atomically(&target, |file| {
+            let mut writer = HashingWriter::new(BufWriter::new(file));
+            let mut source = File::open(local_path)?;
+            transfer::stream_with_progress(&mut source, &mut writer, &mut on_progress)?;
+            let (mut buffered, written_hash) = writer.finish();
+            buffered.flush()?;
+            transfer::verify_content_hash(&written_hash, &local_hash, "uploaded file")
+        })?;
+        write_atomically(&self.resolve(&hash_sidecar(path)), |file| {
+            file.write_all(local_hash.as_bytes())?;
+            Ok(())
+        })?;
+
+        self.stat(path)?.ok_or_else(|| {
+            SyncError::RemoteApi(format!("{} vanished after upload", target.display()))
+        })
+    }
+
+    fn copy(&self, from: &str, to: &str) -> SyncResult<()> {
+        let source = self.resolve(from);
+        let hash = self
+            .stat(from)?
+            .and_then(|f| f.content_hash)
+            .ok_or_else(|| SyncError::RemoteApi(format!("{} not found", source.display())))?;
+        write_atomically(&self.resolve(to), |file| {
+            std::io::copy(&mut File::open(&source)?, file)?;
+            Ok(())
+        })?;
+        write_atomically(&self.resolve(&hash_sidecar(to)), |file| {
+            file.write_all(hash.as_bytes())?;
+            Ok(())
+        })
+    }
+
+    fn delete(&self, path: &str) -> SyncResult<()> {
+        for target in [self.resolve(path), self.resolve(&hash_sidecar(path))] {
+            match fs::remove_file(&target) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn download_to_writer(
+        &self,
+        path: &str,
+        writer: &mut dyn Write,
+        on_progress: &mut dyn FnMut(u64),
+    ) -> SyncResult<u64> {
+        let mut source = File::open(self.resolve(path))?;
+        Ok(transfer::stream_with_progress(
+            &mut source,
+            writer,
+            on_progress,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::transfer::no_progress;
+    use tempfile::TempDir;
+
+    #[test]
+    fn upload_then_download_round_trips_with_hash() {
+        let dir = TempDir::new().unwrap();
+        let local = dir.path().join("local.db");
+        fs::write(&local, b"database bytes").unwrap();
+        fs::create_dir(dir.path().join("share")).unwrap();
+        let store = FolderStore::new(dir.path().join("share"));
+
+        assert!(store.stat("/grans.db").unwrap().is_none());
+        let stored = store.upload(&local, "/grans.db", no_progress()).unwrap();
+        assert_eq!(stored.size, 14);
+        assert_eq!(stored.content_hash, Some(hash_file(&local).unwrap()));
+        assert!(dir.path().join("share/grans.db.content_hash").exists());
+        assert!(!dir.path().join("share/grans.db.tmp").exists());
+
+        assert_eq!(store.download("/grans.db").unwrap(), b"database bytes");
+    }
+
+    #[test]
+    fn stale_sidecar_is_ignored() {
+        let dir = TempDir::new().unwrap();
+        let local = dir.path().join("local.db");
+        fs::write(&local, b"first").unwrap();
+        let store = FolderStore::new(dir.path());
+        store.upload(&local, "/grans.db", no_progress()).unwrap();
+
+        // Another tool replaces the file without touching the sidecar.
+        let target = dir.path().join("grans.db");
+        fs::write(&target, b"second").unwrap();
+        let later = fs::metadata(dir.path().join("grans.db.content_hash"))
+            .unwrap()
+            .modified()
+            .unwrap()
+            + std::time::Duration::from_secs(5);
+        File::options()
+            .write(true)
+            .open(&target)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+
+        let stat = store.stat("/grans.db").unwrap().unwrap();
+        assert_eq!(stat.content_hash, Some(hash_file(&target).unwrap()));
+    }
+
+    #[test]
+    fn copy_keeps_contents_and_hash() {
+        let dir = TempDir::new().unwrap();
+        let local = dir.path().join("local.db");
+        fs::write(&local, b"database bytes").unwrap();
+        fs::create_dir(dir.path().join("share")).unwrap();
+        let store = FolderStore::new(dir.path().join("share"));
+        let stored = store.upload(&local, "/grans.db", no_progress()).unwrap();
+
+        store.copy("/grans.db", "/grans.db.copy").unwrap();
+        let copy = store.stat("/grans.db.copy").unwrap().unwrap();
+        assert_eq!(copy.content_hash, stored.content_hash);
+        assert_eq!(store.download("/grans.db.copy").unwrap(), b"database bytes");
+        assert!(store.copy("/missing", "/grans.db.copy").is_err());
+    }
+}
@@ -1,12 +1,20 @@
-//! Dropbox sync module for synchronizing SQLite index and embeddings databases.
+//! Replicating the database to remote storage: Dropbox, a plain folder,
+//! WebDAV or an S3-compatible bucket (see [`remote`]).
 
 pub mod config;
 pub mod content_hash;
+pub mod crypto;
+pub mod delta;
 pub mod dropbox;
+pub mod folder;
 pub mod metadata;
 pub mod oauth;
 pub mod reconcile;
+pub mod remote;
+pub mod s3;
+pub mod snapshot;
 pub mod transfer;
+pub mod webdav;
 
 use thiserror::Error;
 
@@ -21,7 +29,10 @@ pub enum SyncError {
     #[error("Dropbox API error: {0}")]
     DropboxApi(String),
 
-    #[error("Dropbox {operation} failed: {hint}")]
+    #[error("Remote storage error: {0}")]
+    RemoteApi(String),
+
+    #[error("{operation} failed: {hint}")]
     Transport {
         operation: String,
         hint: String,
@@ -30,7 +41,7 @@ pub enum SyncError {
     },
 
     #[error(
-        "Incomplete transfer: {what} delivered {actual} bytes but Dropbox reported {expected}. \
+        "Incomplete transfer: {what} delivered {actual} bytes but the remote reported {expected}. \
          The local file was left untouched; retry the transfer."
     )]
     IncompleteTransfer {
@@ -40,7 +51,7 @@ pub enum SyncError {
     },
 
     #[error(
-        "Corrupt transfer: the {what} received does not match the content hash Dropbox reported \
+        "Corrupt transfer: the {what} received does not match the content hash the remote reported \
          (expected {expected}, computed {actual}). The local file was left untouched; \
          retry the transfer."
     )]
@@ -51,7 +62,7 @@ pub enum SyncError {
     },
 
     #[error(
-        "Dropbox reported no content hash for {path}, so the download could not be verified \
+        "The remote reported no content hash for {path}, so the download could not be verified \
          against the stored file."
     )]
     MissingContentHash { path: String },
@@ -60,23 +71,34 @@ pub enum SyncError {
     Config(String),
 
     #[error(
-        "The {what} on Dropbox has changed since this machine last synced, so pushing would \
-         discard those changes. Run 'grans dropbox pull' first, or push --force to overwrite."
+        "The {what} on {remote} has changed since this machine last synced, so pushing would \
+         discard those changes. Run '{cli} merge' to combine both copies, '{cli} pull' to take \
+         the remote one, or push --force to overwrite it."
     )]
-    ConflictRemoteChanged { what: String },
+    ConflictRemoteChanged {
+        what: String,
+        remote: String,
+        cli: String,
+    },
 
     #[error(
         "The local {what} has changed since this machine last synced, so pulling would discard \
-         those changes. Run 'grans dropbox push' first, or pull --force to overwrite."
+         those changes. Run '{cli} merge' to combine both copies, '{cli} push' to keep the \
+         local one, or pull --force to overwrite it."
     )]
-    ConflictLocalChanged { what: String },
+    ConflictLocalChanged { what: String, cli: String },
 
     #[error(
-        "The {what} on Dropbox differs from the local copy, and grans has no record of syncing \
+        "The {what} on {remote} differs from the local copy, and grans has no record of syncing \
          with it, so neither can be shown to supersede the other. Compare them with \
-         'grans dropbox status', then push --force or pull --force to choose."
+         '{cli} status', then '{cli} merge' to combine them, or push --force or pull --force \
+         to choose one."
     )]
-    ConflictNoSyncRecord { what: String },
+    ConflictNoSyncRecord {
+        what: String,
+        remote: String,
+        cli: String,
+    },
 
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
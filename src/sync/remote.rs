@@ -0,0 +1,87 @@
+//! Where pushed databases are kept.
+//!
+//! Push, pull and status need only a handful of things from a backend:
+//! describe a stored file, upload one, stream one back, copy or delete one,
+//! and name itself in messages.
+//! Everything else (deciding who may overwrite whom, verifying a download,
+//! checking the pulled database) is the same whichever backend holds the
+//! files.
+//!
+//! Hashes are always Dropbox content hashes ([`super::content_hash`]), so
+//! `last_synced_hash` means the same thing for every backend. Dropbox
+//! computes them itself; S3 keeps them as object metadata; a folder or a
+//! WebDAV collection keeps them in a sidecar file next to the one it
+//! describes.
+
+use std::io::Write;
+use std::path::Path;
+
+use super::SyncResult;
+use super::transfer::ProgressFn;
+
+/// Remote path of the database, relative to the backend's root.
+pub const REMOTE_DB_PATH: &str = "/grans.db";
+
+/// Remote path of the [`super::metadata::SyncMetadata`] pushed alongside it.
+pub const REMOTE_METADATA_PATH: &str = "/sync_metadata.json";
+
+/// A stored file, as a backend describes it.
+#[derive(Debug, Clone)]
+pub struct RemoteFile {
+    pub size: u64,
+    /// Unix timestamp of the last write, if the backend reports one.
+    pub modified: Option<u64>,
+    /// Dropbox content hash of the stored bytes. `None` when the backend
+    /// cannot say, which makes a download unverifiable.
+    pub content_hash: Option<String>,
+}
+
+/// A place push and pull replicate the database to.
+///
+/// Paths are the `REMOTE_*` constants: absolute within the backend's root.
+pub trait RemoteStore {
+    /// How the store reads in messages: "Dropbox", "/mnt/nas/grans".
+    fn describe(&self) -> String;
+
+    /// Describe a stored file, or `None` if there is none.
+    fn stat(&self, path: &str) -> SyncResult<Option<RemoteFile>>;
+
+    /// Store a local file, replacing any file at `path`, and prove it
+    /// arrived intact.
+    fn upload(
+        &self,
+        local_path: &Path,
+        path: &str,
+        on_progress: ProgressFn,
+    ) -> SyncResult<RemoteFile>;
+
+    /// Copy a stored file to `to`, replacing any file there, without the
+    /// bytes passing through this machine where the backend allows. The copy
+    /// keeps the original's content hash.
+    fn copy(&self, from: &str, to: &str) -> SyncResult<()>;
+
+    /// Remove a stored file. A file that is already gone is not an error.
+    fn delete(&self, path: &str) -> SyncResult<()>;
+
+    /// Stream a stored file into `writer`, reporting the running byte count.
+    /// Returns the number of bytes written.
+    fn download_to_writer(
+        &self,
+        path: &str,
+        writer: &mut dyn Write,
+        on_progress: &mut dyn FnMut(u64),
+    ) -> SyncResult<u64>;
+
+    /// Download a small file, such as sync metadata, into memory.
+    fn download(&self, path: &str) -> SyncResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        self.download_to_writer(path, &mut buffer, &mut |_| {})?;
+        Ok(buffer)
+    }
+}
+
+/// Path of the sidecar holding a file's content hash, for backends that do
+/// not store one themselves.
+pub fn hash_sidecar(path: &str) -> String {
+    format!("{}.content_hash", path)
+}
@@ -0,0 +1,252 @@
+//! Dated snapshots of the remote database, so a bad push cannot destroy the
+//! only remote copy.
+//!
+//! After each push that changes the remote database, the remote keeps a
+//! copy of the files making it up (the base and its patches) under
+//! snapshot names, and an index listing each snapshot with the metadata
+//! pushed alongside it. Copies are made on the remote, so a snapshot costs
+//! storage but no upload. Copies are named after their contents, so
+//! snapshots taken on the same base share its copy, and a delta push adds
+//! only a copy of its patch. Once more than the configured number of
+//! snapshots are kept, the oldest are dropped along with any copies no
+//! remaining snapshot uses. A remote database no snapshot holds, such as
+//! one pushed from a machine keeping none, is snapshotted before a push
+//! replaces it.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::delta::{DeltaManifest, RemoteDatabase};
+use super::metadata::SyncMetadata;
+
+/// Remote path of the snapshot index.
+pub const REMOTE_SNAPSHOTS_PATH: &str = "/grans.db.snapshots.json";
+
+/// How many snapshots push keeps unless configured otherwise.
+pub const DEFAULT_SNAPSHOTS_KEPT: usize = 5;
+
+/// The remote database as one push left it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// When it was taken, as `20261017T140312Z`; unique within the index.
+    pub name: String,
+    /// The same moment as a Unix timestamp.
+    pub created: u64,
+    /// Remote path of the copy of the base.
+    pub base: String,
+    /// The patches on top of that base, with their paths pointing at the
+    /// snapshot's copies.
+    pub manifest: DeltaManifest,
+    /// Statistics of the database, as pushed alongside it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<SyncMetadata>,
+}
+
+impl Snapshot {
+    /// Take a snapshot of `remote` at `created`. Returns it along with the
+    /// copies it needs, as (live path, snapshot path) pairs; `None` when
+    /// the remote base has no content hash to name its copy after.
+    pub fn of(
+        remote: &RemoteDatabase,
+        created: DateTime<Utc>,
+        metadata: Option<SyncMetadata>,
+    ) -> Option<(Self, Vec<(String, String)>)> {
+        let stored_hash = remote.base.content_hash.as_deref()?;
+        // Without a manifest the base is the unencrypted database itself.
+        let mut manifest = remote
+            .chain()
+            .cloned()
+            .unwrap_or_else(|| DeltaManifest::new(stored_hash, remote.base.size));
+
+        let base = format!(
+            "/snapshot-grans.db-{}",
+            &stored_hash[..stored_hash.len().min(16)]
+        );
+        let mut copies = vec![(remote.path().to_string(), base.clone())];
+        for patch in &mut manifest.patches {
+            let copy = format!("/snapshot-{}", patch.path.trim_start_matches('/'));
+            copies.push((std::mem::replace(&mut patch.path, copy.clone()), copy));
+        }
+
+        let snapshot = Self {
+            name: created.format("%Y%m%dT%H%M%SZ").to_string(),
+            created: u64::try_from(created.timestamp()).unwrap_or_default(),
+            base,
+            manifest,
+            metadata,
+        };
+        Some((snapshot, copies))
+    }
+
+    /// Content hash of the database the snapshot holds.
+    pub fn hash(&self) -> &str {
+        self.manifest.tip()
+    }
+
+    /// Size of the database the snapshot holds.
+    pub fn size(&self) -> u64 {
+        self.manifest.tip_size()
+    }
+
+    /// Remote paths of the copies the snapshot is made of.
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.base.as_str())
+            .chain(self.manifest.patches.iter().map(|p| p.path.as_str()))
+    }
+}
+
+/// Every snapshot the remote keeps, oldest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SnapshotIndex {
+    pub snapshots: Vec<Snapshot>,
+}
+
+impl SnapshotIndex {
+    pub fn find(&self, name: &str) -> Option<&Snapshot> {
+        self.snapshots.iter().find(|s| s.name == name)
+    }
+
+    /// Whether some snapshot holds the database with content hash `hash`.
+    pub fn holds(&self, hash: &str) -> bool {
+        self.snapshots.iter().any(|s| s.hash() == hash)
+    }
+
+    /// Whether some snapshot already uses the copy at `path`.
+    pub fn uses(&self, path: &str) -> bool {
+        self.snapshots.iter().any(|s| s.files().any(|f| f == path))
+    }
+
+    /// A creation time for a new snapshot at `now`, moved on by a second
+    /// at a time until its name is not taken.
+    pub fn free_moment(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let mut moment = now;
+        while self
+            .find(&moment.format("%Y%m%dT%H%M%SZ").to_string())
+            .is_some()
+        {
+            moment += Duration::seconds(1);
+        }
+        moment
+    }
+
+    /// Drop all but the newest `keep` snapshots. Returns the copies no
+    /// remaining snapshot uses, to be deleted once the index is saved.
+    pub fn prune(&mut self, keep: usize) -> Vec<String> {
+        let excess = self.snapshots.len().saturating_sub(keep);
+        let dropped: Vec<Snapshot> = self.snapshots.drain(..excess).collect();
+        let kept: HashSet<&str> = self.snapshots.iter().flat_map(Snapshot::files).collect();
+
+        let mut unused = Vec::new();
+        for path in dropped.iter().flat_map(Snapshot::files) {
+            if !kept.contains(path) && !unused.iter().any(|p| p == path) {
+                unused.push(path.to_string());
+            }
+        }
+        unused
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::delta::PatchEntry;
+    use crate::sync::remote::{REMOTE_DB_PATH, RemoteFile};
+    use chrono::TimeZone;
+
+    fn remote(stored_hash: &str, patches: &[(&str, &str)]) -> RemoteDatabase {
+        let mut manifest = DeltaManifest::new(stored_hash, 100);
+        for (from, to) in patches {
+            manifest.patches.push(PatchEntry {
+                path: DeltaManifest::patch_path(from, to),
+                from: from.to_string(),
+                to: to.to_string(),
+                db_size: 100,
+                size: 10,
+            });
+        }
+        RemoteDatabase::at(
+            REMOTE_DB_PATH,
+            RemoteFile {
+                size: 100,
+                modified: None,
+                content_hash: Some(stored_hash.to_string()),
+            },
+            Some(manifest),
+        )
+    }
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_760_000_000 + seconds, 0).unwrap()
+    }
+
+    #[test]
+    fn a_snapshot_points_at_copies_of_the_base_and_patches() {
+        let (snapshot, copies) = Snapshot::of(&remote("base", &[("base", "tip")]), at(0), None)
+            .expect("the base has a hash");
+
+        assert_eq!(snapshot.name, "20251009T085320Z");
+        assert_eq!(snapshot.hash(), "tip");
+        assert_eq!(
+            copies,
+            vec![
+                (
+                    "/grans.db".to_string(),
+                    "/snapshot-grans.db-base".to_string()
+                ),
+                (
+                    "/grans.db.delta-base-tip".to_string(),
+                    "/snapshot-grans.db.delta-base-tip".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            snapshot.files().collect::<Vec<_>>(),
+            copies.iter().map(|(_, to)| to.as_str()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn snapshots_taken_in_the_same_second_get_distinct_names() {
+        let mut index = SnapshotIndex::default();
+        let (first, _) = Snapshot::of(&remote("a", &[]), at(0), None).unwrap();
+        index.snapshots.push(first);
+
+        let moment = index.free_moment(at(0));
+        assert_eq!(moment, at(1));
+        assert_eq!(index.free_moment(at(5)), at(5));
+    }
+
+    #[test]
+    fn pruning_deletes_only_copies_no_kept_snapshot_uses() {
+        let mut index = SnapshotIndex::default();
+        for (i, database) in [
+            remote("a", &[]),
+            remote("a", &[("a", "b")]),
+            remote("c", &[]),
+        ]
+        .iter()
+        .enumerate()
+        {
+            let (snapshot, _) = Snapshot::of(database, at(i as i64), None).unwrap();
+            index.snapshots.push(snapshot);
+        }
+        assert!(index.uses("/snapshot-grans.db-a"));
+
+        // The second snapshot still needs the first one's base.
+        assert!(index.prune(2).is_empty());
+        assert_eq!(index.snapshots.len(), 2);
+
+        assert_eq!(
+            index.prune(1),
+            vec![
+                "/snapshot-grans.db-a".to_string(),
+                "/snapshot-grans.db.delta-a-b".to_string()
+            ]
+        );
+        assert!(index.holds("c") && !index.holds("b"));
+        assert_eq!(index.prune(0), vec!["/snapshot-grans.db-c".to_string()]);
+        assert!(index.snapshots.is_empty());
+    }
+}
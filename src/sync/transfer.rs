@@ -1,4 +1,4 @@
-//! HTTP transport policy and byte-moving helpers for Dropbox sync.
+//! HTTP transport policy and byte-moving helpers for remote sync.
 
 use std::io::{self, Read, Write};
 use std::time::Duration;
@@ -73,7 +73,7 @@ impl<R: Read> Read for ProgressReader<R> {
     }
 }
 
-/// Build the HTTP client used for every Dropbox call.
+/// Build the HTTP client used for every remote storage call.
 pub fn build_client() -> SyncResult<reqwest::blocking::Client> {
     build_client_with_stall_timeout(STALL_TIMEOUT)
 }
@@ -104,7 +104,7 @@ pub fn transport_hint(err: &reqwest::Error) -> String {
             STALL_TIMEOUT.as_secs()
         )
     } else if err.is_connect() {
-        "could not reach Dropbox (DNS, proxy, or TLS failure)".to_string()
+        "could not reach the server (DNS, proxy, or TLS failure)".to_string()
     } else if err.is_body() || err.is_decode() {
         "the connection dropped mid-transfer".to_string()
     } else {
@@ -124,7 +124,7 @@ pub fn transport_error(operation: impl Into<String>, source: reqwest::Error) ->
 /// Stream `reader` into `writer`, invoking `on_progress` with the running total.
 ///
 /// Returns the number of bytes copied.
-pub fn stream_with_progress<R: Read, W: Write>(
+pub fn stream_with_progress<R: Read, W: Write + ?Sized>(
     reader: &mut R,
     writer: &mut W,
     on_progress: &mut dyn FnMut(u64),
@@ -0,0 +1,232 @@
+//! A WebDAV collection as remote storage (Nextcloud, ownCloud, a NAS, or
+//! any server speaking WebDAV).
+//!
+//! Only plain `PUT`, `GET` and `HEAD` are used, on files directly inside a
+//! collection the user names, which must already exist. WebDAV has no
+//! content hash, so the hash is stored in a sidecar next to each file and an
+//! upload is checked by reading back the stored size.
+
+use std::io::Write;
+use std::path::Path;
+
+use log::debug;
+use reqwest::blocking::{RequestBuilder, Response};
+use reqwest::{Method, StatusCode};
+
+use super::content_hash::hash_file;
+use super::remote::{RemoteFile, RemoteStore, hash_sidecar};
+use super::transfer::{self, ProgressFn, ProgressReader, UPLOAD_TIMEOUT};
+use super::{SyncError, SyncResult};
+
+/// A WebDAV collection holding the pushed files.
+pub struct WebDavStore {
+    /// Collection URL without a trailing slash.
+    base: String,
+    username: Option<String>,
+    password: Option<String>,
+    http: reqwest::blocking::Client,
+}
+
+impl WebDavStore {
+    pub fn new(url: &str, username: Option<String>, password: Option<String>) -> SyncResult<Self> {
+        Ok(Self {
+            base: url.trim_end_matches('/').to_string(),
+            username,
+            password,
+            http: transfer::build_client()?,
+        })
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let request = self.http.request(method, format!("{}{}", self.base, path));
+        match &self.username {
+            Some(user) => request.basic_auth(user, self.password.as_ref()),
+            None => request,
+        }
+    }
+
+    /// Send a request, turning transport failures and error statuses into
+    /// errors. A 404 is returned as `None`.
+    fn send(&self, request: RequestBuilder, operation: &str) -> SyncResult<Option<Response>> {
+        let response = request
+            .send()
+            .map_err(|e| transfer::transport_error(format!("WebDAV {}", operation), e))?;
+        let status = response.status();
+        debug!("  WebDAV {}: {}", operation, status);
+        if status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(SyncError::RemoteApi(format!(
+                "WebDAV {} returned HTTP {}: {}",
+                operation,
+                status,
+                body.trim()
+            )));
+        }
+        Ok(Some(response))
+    }
+
+    fn put(&self, path: &str, body: reqwest::blocking::Body) -> SyncResult<()> {
+        let request = self
+            .request(Method::PUT, path)
+            .header("Content-Type", "application/octet-stream")
+            .timeout(UPLOAD_TIMEOUT)
+            .body(body);
+        self.send(request, &format!("upload of {}", path))?
+            .ok_or_else(|| {
+                SyncError::RemoteApi(format!(
+                    "{} does not exist; create the collection first",
+                    self.base
+                ))
+            })
+            .map(drop)
+    }
+}
+
+/// Parse an HTTP-date header ("Sun, 06 Nov 1994 08:49:37 GMT") to a Unix
+/// timestamp.
+fn parse_http_date(value: &str) -> Option<u64> {
+    chrono::DateTime::parse_from_rfc2822(value)
+        .ok()
+        .and_then(|dt| u64::try_from(dt.timestamp()).ok())
+}
+
+impl RemoteStore for WebDavStore {
+    fn describe(&self) -> String {
+        self.base.clone()
+    }
+
+    fn stat(&self, path: &str) -> SyncResult<Option<RemoteFile>> {
+        let head = self.request(Method::HEAD, path);
+        let Some(response) = self.send(head, &format!("metadata lookup for {}", path))? else {
+            return Ok(None);
+        };
+        // Read the header itself: for HEAD, reqwest's content_length() reports
+        // the (empty) body.
+        let header = |name: reqwest::header::HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let size = header(reqwest::header::CONTENT_LENGTH)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0);
+        let modified = header(reqwest::header::LAST_MODIFIED).and_then(|v| parse_http_date(&v));
+
+        let sidecar = hash_sidecar(path);
+        let get = self.request(Method::GET, &sidecar);
+        let content_hash = match self.send(get, &format!("download of {}", sidecar))? {
+            Some(response) => {
+                let text = response.text().map_err(|e| {
+                    transfer::transport_error(format!("WebDAV download of {}", sidecar), e)
+                })?;
+                Some(text.trim().to_string()).filter(|h| !h.is_empty())
+            }
+            None => None,
+        };
+
+        Ok(Some(RemoteFile {
+            size,
+            modified,
+            content_hash,
+        }))
+    }
+
+    fn upload(
+        &self,
+        local_path: &Path,
+        path: &str,
+        on_progress: ProgressFn,
+    ) -> SyncResult<RemoteFile> {
+        let size = std::fs::metadata(local_path)?.len();
+        let local_hash = hash_file(local_path)?;
+
+        let file = std::fs::File::open(local_path)?;
+        self.put(
+            path,
+            reqwest::blocking::Body::sized(ProgressReader::new(file, on_progress), size),
+        )?;
+        // Written second, so a reader never pairs the new hash with the old
+        // file; the reverse pairing fails verification and is retried.
+        self.put(&hash_sidecar(path), local_hash.clone().into())?;
+
+        let stored = self.stat(path)?.ok_or_else(|| {
+            SyncError::RemoteApi(format!("{}{} vanished after upload", self.base, path))
+        })?;
+        transfer::verify_transfer_size(stored.size, size, "uploaded file")?;
+        Ok(RemoteFile {
+            content_hash: Some(local_hash),
+            ..stored
+        })
+    }
+
+    fn copy(&self, from: &str, to: &str) -> SyncResult<()> {
+        let copy = Method::from_bytes(b"COPY").expect("COPY is a valid method");
+        for (source, target) in [
+            (from.to_string(), to.to_string()),
+            (hash_sidecar(from), hash_sidecar(to)),
+        ] {
+            let request = self
+                .request(copy.clone(), &source)
+                .header("Destination", format!("{}{}", self.base, target))
+                .header("Overwrite", "T")
+                .timeout(UPLOAD_TIMEOUT);
+            let operation = format!("copy of {} to {}", source, target);
+            self.send(request, &operation)?.ok_or_else(|| {
+                SyncError::RemoteApi(format!("{}{} not found", self.base, source))
+            })?;
+        }
+        Ok(())
+    }
+
+    fn delete(&self, path: &str) -> SyncResult<()> {
+        for target in [path.to_string(), hash_sidecar(path)] {
+            let request = self.request(Method::DELETE, &target);
+            // A 404 comes back as None: already gone.
+            self.send(request, &format!("delete of {}", target))?;
+        }
+        Ok(())
+    }
+
+    fn download_to_writer(
+        &self,
+        path: &str,
+        writer: &mut dyn Write,
+        on_progress: &mut dyn FnMut(u64),
+    ) -> SyncResult<u64> {
+        let operation = format!("download of {}", path);
+        let mut response = self
+            .send(self.request(Method::GET, path), &operation)?
+            .ok_or_else(|| SyncError::RemoteApi(format!("{}{} not found", self.base, path)))?;
+        transfer::stream_with_progress(&mut response, writer, on_progress)
+            .map_err(|e| transfer::stream_error(format!("WebDAV {}", operation), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_http_dates() {
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(784111777)
+        );
+        assert_eq!(parse_http_date("yesterday"), None);
+    }
+
+    #[test]
+    fn collection_url_is_joined_without_doubled_slashes() {
+        let store = WebDavStore::new("https://dav.example.com/grans/", None, None).unwrap();
+        let request = store.request(Method::GET, "/grans.db").build().unwrap();
+        assert_eq!(
+            request.url().as_str(),
+            "https://dav.example.com/grans/grans.db"
+        );
+    }
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use common::TestEnv;
+
+fn env() -> TestEnv {
+    TestEnv::with_state(
+        &serde_json::json!({
+            "documents": {
+                "doc-1": {
+                    "id": "doc-1",
+                    "title": "Launch Planning",
+                    "created_at": "2026-01-20T10:00:00Z",
+                    "people": {"creator": {"name": "Alice Smith", "email": "alice@example.com"}}
+                }
+            },
+            "panels": {
+                "doc-1": [{
+                    "id": "panel-1",
+                    "document_id": "doc-1",
+                    "title": "Summary",
+                    "content_markdown": "### Decisions\n\n- Launch on March 3\n\n### Action Items\n\n- Alice: send the launch deck\n- Bob to book the venue",
+                    "created_at": "2026-01-20T11:00:00Z"
+                }]
+            }
+        })
+        .to_string(),
+    )
+}
+
+#[test]
+fn actions_list_mine_and_mark_done() {
+    let env = env();
+    let json = |args: &[&str]| -> serde_json::Value {
+        let output = env.cmd_json().args(args).output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    // Panels stored before extraction existed are backfilled on first use.
+    let all = json(&["actions", "list"]);
+    assert_eq!(all.as_array().unwrap().len(), 2);
+    assert_eq!(all[1]["owner"], "Bob");
+    assert_eq!(all[1]["title"], "Launch Planning");
+
+    let mine = json(&["actions", "list", "--mine", "--open"]);
+    assert_eq!(mine.as_array().unwrap().len(), 1);
+    assert_eq!(mine[0]["text"], "send the launch deck");
+    let id = mine[0]["id"].as_str().unwrap();
+
+    let marked = json(&["actions", "done", &id[..6]]);
+    assert_eq!(marked["changed"][0], id);
+    let mine = json(&["actions", "list", "--mine", "--open"]);
+    assert!(mine.as_array().unwrap().is_empty());
+
+    let decisions = json(&["actions", "list", "--decisions"]);
+    assert_eq!(decisions[0]["text"], "Launch on March 3");
+    assert_eq!(decisions[0]["kind"], "decision");
+}
+
+#[test]
+fn actions_tty_groups_items_under_their_meeting() {
+    let env = env();
+    let output = env.cmd().args(["actions", "list"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Launch Planning"));
+    assert!(stdout.contains("[ ] send the launch deck — Alice"));
+}
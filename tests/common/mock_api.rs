@@ -0,0 +1,185 @@
+//! A stand-in for the Granola API, driven by the JSON fixtures under
+//! `tests/fixtures/api`.
+//!
+//! grans reaches it through `GRANS_API_BASE_URL`, so a test exercises the real
+//! client end to end: request building, status handling, deserialization and
+//! the upserts behind it. The fixture tree mirrors the URL space:
+//!
+//! - `v1/<endpoint>.json` and `v2/<endpoint>.json` answer that endpoint
+//! - `v1/<endpoint>/<document_id>.json` answers a per-document endpoint
+//!   (`get-document-transcript`, `get-document-panels`), keyed by the
+//!   `document_id` in the request body
+//! - `v2/<endpoint>/<cursor>.json` answers a follow-up page, keyed by the
+//!   `cursor` in the request body
+//!
+//! A request with no matching fixture gets a 404, which is what the real
+//! service answers for a document with no transcript. [`MockApi::rate_limit_next`]
+//! makes the next requests answer 429 instead, as a throttled service does.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// One request the mock received.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub authorization: Option<String>,
+    pub body: serde_json::Value,
+}
+
+/// A running mock server. It lives until the test process exits.
+pub struct MockApi {
+    base_url: String,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    throttled: Arc<AtomicUsize>,
+}
+
+impl MockApi {
+    /// Serve the bundled fixtures in `tests/fixtures/api`.
+    pub fn start() -> Self {
+        Self::serve(PathBuf::from(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/api"
+        )))
+    }
+
+    /// Serve the fixtures under `root` on an ephemeral loopback port.
+    pub fn serve(root: PathBuf) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let throttled = Arc::new(AtomicUsize::new(0));
+
+        let log = Arc::clone(&requests);
+        let throttle = Arc::clone(&throttled);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let root = root.clone();
+                let log = Arc::clone(&log);
+                let throttle = Arc::clone(&throttle);
+                thread::spawn(move || handle(stream, &root, &log, &throttle));
+            }
+        });
+
+        MockApi {
+            base_url,
+            requests,
+            throttled,
+        }
+    }
+
+    /// Answer the next `count` requests with 429 and `Retry-After: 0`.
+    pub fn rate_limit_next(&self, count: usize) {
+        self.throttled.store(count, Ordering::SeqCst);
+    }
+
+    /// The value to put in `GRANS_API_BASE_URL`.
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Every request received so far, in arrival order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Paths of every request received so far, in arrival order.
+    pub fn paths(&self) -> Vec<String> {
+        self.requests().into_iter().map(|r| r.path).collect()
+    }
+}
+
+fn handle(
+    stream: TcpStream,
+    root: &Path,
+    log: &Mutex<Vec<RecordedRequest>>,
+    throttled: &AtomicUsize,
+) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+        return;
+    }
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    let mut authorization = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).unwrap_or(0) == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => content_length = value.trim().parse().unwrap_or(0),
+                "authorization" => authorization = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+    }
+
+    let mut raw_body = vec![0; content_length];
+    reader.read_exact(&mut raw_body).unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&raw_body).unwrap_or_default();
+
+    let fixture = fixture_path(root, &path, &body);
+    log.lock().unwrap().push(RecordedRequest {
+        method,
+        path,
+        authorization,
+        body,
+    });
+
+    let throttle = throttled
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+        .is_ok();
+    let (status, payload) = match fixture.and_then(|p| std::fs::read_to_string(p).ok()) {
+        _ if throttle => (
+            "429 Too Many Requests",
+            r#"{"message":"Slow down"}"#.to_string(),
+        ),
+        Some(json) => ("200 OK", json),
+        None => ("404 Not Found", r#"{"message":"Not found"}"#.to_string()),
+    };
+    let extra_headers = if throttle { "Retry-After: 0\r\n" } else { "" };
+
+    let mut stream = stream;
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+        status,
+        payload.len(),
+        extra_headers,
+        payload
+    );
+}
+
+/// The fixture answering a request, if the tree has one.
+fn fixture_path(root: &Path, path: &str, body: &serde_json::Value) -> Option<PathBuf> {
+    let relative = path.trim_start_matches('/');
+    if relative.is_empty() || relative.contains("..") {
+        return None;
+    }
+
+    let keyed = ["document_id", "cursor"]
+        .iter()
+        .find_map(|key| body.get(key).and_then(|v| v.as_str()))
+        .map(|key| root.join(relative).join(format!("{}.json", key)));
+    let per_endpoint = root.join(format!("{}.json", relative));
+
+    keyed
+        .filter(|p| p.exists())
+        .or_else(|| per_endpoint.exists().then_some(per_endpoint))
+}
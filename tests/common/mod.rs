@@ -1,5 +1,7 @@
 #![allow(dead_code)]
 
+pub mod mock_api;
+
 use std::path::PathBuf;
 
 use assert_cmd::Command;
@@ -29,6 +31,11 @@ const MIGRATION_SQL: &[&str] = &[
     include_str!("../../src/db/migrations/v015_fts_triggers.sql"),
     include_str!("../../src/db/migrations/v016_titles_fts.sql"),
     include_str!("../../src/db/migrations/v017_account_provenance.sql"),
+    include_str!("../../src/db/migrations/v018_ann_index.sql"),
+    include_str!("../../src/db/migrations/v019_saved_searches.sql"),
+    include_str!("../../src/db/migrations/v020_action_items.sql"),
+    include_str!("../../src/db/migrations/v021_person_aliases.sql"),
+    include_str!("../../src/db/migrations/v022_event_documents.sql"),
 ];
 
 /// A self-contained test environment with a test database and isolated data directory.
@@ -236,15 +243,17 @@ fn insert_test_data(conn: &Connection, state: &serde_json::Value) {
                 .get("end")
                 .and_then(|e| e.get("dateTime"))
                 .and_then(|d| d.as_str());
+            let attendees_json = event.get("attendees").map(|a| a.to_string());
             conn.execute(
-                "INSERT INTO events (id, summary, start_time, end_time, calendar_id)
-                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                "INSERT INTO events (id, summary, start_time, end_time, calendar_id, attendees_json)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
                 rusqlite::params![
                     event.get("id").and_then(|v| v.as_str()),
                     event.get("summary").and_then(|v| v.as_str()),
                     start_time,
                     end_time,
                     event.get("calendarId").and_then(|v| v.as_str()),
+                    attendees_json,
                 ],
             )
             .unwrap();
@@ -1,6 +1,6 @@
 mod common;
 
-use common::TestEnv;
+use common::{TestEnv, fixture_state};
 use predicates::prelude::*;
 
 // --- list ---
@@ -125,6 +125,58 @@ fn meetings_show_transcript_json() {
     assert!(json.get("notes_plain").is_none());
 }
 
+#[test]
+fn stats_talk_time_for_one_meeting() {
+    let env = TestEnv::with_fixture();
+    let output = env
+        .cmd_json()
+        .args(["stats", "talk-time", "Beta"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["meetings"][0]["id"], "doc-beta");
+    assert_eq!(json["talk_seconds"], 90.0);
+    let speakers = json["speakers"].as_array().unwrap();
+    // Each detected speaker holds the floor for one 30-second turn.
+    assert_eq!(speakers.len(), 3);
+    assert!(
+        speakers
+            .iter()
+            .all(|s| s["turns"] == 1 && s["talk_seconds"] == 30.0)
+    );
+}
+
+#[test]
+fn stats_talk_time_totals_a_date_range() {
+    let env = TestEnv::with_fixture();
+    env.cmd()
+        .args([
+            "stats",
+            "talk-time",
+            "--from",
+            "2025-06-01",
+            "--to",
+            "2025-08-01",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Talk time in 2 meetings"))
+        .stdout(predicate::str::contains("Other"))
+        .stdout(predicate::str::contains("Marcus Webb"));
+}
+
+#[test]
+fn stats_talk_time_needs_a_meeting_or_range() {
+    let env = TestEnv::with_fixture();
+    env.cmd()
+        .args(["stats", "talk-time"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("date range"));
+}
+
 #[test]
 fn meetings_show_notes_json() {
     let env = TestEnv::with_fixture();
@@ -317,6 +369,56 @@ fn people_show_by_email() {
         .stdout(predicate::str::contains("Bob Smith"));
 }
 
+// --- browse people profile ---
+
+#[test]
+fn people_profile_json() {
+    let env = TestEnv::with_fixture();
+    let output = env
+        .cmd_json()
+        .args(["browse", "people", "profile", "bob@example.com"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["name"], "Bob Smith");
+    assert_eq!(json["company_name"], "Acme Corp");
+    assert_eq!(json["meetings"], 2);
+    assert_eq!(json["last_meeting"]["id"], "doc-beta");
+    assert_eq!(json["monthly"].as_array().unwrap().len(), 2);
+    // Alice recorded doc-alpha, so she is not counted as a co-attendee.
+    let co = json["co_attendees"].as_array().unwrap();
+    assert_eq!(co.len(), 1);
+    assert_eq!(co[0]["name"], "Carol Williams");
+}
+
+#[test]
+fn people_profile_for_detected_speaker() {
+    let env = TestEnv::with_fixture();
+    env.cmd()
+        .args(["browse", "people", "profile", "Marcus"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Marcus Webb"))
+        .stdout(predicate::str::contains("Beta Feature Review"))
+        .stdout(predicate::str::contains(
+            "We should deploy the prototype to staging next sprint.",
+        ));
+}
+
+#[test]
+fn people_profile_ambiguous_query_errors() {
+    let env = TestEnv::with_fixture();
+    // "Priya" names two detected speakers.
+    env.cmd()
+        .args(["browse", "people", "profile", "Priya"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Priya Raman"))
+        .stderr(predicate::str::contains("Priya Nair"));
+}
+
 // --- with (people meetings) ---
 
 #[test]
@@ -371,6 +473,76 @@ fn calendars_events_shows_events() {
         .stdout(predicate::str::contains("Beta Feature Review"));
 }
 
+#[test]
+fn calendars_events_json_includes_recorded_meetings() {
+    let env = TestEnv::with_fixture();
+    let output = env
+        .cmd_json()
+        .args(["browse", "calendars", "events"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let events: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let alpha = events
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|e| e["id"] == "evt-alpha")
+        .unwrap();
+    assert_eq!(alpha["meetings"][0]["id"], "doc-alpha");
+    assert_eq!(alpha["meetings"][0]["method"], "event_id");
+}
+
+// --- calendar gaps ---
+
+fn state_with_unrecorded_event() -> String {
+    let mut state: serde_json::Value = serde_json::from_str(&fixture_state()).unwrap();
+    state["events"]
+        .as_array_mut()
+        .unwrap()
+        .push(serde_json::json!({
+            "id": "evt-delta",
+            "summary": "Quarterly Planning",
+            "start": {"dateTime": "2025-08-12T13:00:00-05:00"},
+            "end": {"dateTime": "2025-08-12T14:00:00-05:00"},
+            "calendarId": "cal-primary",
+            "attendees": [
+                {"email": "alice@example.com", "responseStatus": "accepted"},
+                {"email": "bob@example.com", "responseStatus": "accepted"}
+            ]
+        }));
+    state.to_string()
+}
+
+#[test]
+fn calendar_gaps_lists_only_unrecorded_events() {
+    let env = TestEnv::with_state(&state_with_unrecorded_event());
+    env.cmd()
+        .args([
+            "calendar",
+            "gaps",
+            "--from",
+            "2025-06-01",
+            "--to",
+            "2025-09-01",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Quarterly Planning"))
+        .stdout(predicate::str::contains("Alpha").not())
+        .stdout(predicate::str::contains("Gamma").not());
+}
+
+#[test]
+fn calendar_gaps_none_when_every_event_recorded() {
+    let env = TestEnv::with_fixture();
+    env.cmd()
+        .args(["calendar", "gaps"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No unrecorded meetings."));
+}
+
 // --- browse templates list ---
 
 #[test]
@@ -32,3 +32,18 @@ fn invalid_subcommand_shows_help() {
 
     cmd.assert().failure();
 }
+
+#[test]
+fn models_import_without_exports_errors() {
+    let env = TestEnv::with_fixture();
+    let empty = env.dir.path().join("empty");
+    std::fs::create_dir_all(&empty).unwrap();
+
+    env.cmd()
+        .env("GRANS_MODEL_DIR", env.dir.path().join("models"))
+        .args(["models", "import"])
+        .arg(&empty)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No exported models"));
+}
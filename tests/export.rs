@@ -0,0 +1,48 @@
+mod common;
+
+use common::TestEnv;
+use predicates::prelude::*;
+
+#[test]
+fn export_markdown_writes_front_matter_and_transcript() {
+    let env = TestEnv::with_fixture();
+    let vault = env.dir.path().join("vault");
+
+    env.cmd()
+        .args(["--utc", "export", "markdown"])
+        .arg(&vault)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("0 unchanged"));
+
+    let note = std::fs::read_to_string(vault.join("2025-06-15 Project Alpha Kickoff.md")).unwrap();
+    assert!(note.starts_with("---\nid: \"doc-alpha\"\n"));
+    assert!(note.contains("  - \"Alice Johnson <alice@example.com>\"\n"));
+    assert!(note.contains("  - \"Bob Smith <bob@example.com>\"\n"));
+    assert!(note.contains("calendar_event:\n  id: \"evt-alpha\"\n"));
+    assert!(note.contains("## Notes\n\n# Project Alpha\n"));
+    assert!(note.contains("- `10:01:00` **Other:** Welcome everyone to the kickoff meeting.\n"));
+}
+
+#[test]
+fn second_export_skips_unchanged_meetings() {
+    let env = TestEnv::with_fixture();
+    let vault = env.dir.path().join("vault");
+
+    env.cmd()
+        .args(["export", "markdown"])
+        .arg(&vault)
+        .assert()
+        .success();
+
+    let output = env
+        .cmd_json()
+        .args(["export", "markdown"])
+        .arg(&vault)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(summary["written"], serde_json::json!([]));
+    assert!(summary["unchanged"].as_u64().unwrap() > 0);
+}
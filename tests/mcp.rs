@@ -0,0 +1,78 @@
+mod common;
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use common::TestEnv;
+use serde_json::{Value, json};
+
+/// Run `grans mcp` over a scripted session and return its replies in order.
+fn mcp_session(env: &TestEnv, messages: &[Value]) -> Vec<Value> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_grans"))
+        .args(["mcp", "--fast"])
+        .env("XDG_DATA_HOME", env.dir.path().join("data"))
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    {
+        let mut stdin = child.stdin.take().unwrap();
+        for message in messages {
+            writeln!(stdin, "{}", message).unwrap();
+        }
+    }
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn mcp_session_initializes_and_answers_tool_calls() {
+    let env = TestEnv::with_fixture();
+
+    let replies = mcp_session(
+        &env,
+        &[
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": {},
+                "clientInfo": {"name": "test", "version": "0"}
+            }}),
+            json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+            json!({"jsonrpc": "2.0", "id": 2, "method": "tools/call", "params": {
+                "name": "grep_meetings",
+                "arguments": {"query": "kickoff", "in": ["transcripts"]}
+            }}),
+            json!({"jsonrpc": "2.0", "id": 3, "method": "resources/read", "params": {
+                "uri": "grans://meetings/doc-alpha/transcript"
+            }}),
+        ],
+    );
+
+    // The notification gets no reply, so three requests give three replies.
+    assert_eq!(replies.len(), 3);
+    assert_eq!(replies[0]["result"]["protocolVersion"], "2025-06-18");
+
+    let grep = &replies[1]["result"]["structuredContent"];
+    assert_eq!(grep["meetings"][0]["id"], "doc-alpha");
+    assert!(
+        grep["meetings"][0]["matches"][0]["snippet"]
+            .as_str()
+            .unwrap()
+            .to_lowercase()
+            .contains("kickoff")
+    );
+
+    let transcript = replies[2]["result"]["contents"][0]["text"]
+        .as_str()
+        .unwrap();
+    assert!(transcript.contains("Welcome everyone to the kickoff meeting."));
+}
@@ -87,3 +87,52 @@ fn people_meetings_json_structure() {
         assert!(doc["created_at"].is_string());
     }
 }
+
+// --- people merge/unmerge ---
+
+#[test]
+fn merged_identities_widen_with_and_speaker() {
+    let env = TestEnv::with_fixture();
+    // Marcus Webb is only a detected speaker (in doc-beta); merge him with
+    // an address so meetings listing the address count as his.
+    env.cmd()
+        .args(["people", "merge", "Marcus", "carol@widgets.io"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Marcus Webb"));
+
+    let output = env.cmd_json().args(["with", "Marcus"]).output().unwrap();
+    assert!(output.status.success());
+    let docs: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(docs.len(), 2);
+
+    // --speaker resolves the address to the name Marcus speaks under.
+    let output = env
+        .cmd_json()
+        .args(["grep", "prototype", "--speaker", "carol@widgets.io"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let meetings = json["meetings"].as_array().unwrap();
+    assert_eq!(meetings.len(), 1, "got: {json}");
+    assert_eq!(meetings[0]["id"], "doc-beta");
+
+    env.cmd()
+        .args(["people", "unmerge", "Marcus Webb"])
+        .assert()
+        .success();
+    let output = env.cmd_json().args(["with", "Marcus"]).output().unwrap();
+    let docs: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(docs.is_empty());
+}
+
+#[test]
+fn people_merge_rejects_unknown_identity() {
+    let env = TestEnv::with_fixture();
+    env.cmd()
+        .args(["people", "merge", "Alice", "nobody@nowhere.example"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("nobody@nowhere.example"));
+}
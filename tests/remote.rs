@@ -0,0 +1,499 @@
+mod common;
+
+use common::TestEnv;
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+/// Point `env` at `share` as its folder remote.
+fn init_folder(env: &TestEnv, share: &TempDir) {
+    env.cmd()
+        .args(["remote", "init", "folder"])
+        .arg(share.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Push and pull now replicate to"));
+}
+
+#[test]
+fn folder_remote_push_pull_and_status() {
+    let share = TempDir::new().unwrap();
+    let first = TestEnv::with_fixture();
+    init_folder(&first, &share);
+
+    first
+        .cmd()
+        .args(["remote", "push"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Push complete!"));
+    assert!(share.path().join("grans.db").exists());
+    assert!(share.path().join("grans.db.content_hash").exists());
+    assert!(share.path().join("sync_metadata.json").exists());
+
+    first
+        .cmd()
+        .args(["remote", "push"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("nothing to upload"));
+
+    // A second machine with its own database has never synced with the
+    // share, so neither side can be assumed to be the newer one.
+    let second = TestEnv::with_state("{}");
+    init_folder(&second, &share);
+    second
+        .cmd()
+        .args(["remote", "pull"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("grans remote"));
+
+    second
+        .cmd()
+        .args(["remote", "pull", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Pull complete!"));
+    assert_eq!(
+        std::fs::read(&second.db_path).unwrap(),
+        std::fs::read(share.path().join("grans.db")).unwrap()
+    );
+
+    let output = second
+        .cmd_json()
+        .args(["remote", "status"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(status["authenticated"], true);
+    assert_eq!(status["remote_kind"], "folder");
+    assert_eq!(status["remote_db"]["exists"], true);
+}
+
+#[test]
+fn folder_remote_requires_an_existing_directory() {
+    let env = TestEnv::with_fixture();
+    env.cmd()
+        .args(["remote", "init", "folder"])
+        .arg(env.dir.path().join("not-mounted"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("is not a directory"));
+}
+
+#[test]
+fn dropbox_commands_refuse_when_another_remote_is_configured() {
+    let share = TempDir::new().unwrap();
+    let env = TestEnv::with_fixture();
+    init_folder(&env, &share);
+
+    env.cmd()
+        .args(["dropbox", "push"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("grans remote push"));
+
+    env.cmd().args(["remote", "forget"]).assert().success();
+    env.cmd().args(["remote", "push"]).assert().failure();
+}
+
+/// A database holding one meeting the fixture lacks.
+fn other_machine() -> TestEnv {
+    TestEnv::with_state(
+        &serde_json::json!({
+            "documents": {
+                "doc-delta": {
+                    "id": "doc-delta",
+                    "title": "Delta Planning",
+                    "created_at": "2025-06-20T10:00:00.000Z",
+                    "updated_at": "2025-06-20T11:00:00.000Z",
+                    "type": "meeting"
+                }
+            }
+        })
+        .to_string(),
+    )
+}
+
+fn titles(env: &TestEnv) -> Vec<String> {
+    let conn = rusqlite::Connection::open(&env.db_path).unwrap();
+    let mut stmt = conn
+        .prepare("SELECT title FROM documents ORDER BY title")
+        .unwrap();
+    stmt.query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+#[test]
+fn merge_combines_diverged_copies_and_pushes_the_result() {
+    let share = TempDir::new().unwrap();
+    let first = TestEnv::with_fixture();
+    init_folder(&first, &share);
+    first.cmd().args(["remote", "push"]).assert().success();
+
+    let second = other_machine();
+    init_folder(&second, &share);
+    second
+        .cmd()
+        .args(["remote", "push"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("grans remote merge"));
+
+    second
+        .cmd()
+        .args(["remote", "merge"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("3 meeting(s) added"))
+        .stdout(predicate::str::contains("Push complete!"));
+    assert!(titles(&second).contains(&"Delta Planning".to_string()));
+    assert!(titles(&second).contains(&"Project Alpha Kickoff".to_string()));
+    assert_eq!(
+        std::fs::read(&second.db_path).unwrap(),
+        std::fs::read(share.path().join("grans.db")).unwrap()
+    );
+
+    // The first machine has not changed since it pushed, so it can pull
+    // the merged copy without a conflict.
+    first.cmd().args(["remote", "pull"]).assert().success();
+    assert!(titles(&first).contains(&"Delta Planning".to_string()));
+}
+
+#[test]
+fn admin_db_merge_reads_another_database_file() {
+    let env = TestEnv::with_fixture();
+    let other = other_machine();
+    let other_before = std::fs::read(&other.db_path).unwrap();
+
+    env.cmd()
+        .args(["admin", "db", "merge"])
+        .arg(&other.db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 meeting(s) added"));
+    assert_eq!(titles(&env).len(), 4);
+    assert_eq!(std::fs::read(&other.db_path).unwrap(), other_before);
+
+    env.cmd()
+        .args(["admin", "db", "merge"])
+        .arg(&other.db_path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("nothing this one lacks"));
+}
+
+#[test]
+fn push_sends_changes_as_a_patch_that_pull_applies() {
+    let share = TempDir::new().unwrap();
+    let first = TestEnv::with_fixture();
+    init_folder(&first, &share);
+    first.cmd().args(["remote", "push"]).assert().success();
+
+    let second = TestEnv::with_state("{}");
+    init_folder(&second, &share);
+    second
+        .cmd()
+        .args(["remote", "pull", "--force"])
+        .assert()
+        .success();
+    let base = std::fs::read(share.path().join("grans.db")).unwrap();
+
+    rusqlite::Connection::open(&first.db_path)
+        .unwrap()
+        .execute(
+            "UPDATE documents SET title = 'Project Alpha Retro' WHERE id = 'doc-alpha'",
+            [],
+        )
+        .unwrap();
+    first
+        .cmd()
+        .args(["remote", "push"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Uploading changes"));
+
+    // The base is moved aside untouched, and the change travels as a patch
+    // beside it. What is left at grans.db is not a database, so a grans
+    // that cannot apply patches refuses it instead of pulling the stale base.
+    assert_eq!(
+        std::fs::read(share.path().join("grans.db.base")).unwrap(),
+        base
+    );
+    assert!(starts_with(
+        share.path().join("grans.db"),
+        b"This grans remote"
+    ));
+    let patches: Vec<_> = std::fs::read_dir(share.path())
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("grans.db.delta-") && !name.ends_with(".content_hash"))
+        .collect();
+    assert_eq!(patches.len(), 1, "{:?}", patches);
+
+    second
+        .cmd()
+        .args(["remote", "pull"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 change(s)"));
+    assert_eq!(
+        std::fs::read(&second.db_path).unwrap(),
+        std::fs::read(&first.db_path).unwrap()
+    );
+
+    // A machine that has never synced rebuilds the same file from the base
+    // and the patch.
+    let third = TestEnv::with_state("{}");
+    init_folder(&third, &share);
+    third
+        .cmd()
+        .args(["remote", "pull", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Downloading database"));
+    assert_eq!(
+        std::fs::read(&third.db_path).unwrap(),
+        std::fs::read(&first.db_path).unwrap()
+    );
+
+    // A full push puts a whole database back where any grans reads it.
+    let fourth = TestEnv::with_fixture();
+    init_folder(&fourth, &share);
+    fourth
+        .cmd()
+        .args(["remote", "push", "--force"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Uploading database"));
+    assert_eq!(
+        std::fs::read(share.path().join("grans.db")).unwrap(),
+        std::fs::read(&fourth.db_path).unwrap()
+    );
+    assert!(!share.path().join("grans.db.base").exists());
+}
+
+fn starts_with(path: std::path::PathBuf, prefix: &[u8]) -> bool {
+    std::fs::read(path).unwrap().starts_with(prefix)
+}
+
+#[test]
+fn encrypted_pushes_are_unreadable_without_the_passphrase() {
+    let share = TempDir::new().unwrap();
+    let first = TestEnv::with_fixture();
+    init_folder(&first, &share);
+    first
+        .cmd()
+        .args(["remote", "encryption", "enable"])
+        .env("GRANS_SYNC_PASSPHRASE", "correct horse")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Encryption is on"));
+    first.cmd().args(["remote", "push"]).assert().success();
+
+    assert!(starts_with(
+        share.path().join("grans.db"),
+        b"grans-encrypted"
+    ));
+    assert!(starts_with(
+        share.path().join("sync_metadata.json"),
+        b"grans-encrypted"
+    ));
+    assert!(share.path().join("grans.key.json").exists());
+
+    let second = TestEnv::with_state("{}");
+    init_folder(&second, &share);
+    second
+        .cmd()
+        .args(["remote", "pull", "--force"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("grans remote encryption unlock"));
+    second
+        .cmd()
+        .args(["remote", "encryption", "unlock"])
+        .env("GRANS_SYNC_PASSPHRASE", "battery staple")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("wrong passphrase"));
+    second
+        .cmd()
+        .args(["remote", "encryption", "unlock"])
+        .env("GRANS_SYNC_PASSPHRASE", "correct horse")
+        .assert()
+        .success();
+    second
+        .cmd()
+        .args(["remote", "pull", "--force"])
+        .assert()
+        .success();
+    assert_eq!(titles(&second), titles(&first));
+
+    // Later pushes send encrypted patches.
+    rusqlite::Connection::open(&first.db_path)
+        .unwrap()
+        .execute(
+            "UPDATE documents SET title = 'Project Alpha Retro' WHERE id = 'doc-alpha'",
+            [],
+        )
+        .unwrap();
+    first
+        .cmd()
+        .args(["remote", "push"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Uploading changes"));
+    let patch = std::fs::read_dir(share.path())
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .find(|p| {
+            let name = p.file_name().unwrap().to_string_lossy();
+            name.starts_with("grans.db.delta-") && !name.ends_with(".content_hash")
+        })
+        .unwrap();
+    assert!(starts_with(patch, b"grans-encrypted"));
+
+    second.cmd().args(["remote", "pull"]).assert().success();
+    assert_eq!(
+        std::fs::read(&second.db_path).unwrap(),
+        std::fs::read(&first.db_path).unwrap()
+    );
+
+    let output = second
+        .cmd_json()
+        .args(["remote", "status"])
+        .output()
+        .unwrap();
+    let status: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(status["encryption_key"].is_string());
+    assert!(status["remote"].is_object());
+
+    // Turning it off sends the next push in the clear again.
+    first
+        .cmd()
+        .args(["remote", "encryption", "disable"])
+        .assert()
+        .success();
+    first.cmd().args(["remote", "push"]).assert().success();
+    assert!(starts_with(
+        share.path().join("grans.db"),
+        b"SQLite format 3"
+    ));
+}
+
+fn snapshots(env: &TestEnv) -> Vec<serde_json::Value> {
+    let output = env
+        .cmd_json()
+        .args(["remote", "snapshots"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let list: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    list["snapshots"].as_array().unwrap().clone()
+}
+
+#[test]
+fn push_keeps_snapshots_that_restore_brings_back() {
+    let share = TempDir::new().unwrap();
+    let env = TestEnv::with_fixture();
+    init_folder(&env, &share);
+    env.cmd()
+        .args(["remote", "push"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Kept snapshot"));
+    let original = std::fs::read(&env.db_path).unwrap();
+
+    rusqlite::Connection::open(&env.db_path)
+        .unwrap()
+        .execute(
+            "UPDATE documents SET title = 'Project Alpha Retro' WHERE id = 'doc-alpha'",
+            [],
+        )
+        .unwrap();
+    env.cmd().args(["remote", "push"]).assert().success();
+
+    let listed = snapshots(&env);
+    assert_eq!(listed.len(), 2, "{:?}", listed);
+    assert_eq!(listed[0]["current"], true);
+    assert_eq!(listed[1]["current"], false);
+    assert!(listed[1]["metadata"]["index_db"]["document_count"].is_number());
+    let first = listed[1]["name"].as_str().unwrap().to_string();
+    let second = listed[0]["name"].as_str().unwrap().to_string();
+
+    env.cmd()
+        .args(["remote", "restore", &first])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Restored snapshot"));
+    assert_eq!(std::fs::read(&env.db_path).unwrap(), original);
+
+    // The restored copy has not been pushed, so restoring over it needs --force.
+    env.cmd()
+        .args(["remote", "restore", &second])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("never pushed"));
+
+    // Pushing the restored copy rolls the remote back too.
+    env.cmd().args(["remote", "push"]).assert().success();
+    let other = TestEnv::with_state("{}");
+    init_folder(&other, &share);
+    other
+        .cmd()
+        .args(["remote", "pull", "--force"])
+        .assert()
+        .success();
+    assert_eq!(std::fs::read(&other.db_path).unwrap(), original);
+
+    env.cmd()
+        .args(["remote", "snapshots", "--keep", "1"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("last 1 snapshot"));
+    assert_eq!(snapshots(&env).len(), 1);
+    env.cmd()
+        .args(["remote", "restore", &first])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no snapshot named"));
+}
+
+#[test]
+fn push_snapshots_a_remote_copy_no_snapshot_holds_before_replacing_it() {
+    let share = TempDir::new().unwrap();
+    let first = TestEnv::with_fixture();
+    init_folder(&first, &share);
+    first
+        .cmd()
+        .args(["remote", "snapshots", "--keep", "0"])
+        .assert()
+        .success();
+    first.cmd().args(["remote", "push"]).assert().success();
+    let pushed = std::fs::read(&first.db_path).unwrap();
+    assert!(snapshots(&first).is_empty());
+
+    let second = other_machine();
+    init_folder(&second, &share);
+    second
+        .cmd()
+        .args(["remote", "push", "--force"])
+        .assert()
+        .success();
+
+    let listed = snapshots(&second);
+    assert_eq!(listed.len(), 2, "{:?}", listed);
+    assert_eq!(listed[0]["current"], true);
+    assert!(listed[1]["metadata"].is_null());
+    let replaced = listed[1]["name"].as_str().unwrap().to_string();
+
+    first
+        .cmd()
+        .args(["remote", "restore", &replaced])
+        .assert()
+        .success();
+    assert_eq!(std::fs::read(&first.db_path).unwrap(), pushed);
+}
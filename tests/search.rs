@@ -184,6 +184,61 @@ fn grep_json_is_shaped_with_evidence() {
     assert!(!m["matches"][0]["highlights"].as_array().unwrap().is_empty());
 }
 
+#[test]
+fn grep_query_operators_reach_fts() {
+    let env = TestEnv::with_fixture();
+    let output = env
+        .cmd_json()
+        .args(["grep", "zyzzyva OR milestones", "--in", "notes"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let meetings = result["meetings"].as_array().unwrap();
+    assert_eq!(meetings.len(), 1);
+    assert_eq!(meetings[0]["id"], "doc-alpha");
+
+    env.cmd()
+        .args(["grep", "milestones OR -timeline"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("excluded term"));
+}
+
+#[test]
+fn grep_meeting_filters_narrow_the_match_set() {
+    let env = TestEnv::with_fixture();
+    let ids = |extra: &[&str]| -> Vec<String> {
+        let output = env
+            .cmd_json()
+            .args(["grep", "timeline OR benchmarks"])
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let result: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let mut ids: Vec<String> = result["meetings"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["id"].as_str().unwrap().to_string())
+            .collect();
+        ids.sort();
+        ids
+    };
+
+    assert_eq!(ids(&[]), vec!["doc-alpha", "doc-beta"]);
+    // Carol, of Widgets Inc, only attended the beta review.
+    assert_eq!(ids(&["--with", "carol"]), vec!["doc-beta"]);
+    assert_eq!(ids(&["--company", "widgets"]), vec!["doc-beta"]);
+    assert_eq!(
+        ids(&["--series", "project alpha kickoff"]),
+        vec!["doc-alpha"]
+    );
+    assert!(ids(&["--min-attendees", "3"]).is_empty());
+}
+
 #[test]
 fn grep_matches_flag_shows_more_snippets() {
     let env = TestEnv::with_fixture();
@@ -496,3 +551,54 @@ fn context_search_limit_counts_meetings() {
     assert_eq!(result["meetings"].as_array().unwrap().len(), 1);
     assert_eq!(result["total_meetings"], 2);
 }
+
+#[test]
+fn saved_grep_baselines_then_runs_and_checks() {
+    let env = TestEnv::with_fixture();
+    let json = |args: &[&str]| -> serde_json::Value {
+        let output = env.cmd_json().args(args).output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        serde_json::from_slice(&output.stdout).unwrap()
+    };
+
+    let added = json(&[
+        "saved",
+        "add",
+        "alpha",
+        "grep",
+        "timeline",
+        "--series",
+        "project alpha kickoff",
+    ]);
+    assert_eq!(added["baseline"], 1);
+
+    let listed = json(&["saved", "list"]);
+    assert_eq!(listed[0]["name"], "alpha");
+    assert_eq!(
+        listed[0]["options"]["filters"]["series"],
+        "project alpha kickoff"
+    );
+
+    // The saved flags apply when it runs.
+    let ran = json(&["saved", "run", "alpha"]);
+    assert_eq!(ran["meetings"][0]["id"], "doc-alpha");
+    assert_eq!(ran["meetings"].as_array().unwrap().len(), 1);
+
+    // Nothing has synced since the baseline, so nothing is new.
+    let checked = json(&["saved", "check"]);
+    assert_eq!(checked["searches"][0]["rerun"], false);
+    assert!(
+        checked["searches"][0]["new_meetings"]
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+
+    json(&["saved", "rm", "alpha"]);
+    let output = env.cmd().args(["saved", "run", "alpha"]).output().unwrap();
+    assert!(!output.status.success());
+}
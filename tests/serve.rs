@@ -0,0 +1,137 @@
+mod common;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use common::TestEnv;
+
+/// A `grans serve` child process, killed on drop.
+struct Server {
+    child: Child,
+    addr: String,
+}
+
+impl Server {
+    /// Start on an ephemeral port and wait for the "Listening on" line.
+    fn start(env: &TestEnv) -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_grans"))
+            .args(["serve", "--listen", "127.0.0.1:0", "--fast"])
+            .env("XDG_DATA_HOME", env.dir.path().join("data"))
+            .env("NO_COLOR", "1")
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+
+        let stderr = BufReader::new(child.stderr.take().unwrap());
+        let mut addr = None;
+        for line in stderr.lines() {
+            let line = line.unwrap();
+            if let Some(rest) = line.strip_prefix("[grans] Listening on http://") {
+                addr = Some(rest.trim().to_string());
+                break;
+            }
+        }
+
+        Server {
+            child,
+            addr: addr.expect("server exited before listening"),
+        }
+    }
+
+    fn get(&self, target: &str) -> (u16, serde_json::Value) {
+        self.get_with(target, &format!("Host: {}\r\n", self.addr))
+    }
+
+    /// GET `target` sending `headers`, each ending in CRLF.
+    fn get_with(&self, target: &str, headers: &str) -> (u16, serde_json::Value) {
+        let mut stream = TcpStream::connect(&self.addr).unwrap();
+        write!(stream, "GET {} HTTP/1.1\r\n{}\r\n", target, headers).unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn serve_answers_with_cli_json_shapes() {
+    let env = TestEnv::with_fixture();
+    let server = Server::start(&env);
+
+    let (status, health) = server.get("/health");
+    assert_eq!(status, 200);
+    assert_eq!(health["status"], "ok");
+
+    let (status, grep) = server.get("/grep?q=kickoff&in=transcripts");
+    assert_eq!(status, 200);
+    assert_eq!(grep["query"], "kickoff");
+    assert!(grep["total_meetings"].as_u64().unwrap() >= 1);
+    assert_eq!(grep["meetings"][0]["id"], "doc-alpha");
+
+    let (status, search) = server.get("/search?q=kickoff");
+    assert_eq!(status, 200);
+    assert!(search.get("keyword_total").is_some());
+    assert!(search["meetings"].is_array());
+
+    let (status, show) = server.get("/show?meeting=doc-alpha");
+    assert_eq!(status, 200);
+    assert_eq!(show["title"], "Project Alpha Kickoff");
+}
+
+#[test]
+fn serve_reports_errors_as_json() {
+    let env = TestEnv::with_fixture();
+    let server = Server::start(&env);
+
+    let (status, body) = server.get("/show?meeting=no-such-meeting");
+    assert_eq!(status, 404);
+    assert!(body["error"].as_str().unwrap().contains("no-such-meeting"));
+
+    let (status, body) = server.get("/grep");
+    assert_eq!(status, 400);
+    assert!(body["error"].as_str().unwrap().contains("'q'"));
+}
+
+#[test]
+fn serve_refuses_other_hosts_and_origins() {
+    let env = TestEnv::with_fixture();
+    let server = Server::start(&env);
+    let port = server.addr.rsplit(':').next().unwrap();
+
+    let (status, _) = server.get_with("/health", &format!("Host: localhost:{}\r\n", port));
+    assert_eq!(status, 200);
+
+    let (status, body) = server.get_with("/list", &format!("Host: rebound.example:{}\r\n", port));
+    assert_eq!(status, 403);
+    assert!(body["error"].as_str().unwrap().contains("rebound.example"));
+
+    let headers = format!(
+        "Host: {}\r\nOrigin: http://rebound.example:{}\r\n",
+        server.addr, port
+    );
+    assert_eq!(server.get_with("/list", &headers).0, 403);
+}
+
+#[test]
+fn serve_answers_while_another_client_stalls() {
+    let env = TestEnv::with_fixture();
+    let server = Server::start(&env);
+
+    let mut stalled = TcpStream::connect(&server.addr).unwrap();
+    write!(stalled, "GET /health HTTP/1.1\r\n").unwrap();
+
+    let started = Instant::now();
+    assert_eq!(server.get("/health").0, 200);
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
@@ -0,0 +1,341 @@
+mod common;
+
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use common::TestEnv;
+use common::mock_api::MockApi;
+use predicates::prelude::*;
+use rusqlite::Connection;
+
+/// An unsigned JWT carrying a `sub`, so sync records a source account (and
+/// calls get-user-info) exactly as it does for a real Granola token.
+fn fake_jwt(sub: &str) -> String {
+    let header = URL_SAFE_NO_PAD.encode(br#"{"alg":"RS256","typ":"JWT"}"#);
+    let payload = URL_SAFE_NO_PAD.encode(serde_json::json!({ "sub": sub }).to_string());
+    format!("{}.{}.fake-signature", header, payload)
+}
+
+fn count(conn: &Connection, sql: &str) -> i64 {
+    conn.query_row(sql, [], |row| row.get(0)).unwrap()
+}
+
+#[test]
+fn sync_all_runs_every_stage_against_the_mock_api() {
+    let env = TestEnv::with_state("{}");
+    let api = MockApi::start();
+    let token = fake_jwt("user_01MOCK");
+
+    env.cmd()
+        .env("GRANS_API_BASE_URL", api.base_url())
+        .args(["sync", "--all", "--token", &token])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Recording new account"))
+        .stderr(predicate::str::contains("No embeddable content found"));
+
+    let conn = Connection::open(&env.db_path).unwrap();
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM documents"), 2);
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM people"), 1);
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM events"), 1);
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM templates"), 1);
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM recipes"), 1);
+    assert_eq!(
+        count(&conn, "SELECT COUNT(*) FROM transcript_utterances"),
+        2
+    );
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM panels"), 1);
+    assert_eq!(
+        count(
+            &conn,
+            "SELECT COUNT(*) FROM documents WHERE source_account_id = 'user_01MOCK'"
+        ),
+        2
+    );
+    // The second meeting has no transcript fixture, so the mock answers 404
+    // and the failure log remembers it for --retry.
+    assert_eq!(
+        count(
+            &conn,
+            "SELECT COUNT(*) FROM transcript_sync_log WHERE document_id = 'doc-sync-two'"
+        ),
+        1
+    );
+
+    let paths = api.paths();
+    for endpoint in [
+        "/v1/get-user-info",
+        "/v2/get-documents",
+        "/v1/get-people",
+        "/v1/refresh-calendar-events",
+        "/v1/get-selected-calendars",
+        "/v1/get-panel-templates",
+        "/v1/get-recipes",
+        "/v1/get-document-transcript",
+        "/v1/get-document-panels",
+    ] {
+        assert!(
+            paths.iter().any(|p| p == endpoint),
+            "{endpoint} was never requested; got {paths:?}"
+        );
+    }
+    assert!(
+        api.requests().iter().all(|r| r.method == "POST"
+            && r.authorization.as_deref() == Some(&format!("Bearer {}", token)))
+    );
+}
+
+#[test]
+fn synced_transcript_is_queryable() {
+    let env = TestEnv::with_state("{}");
+    let api = MockApi::start();
+
+    env.cmd()
+        .env("GRANS_API_BASE_URL", api.base_url())
+        .args(["sync", "--all", "--token", "not-a-jwt"])
+        .assert()
+        .success();
+
+    env.cmd()
+        .args(["show", "Mock Sync Standup", "--transcript"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Kumquat is green."));
+}
+
+#[test]
+fn config_key_selects_the_api_base_url() {
+    let env = TestEnv::with_state("{}");
+    let api = MockApi::start();
+    let config = env.db_path.with_file_name("config.toml");
+    std::fs::write(&config, format!("api_base_url = \"{}\"\n", api.base_url())).unwrap();
+
+    env.cmd()
+        .args(["sync", "documents", "--token", "not-a-jwt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 inserted"));
+
+    assert_eq!(api.paths(), vec!["/v2/get-documents".to_string()]);
+}
+
+#[test]
+fn environment_overrides_config_key() {
+    let env = TestEnv::with_state("{}");
+    let api = MockApi::start();
+    // Nothing listens on the discard port, so reaching it would fail the sync.
+    let config = env.db_path.with_file_name("config.toml");
+    std::fs::write(&config, "api_base_url = \"http://127.0.0.1:9\"\n").unwrap();
+
+    env.cmd()
+        .env("GRANS_API_BASE_URL", api.base_url())
+        .args(["sync", "documents", "--token", "not-a-jwt"])
+        .assert()
+        .success();
+
+    assert_eq!(api.paths(), vec!["/v2/get-documents".to_string()]);
+}
+
+#[test]
+fn invalid_base_url_fails_before_any_request() {
+    let env = TestEnv::with_state("{}");
+
+    env.cmd()
+        .env("GRANS_API_BASE_URL", "localhost:8080")
+        .args(["sync", "documents", "--token", "not-a-jwt"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid Granola API base URL"));
+}
+
+/// The JSON summary `grans sync documents --json` prints.
+fn sync_documents_json(env: &TestEnv, api: &MockApi, extra: &[&str]) -> serde_json::Value {
+    let output = env
+        .cmd_json()
+        .env("GRANS_API_BASE_URL", api.base_url())
+        .args(["sync", "documents", "--token", "not-a-jwt"])
+        .args(extra)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+fn document_requests(api: &MockApi) -> Vec<serde_json::Value> {
+    api.requests()
+        .into_iter()
+        .filter(|r| r.path == "/v2/get-documents")
+        .map(|r| r.body)
+        .collect()
+}
+
+#[test]
+fn document_sync_follows_cursor_pages() {
+    let root = tempfile::TempDir::new().unwrap();
+    let pages = root.path().join("v2").join("get-documents");
+    std::fs::create_dir_all(&pages).unwrap();
+    let doc = |id: &str| {
+        serde_json::json!({
+            "id": id,
+            "title": id,
+            "created_at": "2026-03-02T09:00:00.000Z",
+            "updated_at": "2026-03-02T09:30:00.000Z"
+        })
+    };
+    std::fs::write(
+        root.path().join("v2").join("get-documents.json"),
+        serde_json::json!({ "docs": [doc("doc-p1")], "next_cursor": "page-2" }).to_string(),
+    )
+    .unwrap();
+    std::fs::write(
+        pages.join("page-2.json"),
+        serde_json::json!({ "docs": [doc("doc-p2"), doc("doc-p3")] }).to_string(),
+    )
+    .unwrap();
+
+    let env = TestEnv::with_state("{}");
+    let api = MockApi::serve(root.path().to_path_buf());
+    let summary = sync_documents_json(&env, &api, &[]);
+
+    assert_eq!(summary["inserted"], 3);
+    let conn = Connection::open(&env.db_path).unwrap();
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM documents"), 3);
+    let requests = document_requests(&api);
+    assert_eq!(requests.len(), 2);
+    assert!(requests[0].get("cursor").is_none());
+    assert_eq!(requests[1]["cursor"], "page-2");
+}
+
+#[test]
+fn second_document_sync_is_incremental() {
+    let env = TestEnv::with_state("{}");
+    let api = MockApi::start();
+
+    let first = sync_documents_json(&env, &api, &[]);
+    let second = sync_documents_json(&env, &api, &[]);
+
+    assert_eq!(first["mode"], "full");
+    assert_eq!(second["mode"], "incremental");
+    assert_eq!(second["unchanged"], 2);
+    let requests = document_requests(&api);
+    assert!(requests[0].get("updated_after").is_none());
+    assert!(
+        requests[1]["updated_after"]
+            .as_str()
+            .unwrap()
+            .ends_with('Z')
+    );
+}
+
+#[test]
+fn full_document_sync_soft_deletes_meetings_gone_from_the_server() {
+    let env = TestEnv::with_state("{}");
+    let api = MockApi::start();
+    sync_documents_json(&env, &api, &[]);
+    let conn = Connection::open(&env.db_path).unwrap();
+    conn.execute(
+        "INSERT INTO documents (id, title, created_at, updated_at)
+         VALUES ('doc-gone', 'Deleted Upstream', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+        [],
+    )
+    .unwrap();
+    let live = "SELECT COUNT(*) FROM documents WHERE deleted_at IS NULL";
+
+    // An incremental sync cannot see deletions, and a dry run changes nothing.
+    assert_eq!(sync_documents_json(&env, &api, &[])["deleted"], 0);
+    let dry_run = sync_documents_json(&env, &api, &["--full", "--dry-run"]);
+    assert_eq!(dry_run["deleted"], 1);
+    assert_eq!(count(&conn, live), 3);
+
+    let full = sync_documents_json(&env, &api, &["--full"]);
+
+    assert_eq!(full["mode"], "full");
+    assert_eq!(full["deleted"], 1);
+    assert_eq!(count(&conn, live), 2);
+    assert_eq!(
+        count(
+            &conn,
+            "SELECT COUNT(*) FROM documents WHERE id = 'doc-gone' AND deleted_at IS NOT NULL"
+        ),
+        1
+    );
+}
+
+#[test]
+fn full_page_without_a_cursor_soft_deletes_nothing() {
+    // A server that caps pages at the requested size but paginates some
+    // other way: a full page with no cursor may not be the last one.
+    let root = tempfile::TempDir::new().unwrap();
+    std::fs::create_dir_all(root.path().join("v2")).unwrap();
+    let docs: Vec<_> = (0..100)
+        .map(|i| {
+            serde_json::json!({
+                "id": format!("doc-page-{}", i),
+                "title": format!("Meeting {}", i),
+                "created_at": "2026-03-02T09:00:00.000Z",
+                "updated_at": "2026-03-02T09:30:00.000Z"
+            })
+        })
+        .collect();
+    std::fs::write(
+        root.path().join("v2").join("get-documents.json"),
+        serde_json::json!({ "docs": docs }).to_string(),
+    )
+    .unwrap();
+
+    let env = TestEnv::with_state("{}");
+    let api = MockApi::serve(root.path().to_path_buf());
+    sync_documents_json(&env, &api, &[]);
+    let conn = Connection::open(&env.db_path).unwrap();
+    conn.execute(
+        "INSERT INTO documents (id, title, created_at, updated_at)
+         VALUES ('doc-page-101', 'On a Later Page', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+        [],
+    )
+    .unwrap();
+
+    env.cmd_json()
+        .env("GRANS_API_BASE_URL", api.base_url())
+        .args(["sync", "documents", "--full", "--token", "not-a-jwt"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"deleted\":0"))
+        .stderr(predicate::str::contains("skipping deletion"));
+    assert_eq!(
+        count(
+            &conn,
+            "SELECT COUNT(*) FROM documents WHERE deleted_at IS NOT NULL"
+        ),
+        0
+    );
+}
+
+#[test]
+fn transcript_backfill_backs_off_and_retries_rate_limited_documents() {
+    let env = TestEnv::with_state("{}");
+    let api = MockApi::start();
+    sync_documents_json(&env, &api, &[]);
+    api.rate_limit_next(2);
+
+    let output = env
+        .cmd_json()
+        .env("GRANS_API_BASE_URL", api.base_url())
+        .args(["sync", "transcripts", "--token", "not-a-jwt"])
+        .args(["--concurrency", "2", "--delay-ms", "0"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "{:?}", output);
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(summary["fetched"], 1);
+    assert_eq!(summary["not_found"], 1);
+    assert_eq!(summary["rate_limited"], false);
+    let transcript_requests = api
+        .paths()
+        .iter()
+        .filter(|p| *p == "/v1/get-document-transcript")
+        .count();
+    assert_eq!(transcript_requests, 4);
+    // Throttled attempts are retried, not logged as failures.
+    let conn = Connection::open(&env.db_path).unwrap();
+    assert_eq!(count(&conn, "SELECT COUNT(*) FROM transcript_sync_log"), 1);
+}